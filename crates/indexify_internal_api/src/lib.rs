@@ -27,9 +27,13 @@ pub struct Index {
 
 impl Index {
     pub fn id(&self) -> String {
+        Self::index_id(&self.namespace, &self.name)
+    }
+
+    pub fn index_id(namespace: &str, name: &str) -> String {
         let mut s = DefaultHasher::new();
-        self.namespace.hash(&mut s);
-        self.name.hash(&mut s);
+        namespace.hash(&mut s);
+        name.hash(&mut s);
         format!("{:x}", s.finish())
     }
 }
@@ -67,16 +71,749 @@ impl From<indexify_coordinator::Index> for Index {
     }
 }
 
+/// Tracks moving an index onto a parallel index populated by a different
+/// extraction policy, e.g. when the embedding dimensions of the extractor
+/// change. Once completed, lookups of the source index resolve to the target.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize, Default)]
+pub struct IndexMigration {
+    pub namespace: String,
+    pub source_index: String,
+    pub target_index: String,
+    pub target_extraction_policy: String,
+    pub created_at: u64,
+    pub completed: bool,
+}
+
+impl IndexMigration {
+    //  Same as the id of the source index, so there is at most one migration
+    // per index.
+    pub fn id(&self) -> String {
+        Index::index_id(&self.namespace, &self.source_index)
+    }
+}
+
+/// Moves the vectors of an index into a new table of the vector store split
+/// into a different number of shards, so existing data spreads over newly
+/// added capacity. The ingestion server running the job reports progress
+/// back to the coordinator.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize, Default)]
+pub struct RebalanceJob {
+    pub namespace: String,
+    pub index: String,
+    pub source_table: String,
+    pub target_table: String,
+    pub shard_count: u32,
+    pub total_content: u64,
+    pub moved_vectors: u64,
+    pub completed: bool,
+    pub error: String,
+    pub created_at: u64,
+    pub updated_at: u64,
+    //  Rebuilds extract the vectors from the content again instead of copying
+    // them from the source table
+    #[serde(default)]
+    pub reextract: bool,
+}
+
+impl RebalanceJob {
+    //  Same as the id of the index, only one rebalancing job per index is
+    // kept.
+    pub fn id(&self) -> String {
+        Index::index_id(&self.namespace, &self.index)
+    }
+}
+
+impl From<RebalanceJob> for indexify_coordinator::RebalanceJob {
+    fn from(value: RebalanceJob) -> Self {
+        Self {
+            namespace: value.namespace,
+            index: value.index,
+            source_table: value.source_table,
+            target_table: value.target_table,
+            shard_count: value.shard_count,
+            total_content: value.total_content,
+            moved_vectors: value.moved_vectors,
+            completed: value.completed,
+            error: value.error,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+            reextract: value.reextract,
+        }
+    }
+}
+
+/// A job copying the blobs of the content of a namespace to another blob
+/// storage backend. The storage urls of the content are switched over to the
+/// copies as the job progresses, so a job which stopped can be started again
+/// and only copies the content it hadn't copied yet.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize, Default)]
+pub struct BlobMigrationJob {
+    pub namespace: String,
+    pub target: String,
+    pub total_content: u64,
+    pub migrated_content: u64,
+    pub completed: bool,
+    pub error: String,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl BlobMigrationJob {
+    //  Only one migration per namespace is kept
+    pub fn id(&self) -> String {
+        self.namespace.clone()
+    }
+}
+
+impl From<BlobMigrationJob> for indexify_coordinator::BlobMigrationJob {
+    fn from(value: BlobMigrationJob) -> Self {
+        Self {
+            namespace: value.namespace,
+            target: value.target,
+            total_content: value.total_content,
+            migrated_content: value.migrated_content,
+            completed: value.completed,
+            error: value.error,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+/// A node of the raft cluster of the coordinators, learners replicate the log
+/// without voting
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize, Default)]
+pub struct ClusterMember {
+    pub node_id: u64,
+    pub address: String,
+    pub coordinator_addr: String,
+    pub voter: bool,
+    pub leader: bool,
+}
+
+impl From<ClusterMember> for indexify_coordinator::ClusterMember {
+    fn from(value: ClusterMember) -> Self {
+        Self {
+            node_id: value.node_id,
+            address: value.address,
+            coordinator_addr: value.coordinator_addr,
+            voter: value.voter,
+            leader: value.leader,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize, Display)]
+pub enum OperationKind {
+    CreateNamespace,
+    CreateExtractionPolicy,
+}
+
+/// Records that a mutating request carrying a client generated operation
+/// token was applied, so a retry of the same request returns the original
+/// result instead of being applied again.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize)]
+pub struct OperationRecord {
+    pub token: String,
+    pub operation: OperationKind,
+    //  Id of the namespace or extraction policy created by the operation
+    pub resource_id: String,
+    pub created_at: u64,
+}
+
+/// A named search stored in the coordinator, so applications can run it by
+/// name while the index, number of results and filters are tuned centrally.
+/// The query may contain `{parameter}` placeholders which are filled in when
+/// the template is run.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize, Default)]
+pub struct QueryTemplate {
+    pub namespace: String,
+    pub name: String,
+    pub index: String,
+    pub query: String,
+    pub k: Option<u64>,
+    //  Labels the content of every result must have
+    pub filters: HashMap<String, String>,
+    //  Default values of the placeholders in the query
+    pub parameters: HashMap<String, String>,
+    //  Module adjusting the scores of the results of the template
+    #[serde(default)]
+    pub scoring_plugin: Option<ScoringPlugin>,
+}
+
+/// A WASM module run on the results of a query template, which can change
+/// their scores or drop them. The module itself is kept in the blob store.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize, Default)]
+pub struct ScoringPlugin {
+    pub wasm_url: String,
+    //  Maximum number of instructions a single run of the module can execute
+    pub max_fuel: u64,
+    //  Maximum size of the linear memory of the module
+    pub max_memory_bytes: u64,
+}
+
+impl From<ScoringPlugin> for indexify_coordinator::ScoringPlugin {
+    fn from(value: ScoringPlugin) -> Self {
+        Self {
+            wasm_url: value.wasm_url,
+            max_fuel: value.max_fuel,
+            max_memory_bytes: value.max_memory_bytes,
+        }
+    }
+}
+
+impl From<indexify_coordinator::ScoringPlugin> for ScoringPlugin {
+    fn from(value: indexify_coordinator::ScoringPlugin) -> Self {
+        Self {
+            wasm_url: value.wasm_url,
+            max_fuel: value.max_fuel,
+            max_memory_bytes: value.max_memory_bytes,
+        }
+    }
+}
+
+impl QueryTemplate {
+    pub fn id(&self) -> String {
+        let mut s = DefaultHasher::new();
+        self.namespace.hash(&mut s);
+        self.name.hash(&mut s);
+        format!("{:x}", s.finish())
+    }
+
+    /// Fills in the placeholders of the query, falling back to the default
+    /// parameters of the template.
+    pub fn render(&self, parameters: &HashMap<String, String>) -> Result<String> {
+        fill_placeholders(&self.query, |name| {
+            parameters.get(name).or_else(|| self.parameters.get(name))
+        })
+        .map_err(|name| {
+            anyhow!(
+                "missing parameter {} for query template {}",
+                name,
+                self.name
+            )
+        })
+    }
+}
+
+/// Replaces the `{name}` placeholders in the text with the value returned by
+/// `lookup`. Braces which don't enclose a name made of alphanumeric
+/// characters and underscores are kept as they are. Returns the name of the
+/// first placeholder without a value as the error.
+pub fn fill_placeholders<'a>(
+    text: &str,
+    lookup: impl Fn(&str) -> Option<&'a String>,
+) -> std::result::Result<String, String> {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let placeholder = after.find('}').map(|end| &after[..end]).filter(|name| {
+            !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        });
+        match placeholder {
+            Some(name) => {
+                let value = lookup(name).ok_or_else(|| name.to_string())?;
+                rendered.push_str(value);
+                rest = &after[name.len() + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+impl From<QueryTemplate> for indexify_coordinator::QueryTemplate {
+    fn from(value: QueryTemplate) -> Self {
+        Self {
+            namespace: value.namespace,
+            name: value.name,
+            index: value.index,
+            query: value.query,
+            k: value.k.unwrap_or_default(),
+            filters: value.filters,
+            parameters: value.parameters,
+            scoring_plugin: value.scoring_plugin.map(|plugin| plugin.into()),
+        }
+    }
+}
+
+impl From<indexify_coordinator::QueryTemplate> for QueryTemplate {
+    fn from(value: indexify_coordinator::QueryTemplate) -> Self {
+        Self {
+            namespace: value.namespace,
+            name: value.name,
+            index: value.index,
+            query: value.query,
+            k: (value.k > 0).then_some(value.k),
+            filters: value.filters,
+            parameters: value.parameters,
+            scoring_plugin: value.scoring_plugin.map(|plugin| plugin.into()),
+        }
+    }
+}
+
+/// A WASM module run on content ingested into a namespace before the content
+/// is stored. It inspects the metadata and the first bytes of the content and
+/// accepts it, rejects it or adds labels to it. The module itself is kept in
+/// the blob store.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize, Default)]
+pub struct IngestionPlugin {
+    pub namespace: String,
+    pub name: String,
+    pub wasm_url: String,
+    //  Maximum number of instructions a single run of the module can execute
+    pub max_fuel: u64,
+    //  Maximum size of the linear memory of the module
+    pub max_memory_bytes: u64,
+}
+
+impl IngestionPlugin {
+    pub fn id(&self) -> String {
+        let mut s = DefaultHasher::new();
+        self.namespace.hash(&mut s);
+        self.name.hash(&mut s);
+        format!("{:x}", s.finish())
+    }
+}
+
+impl From<IngestionPlugin> for indexify_coordinator::IngestionPlugin {
+    fn from(value: IngestionPlugin) -> Self {
+        Self {
+            namespace: value.namespace,
+            name: value.name,
+            wasm_url: value.wasm_url,
+            max_fuel: value.max_fuel,
+            max_memory_bytes: value.max_memory_bytes,
+        }
+    }
+}
+
+impl From<indexify_coordinator::IngestionPlugin> for IngestionPlugin {
+    fn from(value: indexify_coordinator::IngestionPlugin) -> Self {
+        Self {
+            namespace: value.namespace,
+            name: value.name,
+            wasm_url: value.wasm_url,
+            max_fuel: value.max_fuel,
+            max_memory_bytes: value.max_memory_bytes,
+        }
+    }
+}
+
+/// Processing target of an extraction policy: the share of content which has
+/// to be processed by the policy within `target_secs` of the original content
+/// being ingested, measured over the last `window_secs`. When attainment
+/// drops below the threshold an alert is posted to `webhook_url`.
+#[derive(Debug, Clone, Serialize, PartialEq, Deserialize, Default)]
+pub struct ProcessingSla {
+    pub namespace: String,
+    pub extraction_policy: String,
+    //  Id of the extraction policy, which is what tasks refer to
+    pub extraction_policy_id: String,
+    pub target_secs: u64,
+    pub attainment_threshold: f64,
+    pub window_secs: u64,
+    //  Empty if no alerts are sent
+    pub webhook_url: String,
+}
+
+impl ProcessingSla {
+    pub fn id(&self) -> String {
+        let mut s = DefaultHasher::new();
+        self.namespace.hash(&mut s);
+        self.extraction_policy.hash(&mut s);
+        format!("{:x}", s.finish())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.target_secs == 0 {
+            return Err(anyhow!("sla target has to be at least one second"));
+        }
+        if !(self.attainment_threshold > 0.0 && self.attainment_threshold <= 1.0) {
+            return Err(anyhow!(
+                "sla attainment threshold has to be between 0 and 1, got {}",
+                self.attainment_threshold
+            ));
+        }
+        if self.window_secs < self.target_secs {
+            return Err(anyhow!(
+                "sla window of {}s is shorter than the target of {}s",
+                self.window_secs,
+                self.target_secs
+            ));
+        }
+        if !self.webhook_url.is_empty() &&
+            !self.webhook_url.starts_with("http://") &&
+            !self.webhook_url.starts_with("https://")
+        {
+            return Err(anyhow!(
+                "sla webhook url has to be an http or https url, got {}",
+                self.webhook_url
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl From<ProcessingSla> for indexify_coordinator::ProcessingSla {
+    fn from(value: ProcessingSla) -> Self {
+        Self {
+            namespace: value.namespace,
+            extraction_policy: value.extraction_policy,
+            extraction_policy_id: value.extraction_policy_id,
+            target_secs: value.target_secs,
+            attainment_threshold: value.attainment_threshold,
+            window_secs: value.window_secs,
+            webhook_url: value.webhook_url,
+        }
+    }
+}
+
+impl From<indexify_coordinator::ProcessingSla> for ProcessingSla {
+    fn from(value: indexify_coordinator::ProcessingSla) -> Self {
+        Self {
+            namespace: value.namespace,
+            extraction_policy: value.extraction_policy,
+            extraction_policy_id: value.extraction_policy_id,
+            target_secs: value.target_secs,
+            attainment_threshold: value.attainment_threshold,
+            window_secs: value.window_secs,
+            webhook_url: value.webhook_url,
+        }
+    }
+}
+
+/// Records that an API server started writing the blob of a piece of content
+/// whose metadata isn't created yet. Intents are kept in the coordinator so
+/// that any API server can clean up the blob if the upload is abandoned, for
+/// example because the server writing it went away. Creating the content
+/// removes its intent.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize)]
+pub struct IngestIntent {
+    pub content_id: String,
+    pub namespace: String,
+    pub storage_url: String,
+    pub ingestion_server_id: String,
+    pub created_at: u64,
+}
+
+impl From<IngestIntent> for indexify_coordinator::IngestIntent {
+    fn from(value: IngestIntent) -> Self {
+        Self {
+            content_id: value.content_id,
+            namespace: value.namespace,
+            storage_url: value.storage_url,
+            ingestion_server_id: value.ingestion_server_id,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<indexify_coordinator::IngestIntent> for IngestIntent {
+    fn from(value: indexify_coordinator::IngestIntent) -> Self {
+        Self {
+            content_id: value.content_id,
+            namespace: value.namespace,
+            storage_url: value.storage_url,
+            ingestion_server_id: value.ingestion_server_id,
+            created_at: value.created_at,
+        }
+    }
+}
+
+/// Time at which the embedding of a piece of content expires and is removed
+/// from an index, either because of the TTL of the content or of the
+/// extraction policy of the index. Expirations are kept in the coordinator and
+/// swept by the API servers.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize)]
+pub struct EmbeddingExpiration {
+    pub namespace: String,
+    pub index_table: String,
+    pub content_id: String,
+    // Hash of the content, its embedding may be shared with identical content
+    pub hash: String,
+    pub expires_at: u64,
+}
+
+impl EmbeddingExpiration {
+    pub fn key(&self) -> String {
+        format!("{}|{}", self.index_table, self.content_id)
+    }
+
+    /// Expiration of an embedding written at `now`, the earlier of the
+    /// expiration of the content and the TTL of the index. None if neither
+    /// is set.
+    pub fn expires_at(content_expires_at: u64, ttl_secs: Option<u64>, now: u64) -> Option<u64> {
+        let index_expires_at = ttl_secs.map(|ttl_secs| now.saturating_add(ttl_secs));
+        match (content_expires_at, index_expires_at) {
+            (0, index_expires_at) => index_expires_at,
+            (content_expires_at, Some(index_expires_at)) => {
+                Some(content_expires_at.min(index_expires_at))
+            }
+            (content_expires_at, None) => Some(content_expires_at),
+        }
+    }
+}
+
+impl From<EmbeddingExpiration> for indexify_coordinator::EmbeddingExpiration {
+    fn from(value: EmbeddingExpiration) -> Self {
+        Self {
+            namespace: value.namespace,
+            index_table: value.index_table,
+            content_id: value.content_id,
+            hash: value.hash,
+            expires_at: value.expires_at,
+        }
+    }
+}
+
+impl From<indexify_coordinator::EmbeddingExpiration> for EmbeddingExpiration {
+    fn from(value: indexify_coordinator::EmbeddingExpiration) -> Self {
+        Self {
+            namespace: value.namespace,
+            index_table: value.index_table,
+            content_id: value.content_id,
+            hash: value.hash,
+            expires_at: value.expires_at,
+        }
+    }
+}
+
+/// Content with the same bytes written to an index, whose embedding is only
+/// stored once under the id of the first content. Search results are expanded
+/// to all the content in the reference.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize)]
+pub struct ChunkReference {
+    pub index_table: String,
+    pub hash: String,
+    pub content_ids: Vec<String>,
+}
+
+impl ChunkReference {
+    pub fn key(index_table: &str, hash: &str) -> String {
+        format!("{}|{}", index_table, hash)
+    }
+
+    /// The content the embedding is stored under
+    pub fn owner(&self) -> Option<&String> {
+        self.content_ids.first()
+    }
+}
+
+impl From<ChunkReference> for indexify_coordinator::ChunkReference {
+    fn from(value: ChunkReference) -> Self {
+        Self {
+            index_table: value.index_table,
+            hash: value.hash,
+            content_ids: value.content_ids,
+        }
+    }
+}
+
+impl From<indexify_coordinator::ChunkReference> for ChunkReference {
+    fn from(value: indexify_coordinator::ChunkReference) -> Self {
+        Self {
+            index_table: value.index_table,
+            hash: value.hash,
+            content_ids: value.content_ids,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EmbeddingSchema {
     pub dim: usize,
     pub distance: String,
+    // Quantization of the embeddings in the vector store, embeddings are
+    // stored uncompressed when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantization: Option<IndexQuantization>,
+    // Names of the additional vectors emitted for every chunk, e.g. a title
+    // and a body vector. They have the dimension and distance of the index
+    // and are stored in a table of their own next to the index table.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub named_vectors: Vec<String>,
+    // Seconds after which embeddings written to the index are removed from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_secs: Option<u64>,
+    // Whether the embeddings are sparse, e.g. SPLADE or BM42 term weights.
+    // The dimension of a sparse index is the size of the vocabulary and it's
+    // searched by dot product.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sparse: bool,
+    // Backend specific tuning of the index, e.g. HNSW M or the replication
+    // factor, applied by the vector store when the index is created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_params: Option<serde_json::Value>,
+}
+
+impl EmbeddingSchema {
+    /// Table storing the named vector of the chunks of an index table.
+    pub fn named_vector_table(table_name: &str, vector: &str) -> String {
+        format!("{}.vectors.{}", table_name, vector)
+    }
+
+    /// Tables of the named vectors of the index table.
+    pub fn named_vector_tables(&self, table_name: &str) -> Vec<String> {
+        self.named_vectors
+            .iter()
+            .map(|vector| Self::named_vector_table(table_name, vector))
+            .collect()
+    }
+
+    /// Bytes taken by an embedding in the vector store after quantization,
+    /// not counting the graph or metadata stored with it.
+    pub fn vector_bytes(&self) -> u64 {
+        let size = (self.dim * std::mem::size_of::<f32>()) as u64;
+        match &self.quantization {
+            None => size,
+            Some(IndexQuantization::Scalar { .. }) => self.dim as u64,
+            Some(IndexQuantization::Product { compression, .. }) => {
+                size / (*compression).max(1) as u64
+            }
+        }
+    }
+}
+
+/// Compression of the embeddings of an index in the vector store, trading
+/// recall for memory.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IndexQuantization {
+    /// Stores every dimension as an 8 bit integer instead of a 32 bit float.
+    Scalar {
+        /// Quantile of the values used to pick the range of the integers,
+        /// outliers beyond it are clipped.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        quantile: Option<f32>,
+        /// Keeps the quantized embeddings in memory when the originals are
+        /// on disk.
+        #[serde(default)]
+        always_ram: bool,
+    },
+    /// Splits embeddings into sub vectors which are each stored as the id of
+    /// their closest centroid.
+    Product {
+        /// Size of the original embeddings relative to the quantized ones,
+        /// one of 4, 8, 16, 32 or 64.
+        compression: u32,
+        #[serde(default)]
+        always_ram: bool,
+    },
+}
+
+pub const PRODUCT_QUANTIZATION_COMPRESSIONS: [u32; 5] = [4, 8, 16, 32, 64];
+
+impl IndexQuantization {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            IndexQuantization::Scalar {
+                quantile: Some(quantile),
+                ..
+            } if !(0.5..=1.0).contains(quantile) => Err(anyhow!(
+                "scalar quantization quantile has to be between 0.5 and 1, got {}",
+                quantile
+            )),
+            IndexQuantization::Product { compression, .. }
+                if !PRODUCT_QUANTIZATION_COMPRESSIONS.contains(compression) =>
+            {
+                Err(anyhow!(
+                    "product quantization compression has to be one of {:?}, got {}",
+                    PRODUCT_QUANTIZATION_COMPRESSIONS,
+                    compression
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Output name under which an extraction policy maps its keyword index. The
+/// keyword index isn't produced by the extractor, it's populated with the
+/// text content the extractor writes.
+pub const KEYWORD_INDEX_OUTPUT: &str = "keyword_index";
+
+/// Schema of a full text index, stored in place of the embedding schema of
+/// an index.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeywordIndexSchema {
+    pub tokenizer: String,
+}
+
+impl Default for KeywordIndexSchema {
+    fn default() -> Self {
+        Self {
+            tokenizer: "default".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Embedding {
     pub values: Vec<f32>,
     pub distance: String,
+    //  Named vectors of the chunk, in addition to the vector in `values`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub vectors: HashMap<String, Vec<f32>>,
+    //  Dimensions of the non-zero weights in `values` if the embedding is
+    // sparse
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub indices: Vec<u32>,
+}
+
+/// Non-zero weights of a sparse embedding and their dimensions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SparseVector {
+    pub indices: Vec<u32>,
+    pub values: Vec<f32>,
+}
+
+impl SparseVector {
+    /// Checks that every weight has a dimension, which is unique and within
+    /// the vocabulary of the index.
+    pub fn validate(&self, dim: usize) -> Result<()> {
+        if self.indices.len() != self.values.len() {
+            return Err(anyhow!(
+                "sparse embedding has {} indices but {} values",
+                self.indices.len(),
+                self.values.len()
+            ));
+        }
+        if let Some(index) = self.indices.iter().find(|index| **index as usize >= dim) {
+            return Err(anyhow!(
+                "sparse embedding index {} is out of the dimension {} of the index",
+                index,
+                dim
+            ));
+        }
+        if self.indices.iter().collect::<HashSet<_>>().len() != self.indices.len() {
+            return Err(anyhow!("sparse embedding has duplicate indices"));
+        }
+        Ok(())
+    }
+}
+
+impl Embedding {
+    pub fn sparse_vector(&self) -> SparseVector {
+        SparseVector {
+            indices: self.indices.clone(),
+            values: self.values.clone(),
+        }
+    }
+
+    /// The named vector, or the values of the embedding if no name is given.
+    pub fn vector(&self, name: Option<&str>) -> Option<&Vec<f32>> {
+        match name {
+            Some(name) => self.vectors.get(name),
+            None => Some(&self.values),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -258,7 +995,7 @@ impl Content {
     }
 }
 
-#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, ToSchema, Default)]
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, Eq, Hash, ToSchema, Default)]
 #[schema(as = internal_api::TaskOutcome)]
 pub enum TaskOutcome {
     #[default]
@@ -310,12 +1047,40 @@ pub struct Task {
     #[schema(value_type = internal_api::TaskOutcome)]
     pub outcome: TaskOutcome,
     pub index_tables: Vec<String>, // list of index tables that this content may be present in
+    // Number of earlier attempts of the task which failed
+    #[serde(default)]
+    pub attempts: u32,
+    // Time, in seconds, after which a failed task is assigned again
+    #[serde(default)]
+    pub retry_at: Option<u64>,
+    // Priority of the extraction policy when the task was created
+    #[serde(default)]
+    pub priority: i32,
 }
 
 impl Task {
     pub fn terminal_state(&self) -> bool {
         self.outcome != TaskOutcome::Unknown
     }
+
+    /// Whether an earlier attempt of the task failed and it's attempted
+    /// again, it's only assigned again once `retry_at` passed
+    pub fn retrying(&self) -> bool {
+        !self.terminal_state() && self.retry_at.is_some()
+    }
+
+    /// Key of the task in the queue of the executor it's assigned to, keys of
+    /// tasks with a higher priority sort first and tasks of the same priority
+    /// sort by when their content was created
+    pub fn queue_key(&self, executor_id: &str) -> String {
+        format!(
+            "{}|{:08x}|{:020}|{}",
+            executor_id,
+            (i32::MAX as i64 - self.priority as i64) as u32,
+            self.content_metadata.created_at,
+            self.id
+        )
+    }
 }
 
 impl Display for Task {
@@ -341,6 +1106,9 @@ impl From<Task> for indexify_coordinator::Task {
             output_index_mapping: value.output_index_table_mapping,
             outcome: outcome as i32,
             index_tables: value.index_tables,
+            attempts: value.attempts,
+            priority: value.priority,
+            retry_at: value.retry_at,
         }
     }
 }
@@ -362,6 +1130,9 @@ impl TryFrom<indexify_coordinator::Task> for Task {
             output_index_table_mapping: value.output_index_mapping,
             outcome,
             index_tables: value.index_tables,
+            attempts: value.attempts,
+            retry_at: value.retry_at,
+            priority: value.priority,
         })
     }
 }
@@ -438,6 +1209,47 @@ pub struct ExtractionPolicy {
     // The source of the content - ingestion, name of some extractor binding
     // which produces the content by invoking an extractor
     pub content_source: String,
+
+    // Incremented every time the policy is updated, zero for a policy which
+    // was never updated
+    #[serde(default)]
+    pub version: u64,
+
+    #[serde(default)]
+    pub content_filter: ContentFilter,
+
+    // Existing content created before the time, in seconds, isn't extracted
+    // when the policy is created or content is extracted again. Content
+    // created later is always extracted.
+    #[serde(default)]
+    pub backfill_from: Option<i64>,
+
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+
+    // Tasks of policies with a higher priority are given to executors before
+    // the tasks of other policies, e.g. to keep chat ingestion ahead of
+    // backfills sharing the same extractors
+    #[serde(default)]
+    pub priority: i32,
+}
+
+impl ExtractionPolicy {
+    /// Whether the policy applies to the content, the content has to match
+    /// every label filter of the policy along with its content filter
+    pub fn matches_content(&self, content: &ContentMetadata) -> bool {
+        self.filters
+            .iter()
+            .all(|(name, value)| content.labels.get(name) == Some(value)) &&
+            self.content_filter.matches(content)
+    }
+
+    /// Whether the policy is applied to the content when it backfills the
+    /// existing content
+    pub fn backfills_content(&self, content: &ContentMetadata) -> bool {
+        self.backfill_from
+            .map_or(true, |backfill_from| content.created_at >= backfill_from)
+    }
 }
 
 impl std::hash::Hash for ExtractionPolicy {
@@ -461,6 +1273,269 @@ impl From<ExtractionPolicy> for indexify_coordinator::ExtractionPolicy {
             filters,
             input_params: value.input_params.to_string(),
             content_source: value.content_source,
+            version: value.version,
+            content_filter: Some(value.content_filter.into()),
+            backfill_from: value.backfill_from,
+            retry_policy: Some(value.retry_policy.into()),
+            priority: value.priority,
+        }
+    }
+}
+
+/// Content an extraction policy is applied to, on top of its label filters.
+/// Criteria which aren't set match any content.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ContentFilter {
+    // Globs of mime types, e.g. `image/*`
+    #[serde(default)]
+    pub mime_types: Vec<String>,
+    #[serde(default)]
+    pub label_ranges: Vec<LabelRange>,
+    pub min_size_bytes: Option<u64>,
+    pub max_size_bytes: Option<u64>,
+}
+
+impl ContentFilter {
+    pub fn validate(&self) -> Result<()> {
+        for mime_type in &self.mime_types {
+            let valid = match mime_type.split_once('/') {
+                Some((type_, subtype)) => {
+                    !type_.is_empty() &&
+                        !subtype.is_empty() &&
+                        !subtype.contains('/') &&
+                        (type_ != "*" || subtype == "*")
+                }
+                None => false,
+            };
+            if !valid {
+                return Err(anyhow!(
+                    "invalid mime type {}, expected a type and a subtype, e.g. image/png or image/*",
+                    mime_type
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_size_bytes, self.max_size_bytes) {
+            if min > max {
+                return Err(anyhow!(
+                    "the minimum size {} is larger than the maximum size {}",
+                    min,
+                    max
+                ));
+            }
+        }
+        for range in &self.label_ranges {
+            if range.min.map_or(false, f64::is_nan) || range.max.map_or(false, f64::is_nan) {
+                return Err(anyhow!(
+                    "the bounds of label {} aren't numbers",
+                    range.label
+                ));
+            }
+            if let (Some(min), Some(max)) = (range.min, range.max) {
+                if min > max {
+                    return Err(anyhow!(
+                        "the minimum {} of label {} is larger than its maximum {}",
+                        min,
+                        range.label,
+                        max
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, content: &ContentMetadata) -> bool {
+        let mime_type_matches = self.mime_types.is_empty() ||
+            self.mime_types
+                .iter()
+                .any(|glob| mime_type_matches_glob(glob, &content.content_type));
+        mime_type_matches &&
+            self.min_size_bytes
+                .map_or(true, |min| content.size_bytes >= min) &&
+            self.max_size_bytes
+                .map_or(true, |max| content.size_bytes <= max) &&
+            self.label_ranges
+                .iter()
+                .all(|range| range.matches(&content.labels))
+    }
+}
+
+/// Matches `type/subtype` mime types against globs like `image/png`,
+/// `image/*` or `*/*`, ignoring case and mime type parameters
+pub fn mime_type_matches_glob(glob: &str, mime_type: &str) -> bool {
+    let essence = |mime_type: &str| {
+        let essence = mime_type.split(';').next().unwrap_or_default();
+        essence.trim().to_ascii_lowercase()
+    };
+    let (glob, mime_type) = (essence(glob), essence(mime_type));
+    let (Some((glob_type, glob_subtype)), Some((type_, subtype))) =
+        (glob.split_once('/'), mime_type.split_once('/'))
+    else {
+        return false;
+    };
+    (glob_type == "*" || glob_type == type_) && (glob_subtype == "*" || glob_subtype == subtype)
+}
+
+impl From<ContentFilter> for indexify_coordinator::ContentFilter {
+    fn from(value: ContentFilter) -> Self {
+        Self {
+            mime_types: value.mime_types,
+            label_ranges: value.label_ranges.into_iter().map(Into::into).collect(),
+            min_size_bytes: value.min_size_bytes,
+            max_size_bytes: value.max_size_bytes,
+        }
+    }
+}
+
+impl From<indexify_coordinator::ContentFilter> for ContentFilter {
+    fn from(value: indexify_coordinator::ContentFilter) -> Self {
+        Self {
+            mime_types: value.mime_types,
+            label_ranges: value.label_ranges.into_iter().map(Into::into).collect(),
+            min_size_bytes: value.min_size_bytes,
+            max_size_bytes: value.max_size_bytes,
+        }
+    }
+}
+
+/// Matches content whose label is a number within the bounds, content
+/// without the label or with a label which isn't a number doesn't match
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LabelRange {
+    pub label: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+//  Bounds are validated not to be NaN before a policy is created
+impl Eq for LabelRange {}
+
+impl LabelRange {
+    pub fn matches(&self, labels: &HashMap<String, String>) -> bool {
+        let Some(value) = labels
+            .get(&self.label)
+            .and_then(|value| value.trim().parse::<f64>().ok())
+        else {
+            return false;
+        };
+        self.min.map_or(true, |min| value >= min) && self.max.map_or(true, |max| value <= max)
+    }
+}
+
+impl From<LabelRange> for indexify_coordinator::LabelRange {
+    fn from(value: LabelRange) -> Self {
+        Self {
+            label: value.label,
+            min: value.min,
+            max: value.max,
+        }
+    }
+}
+
+impl From<indexify_coordinator::LabelRange> for LabelRange {
+    fn from(value: indexify_coordinator::LabelRange) -> Self {
+        Self {
+            label: value.label,
+            min: value.min,
+            max: value.max,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum BackoffStrategy {
+    // The backoff doubles with every attempt, up to the maximum backoff
+    #[default]
+    Exponential,
+    Fixed,
+}
+
+impl From<BackoffStrategy> for indexify_coordinator::BackoffStrategy {
+    fn from(value: BackoffStrategy) -> Self {
+        match value {
+            BackoffStrategy::Exponential => indexify_coordinator::BackoffStrategy::Exponential,
+            BackoffStrategy::Fixed => indexify_coordinator::BackoffStrategy::Fixed,
+        }
+    }
+}
+
+impl From<indexify_coordinator::BackoffStrategy> for BackoffStrategy {
+    fn from(value: indexify_coordinator::BackoffStrategy) -> Self {
+        match value {
+            indexify_coordinator::BackoffStrategy::Exponential => BackoffStrategy::Exponential,
+            indexify_coordinator::BackoffStrategy::Fixed => BackoffStrategy::Fixed,
+        }
+    }
+}
+
+/// How the failed tasks of an extraction policy are retried. Failed tasks
+/// aren't retried by default.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SmartDefault)]
+#[serde(default)]
+pub struct RetryPolicy {
+    // Attempts of a task including the first one
+    #[default = 1]
+    pub max_attempts: u32,
+    pub backoff: BackoffStrategy,
+    #[default = 10]
+    pub initial_backoff_secs: u64,
+    #[default = 600]
+    pub max_backoff_secs: u64,
+}
+
+impl RetryPolicy {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_attempts == 0 {
+            return Err(anyhow!("max_attempts has to be at least 1"));
+        }
+        if self.initial_backoff_secs > self.max_backoff_secs {
+            return Err(anyhow!(
+                "the initial backoff {}s is larger than the maximum backoff {}s",
+                self.initial_backoff_secs,
+                self.max_backoff_secs
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether a task is retried after its attempt number `attempt`, counting
+    /// from 1, failed
+    pub fn retries(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    /// Seconds to wait before retrying a task after its attempt number
+    /// `attempt`, counting from 1, failed
+    pub fn backoff_secs(&self, attempt: u32) -> u64 {
+        match self.backoff {
+            BackoffStrategy::Fixed => self.initial_backoff_secs,
+            BackoffStrategy::Exponential => self
+                .initial_backoff_secs
+                .saturating_mul(2u64.saturating_pow(attempt.saturating_sub(1)))
+                .min(self.max_backoff_secs),
+        }
+    }
+}
+
+impl From<RetryPolicy> for indexify_coordinator::RetryPolicy {
+    fn from(value: RetryPolicy) -> Self {
+        let backoff: indexify_coordinator::BackoffStrategy = value.backoff.into();
+        Self {
+            max_attempts: value.max_attempts,
+            backoff: backoff as i32,
+            initial_backoff_secs: value.initial_backoff_secs,
+            max_backoff_secs: value.max_backoff_secs,
+        }
+    }
+}
+
+impl From<indexify_coordinator::RetryPolicy> for RetryPolicy {
+    fn from(value: indexify_coordinator::RetryPolicy) -> Self {
+        Self {
+            max_attempts: value.max_attempts,
+            backoff: value.backoff().into(),
+            initial_backoff_secs: value.initial_backoff_secs,
+            max_backoff_secs: value.max_backoff_secs,
         }
     }
 }
@@ -480,6 +1555,21 @@ pub struct ContentMetadata {
     pub size_bytes: u64,
     pub tombstoned: bool,
     pub extraction_policy_ids: HashMap<String, u64>,
+    // Id supplied by the client at ingestion, unique within the namespace.
+    // Empty if the client didn't provide one.
+    #[serde(default)]
+    pub external_id: String,
+    // Beginning of the text of text content, or the preview of the first
+    // extracted text content otherwise. Empty if there is no text.
+    #[serde(default)]
+    pub preview: String,
+    // SHA-256 of the content bytes, used to store the embedding of identical
+    // content once per index. Empty for content which wasn't hashed.
+    #[serde(default)]
+    pub hash: String, // Time after which the embeddings of the content are removed from the
+    // indexes, zero if they don't expire
+    #[serde(default)]
+    pub expires_at: u64,
 }
 
 impl From<ContentMetadata> for indexify_coordinator::ContentMetadata {
@@ -496,6 +1586,10 @@ impl From<ContentMetadata> for indexify_coordinator::ContentMetadata {
             source: value.source,
             size_bytes: value.size_bytes,
             extraction_policy_ids: value.extraction_policy_ids,
+            external_id: value.external_id,
+            preview: value.preview,
+            hash: value.hash,
+            expires_at: value.expires_at,
         }
     }
 }
@@ -520,6 +1614,10 @@ impl Default for ContentMetadata {
             size_bytes: 1234567890,
             extraction_policy_ids: HashMap::new(),
             tombstoned: false,
+            external_id: "".to_string(),
+            preview: "".to_string(),
+            hash: "".to_string(),
+            expires_at: 0,
         }
     }
 }
@@ -541,16 +1639,56 @@ impl TryFrom<indexify_coordinator::ContentMetadata> for ContentMetadata {
             size_bytes: value.size_bytes,
             tombstoned: false,
             extraction_policy_ids: value.extraction_policy_ids,
+            external_id: value.external_id,
+            preview: value.preview,
+            hash: value.hash,
+            expires_at: value.expires_at,
         })
     }
 }
 
+/// Well known topology labels of executors
+pub const EXECUTOR_ZONE_LABEL: &str = "zone";
+pub const EXECUTOR_REGION_LABEL: &str = "region";
+pub const EXECUTOR_HARDWARE_CLASS_LABEL: &str = "hardware_class";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ExecutorMetadata {
     pub id: String,
     pub last_seen: u64,
     pub addr: String,
     pub extractor: ExtractorDescription,
+    //  Topology labels the executor registered with, e.g. its zone, region
+    // and hardware class
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    //  Set when the executor is drained, no new tasks are assigned to it
+    // until it registers again
+    #[serde(default)]
+    pub draining: bool,
+}
+
+impl ExecutorMetadata {
+    /// Whether the executor has all of the labels
+    pub fn has_labels(&self, labels: &HashMap<String, String>) -> bool {
+        labels
+            .iter()
+            .all(|(key, value)| self.labels.get(key) == Some(value))
+    }
+}
+
+impl From<ExecutorMetadata> for indexify_coordinator::Executor {
+    fn from(value: ExecutorMetadata) -> Self {
+        Self {
+            id: value.id,
+            addr: value.addr,
+            extractor: Some(value.extractor.into()),
+            last_seen: value.last_seen,
+            labels: value.labels,
+            draining: value.draining,
+            running_tasks: 0,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -611,14 +1749,55 @@ impl From<Namespace> for indexify_coordinator::Namespace {
     }
 }
 
+/// Limits of a namespace enforced by the state machine, limits which aren't
+/// set don't apply
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct NamespaceQuotas {
+    pub max_content: Option<u64>,
+    pub max_stored_bytes: Option<u64>,
+    pub max_extraction_policies: Option<u64>,
+}
+
+impl From<NamespaceQuotas> for indexify_coordinator::NamespaceQuotas {
+    fn from(value: NamespaceQuotas) -> Self {
+        Self {
+            max_content: value.max_content,
+            max_stored_bytes: value.max_stored_bytes,
+            max_extraction_policies: value.max_extraction_policies,
+        }
+    }
+}
+
+impl From<indexify_coordinator::NamespaceQuotas> for NamespaceQuotas {
+    fn from(value: indexify_coordinator::NamespaceQuotas) -> Self {
+        Self {
+            max_content: value.max_content,
+            max_stored_bytes: value.max_stored_bytes,
+            max_extraction_policies: value.max_extraction_policies,
+        }
+    }
+}
+
+/// Counters of a namespace updated by the state machine as writes are
+/// applied, the bytes stored by the namespace are tracked on their own
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct NamespaceStats {
+    pub content: u64,
+    pub tasks_pending: u64,
+    pub tasks_failed: u64,
+    pub indexes: u64,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum ChangeType {
     NewContent,
     TombstoneContent,
     NewExtractionPolicy,
+    ExtractionPolicyUpdated,
     ExecutorAdded,
     ExecutorRemoved,
     NewGargabeCollectionTask,
+    TaskCompleted,
 }
 
 impl fmt::Display for ChangeType {
@@ -627,9 +1806,11 @@ impl fmt::Display for ChangeType {
             ChangeType::NewContent => write!(f, "NewContent"),
             ChangeType::TombstoneContent => write!(f, "TombstoneContent"),
             ChangeType::NewExtractionPolicy => write!(f, "NewBinding"),
+            ChangeType::ExtractionPolicyUpdated => write!(f, "ExtractionPolicyUpdated"),
             ChangeType::ExecutorAdded => write!(f, "ExecutorAdded"),
             ChangeType::ExecutorRemoved => write!(f, "ExecutorRemoved"),
             ChangeType::NewGargabeCollectionTask => write!(f, "NewGarbageCollectionTask"),
+            ChangeType::TaskCompleted => write!(f, "TaskCompleted"),
         }
     }
 }
@@ -674,8 +1855,10 @@ impl TryFrom<indexify_coordinator::StateChange> for StateChange {
         let change_type = match value.change_type.as_str() {
             "NewContent" => ChangeType::NewContent,
             "NewBinding" => ChangeType::NewExtractionPolicy,
+            "ExtractionPolicyUpdated" => ChangeType::ExtractionPolicyUpdated,
             "ExecutorAdded" => ChangeType::ExecutorAdded,
             "ExecutorRemoved" => ChangeType::ExecutorRemoved,
+            "TaskCompleted" => ChangeType::TaskCompleted,
             _ => return Err(anyhow!("Invalid ChangeType")),
         };
         Ok(Self {
@@ -720,12 +1903,65 @@ pub enum SchemaColumnType {
     Object,
 }
 
+impl SchemaColumnType {
+    /// Type of a column holding the value, None for null which fits a column
+    /// of any type
+    pub fn infer(value: &serde_json::Value) -> Option<Self> {
+        match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::Bool(_) => Some(SchemaColumnType::Bool),
+            serde_json::Value::Number(number) => match number.as_i64() {
+                Some(int) if i32::try_from(int).is_ok() => Some(SchemaColumnType::Int),
+                Some(_) => Some(SchemaColumnType::BigInt),
+                None => Some(SchemaColumnType::Float),
+            },
+            serde_json::Value::String(_) => Some(SchemaColumnType::Text),
+            serde_json::Value::Array(_) => Some(SchemaColumnType::Array),
+            serde_json::Value::Object(_) => Some(SchemaColumnType::Object),
+        }
+    }
+
+    /// Whether values of the other type can be stored in a column of this
+    /// type, integers are widened to bigger integers and floats
+    pub fn accepts(&self, other: &SchemaColumnType) -> bool {
+        matches!(
+            (self, other),
+            (SchemaColumnType::Null, _) |
+                (SchemaColumnType::BigInt, SchemaColumnType::Int) |
+                (
+                    SchemaColumnType::Float,
+                    SchemaColumnType::Int | SchemaColumnType::BigInt
+                )
+        ) || self == other
+    }
+}
+
+/// Columns inferred from the fields of extracted metadata. New columns are
+/// fields which aren't in the schema yet, conflicts are fields whose value
+/// doesn't fit the type of their column.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaInference {
+    pub columns: BTreeMap<String, SchemaColumnType>,
+    pub conflicts: BTreeMap<String, SchemaColumnType>,
+}
+
+impl SchemaInference {
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty() && self.conflicts.is_empty()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct StructuredDataSchema {
     pub columns: BTreeMap<String, SchemaColumnType>,
     pub content_source: String,
     pub namespace: String,
     pub id: String,
+    // Columns extractors wrote values of another type to, with the type of
+    // the last conflicting value. The values are stored but can't be queried
+    // through the column.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub conflicts: BTreeMap<String, SchemaColumnType>,
 }
 
 impl StructuredDataSchema {
@@ -736,6 +1972,7 @@ impl StructuredDataSchema {
             content_source: content_source.to_string(),
             namespace: namespace.to_string(),
             id,
+            conflicts: BTreeMap::new(),
         }
     }
 
@@ -749,9 +1986,71 @@ impl StructuredDataSchema {
             columns,
             namespace: self.namespace.clone(),
             id: self.id.clone(),
+            conflicts: self.conflicts.clone(),
         })
     }
 
+    /// Additively evolves the schema with the columns of an extractor output.
+    /// Columns which already exist keep their type, since rows written with it
+    /// can't be migrated. Returns None if no column was added.
+    pub fn evolve(
+        &self,
+        columns: impl IntoIterator<Item = (String, SchemaColumnType)>,
+    ) -> Option<Self> {
+        let mut evolved = self.clone();
+        for (column, dtype) in columns {
+            evolved.columns.entry(column).or_insert(dtype);
+        }
+        (evolved.columns.len() != self.columns.len()).then_some(evolved)
+    }
+
+    /// Infers the columns of the top level fields of extracted metadata.
+    /// Null values don't add columns, and conflicts already recorded with
+    /// the same type aren't reported again.
+    pub fn infer(&self, metadata: &serde_json::Value) -> SchemaInference {
+        let mut inference = SchemaInference::default();
+        let Some(fields) = metadata.as_object() else {
+            return inference;
+        };
+        for (field, value) in fields {
+            let Some(dtype) = SchemaColumnType::infer(value) else {
+                continue;
+            };
+            match self.columns.get(field) {
+                None => {
+                    inference.columns.insert(field.clone(), dtype);
+                }
+                Some(column) if !column.accepts(&dtype) => {
+                    if self.conflicts.get(field) != Some(&dtype) {
+                        inference.conflicts.insert(field.clone(), dtype);
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+        inference
+    }
+
+    /// Evolves the schema with the columns inferred from metadata and records
+    /// the conflicts. Returns None if the schema is unchanged.
+    pub fn evolve_inferred(&self, inference: SchemaInference) -> Option<Self> {
+        let mut evolved = self
+            .evolve(inference.columns)
+            .unwrap_or_else(|| self.clone());
+        for (column, dtype) in inference.conflicts {
+            //  A conflict can be resolved by a column added since it was
+            // inferred, e.g. by another ingestion server
+            if !evolved
+                .columns
+                .get(&column)
+                .map_or(true, |column| column.accepts(&dtype))
+            {
+                evolved.conflicts.insert(column, dtype);
+            }
+        }
+        (evolved != *self).then_some(evolved)
+    }
+
     pub fn schema_id(namespace: &str, content_source: &str) -> String {
         let mut s = DefaultHasher::new();
         namespace.hash(&mut s);
@@ -819,4 +2118,313 @@ mod test {
             );"
         );
     }
+
+    #[test]
+    fn test_evolve_structured_data_schema() {
+        let schema = StructuredDataSchema::new("test", "test-namespace")
+            .evolve(HashMap::from([(
+                "object_class".to_string(),
+                SchemaColumnType::Text,
+            )]))
+            .unwrap();
+        assert!(schema
+            .evolve(HashMap::from([(
+                "object_class".to_string(),
+                SchemaColumnType::Int,
+            )]))
+            .is_none());
+
+        let evolved = schema
+            .evolve(HashMap::from([
+                ("object_class".to_string(), SchemaColumnType::Int),
+                ("score".to_string(), SchemaColumnType::Float),
+            ]))
+            .unwrap();
+        assert_eq!(evolved.id, schema.id);
+        assert_eq!(evolved.columns.len(), 2);
+        assert_eq!(evolved.columns["object_class"], SchemaColumnType::Text);
+        assert_eq!(evolved.columns["score"], SchemaColumnType::Float);
+    }
+
+    #[test]
+    fn test_infer_structured_data_schema() {
+        let schema = StructuredDataSchema::new("test", "test-namespace")
+            .evolve(HashMap::from([
+                ("object_class".to_string(), SchemaColumnType::Text),
+                ("score".to_string(), SchemaColumnType::Float),
+            ]))
+            .unwrap();
+        let metadata = serde_json::json!({
+            "object_class": 3,
+            "score": 1,
+            "count": 5_000_000_000i64,
+            "tags": ["a"],
+            "label": null,
+        });
+        let inference = schema.infer(&metadata);
+        assert_eq!(
+            inference.columns,
+            BTreeMap::from([
+                ("count".to_string(), SchemaColumnType::BigInt),
+                ("tags".to_string(), SchemaColumnType::Array),
+            ])
+        );
+        assert_eq!(
+            inference.conflicts,
+            BTreeMap::from([("object_class".to_string(), SchemaColumnType::Int)])
+        );
+
+        let evolved = schema.evolve_inferred(inference).unwrap();
+        assert_eq!(evolved.id, schema.id);
+        assert_eq!(evolved.columns.len(), 4);
+        assert_eq!(evolved.columns["object_class"], SchemaColumnType::Text);
+        assert_eq!(evolved.conflicts["object_class"], SchemaColumnType::Int);
+
+        //  Recorded conflicts and known columns don't evolve the schema again
+        let inference = evolved.infer(&metadata);
+        assert!(inference.is_empty());
+        assert!(evolved.evolve_inferred(inference).is_none());
+    }
+
+    #[test]
+    fn test_render_query_template() {
+        let template = QueryTemplate {
+            namespace: "test-namespace".to_string(),
+            name: "news".to_string(),
+            index: "news.embedding".to_string(),
+            query: "{topic} news from {region} {not a placeholder}".to_string(),
+            k: None,
+            filters: HashMap::new(),
+            parameters: HashMap::from([("region".to_string(), "europe".to_string())]),
+            scoring_plugin: None,
+        };
+        let parameters = HashMap::from([("topic".to_string(), "energy".to_string())]);
+        assert_eq!(
+            template.render(&parameters).unwrap(),
+            "energy news from europe {not a placeholder}"
+        );
+        let parameters = HashMap::from([
+            ("topic".to_string(), "energy".to_string()),
+            ("region".to_string(), "asia".to_string()),
+        ]);
+        assert_eq!(
+            template.render(&parameters).unwrap(),
+            "energy news from asia {not a placeholder}"
+        );
+        assert!(template.render(&HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_validate_quantization() {
+        let schema: EmbeddingSchema = serde_json::from_str(
+            r#"{"dim": 384, "distance": "cosine", "quantization": {"type": "product", "compression": 16}}"#,
+        )
+        .unwrap();
+        let quantization = schema.quantization.unwrap();
+        assert_eq!(
+            quantization,
+            IndexQuantization::Product {
+                compression: 16,
+                always_ram: false
+            }
+        );
+        assert!(quantization.validate().is_ok());
+        assert!(IndexQuantization::Product {
+            compression: 12,
+            always_ram: false
+        }
+        .validate()
+        .is_err());
+        assert!(IndexQuantization::Scalar {
+            quantile: Some(0.2),
+            always_ram: false
+        }
+        .validate()
+        .is_err());
+
+        //  Schemas of indexes created before quantization have none
+        let schema: EmbeddingSchema =
+            serde_json::from_str(r#"{"dim": 384, "distance": "cosine"}"#).unwrap();
+        assert!(schema.quantization.is_none());
+    }
+
+    #[test]
+    fn test_vector_bytes() {
+        let mut schema = EmbeddingSchema {
+            dim: 384,
+            distance: "cosine".to_string(),
+            quantization: None,
+            named_vectors: vec![],
+            ttl_secs: None,
+            sparse: false,
+            index_params: None,
+        };
+        assert_eq!(schema.vector_bytes(), 1536);
+        schema.quantization = Some(IndexQuantization::Scalar {
+            quantile: None,
+            always_ram: false,
+        });
+        assert_eq!(schema.vector_bytes(), 384);
+        schema.quantization = Some(IndexQuantization::Product {
+            compression: 16,
+            always_ram: false,
+        });
+        assert_eq!(schema.vector_bytes(), 96);
+    }
+
+    #[test]
+    fn test_embedding_expiration() {
+        assert_eq!(EmbeddingExpiration::expires_at(0, None, 100), None);
+        assert_eq!(EmbeddingExpiration::expires_at(0, Some(10), 100), Some(110));
+        assert_eq!(EmbeddingExpiration::expires_at(150, None, 100), Some(150));
+        assert_eq!(
+            EmbeddingExpiration::expires_at(150, Some(10), 100),
+            Some(110)
+        );
+        assert_eq!(
+            EmbeddingExpiration::expires_at(105, Some(10), 100),
+            Some(105)
+        );
+    }
+
+    #[test]
+    fn test_named_vectors() {
+        let schema: EmbeddingSchema = serde_json::from_str(
+            r#"{"dim": 2, "distance": "cosine", "named_vectors": ["title", "body"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            schema.named_vector_tables("default.embeddings.embedding"),
+            vec![
+                "default.embeddings.embedding.vectors.title",
+                "default.embeddings.embedding.vectors.body"
+            ]
+        );
+
+        let embedding: Embedding = serde_json::from_str(
+            r#"{"values": [1.0, 0.0], "distance": "cosine", "vectors": {"title": [0.0, 1.0]}}"#,
+        )
+        .unwrap();
+        assert_eq!(embedding.vector(None), Some(&vec![1.0, 0.0]));
+        assert_eq!(embedding.vector(Some("title")), Some(&vec![0.0, 1.0]));
+        assert_eq!(embedding.vector(Some("body")), None);
+
+        //  Embeddings without named vectors serialize as before
+        let embedding = Embedding {
+            values: vec![1.0],
+            distance: "cosine".to_string(),
+            vectors: HashMap::new(),
+            indices: vec![],
+        };
+        assert_eq!(
+            serde_json::to_string(&embedding).unwrap(),
+            r#"{"values":[1.0],"distance":"cosine"}"#
+        );
+    }
+
+    #[test]
+    fn test_sparse_embeddings() {
+        let schema: EmbeddingSchema =
+            serde_json::from_str(r#"{"dim": 30522, "distance": "dot", "sparse": true}"#).unwrap();
+        assert!(schema.sparse);
+        let dense: EmbeddingSchema =
+            serde_json::from_str(r#"{"dim": 2, "distance": "cosine"}"#).unwrap();
+        assert!(!dense.sparse);
+        assert!(!serde_json::to_string(&dense).unwrap().contains("sparse"));
+
+        let embedding: Embedding = serde_json::from_str(
+            r#"{"values": [0.5, 1.2], "distance": "dot", "indices": [17, 2048]}"#,
+        )
+        .unwrap();
+        let vector = embedding.sparse_vector();
+        assert_eq!(vector.indices, vec![17, 2048]);
+        assert!(vector.validate(schema.dim).is_ok());
+        assert!(vector.validate(1000).is_err());
+
+        let mismatched = SparseVector {
+            indices: vec![1],
+            values: vec![0.5, 0.5],
+        };
+        assert!(mismatched.validate(10).is_err());
+        let duplicated = SparseVector {
+            indices: vec![1, 1],
+            values: vec![0.5, 0.5],
+        };
+        assert!(duplicated.validate(10).is_err());
+    }
+
+    #[test]
+    fn test_validate_processing_sla() {
+        let sla = ProcessingSla {
+            namespace: "default".to_string(),
+            extraction_policy: "embeddings".to_string(),
+            target_secs: 600,
+            attainment_threshold: 0.95,
+            window_secs: 3600,
+            ..Default::default()
+        };
+        assert!(sla.validate().is_ok());
+        assert!(ProcessingSla {
+            attainment_threshold: 1.5,
+            ..sla.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(ProcessingSla {
+            window_secs: 60,
+            ..sla.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(ProcessingSla {
+            webhook_url: "ftp://alerts".to_string(),
+            ..sla.clone()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        //  Failed tasks aren't retried by default
+        assert!(!RetryPolicy::default().retries(1));
+
+        let retry_policy = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff_secs: 10,
+            max_backoff_secs: 30,
+            ..Default::default()
+        };
+        assert!(retry_policy.validate().is_ok());
+        assert!(retry_policy.retries(1));
+        assert!(retry_policy.retries(2));
+        assert!(!retry_policy.retries(3));
+        assert_eq!(retry_policy.backoff_secs(1), 10);
+        assert_eq!(retry_policy.backoff_secs(2), 20);
+        assert_eq!(retry_policy.backoff_secs(3), 30);
+        assert_eq!(retry_policy.backoff_secs(64), 30);
+        let fixed = RetryPolicy {
+            backoff: BackoffStrategy::Fixed,
+            ..retry_policy.clone()
+        };
+        assert_eq!(fixed.backoff_secs(3), 10);
+
+        assert!(RetryPolicy {
+            max_attempts: 0,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(RetryPolicy {
+            initial_backoff_secs: 60,
+            max_backoff_secs: 10,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+
+        //  Policies created before retries were configurable aren't retried
+        let retry_policy: RetryPolicy = serde_json::from_str("{}").unwrap();
+        assert_eq!(retry_policy, RetryPolicy::default());
+    }
 }
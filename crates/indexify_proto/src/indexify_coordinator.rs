@@ -4,6 +4,8 @@
 pub struct GetContentMetadataRequest {
     #[prost(string, repeated, tag = "1")]
     pub content_list: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(enumeration = "ReadConsistency", tag = "2")]
+    pub consistency: i32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -41,6 +43,10 @@ pub struct UpdateTaskRequest {
     pub content_id: ::prost::alloc::string::String,
     #[prost(string, tag = "6")]
     pub extraction_policy_name: ::prost::alloc::string::String,
+    ///   Id the client gives the update, a retry of the update with the same id
+    /// isn't applied again
+    #[prost(string, tag = "7")]
+    pub request_id: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -61,6 +67,26 @@ pub struct StateChange {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WatchStateChangesRequest {
+    ///   Changes of all namespaces are sent if it's empty
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    ///   Types of the changes to send, changes of every type are sent if it's
+    /// empty
+    #[prost(string, repeated, tag = "2")]
+    pub change_types: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StateChangeEvent {
+    #[prost(message, optional, tag = "1")]
+    pub change: ::core::option::Option<StateChange>,
+    ///   Empty for changes which don't belong to a namespace, e.g. executors
+    #[prost(string, tag = "2")]
+    pub namespace: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListStateChangesResponse {
     #[prost(message, repeated, tag = "1")]
     pub changes: ::prost::alloc::vec::Vec<StateChange>,
@@ -113,6 +139,8 @@ pub struct GetIndexRequest {
     pub namespace: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub name: ::prost::alloc::string::String,
+    #[prost(enumeration = "ReadConsistency", tag = "3")]
+    pub consistency: i32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -186,6 +214,13 @@ pub struct RegisterExecutorRequest {
     pub addr: ::prost::alloc::string::String,
     #[prost(message, optional, tag = "3")]
     pub extractor: ::core::option::Option<Extractor>,
+    ///   Topology labels of the executor, e.g. its zone, region and hardware
+    ///   class
+    #[prost(map = "string, string", tag = "4")]
+    pub labels: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -195,6 +230,53 @@ pub struct RegisterExecutorResponse {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListExecutorsRequest {
+    ///   Only the executors having all of these labels are listed
+    #[prost(map = "string, string", tag = "1")]
+    pub labels_eq: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListExecutorsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub executors: ::prost::alloc::vec::Vec<Executor>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Executor {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub addr: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub extractor: ::core::option::Option<Extractor>,
+    #[prost(uint64, tag = "4")]
+    pub last_seen: u64,
+    #[prost(map = "string, string", tag = "5")]
+    pub labels: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    ///   No new tasks are assigned to a draining executor
+    #[prost(bool, tag = "6")]
+    pub draining: bool,
+    #[prost(uint64, tag = "7")]
+    pub running_tasks: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DrainExecutorRequest {
+    #[prost(string, tag = "1")]
+    pub executor_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DrainExecutorResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RegisterIngestionServerRequest {
     #[prost(string, tag = "1")]
     pub ingestion_server_id: ::prost::alloc::string::String,
@@ -292,6 +374,16 @@ pub struct Task {
     /// list of all tables that the content may belong to
     #[prost(string, repeated, tag = "9")]
     pub index_tables: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// number of earlier attempts of the task which failed
+    #[prost(uint32, tag = "10")]
+    pub attempts: u32,
+    /// priority of the extraction policy of the task
+    #[prost(int32, tag = "11")]
+    pub priority: i32,
+    /// unix time in seconds a failed task is assigned again at while it's
+    /// retried
+    #[prost(uint64, optional, tag = "12")]
+    pub retry_at: ::core::option::Option<u64>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -338,6 +430,19 @@ pub struct GetNamespaceResponse {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteNamespaceRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteNamespaceResponse {
+    ///   Garbage collection tasks created to delete the content of the namespace
+    #[prost(uint64, tag = "1")]
+    pub gc_tasks: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListContentRequest {
     #[prost(string, tag = "1")]
     pub namespace: ::prost::alloc::string::String,
@@ -350,6 +455,10 @@ pub struct ListContentRequest {
         ::prost::alloc::string::String,
         ::prost::alloc::string::String,
     >,
+    #[prost(string, tag = "5")]
+    pub external_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub filter: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -376,6 +485,8 @@ pub struct CreateNamespaceRequest {
     pub name: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "2")]
     pub policies: ::prost::alloc::vec::Vec<ExtractionPolicy>,
+    #[prost(string, tag = "3")]
+    pub operation_token: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -412,6 +523,61 @@ pub struct ExtractionPolicy {
     >,
     #[prost(string, tag = "6")]
     pub content_source: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "7")]
+    pub version: u64,
+    #[prost(message, optional, tag = "8")]
+    pub content_filter: ::core::option::Option<ContentFilter>,
+    ///   Existing content created before the time, in seconds, isn't extracted
+    /// when the policy is created, all of it is when it's not set
+    #[prost(int64, optional, tag = "9")]
+    pub backfill_from: ::core::option::Option<i64>,
+    #[prost(message, optional, tag = "10")]
+    pub retry_policy: ::core::option::Option<RetryPolicy>,
+    ///   Tasks of policies with a higher priority are given to executors
+    /// before the tasks of other policies
+    #[prost(int32, tag = "11")]
+    pub priority: i32,
+}
+/// How the failed tasks of a policy are retried
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RetryPolicy {
+    ///   Attempts of a task including the first one, failed tasks aren't
+    /// retried when it's at most 1
+    #[prost(uint32, tag = "1")]
+    pub max_attempts: u32,
+    #[prost(enumeration = "BackoffStrategy", tag = "2")]
+    pub backoff: i32,
+    #[prost(uint64, tag = "3")]
+    pub initial_backoff_secs: u64,
+    #[prost(uint64, tag = "4")]
+    pub max_backoff_secs: u64,
+}
+/// Content a policy is applied to, on top of its label filters. Criteria which
+/// aren't set match any content.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ContentFilter {
+    /// Globs of mime types, e.g. image/*
+    #[prost(string, repeated, tag = "1")]
+    pub mime_types: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(message, repeated, tag = "2")]
+    pub label_ranges: ::prost::alloc::vec::Vec<LabelRange>,
+    #[prost(uint64, optional, tag = "3")]
+    pub min_size_bytes: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "4")]
+    pub max_size_bytes: ::core::option::Option<u64>,
+}
+/// Matches content whose label is a number within the bounds
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LabelRange {
+    #[prost(string, tag = "1")]
+    pub label: ::prost::alloc::string::String,
+    #[prost(double, optional, tag = "2")]
+    pub min: ::core::option::Option<f64>,
+    #[prost(double, optional, tag = "3")]
+    pub max: ::core::option::Option<f64>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -433,6 +599,74 @@ pub struct ExtractionPolicyRequest {
     pub content_source: ::prost::alloc::string::String,
     #[prost(int64, tag = "7")]
     pub created_at: i64,
+    #[prost(string, tag = "8")]
+    pub operation_token: ::prost::alloc::string::String,
+    #[prost(bool, tag = "9")]
+    pub keyword_index: bool,
+    #[prost(message, optional, tag = "10")]
+    pub content_filter: ::core::option::Option<ContentFilter>,
+    #[prost(int64, optional, tag = "11")]
+    pub backfill_from: ::core::option::Option<i64>,
+    #[prost(message, optional, tag = "12")]
+    pub retry_policy: ::core::option::Option<RetryPolicy>,
+    #[prost(int32, tag = "13")]
+    pub priority: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExtractionPolicyFilters {
+    #[prost(map = "string, string", tag = "1")]
+    pub filters: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateExtractionPolicyRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    ///   Fields which aren't set are left unchanged
+    #[prost(message, optional, tag = "3")]
+    pub filters: ::core::option::Option<ExtractionPolicyFilters>,
+    #[prost(string, optional, tag = "4")]
+    pub input_params: ::core::option::Option<::prost::alloc::string::String>,
+    ///   Creates tasks for all the content matching the updated policy, even
+    /// content the policy was already applied to
+    #[prost(bool, tag = "5")]
+    pub reextract: bool,
+    #[prost(message, optional, tag = "6")]
+    pub content_filter: ::core::option::Option<ContentFilter>,
+    #[prost(message, optional, tag = "7")]
+    pub retry_policy: ::core::option::Option<RetryPolicy>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateExtractionPolicyResponse {
+    #[prost(message, optional, tag = "1")]
+    pub extraction_policy: ::core::option::Option<ExtractionPolicy>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteExtractionPolicyRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    ///   The indexes of the policy are removed along with it, otherwise they're
+    /// kept and can still be searched
+    #[prost(bool, tag = "3")]
+    pub drop_indexes: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteExtractionPolicyResponse {
+    ///   Tables of the dropped indexes, which are dropped from the vector and
+    /// keyword stores by the caller
+    #[prost(string, repeated, tag = "1")]
+    pub dropped_index_tables: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -486,6 +720,17 @@ pub struct ContentMetadata {
         ::prost::alloc::string::String,
         u64,
     >,
+    #[prost(string, tag = "12")]
+    pub external_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "13")]
+    pub preview: ::prost::alloc::string::String,
+    #[prost(string, tag = "14")]
+    pub hash: ::prost::alloc::string::String,
+    ///   Time after which the embeddings of the content are removed from the
+    /// indexes, zero if they don't expire. Extracted content inherits it from
+    /// its parent.
+    #[prost(uint64, tag = "15")]
+    pub expires_at: u64,
 }
 #[derive(serde::Deserialize, serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -493,12 +738,19 @@ pub struct ContentMetadata {
 pub struct CreateContentRequest {
     #[prost(message, optional, tag = "2")]
     pub content: ::core::option::Option<ContentMetadata>,
+    ///   Id the client gives the write, a retry of the write with the same id
+    /// isn't applied again
+    #[prost(string, tag = "3")]
+    pub request_id: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CreateContentResponse {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
+    ///   Expiration of the content, including one inherited from its parent
+    #[prost(uint64, tag = "2")]
+    pub expires_at: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -540,6 +792,28 @@ pub struct StructuredDataSchema {
     pub columns: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub content_source: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub conflicts: ::prost::alloc::string::String,
+}
+///   Columns inferred from the metadata written by extractors, as JSON maps of
+/// column names to types
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EvolveStructuredDataSchemaRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content_source: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub columns: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub conflicts: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EvolveStructuredDataSchemaResponse {
+    #[prost(message, optional, tag = "1")]
+    pub schema: ::core::option::Option<StructuredDataSchema>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -635,6 +909,18 @@ pub struct RaftMetricsSnapshotResponse {
     pub last_log_index: u64,
     #[prost(uint64, tag = "20")]
     pub current_leader: u64,
+    #[prost(uint64, tag = "21")]
+    pub last_applied_index: u64,
+    ///   Only known by the leader, the highest index of the log replicated to a
+    /// quorum of the voters
+    #[prost(uint64, optional, tag = "22")]
+    pub committed_index: ::core::option::Option<u64>,
+    ///   Entries of the log which aren't applied to the state machine yet
+    #[prost(uint64, tag = "23")]
+    pub pending_log_entries: u64,
+    ///   Entries of the log each member is missing, only known by the leader
+    #[prost(map = "uint64, uint64", tag = "24")]
+    pub replication_lag: ::std::collections::HashMap<u64, u64>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -648,125 +934,2254 @@ pub struct TaskAssignments {
         ::prost::alloc::string::String,
     >,
 }
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
-#[repr(i32)]
-pub enum TaskOutcome {
-    Unknown = 0,
-    Failed = 1,
-    Success = 2,
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlanExtractionPolicyRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_policy: ::prost::alloc::string::String,
+    ///   Overrides the observed average task latency of the extractor
+    #[prost(uint64, tag = "3")]
+    pub task_latency_ms: u64,
+    ///   Number of tasks an executor runs concurrently, defaults to 1
+    #[prost(uint64, tag = "4")]
+    pub executor_concurrency: u64,
 }
-impl TaskOutcome {
-    /// String value of the enum field names used in the ProtoBuf definition.
-    ///
-    /// The values are not transformed in any way and thus are considered stable
-    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
-    pub fn as_str_name(&self) -> &'static str {
-        match self {
-            TaskOutcome::Unknown => "UNKNOWN",
-            TaskOutcome::Failed => "FAILED",
-            TaskOutcome::Success => "SUCCESS",
-        }
-    }
-    /// Creates an enum from field names used in the ProtoBuf definition.
-    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
-        match value {
-            "UNKNOWN" => Some(Self::Unknown),
-            "FAILED" => Some(Self::Failed),
-            "SUCCESS" => Some(Self::Success),
-            _ => None,
-        }
-    }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlanExtractionPolicyResponse {
+    #[prost(string, tag = "1")]
+    pub extractor: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub total_tasks: u64,
+    #[prost(uint64, tag = "3")]
+    pub total_bytes: u64,
+    #[prost(uint64, tag = "4")]
+    pub queued_tasks: u64,
+    #[prost(uint64, tag = "5")]
+    pub executors: u64,
+    #[prost(uint64, tag = "6")]
+    pub task_latency_ms: u64,
+    ///   Zero when there are no executors for the extractor
+    #[prost(uint64, tag = "7")]
+    pub estimated_completion_ms: u64,
+    #[prost(uint64, tag = "8")]
+    pub compute_time_ms: u64,
+    #[prost(map = "string, uint64", tag = "9")]
+    pub tasks_per_executor: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        u64,
+    >,
 }
-/// Generated client implementations.
-pub mod coordinator_service_client {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    use tonic::codegen::http::Uri;
-    #[derive(Debug, Clone)]
-    pub struct CoordinatorServiceClient<T> {
-        inner: tonic::client::Grpc<T>,
-    }
-    impl CoordinatorServiceClient<tonic::transport::Channel> {
-        /// Attempt to create a new client by connecting to a given endpoint.
-        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
-        where
-            D: TryInto<tonic::transport::Endpoint>,
-            D::Error: Into<StdError>,
-        {
-            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
-            Ok(Self::new(conn))
-        }
-    }
-    impl<T> CoordinatorServiceClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
-        }
-        pub fn with_origin(inner: T, origin: Uri) -> Self {
-            let inner = tonic::client::Grpc::with_origin(inner, origin);
-            Self { inner }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> CoordinatorServiceClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
-            T::ResponseBody: Default,
-            T: tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-                Response = http::Response<
-                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
-                >,
-            >,
-            <T as tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-            >>::Error: Into<StdError> + Send + Sync,
-        {
-            CoordinatorServiceClient::new(InterceptedService::new(inner, interceptor))
-        }
-        /// Compress requests with the given encoding.
-        ///
-        /// This requires the server to support it otherwise it might respond with an
-        /// error.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.send_compressed(encoding);
-            self
-        }
-        /// Enable decompressing responses.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.accept_compressed(encoding);
-            self
-        }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_decoding_message_size(limit);
-            self
-        }
-        /// Limits the maximum size of an encoded message.
-        ///
-        /// Default: `usize::MAX`
-        #[must_use]
-        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_encoding_message_size(limit);
-            self
-        }
-        pub async fn create_content(
-            &mut self,
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DryRunExtractionPolicyRequest {
+    ///   The proposed policy, which isn't created
+    #[prost(message, optional, tag = "1")]
+    pub extraction_policy: ::core::option::Option<ExtractionPolicyRequest>,
+    ///   Number of matching content returned as a sample
+    #[prost(uint64, tag = "2")]
+    pub sample_size: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DryRunExtractionPolicyResponse {
+    #[prost(uint64, tag = "1")]
+    pub matching_content: u64,
+    #[prost(uint64, tag = "2")]
+    pub matching_bytes: u64,
+    ///   The matching content which was created first
+    #[prost(message, repeated, tag = "3")]
+    pub sample: ::prost::alloc::vec::Vec<ContentMetadata>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateIndexMigrationRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub source_index: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub target_index: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetIndexMigrationRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub source_index: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CompleteIndexMigrationRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub source_index: ::prost::alloc::string::String,
+    ///   Complete the migration even if not all content was extracted into the target index
+    #[prost(bool, tag = "3")]
+    pub force: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IndexMigrationResponse {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub source_index: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub target_index: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub target_extraction_policy: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "5")]
+    pub created_at: u64,
+    #[prost(bool, tag = "6")]
+    pub completed: bool,
+    #[prost(uint64, tag = "7")]
+    pub total_content: u64,
+    #[prost(uint64, tag = "8")]
+    pub migrated_content: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RebalanceJob {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub index: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub source_table: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub target_table: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "5")]
+    pub shard_count: u32,
+    #[prost(uint64, tag = "6")]
+    pub total_content: u64,
+    #[prost(uint64, tag = "7")]
+    pub moved_vectors: u64,
+    #[prost(bool, tag = "8")]
+    pub completed: bool,
+    #[prost(string, tag = "9")]
+    pub error: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "10")]
+    pub created_at: u64,
+    #[prost(uint64, tag = "11")]
+    pub updated_at: u64,
+    ///   Whether the vectors are extracted again from the content rather than
+    /// copied from the source table
+    #[prost(bool, tag = "12")]
+    pub reextract: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateRebalanceJobRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub index: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub target_table: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "4")]
+    pub shard_count: u32,
+    #[prost(bool, tag = "5")]
+    pub reextract: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateRebalanceJobResponse {
+    #[prost(message, optional, tag = "1")]
+    pub job: ::core::option::Option<RebalanceJob>,
+    ///   Content whose vectors have to be moved to the target table
+    #[prost(string, repeated, tag = "2")]
+    pub content_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRebalanceJobRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub index: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateRebalanceJobRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub index: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub moved_vectors: u64,
+    #[prost(bool, tag = "4")]
+    pub completed: bool,
+    #[prost(string, tag = "5")]
+    pub error: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListIndexContentRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub index: ::prost::alloc::string::String,
+}
+///   Content whose extracted embeddings are written to the index
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListIndexContentResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub content_list: ::prost::alloc::vec::Vec<ContentMetadata>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryTemplate {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub index: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub query: ::prost::alloc::string::String,
+    ///   Zero uses the default number of results
+    #[prost(uint64, tag = "5")]
+    pub k: u64,
+    #[prost(map = "string, string", tag = "6")]
+    pub filters: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    #[prost(map = "string, string", tag = "7")]
+    pub parameters: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    #[prost(message, optional, tag = "8")]
+    pub scoring_plugin: ::core::option::Option<ScoringPlugin>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScoringPlugin {
+    #[prost(string, tag = "1")]
+    pub wasm_url: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub max_fuel: u64,
+    #[prost(uint64, tag = "3")]
+    pub max_memory_bytes: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetQueryTemplateRequest {
+    #[prost(message, optional, tag = "1")]
+    pub template: ::core::option::Option<QueryTemplate>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetQueryTemplateResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetQueryTemplateRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetQueryTemplateResponse {
+    #[prost(message, optional, tag = "1")]
+    pub template: ::core::option::Option<QueryTemplate>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListQueryTemplatesRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListQueryTemplatesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub templates: ::prost::alloc::vec::Vec<QueryTemplate>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteQueryTemplateRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteQueryTemplateResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IngestIntent {
+    #[prost(string, tag = "1")]
+    pub content_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub storage_url: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub ingestion_server_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "5")]
+    pub created_at: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateIngestIntentRequest {
+    #[prost(message, optional, tag = "1")]
+    pub intent: ::core::option::Option<IngestIntent>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateIngestIntentResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListIngestIntentsRequest {
+    ///   Only intents created before this timestamp are returned, zero returns
+    /// all of them
+    #[prost(uint64, tag = "1")]
+    pub created_before: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListIngestIntentsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub intents: ::prost::alloc::vec::Vec<IngestIntent>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteIngestIntentsRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub content_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteIngestIntentsResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EmbeddingExpiration {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub index_table: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub content_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub hash: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "5")]
+    pub expires_at: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateEmbeddingExpirationsRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub expirations: ::prost::alloc::vec::Vec<EmbeddingExpiration>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateEmbeddingExpirationsResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListEmbeddingExpirationsRequest {
+    ///   Only embeddings expiring before this timestamp are returned, zero
+    /// returns all of them
+    #[prost(uint64, tag = "1")]
+    pub expires_before: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListEmbeddingExpirationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub expirations: ::prost::alloc::vec::Vec<EmbeddingExpiration>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteEmbeddingExpirationsRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub expirations: ::prost::alloc::vec::Vec<EmbeddingExpiration>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteEmbeddingExpirationsResponse {}
+///   The content with the same bytes whose embedding is stored once in an
+/// index, under the id of the first content
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChunkReference {
+    #[prost(string, tag = "1")]
+    pub index_table: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub hash: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "3")]
+    pub content_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AddChunkReferenceRequest {
+    #[prost(string, tag = "1")]
+    pub index_table: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub hash: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub content_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AddChunkReferenceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub reference: ::core::option::Option<ChunkReference>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RemoveChunkReferenceRequest {
+    #[prost(string, tag = "1")]
+    pub index_table: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub hash: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub content_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RemoveChunkReferenceResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetChunkReferencesRequest {
+    #[prost(string, tag = "1")]
+    pub index_table: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub hashes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetChunkReferencesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub references: ::prost::alloc::vec::Vec<ChunkReference>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IngestionPlugin {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub wasm_url: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "4")]
+    pub max_fuel: u64,
+    #[prost(uint64, tag = "5")]
+    pub max_memory_bytes: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetIngestionPluginRequest {
+    #[prost(message, optional, tag = "1")]
+    pub plugin: ::core::option::Option<IngestionPlugin>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetIngestionPluginResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListIngestionPluginsRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListIngestionPluginsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub plugins: ::prost::alloc::vec::Vec<IngestionPlugin>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteIngestionPluginRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+}
+///   Returns the deleted plugin so its module can be removed from the blob store
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteIngestionPluginResponse {
+    #[prost(message, optional, tag = "1")]
+    pub plugin: ::core::option::Option<IngestionPlugin>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProcessingSla {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_policy: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub extraction_policy_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "4")]
+    pub target_secs: u64,
+    #[prost(double, tag = "5")]
+    pub attainment_threshold: f64,
+    #[prost(uint64, tag = "6")]
+    pub window_secs: u64,
+    #[prost(string, tag = "7")]
+    pub webhook_url: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetProcessingSlaRequest {
+    #[prost(message, optional, tag = "1")]
+    pub sla: ::core::option::Option<ProcessingSla>,
+}
+///   Returns the sla with the id of its extraction policy filled in
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetProcessingSlaResponse {
+    #[prost(message, optional, tag = "1")]
+    pub sla: ::core::option::Option<ProcessingSla>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListProcessingSlasRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListProcessingSlasResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub slas: ::prost::alloc::vec::Vec<ProcessingSla>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteProcessingSlaRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_policy: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteProcessingSlaResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNamespaceStorageRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNamespaceStorageResponse {
+    #[prost(uint64, tag = "1")]
+    pub stored_bytes: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NamespaceQuotas {
+    ///   Limits which aren't set don't apply
+    #[prost(uint64, optional, tag = "1")]
+    pub max_content: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "2")]
+    pub max_stored_bytes: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "3")]
+    pub max_extraction_policies: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetNamespaceQuotasRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub quotas: ::core::option::Option<NamespaceQuotas>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetNamespaceQuotasResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNamespaceQuotasRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+}
+///   The quotas of the namespace along with how much of them is used
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNamespaceQuotasResponse {
+    #[prost(message, optional, tag = "1")]
+    pub quotas: ::core::option::Option<NamespaceQuotas>,
+    #[prost(uint64, tag = "2")]
+    pub content: u64,
+    #[prost(uint64, tag = "3")]
+    pub stored_bytes: u64,
+    #[prost(uint64, tag = "4")]
+    pub extraction_policies: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNamespaceStatsRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+}
+///   Counters of the namespace kept up to date as writes are applied
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNamespaceStatsResponse {
+    #[prost(uint64, tag = "1")]
+    pub content: u64,
+    #[prost(uint64, tag = "2")]
+    pub stored_bytes: u64,
+    #[prost(uint64, tag = "3")]
+    pub tasks_pending: u64,
+    #[prost(uint64, tag = "4")]
+    pub tasks_failed: u64,
+    #[prost(uint64, tag = "5")]
+    pub indexes: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlobMigrationJob {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    ///   Backend the blobs are copied to, s3, gcs or disk
+    #[prost(string, tag = "2")]
+    pub target: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub total_content: u64,
+    #[prost(uint64, tag = "4")]
+    pub migrated_content: u64,
+    #[prost(bool, tag = "5")]
+    pub completed: bool,
+    #[prost(string, tag = "6")]
+    pub error: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "7")]
+    pub created_at: u64,
+    #[prost(uint64, tag = "8")]
+    pub updated_at: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateBlobMigrationJobRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub target: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateBlobMigrationJobResponse {
+    #[prost(message, optional, tag = "1")]
+    pub job: ::core::option::Option<BlobMigrationJob>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetBlobMigrationJobRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateBlobMigrationJobRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    ///   Storage urls of the content copied since the last update, by content id
+    #[prost(map = "string, string", tag = "2")]
+    pub storage_urls: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    #[prost(bool, tag = "3")]
+    pub completed: bool,
+    #[prost(string, tag = "4")]
+    pub error: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClusterMember {
+    #[prost(uint64, tag = "1")]
+    pub node_id: u64,
+    ///   Address of the raft server of the node
+    #[prost(string, tag = "2")]
+    pub address: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub coordinator_addr: ::prost::alloc::string::String,
+    ///   Learners replicate the log without voting
+    #[prost(bool, tag = "4")]
+    pub voter: bool,
+    #[prost(bool, tag = "5")]
+    pub leader: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListClusterMembersRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNodeHealthRequest {}
+/// Health of the coordinator node serving the request, as seen by the node
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetNodeHealthResponse {
+    #[prost(uint64, tag = "1")]
+    pub node_id: u64,
+    /// leader, follower, learner, candidate or shutdown
+    #[prost(string, tag = "2")]
+    pub role: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub applied_index: u64,
+    /// Unix time in milliseconds the node last heard from the leader, 0 if it
+    /// never did. The leader reports the time of the request.
+    #[prost(uint64, tag = "4")]
+    pub last_heartbeat_ms: u64,
+    /// Executors sending their heartbeats to the node
+    #[prost(string, repeated, tag = "5")]
+    pub executors: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListClusterMembersResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub members: ::prost::alloc::vec::Vec<ClusterMember>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AddClusterLearnerRequest {
+    #[prost(uint64, tag = "1")]
+    pub node_id: u64,
+    #[prost(string, tag = "2")]
+    pub address: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub coordinator_addr: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PromoteClusterLearnerRequest {
+    #[prost(uint64, tag = "1")]
+    pub node_id: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RemoveClusterMemberRequest {
+    #[prost(uint64, tag = "1")]
+    pub node_id: u64,
+}
+/// Bounded reads are served from the state of the coordinator, which lags
+/// behind the leader by at most the staleness of the follower reads config.
+/// Linearizable reads reflect every write committed before the read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ReadConsistency {
+    Bounded = 0,
+    Linearizable = 1,
+}
+impl ReadConsistency {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ReadConsistency::Bounded => "BOUNDED",
+            ReadConsistency::Linearizable => "LINEARIZABLE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "BOUNDED" => Some(Self::Bounded),
+            "LINEARIZABLE" => Some(Self::Linearizable),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TaskOutcome {
+    Unknown = 0,
+    Failed = 1,
+    Success = 2,
+}
+impl TaskOutcome {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            TaskOutcome::Unknown => "UNKNOWN",
+            TaskOutcome::Failed => "FAILED",
+            TaskOutcome::Success => "SUCCESS",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UNKNOWN" => Some(Self::Unknown),
+            "FAILED" => Some(Self::Failed),
+            "SUCCESS" => Some(Self::Success),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum BackoffStrategy {
+    Exponential = 0,
+    Fixed = 1,
+}
+impl BackoffStrategy {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            BackoffStrategy::Exponential => "EXPONENTIAL",
+            BackoffStrategy::Fixed => "FIXED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "EXPONENTIAL" => Some(Self::Exponential),
+            "FIXED" => Some(Self::Fixed),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod coordinator_service_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    #[derive(Debug, Clone)]
+    pub struct CoordinatorServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl CoordinatorServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> CoordinatorServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> CoordinatorServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            CoordinatorServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        pub async fn create_content(
+            &mut self,
             request: impl tonic::IntoRequest<super::CreateContentRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateContentResponse>,
+            tonic::Response<super::CreateContentResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CreateContent",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CreateContent",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn tombstone_content(
+            &mut self,
+            request: impl tonic::IntoRequest<super::TombstoneContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::TombstoneContentResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/TombstoneContent",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "TombstoneContent",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_content_metadata(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetContentMetadataRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetContentMetadataResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetContentMetadata",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetContentMetadata",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_content_tree_metadata(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetContentTreeMetadataRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetContentTreeMetadataResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetContentTreeMetadata",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetContentTreeMetadata",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_content(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListContentResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListContent",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListContent",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_extraction_policy(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExtractionPolicyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ExtractionPolicyResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CreateExtractionPolicy",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CreateExtractionPolicy",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_extraction_policy(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateExtractionPolicyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateExtractionPolicyResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/UpdateExtractionPolicy",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "UpdateExtractionPolicy",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn delete_extraction_policy(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteExtractionPolicyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeleteExtractionPolicyResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/DeleteExtractionPolicy",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "DeleteExtractionPolicy",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_extraction_policies(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListExtractionPoliciesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExtractionPoliciesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListExtractionPolicies",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListExtractionPolicies",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_ns(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateNamespaceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CreateNS",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CreateNS",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_ns(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListNamespaceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListNS",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("indexify_coordinator.CoordinatorService", "ListNS"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_ns(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetNamespaceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetNS",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("indexify_coordinator.CoordinatorService", "GetNS"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn delete_ns(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeleteNamespaceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/DeleteNS",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "DeleteNS",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_extractors(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListExtractorsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExtractorsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListExtractors",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListExtractors",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn register_executor(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RegisterExecutorRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RegisterExecutorResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/RegisterExecutor",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "RegisterExecutor",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_executors(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListExecutorsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExecutorsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListExecutors",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListExecutors",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn drain_executor(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DrainExecutorRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DrainExecutorResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/DrainExecutor",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "DrainExecutor",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn register_ingestion_server(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RegisterIngestionServerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RegisterIngestionServerResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/RegisterIngestionServer",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "RegisterIngestionServer",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn remove_ingestion_server(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoveIngestionServerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RemoveIngestionServerResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/RemoveIngestionServer",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "RemoveIngestionServer",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_gc_tasks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateGcTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateGcTasksResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CreateGCTasks",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CreateGCTasks",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn gc_tasks_stream(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<
+                Message = super::GcTaskAcknowledgement,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::CoordinatorCommand>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GCTasksStream",
+            );
+            let mut req = request.into_streaming_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GCTasksStream",
+                    ),
+                );
+            self.inner.streaming(req, path, codec).await
+        }
+        pub async fn heartbeat(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = super::HeartbeatRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::HeartbeatResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/Heartbeat",
+            );
+            let mut req = request.into_streaming_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "Heartbeat",
+                    ),
+                );
+            self.inner.streaming(req, path, codec).await
+        }
+        pub async fn list_indexes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListIndexesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListIndexes",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListIndexes",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetIndexResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetIndex",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateIndexResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CreateIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CreateIndex",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_extractor_coordinates(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetExtractorCoordinatesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetExtractorCoordinatesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetExtractorCoordinates",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetExtractorCoordinates",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_task(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateTaskRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateTaskResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/UpdateTask",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "UpdateTask",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_state_changes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListStateChangesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListStateChangesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListStateChanges",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListStateChanges",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn watch_state_changes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::WatchStateChangesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::StateChangeEvent>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/WatchStateChanges",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "WatchStateChanges",
+                    ),
+                );
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn list_tasks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTasksResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListTasks",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListTasks",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_schema(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetSchemaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSchemaResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetSchema",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetSchema",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_schemas(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetAllSchemaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetAllSchemaResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListSchemas",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListSchemas",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn evolve_structured_data_schema(
+            &mut self,
+            request: impl tonic::IntoRequest<super::EvolveStructuredDataSchemaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::EvolveStructuredDataSchemaResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/EvolveStructuredDataSchema",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "EvolveStructuredDataSchema",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_raft_metrics_snapshot(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetRaftMetricsSnapshotRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RaftMetricsSnapshotResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetRaftMetricsSnapshot",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetRaftMetricsSnapshot",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_all_task_assignments(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetAllTaskAssignmentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::TaskAssignments>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetAllTaskAssignments",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetAllTaskAssignments",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn plan_extraction_policy(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PlanExtractionPolicyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PlanExtractionPolicyResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/PlanExtractionPolicy",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "PlanExtractionPolicy",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn dry_run_extraction_policy(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DryRunExtractionPolicyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DryRunExtractionPolicyResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/DryRunExtractionPolicy",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "DryRunExtractionPolicy",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_index_migration(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateIndexMigrationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::IndexMigrationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CreateIndexMigration",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CreateIndexMigration",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_index_migration(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetIndexMigrationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::IndexMigrationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetIndexMigration",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetIndexMigration",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn complete_index_migration(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CompleteIndexMigrationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::IndexMigrationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CompleteIndexMigration",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CompleteIndexMigration",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_rebalance_job(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateRebalanceJobRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateRebalanceJobResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CreateRebalanceJob",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CreateRebalanceJob",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_rebalance_job(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetRebalanceJobRequest>,
+        ) -> std::result::Result<tonic::Response<super::RebalanceJob>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetRebalanceJob",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetRebalanceJob",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_rebalance_job(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateRebalanceJobRequest>,
+        ) -> std::result::Result<tonic::Response<super::RebalanceJob>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/UpdateRebalanceJob",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "UpdateRebalanceJob",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_index_content(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListIndexContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexContentResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListIndexContent",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListIndexContent",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn set_query_template(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetQueryTemplateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetQueryTemplateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/SetQueryTemplate",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "SetQueryTemplate",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_query_template(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetQueryTemplateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetQueryTemplateResponse>,
             tonic::Status,
         > {
             self.inner
@@ -780,23 +3195,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/CreateContent",
+                "/indexify_coordinator.CoordinatorService/GetQueryTemplate",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "CreateContent",
+                        "GetQueryTemplate",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn tombstone_content(
+        pub async fn list_query_templates(
             &mut self,
-            request: impl tonic::IntoRequest<super::TombstoneContentRequest>,
+            request: impl tonic::IntoRequest<super::ListQueryTemplatesRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::TombstoneContentResponse>,
+            tonic::Response<super::ListQueryTemplatesResponse>,
             tonic::Status,
         > {
             self.inner
@@ -810,23 +3225,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/TombstoneContent",
+                "/indexify_coordinator.CoordinatorService/ListQueryTemplates",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "TombstoneContent",
+                        "ListQueryTemplates",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_content_metadata(
+        pub async fn delete_query_template(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetContentMetadataRequest>,
+            request: impl tonic::IntoRequest<super::DeleteQueryTemplateRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetContentMetadataResponse>,
+            tonic::Response<super::DeleteQueryTemplateResponse>,
             tonic::Status,
         > {
             self.inner
@@ -840,23 +3255,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetContentMetadata",
+                "/indexify_coordinator.CoordinatorService/DeleteQueryTemplate",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetContentMetadata",
+                        "DeleteQueryTemplate",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_content_tree_metadata(
+        pub async fn create_ingest_intent(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetContentTreeMetadataRequest>,
+            request: impl tonic::IntoRequest<super::CreateIngestIntentRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetContentTreeMetadataResponse>,
+            tonic::Response<super::CreateIngestIntentResponse>,
             tonic::Status,
         > {
             self.inner
@@ -870,23 +3285,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetContentTreeMetadata",
+                "/indexify_coordinator.CoordinatorService/CreateIngestIntent",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetContentTreeMetadata",
+                        "CreateIngestIntent",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_content(
+        pub async fn list_ingest_intents(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListContentRequest>,
+            request: impl tonic::IntoRequest<super::ListIngestIntentsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListContentResponse>,
+            tonic::Response<super::ListIngestIntentsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -900,23 +3315,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListContent",
+                "/indexify_coordinator.CoordinatorService/ListIngestIntents",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListContent",
+                        "ListIngestIntents",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn create_extraction_policy(
+        pub async fn delete_ingest_intents(
             &mut self,
-            request: impl tonic::IntoRequest<super::ExtractionPolicyRequest>,
+            request: impl tonic::IntoRequest<super::DeleteIngestIntentsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ExtractionPolicyResponse>,
+            tonic::Response<super::DeleteIngestIntentsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -930,23 +3345,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/CreateExtractionPolicy",
+                "/indexify_coordinator.CoordinatorService/DeleteIngestIntents",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "CreateExtractionPolicy",
+                        "DeleteIngestIntents",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_extraction_policies(
+        pub async fn create_embedding_expirations(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListExtractionPoliciesRequest>,
+            request: impl tonic::IntoRequest<super::CreateEmbeddingExpirationsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListExtractionPoliciesResponse>,
+            tonic::Response<super::CreateEmbeddingExpirationsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -960,23 +3375,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListExtractionPolicies",
+                "/indexify_coordinator.CoordinatorService/CreateEmbeddingExpirations",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListExtractionPolicies",
+                        "CreateEmbeddingExpirations",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn create_ns(
+        pub async fn list_embedding_expirations(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateNamespaceRequest>,
+            request: impl tonic::IntoRequest<super::ListEmbeddingExpirationsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateNamespaceResponse>,
+            tonic::Response<super::ListEmbeddingExpirationsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -990,23 +3405,263 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/CreateNS",
+                "/indexify_coordinator.CoordinatorService/ListEmbeddingExpirations",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "CreateNS",
+                        "ListEmbeddingExpirations",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_ns(
+        pub async fn delete_embedding_expirations(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListNamespaceRequest>,
+            request: impl tonic::IntoRequest<super::DeleteEmbeddingExpirationsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListNamespaceResponse>,
+            tonic::Response<super::DeleteEmbeddingExpirationsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/DeleteEmbeddingExpirations",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "DeleteEmbeddingExpirations",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn add_chunk_reference(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AddChunkReferenceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AddChunkReferenceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/AddChunkReference",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "AddChunkReference",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn remove_chunk_reference(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoveChunkReferenceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RemoveChunkReferenceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/RemoveChunkReference",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "RemoveChunkReference",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_chunk_references(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetChunkReferencesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetChunkReferencesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetChunkReferences",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetChunkReferences",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn set_ingestion_plugin(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetIngestionPluginRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetIngestionPluginResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/SetIngestionPlugin",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "SetIngestionPlugin",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_ingestion_plugins(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListIngestionPluginsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIngestionPluginsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListIngestionPlugins",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListIngestionPlugins",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn delete_ingestion_plugin(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteIngestionPluginRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeleteIngestionPluginResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/DeleteIngestionPlugin",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "DeleteIngestionPlugin",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn set_processing_sla(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetProcessingSlaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetProcessingSlaResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/SetProcessingSla",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "SetProcessingSla",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_processing_slas(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListProcessingSlasRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListProcessingSlasResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1020,20 +3675,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListNS",
+                "/indexify_coordinator.CoordinatorService/ListProcessingSlas",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("indexify_coordinator.CoordinatorService", "ListNS"),
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListProcessingSlas",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_ns(
+        pub async fn delete_processing_sla(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetNamespaceRequest>,
+            request: impl tonic::IntoRequest<super::DeleteProcessingSlaRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetNamespaceResponse>,
+            tonic::Response<super::DeleteProcessingSlaResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1047,20 +3705,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetNS",
+                "/indexify_coordinator.CoordinatorService/DeleteProcessingSla",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("indexify_coordinator.CoordinatorService", "GetNS"),
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "DeleteProcessingSla",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_extractors(
+        pub async fn get_namespace_storage(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListExtractorsRequest>,
+            request: impl tonic::IntoRequest<super::GetNamespaceStorageRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListExtractorsResponse>,
+            tonic::Response<super::GetNamespaceStorageResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1074,23 +3735,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListExtractors",
+                "/indexify_coordinator.CoordinatorService/GetNamespaceStorage",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListExtractors",
+                        "GetNamespaceStorage",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn register_executor(
+        pub async fn set_namespace_quotas(
             &mut self,
-            request: impl tonic::IntoRequest<super::RegisterExecutorRequest>,
+            request: impl tonic::IntoRequest<super::SetNamespaceQuotasRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RegisterExecutorResponse>,
+            tonic::Response<super::SetNamespaceQuotasResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1104,23 +3765,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/RegisterExecutor",
+                "/indexify_coordinator.CoordinatorService/SetNamespaceQuotas",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "RegisterExecutor",
+                        "SetNamespaceQuotas",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn register_ingestion_server(
+        pub async fn get_namespace_quotas(
             &mut self,
-            request: impl tonic::IntoRequest<super::RegisterIngestionServerRequest>,
+            request: impl tonic::IntoRequest<super::GetNamespaceQuotasRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RegisterIngestionServerResponse>,
+            tonic::Response<super::GetNamespaceQuotasResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1134,23 +3795,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/RegisterIngestionServer",
+                "/indexify_coordinator.CoordinatorService/GetNamespaceQuotas",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "RegisterIngestionServer",
+                        "GetNamespaceQuotas",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn remove_ingestion_server(
+        pub async fn get_namespace_stats(
             &mut self,
-            request: impl tonic::IntoRequest<super::RemoveIngestionServerRequest>,
+            request: impl tonic::IntoRequest<super::GetNamespaceStatsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RemoveIngestionServerResponse>,
+            tonic::Response<super::GetNamespaceStatsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1164,23 +3825,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/RemoveIngestionServer",
+                "/indexify_coordinator.CoordinatorService/GetNamespaceStats",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "RemoveIngestionServer",
+                        "GetNamespaceStats",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn create_gc_tasks(
+        pub async fn create_blob_migration_job(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateGcTasksRequest>,
+            request: impl tonic::IntoRequest<super::CreateBlobMigrationJobRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateGcTasksResponse>,
+            tonic::Response<super::CreateBlobMigrationJobResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1194,25 +3855,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/CreateGCTasks",
+                "/indexify_coordinator.CoordinatorService/CreateBlobMigrationJob",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "CreateGCTasks",
+                        "CreateBlobMigrationJob",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn gc_tasks_stream(
+        pub async fn get_blob_migration_job(
             &mut self,
-            request: impl tonic::IntoStreamingRequest<
-                Message = super::GcTaskAcknowledgement,
-            >,
+            request: impl tonic::IntoRequest<super::GetBlobMigrationJobRequest>,
         ) -> std::result::Result<
-            tonic::Response<tonic::codec::Streaming<super::CoordinatorCommand>>,
+            tonic::Response<super::BlobMigrationJob>,
             tonic::Status,
         > {
             self.inner
@@ -1226,23 +3885,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GCTasksStream",
+                "/indexify_coordinator.CoordinatorService/GetBlobMigrationJob",
             );
-            let mut req = request.into_streaming_request();
+            let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GCTasksStream",
+                        "GetBlobMigrationJob",
                     ),
                 );
-            self.inner.streaming(req, path, codec).await
+            self.inner.unary(req, path, codec).await
         }
-        pub async fn heartbeat(
+        pub async fn update_blob_migration_job(
             &mut self,
-            request: impl tonic::IntoStreamingRequest<Message = super::HeartbeatRequest>,
+            request: impl tonic::IntoRequest<super::UpdateBlobMigrationJobRequest>,
         ) -> std::result::Result<
-            tonic::Response<tonic::codec::Streaming<super::HeartbeatResponse>>,
+            tonic::Response<super::BlobMigrationJob>,
             tonic::Status,
         > {
             self.inner
@@ -1256,23 +3915,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/Heartbeat",
+                "/indexify_coordinator.CoordinatorService/UpdateBlobMigrationJob",
             );
-            let mut req = request.into_streaming_request();
+            let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "Heartbeat",
+                        "UpdateBlobMigrationJob",
                     ),
                 );
-            self.inner.streaming(req, path, codec).await
+            self.inner.unary(req, path, codec).await
         }
-        pub async fn list_indexes(
+        pub async fn list_cluster_members(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListIndexesRequest>,
+            request: impl tonic::IntoRequest<super::ListClusterMembersRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListIndexesResponse>,
+            tonic::Response<super::ListClusterMembersResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1286,23 +3945,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListIndexes",
+                "/indexify_coordinator.CoordinatorService/ListClusterMembers",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListIndexes",
+                        "ListClusterMembers",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_index(
+        pub async fn add_cluster_learner(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetIndexRequest>,
+            request: impl tonic::IntoRequest<super::AddClusterLearnerRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetIndexResponse>,
+            tonic::Response<super::ListClusterMembersResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1316,23 +3975,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetIndex",
+                "/indexify_coordinator.CoordinatorService/AddClusterLearner",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetIndex",
+                        "AddClusterLearner",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn create_index(
+        pub async fn promote_cluster_learner(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateIndexRequest>,
+            request: impl tonic::IntoRequest<super::PromoteClusterLearnerRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateIndexResponse>,
+            tonic::Response<super::ListClusterMembersResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1346,23 +4005,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/CreateIndex",
+                "/indexify_coordinator.CoordinatorService/PromoteClusterLearner",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "CreateIndex",
+                        "PromoteClusterLearner",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_extractor_coordinates(
+        pub async fn remove_cluster_member(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetExtractorCoordinatesRequest>,
+            request: impl tonic::IntoRequest<super::RemoveClusterMemberRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetExtractorCoordinatesResponse>,
+            tonic::Response<super::ListClusterMembersResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1376,23 +4035,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetExtractorCoordinates",
+                "/indexify_coordinator.CoordinatorService/RemoveClusterMember",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetExtractorCoordinates",
+                        "RemoveClusterMember",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn update_task(
+        pub async fn get_node_health(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateTaskRequest>,
+            request: impl tonic::IntoRequest<super::GetNodeHealthRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::UpdateTaskResponse>,
+            tonic::Response<super::GetNodeHealthResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1406,409 +4065,558 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/UpdateTask",
+                "/indexify_coordinator.CoordinatorService/GetNodeHealth",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "UpdateTask",
+                        "GetNodeHealth",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_state_changes(
-            &mut self,
-            request: impl tonic::IntoRequest<super::ListStateChangesRequest>,
+    }
+}
+/// Generated server implementations.
+pub mod coordinator_service_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with CoordinatorServiceServer.
+    #[async_trait]
+    pub trait CoordinatorService: Send + Sync + 'static {
+        async fn create_content(
+            &self,
+            request: tonic::Request<super::CreateContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateContentResponse>,
+            tonic::Status,
+        >;
+        async fn tombstone_content(
+            &self,
+            request: tonic::Request<super::TombstoneContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::TombstoneContentResponse>,
+            tonic::Status,
+        >;
+        async fn get_content_metadata(
+            &self,
+            request: tonic::Request<super::GetContentMetadataRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetContentMetadataResponse>,
+            tonic::Status,
+        >;
+        async fn get_content_tree_metadata(
+            &self,
+            request: tonic::Request<super::GetContentTreeMetadataRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetContentTreeMetadataResponse>,
+            tonic::Status,
+        >;
+        async fn list_content(
+            &self,
+            request: tonic::Request<super::ListContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListContentResponse>,
+            tonic::Status,
+        >;
+        async fn create_extraction_policy(
+            &self,
+            request: tonic::Request<super::ExtractionPolicyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ExtractionPolicyResponse>,
+            tonic::Status,
+        >;
+        async fn update_extraction_policy(
+            &self,
+            request: tonic::Request<super::UpdateExtractionPolicyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateExtractionPolicyResponse>,
+            tonic::Status,
+        >;
+        async fn delete_extraction_policy(
+            &self,
+            request: tonic::Request<super::DeleteExtractionPolicyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeleteExtractionPolicyResponse>,
+            tonic::Status,
+        >;
+        async fn list_extraction_policies(
+            &self,
+            request: tonic::Request<super::ListExtractionPoliciesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExtractionPoliciesResponse>,
+            tonic::Status,
+        >;
+        async fn create_ns(
+            &self,
+            request: tonic::Request<super::CreateNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateNamespaceResponse>,
+            tonic::Status,
+        >;
+        async fn list_ns(
+            &self,
+            request: tonic::Request<super::ListNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListNamespaceResponse>,
+            tonic::Status,
+        >;
+        async fn get_ns(
+            &self,
+            request: tonic::Request<super::GetNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetNamespaceResponse>,
+            tonic::Status,
+        >;
+        async fn delete_ns(
+            &self,
+            request: tonic::Request<super::DeleteNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeleteNamespaceResponse>,
+            tonic::Status,
+        >;
+        async fn list_extractors(
+            &self,
+            request: tonic::Request<super::ListExtractorsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExtractorsResponse>,
+            tonic::Status,
+        >;
+        async fn register_executor(
+            &self,
+            request: tonic::Request<super::RegisterExecutorRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RegisterExecutorResponse>,
+            tonic::Status,
+        >;
+        async fn list_executors(
+            &self,
+            request: tonic::Request<super::ListExecutorsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExecutorsResponse>,
+            tonic::Status,
+        >;
+        async fn drain_executor(
+            &self,
+            request: tonic::Request<super::DrainExecutorRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DrainExecutorResponse>,
+            tonic::Status,
+        >;
+        async fn register_ingestion_server(
+            &self,
+            request: tonic::Request<super::RegisterIngestionServerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RegisterIngestionServerResponse>,
+            tonic::Status,
+        >;
+        async fn remove_ingestion_server(
+            &self,
+            request: tonic::Request<super::RemoveIngestionServerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RemoveIngestionServerResponse>,
+            tonic::Status,
+        >;
+        async fn create_gc_tasks(
+            &self,
+            request: tonic::Request<super::CreateGcTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateGcTasksResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the GCTasksStream method.
+        type GCTasksStreamStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::CoordinatorCommand, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        async fn gc_tasks_stream(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::GcTaskAcknowledgement>>,
+        ) -> std::result::Result<
+            tonic::Response<Self::GCTasksStreamStream>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the Heartbeat method.
+        type HeartbeatStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::HeartbeatResponse, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        async fn heartbeat(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::HeartbeatRequest>>,
+        ) -> std::result::Result<tonic::Response<Self::HeartbeatStream>, tonic::Status>;
+        async fn list_indexes(
+            &self,
+            request: tonic::Request<super::ListIndexesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexesResponse>,
+            tonic::Status,
+        >;
+        async fn get_index(
+            &self,
+            request: tonic::Request<super::GetIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetIndexResponse>,
+            tonic::Status,
+        >;
+        async fn create_index(
+            &self,
+            request: tonic::Request<super::CreateIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateIndexResponse>,
+            tonic::Status,
+        >;
+        async fn get_extractor_coordinates(
+            &self,
+            request: tonic::Request<super::GetExtractorCoordinatesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetExtractorCoordinatesResponse>,
+            tonic::Status,
+        >;
+        async fn update_task(
+            &self,
+            request: tonic::Request<super::UpdateTaskRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateTaskResponse>,
+            tonic::Status,
+        >;
+        async fn list_state_changes(
+            &self,
+            request: tonic::Request<super::ListStateChangesRequest>,
         ) -> std::result::Result<
             tonic::Response<super::ListStateChangesResponse>,
             tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::new(
-                        tonic::Code::Unknown,
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListStateChanges",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(
-                    GrpcMethod::new(
-                        "indexify_coordinator.CoordinatorService",
-                        "ListStateChanges",
-                    ),
-                );
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn list_tasks(
-            &mut self,
-            request: impl tonic::IntoRequest<super::ListTasksRequest>,
+        >;
+        /// Server streaming response type for the WatchStateChanges method.
+        type WatchStateChangesStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::StateChangeEvent, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        async fn watch_state_changes(
+            &self,
+            request: tonic::Request<super::WatchStateChangesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::WatchStateChangesStream>,
+            tonic::Status,
+        >;
+        async fn list_tasks(
+            &self,
+            request: tonic::Request<super::ListTasksRequest>,
         ) -> std::result::Result<
             tonic::Response<super::ListTasksResponse>,
             tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::new(
-                        tonic::Code::Unknown,
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListTasks",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(
-                    GrpcMethod::new(
-                        "indexify_coordinator.CoordinatorService",
-                        "ListTasks",
-                    ),
-                );
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn get_schema(
-            &mut self,
-            request: impl tonic::IntoRequest<super::GetSchemaRequest>,
+        >;
+        async fn get_schema(
+            &self,
+            request: tonic::Request<super::GetSchemaRequest>,
         ) -> std::result::Result<
             tonic::Response<super::GetSchemaResponse>,
             tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::new(
-                        tonic::Code::Unknown,
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetSchema",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(
-                    GrpcMethod::new(
-                        "indexify_coordinator.CoordinatorService",
-                        "GetSchema",
-                    ),
-                );
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn list_schemas(
-            &mut self,
-            request: impl tonic::IntoRequest<super::GetAllSchemaRequest>,
+        >;
+        async fn list_schemas(
+            &self,
+            request: tonic::Request<super::GetAllSchemaRequest>,
         ) -> std::result::Result<
             tonic::Response<super::GetAllSchemaResponse>,
             tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::new(
-                        tonic::Code::Unknown,
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListSchemas",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(
-                    GrpcMethod::new(
-                        "indexify_coordinator.CoordinatorService",
-                        "ListSchemas",
-                    ),
-                );
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn get_raft_metrics_snapshot(
-            &mut self,
-            request: impl tonic::IntoRequest<super::GetRaftMetricsSnapshotRequest>,
+        >;
+        async fn evolve_structured_data_schema(
+            &self,
+            request: tonic::Request<super::EvolveStructuredDataSchemaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::EvolveStructuredDataSchemaResponse>,
+            tonic::Status,
+        >;
+        async fn get_raft_metrics_snapshot(
+            &self,
+            request: tonic::Request<super::GetRaftMetricsSnapshotRequest>,
         ) -> std::result::Result<
             tonic::Response<super::RaftMetricsSnapshotResponse>,
             tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::new(
-                        tonic::Code::Unknown,
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetRaftMetricsSnapshot",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(
-                    GrpcMethod::new(
-                        "indexify_coordinator.CoordinatorService",
-                        "GetRaftMetricsSnapshot",
-                    ),
-                );
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn get_all_task_assignments(
-            &mut self,
-            request: impl tonic::IntoRequest<super::GetAllTaskAssignmentRequest>,
+        >;
+        async fn get_all_task_assignments(
+            &self,
+            request: tonic::Request<super::GetAllTaskAssignmentRequest>,
+        ) -> std::result::Result<tonic::Response<super::TaskAssignments>, tonic::Status>;
+        async fn plan_extraction_policy(
+            &self,
+            request: tonic::Request<super::PlanExtractionPolicyRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::TaskAssignments>,
+            tonic::Response<super::PlanExtractionPolicyResponse>,
             tonic::Status,
-        > {
-            self.inner
-                .ready()
-                .await
-                .map_err(|e| {
-                    tonic::Status::new(
-                        tonic::Code::Unknown,
-                        format!("Service was not ready: {}", e.into()),
-                    )
-                })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetAllTaskAssignments",
-            );
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(
-                    GrpcMethod::new(
-                        "indexify_coordinator.CoordinatorService",
-                        "GetAllTaskAssignments",
-                    ),
-                );
-            self.inner.unary(req, path, codec).await
-        }
-    }
-}
-/// Generated server implementations.
-pub mod coordinator_service_server {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with CoordinatorServiceServer.
-    #[async_trait]
-    pub trait CoordinatorService: Send + Sync + 'static {
-        async fn create_content(
+        >;
+        async fn dry_run_extraction_policy(
+            &self,
+            request: tonic::Request<super::DryRunExtractionPolicyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DryRunExtractionPolicyResponse>,
+            tonic::Status,
+        >;
+        async fn create_index_migration(
+            &self,
+            request: tonic::Request<super::CreateIndexMigrationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::IndexMigrationResponse>,
+            tonic::Status,
+        >;
+        async fn get_index_migration(
+            &self,
+            request: tonic::Request<super::GetIndexMigrationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::IndexMigrationResponse>,
+            tonic::Status,
+        >;
+        async fn complete_index_migration(
+            &self,
+            request: tonic::Request<super::CompleteIndexMigrationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::IndexMigrationResponse>,
+            tonic::Status,
+        >;
+        async fn create_rebalance_job(
+            &self,
+            request: tonic::Request<super::CreateRebalanceJobRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateRebalanceJobResponse>,
+            tonic::Status,
+        >;
+        async fn get_rebalance_job(
+            &self,
+            request: tonic::Request<super::GetRebalanceJobRequest>,
+        ) -> std::result::Result<tonic::Response<super::RebalanceJob>, tonic::Status>;
+        async fn update_rebalance_job(
+            &self,
+            request: tonic::Request<super::UpdateRebalanceJobRequest>,
+        ) -> std::result::Result<tonic::Response<super::RebalanceJob>, tonic::Status>;
+        async fn list_index_content(
+            &self,
+            request: tonic::Request<super::ListIndexContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexContentResponse>,
+            tonic::Status,
+        >;
+        async fn set_query_template(
+            &self,
+            request: tonic::Request<super::SetQueryTemplateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetQueryTemplateResponse>,
+            tonic::Status,
+        >;
+        async fn get_query_template(
             &self,
-            request: tonic::Request<super::CreateContentRequest>,
+            request: tonic::Request<super::GetQueryTemplateRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateContentResponse>,
+            tonic::Response<super::GetQueryTemplateResponse>,
             tonic::Status,
         >;
-        async fn tombstone_content(
+        async fn list_query_templates(
             &self,
-            request: tonic::Request<super::TombstoneContentRequest>,
+            request: tonic::Request<super::ListQueryTemplatesRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::TombstoneContentResponse>,
+            tonic::Response<super::ListQueryTemplatesResponse>,
             tonic::Status,
         >;
-        async fn get_content_metadata(
+        async fn delete_query_template(
             &self,
-            request: tonic::Request<super::GetContentMetadataRequest>,
+            request: tonic::Request<super::DeleteQueryTemplateRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetContentMetadataResponse>,
+            tonic::Response<super::DeleteQueryTemplateResponse>,
             tonic::Status,
         >;
-        async fn get_content_tree_metadata(
+        async fn create_ingest_intent(
             &self,
-            request: tonic::Request<super::GetContentTreeMetadataRequest>,
+            request: tonic::Request<super::CreateIngestIntentRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetContentTreeMetadataResponse>,
+            tonic::Response<super::CreateIngestIntentResponse>,
             tonic::Status,
         >;
-        async fn list_content(
+        async fn list_ingest_intents(
             &self,
-            request: tonic::Request<super::ListContentRequest>,
+            request: tonic::Request<super::ListIngestIntentsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListContentResponse>,
+            tonic::Response<super::ListIngestIntentsResponse>,
             tonic::Status,
         >;
-        async fn create_extraction_policy(
+        async fn delete_ingest_intents(
             &self,
-            request: tonic::Request<super::ExtractionPolicyRequest>,
+            request: tonic::Request<super::DeleteIngestIntentsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ExtractionPolicyResponse>,
+            tonic::Response<super::DeleteIngestIntentsResponse>,
             tonic::Status,
         >;
-        async fn list_extraction_policies(
+        async fn create_embedding_expirations(
             &self,
-            request: tonic::Request<super::ListExtractionPoliciesRequest>,
+            request: tonic::Request<super::CreateEmbeddingExpirationsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListExtractionPoliciesResponse>,
+            tonic::Response<super::CreateEmbeddingExpirationsResponse>,
             tonic::Status,
         >;
-        async fn create_ns(
+        async fn list_embedding_expirations(
             &self,
-            request: tonic::Request<super::CreateNamespaceRequest>,
+            request: tonic::Request<super::ListEmbeddingExpirationsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateNamespaceResponse>,
+            tonic::Response<super::ListEmbeddingExpirationsResponse>,
             tonic::Status,
         >;
-        async fn list_ns(
+        async fn delete_embedding_expirations(
             &self,
-            request: tonic::Request<super::ListNamespaceRequest>,
+            request: tonic::Request<super::DeleteEmbeddingExpirationsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListNamespaceResponse>,
+            tonic::Response<super::DeleteEmbeddingExpirationsResponse>,
             tonic::Status,
         >;
-        async fn get_ns(
+        async fn add_chunk_reference(
             &self,
-            request: tonic::Request<super::GetNamespaceRequest>,
+            request: tonic::Request<super::AddChunkReferenceRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetNamespaceResponse>,
+            tonic::Response<super::AddChunkReferenceResponse>,
             tonic::Status,
         >;
-        async fn list_extractors(
+        async fn remove_chunk_reference(
             &self,
-            request: tonic::Request<super::ListExtractorsRequest>,
+            request: tonic::Request<super::RemoveChunkReferenceRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListExtractorsResponse>,
+            tonic::Response<super::RemoveChunkReferenceResponse>,
             tonic::Status,
         >;
-        async fn register_executor(
+        async fn get_chunk_references(
             &self,
-            request: tonic::Request<super::RegisterExecutorRequest>,
+            request: tonic::Request<super::GetChunkReferencesRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RegisterExecutorResponse>,
+            tonic::Response<super::GetChunkReferencesResponse>,
             tonic::Status,
         >;
-        async fn register_ingestion_server(
+        async fn set_ingestion_plugin(
             &self,
-            request: tonic::Request<super::RegisterIngestionServerRequest>,
+            request: tonic::Request<super::SetIngestionPluginRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RegisterIngestionServerResponse>,
+            tonic::Response<super::SetIngestionPluginResponse>,
             tonic::Status,
         >;
-        async fn remove_ingestion_server(
+        async fn list_ingestion_plugins(
             &self,
-            request: tonic::Request<super::RemoveIngestionServerRequest>,
+            request: tonic::Request<super::ListIngestionPluginsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RemoveIngestionServerResponse>,
+            tonic::Response<super::ListIngestionPluginsResponse>,
             tonic::Status,
         >;
-        async fn create_gc_tasks(
+        async fn delete_ingestion_plugin(
             &self,
-            request: tonic::Request<super::CreateGcTasksRequest>,
+            request: tonic::Request<super::DeleteIngestionPluginRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateGcTasksResponse>,
+            tonic::Response<super::DeleteIngestionPluginResponse>,
             tonic::Status,
         >;
-        /// Server streaming response type for the GCTasksStream method.
-        type GCTasksStreamStream: tonic::codegen::tokio_stream::Stream<
-                Item = std::result::Result<super::CoordinatorCommand, tonic::Status>,
-            >
-            + Send
-            + 'static;
-        async fn gc_tasks_stream(
+        async fn set_processing_sla(
             &self,
-            request: tonic::Request<tonic::Streaming<super::GcTaskAcknowledgement>>,
+            request: tonic::Request<super::SetProcessingSlaRequest>,
         ) -> std::result::Result<
-            tonic::Response<Self::GCTasksStreamStream>,
+            tonic::Response<super::SetProcessingSlaResponse>,
             tonic::Status,
         >;
-        /// Server streaming response type for the Heartbeat method.
-        type HeartbeatStream: tonic::codegen::tokio_stream::Stream<
-                Item = std::result::Result<super::HeartbeatResponse, tonic::Status>,
-            >
-            + Send
-            + 'static;
-        async fn heartbeat(
+        async fn list_processing_slas(
             &self,
-            request: tonic::Request<tonic::Streaming<super::HeartbeatRequest>>,
-        ) -> std::result::Result<tonic::Response<Self::HeartbeatStream>, tonic::Status>;
-        async fn list_indexes(
+            request: tonic::Request<super::ListProcessingSlasRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListProcessingSlasResponse>,
+            tonic::Status,
+        >;
+        async fn delete_processing_sla(
             &self,
-            request: tonic::Request<super::ListIndexesRequest>,
+            request: tonic::Request<super::DeleteProcessingSlaRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListIndexesResponse>,
+            tonic::Response<super::DeleteProcessingSlaResponse>,
             tonic::Status,
         >;
-        async fn get_index(
+        async fn get_namespace_storage(
             &self,
-            request: tonic::Request<super::GetIndexRequest>,
+            request: tonic::Request<super::GetNamespaceStorageRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetIndexResponse>,
+            tonic::Response<super::GetNamespaceStorageResponse>,
             tonic::Status,
         >;
-        async fn create_index(
+        async fn set_namespace_quotas(
             &self,
-            request: tonic::Request<super::CreateIndexRequest>,
+            request: tonic::Request<super::SetNamespaceQuotasRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateIndexResponse>,
+            tonic::Response<super::SetNamespaceQuotasResponse>,
             tonic::Status,
         >;
-        async fn get_extractor_coordinates(
+        async fn get_namespace_quotas(
             &self,
-            request: tonic::Request<super::GetExtractorCoordinatesRequest>,
+            request: tonic::Request<super::GetNamespaceQuotasRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetExtractorCoordinatesResponse>,
+            tonic::Response<super::GetNamespaceQuotasResponse>,
             tonic::Status,
         >;
-        async fn update_task(
+        async fn get_namespace_stats(
             &self,
-            request: tonic::Request<super::UpdateTaskRequest>,
+            request: tonic::Request<super::GetNamespaceStatsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::UpdateTaskResponse>,
+            tonic::Response<super::GetNamespaceStatsResponse>,
             tonic::Status,
         >;
-        async fn list_state_changes(
+        async fn create_blob_migration_job(
             &self,
-            request: tonic::Request<super::ListStateChangesRequest>,
+            request: tonic::Request<super::CreateBlobMigrationJobRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListStateChangesResponse>,
+            tonic::Response<super::CreateBlobMigrationJobResponse>,
             tonic::Status,
         >;
-        async fn list_tasks(
+        async fn get_blob_migration_job(
             &self,
-            request: tonic::Request<super::ListTasksRequest>,
+            request: tonic::Request<super::GetBlobMigrationJobRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListTasksResponse>,
+            tonic::Response<super::BlobMigrationJob>,
             tonic::Status,
         >;
-        async fn get_schema(
+        async fn update_blob_migration_job(
             &self,
-            request: tonic::Request<super::GetSchemaRequest>,
+            request: tonic::Request<super::UpdateBlobMigrationJobRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetSchemaResponse>,
+            tonic::Response<super::BlobMigrationJob>,
             tonic::Status,
         >;
-        async fn list_schemas(
+        async fn list_cluster_members(
             &self,
-            request: tonic::Request<super::GetAllSchemaRequest>,
+            request: tonic::Request<super::ListClusterMembersRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetAllSchemaResponse>,
+            tonic::Response<super::ListClusterMembersResponse>,
             tonic::Status,
         >;
-        async fn get_raft_metrics_snapshot(
+        async fn add_cluster_learner(
             &self,
-            request: tonic::Request<super::GetRaftMetricsSnapshotRequest>,
+            request: tonic::Request<super::AddClusterLearnerRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RaftMetricsSnapshotResponse>,
+            tonic::Response<super::ListClusterMembersResponse>,
             tonic::Status,
         >;
-        async fn get_all_task_assignments(
+        async fn promote_cluster_learner(
             &self,
-            request: tonic::Request<super::GetAllTaskAssignmentRequest>,
-        ) -> std::result::Result<tonic::Response<super::TaskAssignments>, tonic::Status>;
+            request: tonic::Request<super::PromoteClusterLearnerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListClusterMembersResponse>,
+            tonic::Status,
+        >;
+        async fn remove_cluster_member(
+            &self,
+            request: tonic::Request<super::RemoveClusterMemberRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListClusterMembersResponse>,
+            tonic::Status,
+        >;
+        async fn get_node_health(
+            &self,
+            request: tonic::Request<super::GetNodeHealthRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetNodeHealthResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct CoordinatorServiceServer<T: CoordinatorService> {
@@ -1891,23 +4699,2124 @@ pub mod coordinator_service_server {
             match req.uri().path() {
                 "/indexify_coordinator.CoordinatorService/CreateContent" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct CreateContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateContentRequest>
+                    for CreateContentSvc<T> {
+                        type Response = super::CreateContentResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateContentRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_content(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateContentSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/TombstoneContent" => {
+                    #[allow(non_camel_case_types)]
+                    struct TombstoneContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::TombstoneContentRequest>
+                    for TombstoneContentSvc<T> {
+                        type Response = super::TombstoneContentResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::TombstoneContentRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::tombstone_content(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = TombstoneContentSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetContentMetadata" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetContentMetadataSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetContentMetadataRequest>
+                    for GetContentMetadataSvc<T> {
+                        type Response = super::GetContentMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetContentMetadataRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_content_metadata(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetContentMetadataSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetContentTreeMetadata" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetContentTreeMetadataSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetContentTreeMetadataRequest>
+                    for GetContentTreeMetadataSvc<T> {
+                        type Response = super::GetContentTreeMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetContentTreeMetadataRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_content_tree_metadata(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetContentTreeMetadataSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListContent" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListContentRequest>
+                    for ListContentSvc<T> {
+                        type Response = super::ListContentResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListContentRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_content(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListContentSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CreateExtractionPolicy" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateExtractionPolicySvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ExtractionPolicyRequest>
+                    for CreateExtractionPolicySvc<T> {
+                        type Response = super::ExtractionPolicyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExtractionPolicyRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_extraction_policy(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateExtractionPolicySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/UpdateExtractionPolicy" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateExtractionPolicySvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::UpdateExtractionPolicyRequest>
+                    for UpdateExtractionPolicySvc<T> {
+                        type Response = super::UpdateExtractionPolicyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateExtractionPolicyRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::update_extraction_policy(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateExtractionPolicySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/DeleteExtractionPolicy" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteExtractionPolicySvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::DeleteExtractionPolicyRequest>
+                    for DeleteExtractionPolicySvc<T> {
+                        type Response = super::DeleteExtractionPolicyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteExtractionPolicyRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::delete_extraction_policy(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteExtractionPolicySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListExtractionPolicies" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListExtractionPoliciesSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListExtractionPoliciesRequest>
+                    for ListExtractionPoliciesSvc<T> {
+                        type Response = super::ListExtractionPoliciesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListExtractionPoliciesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_extraction_policies(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListExtractionPoliciesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CreateNS" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateNamespaceRequest>
+                    for CreateNSSvc<T> {
+                        type Response = super::CreateNamespaceResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateNamespaceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_ns(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateNSSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListNS" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListNamespaceRequest>
+                    for ListNSSvc<T> {
+                        type Response = super::ListNamespaceResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListNamespaceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_ns(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListNSSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetNS" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetNamespaceRequest>
+                    for GetNSSvc<T> {
+                        type Response = super::GetNamespaceResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetNamespaceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_ns(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetNSSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/DeleteNS" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::DeleteNamespaceRequest>
+                    for DeleteNSSvc<T> {
+                        type Response = super::DeleteNamespaceResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteNamespaceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::delete_ns(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteNSSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListExtractors" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListExtractorsSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListExtractorsRequest>
+                    for ListExtractorsSvc<T> {
+                        type Response = super::ListExtractorsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListExtractorsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_extractors(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListExtractorsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/RegisterExecutor" => {
+                    #[allow(non_camel_case_types)]
+                    struct RegisterExecutorSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::RegisterExecutorRequest>
+                    for RegisterExecutorSvc<T> {
+                        type Response = super::RegisterExecutorResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RegisterExecutorRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::register_executor(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RegisterExecutorSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListExecutors" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListExecutorsSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListExecutorsRequest>
+                    for ListExecutorsSvc<T> {
+                        type Response = super::ListExecutorsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListExecutorsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_executors(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListExecutorsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/DrainExecutor" => {
+                    #[allow(non_camel_case_types)]
+                    struct DrainExecutorSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::DrainExecutorRequest>
+                    for DrainExecutorSvc<T> {
+                        type Response = super::DrainExecutorResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DrainExecutorRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::drain_executor(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DrainExecutorSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/RegisterIngestionServer" => {
+                    #[allow(non_camel_case_types)]
+                    struct RegisterIngestionServerSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::RegisterIngestionServerRequest>
+                    for RegisterIngestionServerSvc<T> {
+                        type Response = super::RegisterIngestionServerResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::RegisterIngestionServerRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::register_ingestion_server(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RegisterIngestionServerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/RemoveIngestionServer" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoveIngestionServerSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::RemoveIngestionServerRequest>
+                    for RemoveIngestionServerSvc<T> {
+                        type Response = super::RemoveIngestionServerResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RemoveIngestionServerRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::remove_ingestion_server(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RemoveIngestionServerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CreateGCTasks" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateGCTasksSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateGcTasksRequest>
+                    for CreateGCTasksSvc<T> {
+                        type Response = super::CreateGcTasksResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateGcTasksRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_gc_tasks(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateGCTasksSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GCTasksStream" => {
+                    #[allow(non_camel_case_types)]
+                    struct GCTasksStreamSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::StreamingService<super::GcTaskAcknowledgement>
+                    for GCTasksStreamSvc<T> {
+                        type Response = super::CoordinatorCommand;
+                        type ResponseStream = T::GCTasksStreamStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::GcTaskAcknowledgement>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::gc_tasks_stream(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GCTasksStreamSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/Heartbeat" => {
+                    #[allow(non_camel_case_types)]
+                    struct HeartbeatSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::StreamingService<super::HeartbeatRequest>
+                    for HeartbeatSvc<T> {
+                        type Response = super::HeartbeatResponse;
+                        type ResponseStream = T::HeartbeatStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::HeartbeatRequest>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::heartbeat(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = HeartbeatSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListIndexes" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListIndexesSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListIndexesRequest>
+                    for ListIndexesSvc<T> {
+                        type Response = super::ListIndexesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListIndexesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_indexes(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListIndexesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetIndexSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetIndexRequest>
+                    for GetIndexSvc<T> {
+                        type Response = super::GetIndexResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetIndexRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_index(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CreateIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateIndexSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateIndexRequest>
+                    for CreateIndexSvc<T> {
+                        type Response = super::CreateIndexResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateIndexRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_index(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetExtractorCoordinates" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetExtractorCoordinatesSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetExtractorCoordinatesRequest>
+                    for GetExtractorCoordinatesSvc<T> {
+                        type Response = super::GetExtractorCoordinatesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::GetExtractorCoordinatesRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_extractor_coordinates(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetExtractorCoordinatesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/UpdateTask" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateTaskSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::UpdateTaskRequest>
+                    for UpdateTaskSvc<T> {
+                        type Response = super::UpdateTaskResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateTaskRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::update_task(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateTaskSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListStateChanges" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListStateChangesSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListStateChangesRequest>
+                    for ListStateChangesSvc<T> {
+                        type Response = super::ListStateChangesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListStateChangesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_state_changes(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListStateChangesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/WatchStateChanges" => {
+                    #[allow(non_camel_case_types)]
+                    struct WatchStateChangesSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::ServerStreamingService<super::WatchStateChangesRequest>
+                    for WatchStateChangesSvc<T> {
+                        type Response = super::StateChangeEvent;
+                        type ResponseStream = T::WatchStateChangesStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::WatchStateChangesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::watch_state_changes(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = WatchStateChangesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListTasks" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListTasksSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListTasksRequest>
+                    for ListTasksSvc<T> {
+                        type Response = super::ListTasksResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListTasksRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_tasks(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListTasksSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetSchema" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSchemaSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetSchemaRequest>
+                    for GetSchemaSvc<T> {
+                        type Response = super::GetSchemaResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSchemaRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_schema(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetSchemaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListSchemas" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListSchemasSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetAllSchemaRequest>
+                    for ListSchemasSvc<T> {
+                        type Response = super::GetAllSchemaResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetAllSchemaRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_schemas(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListSchemasSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/EvolveStructuredDataSchema" => {
+                    #[allow(non_camel_case_types)]
+                    struct EvolveStructuredDataSchemaSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::EvolveStructuredDataSchemaRequest>
+                    for EvolveStructuredDataSchemaSvc<T> {
+                        type Response = super::EvolveStructuredDataSchemaResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::EvolveStructuredDataSchemaRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::evolve_structured_data_schema(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = EvolveStructuredDataSchemaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetRaftMetricsSnapshot" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetRaftMetricsSnapshotSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetRaftMetricsSnapshotRequest>
+                    for GetRaftMetricsSnapshotSvc<T> {
+                        type Response = super::RaftMetricsSnapshotResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetRaftMetricsSnapshotRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_raft_metrics_snapshot(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetRaftMetricsSnapshotSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetAllTaskAssignments" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAllTaskAssignmentsSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetAllTaskAssignmentRequest>
+                    for GetAllTaskAssignmentsSvc<T> {
+                        type Response = super::TaskAssignments;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetAllTaskAssignmentRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_all_task_assignments(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetAllTaskAssignmentsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/PlanExtractionPolicy" => {
+                    #[allow(non_camel_case_types)]
+                    struct PlanExtractionPolicySvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::PlanExtractionPolicyRequest>
+                    for PlanExtractionPolicySvc<T> {
+                        type Response = super::PlanExtractionPolicyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PlanExtractionPolicyRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::plan_extraction_policy(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = PlanExtractionPolicySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/DryRunExtractionPolicy" => {
+                    #[allow(non_camel_case_types)]
+                    struct DryRunExtractionPolicySvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::DryRunExtractionPolicyRequest>
+                    for DryRunExtractionPolicySvc<T> {
+                        type Response = super::DryRunExtractionPolicyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DryRunExtractionPolicyRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::dry_run_extraction_policy(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DryRunExtractionPolicySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CreateIndexMigration" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateIndexMigrationSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateIndexMigrationRequest>
+                    for CreateIndexMigrationSvc<T> {
+                        type Response = super::IndexMigrationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateIndexMigrationRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_index_migration(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateIndexMigrationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetIndexMigration" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetIndexMigrationSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetIndexMigrationRequest>
+                    for GetIndexMigrationSvc<T> {
+                        type Response = super::IndexMigrationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetIndexMigrationRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_index_migration(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetIndexMigrationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CompleteIndexMigration" => {
+                    #[allow(non_camel_case_types)]
+                    struct CompleteIndexMigrationSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CompleteIndexMigrationRequest>
+                    for CompleteIndexMigrationSvc<T> {
+                        type Response = super::IndexMigrationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CompleteIndexMigrationRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::complete_index_migration(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CompleteIndexMigrationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CreateRebalanceJob" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateRebalanceJobSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateRebalanceJobRequest>
+                    for CreateRebalanceJobSvc<T> {
+                        type Response = super::CreateRebalanceJobResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateRebalanceJobRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_rebalance_job(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateRebalanceJobSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetRebalanceJob" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetRebalanceJobSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetRebalanceJobRequest>
+                    for GetRebalanceJobSvc<T> {
+                        type Response = super::RebalanceJob;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetRebalanceJobRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_rebalance_job(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetRebalanceJobSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/UpdateRebalanceJob" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateRebalanceJobSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::UpdateRebalanceJobRequest>
+                    for UpdateRebalanceJobSvc<T> {
+                        type Response = super::RebalanceJob;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateRebalanceJobRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::update_rebalance_job(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateRebalanceJobSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListIndexContent" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListIndexContentSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::CreateContentRequest>
-                    for CreateContentSvc<T> {
-                        type Response = super::CreateContentResponse;
+                    > tonic::server::UnaryService<super::ListIndexContentRequest>
+                    for ListIndexContentSvc<T> {
+                        type Response = super::ListIndexContentResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateContentRequest>,
+                            request: tonic::Request<super::ListIndexContentRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::create_content(&inner, request)
+                                <T as CoordinatorService>::list_index_content(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -1920,7 +6829,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateContentSvc(inner);
+                        let method = ListIndexContentSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1936,25 +6845,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/TombstoneContent" => {
+                "/indexify_coordinator.CoordinatorService/SetQueryTemplate" => {
                     #[allow(non_camel_case_types)]
-                    struct TombstoneContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct SetQueryTemplateSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::TombstoneContentRequest>
-                    for TombstoneContentSvc<T> {
-                        type Response = super::TombstoneContentResponse;
+                    > tonic::server::UnaryService<super::SetQueryTemplateRequest>
+                    for SetQueryTemplateSvc<T> {
+                        type Response = super::SetQueryTemplateResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::TombstoneContentRequest>,
+                            request: tonic::Request<super::SetQueryTemplateRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::tombstone_content(
+                                <T as CoordinatorService>::set_query_template(
                                         &inner,
                                         request,
                                     )
@@ -1970,7 +6879,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = TombstoneContentSvc(inner);
+                        let method = SetQueryTemplateSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1986,25 +6895,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetContentMetadata" => {
+                "/indexify_coordinator.CoordinatorService/GetQueryTemplate" => {
                     #[allow(non_camel_case_types)]
-                    struct GetContentMetadataSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetQueryTemplateSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetContentMetadataRequest>
-                    for GetContentMetadataSvc<T> {
-                        type Response = super::GetContentMetadataResponse;
+                    > tonic::server::UnaryService<super::GetQueryTemplateRequest>
+                    for GetQueryTemplateSvc<T> {
+                        type Response = super::GetQueryTemplateResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetContentMetadataRequest>,
+                            request: tonic::Request<super::GetQueryTemplateRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_content_metadata(
+                                <T as CoordinatorService>::get_query_template(
                                         &inner,
                                         request,
                                     )
@@ -2020,7 +6929,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetContentMetadataSvc(inner);
+                        let method = GetQueryTemplateSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2036,25 +6945,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetContentTreeMetadata" => {
+                "/indexify_coordinator.CoordinatorService/ListQueryTemplates" => {
                     #[allow(non_camel_case_types)]
-                    struct GetContentTreeMetadataSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ListQueryTemplatesSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetContentTreeMetadataRequest>
-                    for GetContentTreeMetadataSvc<T> {
-                        type Response = super::GetContentTreeMetadataResponse;
+                    > tonic::server::UnaryService<super::ListQueryTemplatesRequest>
+                    for ListQueryTemplatesSvc<T> {
+                        type Response = super::ListQueryTemplatesResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetContentTreeMetadataRequest>,
+                            request: tonic::Request<super::ListQueryTemplatesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_content_tree_metadata(
+                                <T as CoordinatorService>::list_query_templates(
                                         &inner,
                                         request,
                                     )
@@ -2070,7 +6979,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetContentTreeMetadataSvc(inner);
+                        let method = ListQueryTemplatesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2086,25 +6995,28 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListContent" => {
+                "/indexify_coordinator.CoordinatorService/DeleteQueryTemplate" => {
                     #[allow(non_camel_case_types)]
-                    struct ListContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct DeleteQueryTemplateSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListContentRequest>
-                    for ListContentSvc<T> {
-                        type Response = super::ListContentResponse;
+                    > tonic::server::UnaryService<super::DeleteQueryTemplateRequest>
+                    for DeleteQueryTemplateSvc<T> {
+                        type Response = super::DeleteQueryTemplateResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListContentRequest>,
+                            request: tonic::Request<super::DeleteQueryTemplateRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_content(&inner, request)
+                                <T as CoordinatorService>::delete_query_template(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -2117,7 +7029,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListContentSvc(inner);
+                        let method = DeleteQueryTemplateSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2133,25 +7045,229 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/CreateExtractionPolicy" => {
+                "/indexify_coordinator.CoordinatorService/CreateIngestIntent" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateExtractionPolicySvc<T: CoordinatorService>(pub Arc<T>);
+                    struct CreateIngestIntentSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateIngestIntentRequest>
+                    for CreateIngestIntentSvc<T> {
+                        type Response = super::CreateIngestIntentResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateIngestIntentRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_ingest_intent(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateIngestIntentSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListIngestIntents" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListIngestIntentsSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListIngestIntentsRequest>
+                    for ListIngestIntentsSvc<T> {
+                        type Response = super::ListIngestIntentsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListIngestIntentsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_ingest_intents(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListIngestIntentsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/DeleteIngestIntents" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteIngestIntentsSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::DeleteIngestIntentsRequest>
+                    for DeleteIngestIntentsSvc<T> {
+                        type Response = super::DeleteIngestIntentsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteIngestIntentsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::delete_ingest_intents(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteIngestIntentsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CreateEmbeddingExpirations" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateEmbeddingExpirationsSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateEmbeddingExpirationsRequest>
+                    for CreateEmbeddingExpirationsSvc<T> {
+                        type Response = super::CreateEmbeddingExpirationsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::CreateEmbeddingExpirationsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_embedding_expirations(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateEmbeddingExpirationsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListEmbeddingExpirations" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListEmbeddingExpirationsSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ExtractionPolicyRequest>
-                    for CreateExtractionPolicySvc<T> {
-                        type Response = super::ExtractionPolicyResponse;
+                    > tonic::server::UnaryService<super::ListEmbeddingExpirationsRequest>
+                    for ListEmbeddingExpirationsSvc<T> {
+                        type Response = super::ListEmbeddingExpirationsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ExtractionPolicyRequest>,
+                            request: tonic::Request<
+                                super::ListEmbeddingExpirationsRequest,
+                            >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::create_extraction_policy(
+                                <T as CoordinatorService>::list_embedding_expirations(
                                         &inner,
                                         request,
                                     )
@@ -2167,7 +7283,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateExtractionPolicySvc(inner);
+                        let method = ListEmbeddingExpirationsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2183,25 +7299,27 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListExtractionPolicies" => {
+                "/indexify_coordinator.CoordinatorService/DeleteEmbeddingExpirations" => {
                     #[allow(non_camel_case_types)]
-                    struct ListExtractionPoliciesSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct DeleteEmbeddingExpirationsSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListExtractionPoliciesRequest>
-                    for ListExtractionPoliciesSvc<T> {
-                        type Response = super::ListExtractionPoliciesResponse;
+                    > tonic::server::UnaryService<super::DeleteEmbeddingExpirationsRequest>
+                    for DeleteEmbeddingExpirationsSvc<T> {
+                        type Response = super::DeleteEmbeddingExpirationsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListExtractionPoliciesRequest>,
+                            request: tonic::Request<
+                                super::DeleteEmbeddingExpirationsRequest,
+                            >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_extraction_policies(
+                                <T as CoordinatorService>::delete_embedding_expirations(
                                         &inner,
                                         request,
                                     )
@@ -2217,7 +7335,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListExtractionPoliciesSvc(inner);
+                        let method = DeleteEmbeddingExpirationsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2233,25 +7351,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/CreateNS" => {
+                "/indexify_coordinator.CoordinatorService/AddChunkReference" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct AddChunkReferenceSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::CreateNamespaceRequest>
-                    for CreateNSSvc<T> {
-                        type Response = super::CreateNamespaceResponse;
+                    > tonic::server::UnaryService<super::AddChunkReferenceRequest>
+                    for AddChunkReferenceSvc<T> {
+                        type Response = super::AddChunkReferenceResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateNamespaceRequest>,
+                            request: tonic::Request<super::AddChunkReferenceRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::create_ns(&inner, request).await
+                                <T as CoordinatorService>::add_chunk_reference(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -2263,7 +7385,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateNSSvc(inner);
+                        let method = AddChunkReferenceSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2279,25 +7401,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListNS" => {
+                "/indexify_coordinator.CoordinatorService/RemoveChunkReference" => {
                     #[allow(non_camel_case_types)]
-                    struct ListNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct RemoveChunkReferenceSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListNamespaceRequest>
-                    for ListNSSvc<T> {
-                        type Response = super::ListNamespaceResponse;
+                    > tonic::server::UnaryService<super::RemoveChunkReferenceRequest>
+                    for RemoveChunkReferenceSvc<T> {
+                        type Response = super::RemoveChunkReferenceResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListNamespaceRequest>,
+                            request: tonic::Request<super::RemoveChunkReferenceRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_ns(&inner, request).await
+                                <T as CoordinatorService>::remove_chunk_reference(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -2309,7 +7435,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListNSSvc(inner);
+                        let method = RemoveChunkReferenceSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2325,25 +7451,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetNS" => {
+                "/indexify_coordinator.CoordinatorService/GetChunkReferences" => {
                     #[allow(non_camel_case_types)]
-                    struct GetNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetChunkReferencesSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetNamespaceRequest>
-                    for GetNSSvc<T> {
-                        type Response = super::GetNamespaceResponse;
+                    > tonic::server::UnaryService<super::GetChunkReferencesRequest>
+                    for GetChunkReferencesSvc<T> {
+                        type Response = super::GetChunkReferencesResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetNamespaceRequest>,
+                            request: tonic::Request<super::GetChunkReferencesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_ns(&inner, request).await
+                                <T as CoordinatorService>::get_chunk_references(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -2355,7 +7485,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetNSSvc(inner);
+                        let method = GetChunkReferencesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2371,25 +7501,28 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListExtractors" => {
+                "/indexify_coordinator.CoordinatorService/SetIngestionPlugin" => {
                     #[allow(non_camel_case_types)]
-                    struct ListExtractorsSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct SetIngestionPluginSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListExtractorsRequest>
-                    for ListExtractorsSvc<T> {
-                        type Response = super::ListExtractorsResponse;
+                    > tonic::server::UnaryService<super::SetIngestionPluginRequest>
+                    for SetIngestionPluginSvc<T> {
+                        type Response = super::SetIngestionPluginResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListExtractorsRequest>,
+                            request: tonic::Request<super::SetIngestionPluginRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_extractors(&inner, request)
+                                <T as CoordinatorService>::set_ingestion_plugin(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -2402,7 +7535,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListExtractorsSvc(inner);
+                        let method = SetIngestionPluginSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2418,25 +7551,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/RegisterExecutor" => {
+                "/indexify_coordinator.CoordinatorService/ListIngestionPlugins" => {
                     #[allow(non_camel_case_types)]
-                    struct RegisterExecutorSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ListIngestionPluginsSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::RegisterExecutorRequest>
-                    for RegisterExecutorSvc<T> {
-                        type Response = super::RegisterExecutorResponse;
+                    > tonic::server::UnaryService<super::ListIngestionPluginsRequest>
+                    for ListIngestionPluginsSvc<T> {
+                        type Response = super::ListIngestionPluginsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RegisterExecutorRequest>,
+                            request: tonic::Request<super::ListIngestionPluginsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::register_executor(
+                                <T as CoordinatorService>::list_ingestion_plugins(
                                         &inner,
                                         request,
                                     )
@@ -2452,7 +7585,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = RegisterExecutorSvc(inner);
+                        let method = ListIngestionPluginsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2468,27 +7601,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/RegisterIngestionServer" => {
+                "/indexify_coordinator.CoordinatorService/DeleteIngestionPlugin" => {
                     #[allow(non_camel_case_types)]
-                    struct RegisterIngestionServerSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct DeleteIngestionPluginSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::RegisterIngestionServerRequest>
-                    for RegisterIngestionServerSvc<T> {
-                        type Response = super::RegisterIngestionServerResponse;
+                    > tonic::server::UnaryService<super::DeleteIngestionPluginRequest>
+                    for DeleteIngestionPluginSvc<T> {
+                        type Response = super::DeleteIngestionPluginResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<
-                                super::RegisterIngestionServerRequest,
-                            >,
+                            request: tonic::Request<super::DeleteIngestionPluginRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::register_ingestion_server(
+                                <T as CoordinatorService>::delete_ingestion_plugin(
                                         &inner,
                                         request,
                                     )
@@ -2504,7 +7635,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = RegisterIngestionServerSvc(inner);
+                        let method = DeleteIngestionPluginSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2520,25 +7651,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/RemoveIngestionServer" => {
+                "/indexify_coordinator.CoordinatorService/SetProcessingSla" => {
                     #[allow(non_camel_case_types)]
-                    struct RemoveIngestionServerSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct SetProcessingSlaSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::RemoveIngestionServerRequest>
-                    for RemoveIngestionServerSvc<T> {
-                        type Response = super::RemoveIngestionServerResponse;
+                    > tonic::server::UnaryService<super::SetProcessingSlaRequest>
+                    for SetProcessingSlaSvc<T> {
+                        type Response = super::SetProcessingSlaResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RemoveIngestionServerRequest>,
+                            request: tonic::Request<super::SetProcessingSlaRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::remove_ingestion_server(
+                                <T as CoordinatorService>::set_processing_sla(
                                         &inner,
                                         request,
                                     )
@@ -2554,7 +7685,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = RemoveIngestionServerSvc(inner);
+                        let method = SetProcessingSlaSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2570,25 +7701,28 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/CreateGCTasks" => {
+                "/indexify_coordinator.CoordinatorService/ListProcessingSlas" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateGCTasksSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ListProcessingSlasSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::CreateGcTasksRequest>
-                    for CreateGCTasksSvc<T> {
-                        type Response = super::CreateGcTasksResponse;
+                    > tonic::server::UnaryService<super::ListProcessingSlasRequest>
+                    for ListProcessingSlasSvc<T> {
+                        type Response = super::ListProcessingSlasResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateGcTasksRequest>,
+                            request: tonic::Request<super::ListProcessingSlasRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::create_gc_tasks(&inner, request)
+                                <T as CoordinatorService>::list_processing_slas(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -2601,7 +7735,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateGCTasksSvc(inner);
+                        let method = ListProcessingSlasSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2617,28 +7751,28 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GCTasksStream" => {
+                "/indexify_coordinator.CoordinatorService/DeleteProcessingSla" => {
                     #[allow(non_camel_case_types)]
-                    struct GCTasksStreamSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct DeleteProcessingSlaSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::StreamingService<super::GcTaskAcknowledgement>
-                    for GCTasksStreamSvc<T> {
-                        type Response = super::CoordinatorCommand;
-                        type ResponseStream = T::GCTasksStreamStream;
+                    > tonic::server::UnaryService<super::DeleteProcessingSlaRequest>
+                    for DeleteProcessingSlaSvc<T> {
+                        type Response = super::DeleteProcessingSlaResponse;
                         type Future = BoxFuture<
-                            tonic::Response<Self::ResponseStream>,
+                            tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<
-                                tonic::Streaming<super::GcTaskAcknowledgement>,
-                            >,
+                            request: tonic::Request<super::DeleteProcessingSlaRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::gc_tasks_stream(&inner, request)
+                                <T as CoordinatorService>::delete_processing_sla(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -2651,7 +7785,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GCTasksStreamSvc(inner);
+                        let method = DeleteProcessingSlaSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2662,33 +7796,34 @@ pub mod coordinator_service_server {
                                 max_decoding_message_size,
                                 max_encoding_message_size,
                             );
-                        let res = grpc.streaming(method, req).await;
+                        let res = grpc.unary(method, req).await;
                         Ok(res)
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/Heartbeat" => {
+                "/indexify_coordinator.CoordinatorService/GetNamespaceStorage" => {
                     #[allow(non_camel_case_types)]
-                    struct HeartbeatSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetNamespaceStorageSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::StreamingService<super::HeartbeatRequest>
-                    for HeartbeatSvc<T> {
-                        type Response = super::HeartbeatResponse;
-                        type ResponseStream = T::HeartbeatStream;
+                    > tonic::server::UnaryService<super::GetNamespaceStorageRequest>
+                    for GetNamespaceStorageSvc<T> {
+                        type Response = super::GetNamespaceStorageResponse;
                         type Future = BoxFuture<
-                            tonic::Response<Self::ResponseStream>,
+                            tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<
-                                tonic::Streaming<super::HeartbeatRequest>,
-                            >,
+                            request: tonic::Request<super::GetNamespaceStorageRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::heartbeat(&inner, request).await
+                                <T as CoordinatorService>::get_namespace_storage(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -2700,7 +7835,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = HeartbeatSvc(inner);
+                        let method = GetNamespaceStorageSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2711,30 +7846,33 @@ pub mod coordinator_service_server {
                                 max_decoding_message_size,
                                 max_encoding_message_size,
                             );
-                        let res = grpc.streaming(method, req).await;
+                        let res = grpc.unary(method, req).await;
                         Ok(res)
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListIndexes" => {
+                "/indexify_coordinator.CoordinatorService/SetNamespaceQuotas" => {
                     #[allow(non_camel_case_types)]
-                    struct ListIndexesSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct SetNamespaceQuotasSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListIndexesRequest>
-                    for ListIndexesSvc<T> {
-                        type Response = super::ListIndexesResponse;
+                    > tonic::server::UnaryService<super::SetNamespaceQuotasRequest>
+                    for SetNamespaceQuotasSvc<T> {
+                        type Response = super::SetNamespaceQuotasResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListIndexesRequest>,
+                            request: tonic::Request<super::SetNamespaceQuotasRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_indexes(&inner, request)
+                                <T as CoordinatorService>::set_namespace_quotas(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -2747,7 +7885,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListIndexesSvc(inner);
+                        let method = SetNamespaceQuotasSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2763,25 +7901,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetIndex" => {
+                "/indexify_coordinator.CoordinatorService/GetNamespaceQuotas" => {
                     #[allow(non_camel_case_types)]
-                    struct GetIndexSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetNamespaceQuotasSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetIndexRequest>
-                    for GetIndexSvc<T> {
-                        type Response = super::GetIndexResponse;
+                    > tonic::server::UnaryService<super::GetNamespaceQuotasRequest>
+                    for GetNamespaceQuotasSvc<T> {
+                        type Response = super::GetNamespaceQuotasResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetIndexRequest>,
+                            request: tonic::Request<super::GetNamespaceQuotasRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_index(&inner, request).await
+                                <T as CoordinatorService>::get_namespace_quotas(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -2793,7 +7935,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetIndexSvc(inner);
+                        let method = GetNamespaceQuotasSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2809,25 +7951,28 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/CreateIndex" => {
+                "/indexify_coordinator.CoordinatorService/GetNamespaceStats" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateIndexSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetNamespaceStatsSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::CreateIndexRequest>
-                    for CreateIndexSvc<T> {
-                        type Response = super::CreateIndexResponse;
+                    > tonic::server::UnaryService<super::GetNamespaceStatsRequest>
+                    for GetNamespaceStatsSvc<T> {
+                        type Response = super::GetNamespaceStatsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateIndexRequest>,
+                            request: tonic::Request<super::GetNamespaceStatsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::create_index(&inner, request)
+                                <T as CoordinatorService>::get_namespace_stats(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -2840,7 +7985,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateIndexSvc(inner);
+                        let method = GetNamespaceStatsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2856,27 +8001,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetExtractorCoordinates" => {
+                "/indexify_coordinator.CoordinatorService/CreateBlobMigrationJob" => {
                     #[allow(non_camel_case_types)]
-                    struct GetExtractorCoordinatesSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct CreateBlobMigrationJobSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetExtractorCoordinatesRequest>
-                    for GetExtractorCoordinatesSvc<T> {
-                        type Response = super::GetExtractorCoordinatesResponse;
+                    > tonic::server::UnaryService<super::CreateBlobMigrationJobRequest>
+                    for CreateBlobMigrationJobSvc<T> {
+                        type Response = super::CreateBlobMigrationJobResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<
-                                super::GetExtractorCoordinatesRequest,
-                            >,
+                            request: tonic::Request<super::CreateBlobMigrationJobRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_extractor_coordinates(
+                                <T as CoordinatorService>::create_blob_migration_job(
                                         &inner,
                                         request,
                                     )
@@ -2892,7 +8035,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetExtractorCoordinatesSvc(inner);
+                        let method = CreateBlobMigrationJobSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2908,25 +8051,28 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/UpdateTask" => {
+                "/indexify_coordinator.CoordinatorService/GetBlobMigrationJob" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateTaskSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetBlobMigrationJobSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::UpdateTaskRequest>
-                    for UpdateTaskSvc<T> {
-                        type Response = super::UpdateTaskResponse;
+                    > tonic::server::UnaryService<super::GetBlobMigrationJobRequest>
+                    for GetBlobMigrationJobSvc<T> {
+                        type Response = super::BlobMigrationJob;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateTaskRequest>,
+                            request: tonic::Request<super::GetBlobMigrationJobRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::update_task(&inner, request)
+                                <T as CoordinatorService>::get_blob_migration_job(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -2939,7 +8085,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = UpdateTaskSvc(inner);
+                        let method = GetBlobMigrationJobSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2955,25 +8101,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListStateChanges" => {
+                "/indexify_coordinator.CoordinatorService/UpdateBlobMigrationJob" => {
                     #[allow(non_camel_case_types)]
-                    struct ListStateChangesSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct UpdateBlobMigrationJobSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListStateChangesRequest>
-                    for ListStateChangesSvc<T> {
-                        type Response = super::ListStateChangesResponse;
+                    > tonic::server::UnaryService<super::UpdateBlobMigrationJobRequest>
+                    for UpdateBlobMigrationJobSvc<T> {
+                        type Response = super::BlobMigrationJob;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListStateChangesRequest>,
+                            request: tonic::Request<super::UpdateBlobMigrationJobRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_state_changes(
+                                <T as CoordinatorService>::update_blob_migration_job(
                                         &inner,
                                         request,
                                     )
@@ -2989,7 +8135,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListStateChangesSvc(inner);
+                        let method = UpdateBlobMigrationJobSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3005,25 +8151,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListTasks" => {
+                "/indexify_coordinator.CoordinatorService/ListClusterMembers" => {
                     #[allow(non_camel_case_types)]
-                    struct ListTasksSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ListClusterMembersSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListTasksRequest>
-                    for ListTasksSvc<T> {
-                        type Response = super::ListTasksResponse;
+                    > tonic::server::UnaryService<super::ListClusterMembersRequest>
+                    for ListClusterMembersSvc<T> {
+                        type Response = super::ListClusterMembersResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListTasksRequest>,
+                            request: tonic::Request<super::ListClusterMembersRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_tasks(&inner, request).await
+                                <T as CoordinatorService>::list_cluster_members(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -3035,7 +8185,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListTasksSvc(inner);
+                        let method = ListClusterMembersSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3051,25 +8201,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetSchema" => {
+                "/indexify_coordinator.CoordinatorService/AddClusterLearner" => {
                     #[allow(non_camel_case_types)]
-                    struct GetSchemaSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct AddClusterLearnerSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetSchemaRequest>
-                    for GetSchemaSvc<T> {
-                        type Response = super::GetSchemaResponse;
+                    > tonic::server::UnaryService<super::AddClusterLearnerRequest>
+                    for AddClusterLearnerSvc<T> {
+                        type Response = super::ListClusterMembersResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetSchemaRequest>,
+                            request: tonic::Request<super::AddClusterLearnerRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_schema(&inner, request).await
+                                <T as CoordinatorService>::add_cluster_learner(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -3081,7 +8235,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetSchemaSvc(inner);
+                        let method = AddClusterLearnerSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3097,25 +8251,28 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListSchemas" => {
+                "/indexify_coordinator.CoordinatorService/PromoteClusterLearner" => {
                     #[allow(non_camel_case_types)]
-                    struct ListSchemasSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct PromoteClusterLearnerSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetAllSchemaRequest>
-                    for ListSchemasSvc<T> {
-                        type Response = super::GetAllSchemaResponse;
+                    > tonic::server::UnaryService<super::PromoteClusterLearnerRequest>
+                    for PromoteClusterLearnerSvc<T> {
+                        type Response = super::ListClusterMembersResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetAllSchemaRequest>,
+                            request: tonic::Request<super::PromoteClusterLearnerRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_schemas(&inner, request)
+                                <T as CoordinatorService>::promote_cluster_learner(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -3128,7 +8285,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListSchemasSvc(inner);
+                        let method = PromoteClusterLearnerSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3144,25 +8301,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetRaftMetricsSnapshot" => {
+                "/indexify_coordinator.CoordinatorService/RemoveClusterMember" => {
                     #[allow(non_camel_case_types)]
-                    struct GetRaftMetricsSnapshotSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct RemoveClusterMemberSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetRaftMetricsSnapshotRequest>
-                    for GetRaftMetricsSnapshotSvc<T> {
-                        type Response = super::RaftMetricsSnapshotResponse;
+                    > tonic::server::UnaryService<super::RemoveClusterMemberRequest>
+                    for RemoveClusterMemberSvc<T> {
+                        type Response = super::ListClusterMembersResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetRaftMetricsSnapshotRequest>,
+                            request: tonic::Request<super::RemoveClusterMemberRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_raft_metrics_snapshot(
+                                <T as CoordinatorService>::remove_cluster_member(
                                         &inner,
                                         request,
                                     )
@@ -3178,7 +8335,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetRaftMetricsSnapshotSvc(inner);
+                        let method = RemoveClusterMemberSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3194,28 +8351,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetAllTaskAssignments" => {
+                "/indexify_coordinator.CoordinatorService/GetNodeHealth" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAllTaskAssignmentsSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetNodeHealthSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetAllTaskAssignmentRequest>
-                    for GetAllTaskAssignmentsSvc<T> {
-                        type Response = super::TaskAssignments;
+                    > tonic::server::UnaryService<super::GetNodeHealthRequest>
+                    for GetNodeHealthSvc<T> {
+                        type Response = super::GetNodeHealthResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetAllTaskAssignmentRequest>,
+                            request: tonic::Request<super::GetNodeHealthRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_all_task_assignments(
-                                        &inner,
-                                        request,
-                                    )
+                                <T as CoordinatorService>::get_node_health(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -3228,7 +8382,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetAllTaskAssignmentsSvc(inner);
+                        let method = GetNodeHealthSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
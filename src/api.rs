@@ -13,7 +13,7 @@ use smart_default::SmartDefault;
 use strum::{Display, EnumString};
 use utoipa::{IntoParams, ToSchema};
 
-use crate::{api_utils, metadata_storage, vectordbs};
+use crate::{api_utils, blob_storage, metadata_storage, vectordbs};
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ExtractionPolicy {
@@ -24,6 +24,149 @@ pub struct ExtractionPolicy {
     pub filters_eq: Option<HashMap<String, String>>,
     pub input_params: Option<serde_json::Value>,
     pub content_source: Option<String>,
+    #[serde(default)]
+    pub version: u64,
+    #[serde(default)]
+    pub content_filter: ContentFilter,
+    #[serde(default)]
+    pub backfill_from: Option<i64>,
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// Content an extraction policy is applied to, on top of its label filters.
+/// Criteria which aren't set match any content.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ContentFilter {
+    // Globs of mime types, e.g. "image/*"
+    #[serde(default)]
+    pub mime_types: Vec<String>,
+    #[serde(default)]
+    pub label_ranges: Vec<LabelRange>,
+    pub min_size_bytes: Option<u64>,
+    pub max_size_bytes: Option<u64>,
+}
+
+/// Matches content whose label is a number within the bounds
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LabelRange {
+    pub label: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BackoffStrategy {
+    /// The backoff doubles with every attempt, up to the maximum backoff
+    #[default]
+    Exponential,
+    Fixed,
+}
+
+/// How the failed tasks of an extraction policy are retried. Failed tasks
+/// aren't retried by default.
+#[derive(Debug, Clone, Serialize, Deserialize, SmartDefault, ToSchema)]
+#[serde(default)]
+pub struct RetryPolicy {
+    // Attempts of a task including the first one
+    #[default = 1]
+    pub max_attempts: u32,
+    pub backoff: BackoffStrategy,
+    #[default = 10]
+    pub initial_backoff_secs: u64,
+    #[default = 600]
+    pub max_backoff_secs: u64,
+}
+
+impl From<indexify_coordinator::RetryPolicy> for RetryPolicy {
+    fn from(value: indexify_coordinator::RetryPolicy) -> Self {
+        let backoff = match value.backoff() {
+            indexify_coordinator::BackoffStrategy::Exponential => BackoffStrategy::Exponential,
+            indexify_coordinator::BackoffStrategy::Fixed => BackoffStrategy::Fixed,
+        };
+        Self {
+            max_attempts: value.max_attempts,
+            backoff,
+            initial_backoff_secs: value.initial_backoff_secs,
+            max_backoff_secs: value.max_backoff_secs,
+        }
+    }
+}
+
+impl From<RetryPolicy> for indexify_coordinator::RetryPolicy {
+    fn from(value: RetryPolicy) -> Self {
+        let backoff = match value.backoff {
+            BackoffStrategy::Exponential => indexify_coordinator::BackoffStrategy::Exponential,
+            BackoffStrategy::Fixed => indexify_coordinator::BackoffStrategy::Fixed,
+        };
+        Self {
+            max_attempts: value.max_attempts,
+            backoff: backoff as i32,
+            initial_backoff_secs: value.initial_backoff_secs,
+            max_backoff_secs: value.max_backoff_secs,
+        }
+    }
+}
+
+impl From<indexify_coordinator::ContentFilter> for ContentFilter {
+    fn from(value: indexify_coordinator::ContentFilter) -> Self {
+        Self {
+            mime_types: value.mime_types,
+            label_ranges: value
+                .label_ranges
+                .into_iter()
+                .map(|range| LabelRange {
+                    label: range.label,
+                    min: range.min,
+                    max: range.max,
+                })
+                .collect(),
+            min_size_bytes: value.min_size_bytes,
+            max_size_bytes: value.max_size_bytes,
+        }
+    }
+}
+
+impl From<ContentFilter> for indexify_coordinator::ContentFilter {
+    fn from(value: ContentFilter) -> Self {
+        Self {
+            mime_types: value.mime_types,
+            label_ranges: value
+                .label_ranges
+                .into_iter()
+                .map(|range| indexify_coordinator::LabelRange {
+                    label: range.label,
+                    min: range.min,
+                    max: range.max,
+                })
+                .collect(),
+            min_size_bytes: value.min_size_bytes,
+            max_size_bytes: value.max_size_bytes,
+        }
+    }
+}
+
+impl TryFrom<indexify_coordinator::ExtractionPolicy> for ExtractionPolicy {
+    type Error = anyhow::Error;
+
+    fn try_from(value: indexify_coordinator::ExtractionPolicy) -> Result<Self> {
+        Ok(Self {
+            id: value.id,
+            extractor: value.extractor,
+            name: value.name,
+            filters_eq: Some(value.filters),
+            input_params: Some(serde_json::from_str(&value.input_params)?),
+            content_source: Some(value.content_source),
+            version: value.version,
+            content_filter: value.content_filter.map(Into::into).unwrap_or_default(),
+            backfill_from: value.backfill_from,
+            retry_policy: value.retry_policy.map(Into::into).unwrap_or_default(),
+            priority: value.priority,
+        })
+    }
 }
 
 impl From<ExtractionPolicy> for indexify_coordinator::ExtractionPolicy {
@@ -38,6 +181,11 @@ impl From<ExtractionPolicy> for indexify_coordinator::ExtractionPolicy {
                 .map(|v| v.to_string())
                 .unwrap_or("{}".to_string()),
             content_source: value.content_source.unwrap_or("ingestion".to_string()),
+            version: value.version,
+            content_filter: Some(value.content_filter.into()),
+            backfill_from: value.backfill_from,
+            retry_policy: Some(value.retry_policy.into()),
+            priority: value.priority,
         }
     }
 }
@@ -48,21 +196,196 @@ pub struct DataNamespace {
     pub extraction_policies: Vec<ExtractionPolicy>,
 }
 
+/// Bytes of content stored in a namespace, and the quota of the namespace
+/// if it has one.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NamespaceStorage {
+    pub namespace: String,
+    pub stored_bytes: u64,
+    pub quota_bytes: Option<u64>,
+}
+
+/// Limits of a namespace enforced by the coordinators, limits which aren't set
+/// don't apply. Uploads and extraction policies which would take the
+/// namespace over a limit are rejected.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
+pub struct NamespaceQuotas {
+    #[serde(default)]
+    pub max_content: Option<u64>,
+    #[serde(default)]
+    pub max_stored_bytes: Option<u64>,
+    #[serde(default)]
+    pub max_extraction_policies: Option<u64>,
+}
+
+impl From<NamespaceQuotas> for internal_api::NamespaceQuotas {
+    fn from(value: NamespaceQuotas) -> Self {
+        Self {
+            max_content: value.max_content,
+            max_stored_bytes: value.max_stored_bytes,
+            max_extraction_policies: value.max_extraction_policies,
+        }
+    }
+}
+
+impl From<internal_api::NamespaceQuotas> for NamespaceQuotas {
+    fn from(value: internal_api::NamespaceQuotas) -> Self {
+        Self {
+            max_content: value.max_content,
+            max_stored_bytes: value.max_stored_bytes,
+            max_extraction_policies: value.max_extraction_policies,
+        }
+    }
+}
+
+/// The quotas of a namespace along with how much of them is used
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NamespaceQuotasResponse {
+    pub namespace: String,
+    pub quotas: NamespaceQuotas,
+    pub content: u64,
+    pub stored_bytes: u64,
+    pub extraction_policies: u64,
+}
+
+/// Counters of a namespace kept up to date by the coordinators
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NamespaceStats {
+    pub namespace: String,
+    pub content: u64,
+    pub stored_bytes: u64,
+    pub tasks_pending: u64,
+    pub tasks_failed: u64,
+    pub indexes: u64,
+}
+
+/// A node of the raft cluster of the coordinators
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ClusterMember {
+    pub node_id: u64,
+    pub address: String,
+    pub coordinator_addr: String,
+    //  Learners replicate the log without voting
+    pub voter: bool,
+    pub leader: bool,
+}
+
+impl From<indexify_coordinator::ClusterMember> for ClusterMember {
+    fn from(value: indexify_coordinator::ClusterMember) -> Self {
+        Self {
+            node_id: value.node_id,
+            address: value.address,
+            coordinator_addr: value.coordinator_addr,
+            voter: value.voter,
+            leader: value.leader,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListClusterMembersResponse {
+    pub members: Vec<ClusterMember>,
+}
+
+/// Health of a coordinator node, only the member details are set if the node
+/// couldn't be reached
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct CoordinatorNodeHealth {
+    pub node_id: u64,
+    pub coordinator_addr: String,
+    pub voter: bool,
+    pub reachable: bool,
+    //  leader, follower, learner, candidate or shutdown
+    pub role: Option<String>,
+    pub applied_index: Option<u64>,
+    //  Unix time in milliseconds the node last heard from the leader
+    pub last_heartbeat_ms: Option<u64>,
+    //  Executors sending their heartbeats to the node
+    pub executors: Vec<String>,
+}
+
+/// Health of the cluster of the coordinators, it's healthy if it has a leader
+/// and a majority of the voters can be reached
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ClusterHealth {
+    pub healthy: bool,
+    pub leader: Option<u64>,
+    pub nodes: Vec<CoordinatorNodeHealth>,
+}
+
+/// Request payload for adding a node to the cluster as a learner
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct AddClusterLearner {
+    pub node_id: u64,
+    //  Address of the raft server of the node
+    pub address: String,
+    pub coordinator_addr: String,
+}
+
+/// A blob storage backend
+#[derive(Display, EnumString, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BlobBackend {
+    S3,
+    Gcs,
+    Disk,
+}
+
+impl From<BlobBackend> for blob_storage::BlobBackend {
+    fn from(value: BlobBackend) -> Self {
+        match value {
+            BlobBackend::S3 => blob_storage::BlobBackend::S3,
+            BlobBackend::Gcs => blob_storage::BlobBackend::Gcs,
+            BlobBackend::Disk => blob_storage::BlobBackend::Disk,
+        }
+    }
+}
+
+/// Request payload for copying the blobs of the content of a namespace to
+/// another blob storage backend.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct MigrateBlobs {
+    //  Defaults to the backend new blobs are written to
+    pub target: Option<BlobBackend>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BlobMigrationJobResponse {
+    pub namespace: String,
+    pub target: String,
+    pub total_content: u64,
+    pub migrated_content: u64,
+    pub completed: bool,
+    pub error: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl From<indexify_coordinator::BlobMigrationJob> for BlobMigrationJobResponse {
+    fn from(value: indexify_coordinator::BlobMigrationJob) -> Self {
+        Self {
+            namespace: value.namespace,
+            target: value.target,
+            total_content: value.total_content,
+            migrated_content: value.migrated_content,
+            completed: value.completed,
+            error: (!value.error.is_empty()).then_some(value.error),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
 impl TryFrom<indexify_coordinator::Namespace> for DataNamespace {
     type Error = anyhow::Error;
 
     fn try_from(value: indexify_coordinator::Namespace) -> Result<Self> {
-        let mut extraction_policies = Vec::new();
-        for policy in value.policies {
-            extraction_policies.push(ExtractionPolicy {
-                id: policy.id,
-                extractor: policy.extractor,
-                name: policy.name,
-                filters_eq: Some(policy.filters),
-                input_params: Some(serde_json::from_str(&policy.input_params)?),
-                content_source: Some(policy.content_source),
-            });
-        }
+        let extraction_policies = value
+            .policies
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<ExtractionPolicy>>>()?;
         Ok(Self {
             name: value.name,
             extraction_policies,
@@ -75,6 +398,9 @@ pub struct CreateNamespace {
     pub name: String,
     pub extraction_policies: Vec<ExtractionPolicy>,
     pub labels: HashMap<String, String>,
+    // Client generated token, retries of a request with the same token are
+    // only applied once
+    pub operation_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -136,6 +462,102 @@ pub struct ExtractionPolicyRequest {
     pub filters_eq: Option<HashMap<String, String>>,
     pub input_params: Option<serde_json::Value>,
     pub content_source: Option<String>,
+    // Client generated token, retries of a request with the same token are
+    // only applied once
+    pub operation_token: Option<String>,
+    // Creates a keyword index populated with the text content written by the
+    // extractor, searchable alongside the embedding indexes of the policy
+    #[serde(default)]
+    pub keyword_index: bool,
+    // Quantization of the embedding indexes of the policy, embeddings are
+    // stored uncompressed when not set
+    #[serde(default)]
+    pub quantization: Option<IndexQuantization>,
+    // Distance metric of the embedding indexes of the policy, the metric in
+    // the output schema of the extractor is used when not set
+    #[serde(default)]
+    pub distance: Option<IndexDistance>,
+    // Seconds after which embeddings written to the indexes of the policy
+    // are removed from them, they are kept until their content is deleted
+    // when not set
+    #[serde(default)]
+    pub embedding_ttl_secs: Option<u64>,
+    // Backend specific tuning of the embedding indexes of the policy, e.g.
+    // {"m": 32, "ef_construction": 200}, applied when the indexes are created
+    #[serde(default)]
+    pub index_params: Option<serde_json::Value>,
+    // Content the policy is applied to on top of the labels in filters_eq,
+    // e.g. only PDFs of at most 100 pages
+    #[serde(default)]
+    pub content_filter: Option<ContentFilter>,
+    // Existing content the policy is applied to when it's created, content
+    // ingested afterwards is always extracted
+    #[serde(default)]
+    pub backfill: Backfill,
+    // Retries of the failed tasks of the policy, they aren't retried when
+    // not set
+    #[serde(default)]
+    pub retry_policy: Option<RetryPolicy>,
+    // Tasks of policies with a higher priority are given to executors before
+    // the tasks of other policies, 0 when not set
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// Existing content an extraction policy is applied to when it's created,
+/// e.g. `"all"`, `"none"` or `{"since": 1718000000}`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Backfill {
+    /// All the existing content
+    #[default]
+    All,
+    /// None of the existing content
+    None,
+    /// Content created at or after the time, in seconds since the epoch
+    Since(i64),
+}
+
+/// Compression of the embeddings of an index in the vector store, trading
+/// recall for memory. Only applied when the index is created.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IndexQuantization {
+    /// Stores every dimension as an 8 bit integer.
+    Scalar {
+        #[serde(default)]
+        quantile: Option<f32>,
+        #[serde(default)]
+        always_ram: bool,
+    },
+    /// Stores sub vectors as the ids of their closest centroids, `compression`
+    /// is one of 4, 8, 16, 32 or 64.
+    Product {
+        compression: u32,
+        #[serde(default)]
+        always_ram: bool,
+    },
+}
+
+impl From<IndexQuantization> for internal_api::IndexQuantization {
+    fn from(value: IndexQuantization) -> Self {
+        match value {
+            IndexQuantization::Scalar {
+                quantile,
+                always_ram,
+            } => internal_api::IndexQuantization::Scalar {
+                quantile,
+                always_ram,
+            },
+            IndexQuantization::Product {
+                compression,
+                always_ram,
+            } => internal_api::IndexQuantization::Product {
+                compression,
+                always_ram,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
@@ -144,17 +566,110 @@ pub struct ExtractionPolicyResponse {
     pub index_names: Vec<String>,
 }
 
+/// Request payload for updating an extraction policy. Fields which aren't set
+/// are left unchanged.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct UpdateExtractionPolicyRequest {
+    #[serde(default, deserialize_with = "api_utils::deserialize_labels_eq_filter")]
+    pub filters_eq: Option<HashMap<String, String>>,
+    pub input_params: Option<serde_json::Value>,
+    #[serde(default)]
+    pub content_filter: Option<ContentFilter>,
+    #[serde(default)]
+    pub retry_policy: Option<RetryPolicy>,
+    // Extracts all the content matching the updated policy again, content
+    // already extracted by the policy is otherwise left as is
+    #[serde(default)]
+    pub reextract: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateExtractionPolicyResponse {
+    pub extraction_policy: ExtractionPolicy,
+}
+
+/// What happens to the indexes of a deleted extraction policy. Archived
+/// indexes are kept and can still be searched, but nothing is written to them
+/// anymore. Dropped indexes are removed along with their tables.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletedPolicyIndexes {
+    #[default]
+    Archive,
+    Drop,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+pub struct DeleteExtractionPolicyParams {
+    #[serde(default)]
+    pub indexes: DeletedPolicyIndexes,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeleteExtractionPolicyResponse {
+    pub dropped_index_tables: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct NamespaceTemplateParameter {
+    pub name: String,
+    pub description: String,
+    pub default: String,
+}
+
+/// A built-in pipeline which can be set up in a new namespace with a single
+/// request. The extractor, content source and input params of the extraction
+/// policies may contain `{parameter}` placeholders. An input param which is
+/// only a placeholder is replaced by the JSON value of the parameter, so
+/// numbers stay numbers.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct NamespaceTemplate {
+    pub name: String,
+    pub description: String,
+    pub parameters: Vec<NamespaceTemplateParameter>,
+    pub extraction_policies: Vec<ExtractionPolicyRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListNamespaceTemplatesResponse {
+    pub templates: Vec<NamespaceTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InstantiateNamespaceTemplate {
+    pub namespace: String,
+    // Overrides of the default parameters of the template
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InstantiateNamespaceTemplateResponse {
+    pub namespace: String,
+    pub extraction_policies: Vec<String>,
+    pub index_names: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Text {
     pub text: String,
     #[serde(default)]
     pub labels: HashMap<String, String>,
+    // Client supplied id, has to be unique within the namespace
+    pub external_id: Option<String>,
+    // Seconds after which the embeddings of the text and of the content
+    // extracted from it are removed from the indexes
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct TextAddRequest {
     pub documents: Vec<Text>,
     pub sync: Option<bool>,
+    // Client supplied id of the request. A retried request with the same id
+    // is only applied once
+    pub request_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -169,6 +684,12 @@ pub struct RunExtractorsResponse {}
 pub struct EmbeddingSchema {
     pub dim: usize,
     pub distance: IndexDistance,
+    // Names of the additional vectors the extractor emits for every chunk
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub named_vectors: Vec<String>,
+    // Whether the extractor emits sparse embeddings, e.g. SPLADE or BM42
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sparse: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Display, ToSchema)]
@@ -217,6 +738,41 @@ impl TryFrom<indexify_coordinator::Extractor> for ExtractorDescription {
 pub struct Executor {
     pub id: String,
     pub extractors: Vec<ExtractorDescription>,
+    pub addr: String,
+    pub last_seen: u64,
+    /// Topology labels of the executor, e.g. its zone, region and hardware
+    /// class
+    pub labels: HashMap<String, String>,
+    /// Whether the executor is drained, no new tasks are assigned to it
+    pub draining: bool,
+    pub running_tasks: u64,
+}
+
+impl TryFrom<indexify_coordinator::Executor> for Executor {
+    type Error = anyhow::Error;
+
+    fn try_from(value: indexify_coordinator::Executor) -> Result<Self> {
+        let extractor = value
+            .extractor
+            .ok_or_else(|| anyhow!("executor {} has no extractor", value.id))?;
+        Ok(Self {
+            id: value.id,
+            extractors: vec![extractor.try_into()?],
+            addr: value.addr,
+            last_seen: value.last_seen,
+            labels: value.labels,
+            draining: value.draining,
+            running_tasks: value.running_tasks,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+pub struct ListExecutorsFilters {
+    /// Only the executors having all of these labels are listed, e.g.
+    /// `labels_eq=zone:us-east-1a,hardware_class:gpu`
+    #[serde(default, deserialize_with = "api_utils::deserialize_labels_eq_filter")]
+    pub labels_eq: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -232,29 +788,76 @@ pub struct ListExtractorsResponse {
 #[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
 pub struct TextAdditionResponse {}
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub enum IndexType {
+    #[serde(rename = "embedding")]
+    Embedding,
+    #[serde(rename = "keyword")]
+    Keyword,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Index {
     pub name: String,
-    pub embedding_schema: EmbeddingSchema,
+    pub index_type: IndexType,
+    // Keyword indexes don't have an embedding schema
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_schema: Option<EmbeddingSchema>,
 }
 
 impl TryFrom<indexify_coordinator::Index> for Index {
     type Error = anyhow::Error;
 
     fn try_from(value: indexify_coordinator::Index) -> Result<Self> {
+        if serde_json::from_str::<internal_api::KeywordIndexSchema>(&value.schema).is_ok() {
+            return Ok(Self {
+                name: value.name,
+                index_type: IndexType::Keyword,
+                embedding_schema: None,
+            });
+        }
         Ok(Self {
             name: value.name,
-            embedding_schema: serde_json::from_str(&value.schema).map_err(|e| {
+            index_type: IndexType::Embedding,
+            embedding_schema: Some(serde_json::from_str(&value.schema).map_err(|e| {
                 anyhow!(
                     "unable to create embedding schema from: {}, error: {}",
                     value.schema,
                     e.to_string()
                 )
-            })?,
+            })?),
         })
     }
 }
 
+/// Size of an index in the store holding it. Statistics the store doesn't
+/// report are left out.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IndexStats {
+    pub name: String,
+    pub index_type: IndexType,
+    pub vector_store: String,
+    pub num_vectors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dim: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shard_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segment_count: Option<u64>,
+    // Estimated from the size of the embeddings when the store doesn't report
+    // its memory usage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approximate_memory_bytes: Option<u64>,
+}
+
+/// Result of loading an index into memory ahead of the first searches.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IndexWarmUp {
+    pub name: String,
+    pub vector_store: String,
+    pub duration_ms: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ListIndexesResponse {
     pub indexes: Vec<Index>,
@@ -265,6 +868,359 @@ pub struct SearchRequest {
     pub index: String,
     pub query: String,
     pub k: Option<u64>,
+    // Only return results of content with one of these external ids, the
+    // filter is applied to the top k results
+    #[serde(default)]
+    pub external_ids: Vec<String>,
+    pub recency_boost: Option<RecencyBoost>,
+    pub geo_filter: Option<GeoFilter>,
+    // Maximum number of results extracted from the same content, so that the
+    // chunks of a single document can't fill the top k
+    pub max_per_parent: Option<u32>,
+    // Filter expression over the labels, extracted metadata and text of the
+    // results, e.g. `labels.topic = news AND NOT text ~ "breaking news"`
+    pub filter: Option<String>,
+    // Collapses duplicate results, such as the same paragraph found in
+    // several documents, into a single result
+    pub dedupe: Option<SearchDedupe>,
+    // Named vector of the index to search, the default vector of the chunks
+    // is searched if it isn't set
+    pub vector: Option<String>,
+    // Writes the embeddings buffered for the index and refreshes it before
+    // searching, so the results include every embedding written before the
+    // search. Slower than the default, which can miss the latest writes.
+    #[serde(default)]
+    pub read_your_writes: bool,
+}
+
+/// Results with identical text are collapsed into the highest scoring one,
+/// which lists the ids of the other content in `duplicate_content_ids`. With
+/// `similarity_threshold` set, results whose embeddings have at least this
+/// cosine similarity are collapsed as well.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct SearchDedupe {
+    pub similarity_threshold: Option<f32>,
+}
+
+/// Matches content whose extracted metadata has a location within a radius
+/// or a bounding box. Exactly one of `radius` and `bounding_box` has to be
+/// set.
+///
+/// The location is read from the metadata field `field`, or from the top
+/// level of the metadata if it isn't set. It can either be an object with
+/// `lat` and `lon` (or `latitude` and `longitude`) keys or a GeoJSON point.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GeoFilter {
+    pub field: Option<String>,
+    pub radius: Option<GeoRadius>,
+    pub bounding_box: Option<GeoBoundingBox>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GeoRadius {
+    pub lat: f64,
+    pub lon: f64,
+    pub radius_meters: f64,
+}
+
+/// A bounding box with `min_lon` greater than `max_lon` crosses the
+/// antimeridian.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GeoBoundingBox {
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+}
+
+/// Exports the metadata extracted in the namespace to Parquet files in the
+/// blob store, one per content source, optionally with the text of the
+/// content the metadata was extracted from.
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct ExportMetadataRequest {
+    // Only export the metadata of this content source
+    pub content_source: Option<String>,
+    #[serde(default)]
+    pub include_text: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MetadataExportFile {
+    pub content_source: String,
+    pub url: String,
+    pub num_rows: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExportMetadataResponse {
+    pub files: Vec<MetadataExportFile>,
+    pub created_at: u64,
+}
+
+/// A version of the metadata of a content, archived when the extraction
+/// policy which wrote it extracted metadata from the content again.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MetadataVersion {
+    pub metadata: ExtractedMetadata,
+    pub extraction_policy: String,
+    pub created_at: u64,
+    pub archived_at: Option<u64>,
+}
+
+impl From<metadata_storage::MetadataVersion> for MetadataVersion {
+    fn from(value: metadata_storage::MetadataVersion) -> Self {
+        Self {
+            extraction_policy: value.metadata.extraction_policy.clone(),
+            metadata: value.metadata.into(),
+            created_at: value.created_at,
+            archived_at: value.archived_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MetadataHistoryResponse {
+    pub versions: Vec<MetadataVersion>,
+}
+
+/// Metadata added to a content by hand, e.g. annotations or corrections of
+/// the extracted metadata. It's merged into the metadata previously added to
+/// the content as a JSON merge patch, fields set to null are removed.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateMetadataRequest {
+    pub metadata: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateMetadataResponse {
+    pub metadata: ExtractedMetadata,
+}
+
+/// Metadata produced outside of Indexify, e.g. by a legacy system, of the
+/// content with the id or the external id.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportedMetadata {
+    pub content_id: Option<String>,
+    pub external_id: Option<String>,
+    pub metadata: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportMetadataRequest {
+    pub records: Vec<ImportedMetadata>,
+}
+
+/// A record which wasn't imported, by its index in the request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RejectedMetadata {
+    pub index: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportMetadataResponse {
+    pub imported: usize,
+    pub rejected: Vec<RejectedMetadata>,
+}
+
+/// Consistency of a read, bounded reads are served from the state of the
+/// coordinator, which lags behind the leader by at most the staleness of the
+/// follower reads config. Linearizable reads reflect every write committed
+/// before the read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadConsistency {
+    #[default]
+    Bounded,
+    Linearizable,
+}
+
+impl From<ReadConsistency> for indexify_coordinator::ReadConsistency {
+    fn from(value: ReadConsistency) -> Self {
+        match value {
+            ReadConsistency::Bounded => indexify_coordinator::ReadConsistency::Bounded,
+            ReadConsistency::Linearizable => indexify_coordinator::ReadConsistency::Linearizable,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+pub struct ReadConsistencyParams {
+    #[serde(default)]
+    pub consistency: ReadConsistency,
+}
+
+/// Restricts metadata to the metadata written by an extractor, or by an
+/// extraction policy given by its name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+pub struct MetadataProvenance {
+    pub extractor: Option<String>,
+    pub extraction_policy: Option<String>,
+}
+
+/// A page of metadata, skipping `offset` rows and returning at most `limit`
+/// of the rest. All the metadata is returned if no limit is given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams, ToSchema)]
+pub struct MetadataPage {
+    #[serde(default)]
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+impl MetadataPage {
+    pub fn apply<T>(&self, rows: Vec<T>) -> Vec<T> {
+        rows.into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+/// Lists the metadata extracted from the content of a content source,
+/// optionally only metadata with a location matching the geo filter.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MetadataQueryRequest {
+    pub content_source: Option<String>,
+    pub geo_filter: Option<GeoFilter>,
+    #[serde(flatten)]
+    pub provenance: MetadataProvenance,
+    #[serde(flatten)]
+    pub page: MetadataPage,
+    // Predicates over fields of the metadata which all have to match,
+    // evaluated by the metadata store
+    #[serde(default)]
+    pub filters: Vec<MetadataFilter>,
+}
+
+/// A predicate over a field of the extracted metadata, e.g.
+/// `{"field": "author.name", "op": "eq", "value": "Jane"}`. Fields of nested
+/// objects are addressed with a dot separated path.
+///
+/// Ranges compare numbers with numbers and strings with strings, fields of
+/// another type don't match. `exists` matches metadata which has the field,
+/// even if it's null.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MetadataFilter {
+    pub field: String,
+    #[serde(flatten)]
+    pub predicate: MetadataPredicate,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "op", content = "value", rename_all = "snake_case")]
+pub enum MetadataPredicate {
+    Eq(serde_json::Value),
+    Gt(serde_json::Value),
+    Gte(serde_json::Value),
+    Lt(serde_json::Value),
+    Lte(serde_json::Value),
+    Exists,
+}
+
+impl MetadataFilter {
+    pub fn validate(&self) -> Result<()> {
+        if self.path().iter().any(|key| key.is_empty()) {
+            return Err(anyhow!("invalid metadata field \"{}\"", self.field));
+        }
+        match &self.predicate {
+            MetadataPredicate::Gt(value) |
+            MetadataPredicate::Gte(value) |
+            MetadataPredicate::Lt(value) |
+            MetadataPredicate::Lte(value)
+                if !value.is_number() && !value.is_string() =>
+            {
+                Err(anyhow!(
+                    "range filter of metadata field {} has to compare with a number or a string",
+                    self.field
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Keys of the nested objects leading to the field
+    pub fn path(&self) -> Vec<&str> {
+        self.field.split('.').collect()
+    }
+}
+
+impl MetadataPredicate {
+    /// SQL operator of the predicate
+    pub fn operator(&self) -> &'static str {
+        match self {
+            MetadataPredicate::Eq(_) => "=",
+            MetadataPredicate::Gt(_) => ">",
+            MetadataPredicate::Gte(_) => ">=",
+            MetadataPredicate::Lt(_) => "<",
+            MetadataPredicate::Lte(_) => "<=",
+            MetadataPredicate::Exists => "IS NOT NULL",
+        }
+    }
+}
+
+/// Request payload of the admin search, which runs the same query against an
+/// index in every namespace, or only in the given namespaces.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CrossNamespaceSearchRequest {
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    pub index: String,
+    pub query: String,
+    pub k: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NamespaceSearchResult {
+    pub namespace: String,
+    pub result: DocumentFragment,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NamespaceSearchError {
+    pub namespace: String,
+    pub message: String,
+}
+
+/// The top k results across all the searched namespaces. Namespaces which
+/// couldn't be searched are reported in `errors`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CrossNamespaceSearchResponse {
+    pub results: Vec<NamespaceSearchResult>,
+    pub errors: Vec<NamespaceSearchError>,
+}
+
+/// Runs several queries against one index, each returning its own top k
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchSearchRequest {
+    pub index: String,
+    pub queries: Vec<String>,
+    pub k: Option<u64>,
+}
+
+/// Results of one query of a batch search. If the query failed `results` is
+/// empty and `error` is set.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchSearchResult {
+    pub query: String,
+    pub results: Vec<DocumentFragment>,
+    pub error: Option<String>,
+}
+
+/// One result set per query, in the order of the queries in the request
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchSearchResponse {
+    pub results: Vec<BatchSearchResult>,
+}
+
+/// Ranks newer content higher by blending the similarity score with an
+/// exponential decay over the age of the content:
+/// `(1 - weight) * similarity + weight * 0.5 ^ (age / half_life_secs)`
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RecencyBoost {
+    // Age in seconds at which the recency score drops to half
+    pub half_life_secs: u64,
+    // Weight of the recency score between 0 and 1, defaults to 0.3
+    pub weight: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -298,8 +1254,23 @@ pub struct ListContentFilters {
         default
     )]
     pub parent_id: String,
+    #[serde(
+        deserialize_with = "api_utils::deserialize_none_to_empty_string",
+        default
+    )]
+    pub external_id: String,
     #[serde(default, deserialize_with = "api_utils::deserialize_labels_eq_filter")]
     pub labels_eq: Option<HashMap<String, String>>,
+    // Filter expression over the labels of the content, e.g.
+    // `labels.topic = news OR labels.topic = sports`
+    #[serde(
+        deserialize_with = "api_utils::deserialize_none_to_empty_string",
+        default
+    )]
+    pub filter: String,
+    // Include the text preview generated at ingestion with each content
+    #[serde(default)]
+    pub include_preview: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
@@ -311,6 +1282,10 @@ pub struct MetadataRequest {
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct MetadataResponse {
     pub metadata: Vec<ExtractedMetadata>,
+    // Number of rows matching the request, of which `metadata` is the
+    // requested page
+    #[serde(default)]
+    pub total: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
@@ -320,6 +1295,11 @@ pub struct DocumentFragment {
     pub mime_type: String,
     pub confidence_score: f32,
     pub labels: HashMap<String, String>,
+    pub external_id: String,
+    // Content with the same text collapsed into this result when the search
+    // removes duplicates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicate_content_ids: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
@@ -327,6 +1307,28 @@ pub struct IndexSearchResponse {
     pub results: Vec<DocumentFragment>,
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StreamingSearchRequest {
+    pub indexes: Vec<String>,
+    pub query: String,
+    pub k: Option<u64>,
+}
+
+/// Payload of the `result` events sent by the streaming search endpoint.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SearchResultEvent {
+    pub index: String,
+    pub result: DocumentFragment,
+}
+
+/// Payload of the `error` events sent by the streaming search endpoint when
+/// one of the indexes couldn't be searched.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SearchErrorEvent {
+    pub index: String,
+    pub message: String,
+}
+
 #[derive(Debug)]
 pub struct IndexifyAPIError {
     status_code: StatusCode,
@@ -375,6 +1377,13 @@ pub struct ContentMetadata {
     pub created_at: i64,
     pub source: String,
     pub size: u64,
+    pub external_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+    /// sha256 checksum of the bytes of the content, empty for content which
+    /// is read from its original location
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub hash: String,
 }
 
 impl From<indexify_coordinator::ContentMetadata> for ContentMetadata {
@@ -390,10 +1399,27 @@ impl From<indexify_coordinator::ContentMetadata> for ContentMetadata {
             created_at: value.created_at,
             source: value.source,
             size: value.size_bytes,
+            external_id: value.external_id,
+            preview: Some(value.preview).filter(|preview| !preview.is_empty()),
+            hash: value.hash,
         }
     }
 }
 
+/// How long a pre-signed download url of a content stays valid, an hour by
+/// default.
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+pub struct PresignedUrlParams {
+    pub expires_in_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PresignedUrlResponse {
+    pub url: String,
+    //  Unix timestamp in seconds after which the url is rejected
+    pub expires_at: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, EnumString, ToSchema)]
 pub enum FeatureType {
     #[serde(rename = "embedding")]
@@ -507,6 +1533,9 @@ pub struct BeginExtractedContentIngest {
     pub extraction_policy: String,
     pub extractor: String,
     pub index_tables: Vec<String>,
+    // Id of the executor's request, which is the same when it retries the
+    // ingestion of a task's output
+    pub request_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
@@ -560,6 +1589,39 @@ pub struct ListStateChangesResponse {
     pub state_changes: Vec<internal_api::StateChange>,
 }
 
+/// Filters of the state changes to watch, changes of all namespaces and of
+/// every type are sent if they're not set.
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+pub struct WatchStateChanges {
+    pub namespace: Option<String>,
+    // Comma separated types of the changes to watch, e.g.
+    // `NewContent,TaskCompleted`
+    pub change_types: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StateChangeEvent {
+    pub id: String,
+    pub object_id: String,
+    pub change_type: String,
+    pub created_at: u64,
+    // Not set for changes which don't belong to a namespace, e.g. executors
+    pub namespace: Option<String>,
+}
+
+impl From<indexify_coordinator::StateChangeEvent> for StateChangeEvent {
+    fn from(value: indexify_coordinator::StateChangeEvent) -> Self {
+        let change = value.change.unwrap_or_default();
+        Self {
+            id: change.id,
+            object_id: change.object_id,
+            change_type: change.change_type,
+            created_at: change.created_at,
+            namespace: (!value.namespace.is_empty()).then_some(value.namespace),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetStructuredDataSchemasResponse {
     pub schemas: Vec<internal_api::StructuredDataSchema>,
@@ -588,6 +1650,12 @@ pub struct RaftMetricsSnapshotResponse {
     pub vote: u64,
     pub last_log_index: u64,
     pub current_leader: u64,
+    pub last_applied_index: u64,
+    /// Only known by the leader
+    pub committed_index: Option<u64>,
+    pub pending_log_entries: u64,
+    /// Entries of the log each member is missing, only known by the leader
+    pub replication_lag: HashMap<u64, u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -605,6 +1673,23 @@ pub struct IngestRemoteFile {
     pub url: String,
     pub mime_type: String,
     pub labels: HashMap<String, String>,
+    pub external_id: Option<String>,
+    // Client supplied id of the request. A retried request with the same id
+    // is only applied once
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+pub struct UploadFileParams {
+    // Client supplied id of the uploaded file, has to be unique within the
+    // namespace
+    pub external_id: Option<String>,
+    // Seconds after which the embeddings of the file and of the content
+    // extracted from it are removed from the indexes
+    pub ttl_secs: Option<u64>,
+    // Client supplied id of the request. A retried request with the same id
+    // is only applied once
+    pub request_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -616,3 +1701,333 @@ pub struct IngestRemoteFileResponse {
 pub struct TaskAssignments {
     pub assignments: HashMap<String, String>,
 }
+
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+pub struct PlanExtractionPolicy {
+    pub task_latency_ms: Option<u64>,
+    pub executor_concurrency: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionPolicyPlanResponse {
+    pub extraction_policy: String,
+    pub extractor: String,
+    pub total_tasks: u64,
+    pub total_bytes: u64,
+    pub queued_tasks: u64,
+    pub executors: u64,
+    pub task_latency_ms: u64,
+    pub estimated_completion_ms: Option<u64>,
+    pub compute_time_ms: u64,
+    pub tasks_per_executor: HashMap<String, u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+pub struct DryRunExtractionPolicy {
+    // Number of matching content returned as a sample, 10 when not set
+    pub sample_size: Option<u64>,
+}
+
+/// Content a proposed extraction policy would be applied to if it were
+/// created. The sample holds the matching content which was created first.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionPolicyDryRunResponse {
+    pub extraction_policy: String,
+    pub matching_content: u64,
+    pub matching_bytes: u64,
+    pub sample: Vec<ContentMetadata>,
+}
+
+/// Request payload for migrating an index to a new extraction policy.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct CreateIndexMigration {
+    // Name of the extraction policy writing the new index
+    pub extraction_policy: String,
+    pub extractor: String,
+    pub input_params: Option<serde_json::Value>,
+    // Embedding output of the extractor to migrate to, only required if the
+    // extractor has more than one
+    pub output: Option<String>,
+    // Quantization of the new index
+    #[serde(default)]
+    pub quantization: Option<IndexQuantization>,
+    // Distance metric of the new index
+    #[serde(default)]
+    pub distance: Option<IndexDistance>,
+    // Backend specific tuning of the new index
+    #[serde(default)]
+    pub index_params: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct CompleteIndexMigration {
+    // Complete the migration before all content was extracted into the new index
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IndexMigrationResponse {
+    pub source_index: String,
+    pub target_index: String,
+    pub target_extraction_policy: String,
+    pub created_at: u64,
+    pub completed: bool,
+    pub total_content: u64,
+    pub migrated_content: u64,
+}
+
+impl From<indexify_coordinator::IndexMigrationResponse> for IndexMigrationResponse {
+    fn from(value: indexify_coordinator::IndexMigrationResponse) -> Self {
+        Self {
+            source_index: value.source_index,
+            target_index: value.target_index,
+            target_extraction_policy: value.target_extraction_policy,
+            created_at: value.created_at,
+            completed: value.completed,
+            total_content: value.total_content,
+            migrated_content: value.migrated_content,
+        }
+    }
+}
+
+/// Request payload for moving the vectors of an index into a new table with
+/// a different number of shards.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RebalanceIndex {
+    pub shard_count: u32,
+    // Number of vectors copied at a time, defaults to 100
+    pub batch_size: Option<usize>,
+    pub max_vectors_per_second: Option<u64>,
+}
+
+/// Where the vectors of a rebuilt index come from.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RebuildSource {
+    //  Copy the embeddings stored in the current table of the index
+    #[default]
+    StoredEmbeddings,
+    //  Run the extractor of the index's extraction policy on the content
+    // again, e.g. when the current table is corrupted or the vector store
+    // was switched
+    Extractor,
+}
+
+/// Request payload for rebuilding an index from scratch into a new table.
+/// Keyword indexes are always rebuilt from the text of the content.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RebuildIndex {
+    #[serde(default)]
+    pub source: RebuildSource,
+    //  Number of shards of the new table, defaults to the vector store's
+    // default
+    pub shard_count: Option<u32>,
+    // Number of content rebuilt at a time, defaults to 100
+    pub batch_size: Option<usize>,
+    pub max_vectors_per_second: Option<u64>,
+}
+
+/// Request payload for restoring an index from a snapshot into a new table.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RestoreIndex {
+    //  Url of the snapshot in the blob store
+    pub url: String,
+    //  Number of shards of the new table, defaults to the vector store's
+    // default
+    pub shard_count: Option<u32>,
+    // Number of content restored at a time, defaults to 100
+    pub batch_size: Option<usize>,
+    pub max_vectors_per_second: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IndexSnapshotResponse {
+    pub index: String,
+    pub url: String,
+    pub num_vectors: u64,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RebalanceJobResponse {
+    pub index: String,
+    pub source_table: String,
+    pub target_table: String,
+    pub shard_count: u32,
+    pub total_content: u64,
+    pub moved_vectors: u64,
+    pub completed: bool,
+    pub error: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub reextract: bool,
+}
+
+impl From<indexify_coordinator::RebalanceJob> for RebalanceJobResponse {
+    fn from(value: indexify_coordinator::RebalanceJob) -> Self {
+        Self {
+            index: value.index,
+            source_table: value.source_table,
+            target_table: value.target_table,
+            shard_count: value.shard_count,
+            total_content: value.total_content,
+            moved_vectors: value.moved_vectors,
+            completed: value.completed,
+            error: (!value.error.is_empty()).then_some(value.error),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+            reextract: value.reextract,
+        }
+    }
+}
+
+/// A saved search against an index. `{name}` placeholders in the query are
+/// filled in with the parameters passed when the template is run, falling
+/// back to the default `parameters` of the template. Results are only kept
+/// when their labels match all the `filters`.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct QueryTemplate {
+    pub name: String,
+    pub index: String,
+    pub query: String,
+    pub k: Option<u64>,
+    #[serde(default)]
+    pub filters: HashMap<String, String>,
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+    //  Set with the scoring plugin endpoints of the template
+    #[serde(default, skip_deserializing)]
+    pub scoring_plugin: Option<ScoringPlugin>,
+}
+
+/// A WASM module run on the results of a query template before they're
+/// returned, which can change their scores or drop them.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ScoringPlugin {
+    pub max_fuel: u64,
+    pub max_memory_bytes: u64,
+}
+
+impl From<internal_api::ScoringPlugin> for ScoringPlugin {
+    fn from(value: internal_api::ScoringPlugin) -> Self {
+        Self {
+            max_fuel: value.max_fuel,
+            max_memory_bytes: value.max_memory_bytes,
+        }
+    }
+}
+
+impl From<internal_api::QueryTemplate> for QueryTemplate {
+    fn from(value: internal_api::QueryTemplate) -> Self {
+        Self {
+            name: value.name,
+            index: value.index,
+            query: value.query,
+            k: value.k,
+            filters: value.filters,
+            parameters: value.parameters,
+            scoring_plugin: value.scoring_plugin.map(|plugin| plugin.into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListQueryTemplatesResponse {
+    pub query_templates: Vec<QueryTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RunQueryTemplate {
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+    pub k: Option<u64>,
+}
+
+/// A WASM module run on every file uploaded to the namespace before it's
+/// stored, which can reject the file or add labels to it. Plugins run in the
+/// order of their names.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct IngestionPlugin {
+    pub name: String,
+    pub max_fuel: u64,
+    pub max_memory_bytes: u64,
+}
+
+impl From<internal_api::IngestionPlugin> for IngestionPlugin {
+    fn from(value: internal_api::IngestionPlugin) -> Self {
+        Self {
+            name: value.name,
+            max_fuel: value.max_fuel,
+            max_memory_bytes: value.max_memory_bytes,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListIngestionPluginsResponse {
+    pub plugins: Vec<IngestionPlugin>,
+}
+
+#[derive(Debug, Serialize, Deserialize, IntoParams, ToSchema)]
+pub struct WasmPluginParams {
+    //  Maximum number of instructions executed per file
+    pub max_fuel: Option<u64>,
+    //  Maximum memory the module can use
+    pub max_memory_bytes: Option<u64>,
+}
+
+/// Processing target of an extraction policy: the share of content which has
+/// to be processed by the policy within `target_secs` of being ingested. A
+/// webhook is called when attainment drops below the threshold, and again
+/// once it recovers.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ProcessingSlaRequest {
+    pub target_secs: u64,
+    //  Share of content which has to meet the target, defaults to 0.95
+    pub attainment_threshold: Option<f64>,
+    //  Sliding window attainment is measured over, defaults to an hour
+    pub window_secs: Option<u64>,
+    pub webhook_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ProcessingSla {
+    pub extraction_policy: String,
+    pub target_secs: u64,
+    pub attainment_threshold: f64,
+    pub window_secs: u64,
+    pub webhook_url: Option<String>,
+}
+
+impl From<internal_api::ProcessingSla> for ProcessingSla {
+    fn from(value: internal_api::ProcessingSla) -> Self {
+        Self {
+            extraction_policy: value.extraction_policy,
+            target_secs: value.target_secs,
+            attainment_threshold: value.attainment_threshold,
+            window_secs: value.window_secs,
+            webhook_url: (!value.webhook_url.is_empty()).then_some(value.webhook_url),
+        }
+    }
+}
+
+/// Attainment of a processing sla over its window, counting the tasks whose
+/// results were written by the server answering the request.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ProcessingSlaStatus {
+    pub sla: ProcessingSla,
+    //  Contents processed by the policy in the window
+    pub processed: u64,
+    //  Contents processed within the target
+    pub within_target: u64,
+    //  Share of contents processed within the target, none if nothing was
+    // processed in the window
+    pub attainment: Option<f64>,
+    pub breached: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListProcessingSlasResponse {
+    pub slas: Vec<ProcessingSlaStatus>,
+}
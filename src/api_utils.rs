@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Range};
 
 use anyhow::{anyhow, Result};
 use serde::Deserialize;
@@ -123,6 +123,33 @@ pub fn parse_validate_label_raw(raw: &str) -> Result<(String, String)> {
     }
 }
 
+/// Parses the Range header of a request for a blob of the size. Headers
+/// which don't request a single byte range are ignored, the whole blob is
+/// returned for them. Errors if the range starts after the end of the blob.
+pub fn parse_byte_range(header: &str, size: u64) -> Result<Option<Range<u64>>> {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    let Some((start, end)) = spec.split_once('-') else {
+        return Ok(None);
+    };
+    if end.contains(',') {
+        return Ok(None);
+    }
+    let range = match (start.trim().parse::<u64>(), end.trim().parse::<u64>()) {
+        (Ok(start), Ok(end)) if start <= end => start..(end + 1).min(size),
+        (Ok(start), Err(_)) if end.trim().is_empty() => start..size,
+        (Err(_), Ok(suffix)) if start.trim().is_empty() && suffix > 0 => {
+            size.saturating_sub(suffix)..size
+        }
+        _ => return Ok(None),
+    };
+    if range.start >= size {
+        return Err(anyhow!("range {} not satisfiable for {} bytes", spec, size));
+    }
+    Ok(Some(range))
+}
+
 #[cfg(test)]
 mod test_label_validation {
     use super::*;
@@ -307,6 +334,9 @@ mod test_deserialize_labels_eq_filter {
         let expected_query: Query<ListContentFilters> = Query(ListContentFilters {
             source: "foo".to_string(),
             parent_id: "".to_string(),
+            external_id: "".to_string(),
+            filter: "".to_string(),
+            include_preview: false,
             labels_eq: Some({
                 let mut labels_eq = HashMap::new();
                 labels_eq.insert("key".to_string(), "value".to_string());
@@ -327,6 +357,9 @@ mod test_deserialize_labels_eq_filter {
         let expected_query: Query<ListContentFilters> = Query(ListContentFilters {
             source: "foo".to_string(),
             parent_id: "".to_string(),
+            external_id: "".to_string(),
+            filter: "".to_string(),
+            include_preview: false,
             labels_eq: Some({
                 let mut labels_eq = HashMap::new();
                 labels_eq.insert("key".to_string(), "".to_string());
@@ -358,6 +391,9 @@ mod test_deserialize_labels_eq_filter {
         let expected_query: Query<ListContentFilters> = Query(ListContentFilters {
             source: "foo".to_string(),
             parent_id: "".to_string(),
+            external_id: "".to_string(),
+            filter: "".to_string(),
+            include_preview: false,
             labels_eq: Some({
                 let mut labels_eq = HashMap::new();
                 labels_eq.insert("key".to_string(), "value".to_string());
@@ -379,6 +415,9 @@ mod test_deserialize_labels_eq_filter {
         let expected_query: Query<ListContentFilters> = Query(ListContentFilters {
             source: "foo".to_string(),
             parent_id: "".to_string(),
+            external_id: "".to_string(),
+            filter: "".to_string(),
+            include_preview: false,
             labels_eq: Some({
                 let mut labels_eq = HashMap::new();
                 labels_eq.insert("key".to_string(), "value".to_string());
@@ -394,6 +433,36 @@ mod test_deserialize_labels_eq_filter {
         assert_eq!(query.0, expected_query.0);
     }
 
+    /// 6. ?source=foo&include_preview=true
+    #[test]
+    fn test_include_preview() {
+        let expected_query: Query<ListContentFilters> = Query(ListContentFilters {
+            source: "foo".to_string(),
+            parent_id: "".to_string(),
+            external_id: "".to_string(),
+            filter: "".to_string(),
+            include_preview: true,
+            labels_eq: None,
+        });
+
+        let query_str: Uri = "http://example.com/path?source=foo&include_preview=true"
+            .parse()
+            .unwrap();
+        let query: Query<ListContentFilters> = Query::try_from_uri(&query_str).unwrap();
+        assert_eq!(query.0, expected_query.0);
+    }
+
+    /// 7. ?filter=labels.key%20%3D%20value%20OR%20labels.key2%20%3D%20value2
+    #[test]
+    fn test_filter_expression() {
+        let query_str: Uri = "http://example.com/path?filter=labels.key%20%3D%20value%20OR%20labels.key2%20%3D%20value2"
+            .parse()
+            .unwrap();
+        let query: Query<ListContentFilters> = Query::try_from_uri(&query_str).unwrap();
+        assert_eq!(query.filter, "labels.key = value OR labels.key2 = value2");
+        assert_eq!(query.labels_eq, None);
+    }
+
     /// INVALID - each of these throws error
     #[test]
     fn test_invalid() {
@@ -417,3 +486,35 @@ mod test_deserialize_labels_eq_filter {
         }
     }
 }
+
+#[cfg(test)]
+mod test_parse_byte_range {
+    use super::*;
+
+    #[test]
+    fn test_parse_byte_range() {
+        assert_eq!(parse_byte_range("bytes=0-9", 100).unwrap(), Some(0..10));
+        assert_eq!(
+            parse_byte_range("bytes=90-199", 100).unwrap(),
+            Some(90..100)
+        );
+        assert_eq!(parse_byte_range("bytes=10-", 100).unwrap(), Some(10..100));
+        assert_eq!(parse_byte_range("bytes=-10", 100).unwrap(), Some(90..100));
+        assert_eq!(parse_byte_range("bytes=-200", 100).unwrap(), Some(0..100));
+
+        //  Headers which aren't a single valid range are ignored
+        for header in [
+            "",
+            "items=0-9",
+            "bytes=9-0",
+            "bytes=0-9,20-29",
+            "bytes=a-b",
+            "bytes=-0",
+        ] {
+            assert_eq!(parse_byte_range(header, 100).unwrap(), None, "{}", header);
+        }
+
+        assert!(parse_byte_range("bytes=100-", 100).is_err());
+        assert!(parse_byte_range("bytes=100-199", 100).is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use bytes::Bytes;
+use moka::{future::Cache, policy::EvictionPolicy};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlobCacheConfig {
+    pub path: String,
+    pub max_size_bytes: u64,
+}
+
+/// The least recently read blobs of remote blob storage, kept on local disk.
+/// Blobs are cached by url and never refreshed, content blobs are stored
+/// under the hash of their bytes so the blob at a url doesn't change.
+pub struct BlobCache {
+    path: PathBuf,
+    //  Size in bytes of the cached blobs, by url
+    entries: Cache<String, u64>,
+}
+
+impl Debug for BlobCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlobCache")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl BlobCache {
+    pub fn new(config: &BlobCacheConfig) -> Result<Self> {
+        let path = PathBuf::from(&config.path);
+        //  Blobs cached before a restart aren't tracked, they're removed rather
+        // than left to take up space outside of the size of the cache
+        if path.exists() {
+            std::fs::remove_dir_all(&path)?;
+        }
+        std::fs::create_dir_all(&path)?;
+        let eviction_path = path.clone();
+        let entries = Cache::builder()
+            .max_capacity(config.max_size_bytes)
+            .weigher(|_url: &String, size_bytes: &u64| {
+                u32::try_from(*size_bytes).unwrap_or(u32::MAX)
+            })
+            .eviction_policy(EvictionPolicy::lru())
+            .eviction_listener(move |url, _, cause| {
+                //  A blob cached again replaces the file of the previous entry
+                if cause.was_evicted() {
+                    let _ = std::fs::remove_file(eviction_path.join(file_name(&url)));
+                }
+            })
+            .build();
+        Ok(Self { path, entries })
+    }
+
+    pub async fn get(&self, url: &str) -> Option<Bytes> {
+        self.entries.get(url).await?;
+        match tokio::fs::read(self.path.join(file_name(url))).await {
+            Ok(bytes) => Some(bytes.into()),
+            Err(_) => {
+                self.entries.invalidate(url).await;
+                None
+            }
+        }
+    }
+
+    //  The blob is written to a temporary file first, so a concurrent read of
+    // the same url never sees a partially written blob
+    pub async fn insert(&self, url: &str, bytes: &Bytes) -> Result<()> {
+        let path = self.path.join(file_name(url));
+        let tmp_path = self
+            .path
+            .join(format!("{}.{}.tmp", file_name(url), nanoid::nanoid!(8)));
+        tokio::fs::write(&tmp_path, bytes).await?;
+        tokio::fs::rename(&tmp_path, &path).await?;
+        self.entries
+            .insert(url.to_string(), bytes.len() as u64)
+            .await;
+        Ok(())
+    }
+
+    pub async fn remove(&self, url: &str) {
+        self.entries.invalidate(url).await;
+        let _ = tokio::fs::remove_file(self.path.join(file_name(url))).await;
+    }
+}
+
+fn file_name(url: &str) -> String {
+    format!("{:x}", Sha256::digest(url.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_blob_cache_evicts_least_recently_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = BlobCache::new(&BlobCacheConfig {
+            path: dir.path().join("cache").to_str().unwrap().to_string(),
+            max_size_bytes: 10,
+        })
+        .unwrap();
+        cache
+            .insert("s3://bucket/a", &Bytes::from("aaaa"))
+            .await
+            .unwrap();
+        cache
+            .insert("s3://bucket/b", &Bytes::from("bbbb"))
+            .await
+            .unwrap();
+        cache.entries.run_pending_tasks().await;
+        assert_eq!(cache.get("s3://bucket/a").await.unwrap(), "aaaa");
+        assert!(cache.get("s3://bucket/c").await.is_none());
+        cache.entries.run_pending_tasks().await;
+
+        cache
+            .insert("s3://bucket/c", &Bytes::from("cccc"))
+            .await
+            .unwrap();
+        cache.entries.run_pending_tasks().await;
+        assert!(cache.get("s3://bucket/b").await.is_none());
+        assert!(!cache.path.join(file_name("s3://bucket/b")).exists());
+        assert_eq!(cache.get("s3://bucket/a").await.unwrap(), "aaaa");
+        assert_eq!(cache.get("s3://bucket/c").await.unwrap(), "cccc");
+
+        cache.remove("s3://bucket/a").await;
+        assert!(cache.get("s3://bucket/a").await.is_none());
+    }
+}
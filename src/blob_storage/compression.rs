@@ -0,0 +1,157 @@
+use std::{io::Write, ops::Range};
+
+use anyhow::Result;
+use bytes::Bytes;
+use futures::{stream::BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+/// Suffix of the keys of blobs compressed with zstd. Whether a blob is
+/// compressed is recorded in its url, so content written before compression
+/// was enabled for its namespace is still read as is.
+pub const COMPRESSED_BLOB_SUFFIX: &str = ".zst";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlobCompressionConfig {
+    #[serde(default = "default_compression_level")]
+    pub level: i32,
+    //  Namespaces whose content is compressed, the content of all namespaces
+    // is compressed if none are listed
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+}
+
+fn default_compression_level() -> i32 {
+    zstd::DEFAULT_COMPRESSION_LEVEL
+}
+
+impl BlobCompressionConfig {
+    pub fn level(&self, namespace: &str) -> Option<i32> {
+        if self.namespaces.is_empty() || self.namespaces.iter().any(|ns| ns == namespace) {
+            Some(self.level)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn is_compressed(url: &str) -> bool {
+    url.ends_with(COMPRESSED_BLOB_SUFFIX)
+}
+
+pub fn compressed_key(key: &str) -> String {
+    format!("{}{}", key, COMPRESSED_BLOB_SUFFIX)
+}
+
+/// Compresses the chunks of the stream into a single zstd frame, yielding the
+/// compressed bytes as the encoder outputs them
+pub fn compress<'a>(
+    mut stream: BoxStream<'a, Result<Bytes>>,
+    level: i32,
+) -> BoxStream<'a, Result<Bytes>> {
+    Box::pin(async_stream::try_stream! {
+        let mut encoder = zstd::stream::write::Encoder::new(Vec::new(), level)?;
+        while let Some(chunk) = stream.next().await {
+            encoder.write_all(&chunk?)?;
+            let compressed = std::mem::take(encoder.get_mut());
+            if !compressed.is_empty() {
+                yield Bytes::from(compressed);
+            }
+        }
+        yield Bytes::from(encoder.finish()?);
+    })
+}
+
+pub fn decompress(
+    mut stream: BoxStream<'static, Result<Bytes>>,
+) -> BoxStream<'static, Result<Bytes>> {
+    Box::pin(async_stream::try_stream! {
+        let mut decoder = zstd::stream::write::Decoder::new(Vec::new())?;
+        while let Some(chunk) = stream.next().await {
+            decoder.write_all(&chunk?)?;
+            let decompressed = std::mem::take(decoder.get_mut());
+            if !decompressed.is_empty() {
+                yield Bytes::from(decompressed);
+            }
+        }
+        decoder.flush()?;
+        let decompressed = decoder.into_inner();
+        if !decompressed.is_empty() {
+            yield Bytes::from(decompressed);
+        }
+    })
+}
+
+//  Compressed blobs can't be read from an offset, the range is cut out of
+// the decompressed stream instead
+pub fn slice(
+    mut stream: BoxStream<'static, Result<Bytes>>,
+    range: Range<u64>,
+) -> BoxStream<'static, Result<Bytes>> {
+    Box::pin(async_stream::try_stream! {
+        let mut offset: u64 = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            let chunk_start = offset;
+            offset += chunk.len() as u64;
+            if offset <= range.start {
+                continue;
+            }
+            let start = range.start.saturating_sub(chunk_start) as usize;
+            let end = (range.end.min(offset) - chunk_start) as usize;
+            yield chunk.slice(start..end);
+            if offset >= range.end {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn collect(stream: BoxStream<'static, Result<Bytes>>) -> Vec<u8> {
+        stream.map(|chunk| chunk.unwrap().to_vec()).concat().await
+    }
+
+    #[tokio::test]
+    async fn test_compress_and_decompress() {
+        let text = "the quick brown fox jumps over the lazy dog ".repeat(1000);
+        let chunks = text
+            .as_bytes()
+            .chunks(1000)
+            .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+            .collect::<Vec<_>>();
+        let compressed = collect(compress(futures::stream::iter(chunks).boxed(), 3)).await;
+        assert!(compressed.len() < text.len() / 10);
+
+        //  Chunks smaller than a zstd block end in the middle of blocks
+        let compressed_chunks = || {
+            let chunks = compressed
+                .chunks(7)
+                .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+                .collect::<Vec<_>>();
+            futures::stream::iter(chunks).boxed()
+        };
+        let decompressed = collect(decompress(compressed_chunks())).await;
+        assert_eq!(decompressed, text.as_bytes());
+
+        let range = collect(slice(decompress(compressed_chunks()), 990..1010)).await;
+        assert_eq!(range, &text.as_bytes()[990..1010]);
+    }
+
+    #[test]
+    fn test_compression_level_of_namespace() {
+        let config = BlobCompressionConfig {
+            level: 5,
+            namespaces: vec!["docs".to_string()],
+        };
+        assert_eq!(config.level("docs"), Some(5));
+        assert_eq!(config.level("images"), None);
+        let config = BlobCompressionConfig {
+            level: 5,
+            namespaces: vec![],
+        };
+        assert_eq!(config.level("images"), Some(5));
+    }
+}
@@ -1,12 +1,15 @@
+use std::ops::Range;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::{stream::BoxStream, StreamExt};
-use object_store::{local::LocalFileSystem, ObjectStore};
+use object_store::{local::LocalFileSystem, GetOptions, ObjectStore};
 use tokio::{fs::File, io::AsyncWriteExt, sync::mpsc};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 use super::{
+    BlobMeta,
     BlobStoragePartWriter,
     BlobStorageReader,
     BlobStorageWriter,
@@ -28,6 +31,39 @@ impl DiskStorage {
         std::fs::create_dir_all(config.path.clone())?;
         Ok(Self { config })
     }
+
+    pub async fn list(&self, prefix: &str) -> Result<Vec<BlobMeta>> {
+        let root = std::path::PathBuf::from(&self.config.path);
+        let dir = root.join(prefix);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut blobs = Vec::new();
+        for entry in walkdir::WalkDir::new(&dir) {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            let key = entry
+                .path()
+                .strip_prefix(&root)?
+                .to_string_lossy()
+                .to_string();
+            let last_modified = metadata
+                .modified()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|modified| modified.as_secs())
+                .unwrap_or(0);
+            blobs.push(BlobMeta {
+                url: format!("file://{}/{}", self.config.path, key),
+                key,
+                size_bytes: metadata.len(),
+                last_modified,
+            });
+        }
+        Ok(blobs)
+    }
 }
 
 #[async_trait]
@@ -38,6 +74,7 @@ impl BlobStorageWriter for DiskStorage {
         data: impl futures::Stream<Item = Result<Bytes>> + Send + Unpin,
     ) -> Result<PutResult, anyhow::Error> {
         let path = format!("{}/{}", self.config.path, key);
+        create_parent_dir(&path).await?;
         let file = File::create(&path).await?;
         let mut file = tokio::io::BufWriter::with_capacity(BUFFER_SIZE, file);
         let mut stream = data;
@@ -61,8 +98,20 @@ impl BlobStorageWriter for DiskStorage {
         let path = key
             .strip_prefix("file://")
             .ok_or_else(|| anyhow::anyhow!("Invalid key format"))?;
-        std::fs::remove_file(path)?;
-        Ok(())
+        //  Deleting a blob which doesn't exist isn't an error, so deletes can
+        // be retried
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => Ok(result?),
+        }
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<String> {
+        let from = format!("{}/{}", self.config.path, from);
+        let to = format!("{}/{}", self.config.path, to);
+        create_parent_dir(&to).await?;
+        tokio::fs::rename(&from, &to).await?;
+        Ok(format!("file://{}", to))
     }
 }
 
@@ -70,6 +119,7 @@ impl BlobStorageWriter for DiskStorage {
 impl BlobStoragePartWriter for DiskStorage {
     async fn writer(&self, key: &str) -> Result<StoragePartWriter> {
         let path = format!("{}/{}", self.config.path, key);
+        create_parent_dir(&path).await?;
         let file = File::create(&path).await?;
         let file = tokio::io::BufWriter::with_capacity(BUFFER_SIZE, file);
         Ok(StoragePartWriter {
@@ -79,6 +129,15 @@ impl BlobStoragePartWriter for DiskStorage {
     }
 }
 
+//  Blobs are stored under the directories of their namespace and hash, which
+// are created with the first blob written to them
+async fn create_parent_dir(path: &str) -> Result<()> {
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    Ok(())
+}
+
 pub struct DiskFileReader {}
 impl DiskFileReader {
     pub fn new() -> Self {
@@ -107,6 +166,21 @@ impl BlobStorageReader for DiskFileReader {
         });
         Box::pin(UnboundedReceiverStream::new(rx))
     }
+
+    fn get_range(&self, file_path: &str, range: Range<u64>) -> BoxStream<Result<Bytes>> {
+        let file_path = file_path.trim_start_matches("file://").to_string();
+        Box::pin(async_stream::try_stream! {
+            let options = GetOptions {
+                range: Some(range.start as usize..range.end as usize),
+                ..Default::default()
+            };
+            let client = LocalFileSystem::new();
+            let mut stream = client.get_opts(&file_path.into(), options).await?.into_stream();
+            while let Some(chunk) = stream.next().await {
+                yield chunk?;
+            }
+        })
+    }
 }
 
 #[cfg(test)]
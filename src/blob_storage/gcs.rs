@@ -0,0 +1,160 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{stream::BoxStream, Stream, StreamExt, TryStreamExt};
+use object_store::{
+    gcp::{GoogleCloudStorage, GoogleCloudStorageBuilder},
+    GetOptions,
+    ObjectStore,
+};
+use tokio::{io::AsyncWriteExt, sync::mpsc};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use super::{
+    BlobMeta,
+    BlobStoragePartWriter,
+    BlobStorageReader,
+    BlobStorageWriter,
+    StoragePartWriter,
+};
+use crate::blob_storage::PutResult;
+
+pub struct GcsStorage {
+    bucket: String,
+    client: GoogleCloudStorage,
+}
+
+impl GcsStorage {
+    pub fn new(bucket: &str, client: GoogleCloudStorage) -> Self {
+        GcsStorage {
+            bucket: bucket.to_string(),
+            client,
+        }
+    }
+
+    pub async fn list(&self, prefix: &str) -> Result<Vec<BlobMeta>> {
+        let objects = self
+            .client
+            .list(Some(&prefix.into()))
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(objects
+            .into_iter()
+            .map(|object| BlobMeta {
+                key: object.location.to_string(),
+                url: format!("gs://{}/{}", self.bucket, object.location),
+                size_bytes: object.size as u64,
+                last_modified: object.last_modified.timestamp().max(0) as u64,
+            })
+            .collect())
+    }
+}
+
+//  Blobs are uploaded as multipart uploads, the parts written before a failed
+// part don't have to be uploaded again
+#[async_trait]
+impl BlobStorageWriter for GcsStorage {
+    async fn put(
+        &self,
+        key: &str,
+        mut data: impl Stream<Item = Result<Bytes>> + Send + Unpin,
+    ) -> Result<PutResult> {
+        let (_, mut writer) = self.client.put_multipart(&key.into()).await?;
+        let mut size_bytes: u64 = 0;
+        while let Some(chunk) = data.next().await {
+            let chunk = chunk?;
+            size_bytes += chunk.len() as u64;
+            writer.write_all(&chunk).await?;
+        }
+        writer.shutdown().await?;
+        Ok(PutResult {
+            url: format!("gs://{}/{}", self.bucket, key),
+            size_bytes,
+        })
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let _ = self
+            .client
+            .delete(&key.into())
+            .await
+            .map_err(|e| anyhow!("Failed to delete key: {}, error: {}", key, e.to_string()))?;
+        Ok(())
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<String> {
+        self.client
+            .rename(&from.into(), &to.into())
+            .await
+            .map_err(|e| anyhow!("Failed to rename key: {} to {}, error: {}", from, to, e))?;
+        Ok(format!("gs://{}/{}", self.bucket, to))
+    }
+}
+
+#[async_trait]
+impl BlobStoragePartWriter for GcsStorage {
+    async fn writer(&self, key: &str) -> Result<StoragePartWriter> {
+        let (_, writer) = self.client.put_multipart(&key.into()).await?;
+        Ok(StoragePartWriter {
+            writer,
+            url: format!("gs://{}/{}", self.bucket, key),
+        })
+    }
+}
+
+pub struct GcsFileReader {
+    client: Arc<dyn ObjectStore>,
+    key: String,
+}
+
+impl GcsFileReader {
+    pub fn new(bucket: &str, key: &str) -> Self {
+        let client = GoogleCloudStorageBuilder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .unwrap();
+        GcsFileReader {
+            client: Arc::new(client),
+            key: key.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl BlobStorageReader for GcsFileReader {
+    fn get(&self, _key: &str) -> BoxStream<Result<Bytes>> {
+        let client_clone = self.client.clone();
+        let (tx, rx) = mpsc::unbounded_channel();
+        let key = self.key.clone();
+        tokio::spawn(async move {
+            let mut stream = match client_clone.get(&key.into()).await {
+                Ok(result) => result.into_stream(),
+                Err(e) => {
+                    let _ = tx.send(Err(anyhow!("unable to read blob from gcs: {}", e)));
+                    return;
+                }
+            };
+            while let Some(chunk) = stream.next().await {
+                let _ = tx.send(chunk.map_err(|e| anyhow!("unable to read blob from gcs: {}", e)));
+            }
+        });
+        Box::pin(UnboundedReceiverStream::new(rx))
+    }
+
+    fn get_range(&self, _key: &str, range: Range<u64>) -> BoxStream<Result<Bytes>> {
+        let client = self.client.clone();
+        let key = self.key.clone();
+        Box::pin(async_stream::try_stream! {
+            let options = GetOptions {
+                range: Some(range.start as usize..range.end as usize),
+                ..Default::default()
+            };
+            let mut stream = client.get_opts(&key.into(), options).await?.into_stream();
+            while let Some(chunk) = stream.next().await {
+                yield chunk?;
+            }
+        })
+    }
+}
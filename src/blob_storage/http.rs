@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use anyhow::{anyhow, Result};
 use bytes::Bytes;
 use futures::{stream::BoxStream, StreamExt};
@@ -19,4 +21,21 @@ impl BlobStorageReader for HttpReader {
         };
         Box::pin(stream)
     }
+
+    fn get_range(&self, key: &str, range: Range<u64>) -> BoxStream<Result<Bytes>> {
+        let client = reqwest::Client::new();
+        let key = key.to_string();
+        let stream = async_stream::stream! {
+            let response = client
+                .get(key)
+                .header(reqwest::header::RANGE, format!("bytes={}-{}", range.start, range.end - 1))
+                .send()
+                .await?;
+            let mut stream = response.bytes_stream();
+            while let Some(chunk) = stream.next().await {
+                yield chunk.map_err(|e| anyhow!("Failed to read chunk: {}", e));
+            }
+        };
+        Box::pin(stream)
+    }
 }
@@ -1,26 +1,276 @@
 use std::{
+    borrow::Cow,
+    collections::HashMap,
     fmt::{self, Debug, Formatter},
+    ops::Range,
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use bytes::{Bytes, BytesMut};
 use futures::{stream::BoxStream, StreamExt};
-use object_store::aws::AmazonS3Builder;
+use object_store::{
+    aws::{AmazonS3Builder, AmazonS3ConfigKey, S3EncryptionConfigKey},
+    gcp::GoogleCloudStorageBuilder,
+    BackoffConfig,
+    ClientOptions,
+    RetryConfig,
+};
+use reqwest::header::{HeaderMap, HeaderValue};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tokio::io::AsyncWrite;
 
-use self::{disk::DiskFileReader, s3::S3FileReader};
+use self::{
+    cache::{BlobCache, BlobCacheConfig},
+    compression::{BlobCompressionConfig, COMPRESSED_BLOB_SUFFIX},
+    disk::DiskFileReader,
+    gcs::GcsFileReader,
+    s3::S3FileReader,
+};
 
+pub mod cache;
+pub mod compression;
 pub mod disk;
+pub mod gcs;
 pub mod http;
 pub mod s3;
 
+/// Longest a pre-signed url stays valid for, the limit of S3 on urls signed
+/// with SigV4
+pub const MAX_PRESIGNED_URL_EXPIRY: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The bytes read from a blob don't match the sha256 checksum recorded when
+/// the blob was written, e.g. because the blob was truncated or overwritten
+/// in blob storage.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("blob {url} is corrupted, its sha256 checksum is {actual} instead of {expected}")]
+pub struct CorruptedBlob {
+    pub url: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Passes the chunks of the blob through while computing their sha256
+/// checksum, ending the stream with a `CorruptedBlob` error if the checksum
+/// doesn't match. Blobs without a recorded checksum aren't verified.
+pub fn verify_checksum(
+    url: &str,
+    checksum: &str,
+    mut stream: BoxStream<'static, Result<Bytes>>,
+) -> BoxStream<'static, Result<Bytes>> {
+    if checksum.is_empty() {
+        return stream;
+    }
+    let url = url.to_string();
+    let checksum = checksum.to_string();
+    Box::pin(async_stream::try_stream! {
+        let mut hasher = Sha256::new();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            hasher.update(&chunk);
+            yield chunk;
+        }
+        let actual = format!("{:x}", hasher.finalize());
+        if actual != checksum {
+            Err::<(), _>(CorruptedBlob {
+                url,
+                expected: checksum,
+                actual,
+            })?;
+        }
+    })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct S3Config {
     pub bucket: String,
     pub region: String,
+    #[serde(default)]
+    pub encryption: Option<S3Encryption>,
+    #[serde(default)]
+    pub tiering: HashMap<String, Vec<S3Transition>>,
+    //  Blobs up to this size are uploaded with a single request, larger blobs
+    // with a multipart upload
+    #[serde(default = "default_multipart_threshold_bytes")]
+    pub multipart_threshold_bytes: usize,
+    #[serde(default)]
+    pub retry: S3RetryConfig,
+    //  Storage class new blobs are written with, S3 Standard if there's none
+    #[serde(default)]
+    pub storage_class: Option<S3StorageClass>,
+}
+
+fn default_multipart_threshold_bytes() -> usize {
+    8 * 1024 * 1024
+}
+
+/// Retries of failed requests to S3 with exponential backoff. Every part of
+/// a multipart upload is retried on its own, so a transient error doesn't
+/// fail the upload of a large blob.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3RetryConfig {
+    pub max_retries: usize,
+    pub init_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    //  Requests aren't retried once this long has passed since the first
+    // attempt, whatever the number of retries left
+    pub retry_timeout_secs: u64,
+}
+
+impl Default for S3RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 10,
+            init_backoff_ms: 100,
+            max_backoff_ms: 15_000,
+            retry_timeout_secs: 180,
+        }
+    }
+}
+
+impl From<&S3RetryConfig> for RetryConfig {
+    fn from(config: &S3RetryConfig) -> Self {
+        RetryConfig {
+            backoff: BackoffConfig {
+                init_backoff: Duration::from_millis(config.init_backoff_ms),
+                max_backoff: Duration::from_millis(config.max_backoff_ms),
+                base: 2.,
+            },
+            max_retries: config.max_retries,
+            retry_timeout: Duration::from_secs(config.retry_timeout_secs),
+        }
+    }
+}
+
+impl S3Config {
+    /// Lifecycle configuration of the bucket transitioning the content blobs
+    /// of each namespace with tiering rules to cheaper storage classes, in
+    /// the format of PutBucketLifecycleConfiguration
+    pub fn lifecycle_configuration(&self) -> Result<serde_json::Value> {
+        let mut namespaces = self.tiering.iter().collect::<Vec<_>>();
+        namespaces.sort_by_key(|(namespace, _)| namespace.as_str());
+        let mut rules = Vec::new();
+        for (namespace, transitions) in namespaces {
+            for transition in transitions {
+                if transition.after_days < transition.storage_class.min_days() {
+                    return Err(anyhow::anyhow!(
+                        "blobs of namespace {} can't be transitioned to {:?} before they're {} \
+                         days old",
+                        namespace,
+                        transition.storage_class,
+                        transition.storage_class.min_days()
+                    ));
+                }
+            }
+            rules.push(serde_json::json!({
+                "ID": format!("indexify-tiering-{}", namespace),
+                //  Content blobs are stored under their namespace, see
+                // content_addressed_key
+                "Filter": {"Prefix": format!("{}/", namespace)},
+                "Status": "Enabled",
+                "Transitions": transitions.iter().map(|transition| serde_json::json!({
+                    "Days": transition.after_days,
+                    "StorageClass": transition.storage_class,
+                })).collect::<Vec<_>>(),
+            }));
+        }
+        Ok(serde_json::json!({ "Rules": rules }))
+    }
+}
+
+/// Moves the blobs of a namespace to a cheaper storage class once they're
+/// `after_days` old. Metadata and indexes of the content stay where they are.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct S3Transition {
+    pub after_days: u32,
+    pub storage_class: S3StorageClass,
+}
+
+/// Storage classes blobs can be tiered to. Only classes S3 serves reads from
+/// without restoring the object first are supported, so tiered blobs are
+/// read like any other blob.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum S3StorageClass {
+    StandardIa,
+    OnezoneIa,
+    IntelligentTiering,
+    GlacierIr,
+}
+
+impl S3StorageClass {
+    fn as_str(&self) -> &'static str {
+        match self {
+            S3StorageClass::StandardIa => "STANDARD_IA",
+            S3StorageClass::OnezoneIa => "ONEZONE_IA",
+            S3StorageClass::IntelligentTiering => "INTELLIGENT_TIERING",
+            S3StorageClass::GlacierIr => "GLACIER_IR",
+        }
+    }
+
+    //  S3 rejects transitions to the infrequent access classes of objects
+    // younger than 30 days
+    fn min_days(&self) -> u32 {
+        match self {
+            S3StorageClass::StandardIa | S3StorageClass::OnezoneIa => 30,
+            S3StorageClass::IntelligentTiering | S3StorageClass::GlacierIr => 0,
+        }
+    }
+}
+
+/// Server side encryption of the blobs written to S3, with keys managed by
+/// S3 or with KMS keys. Namespaces can be encrypted with their own KMS key,
+/// blobs of other namespaces and blobs which don't belong to a namespace are
+/// encrypted with the default key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum S3Encryption {
+    SseS3,
+    SseKms {
+        kms_key_id: String,
+        #[serde(default)]
+        namespace_kms_key_ids: HashMap<String, String>,
+    },
+}
+
+impl S3Encryption {
+    /// The KMS key blobs of the namespace are encrypted with, None for keys
+    /// managed by S3
+    pub fn kms_key_id(&self, namespace: Option<&str>) -> Option<&str> {
+        match self {
+            S3Encryption::SseS3 => None,
+            S3Encryption::SseKms {
+                kms_key_id,
+                namespace_kms_key_ids,
+            } => Some(
+                namespace
+                    .and_then(|namespace| namespace_kms_key_ids.get(namespace))
+                    .unwrap_or(kms_key_id),
+            ),
+        }
+    }
+
+    fn apply(&self, builder: AmazonS3Builder, namespace: Option<&str>) -> AmazonS3Builder {
+        match self.kms_key_id(namespace) {
+            Some(kms_key_id) => builder.with_sse_kms_encryption(kms_key_id),
+            None => builder.with_config(
+                AmazonS3ConfigKey::Encryption(S3EncryptionConfigKey::ServerSideEncryption),
+                "AES256",
+            ),
+        }
+    }
+}
+
+//  Credentials are read from the environment, a service account key file
+// given by GOOGLE_APPLICATION_CREDENTIALS or, on GKE and GCE, the credentials
+// of the workload identity from the metadata server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GcsConfig {
+    pub bucket: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,7 +281,111 @@ pub struct DiskStorageConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlobStorageConfig {
     pub s3: Option<S3Config>,
+    #[serde(default)]
+    pub gcs: Option<GcsConfig>,
     pub disk: Option<DiskStorageConfig>,
+    #[serde(default)]
+    pub cache: Option<BlobCacheConfig>,
+    #[serde(default)]
+    pub compression: Option<BlobCompressionConfig>,
+    //  Blob storage of namespaces which don't store their blobs with the
+    // global configuration
+    #[serde(default)]
+    pub namespaces: HashMap<String, NamespaceBlobStorageConfig>,
+}
+
+impl BlobStorageConfig {
+    /// The region of each S3 bucket blobs are written to, so blobs of
+    /// namespaces stored in other regions can be read
+    pub fn s3_bucket_regions(&self) -> HashMap<String, String> {
+        let mut regions = HashMap::new();
+        if let Some(s3) = &self.s3 {
+            regions.insert(s3.bucket.clone(), s3.region.clone());
+        }
+        for namespace in self.namespaces.values() {
+            if let Some(s3) = &namespace.s3 {
+                regions.insert(s3.bucket.clone(), self.namespace_s3_region(s3).to_string());
+            }
+        }
+        regions
+    }
+
+    fn namespace_s3_region<'a>(&'a self, s3: &'a NamespaceS3Config) -> &'a str {
+        s3.region
+            .as_deref()
+            .or(self.s3.as_ref().map(|s3| s3.region.as_str()))
+            .unwrap_or("us-east-1")
+    }
+}
+
+/// Blob storage of a namespace overriding the global configuration, e.g. a
+/// bucket in the region the data of a tenant has to stay in. Only blobs
+/// written after the namespace is configured are stored in it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NamespaceBlobStorageConfig {
+    #[serde(default)]
+    pub s3: Option<NamespaceS3Config>,
+    #[serde(default)]
+    pub gcs: Option<GcsConfig>,
+}
+
+//  Encryption, retries and the multipart threshold are those of the global
+// S3 configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceS3Config {
+    pub bucket: String,
+    //  Defaults to the region of the global S3 configuration
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub storage_class: Option<S3StorageClass>,
+}
+
+/// Key of a blob of the namespace. The blobs of a namespace are all stored
+/// under its prefix, so they can be listed, tiered or removed together.
+pub fn namespace_key(namespace: &str, key: &str) -> String {
+    format!("{}/{}", namespace, key)
+}
+
+/// Key of the blob of content with the sha256 hash. Content with the same
+/// bytes shares the blob, and the blob can be verified against its key. The
+/// two levels of directories keep any of them from holding too many blobs.
+pub fn content_addressed_key(namespace: &str, hash: &str) -> String {
+    namespace_key(
+        namespace,
+        &format!("{}/{}/{}", &hash[..2], &hash[2..4], hash),
+    )
+}
+
+/// A blob store of the blob storage configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum BlobBackend {
+    S3,
+    Gcs,
+    Disk,
+}
+
+impl BlobBackend {
+    /// Returns the backend storing the blob at the url. Blobs at other urls,
+    /// e.g. content ingested from http urls, aren't stored in a backend.
+    pub fn of_url(url: &str) -> Option<Self> {
+        match url.split_once("://") {
+            Some(("s3", _)) => Some(BlobBackend::S3),
+            Some(("gs", _)) => Some(BlobBackend::Gcs),
+            Some(("file", _)) => Some(BlobBackend::Disk),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the sha256 hash of the content stored at a key of the namespace,
+/// if the key is one `content_addressed_key` derives from a hash
+pub fn content_addressed_hash<'a>(namespace: &str, key: &'a str) -> Option<&'a str> {
+    let key = key.strip_suffix(COMPRESSED_BLOB_SUFFIX).unwrap_or(key);
+    let hash = key.rsplit('/').next()?;
+    let is_hash = hash.len() == 64 && hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+    (is_hash && content_addressed_key(namespace, hash) == key).then_some(hash)
 }
 
 #[derive(Debug)]
@@ -40,6 +394,16 @@ pub struct PutResult {
     pub size_bytes: u64,
 }
 
+/// A blob listed in blob storage
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlobMeta {
+    pub key: String,
+    pub url: String,
+    pub size_bytes: u64,
+    //  Seconds since the epoch
+    pub last_modified: u64,
+}
+
 #[async_trait]
 pub trait BlobStorageWriter {
     async fn put(
@@ -48,6 +412,9 @@ pub trait BlobStorageWriter {
         data: impl futures::Stream<Item = Result<Bytes>> + Send + Unpin,
     ) -> Result<PutResult, anyhow::Error>;
     async fn delete(&self, key: &str) -> Result<()>;
+    /// Moves the blob to another key, replacing the blob stored under it.
+    /// Returns the url of the moved blob.
+    async fn rename(&self, from: &str, to: &str) -> Result<String>;
 }
 
 #[async_trait]
@@ -59,6 +426,10 @@ type BlobStorageReaderTS = Arc<dyn BlobStorageReader + Sync + Send>;
 
 pub trait BlobStorageReader {
     fn get(&self, key: &str) -> BoxStream<Result<Bytes>>;
+
+    /// Streams the bytes of the range of the blob as they're read, without
+    /// reading ahead of the consumer of the stream
+    fn get_range(&self, key: &str, range: Range<u64>) -> BoxStream<Result<Bytes>>;
 }
 
 #[derive(Clone)]
@@ -93,21 +464,149 @@ impl BlobStorage {
         Self { config }
     }
 
-    fn s3_storage(&self, s3: &S3Config) -> Result<s3::S3Storage> {
+    //  Encryption is applied to every blob the storage writes, with the key
+    // of the namespace the blobs belong to
+    fn s3_storage(&self, s3: &S3Config, namespace: Option<&str>) -> Result<s3::S3Storage> {
+        let mut builder = AmazonS3Builder::from_env()
+            .with_region(s3.region.as_str())
+            .with_allow_http(true)
+            .with_bucket_name(s3.bucket.clone())
+            .with_retry(RetryConfig::from(&s3.retry));
+        if let Some(encryption) = &s3.encryption {
+            builder = encryption.apply(builder, namespace);
+        }
+        if let Some(storage_class) = s3.storage_class {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                "x-amz-storage-class",
+                HeaderValue::from_static(storage_class.as_str()),
+            );
+            builder =
+                builder.with_client_options(ClientOptions::new().with_default_headers(headers));
+        }
         Ok(s3::S3Storage::new(
             &s3.bucket,
-            AmazonS3Builder::from_env()
-                .with_region(s3.region.as_str())
-                .with_allow_http(true)
-                .with_bucket_name(s3.bucket.clone())
+            builder.build().context("unable to build S3 builder")?,
+            s3.multipart_threshold_bytes,
+        ))
+    }
+
+    fn gcs_storage(&self, gcs: &GcsConfig) -> Result<gcs::GcsStorage> {
+        Ok(gcs::GcsStorage::new(
+            &gcs.bucket,
+            GoogleCloudStorageBuilder::from_env()
+                .with_bucket_name(gcs.bucket.clone())
                 .build()
-                .context("unable to build S3 builder")?,
+                .context("unable to build GCS builder")?,
         ))
     }
 
-    pub async fn writer(&self, _namespace: &str, key: &str) -> Result<StoragePartWriter> {
-        if let Some(s3) = self.config.s3.as_ref() {
-            self.s3_storage(s3)?.writer(key).await
+    /// The S3 configuration blobs of the namespace are written with, the
+    /// bucket, region and storage class of a namespace stored in its own
+    /// bucket replace the global ones
+    fn s3_config(&self, namespace: Option<&str>) -> Option<Cow<'_, S3Config>> {
+        let Some(config) = self.namespace_config(namespace) else {
+            return self.config.s3.as_ref().map(Cow::Borrowed);
+        };
+        let s3 = config.s3.as_ref()?;
+        let mut s3_config = self.config.s3.clone().unwrap_or_else(|| S3Config {
+            bucket: s3.bucket.clone(),
+            region: "us-east-1".to_string(),
+            encryption: None,
+            tiering: HashMap::new(),
+            multipart_threshold_bytes: default_multipart_threshold_bytes(),
+            retry: S3RetryConfig::default(),
+            storage_class: None,
+        });
+        s3_config.region = self.config.namespace_s3_region(s3).to_string();
+        s3_config.bucket = s3.bucket.clone();
+        s3_config.storage_class = s3.storage_class;
+        Some(Cow::Owned(s3_config))
+    }
+
+    fn gcs_config(&self, namespace: Option<&str>) -> Option<&GcsConfig> {
+        match self.namespace_config(namespace) {
+            Some(config) => config.gcs.as_ref(),
+            None => self.config.gcs.as_ref(),
+        }
+    }
+
+    //  Namespaces configured without a bucket are stored with the global
+    // configuration
+    fn namespace_config(&self, namespace: Option<&str>) -> Option<&NamespaceBlobStorageConfig> {
+        self.config
+            .namespaces
+            .get(namespace?)
+            .filter(|config| config.s3.is_some() || config.gcs.is_some())
+    }
+
+    /// A reader of the blobs of the storage, without a cache
+    pub fn content_reader(&self) -> ContentReader {
+        ContentReader {
+            cache: None,
+            s3_regions: self.config.s3_bucket_regions(),
+        }
+    }
+
+    /// The zstd level the content of the namespace is compressed with, if
+    /// its content is compressed
+    pub fn compression_level(&self, namespace: &str) -> Option<i32> {
+        self.config
+            .compression
+            .as_ref()
+            .and_then(|compression| compression.level(namespace))
+    }
+
+    /// The backend new blobs are written to
+    pub fn backend(&self) -> BlobBackend {
+        self.backend_of(None)
+    }
+
+    /// The backend new blobs of the namespace are written to
+    pub fn namespace_backend(&self, namespace: &str) -> BlobBackend {
+        self.backend_of(Some(namespace))
+    }
+
+    fn backend_of(&self, namespace: Option<&str>) -> BlobBackend {
+        match (self.s3_config(namespace), self.gcs_config(namespace)) {
+            (Some(_), _) => BlobBackend::S3,
+            (None, Some(_)) => BlobBackend::Gcs,
+            (None, None) => BlobBackend::Disk,
+        }
+    }
+
+    /// Returns the url the blob with the key is stored at, which is known
+    /// before the blob is written.
+    pub fn url(&self, key: &str) -> String {
+        self.url_with(None, key)
+    }
+
+    /// Returns the url the blob of the namespace with the key is stored at
+    pub fn url_in_namespace(&self, namespace: &str, key: &str) -> String {
+        self.url_with(Some(namespace), key)
+    }
+
+    fn url_with(&self, namespace: Option<&str>, key: &str) -> String {
+        match (self.s3_config(namespace), self.gcs_config(namespace)) {
+            (Some(s3), _) => format!("s3://{}/{}", s3.bucket, key),
+            (None, Some(gcs)) => format!("gs://{}/{}", gcs.bucket, key),
+            (None, None) => {
+                let path = self
+                    .config
+                    .disk
+                    .as_ref()
+                    .map(|disk| disk.path.as_str())
+                    .unwrap_or("blobs");
+                format!("file://{}/{}", path, key)
+            }
+        }
+    }
+
+    pub async fn writer(&self, namespace: &str, key: &str) -> Result<StoragePartWriter> {
+        if let Some(s3) = self.s3_config(Some(namespace)) {
+            self.s3_storage(&s3, Some(namespace))?.writer(key).await
+        } else if let Some(gcs) = self.gcs_config(Some(namespace)) {
+            self.gcs_storage(gcs)?.writer(key).await
         } else {
             // If it's not S3, assume it's a file
 
@@ -120,21 +619,121 @@ impl BlobStorage {
             storage.writer(key).await
         }
     }
-}
 
-#[async_trait]
-impl BlobStorageWriter for BlobStorage {
-    async fn put(
+    /// Writes a blob of the namespace, encrypted with the key of the namespace
+    /// if the storage encrypts blobs
+    pub async fn put_in_namespace(
         &self,
+        namespace: &str,
         key: &str,
         data: impl futures::Stream<Item = Result<Bytes>> + Send + Unpin,
-    ) -> Result<PutResult, anyhow::Error> {
-        if let Some(s3) = self.config.s3.as_ref() {
-            self.s3_storage(s3)?.put(key, data).await
+    ) -> Result<PutResult> {
+        self.put_with(Some(namespace), key, data).await
+    }
+
+    /// Moves a blob of the namespace, the moved blob is encrypted with the key
+    /// of the namespace if the storage encrypts blobs
+    pub async fn rename_in_namespace(
+        &self,
+        namespace: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<String> {
+        self.rename_with(Some(namespace), from, to).await
+    }
+
+    /// Lists every blob stored under the prefix of the namespace
+    pub async fn list_namespace(&self, namespace: &str) -> Result<Vec<BlobMeta>> {
+        self.list_with(Some(namespace), &namespace_key(namespace, ""))
+            .await
+    }
+
+    /// Lists every blob stored under the prefix, which doesn't belong to a
+    /// namespace
+    pub async fn list(&self, prefix: &str) -> Result<Vec<BlobMeta>> {
+        self.list_with(None, prefix).await
+    }
+
+    async fn list_with(&self, namespace: Option<&str>, prefix: &str) -> Result<Vec<BlobMeta>> {
+        if let Some(s3) = self.s3_config(namespace) {
+            self.s3_storage(&s3, namespace)?.list(prefix).await
+        } else if let Some(gcs) = self.gcs_config(namespace) {
+            self.gcs_storage(gcs)?.list(prefix).await
         } else {
-            // If it's not S3, assume it's a file
+            disk::DiskStorage::new(
+                self.config
+                    .disk
+                    .clone()
+                    .unwrap_or_else(|| DiskStorageConfig {
+                        path: "blobs".to_string(),
+                    }),
+            )?
+            .list(prefix)
+            .await
+        }
+    }
 
+    /// Writes a blob of the namespace to the backend, which has to be
+    /// configured
+    pub async fn put_in_backend(
+        &self,
+        backend: BlobBackend,
+        namespace: &str,
+        key: &str,
+        data: impl futures::Stream<Item = Result<Bytes>> + Send + Unpin,
+    ) -> Result<PutResult> {
+        self.put_to(backend, Some(namespace), key, data).await
+    }
+
+    async fn put_with(
+        &self,
+        namespace: Option<&str>,
+        key: &str,
+        data: impl futures::Stream<Item = Result<Bytes>> + Send + Unpin,
+    ) -> Result<PutResult> {
+        self.put_to(self.backend_of(namespace), namespace, key, data)
+            .await
+    }
+
+    async fn put_to(
+        &self,
+        backend: BlobBackend,
+        namespace: Option<&str>,
+        key: &str,
+        data: impl futures::Stream<Item = Result<Bytes>> + Send + Unpin,
+    ) -> Result<PutResult> {
+        match backend {
+            BlobBackend::S3 => {
+                let s3 = self
+                    .s3_config(namespace)
+                    .ok_or_else(|| anyhow::anyhow!("s3 blob storage isn't configured"))?;
+                self.s3_storage(&s3, namespace)?.put(key, data).await
+            }
+            BlobBackend::Gcs => {
+                let gcs = self
+                    .gcs_config(namespace)
+                    .ok_or_else(|| anyhow::anyhow!("gcs blob storage isn't configured"))?;
+                self.gcs_storage(gcs)?.put(key, data).await
+            }
             // We need a default implementation for `DiskStorageConfig`
+            BlobBackend::Disk => {
+                disk::DiskStorage::new(self.config.disk.clone().unwrap_or_else(|| {
+                    DiskStorageConfig {
+                        path: "blobs".to_string(),
+                    }
+                }))?
+                .put(key, data)
+                .await
+            }
+        }
+    }
+
+    async fn rename_with(&self, namespace: Option<&str>, from: &str, to: &str) -> Result<String> {
+        if let Some(s3) = self.s3_config(namespace) {
+            self.s3_storage(&s3, namespace)?.rename(from, to).await
+        } else if let Some(gcs) = self.gcs_config(namespace) {
+            self.gcs_storage(gcs)?.rename(from, to).await
+        } else {
             disk::DiskStorage::new(
                 self.config
                     .disk
@@ -143,34 +742,59 @@ impl BlobStorageWriter for BlobStorage {
                         path: "blobs".to_string(),
                     }),
             )?
-            .put(key, data)
+            .rename(from, to)
             .await
         }
     }
+}
+
+#[async_trait]
+impl BlobStorageWriter for BlobStorage {
+    async fn put(
+        &self,
+        key: &str,
+        data: impl futures::Stream<Item = Result<Bytes>> + Send + Unpin,
+    ) -> Result<PutResult, anyhow::Error> {
+        self.put_with(None, key, data).await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<String> {
+        self.rename_with(None, from, to).await
+    }
 
     async fn delete(&self, key: &str) -> Result<()> {
         if key.starts_with("s3://") {
             let (bucket, key) = parse_s3_url(key)
                 .map_err(|err| anyhow::anyhow!("unable to parse s3 url: {}", err))?;
+            let region = self
+                .config
+                .s3_bucket_regions()
+                .remove(bucket)
+                .unwrap_or_else(|| "us-east-1".to_string());
             return s3::S3Storage::new(
-                key,
+                bucket,
                 AmazonS3Builder::from_env()
-                    .with_region(
-                        self.config
-                            .s3
-                            .as_ref()
-                            .map(|config| config.region.as_str())
-                            .unwrap_or("us-east-1"),
-                    )
+                    .with_region(region)
                     .with_bucket_name(bucket)
                     .build()
                     .context("unable to build S3 builder")?,
+                default_multipart_threshold_bytes(),
             )
             .delete(key)
             .await;
         }
+        if key.starts_with("gs://") {
+            let (bucket, key) = parse_gcs_url(key)
+                .map_err(|err| anyhow::anyhow!("unable to parse gcs url: {}", err))?;
+            return self
+                .gcs_storage(&GcsConfig {
+                    bucket: bucket.to_string(),
+                })?
+                .delete(key)
+                .await;
+        }
 
-        // If it's not S3, assume it's a file
+        // If it's not S3 or GCS, assume it's a file
 
         // We need a default implementation for `DiskStorageConfig`
         disk::DiskStorage::new(
@@ -198,8 +822,25 @@ fn parse_s3_url(s3_url: &str) -> Result<(&str, &str), &str> {
     Ok((bucket, key))
 }
 
+fn parse_gcs_url(gcs_url: &str) -> Result<(&str, &str), &str> {
+    let Some(("gs", url)) = gcs_url.split_once("://") else {
+        return Err("Invalid GCS URL format");
+    };
+
+    let Some((bucket, key)) = url.split_once('/') else {
+        return Err("Invalid GCS URL format");
+    };
+
+    Ok((bucket, key))
+}
+
 #[derive(Debug)]
-pub struct ContentReader {}
+pub struct ContentReader {
+    cache: Option<Arc<BlobCache>>,
+    //  Region of the S3 buckets of the blob storage configuration, blobs in
+    // other buckets are read from the region of the environment
+    s3_regions: HashMap<String, String>,
+}
 
 impl Default for ContentReader {
     fn default() -> Self {
@@ -209,7 +850,30 @@ impl Default for ContentReader {
 
 impl ContentReader {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            cache: None,
+            s3_regions: HashMap::new(),
+        }
+    }
+
+    /// A reader keeping the blobs it reads from S3 or GCS in the cache, so
+    /// content read by several extractors is only downloaded once
+    pub fn with_cache(cache: Arc<BlobCache>) -> Self {
+        Self {
+            cache: Some(cache),
+            s3_regions: HashMap::new(),
+        }
+    }
+
+    pub fn from_config(config: &BlobStorageConfig) -> Result<Self> {
+        let reader = match &config.cache {
+            Some(cache) => Self::with_cache(Arc::new(BlobCache::new(cache)?)),
+            None => Self::new(),
+        };
+        Ok(Self {
+            s3_regions: config.s3_bucket_regions(),
+            ..reader
+        })
     }
 
     pub fn get(&self, key: &str) -> BlobStorageReaderTS {
@@ -217,28 +881,127 @@ impl ContentReader {
             let (bucket, key) = parse_s3_url(key)
                 .map_err(|err| anyhow::anyhow!("unable to parse s3 url: {}", err))
                 .unwrap();
-            return Arc::new(S3FileReader::new(bucket, key));
+            return Arc::new(S3FileReader::new(
+                bucket,
+                key,
+                self.s3_regions.get(bucket).map(String::as_str),
+            ));
+        }
+
+        if key.starts_with("gs://") {
+            let (bucket, key) = parse_gcs_url(key)
+                .map_err(|err| anyhow::anyhow!("unable to parse gcs url: {}", err))
+                .unwrap();
+            return Arc::new(GcsFileReader::new(bucket, key));
         }
 
         if key.starts_with("http") {
             return Arc::new(http::HttpReader {});
         }
 
-        // If it's not S3, assume it's a file
+        // If it's not S3 or GCS, assume it's a file
         Arc::new(DiskFileReader::new())
     }
 
-    pub async fn bytes(&self, key: &str) -> Result<Bytes> {
-        let reader = self.get(key);
-        let mut stream = reader.get(key);
-        let mut bytes = BytesMut::new();
-        while let Some(chunk) = stream.next().await {
-            bytes.extend_from_slice(&chunk?);
+    /// Streams the bytes of the blob at the url, decompressed if the blob is
+    /// compressed
+    pub fn get_all(&self, url: &str) -> BoxStream<'static, Result<Bytes>> {
+        let stream = self.get_stored(url);
+        if compression::is_compressed(url) {
+            return compression::decompress(stream);
+        }
+        stream
+    }
+
+    /// Streams the bytes of the blob at the url as they're stored, compressed
+    /// if the blob is compressed
+    pub fn get_stored(&self, url: &str) -> BoxStream<'static, Result<Bytes>> {
+        let reader = self.get(url);
+        let url = url.to_string();
+        Box::pin(async_stream::try_stream! {
+            let mut stream = reader.get(&url);
+            while let Some(chunk) = stream.next().await {
+                yield chunk?;
+            }
+        })
+    }
+
+    /// Streams the bytes of the range of the blob at the url
+    pub fn get_range(&self, url: &str, range: Range<u64>) -> BoxStream<'static, Result<Bytes>> {
+        if compression::is_compressed(url) {
+            return compression::slice(self.get_all(url), range);
+        }
+        let reader = self.get(url);
+        let url = url.to_string();
+        Box::pin(async_stream::try_stream! {
+            let mut stream = reader.get_range(&url, range);
+            while let Some(chunk) = stream.next().await {
+                yield chunk?;
+            }
+        })
+    }
+
+    /// Signs a url the blob can be downloaded from directly until it expires.
+    /// Only blobs stored in S3 can be pre-signed.
+    pub async fn presigned_url(&self, url: &str, expires_in: Duration) -> Result<String> {
+        if expires_in.is_zero() || expires_in > MAX_PRESIGNED_URL_EXPIRY {
+            return Err(anyhow::anyhow!(
+                "pre-signed urls expire after 1 to {} seconds",
+                MAX_PRESIGNED_URL_EXPIRY.as_secs()
+            ));
+        }
+        if url.starts_with("s3://") {
+            let (bucket, key) = parse_s3_url(url)
+                .map_err(|err| anyhow::anyhow!("unable to parse s3 url: {}", err))?;
+            let region = self.s3_regions.get(bucket).map(String::as_str);
+            return s3::presigned_get_url(bucket, key, region, expires_in).await;
         }
-        Ok(bytes.into())
+        Err(anyhow::anyhow!(
+            "unable to pre-sign {}, pre-signed urls are only supported for blobs stored in S3",
+            url
+        ))
+    }
+
+    /// Reads the blob at the url, erroring with `CorruptedBlob` rather than
+    /// returning its bytes if they don't match the checksum
+    pub async fn verified_bytes(&self, url: &str, checksum: &str) -> Result<Bytes> {
+        let cache = self
+            .cache
+            .as_ref()
+            .filter(|_| url.starts_with("s3://") || url.starts_with("gs://"));
+        if let Some(cache) = cache {
+            if let Some(bytes) = cache.get(url).await {
+                //  Cached blobs are verified too, a blob corrupted on local disk
+                // is read again from blob storage
+                let cached = futures::stream::once(async { Ok(bytes) }).boxed();
+                match collect_bytes(verify_checksum(url, checksum, cached)).await {
+                    Ok(bytes) => return Ok(bytes),
+                    Err(_) => cache.remove(url).await,
+                }
+            }
+        }
+        let bytes = collect_bytes(verify_checksum(url, checksum, self.get_all(url))).await?;
+        if let Some(cache) = cache {
+            if let Err(e) = cache.insert(url, &bytes).await {
+                tracing::warn!("unable to cache blob {}: {}", url, e);
+            }
+        }
+        Ok(bytes)
+    }
+
+    pub async fn bytes(&self, key: &str) -> Result<Bytes> {
+        self.verified_bytes(key, "").await
     }
 }
 
+async fn collect_bytes(mut stream: BoxStream<'static, Result<Bytes>>) -> Result<Bytes> {
+    let mut bytes = BytesMut::new();
+    while let Some(chunk) = stream.next().await {
+        bytes.extend_from_slice(&chunk?);
+    }
+    Ok(bytes.into())
+}
+
 #[cfg(test)]
 mod tests {
     use core::pin::pin;
@@ -280,30 +1043,42 @@ mod tests {
             println!("localstack not configured skipping test");
             return;
         }
-        let data = vec![
-            Bytes::from("test_data_1"),
-            Bytes::from("test_data_2"),
-            Bytes::from("test_data_3"),
-        ];
-        let stream = stream::iter(data.into_iter().map(Ok));
-
-        let storage = BlobStorage::new_with_config(BlobStorageConfig {
-            s3: Some(S3Config {
-                bucket: "test-bucket".to_string(),
-                region: "us-east-1".to_string(),
-            }),
-            disk: None,
-        });
-        let result = storage.put("test-key-2", pin!(stream)).await;
+        //  Written with a single put, and with a multipart upload once the
+        // first chunk reaches the threshold
+        for multipart_threshold_bytes in [default_multipart_threshold_bytes(), 8] {
+            let data = vec![
+                Bytes::from("test_data_1"),
+                Bytes::from("test_data_2"),
+                Bytes::from("test_data_3"),
+            ];
+            let stream = stream::iter(data.into_iter().map(Ok));
 
-        assert!(result.is_ok());
+            let storage = BlobStorage::new_with_config(BlobStorageConfig {
+                s3: Some(S3Config {
+                    bucket: "test-bucket".to_string(),
+                    region: "us-east-1".to_string(),
+                    encryption: None,
+                    tiering: HashMap::new(),
+                    multipart_threshold_bytes,
+                    retry: S3RetryConfig::default(),
+                    storage_class: None,
+                }),
+                gcs: None,
+                disk: None,
+                cache: None,
+                compression: None,
+                namespaces: HashMap::new(),
+            });
+            let result = storage.put("test-key-2", pin!(stream)).await.unwrap();
+            assert_eq!(result.size_bytes, 33);
 
-        let read_client = test_client();
-        let path = object_store::path::Path::from("test-key-2");
-        let res = read_client.get(&path).await.unwrap().bytes().await.unwrap();
-        assert_eq!(res, "test_data_1test_data_2test_data_3");
+            let read_client = test_client();
+            let path = object_store::path::Path::from("test-key-2");
+            let res = read_client.get(&path).await.unwrap().bytes().await.unwrap();
+            assert_eq!(res, "test_data_1test_data_2test_data_3");
 
-        storage.delete("s3://test-bucket/test-key-2").await.unwrap();
+            storage.delete("s3://test-bucket/test-key-2").await.unwrap();
+        }
     }
 
     #[tokio::test]
@@ -327,8 +1102,17 @@ mod tests {
             s3: Some(S3Config {
                 bucket: "test-bucket".to_string(),
                 region: "us-east-1".to_string(),
+                encryption: None,
+                tiering: HashMap::new(),
+                multipart_threshold_bytes: default_multipart_threshold_bytes(),
+                retry: S3RetryConfig::default(),
+                storage_class: None,
             }),
+            gcs: None,
             disk: None,
+            cache: None,
+            compression: None,
+            namespaces: HashMap::new(),
         });
         let mut writer = storage
             .writer("test-namespace", "test-key-3")
@@ -346,4 +1130,420 @@ mod tests {
 
         storage.delete("s3://test-bucket/test-key-3").await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_delete_from_other_bucket() {
+        set_aws_env();
+
+        //  Blobs are deleted from the bucket in their url rather than the
+        // configured one. Make bucket 'other-bucket' as well
+        let client = AmazonS3Builder::from_env()
+            .with_bucket_name("other-bucket")
+            .with_region("us-east-1")
+            .build()
+            .unwrap();
+
+        let list_result = client.list(None).try_collect::<Vec<_>>().await;
+        if list_result.is_err() {
+            println!("localstack not configured skipping test");
+            return;
+        }
+        let path = object_store::path::Path::from("test-key-4");
+        client.put(&path, Bytes::from("test_data_1")).await.unwrap();
+
+        let storage = BlobStorage::new_with_config(BlobStorageConfig {
+            s3: Some(S3Config {
+                bucket: "test-bucket".to_string(),
+                region: "us-east-1".to_string(),
+                encryption: None,
+                tiering: HashMap::new(),
+                multipart_threshold_bytes: default_multipart_threshold_bytes(),
+                retry: S3RetryConfig::default(),
+                storage_class: None,
+            }),
+            gcs: None,
+            disk: None,
+            cache: None,
+            compression: None,
+            namespaces: HashMap::new(),
+        });
+        storage
+            .delete("s3://other-bucket/test-key-4")
+            .await
+            .unwrap();
+
+        assert!(client.head(&path).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_url_of_disk_blob() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            gcs: None,
+            disk: Some(DiskStorageConfig {
+                path: dir.path().to_str().unwrap().to_string(),
+            }),
+            cache: None,
+            compression: None,
+            namespaces: HashMap::new(),
+        });
+        let url = storage.url("test-key");
+        let data = stream::iter(vec![Ok(Bytes::from("test_data"))]);
+        let result = storage.put("test-key", pin!(data)).await.unwrap();
+        assert_eq!(result.url, url);
+
+        //  Deleting twice succeeds so abandoned uploads can be cleaned up
+        // by more than one server
+        storage.delete(&url).await.unwrap();
+        storage.delete(&url).await.unwrap();
+    }
+
+    #[test]
+    fn test_namespace_blob_storage() {
+        let storage = BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            gcs: None,
+            disk: Some(DiskStorageConfig {
+                path: "/tmp/blobs".to_string(),
+            }),
+            cache: None,
+            compression: None,
+            namespaces: HashMap::from([
+                (
+                    "eu".to_string(),
+                    NamespaceBlobStorageConfig {
+                        s3: Some(NamespaceS3Config {
+                            bucket: "eu-bucket".to_string(),
+                            region: Some("eu-west-1".to_string()),
+                            storage_class: Some(S3StorageClass::IntelligentTiering),
+                        }),
+                        gcs: None,
+                    },
+                ),
+                (
+                    "asia".to_string(),
+                    NamespaceBlobStorageConfig {
+                        s3: None,
+                        gcs: Some(GcsConfig {
+                            bucket: "asia-bucket".to_string(),
+                        }),
+                    },
+                ),
+            ]),
+        });
+        assert_eq!(storage.namespace_backend("eu"), BlobBackend::S3);
+        assert_eq!(
+            storage.url_in_namespace("eu", "eu/test-key"),
+            "s3://eu-bucket/eu/test-key"
+        );
+        let s3 = storage.s3_config(Some("eu")).unwrap();
+        assert_eq!(s3.region, "eu-west-1");
+        assert_eq!(s3.storage_class, Some(S3StorageClass::IntelligentTiering));
+        assert_eq!(storage.namespace_backend("asia"), BlobBackend::Gcs);
+        assert_eq!(
+            storage.url_in_namespace("asia", "asia/test-key"),
+            "gs://asia-bucket/asia/test-key"
+        );
+
+        //  Other namespaces are stored with the global configuration
+        assert_eq!(storage.namespace_backend("us"), BlobBackend::Disk);
+        assert_eq!(
+            storage.url_in_namespace("us", "us/test-key"),
+            "file:///tmp/blobs/us/test-key"
+        );
+        assert_eq!(
+            storage.config.s3_bucket_regions(),
+            HashMap::from([("eu-bucket".to_string(), "eu-west-1".to_string())])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_put_namespaced_disk_blob() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            gcs: None,
+            disk: Some(DiskStorageConfig {
+                path: dir.path().to_str().unwrap().to_string(),
+            }),
+            cache: None,
+            compression: None,
+            namespaces: HashMap::new(),
+        });
+
+        //  The directory of the namespace doesn't exist until the first blob
+        // of the namespace is written
+        let key = namespace_key("test-namespace", "test-key");
+        let data = stream::iter(vec![Ok(Bytes::from("test_data"))]);
+        storage.put(&key, pin!(data)).await.unwrap();
+        let path = dir.path().join("test-namespace").join("test-key");
+        assert_eq!(std::fs::read(&path).unwrap(), b"test_data");
+
+        let key = namespace_key("other-namespace", "test-key");
+        let mut writer = storage.writer("other-namespace", &key).await.unwrap();
+        writer.writer.write_all(b"test_data").await.unwrap();
+        writer.writer.shutdown().await.unwrap();
+        let path = dir.path().join("other-namespace").join("test-key");
+        assert_eq!(std::fs::read(&path).unwrap(), b"test_data");
+    }
+
+    #[tokio::test]
+    async fn test_rename_to_content_addressed_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            gcs: None,
+            disk: Some(DiskStorageConfig {
+                path: dir.path().to_str().unwrap().to_string(),
+            }),
+            cache: None,
+            compression: None,
+            namespaces: HashMap::new(),
+        });
+        let hash = "ab12cd34ef";
+        let key = content_addressed_key("default", hash);
+        assert_eq!(key, "default/ab/12/ab12cd34ef");
+
+        //  Content with the same bytes replaces the blob with the same bytes
+        for file_name in ["a.txt", "b.txt"] {
+            let data = stream::iter(vec![Ok(Bytes::from("test_data"))]);
+            storage.put(file_name, pin!(data)).await.unwrap();
+            let url = storage.rename(file_name, &key).await.unwrap();
+            assert_eq!(url, storage.url(&key));
+        }
+        let bytes = ContentReader::new()
+            .bytes(&storage.url(&key))
+            .await
+            .unwrap();
+        assert_eq!(bytes, "test_data");
+        assert!(!dir.path().join("a.txt").exists());
+        assert!(!dir.path().join("b.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_list_namespace() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            gcs: None,
+            disk: Some(DiskStorageConfig {
+                path: dir.path().to_str().unwrap().to_string(),
+            }),
+            cache: None,
+            compression: None,
+            namespaces: HashMap::new(),
+        });
+        let hash = format!("{:x}", Sha256::digest(b"test_data"));
+        let content_key = content_addressed_key("default", &hash);
+        for (namespace, key) in [
+            ("default", namespace_key("default", "a.txt")),
+            ("default", namespace_key("default", "plugin.wasm")),
+            ("other", namespace_key("other", "b.txt")),
+        ] {
+            let data = stream::iter(vec![Ok(Bytes::from("test_data"))]);
+            storage
+                .put_in_namespace(namespace, &key, pin!(data))
+                .await
+                .unwrap();
+        }
+        storage
+            .rename_in_namespace("default", "default/a.txt", &content_key)
+            .await
+            .unwrap();
+
+        let mut blobs = storage.list_namespace("default").await.unwrap();
+        blobs.sort_by(|a, b| a.key.cmp(&b.key));
+        let keys = blobs
+            .iter()
+            .map(|blob| blob.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![content_key.as_str(), "default/plugin.wasm"]);
+        assert_eq!(blobs[0].url, storage.url(&content_key));
+        assert_eq!(blobs[0].size_bytes, 9);
+        assert!(storage.list_namespace("missing").await.unwrap().is_empty());
+
+        assert_eq!(
+            content_addressed_hash("default", &content_key),
+            Some(hash.as_str())
+        );
+        assert_eq!(
+            content_addressed_hash("default", &compression::compressed_key(&content_key)),
+            Some(hash.as_str())
+        );
+        assert_eq!(content_addressed_hash("other", &content_key), None);
+        assert_eq!(
+            content_addressed_hash("default", "default/plugin.wasm"),
+            None
+        );
+        let misplaced = namespace_key("default", &format!("00/00/{}", hash));
+        assert_eq!(content_addressed_hash("default", &misplaced), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_range_of_disk_blob() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            gcs: None,
+            disk: Some(DiskStorageConfig {
+                path: dir.path().to_str().unwrap().to_string(),
+            }),
+            cache: None,
+            compression: None,
+            namespaces: HashMap::new(),
+        });
+        let data = stream::iter(vec![Ok(Bytes::from("0123456789"))]);
+        let result = storage.put("test-key", pin!(data)).await.unwrap();
+        let bytes = ContentReader::new()
+            .get_range(&result.url, 2..5)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .concat();
+        assert_eq!(bytes, b"234");
+    }
+
+    #[tokio::test]
+    async fn test_presigned_url() {
+        set_aws_env();
+        let reader = ContentReader::new();
+        let url = reader
+            .presigned_url("s3://test-bucket/test-key", Duration::from_secs(3600))
+            .await
+            .unwrap();
+        let url = url::Url::parse(&url).unwrap();
+        assert!(url.path().ends_with("/test-key"));
+        let query = url.query_pairs().collect::<HashMap<_, _>>();
+        assert_eq!(query["X-Amz-Expires"], "3600");
+        assert!(query.contains_key("X-Amz-Signature"));
+
+        assert!(reader
+            .presigned_url("s3://test-bucket/test-key", Duration::ZERO)
+            .await
+            .is_err());
+        assert!(reader
+            .presigned_url(
+                "s3://test-bucket/test-key",
+                MAX_PRESIGNED_URL_EXPIRY + Duration::from_secs(1)
+            )
+            .await
+            .is_err());
+        assert!(reader
+            .presigned_url("file:///tmp/test-key", Duration::from_secs(3600))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verified_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            gcs: None,
+            disk: Some(DiskStorageConfig {
+                path: dir.path().to_str().unwrap().to_string(),
+            }),
+            cache: None,
+            compression: None,
+            namespaces: HashMap::new(),
+        });
+        let data = stream::iter(vec![Ok(Bytes::from("test_data"))]);
+        let result = storage.put("test-key", pin!(data)).await.unwrap();
+        let checksum = format!("{:x}", Sha256::digest(b"test_data"));
+        let reader = ContentReader::new();
+        let bytes = reader.verified_bytes(&result.url, &checksum).await.unwrap();
+        assert_eq!(bytes, "test_data");
+        assert_eq!(reader.verified_bytes(&result.url, "").await.unwrap(), bytes);
+
+        std::fs::write(dir.path().join("test-key"), "test_dat").unwrap();
+        let err = reader
+            .verified_bytes(&result.url, &checksum)
+            .await
+            .unwrap_err();
+        let err = err.downcast_ref::<CorruptedBlob>().unwrap();
+        assert_eq!(err.expected, checksum);
+        assert_eq!(err.actual, format!("{:x}", Sha256::digest(b"test_dat")));
+    }
+
+    #[test]
+    fn test_url_of_gcs_blob() {
+        let storage = BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            gcs: Some(GcsConfig {
+                bucket: "test-bucket".to_string(),
+            }),
+            disk: None,
+            cache: None,
+            compression: None,
+            namespaces: HashMap::new(),
+        });
+        let url = storage.url("test-key");
+        assert_eq!(url, "gs://test-bucket/test-key");
+        assert_eq!(parse_gcs_url(&url).unwrap(), ("test-bucket", "test-key"));
+        assert!(parse_gcs_url("s3://test-bucket/test-key").is_err());
+        assert!(parse_gcs_url("gs://test-bucket").is_err());
+    }
+
+    #[test]
+    fn test_s3_lifecycle_configuration() {
+        let config: S3Config = serde_yaml::from_str(
+            "
+            bucket: test-bucket
+            region: us-east-1
+            tiering:
+              invoices:
+                - after_days: 30
+                  storage_class: STANDARD_IA
+                - after_days: 90
+                  storage_class: GLACIER_IR
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            config.lifecycle_configuration().unwrap(),
+            serde_json::json!({"Rules": [{
+                "ID": "indexify-tiering-invoices",
+                "Filter": {"Prefix": "invoices/"},
+                "Status": "Enabled",
+                "Transitions": [
+                    {"Days": 30, "StorageClass": "STANDARD_IA"},
+                    {"Days": 90, "StorageClass": "GLACIER_IR"},
+                ],
+            }]})
+        );
+
+        let mut config = config;
+        config.tiering.insert(
+            "logs".to_string(),
+            vec![S3Transition {
+                after_days: 7,
+                storage_class: S3StorageClass::OnezoneIa,
+            }],
+        );
+        assert!(config.lifecycle_configuration().is_err());
+    }
+
+    #[test]
+    fn test_s3_encryption() {
+        let encryption: S3Encryption = serde_yaml::from_str(
+            "
+            type: sse_kms
+            kms_key_id: default-key
+            namespace_kms_key_ids:
+              patients: patients-key
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            encryption.kms_key_id(Some("patients")),
+            Some("patients-key")
+        );
+        assert_eq!(encryption.kms_key_id(Some("default")), Some("default-key"));
+        assert_eq!(encryption.kms_key_id(None), Some("default-key"));
+
+        let encryption: S3Encryption = serde_yaml::from_str("type: sse_s3").unwrap();
+        assert_eq!(encryption, S3Encryption::SseS3);
+        assert_eq!(encryption.kms_key_id(Some("patients")), None);
+    }
 }
@@ -1,31 +1,95 @@
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc, time::Duration};
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use bytes::Bytes;
-use futures::{stream::BoxStream, Stream, StreamExt};
+use bytes::{Bytes, BytesMut};
+use futures::{stream::BoxStream, Stream, StreamExt, TryStreamExt};
 use object_store::{
     aws::{AmazonS3, AmazonS3Builder},
+    path::Path,
+    signer::Signer,
+    GetOptions,
     ObjectStore,
 };
 use tokio::{io::AsyncWriteExt, sync::mpsc};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
-use super::{BlobStoragePartWriter, BlobStorageReader, BlobStorageWriter, StoragePartWriter};
+use super::{
+    BlobMeta,
+    BlobStoragePartWriter,
+    BlobStorageReader,
+    BlobStorageWriter,
+    StoragePartWriter,
+};
 use crate::blob_storage::PutResult;
 
 pub struct S3Storage {
     bucket: String,
     client: AmazonS3,
+    multipart_threshold_bytes: usize,
 }
 
 impl S3Storage {
-    pub fn new(bucket: &str, client: AmazonS3) -> Self {
+    pub fn new(bucket: &str, client: AmazonS3, multipart_threshold_bytes: usize) -> Self {
         S3Storage {
             bucket: bucket.to_string(),
             client,
+            multipart_threshold_bytes,
         }
     }
+
+    pub async fn list(&self, prefix: &str) -> Result<Vec<BlobMeta>> {
+        let prefix: Path = prefix.into();
+        let objects = self
+            .client
+            .list(Some(&prefix))
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(objects
+            .into_iter()
+            .map(|object| BlobMeta {
+                key: object.location.to_string(),
+                url: format!("s3://{}/{}", self.bucket, object.location),
+                size_bytes: object.size as u64,
+                last_modified: object.last_modified.timestamp().max(0) as u64,
+            })
+            .collect())
+    }
+
+    //  Uploads the buffered start of the blob and the rest of the stream as
+    // parts, the upload is aborted on failure so its parts aren't left behind
+    // in the bucket
+    async fn put_multipart(
+        &self,
+        key: &str,
+        buffer: Bytes,
+        mut data: impl Stream<Item = Result<Bytes>> + Send + Unpin,
+    ) -> Result<u64> {
+        let path: Path = key.into();
+        let (multipart_id, mut writer) = self.client.put_multipart(&path).await?;
+        let result = async {
+            let mut size_bytes = buffer.len() as u64;
+            writer.write_all(&buffer).await?;
+            while let Some(chunk) = data.next().await {
+                let chunk = chunk?;
+                size_bytes += chunk.len() as u64;
+                writer.write_all(&chunk).await?;
+            }
+            writer.shutdown().await?;
+            Ok::<_, anyhow::Error>(size_bytes)
+        }
+        .await;
+        if result.is_err() {
+            if let Err(e) = self.client.abort_multipart(&path, &multipart_id).await {
+                tracing::warn!(
+                    "unable to abort multipart upload of key: {}, error: {}",
+                    key,
+                    e
+                );
+            }
+        }
+        result
+    }
 }
 
 #[async_trait]
@@ -35,14 +99,26 @@ impl BlobStorageWriter for S3Storage {
         key: &str,
         mut data: impl Stream<Item = Result<Bytes>> + Send + Unpin,
     ) -> Result<PutResult> {
-        let (_, mut writer) = self.client.put_multipart(&key.into()).await?;
-        let mut size_bytes: u64 = 0;
-        while let Some(chunk) = data.next().await {
-            let chunk = chunk?;
-            size_bytes += chunk.len() as u64;
-            writer.write_all(&chunk).await?;
+        //  Blobs smaller than the threshold are uploaded with a single put
+        // rather than the three requests of a multipart upload
+        let mut buffer = BytesMut::new();
+        let mut ended = false;
+        while buffer.len() < self.multipart_threshold_bytes {
+            match data.next().await {
+                Some(chunk) => buffer.extend_from_slice(&chunk?),
+                None => {
+                    ended = true;
+                    break;
+                }
+            }
         }
-        writer.shutdown().await?;
+        let size_bytes = if ended {
+            let size_bytes = buffer.len() as u64;
+            self.client.put(&key.into(), buffer.freeze()).await?;
+            size_bytes
+        } else {
+            self.put_multipart(key, buffer.freeze(), data).await?
+        };
         Ok(PutResult {
             url: format!("s3://{}/{}", self.bucket, key),
             size_bytes,
@@ -57,6 +133,14 @@ impl BlobStorageWriter for S3Storage {
             .map_err(|e| anyhow!("Failed to delete key: {}, error: {}", key, e.to_string()))?;
         Ok(())
     }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<String> {
+        self.client
+            .rename(&from.into(), &to.into())
+            .await
+            .map_err(|e| anyhow!("Failed to rename key: {} to {}, error: {}", from, to, e))?;
+        Ok(format!("s3://{}/{}", self.bucket, to))
+    }
 }
 
 #[async_trait]
@@ -76,11 +160,12 @@ pub struct S3FileReader {
 }
 
 impl S3FileReader {
-    pub fn new(bucket: &str, key: &str) -> Self {
-        let client = AmazonS3Builder::from_env()
-            .with_bucket_name(bucket)
-            .build()
-            .unwrap();
+    pub fn new(bucket: &str, key: &str, region: Option<&str>) -> Self {
+        let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+        if let Some(region) = region {
+            builder = builder.with_region(region);
+        }
+        let client = builder.build().unwrap();
         S3FileReader {
             client: Arc::new(client),
             key: key.to_string(),
@@ -88,6 +173,29 @@ impl S3FileReader {
     }
 }
 
+/// Signs a url the object can be downloaded from without credentials until
+/// it expires, with the credentials of the environment. The url is signed for
+/// the region of the environment if the region of the bucket isn't known.
+pub async fn presigned_get_url(
+    bucket: &str,
+    key: &str,
+    region: Option<&str>,
+    expires_in: Duration,
+) -> Result<String> {
+    let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+    if let Some(region) = region {
+        builder = builder.with_region(region);
+    }
+    let client = builder
+        .build()
+        .map_err(|e| anyhow!("unable to build S3 client: {}", e))?;
+    let url = client
+        .signed_url(reqwest::Method::GET, &key.into(), expires_in)
+        .await
+        .map_err(|e| anyhow!("Failed to sign url of key: {}, error: {}", key, e))?;
+    Ok(url.to_string())
+}
+
 #[async_trait]
 impl BlobStorageReader for S3FileReader {
     fn get(&self, _key: &str) -> BoxStream<Result<Bytes>> {
@@ -104,6 +212,21 @@ impl BlobStorageReader for S3FileReader {
         });
         Box::pin(UnboundedReceiverStream::new(rx))
     }
+
+    fn get_range(&self, _key: &str, range: Range<u64>) -> BoxStream<Result<Bytes>> {
+        let client = self.client.clone();
+        let key = self.key.clone();
+        Box::pin(async_stream::try_stream! {
+            let options = GetOptions {
+                range: Some(range.start as usize..range.end as usize),
+                ..Default::default()
+            };
+            let mut stream = client.get_opts(&key.into(), options).await?.into_stream();
+            while let Some(chunk) = stream.next().await {
+                yield chunk?;
+            }
+        })
+    }
 }
 
 #[cfg(test)]
@@ -139,7 +262,7 @@ mod tests {
             println!("localstack not configured skipping test");
             return;
         }
-        let storage = S3Storage::new("test-bucket", client);
+        let storage = S3Storage::new("test-bucket", client, 8 * 1024 * 1024);
         let data = vec![
             Bytes::from("test_data_1"),
             Bytes::from("test_data_2"),
@@ -0,0 +1,31 @@
+use clap::Args as ClapArgs;
+
+use super::GlobalArgs;
+use crate::server_config::ServerConfig;
+
+/// Prints the lifecycle configuration of the S3 bucket tiering the blobs of
+/// namespaces, to be applied with `aws s3api
+/// put-bucket-lifecycle-configuration`
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// path to the server config file
+    #[arg(long, short = 'c')]
+    config_path: String,
+}
+
+impl Args {
+    pub async fn run(self, _: GlobalArgs) {
+        let Self { config_path } = self;
+
+        let config = ServerConfig::from_path(&config_path)
+            .unwrap_or_else(|e| panic!("failed to load config file `{}`: {}", config_path, e));
+        let s3 = config
+            .blob_storage
+            .s3
+            .expect("blob storage isn't configured to use S3");
+        let lifecycle = s3
+            .lifecycle_configuration()
+            .unwrap_or_else(|e| panic!("invalid tiering rules: {}", e));
+        println!("{}", serde_json::to_string_pretty(&lifecycle).unwrap());
+    }
+}
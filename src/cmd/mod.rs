@@ -1,8 +1,10 @@
 use clap::{Args, Parser, Subcommand};
 
+mod blob_lifecycle;
 mod coordinator;
 mod init_compose;
 mod init_config;
+mod replay_state_changes;
 mod server;
 
 /// Global arguments for the CLI. These are arguments that are shared across all
@@ -28,6 +30,8 @@ pub enum Commands {
     Coordinator(coordinator::Args),
     InitConfig(init_config::Args),
     InitCompose(init_compose::Args),
+    BlobLifecycle(blob_lifecycle::Args),
+    ReplayStateChanges(replay_state_changes::Args),
 }
 
 /// The main CLI struct. This is the root of the CLI tree.
@@ -49,6 +53,8 @@ impl Cli {
             Commands::Coordinator(args) => args.run(self.global_args).await,
             Commands::InitConfig(args) => args.run(self.global_args).await,
             Commands::InitCompose(args) => args.run(self.global_args).await,
+            Commands::BlobLifecycle(args) => args.run(self.global_args).await,
+            Commands::ReplayStateChanges(args) => args.run(self.global_args).await,
         }
     }
 }
@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use clap::Args as ClapArgs;
+
+use super::GlobalArgs;
+use crate::state::store::replay::{BackupContents, StateChangeReplay};
+
+/// Replays the state change log of a backup of the state machine against a
+/// fresh state machine, printing every replayed entry as a line of json. The
+/// state is compared with the backup if the whole log is replayed.
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// path to the backup whose state change log is replayed
+    #[arg(long)]
+    backup: PathBuf,
+    /// path to an older backup the replay starts from, the replay starts
+    /// from an empty state if it's not set
+    #[arg(long)]
+    base_backup: Option<PathBuf>,
+    /// empty directory the state of the replay is written to
+    #[arg(long)]
+    path: PathBuf,
+    /// log index of the first entry replayed
+    #[arg(long, default_value_t = 0)]
+    from: u64,
+    /// log index of the last entry replayed, the log is replayed up to its
+    /// last entry if it's not set
+    #[arg(long)]
+    to: Option<u64>,
+}
+
+impl Args {
+    pub async fn run(self, _: GlobalArgs) {
+        let Self {
+            backup,
+            base_backup,
+            path,
+            from,
+            to,
+        } = self;
+
+        let bytes = std::fs::read(&backup)
+            .unwrap_or_else(|e| panic!("failed to read backup `{}`: {}", backup.display(), e));
+        let backup = BackupContents::decode(&bytes).expect("failed to decode backup");
+        let base_backup = base_backup.map(|base_backup| {
+            std::fs::read(&base_backup).unwrap_or_else(|e| {
+                panic!(
+                    "failed to read base backup `{}`: {}",
+                    base_backup.display(),
+                    e
+                )
+            })
+        });
+        let replay = StateChangeReplay::new(&path, base_backup.as_deref())
+            .await
+            .expect("failed to create the state machine of the replay");
+        let replayed = replay
+            .replay(backup.state_change_log, from..=to.unwrap_or(u64::MAX))
+            .await
+            .unwrap_or_else(|e| panic!("failed to replay the state change log: {}", e));
+        for entry in &replayed {
+            println!("{}", serde_json::to_string(entry).unwrap());
+        }
+
+        //  The columns of a backup are read after its last applied entry, so
+        // they can differ from the replayed state by the writes of the entries
+        // applied while the backup was taken
+        if replay.last_applied_index().await != backup.last_applied_index {
+            return;
+        }
+        let column_digests = replay
+            .column_digests()
+            .expect("failed to read the state of the replay");
+        for (column, digest) in &column_digests {
+            if backup.column_digests.get(column) != Some(digest) {
+                eprintln!("column {} differs from the backup", column);
+            }
+        }
+    }
+}
@@ -1,32 +1,74 @@
 use std::{
-    collections::{hash_map::DefaultHasher, HashMap, HashSet},
-    hash::{Hash, Hasher},
-    sync::Arc,
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use anyhow::{anyhow, Ok, Result};
 use indexify_internal_api as internal_api;
 use indexify_proto::indexify_coordinator;
-use internal_api::{GarbageCollectionTask, OutputSchema, StateChange, StructuredDataSchema};
+use internal_api::{
+    EmbeddingSchema,
+    GarbageCollectionTask,
+    OutputSchema,
+    SchemaInference,
+    StateChange,
+    StructuredDataSchema,
+};
 use jsonschema::JSONSchema;
-use tokio::sync::{broadcast, watch::Receiver};
-use tracing::info;
+use tokio::sync::{broadcast, mpsc, watch::Receiver};
+use tracing::{error, info, warn};
 
 use crate::{
+    blob_storage::BlobBackend,
     coordinator_client::CoordinatorClient,
     coordinator_filters::*,
+    filter_expression::FilterExpr,
     forwardable_coordinator::ForwardableCoordinator,
     garbage_collector::GarbageCollector,
-    scheduler::Scheduler,
-    state::{RaftMetrics, SharedState},
+    metrics::lifecycle_events,
+    scheduler::{
+        simulation::{self, ExecutorCapacity, SchedulePlan, SimulationInput, TaskLatencyStats},
+        Scheduler,
+    },
+    state::{forwardable_raft::NotLeaderError, RaftMetrics, SharedState},
     task_allocator::TaskAllocator,
+    utils::timestamp_secs,
 };
 
+//  A rebalancing job which hasn't reported progress for this long is assumed
+// to be abandoned, e.g. because the ingestion server running it restarted.
+const REBALANCE_JOB_TIMEOUT_SECS: u64 = 300;
+
+//  Number of state changes buffered for a watcher which reads them slower
+// than they're applied
+const STATE_CHANGE_WATCH_BUFFER: usize = 64;
+
+fn query_template_id(namespace: &str, name: &str) -> String {
+    internal_api::QueryTemplate {
+        namespace: namespace.to_string(),
+        name: name.to_string(),
+        ..Default::default()
+    }
+    .id()
+}
+
+pub struct IndexMigrationProgress {
+    pub migration: internal_api::IndexMigration,
+    //  Content matching the extraction policy writing the target index
+    pub total_content: usize,
+    //  Content the target extraction policy was already applied to
+    pub migrated_content: usize,
+}
+
 pub struct Coordinator {
     pub shared_state: SharedState,
     scheduler: Scheduler,
     garbage_collector: Arc<GarbageCollector>,
     forwardable_coordinator: ForwardableCoordinator,
+    task_latency_stats: TaskLatencyStats,
+    //  Executors sending their heartbeats to this coordinator
+    connected_executors: Mutex<HashSet<String>>,
 }
 
 impl Coordinator {
@@ -43,6 +85,8 @@ impl Coordinator {
             scheduler,
             garbage_collector,
             forwardable_coordinator,
+            task_latency_stats: TaskLatencyStats::default(),
+            connected_executors: Mutex::new(HashSet::new()),
         })
     }
 
@@ -51,10 +95,17 @@ impl Coordinator {
         namespace: &str,
         source: &str,
         parent_id: &str,
+        external_id: &str,
         labels_eq: &HashMap<String, String>,
+        filter: &str,
     ) -> Result<Vec<internal_api::ContentMetadata>> {
+        let filter = match filter {
+            "" => None,
+            filter => Some(FilterExpr::parse(filter)?.for_content_store()?),
+        };
         let content = self.shared_state.list_content(namespace).await?.into_iter();
-        list_content_filter(content, source, parent_id, labels_eq)
+        let content = list_content_filter(content, source, parent_id, external_id, labels_eq);
+        content_filter_expression(content, filter.as_ref())
             .map(Ok)
             .collect::<Result<Vec<internal_api::ContentMetadata>>>()
     }
@@ -72,6 +123,7 @@ impl Coordinator {
         executor_id: &str,
         outcome: internal_api::TaskOutcome,
         content_list: Vec<indexify_coordinator::ContentMetadata>,
+        request_id: Option<String>,
     ) -> Result<()> {
         info!(
             "updating task: {}, executor_id: {}, outcome: {:?}",
@@ -80,12 +132,65 @@ impl Coordinator {
         let mut task = self.shared_state.task_with_id(task_id).await?;
         let content_meta_list = content_request_to_content_metadata(content_list)?;
         task.outcome = outcome;
+        let created_content = content_meta_list.clone();
         self.shared_state
-            .update_task(task, Some(executor_id.to_string()), content_meta_list)
+            .update_task(
+                task,
+                Some(executor_id.to_string()),
+                content_meta_list,
+                request_id,
+            )
             .await?;
+
+        //  The state machine decides whether a failed task is retried as the
+        // update is applied
+        let task = self.shared_state.task_with_id(task_id).await?;
+        if task.terminal_state() {
+            self.task_latency_stats
+                .record_completion(&task.id, &task.extractor);
+        }
+        if task.outcome == internal_api::TaskOutcome::Failed {
+            lifecycle_events::task_failed(
+                &task.namespace,
+                &task.id,
+                &task.content_metadata.id,
+                &task.extractor,
+                &task.extraction_policy_id,
+            );
+        } else if task.retrying() {
+            info!(
+                "retrying task {} at {} after attempt {} failed",
+                task.id,
+                task.retry_at.unwrap_or_default(),
+                task.attempts
+            );
+        }
+        emit_content_created(&created_content);
         Ok(())
     }
 
+    /// Assigns the failed tasks whose backoff passed, returning the number of
+    /// assigned tasks
+    pub async fn assign_retried_tasks(&self) -> Result<usize> {
+        let now = timestamp_secs();
+        let tasks: Vec<_> = self
+            .shared_state
+            .unassigned_tasks()
+            .await?
+            .into_iter()
+            .filter(|task| task.retry_at.map_or(false, |retry_at| retry_at <= now))
+            .collect();
+        if tasks.is_empty() {
+            return Ok(0);
+        }
+        let allocation_plan = self.scheduler.allocate_tasks(tasks).await?;
+        let assigned = allocation_plan.0.len();
+        if assigned > 0 {
+            self.shared_state.assign_tasks(allocation_plan.0).await?;
+        }
+        Ok(assigned)
+    }
+
     pub async fn update_gc_task(
         &self,
         gc_task_id: &str,
@@ -98,6 +203,24 @@ impl Coordinator {
     }
 
     pub async fn create_namespace(&self, namespace: &str) -> Result<()> {
+        self.create_namespace_with_token(namespace, "").await
+    }
+
+    /// Creates the namespace unless the operation token was already applied.
+    /// An empty token disables the check.
+    pub async fn create_namespace_with_token(
+        &self,
+        namespace: &str,
+        operation_token: &str,
+    ) -> Result<()> {
+        let operation = internal_api::OperationKind::CreateNamespace;
+        if self
+            .applied_operation(operation_token, operation.clone(), namespace)
+            .await?
+            .is_some()
+        {
+            return Ok(());
+        }
         match self.shared_state.namespace(namespace).await {
             Result::Ok(Some(_)) => {
                 return Ok(());
@@ -105,10 +228,43 @@ impl Coordinator {
             Result::Ok(None) => {}
             Result::Err(_) => {}
         }
-        self.shared_state.create_namespace(namespace).await?;
+        self.shared_state
+            .create_namespace_with_operation(
+                namespace,
+                operation_record(operation_token, operation, namespace),
+            )
+            .await?;
         Ok(())
     }
 
+    /// Returns the record of an operation token which was already applied.
+    /// Reusing a token for a different operation is an error.
+    pub async fn applied_operation(
+        &self,
+        operation_token: &str,
+        operation: internal_api::OperationKind,
+        resource_id: &str,
+    ) -> Result<Option<internal_api::OperationRecord>> {
+        if operation_token.is_empty() {
+            return Ok(None);
+        }
+        match self
+            .shared_state
+            .get_operation_record(operation_token)
+            .await?
+        {
+            Some(record) if record.operation != operation || record.resource_id != resource_id => {
+                Err(anyhow!(
+                    "operation token {} was already used for {} of {}",
+                    operation_token,
+                    record.operation,
+                    record.resource_id
+                ))
+            }
+            record => Ok(record),
+        }
+    }
+
     pub async fn list_namespaces(&self) -> Result<Vec<internal_api::Namespace>> {
         self.shared_state.list_namespaces().await
     }
@@ -122,10 +278,17 @@ impl Coordinator {
     }
 
     pub async fn heartbeat(&self, executor_id: &str) -> Result<Vec<internal_api::Task>> {
+        self.connected_executors
+            .lock()
+            .unwrap()
+            .insert(executor_id.to_string());
         let tasks = self
             .shared_state
             .tasks_for_executor(executor_id, Some(10))
             .await?;
+        for task in &tasks {
+            self.task_latency_stats.record_dispatch(&task.id);
+        }
         Ok(tasks)
     }
 
@@ -147,1306 +310,4763 @@ impl Coordinator {
             .await
     }
 
+    /// Returns the existing content the proposed extraction policy would be
+    /// applied to if it were created, without creating the policy or any
+    /// tasks. The content is ordered by creation time.
+    pub async fn dry_run_policy(
+        &self,
+        extraction_policy: &internal_api::ExtractionPolicy,
+    ) -> Result<Vec<internal_api::ContentMetadata>> {
+        let extractor = self
+            .shared_state
+            .extractor_with_name(&extraction_policy.extractor)
+            .await?;
+        validate_input_params(extraction_policy, &extractor)?;
+        extraction_policy.content_filter.validate()?;
+        let mut content_list = self
+            .shared_state
+            .content_matching_extraction_policy(extraction_policy)
+            .await?;
+        content_list.retain(|content| extraction_policy.backfills_content(content));
+        content_list.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        Ok(content_list)
+    }
+
+    /// Simulates running an extraction policy over all the content it matches
+    /// which it hasn't been applied to yet, using the executors currently
+    /// registered for the policy's extractor.
+    pub async fn plan_extraction_policy(
+        &self,
+        namespace: &str,
+        policy_name: &str,
+        task_latency: Option<Duration>,
+        executor_concurrency: usize,
+    ) -> Result<(internal_api::ExtractionPolicy, SchedulePlan)> {
+        let policy = self
+            .extraction_policy_with_name(namespace, policy_name)
+            .await?;
+        let task_sizes = self
+            .shared_state
+            .content_matching_policy(&policy.id)
+            .await?
+            .into_iter()
+            .filter(|content| {
+                !content.extraction_policy_ids.contains_key(&policy.id) &&
+                    policy.backfills_content(content)
+            })
+            .map(|content| content.size_bytes)
+            .collect();
+        let running_task_count = self.shared_state.get_executor_running_task_count().await;
+        let executors = self
+            .shared_state
+            .get_executors_for_extractor(&policy.extractor)
+            .await?
+            .into_iter()
+            .map(|executor| ExecutorCapacity {
+                running_task_count: running_task_count
+                    .get(&executor.id)
+                    .cloned()
+                    .unwrap_or_default(),
+                executor_id: executor.id,
+            })
+            .collect();
+        let task_latency = task_latency
+            .or_else(|| self.task_latency_stats.average_latency(&policy.extractor))
+            .unwrap_or(simulation::DEFAULT_TASK_LATENCY);
+        let plan = simulation::simulate(SimulationInput {
+            task_sizes,
+            executors,
+            task_latency,
+            executor_concurrency,
+        });
+        Ok((policy, plan))
+    }
+
     pub async fn remove_executor(&self, executor_id: &str) -> Result<()> {
         info!("removing executor: {}", executor_id);
+        self.connected_executors.lock().unwrap().remove(executor_id);
         self.shared_state.remove_executor(executor_id).await?;
         Ok(())
     }
 
+    /// Stops assigning new tasks to the executor, the tasks already assigned
+    /// to it are left to finish. The executor takes new tasks again once it
+    /// registers again, e.g. after it was upgraded.
+    pub async fn drain_executor(&self, executor_id: &str) -> Result<()> {
+        info!("draining executor: {}", executor_id);
+        self.shared_state.get_executor_by_id(executor_id).await?;
+        self.shared_state.drain_executor(executor_id).await
+    }
+
+    pub async fn executor_running_task_count(&self) -> HashMap<String, usize> {
+        self.shared_state.get_executor_running_task_count().await
+    }
+
     pub async fn list_indexes(&self, namespace: &str) -> Result<Vec<internal_api::Index>> {
         self.shared_state.list_indexes(namespace).await
     }
 
     pub async fn get_index(&self, namespace: &str, name: &str) -> Result<internal_api::Index> {
-        let mut s = DefaultHasher::new();
-        namespace.hash(&mut s);
-        name.hash(&mut s);
-        let id = format!("{:x}", s.finish());
+        let id = internal_api::Index::index_id(namespace, name);
+        //  Once an index is migrated, lookups resolve to the index it was migrated to
+        if let Some(migration) = self.shared_state.get_index_migration(&id).await? {
+            if migration.completed {
+                let target_id = internal_api::Index::index_id(namespace, &migration.target_index);
+                return self.shared_state.get_index(&target_id).await;
+            }
+        }
         self.shared_state.get_index(&id).await
     }
 
     pub async fn create_index(&self, namespace: &str, index: internal_api::Index) -> Result<()> {
+        if let Result::Ok(schema) = serde_json::from_str::<EmbeddingSchema>(&index.schema) {
+            self.check_embedding_dimension(namespace, &index.name, &schema)
+                .await?;
+            self.check_index_quantization(namespace, &index.name, &schema)
+                .await?;
+        }
         let id = index.id();
         self.shared_state.create_index(namespace, index, id).await
     }
 
-    pub async fn get_extractor_coordinates(&self, extractor_name: &str) -> Result<Vec<String>> {
-        let executors = self
+    async fn existing_embedding_schema(
+        &self,
+        namespace: &str,
+        index_name: &str,
+    ) -> Result<Option<EmbeddingSchema>> {
+        let existing_index = self
             .shared_state
-            .get_executors_for_extractor(extractor_name)
-            .await?;
-        let addresses = executors
-            .iter()
-            .map(|e| e.addr.clone())
-            .collect::<Vec<String>>();
-        Ok(addresses)
+            .list_indexes(namespace)
+            .await?
+            .into_iter()
+            .find(|index| index.name == index_name);
+        match existing_index {
+            Some(index) => Ok(Some(serde_json::from_str(&index.schema)?)),
+            None => Ok(None),
+        }
     }
 
-    pub async fn register_executor(
+    //  Embeddings of different dimensions can't be stored in the same index, so
+    // an existing index can't be recreated with another dimension. The index
+    // has to be migrated to a new extraction policy instead.
+    async fn check_embedding_dimension(
         &self,
-        addr: &str,
-        executor_id: &str,
-        extractor: internal_api::ExtractorDescription,
+        namespace: &str,
+        index_name: &str,
+        schema: &EmbeddingSchema,
     ) -> Result<()> {
-        let _ = self
-            .shared_state
-            .register_executor(addr, executor_id, extractor)
-            .await;
+        if let Some(existing_schema) = self
+            .existing_embedding_schema(namespace, index_name)
+            .await?
+        {
+            if existing_schema.dim != schema.dim {
+                return Err(anyhow!(
+                    "index {} has embedding dimension {} but the extractor produces dimension {}, migrate the index to a new extraction policy instead",
+                    index_name,
+                    existing_schema.dim,
+                    schema.dim
+                ));
+            }
+        }
         Ok(())
     }
 
-    pub async fn register_ingestion_server(&self, ingestion_server_id: &str) -> Result<()> {
-        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
-            let leader_node_id = forward_to_leader
-                .leader_id
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node id"))?;
-            let leader_coord_addr = self
-                .shared_state
-                .get_coordinator_addr(leader_node_id)
-                .await?
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node coordinator address"))?;
-            self.forwardable_coordinator
-                .register_ingestion_server(&leader_coord_addr, ingestion_server_id)
-                .await?;
-            return Ok(());
+    //  Vector stores only apply the quantization when the index is created, so
+    // it can't be changed on an existing index either
+    async fn check_index_quantization(
+        &self,
+        namespace: &str,
+        index_name: &str,
+        schema: &EmbeddingSchema,
+    ) -> Result<()> {
+        if let Some(existing_schema) = self
+            .existing_embedding_schema(namespace, index_name)
+            .await?
+        {
+            if existing_schema.quantization != schema.quantization {
+                return Err(anyhow!(
+                    "index {} was created with quantization {:?}, migrate the index to a new extraction policy to change it",
+                    index_name,
+                    existing_schema.quantization
+                ));
+            }
         }
-        self.garbage_collector
-            .register_ingestion_server(ingestion_server_id)
-            .await;
         Ok(())
     }
 
-    pub async fn remove_ingestion_server(&self, ingestion_server_id: &str) -> Result<()> {
-        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
-            let leader_node_id = forward_to_leader
-                .leader_id
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node id"))?;
-            let leader_coord_addr = self
-                .shared_state
-                .get_coordinator_addr(leader_node_id)
-                .await?
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node coordinator address"))?;
-            self.forwardable_coordinator
-                .remove_ingestion_server(&leader_coord_addr, ingestion_server_id)
-                .await?;
-            return Ok(());
+    /// Starts migrating an index to a parallel index, usually written by a
+    /// new extraction policy with the same filters as the one writing the
+    /// source index.
+    pub async fn create_index_migration(
+        &self,
+        namespace: &str,
+        source_index: &str,
+        target_index: &str,
+    ) -> Result<IndexMigrationProgress> {
+        let source_id = internal_api::Index::index_id(namespace, source_index);
+        let _ = self.shared_state.get_index(&source_id).await?;
+        let target = self
+            .shared_state
+            .get_index(&internal_api::Index::index_id(namespace, target_index))
+            .await?;
+        if let Some(migration) = self.shared_state.get_index_migration(&source_id).await? {
+            if migration.completed {
+                return Err(anyhow!(
+                    "index {} was already migrated to {}",
+                    source_index,
+                    migration.target_index
+                ));
+            }
         }
-        self.garbage_collector
-            .remove_ingestion_server(ingestion_server_id)
-            .await;
-        Ok(())
+        let migration = internal_api::IndexMigration {
+            namespace: namespace.to_string(),
+            source_index: source_index.to_string(),
+            target_index: target_index.to_string(),
+            target_extraction_policy: target.extraction_policy,
+            created_at: timestamp_secs(),
+            completed: false,
+        };
+        self.shared_state
+            .set_index_migration(migration.clone())
+            .await?;
+        self.index_migration_progress(migration).await
     }
 
-    pub async fn get_content_metadata(
+    pub async fn get_index_migration(
         &self,
-        content_ids: Vec<String>,
-    ) -> Result<Vec<internal_api::ContentMetadata>> {
-        self.shared_state
-            .get_content_metadata_batch(content_ids)
-            .await
+        namespace: &str,
+        source_index: &str,
+    ) -> Result<IndexMigrationProgress> {
+        let migration = self
+            .shared_state
+            .get_index_migration(&internal_api::Index::index_id(namespace, source_index))
+            .await?
+            .ok_or_else(|| anyhow!("no migration found for index {}", source_index))?;
+        self.index_migration_progress(migration).await
     }
 
-    pub async fn get_content_tree_metadata(
+    /// Flips lookups of the source index over to the target index. Unless
+    /// forced, all the content matching the target extraction policy has to
+    /// have been extracted first.
+    pub async fn complete_index_migration(
         &self,
-        content_id: &str,
-    ) -> Result<Vec<internal_api::ContentMetadata>> {
+        namespace: &str,
+        source_index: &str,
+        force: bool,
+    ) -> Result<IndexMigrationProgress> {
+        let mut progress = self.get_index_migration(namespace, source_index).await?;
+        if !force && progress.migrated_content < progress.total_content {
+            return Err(anyhow!(
+                "index migration of {} is not finished, {} out of {} content migrated",
+                source_index,
+                progress.migrated_content,
+                progress.total_content
+            ));
+        }
+        progress.migration.completed = true;
         self.shared_state
-            .get_content_tree_metadata(content_id)
-            .await
+            .set_index_migration(progress.migration.clone())
+            .await?;
+        Ok(progress)
     }
 
-    pub async fn get_extractor(
+    async fn index_migration_progress(
         &self,
-        extractor_name: &str,
-    ) -> Result<internal_api::ExtractorDescription> {
-        self.shared_state.extractor_with_name(extractor_name).await
+        migration: internal_api::IndexMigration,
+    ) -> Result<IndexMigrationProgress> {
+        let policy = self
+            .extraction_policy_with_name(&migration.namespace, &migration.target_extraction_policy)
+            .await?;
+        //  Content the new policy doesn't backfill isn't migrated
+        let content = self
+            .shared_state
+            .content_matching_policy(&policy.id)
+            .await?
+            .into_iter()
+            .filter(|content| policy.backfills_content(content))
+            .collect::<Vec<_>>();
+        let migrated_content = content
+            .iter()
+            .filter(|content| {
+                content
+                    .extraction_policy_ids
+                    .get(&policy.id)
+                    .map_or(false, |completion_time| *completion_time > 0)
+            })
+            .count();
+        Ok(IndexMigrationProgress {
+            migration,
+            total_content: content.len(),
+            migrated_content,
+        })
     }
 
-    pub async fn create_policy(
+    /// Registers a job moving the vectors of an index to a new table. New
+    /// extraction results for the index are written to the target table
+    /// right away, the returned content has to be moved over from the source
+    /// table, or extracted again if `reextract` is set, by whoever runs the
+    /// job.
+    pub async fn create_rebalance_job(
         &self,
-        extraction_policy: internal_api::ExtractionPolicy,
-        extractor: internal_api::ExtractorDescription,
-    ) -> Result<()> {
-        if extractor.input_params != serde_json::Value::Null {
-            let input_params_schema =
-                JSONSchema::compile(&extractor.input_params).map_err(|e| {
-                    anyhow!(
-                        "unable to compile json schema for input params: {:?}, error: {:?}",
-                        &extractor.input_params,
-                        e
-                    )
-                })?;
-            let extractor_params_schema = extraction_policy.input_params.clone();
-            let validation_result = input_params_schema.validate(&extractor_params_schema);
-            if let Err(errors) = validation_result {
-                let errors = errors
-                    .into_iter()
-                    .map(|e| e.to_string())
-                    .collect::<Vec<String>>();
+        namespace: &str,
+        index_name: &str,
+        target_table: &str,
+        shard_count: u32,
+        reextract: bool,
+    ) -> Result<(internal_api::RebalanceJob, Vec<String>)> {
+        let id = internal_api::Index::index_id(namespace, index_name);
+        if let Some(job) = self.shared_state.get_rebalance_job(&id).await? {
+            let abandoned =
+                timestamp_secs().saturating_sub(job.updated_at) > REBALANCE_JOB_TIMEOUT_SECS;
+            if !job.completed && job.error.is_empty() && !abandoned {
                 return Err(anyhow!(
-                    "unable to validate input params for extractor policy: {}, errors: {}",
-                    &extraction_policy.name,
-                    errors.join(",")
+                    "index {} is already being rebalanced or rebuilt",
+                    index_name
                 ));
             }
         }
-        let structured_data_schema = self
-            .shared_state
-            .get_structured_data_schema(
-                &extraction_policy.namespace,
-                &extraction_policy.content_source,
-            )
+        let index = self.shared_state.get_index(&id).await?;
+        let mut policy = self
+            .extraction_policy_with_name(namespace, &index.extraction_policy)
             .await?;
-        let mut updated_schema = None;
-        for (_, output_schema) in extractor.outputs {
-            if let OutputSchema::Attributes(columns) = output_schema {
-                let updated_structured_data_schema = structured_data_schema.merge(columns)?;
-                updated_schema.replace(updated_structured_data_schema);
-            }
-        }
+        let content_ids = self
+            .shared_state
+            .content_matching_policy(&policy.id)
+            .await?
+            .into_iter()
+            .map(|content| content.id)
+            .collect::<Vec<String>>();
+        policy
+            .index_name_table_mapping
+            .insert(index.name.clone(), target_table.to_string());
+        let now = timestamp_secs();
+        let job = internal_api::RebalanceJob {
+            namespace: namespace.to_string(),
+            index: index.name,
+            source_table: index.table_name,
+            target_table: target_table.to_string(),
+            shard_count,
+            total_content: content_ids.len() as u64,
+            moved_vectors: 0,
+            completed: false,
+            error: "".to_string(),
+            created_at: now,
+            updated_at: now,
+            reextract,
+        };
         self.shared_state
-            .create_extraction_policy(extraction_policy, updated_schema)
+            .set_rebalance_job(job.clone(), None, Some(policy))
             .await?;
-        Ok(())
+        Ok((job, content_ids))
     }
 
-    pub async fn create_gc_tasks(&self, content_id: &str) -> Result<Vec<GarbageCollectionTask>> {
-        let content_tree_metadata = self
-            .shared_state
-            .get_content_tree_metadata(content_id)
-            .await?;
-        let mut output_tables = HashMap::new();
-
-        for content_metadata in &content_tree_metadata {
-            if content_metadata.extraction_policy_ids.keys().len() == 0 {
-                continue;
-            }
-            let applied_extraction_policy_ids: HashSet<String> = content_metadata
-                .extraction_policy_ids
-                .clone()
-                .into_iter()
-                .filter(|(_, completion_time)| *completion_time > 0)
-                .map(|(extraction_policy_id, _)| extraction_policy_id)
-                .collect();
+    pub async fn get_rebalance_job(
+        &self,
+        namespace: &str,
+        index_name: &str,
+    ) -> Result<internal_api::RebalanceJob> {
+        self.shared_state
+            .get_rebalance_job(&internal_api::Index::index_id(namespace, index_name))
+            .await?
+            .ok_or_else(|| anyhow!("no rebalancing job found for index {}", index_name))
+    }
 
-            if applied_extraction_policy_ids.is_empty() {
-                continue;
-            }
-            let applied_extraction_policies = self
+    /// Records the progress of a rebalancing job. Once completed, searches
+    /// on the index read from the target table.
+    pub async fn update_rebalance_job(
+        &self,
+        namespace: &str,
+        index_name: &str,
+        moved_vectors: u64,
+        completed: bool,
+        error: &str,
+    ) -> Result<internal_api::RebalanceJob> {
+        let mut job = self.get_rebalance_job(namespace, index_name).await?;
+        job.moved_vectors = moved_vectors;
+        job.error = error.to_string();
+        job.updated_at = timestamp_secs();
+        let mut index = None;
+        if completed {
+            let mut rebalanced_index = self
                 .shared_state
-                .get_extraction_policies_from_ids(applied_extraction_policy_ids)
+                .get_index(&internal_api::Index::index_id(namespace, index_name))
                 .await?;
-            for applied_extraction_policy in applied_extraction_policies.clone().unwrap() {
-                output_tables.insert(
-                    content_metadata.id.clone(),
-                    applied_extraction_policy
-                        .index_name_table_mapping
-                        .values()
-                        .cloned()
-                        .collect::<HashSet<_>>(),
-                );
-            }
+            rebalanced_index.table_name = job.target_table.clone();
+            index.replace(rebalanced_index);
+            job.completed = true;
         }
+        self.shared_state
+            .set_rebalance_job(job.clone(), index, None)
+            .await?;
+        Ok(job)
+    }
 
-        let tasks = self
-            .garbage_collector
-            .create_gc_tasks(content_tree_metadata, output_tables)
+    /// Returns the content whose extracted embeddings are written to the
+    /// index, the content matching the extraction policy of the index.
+    pub async fn list_index_content(
+        &self,
+        namespace: &str,
+        index_name: &str,
+    ) -> Result<Vec<internal_api::ContentMetadata>> {
+        let index = self
+            .shared_state
+            .get_index(&internal_api::Index::index_id(namespace, index_name))
             .await?;
-        self.shared_state.create_gc_tasks(tasks.clone()).await?;
-        Ok(tasks)
+        let policy = self
+            .extraction_policy_with_name(namespace, &index.extraction_policy)
+            .await?;
+        self.shared_state.content_matching_policy(&policy.id).await
     }
 
-    async fn handle_tombstone_content(
+    /// Creates or replaces a query template. The index it searches has to
+    /// exist.
+    pub async fn set_query_template(
         &self,
-        change: StateChange,
-    ) -> Result<Vec<GarbageCollectionTask>> {
-        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
-            let leader_id = forward_to_leader
-                .leader_id
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node id"))?;
-            let leader_coord_addr = self
-                .shared_state
-                .get_coordinator_addr(leader_id)
-                .await?
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node coordinator address"))?;
-            self.forwardable_coordinator
-                .create_gc_tasks(&leader_coord_addr, &change.object_id)
-                .await?;
-            return Ok(Vec::new());
-        }
+        query_template: internal_api::QueryTemplate,
+    ) -> Result<()> {
+        let _ = self
+            .get_index(&query_template.namespace, &query_template.index)
+            .await?;
+        self.shared_state.set_query_template(query_template).await
+    }
 
-        //  this coordinator node is the leader
-        self.create_gc_tasks(&change.object_id).await
+    pub async fn get_query_template(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<internal_api::QueryTemplate> {
+        self.shared_state
+            .get_query_template(&query_template_id(namespace, name))
+            .await?
+            .ok_or_else(|| anyhow!("query template {} not found", name))
     }
 
-    #[tracing::instrument(skip(self))]
-    pub async fn run_scheduler(&self) -> Result<()> {
-        let state_changes = self.shared_state.unprocessed_state_change_events().await?;
-        for change in state_changes {
-            info!(
-                "processing change event: {}, type: {}, id: {}",
-                change.id, change.change_type, change.object_id
-            );
+    pub async fn list_query_templates(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<internal_api::QueryTemplate>> {
+        self.shared_state.list_query_templates(namespace).await
+    }
 
-            match change.change_type {
-                indexify_internal_api::ChangeType::TombstoneContent => {
-                    let _ = self.handle_tombstone_content(change).await?;
-                }
-                _ => self.scheduler.handle_change_event(change).await?,
-            }
+    pub async fn delete_query_template(&self, namespace: &str, name: &str) -> Result<()> {
+        let query_template = self.get_query_template(namespace, name).await?;
+        self.shared_state
+            .delete_query_template(&query_template.id())
+            .await
+    }
+
+    /// Registers or replaces an ingestion plugin of an existing namespace.
+    pub async fn set_ingestion_plugin(&self, plugin: internal_api::IngestionPlugin) -> Result<()> {
+        if self.get_namespace(&plugin.namespace).await?.is_none() {
+            return Err(anyhow!("namespace {} not found", plugin.namespace));
         }
-        Ok(())
+        self.shared_state.set_ingestion_plugin(plugin).await
     }
 
-    pub async fn subscribe_to_gc_events(&self) -> broadcast::Receiver<GarbageCollectionTask> {
-        self.shared_state.subscribe_to_gc_task_events().await
+    /// Returns the ingestion plugins of the namespace ordered by name, which
+    /// is the order they run in.
+    pub async fn list_ingestion_plugins(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<internal_api::IngestionPlugin>> {
+        let mut plugins = self.shared_state.list_ingestion_plugins(namespace).await?;
+        plugins.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(plugins)
     }
 
-    pub fn get_state_watcher(&self) -> Receiver<StateChange> {
-        self.shared_state.get_state_change_watcher()
+    pub async fn delete_ingestion_plugin(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<internal_api::IngestionPlugin> {
+        let id = internal_api::IngestionPlugin {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        }
+        .id();
+        let plugin = self
+            .shared_state
+            .get_ingestion_plugin(&id)
+            .await?
+            .ok_or_else(|| anyhow!("ingestion plugin {} not found", name))?;
+        self.shared_state.delete_ingestion_plugin(&id).await?;
+        Ok(plugin)
     }
 
-    pub async fn create_content_metadata(
+    /// Creates or replaces the processing sla of an extraction policy and
+    /// returns it with the id of the policy filled in.
+    pub async fn set_processing_sla(
         &self,
-        content_list: Vec<indexify_coordinator::ContentMetadata>,
-    ) -> Result<()> {
-        let content_meta_list = content_request_to_content_metadata(content_list)?;
-        self.shared_state
-            .create_content_batch(content_meta_list)
+        mut sla: internal_api::ProcessingSla,
+    ) -> Result<internal_api::ProcessingSla> {
+        sla.validate()?;
+        let policy = self
+            .extraction_policy_with_name(&sla.namespace, &sla.extraction_policy)
             .await?;
-        Ok(())
+        sla.extraction_policy_id = policy.id;
+        self.shared_state.set_processing_sla(sla.clone()).await?;
+        Ok(sla)
     }
 
-    pub async fn tombstone_content_metadatas(
+    pub async fn list_processing_slas(
         &self,
         namespace: &str,
-        content_ids: &[String],
-    ) -> Result<()> {
-        self.shared_state
-            .tombstone_content_batch(namespace, content_ids)
-            .await?;
-        Ok(())
+    ) -> Result<Vec<internal_api::ProcessingSla>> {
+        let mut slas = self.shared_state.list_processing_slas(namespace).await?;
+        slas.sort_by(|a, b| a.extraction_policy.cmp(&b.extraction_policy));
+        Ok(slas)
     }
 
-    pub async fn get_schema(
+    pub async fn namespace_stored_bytes(&self, namespace: &str) -> Result<u64> {
+        self.shared_state.namespace_stored_bytes(namespace).await
+    }
+
+    /// Replaces the quotas of the namespace, content and extraction policies
+    /// already in the namespace are kept when it's over a new quota
+    pub async fn set_namespace_quotas(
         &self,
         namespace: &str,
-        content_source: &str,
-    ) -> Result<StructuredDataSchema> {
+        quotas: internal_api::NamespaceQuotas,
+    ) -> Result<()> {
+        if self.shared_state.namespace(namespace).await?.is_none() {
+            return Err(anyhow!("namespace {} not found", namespace));
+        }
         self.shared_state
-            .get_structured_data_schema(namespace, content_source)
+            .set_namespace_quotas(namespace, quotas)
             .await
     }
 
-    pub async fn list_schemas(&self, namespace: &str) -> Result<Vec<StructuredDataSchema>> {
-        self.shared_state.get_schemas_for_namespace(namespace).await
+    pub async fn get_namespace_quotas(
+        &self,
+        namespace: &str,
+    ) -> Result<internal_api::NamespaceQuotas> {
+        self.shared_state.get_namespace_quotas(namespace).await
     }
 
-    pub fn get_leader_change_watcher(&self) -> Receiver<bool> {
-        self.shared_state.leader_change_rx.clone()
+    /// Executors sending their heartbeats to this coordinator
+    pub fn connected_executors(&self) -> Vec<String> {
+        let mut executors: Vec<_> = self
+            .connected_executors
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect();
+        executors.sort();
+        executors
     }
 
-    pub fn get_raft_metrics(&self) -> RaftMetrics {
-        self.shared_state.get_raft_metrics()
+    /// Unix time in milliseconds this coordinator last heard from the leader
+    pub fn last_leader_contact_ms(&self) -> u64 {
+        self.shared_state.last_leader_contact_ms()
     }
-}
 
-fn content_request_to_content_metadata(
-    content_list: Vec<indexify_coordinator::ContentMetadata>,
-) -> Result<Vec<internal_api::ContentMetadata>> {
-    let mut content_meta_list = Vec::new();
-    for content in content_list {
-        let c: internal_api::ContentMetadata = content.try_into()?;
-        content_meta_list.push(c.clone());
+    /// Waits until the state of this coordinator reflects every write
+    /// committed before the call, for reads which can't observe stale state
+    pub async fn ensure_linearizable_read(&self) -> Result<()> {
+        self.shared_state.ensure_linearizable_read().await
     }
-    Ok(content_meta_list)
-}
-
-#[cfg(test)]
-mod tests {
-    use std::{collections::HashMap, fs, sync::Arc, time::Duration};
 
-    use indexify_internal_api as internal_api;
-    use indexify_proto::indexify_coordinator;
-
-    use super::Coordinator;
-    use crate::{
-        coordinator_client::CoordinatorClient,
-        garbage_collector::GarbageCollector,
-        server_config::ServerConfig,
-        state::App,
-        test_util::db_utils::{mock_extractor, DEFAULT_TEST_EXTRACTOR, DEFAULT_TEST_NAMESPACE},
-        test_utils::RaftTestCluster,
-    };
+    pub async fn get_namespace_stats(
+        &self,
+        namespace: &str,
+    ) -> Result<internal_api::NamespaceStats> {
+        self.shared_state.get_namespace_stats(namespace).await
+    }
 
-    async fn setup_coordinator() -> (Arc<Coordinator>, Arc<App>) {
-        let config = Arc::new(ServerConfig::default());
-        let _ = fs::remove_dir_all(config.state_store.clone().path.unwrap());
-        let garbage_collector = GarbageCollector::new();
-        let coordinator_client = CoordinatorClient::new(&config.coordinator_addr);
-        let shared_state = App::new(
-            config.clone(),
-            None,
-            garbage_collector.clone(),
-            &config.coordinator_addr,
-        )
-        .await
-        .unwrap();
-        shared_state.initialize_raft().await.unwrap();
-        let coordinator = crate::coordinator::Coordinator::new(
-            shared_state.clone(),
-            coordinator_client,
-            garbage_collector,
-        );
-        (coordinator, shared_state)
+    pub async fn namespace_extraction_policy_count(&self, namespace: &str) -> u64 {
+        self.shared_state
+            .namespace_extraction_policy_count(namespace)
+            .await
     }
 
-    #[tokio::test]
-    #[tracing_test::traced_test]
-    async fn test_create_extraction_events() -> Result<(), anyhow::Error> {
-        let (coordinator, shared_state) = setup_coordinator().await;
+    /// Starts a job copying the blobs of the content of the namespace to the
+    /// target backend. Content whose blob is already stored in the target
+    /// backend counts as migrated, so a job started again after it stopped
+    /// picks up where it left off.
+    pub async fn create_blob_migration_job(
+        &self,
+        namespace: &str,
+        target: &str,
+    ) -> Result<internal_api::BlobMigrationJob> {
+        let target_backend: BlobBackend = target
+            .parse()
+            .map_err(|_| anyhow!("unknown blob storage backend {}", target))?;
+        if let Some(job) = self.shared_state.get_blob_migration_job(namespace).await? {
+            let abandoned =
+                timestamp_secs().saturating_sub(job.updated_at) > REBALANCE_JOB_TIMEOUT_SECS;
+            if !job.completed && job.error.is_empty() && !abandoned {
+                return Err(anyhow!(
+                    "blobs of namespace {} are already being migrated",
+                    namespace
+                ));
+            }
+        }
+        let backends = self
+            .shared_state
+            .list_content(namespace)
+            .await?
+            .into_iter()
+            .filter_map(|content| BlobBackend::of_url(&content.storage_url))
+            .collect::<Vec<_>>();
+        let now = timestamp_secs();
+        let job = internal_api::BlobMigrationJob {
+            namespace: namespace.to_string(),
+            target: target_backend.to_string(),
+            total_content: backends.len() as u64,
+            migrated_content: backends
+                .iter()
+                .filter(|backend| **backend == target_backend)
+                .count() as u64,
+            completed: false,
+            error: "".to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.shared_state
+            .set_blob_migration_job(job.clone(), HashMap::new())
+            .await?;
+        Ok(job)
+    }
 
-        // Add a namespace
-        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+    pub async fn get_blob_migration_job(
+        &self,
+        namespace: &str,
+    ) -> Result<internal_api::BlobMigrationJob> {
+        self.shared_state
+            .get_blob_migration_job(namespace)
+            .await?
+            .ok_or_else(|| anyhow!("no blob migration job found for namespace {}", namespace))
+    }
 
-        // Add content and ensure that we are creating a extraction event
-        coordinator
-            .create_content_metadata(vec![indexify_coordinator::ContentMetadata {
-                id: "test".to_string(),
-                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-                parent_id: "".to_string(),
-                file_name: "test".to_string(),
-                mime: "text/plain".to_string(),
-                created_at: 0,
-                storage_url: "test".to_string(),
-                labels: HashMap::new(),
-                source: "ingestion".to_string(),
-                size_bytes: 100,
-                extraction_policy_ids: HashMap::new(),
-            }])
+    /// Records the progress of a blob migration job, switching the content
+    /// copied since the last update over to the storage urls of the copies
+    pub async fn update_blob_migration_job(
+        &self,
+        namespace: &str,
+        storage_urls: HashMap<String, String>,
+        completed: bool,
+        error: &str,
+    ) -> Result<internal_api::BlobMigrationJob> {
+        let mut job = self.get_blob_migration_job(namespace).await?;
+        job.migrated_content += storage_urls.len() as u64;
+        job.completed = completed;
+        job.error = error.to_string();
+        job.updated_at = timestamp_secs();
+        self.shared_state
+            .set_blob_migration_job(job.clone(), storage_urls)
             .await?;
+        Ok(job)
+    }
 
-        let events = shared_state.unprocessed_state_change_events().await?;
-        assert_eq!(events.len(), 1);
+    pub async fn delete_processing_sla(
+        &self,
+        namespace: &str,
+        extraction_policy: &str,
+    ) -> Result<()> {
+        let id = internal_api::ProcessingSla {
+            namespace: namespace.to_string(),
+            extraction_policy: extraction_policy.to_string(),
+            ..Default::default()
+        }
+        .id();
+        if self.shared_state.get_processing_sla(&id).await?.is_none() {
+            return Err(anyhow!(
+                "extraction policy {} has no processing sla",
+                extraction_policy
+            ));
+        }
+        self.shared_state.delete_processing_sla(&id).await
+    }
 
-        //  Run scheduler without any bindings to make
-        // sure that the event is processed and we don't have any tasks
-        coordinator.run_scheduler().await?;
-        let events = shared_state.unprocessed_state_change_events().await?;
-        assert_eq!(events.len(), 0);
-        let tasks = shared_state.unassigned_tasks().await?;
-        assert_eq!(tasks.len(), 0);
+    pub async fn create_ingest_intent(&self, intent: internal_api::IngestIntent) -> Result<()> {
+        self.shared_state.create_ingest_intent(intent).await
+    }
 
-        // Add extractors and extractor bindings and ensure that we are creating tasks
-        coordinator
-            .register_executor("localhost:8956", "test_executor_id", mock_extractor())
-            .await?;
-        coordinator
-            .create_policy(
-                internal_api::ExtractionPolicy {
-                    id: "test-binding-id".to_string(),
-                    name: "test".to_string(),
-                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
-                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-                    input_params: serde_json::json!({}),
-                    filters: HashMap::new(),
-                    output_index_name_mapping: HashMap::from([(
-                        "test_output".to_string(),
-                        "test.test_output".to_string(),
-                    )]),
-                    index_name_table_mapping: HashMap::from([(
-                        "test.test_output".to_string(),
-                        "test_namespace.test.test_output".to_string(),
-                    )]),
-                    content_source: "ingestion".to_string(),
-                },
-                mock_extractor(),
-            )
-            .await?;
-        assert_eq!(
-            2,
-            shared_state.unprocessed_state_change_events().await?.len()
-        );
-        coordinator.run_scheduler().await?;
-        assert_eq!(
-            0,
-            shared_state.unprocessed_state_change_events().await?.len()
-        );
-        assert_eq!(
-            1,
-            shared_state
-                .tasks_for_executor("test_executor_id", None)
-                .await?
-                .len()
-        );
-        assert_eq!(0, shared_state.unassigned_tasks().await?.len());
+    /// Returns the intents created before the timestamp, or all of them if
+    /// it's zero.
+    pub async fn list_ingest_intents(
+        &self,
+        created_before: u64,
+    ) -> Result<Vec<internal_api::IngestIntent>> {
+        Ok(self
+            .shared_state
+            .list_ingest_intents()
+            .await?
+            .into_iter()
+            .filter(|intent| created_before == 0 || intent.created_at < created_before)
+            .collect())
+    }
 
-        // Add a content with a different source and ensure we don't create a task
-        coordinator
-            .create_content_metadata(vec![indexify_coordinator::ContentMetadata {
-                id: "test2".to_string(),
-                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-                parent_id: "test".to_string(),
-                file_name: "test2".to_string(),
-                mime: "text/plain".to_string(),
-                created_at: 0,
-                storage_url: "test2".to_string(),
-                labels: HashMap::new(),
-                source: "some_extractor_produced_this".to_string(),
-                size_bytes: 100,
-                extraction_policy_ids: HashMap::new(),
-            }])
-            .await?;
-        coordinator.run_scheduler().await?;
-        assert_eq!(
-            0,
-            shared_state.unprocessed_state_change_events().await?.len()
-        );
-        let tasks = shared_state
-            .tasks_for_executor("test_executor_id", None)
-            .await
-            .unwrap();
-        assert_eq!(1, tasks.clone().len());
-        assert_eq!(0, shared_state.unassigned_tasks().await?.len());
+    pub async fn delete_ingest_intents(&self, content_ids: Vec<String>) -> Result<()> {
+        self.shared_state.delete_ingest_intents(content_ids).await
+    }
 
-        let mut task_clone = tasks[0].clone();
-        task_clone.outcome = internal_api::TaskOutcome::Success;
-        shared_state
-            .update_task(task_clone, Some("test_executor_id".to_string()), vec![])
-            .await
-            .unwrap();
-        let tasks = shared_state
-            .tasks_for_executor("test_executor_id", None)
+    pub async fn create_embedding_expirations(
+        &self,
+        expirations: Vec<internal_api::EmbeddingExpiration>,
+    ) -> Result<()> {
+        if expirations.is_empty() {
+            return Ok(());
+        }
+        self.shared_state
+            .create_embedding_expirations(expirations)
             .await
-            .unwrap();
-        assert_eq!(0, tasks.clone().len());
-        Ok(())
     }
 
-    #[tokio::test]
-    #[tracing_test::traced_test]
-    async fn test_create_multiple_extraction_policies_and_contents() -> Result<(), anyhow::Error> {
-        let (coordinator, shared_state) = setup_coordinator().await;
-
-        // Add a namespace
-        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+    /// Returns the embeddings expiring before the timestamp, or all of them
+    /// if it's zero.
+    pub async fn list_embedding_expirations(
+        &self,
+        expires_before: u64,
+    ) -> Result<Vec<internal_api::EmbeddingExpiration>> {
+        Ok(self
+            .shared_state
+            .list_embedding_expirations()
+            .await?
+            .into_iter()
+            .filter(|expiration| expires_before == 0 || expiration.expires_at < expires_before)
+            .collect())
+    }
+
+    pub async fn delete_embedding_expirations(
+        &self,
+        expirations: Vec<internal_api::EmbeddingExpiration>,
+    ) -> Result<()> {
+        let keys = expirations
+            .iter()
+            .map(|expiration| expiration.key())
+            .collect();
+        self.shared_state.delete_embedding_expirations(keys).await
+    }
+
+    /// Adds the content to the reference of the content with the same hash in
+    /// the index. The embedding of the content only has to be written if it's
+    /// the owner of the returned reference.
+    pub async fn add_chunk_reference(
+        &self,
+        index_table: &str,
+        hash: &str,
+        content_id: &str,
+    ) -> Result<internal_api::ChunkReference> {
+        self.shared_state
+            .add_chunk_reference(index_table, hash, content_id)
+            .await?;
+        //  The write might not be applied locally yet if it was forwarded to
+        // the leader, in which case the content writes its own embedding
+        let reference = self
+            .shared_state
+            .get_chunk_reference(index_table, hash)
+            .await?
+            .unwrap_or_else(|| internal_api::ChunkReference {
+                index_table: index_table.to_string(),
+                hash: hash.to_string(),
+                content_ids: vec![content_id.to_string()],
+            });
+        Ok(reference)
+    }
+
+    /// Removes the content from the reference of its hash in the index. The
+    /// next content of the reference becomes its owner.
+    pub async fn remove_chunk_reference(
+        &self,
+        index_table: &str,
+        hash: &str,
+        content_id: &str,
+    ) -> Result<()> {
+        self.shared_state
+            .remove_chunk_reference(index_table, hash, content_id)
+            .await
+    }
+
+    pub async fn get_chunk_references(
+        &self,
+        index_table: &str,
+        hashes: &[String],
+    ) -> Result<Vec<internal_api::ChunkReference>> {
+        let mut references = Vec::new();
+        for hash in hashes.iter().collect::<HashSet<_>>() {
+            if let Some(reference) = self
+                .shared_state
+                .get_chunk_reference(index_table, hash)
+                .await?
+            {
+                references.push(reference);
+            }
+        }
+        Ok(references)
+    }
+
+    async fn extraction_policy_with_name(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<internal_api::ExtractionPolicy> {
+        self.shared_state
+            .list_extraction_policy(namespace)
+            .await?
+            .into_iter()
+            .find(|policy| policy.name == name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "extraction policy {} not found in namespace {}",
+                    name,
+                    namespace
+                )
+            })
+    }
+
+    pub async fn get_extractor_coordinates(&self, extractor_name: &str) -> Result<Vec<String>> {
+        let executors = self
+            .shared_state
+            .get_executors_for_extractor(extractor_name)
+            .await?;
+        let addresses = executors
+            .iter()
+            .map(|e| e.addr.clone())
+            .collect::<Vec<String>>();
+        Ok(addresses)
+    }
+
+    /// Lists the executors having all of the topology labels in `labels_eq`
+    pub async fn list_executors(
+        &self,
+        labels_eq: &HashMap<String, String>,
+    ) -> Result<Vec<internal_api::ExecutorMetadata>> {
+        let executors = self.shared_state.get_executors().await?;
+        Ok(executors
+            .into_iter()
+            .filter(|executor| executor.has_labels(labels_eq))
+            .collect())
+    }
+
+    pub async fn register_executor(
+        &self,
+        addr: &str,
+        executor_id: &str,
+        extractor: internal_api::ExtractorDescription,
+        labels: HashMap<String, String>,
+    ) -> Result<()> {
+        let _ = self
+            .shared_state
+            .register_executor(addr, executor_id, extractor.clone(), labels)
+            .await;
+        if let Err(e) = self.evolve_structured_data_schemas(&extractor).await {
+            tracing::error!(
+                "unable to evolve structured data schemas for extractor {}: {}",
+                extractor.name,
+                e
+            );
+        }
+        Ok(())
+    }
+
+    /// Adds the attribute columns the extractor outputs to the structured data
+    /// schemas of the content sources its extraction policies run on, so
+    /// fields added by a new version of an extractor aren't dropped from the
+    /// structured tables. Metadata is stored as JSON by the metadata stores,
+    /// so the tables are migrated by the new columns of the schema and rows
+    /// written before have them set to NULL.
+    async fn evolve_structured_data_schemas(
+        &self,
+        extractor: &internal_api::ExtractorDescription,
+    ) -> Result<()> {
+        let columns = extractor
+            .outputs
+            .values()
+            .filter_map(|output_schema| match output_schema {
+                OutputSchema::Attributes(columns) => Some(columns.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if columns.is_empty() {
+            return Ok(());
+        }
+        let mut evolved_schemas: HashMap<String, StructuredDataSchema> = HashMap::new();
+        for namespace in self.shared_state.list_namespaces().await? {
+            for policy in namespace
+                .extraction_policies
+                .iter()
+                .filter(|policy| policy.extractor == extractor.name)
+            {
+                let schema_id =
+                    StructuredDataSchema::schema_id(&policy.namespace, &policy.content_source);
+                let (mut schema, mut evolved) = match evolved_schemas.remove(&schema_id) {
+                    Some(schema) => (schema, true),
+                    None => {
+                        let schema = self
+                            .shared_state
+                            .get_structured_data_schema(&policy.namespace, &policy.content_source)
+                            .await?;
+                        (schema, false)
+                    }
+                };
+                for columns in &columns {
+                    if let Some(evolved_schema) = schema.evolve(columns.clone()) {
+                        schema = evolved_schema;
+                        evolved = true;
+                    }
+                }
+                if evolved {
+                    evolved_schemas.insert(schema_id, schema);
+                }
+            }
+        }
+        if evolved_schemas.is_empty() {
+            return Ok(());
+        }
+        info!(
+            "evolving structured data schemas {:?} with the outputs of extractor {}",
+            evolved_schemas.keys().collect::<Vec<_>>(),
+            extractor.name
+        );
+        self.shared_state
+            .evolve_structured_data_schemas(evolved_schemas.into_values().collect())
+            .await
+    }
+
+    /// Evolves the schema of a content source with the columns inferred from
+    /// extracted metadata, returning the current schema. The inference is
+    /// applied to the stored schema since it may have evolved after the
+    /// ingestion server read it.
+    pub async fn evolve_structured_data_schema(
+        &self,
+        namespace: &str,
+        content_source: &str,
+        inference: SchemaInference,
+    ) -> Result<StructuredDataSchema> {
+        //  Content sources without an extraction policy don't have a schema
+        // until metadata is written to them
+        let schema = self
+            .shared_state
+            .get_structured_data_schema(namespace, content_source)
+            .await
+            .unwrap_or_else(|_| StructuredDataSchema::new(content_source, namespace));
+        let Some(evolved) = schema.evolve_inferred(inference) else {
+            return Ok(schema);
+        };
+        for (column, dtype) in &evolved.conflicts {
+            if schema.conflicts.get(column) != Some(dtype) {
+                warn!(
+                    "metadata of type {:?} written to column {} of schema {}, expected {:?}",
+                    dtype, column, evolved.id, evolved.columns[column]
+                );
+            }
+        }
+        self.shared_state
+            .evolve_structured_data_schemas(vec![evolved.clone()])
+            .await?;
+        Ok(evolved)
+    }
+
+    pub async fn register_ingestion_server(&self, ingestion_server_id: &str) -> Result<()> {
+        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
+            let leader_node_id = forward_to_leader
+                .leader_id
+                .ok_or_else(|| NotLeaderError("could not get leader node id".to_string()))?;
+            let leader_coord_addr = self
+                .shared_state
+                .get_coordinator_addr(leader_node_id)
+                .await?
+                .ok_or_else(|| {
+                    NotLeaderError("could not get leader node coordinator address".to_string())
+                })?;
+            self.forwardable_coordinator
+                .register_ingestion_server(&leader_coord_addr, ingestion_server_id)
+                .await?;
+            return Ok(());
+        }
+        self.garbage_collector
+            .register_ingestion_server(ingestion_server_id)
+            .await;
+        Ok(())
+    }
+
+    pub async fn remove_ingestion_server(&self, ingestion_server_id: &str) -> Result<()> {
+        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
+            let leader_node_id = forward_to_leader
+                .leader_id
+                .ok_or_else(|| NotLeaderError("could not get leader node id".to_string()))?;
+            let leader_coord_addr = self
+                .shared_state
+                .get_coordinator_addr(leader_node_id)
+                .await?
+                .ok_or_else(|| {
+                    NotLeaderError("could not get leader node coordinator address".to_string())
+                })?;
+            self.forwardable_coordinator
+                .remove_ingestion_server(&leader_coord_addr, ingestion_server_id)
+                .await?;
+            return Ok(());
+        }
+        self.garbage_collector
+            .remove_ingestion_server(ingestion_server_id)
+            .await;
+        Ok(())
+    }
+
+    pub async fn get_content_metadata(
+        &self,
+        content_ids: Vec<String>,
+    ) -> Result<Vec<internal_api::ContentMetadata>> {
+        self.shared_state
+            .get_content_metadata_batch(content_ids)
+            .await
+    }
+
+    pub async fn get_content_tree_metadata(
+        &self,
+        content_id: &str,
+    ) -> Result<Vec<internal_api::ContentMetadata>> {
+        self.shared_state
+            .get_content_tree_metadata(content_id)
+            .await
+    }
+
+    pub async fn get_extractor(
+        &self,
+        extractor_name: &str,
+    ) -> Result<internal_api::ExtractorDescription> {
+        self.shared_state.extractor_with_name(extractor_name).await
+    }
+
+    pub async fn get_extraction_policy(&self, id: &str) -> Result<internal_api::ExtractionPolicy> {
+        self.shared_state.get_extraction_policy(id).await
+    }
+
+    pub async fn create_policy(
+        &self,
+        extraction_policy: internal_api::ExtractionPolicy,
+        extractor: internal_api::ExtractorDescription,
+    ) -> Result<()> {
+        self.create_policy_with_token(extraction_policy, extractor, "")
+            .await
+    }
+
+    /// Creates the extraction policy and records the operation token along
+    /// with it. Callers check `applied_operation` first to return the result
+    /// of the original request on retries.
+    pub async fn create_policy_with_token(
+        &self,
+        extraction_policy: internal_api::ExtractionPolicy,
+        extractor: internal_api::ExtractorDescription,
+        operation_token: &str,
+    ) -> Result<()> {
+        validate_input_params(&extraction_policy, &extractor)?;
+        extraction_policy.content_filter.validate()?;
+        extraction_policy.retry_policy.validate()?;
+        let structured_data_schema = self
+            .shared_state
+            .get_structured_data_schema(
+                &extraction_policy.namespace,
+                &extraction_policy.content_source,
+            )
+            .await?;
+        for (output_name, output_schema) in &extractor.outputs {
+            if let OutputSchema::Embedding(embedding_schema) = output_schema {
+                if let Some(index_name) =
+                    extraction_policy.output_index_name_mapping.get(output_name)
+                {
+                    self.check_embedding_dimension(
+                        &extraction_policy.namespace,
+                        index_name,
+                        embedding_schema,
+                    )
+                    .await?;
+                }
+            }
+        }
+        //  Only add the columns of the outputs, the columns other extractors
+        // already wrote to the content source's table are kept
+        let mut updated_schema: Option<StructuredDataSchema> = None;
+        for (_, output_schema) in extractor.outputs {
+            if let OutputSchema::Attributes(columns) = output_schema {
+                let schema = updated_schema.as_ref().unwrap_or(&structured_data_schema);
+                if let Some(evolved_schema) = schema.evolve(columns) {
+                    updated_schema.replace(evolved_schema);
+                }
+            }
+        }
+        let operation = operation_record(
+            operation_token,
+            internal_api::OperationKind::CreateExtractionPolicy,
+            &extraction_policy.id,
+        );
+        self.shared_state
+            .create_extraction_policy_with_operation(extraction_policy, updated_schema, operation)
+            .await?;
+        Ok(())
+    }
+
+    /// Updates the filters, input params and retry policy which are set,
+    /// bumping its version. Content already extracted by the policy is only
+    /// extracted again if `reextract` is set.
+    pub async fn update_policy(
+        &self,
+        extraction_policy_id: &str,
+        filters: Option<HashMap<String, String>>,
+        input_params: Option<serde_json::Value>,
+        content_filter: Option<internal_api::ContentFilter>,
+        retry_policy: Option<internal_api::RetryPolicy>,
+        reextract: bool,
+    ) -> Result<internal_api::ExtractionPolicy> {
+        let mut extraction_policy = self
+            .shared_state
+            .get_extraction_policy(extraction_policy_id)
+            .await?;
+        if let Some(filters) = filters {
+            extraction_policy.filters = filters;
+        }
+        if let Some(content_filter) = content_filter {
+            content_filter.validate()?;
+            extraction_policy.content_filter = content_filter;
+        }
+        if let Some(retry_policy) = retry_policy {
+            retry_policy.validate()?;
+            extraction_policy.retry_policy = retry_policy;
+        }
+        if let Some(input_params) = input_params {
+            let extractor = self
+                .shared_state
+                .extractor_with_name(&extraction_policy.extractor)
+                .await?;
+            extraction_policy.input_params = input_params;
+            validate_input_params(&extraction_policy, &extractor)?;
+        }
+        extraction_policy.version += 1;
+        self.shared_state
+            .update_extraction_policy(extraction_policy.clone(), reextract)
+            .await?;
+        Ok(extraction_policy)
+    }
+
+    /// Deletes the extraction policy so that no more tasks are created for
+    /// it, cancelling its tasks which weren't assigned yet. The indexes of the
+    /// policy are removed if `drop_indexes` is set and the tables of the
+    /// removed indexes are returned, otherwise the indexes are archived: they
+    /// are kept and can still be searched, but nothing is written to them.
+    pub async fn delete_policy(
+        &self,
+        namespace: &str,
+        name: &str,
+        drop_indexes: bool,
+    ) -> Result<Vec<String>> {
+        let extraction_policy = self.extraction_policy_with_name(namespace, name).await?;
+        let dependent_policies = self
+            .shared_state
+            .list_extraction_policy(namespace)
+            .await?
+            .into_iter()
+            .filter(|policy| policy.content_source == extraction_policy.name)
+            .map(|policy| policy.name)
+            .collect::<Vec<_>>();
+        if !dependent_policies.is_empty() {
+            return Err(anyhow!(
+                "extraction policy {} is the content source of the extraction policies {}",
+                name,
+                dependent_policies.join(", ")
+            ));
+        }
+        let mut index_ids = Vec::new();
+        let mut dropped_tables = HashSet::new();
+        if drop_indexes {
+            for (index_name, table_name) in &extraction_policy.index_name_table_mapping {
+                let id = internal_api::Index::index_id(namespace, index_name);
+                if let Some(job) = self.shared_state.get_rebalance_job(&id).await? {
+                    let abandoned = timestamp_secs().saturating_sub(job.updated_at) >
+                        REBALANCE_JOB_TIMEOUT_SECS;
+                    if !job.completed && job.error.is_empty() && !abandoned {
+                        return Err(anyhow!(
+                            "index {} of extraction policy {} is being rebalanced",
+                            index_name,
+                            name
+                        ));
+                    }
+                }
+                //  The tables of the named vectors of the index are dropped with
+                // the index table
+                if let Result::Ok(index) = self.shared_state.get_index(&id).await {
+                    if let Result::Ok(schema) =
+                        serde_json::from_str::<EmbeddingSchema>(&index.schema)
+                    {
+                        dropped_tables.extend(schema.named_vector_tables(table_name));
+                    }
+                }
+                index_ids.push(id);
+                dropped_tables.insert(table_name.clone());
+            }
+        }
+
+        //  Running tasks are left to finish, they only stop writing to the
+        // tables which are dropped
+        let unassigned_task_ids = self
+            .shared_state
+            .unassigned_tasks()
+            .await?
+            .into_iter()
+            .map(|task| task.id)
+            .collect::<HashSet<_>>();
+        let mut tasks = Vec::new();
+        for task_id in self
+            .shared_state
+            .unfinished_tasks_by_extractor(&extraction_policy.extractor)
+            .await?
+        {
+            let mut task = self.shared_state.task_with_id(&task_id).await?;
+            if task.extraction_policy_id != extraction_policy.id {
+                continue;
+            }
+            if unassigned_task_ids.contains(&task.id) {
+                task.outcome = internal_api::TaskOutcome::Failed;
+            } else if !dropped_tables.is_empty() {
+                task.output_index_table_mapping
+                    .retain(|_, table| !dropped_tables.contains(table));
+                task.index_tables
+                    .retain(|table| !dropped_tables.contains(table));
+            } else {
+                continue;
+            }
+            tasks.push(task);
+        }
+        info!(
+            "deleting extraction policy {} in namespace {}, cancelling {} tasks",
+            name,
+            namespace,
+            tasks.iter().filter(|task| task.terminal_state()).count()
+        );
+        self.shared_state
+            .delete_extraction_policy(extraction_policy, index_ids, tasks)
+            .await?;
+        Ok(dropped_tables.into_iter().collect())
+    }
+
+    pub async fn create_gc_tasks(&self, content_id: &str) -> Result<Vec<GarbageCollectionTask>> {
+        let tasks = self.gc_tasks_for_content(content_id).await?;
+        self.shared_state.create_gc_tasks(tasks.clone()).await?;
+        Ok(tasks)
+    }
+
+    async fn gc_tasks_for_content(&self, content_id: &str) -> Result<Vec<GarbageCollectionTask>> {
+        let content_tree_metadata = self
+            .shared_state
+            .get_content_tree_metadata(content_id)
+            .await?;
+        let mut output_tables: HashMap<String, HashSet<String>> = HashMap::new();
+
+        for content_metadata in &content_tree_metadata {
+            //  Policies whose task didn't finish yet may already have written
+            // embeddings, so the indexes of every policy applied to the
+            // content are cleaned up
+            let applied_extraction_policy_ids: HashSet<String> = content_metadata
+                .extraction_policy_ids
+                .keys()
+                .cloned()
+                .collect();
+            if applied_extraction_policy_ids.is_empty() {
+                continue;
+            }
+            let applied_extraction_policies = self
+                .shared_state
+                .get_extraction_policies_from_ids(applied_extraction_policy_ids)
+                .await?
+                .unwrap_or_default();
+            for applied_extraction_policy in applied_extraction_policies {
+                output_tables
+                    .entry(content_metadata.id.clone())
+                    .or_default()
+                    .extend(
+                        applied_extraction_policy
+                            .index_name_table_mapping
+                            .into_values(),
+                    );
+            }
+        }
+
+        self.garbage_collector
+            .create_gc_tasks(content_tree_metadata, output_tables)
+            .await
+    }
+
+    /// Deletes the namespace with its extraction policies, indexes and
+    /// schemas. Its content is tombstoned and garbage collected by the tasks
+    /// returned, which are created along with the deletion.
+    pub async fn delete_namespace(&self, namespace: &str) -> Result<Vec<GarbageCollectionTask>> {
+        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
+            let leader_id = forward_to_leader
+                .leader_id
+                .ok_or_else(|| NotLeaderError("could not get leader node id".to_string()))?;
+            let leader_coord_addr = self
+                .shared_state
+                .get_coordinator_addr(leader_id)
+                .await?
+                .ok_or_else(|| {
+                    NotLeaderError("could not get leader node coordinator address".to_string())
+                })?;
+            self.forwardable_coordinator
+                .delete_namespace(&leader_coord_addr, namespace)
+                .await?;
+            return Ok(Vec::new());
+        }
+
+        if self.shared_state.namespace(namespace).await?.is_none() {
+            return Err(anyhow!("namespace {} not found", namespace));
+        }
+        //  The output tables of the content are found through the extraction
+        // policies, so the tasks are created before the policies are deleted
+        let mut gc_tasks = Vec::new();
+        for content in self.shared_state.list_content(namespace).await? {
+            if content.parent_id.is_empty() && !content.tombstoned {
+                gc_tasks.extend(self.gc_tasks_for_content(&content.id).await?);
+            }
+        }
+        self.shared_state
+            .delete_namespace(namespace, gc_tasks.clone())
+            .await?;
+        Ok(gc_tasks)
+    }
+
+    async fn handle_tombstone_content(
+        &self,
+        change: StateChange,
+    ) -> Result<Vec<GarbageCollectionTask>> {
+        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
+            let leader_id = forward_to_leader
+                .leader_id
+                .ok_or_else(|| NotLeaderError("could not get leader node id".to_string()))?;
+            let leader_coord_addr = self
+                .shared_state
+                .get_coordinator_addr(leader_id)
+                .await?
+                .ok_or_else(|| {
+                    NotLeaderError("could not get leader node coordinator address".to_string())
+                })?;
+            self.forwardable_coordinator
+                .create_gc_tasks(&leader_coord_addr, &change.object_id)
+                .await?;
+            return Ok(Vec::new());
+        }
+
+        //  this coordinator node is the leader
+        self.create_gc_tasks(&change.object_id).await
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn run_scheduler(&self) -> Result<()> {
+        let state_changes = self.shared_state.unprocessed_state_change_events().await?;
+        for change in state_changes {
+            info!(
+                "processing change event: {}, type: {}, id: {}",
+                change.id, change.change_type, change.object_id
+            );
+
+            match change.change_type {
+                indexify_internal_api::ChangeType::TombstoneContent => {
+                    let _ = self.handle_tombstone_content(change).await?;
+                }
+                _ => self.scheduler.handle_change_event(change).await?,
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn subscribe_to_gc_events(&self) -> broadcast::Receiver<GarbageCollectionTask> {
+        self.shared_state.subscribe_to_gc_task_events().await
+    }
+
+    pub fn get_state_watcher(&self) -> Receiver<StateChange> {
+        self.shared_state.get_state_change_watcher()
+    }
+
+    /// Subscribes to the state changes applied on this node, a change is
+    /// sent if it's one of `change_types` and belongs to `namespace`. Empty
+    /// filters match every change.
+    pub fn watch_state_changes(
+        self: &Arc<Self>,
+        namespace: String,
+        change_types: HashSet<String>,
+    ) -> mpsc::Receiver<(StateChange, Option<String>)> {
+        let mut state_changes_rx = self.shared_state.subscribe_to_state_changes();
+        let (tx, rx) = mpsc::channel(STATE_CHANGE_WATCH_BUFFER);
+        let coordinator = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let change = match state_changes_rx.recv().await {
+                    Ok(change) => change,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "state change watcher fell behind, skipped {} changes",
+                            skipped
+                        );
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if !change_types.is_empty() &&
+                    !change_types.contains(&change.change_type.to_string())
+                {
+                    continue;
+                }
+                let change_namespace = match coordinator
+                    .shared_state
+                    .state_change_namespace(&change)
+                    .await
+                {
+                    Ok(change_namespace) => change_namespace,
+                    Err(e) => {
+                        error!(
+                            "unable to get the namespace of state change {}: {}",
+                            change.id, e
+                        );
+                        continue;
+                    }
+                };
+                if !namespace.is_empty() && change_namespace.as_deref() != Some(namespace.as_str())
+                {
+                    continue;
+                }
+                //  The watcher disconnected
+                if tx.send((change, change_namespace)).await.is_err() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
+
+    /// Creates the content, returning it as stored with the expiration
+    /// inherited from its parent.
+    pub async fn create_content_metadata(
+        &self,
+        content_list: Vec<indexify_coordinator::ContentMetadata>,
+    ) -> Result<Vec<internal_api::ContentMetadata>> {
+        self.create_content_metadata_with_request_id(content_list, None)
+            .await
+    }
+
+    /// Creates the content, a retry with the same request id returns the
+    /// content without creating it again
+    pub async fn create_content_metadata_with_request_id(
+        &self,
+        content_list: Vec<indexify_coordinator::ContentMetadata>,
+        request_id: Option<String>,
+    ) -> Result<Vec<internal_api::ContentMetadata>> {
+        let mut content_meta_list = content_request_to_content_metadata(content_list)?;
+        self.check_external_ids(&content_meta_list).await?;
+        self.inherit_expirations(&mut content_meta_list).await?;
+        let created_content = content_meta_list.clone();
+        self.shared_state
+            .create_content_batch_with_request_id(content_meta_list, request_id)
+            .await?;
+        emit_content_created(&created_content);
+        Ok(created_content)
+    }
+
+    //  Content extracted from content with a TTL expires with its parent,
+    // unless it was given an earlier expiration
+    async fn inherit_expirations(
+        &self,
+        content_list: &mut [internal_api::ContentMetadata],
+    ) -> Result<()> {
+        let parent_ids = content_list
+            .iter()
+            .filter(|c| !c.parent_id.is_empty())
+            .map(|c| c.parent_id.clone())
+            .collect::<Vec<_>>();
+        if parent_ids.is_empty() {
+            return Ok(());
+        }
+        let parent_expirations: HashMap<String, u64> = self
+            .shared_state
+            .get_content_metadata_batch(parent_ids)
+            .await?
+            .into_iter()
+            .filter(|parent| parent.expires_at > 0)
+            .map(|parent| (parent.id, parent.expires_at))
+            .collect();
+        for content in content_list.iter_mut() {
+            if let Some(parent_expires_at) = parent_expirations.get(&content.parent_id) {
+                content.expires_at = match content.expires_at {
+                    0 => *parent_expires_at,
+                    expires_at => expires_at.min(*parent_expires_at),
+                };
+            }
+        }
+        Ok(())
+    }
+
+    //  External ids are supplied by clients to correlate content with their own
+    // documents, so they have to be unique within a namespace. Writing the same
+    // content again with its external id is allowed.
+    async fn check_external_ids(
+        &self,
+        content_list: &[internal_api::ContentMetadata],
+    ) -> Result<()> {
+        let mut external_ids: HashMap<(&str, &str), &str> = HashMap::new();
+        for content in content_list.iter().filter(|c| !c.external_id.is_empty()) {
+            let existing_id = external_ids.insert(
+                (content.namespace.as_str(), content.external_id.as_str()),
+                content.id.as_str(),
+            );
+            if existing_id.is_some_and(|id| id != content.id) {
+                return Err(anyhow!(
+                    "external id {} is used by more than one content in the request",
+                    content.external_id
+                ));
+            }
+        }
+        for ((namespace, external_id), id) in external_ids {
+            let existing_content = self
+                .shared_state
+                .list_content(namespace)
+                .await?
+                .into_iter()
+                .find(|c| !c.tombstoned && c.external_id == external_id && c.id != id);
+            if let Some(existing_content) = existing_content {
+                return Err(anyhow!(
+                    "external id {} is already used by content {} in namespace {}",
+                    external_id,
+                    existing_content.id,
+                    namespace
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn tombstone_content_metadatas(
+        &self,
+        namespace: &str,
+        content_ids: &[String],
+    ) -> Result<()> {
+        self.shared_state
+            .tombstone_content_batch(namespace, content_ids)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_schema(
+        &self,
+        namespace: &str,
+        content_source: &str,
+    ) -> Result<StructuredDataSchema> {
+        self.shared_state
+            .get_structured_data_schema(namespace, content_source)
+            .await
+    }
+
+    pub async fn list_schemas(&self, namespace: &str) -> Result<Vec<StructuredDataSchema>> {
+        self.shared_state.get_schemas_for_namespace(namespace).await
+    }
+
+    pub fn get_leader_change_watcher(&self) -> Receiver<bool> {
+        self.shared_state.leader_change_rx.clone()
+    }
+
+    pub fn get_raft_metrics(&self) -> RaftMetrics {
+        self.shared_state.get_raft_metrics()
+    }
+
+    pub async fn list_cluster_members(&self) -> Result<Vec<internal_api::ClusterMember>> {
+        self.shared_state.cluster_members().await
+    }
+
+    pub async fn add_cluster_learner(
+        &self,
+        node_id: u64,
+        address: &str,
+        coordinator_addr: &str,
+    ) -> Result<()> {
+        if address.is_empty() || coordinator_addr.is_empty() {
+            return Err(anyhow!(
+                "a learner needs the address of its raft server and of its coordinator"
+            ));
+        }
+        self.shared_state
+            .add_learner(node_id, address, coordinator_addr)
+            .await
+    }
+
+    pub async fn promote_cluster_learner(&self, node_id: u64) -> Result<()> {
+        self.shared_state.promote_learner(node_id).await
+    }
+
+    pub async fn remove_cluster_member(&self, node_id: u64) -> Result<()> {
+        self.shared_state.remove_node(node_id).await
+    }
+}
+
+fn operation_record(
+    operation_token: &str,
+    operation: internal_api::OperationKind,
+    resource_id: &str,
+) -> Option<internal_api::OperationRecord> {
+    (!operation_token.is_empty()).then(|| internal_api::OperationRecord {
+        token: operation_token.to_string(),
+        operation,
+        resource_id: resource_id.to_string(),
+        created_at: timestamp_secs(),
+    })
+}
+
+fn validate_input_params(
+    extraction_policy: &internal_api::ExtractionPolicy,
+    extractor: &internal_api::ExtractorDescription,
+) -> Result<()> {
+    if extractor.input_params == serde_json::Value::Null {
+        return Ok(());
+    }
+    let input_params_schema = JSONSchema::compile(&extractor.input_params).map_err(|e| {
+        anyhow!(
+            "unable to compile json schema for input params: {:?}, error: {:?}",
+            &extractor.input_params,
+            e
+        )
+    })?;
+    let validation_result = input_params_schema.validate(&extraction_policy.input_params);
+    if let Err(errors) = validation_result {
+        let errors = errors
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<String>>();
+        return Err(anyhow!(
+            "unable to validate input params for extractor policy: {}, errors: {}",
+            &extraction_policy.name,
+            errors.join(",")
+        ));
+    }
+    Ok(())
+}
+
+fn emit_content_created(content_list: &[internal_api::ContentMetadata]) {
+    for content in content_list {
+        lifecycle_events::content_created(
+            &content.namespace,
+            &content.id,
+            &content.source,
+            &content.content_type,
+        );
+    }
+}
+
+fn content_request_to_content_metadata(
+    content_list: Vec<indexify_coordinator::ContentMetadata>,
+) -> Result<Vec<internal_api::ContentMetadata>> {
+    let mut content_meta_list = Vec::new();
+    for content in content_list {
+        let c: internal_api::ContentMetadata = content.try_into()?;
+        content_meta_list.push(c.clone());
+    }
+    Ok(content_meta_list)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        fs,
+        sync::Arc,
+        time::Duration,
+    };
+
+    use indexify_internal_api as internal_api;
+    use indexify_proto::indexify_coordinator;
+
+    use super::Coordinator;
+    use crate::{
+        coordinator_client::CoordinatorClient,
+        garbage_collector::GarbageCollector,
+        server_config::ServerConfig,
+        state::App,
+        test_util::db_utils::{mock_extractor, DEFAULT_TEST_EXTRACTOR, DEFAULT_TEST_NAMESPACE},
+        test_utils::RaftTestCluster,
+    };
+
+    async fn setup_coordinator() -> (Arc<Coordinator>, Arc<App>) {
+        let config = Arc::new(ServerConfig::default());
+        let _ = fs::remove_dir_all(config.state_store.clone().path.unwrap());
+        let garbage_collector = GarbageCollector::new();
+        let coordinator_client = CoordinatorClient::new(&config.coordinator_addr);
+        let shared_state = App::new(
+            config.clone(),
+            None,
+            garbage_collector.clone(),
+            &config.coordinator_addr,
+        )
+        .await
+        .unwrap();
+        shared_state.initialize_raft().await.unwrap();
+        let coordinator = crate::coordinator::Coordinator::new(
+            shared_state.clone(),
+            coordinator_client,
+            garbage_collector,
+        );
+        (coordinator, shared_state)
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_create_extraction_events() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+
+        // Add a namespace
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        // Add content and ensure that we are creating a extraction event
+        coordinator
+            .create_content_metadata(vec![indexify_coordinator::ContentMetadata {
+                id: "test".to_string(),
+                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                parent_id: "".to_string(),
+                file_name: "test".to_string(),
+                mime: "text/plain".to_string(),
+                created_at: 0,
+                storage_url: "test".to_string(),
+                labels: HashMap::new(),
+                source: "ingestion".to_string(),
+                size_bytes: 100,
+                extraction_policy_ids: HashMap::new(),
+                external_id: "".to_string(),
+                preview: "".to_string(),
+                hash: "".to_string(),
+                expires_at: 0,
+            }])
+            .await?;
+
+        let events = shared_state.unprocessed_state_change_events().await?;
+        assert_eq!(events.len(), 1);
+
+        //  Run scheduler without any bindings to make
+        // sure that the event is processed and we don't have any tasks
+        coordinator.run_scheduler().await?;
+        let events = shared_state.unprocessed_state_change_events().await?;
+        assert_eq!(events.len(), 0);
+        let tasks = shared_state.unassigned_tasks().await?;
+        assert_eq!(tasks.len(), 0);
+
+        // Add extractors and extractor bindings and ensure that we are creating tasks
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        coordinator
+            .create_policy(
+                internal_api::ExtractionPolicy {
+                    id: "test-binding-id".to_string(),
+                    name: "test".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    input_params: serde_json::json!({}),
+                    filters: HashMap::new(),
+                    output_index_name_mapping: HashMap::from([(
+                        "test_output".to_string(),
+                        "test.test_output".to_string(),
+                    )]),
+                    index_name_table_mapping: HashMap::from([(
+                        "test.test_output".to_string(),
+                        "test_namespace.test.test_output".to_string(),
+                    )]),
+                    content_source: "ingestion".to_string(),
+                    version: 0,
+                    content_filter: Default::default(),
+                    backfill_from: None,
+                    retry_policy: Default::default(),
+                    priority: 0,
+                },
+                mock_extractor(),
+            )
+            .await?;
+        assert_eq!(
+            2,
+            shared_state.unprocessed_state_change_events().await?.len()
+        );
+        coordinator.run_scheduler().await?;
+        assert_eq!(
+            0,
+            shared_state.unprocessed_state_change_events().await?.len()
+        );
+        assert_eq!(
+            1,
+            shared_state
+                .tasks_for_executor("test_executor_id", None)
+                .await?
+                .len()
+        );
+        assert_eq!(0, shared_state.unassigned_tasks().await?.len());
+
+        // Add a content with a different source and ensure we don't create a task
+        coordinator
+            .create_content_metadata(vec![indexify_coordinator::ContentMetadata {
+                id: "test2".to_string(),
+                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                parent_id: "test".to_string(),
+                file_name: "test2".to_string(),
+                mime: "text/plain".to_string(),
+                created_at: 0,
+                storage_url: "test2".to_string(),
+                labels: HashMap::new(),
+                source: "some_extractor_produced_this".to_string(),
+                size_bytes: 100,
+                extraction_policy_ids: HashMap::new(),
+                external_id: "".to_string(),
+                preview: "".to_string(),
+                hash: "".to_string(),
+                expires_at: 0,
+            }])
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(
+            0,
+            shared_state.unprocessed_state_change_events().await?.len()
+        );
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await
+            .unwrap();
+        assert_eq!(1, tasks.clone().len());
+        assert_eq!(0, shared_state.unassigned_tasks().await?.len());
+
+        let mut task_clone = tasks[0].clone();
+        task_clone.outcome = internal_api::TaskOutcome::Success;
+        shared_state
+            .update_task(
+                task_clone,
+                Some("test_executor_id".to_string()),
+                vec![],
+                None,
+            )
+            .await
+            .unwrap();
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await
+            .unwrap();
+        assert_eq!(0, tasks.clone().len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_create_multiple_extraction_policies_and_contents() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+
+        // Add a namespace
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        //  Create an extractor, executor and associated extraction policy
+        let extractor = mock_extractor();
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                extractor.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy_1 = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_1".to_string(),
+            name: "extraction_policy_name_1".to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: "ingestion".to_string(),
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy_1.clone(), extractor.clone())
+            .await?;
+
+        //  Create content metadata
+        let content_metadata_1 = indexify_coordinator::ContentMetadata {
+            id: "test_id".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: "ingestion".to_string(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        coordinator
+            .create_content_metadata(vec![content_metadata_1])
+            .await?;
+
+        //  Assert that tasks are created
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+
+        //  Create a different extractor, executor and associated extraction policy
+        let mut extractor_2 = mock_extractor();
+        let extractor_2_name = "MockExtractor2".to_string();
+        extractor_2.name = extractor_2_name.clone();
+        coordinator
+            .register_executor(
+                "localhost:8957",
+                "test_executor_id_2",
+                extractor_2.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy_2 = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_2".to_string(),
+            extractor: extractor_2_name.clone(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: extraction_policy_1.name,
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy_2, extractor_2)
+            .await?;
+
+        //  Create content metadata
+        let content_metadata_2 = indexify_coordinator::ContentMetadata {
+            id: "test_id".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_1.id,
+            size_bytes: 100,
+            ..Default::default()
+        };
+        coordinator
+            .create_content_metadata(vec![content_metadata_2])
+            .await?;
+
+        //  Run the scheduler
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id_2", None)
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    // #[tracing_test::traced_test]
+    async fn test_create_multiple_contents_and_extraction_policies() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+
+        // Add a namespace
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        //  Create content metadata
+        let content_metadata_1 = indexify_coordinator::ContentMetadata {
+            id: "test_id".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: "ingestion".to_string(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        coordinator
+            .create_content_metadata(vec![content_metadata_1])
+            .await?;
+
+        //  Create an extractor, executor and associated extraction policy
+        let extractor = mock_extractor();
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                extractor.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy_1 = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_1".to_string(),
+            name: "extraction_policy_name_1".to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: "ingestion".to_string(),
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy_1.clone(), extractor.clone())
+            .await?;
+
+        //  Assert that tasks are created
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+
+        //  The second part of the test
+
+        //  Create content metadata
+        let content_metadata_2 = indexify_coordinator::ContentMetadata {
+            id: "test_id".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_1.id,
+            size_bytes: 100,
+            ..Default::default()
+        };
+        coordinator
+            .create_content_metadata(vec![content_metadata_2])
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        //  Create a different extractor, executor and associated extraction policy
+        let mut extractor_2 = mock_extractor();
+        let extractor_2_name = "MockExtractor2".to_string();
+        extractor_2.name = extractor_2_name.clone();
+        coordinator
+            .register_executor(
+                "localhost:8957",
+                "test_executor_id_2",
+                extractor_2.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy_2 = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_2".to_string(),
+            extractor: extractor_2_name.clone(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: extraction_policy_1.name,
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy_2, extractor_2)
+            .await?;
+
+        //  Run the scheduler
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id_2", None)
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_form_raft_cluster() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(5, None).await?;
+        cluster.initialize(Duration::from_secs(10)).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_leader_redirect() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(3, None).await?;
+        cluster.initialize(Duration::from_secs(5)).await?;
+
+        //  assert that the seed node is the current leader
+        cluster.assert_is_leader(cluster.seed_node_id).await;
+
+        //  force leader promotion of node 2
+        cluster.force_current_leader_abdication().await?;
+        let new_leader_id = 2;
+        cluster.promote_node_to_leader(new_leader_id).await?;
+        cluster.assert_is_leader(new_leader_id).await;
+
+        //  check leader re-direct
+        let alt_node = cluster.get_raft_node(1)?;
+        let response = alt_node.check_cluster_membership().await?;
+        assert_eq!(response.handled_by, new_leader_id);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_remove_cluster_member() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(3, None).await?;
+        cluster.initialize(Duration::from_secs(5)).await?;
+        assert!(cluster.assert_is_leader(cluster.seed_node_id).await);
+        let (follower_id, removed_id) = match cluster.seed_node_id {
+            0 => (1, 2),
+            1 => (0, 2),
+            _ => (0, 1),
+        };
+        let follower = cluster.get_raft_node(follower_id)?;
+        let members = follower.cluster_members().await?;
+        assert_eq!(members.len(), 3);
+        assert!(members.iter().all(|member| member.voter));
+
+        //  Membership changes sent to a follower are forwarded to the leader
+        follower.remove_node(removed_id).await?;
+        let leader = cluster.get_raft_node(cluster.seed_node_id)?;
+        let members = leader.cluster_members().await?;
+        assert_eq!(members.len(), 2);
+        assert!(members.iter().all(|member| member.node_id != removed_id));
+        assert!(members
+            .iter()
+            .any(|member| member.node_id == cluster.seed_node_id && member.leader));
+
+        assert!(follower.promote_learner(removed_id).await.is_err());
+        assert!(follower.remove_node(removed_id).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_create_and_read_content_tree() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+
+        //  Add a namespace
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        //  Create an extractor, executor and associated extraction policy
+        let extractor = mock_extractor();
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                extractor.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy_1 = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_1".to_string(),
+            name: "extraction_policy_name_1".to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: "ingestion".to_string(),
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy_1.clone(), extractor.clone())
+            .await?;
+
+        //  Create a different extractor, executor and associated extraction policy
+        let mut extractor_2 = mock_extractor();
+        let extractor_2_name = "MockExtractor2".to_string();
+        extractor_2.name = extractor_2_name.clone();
+        coordinator
+            .register_executor(
+                "localhost:8957",
+                "test_executor_id_2",
+                extractor_2.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy_2 = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_2".to_string(),
+            extractor: extractor_2_name.clone(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: extraction_policy_1.name,
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy_2.clone(), extractor_2)
+            .await?;
+
+        //  Build a content tree where the parent content id is the pointer
+        let parent_content = indexify_coordinator::ContentMetadata {
+            id: "test_parent_id".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: "ingestion".to_string(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        let child_content_1 = indexify_coordinator::ContentMetadata {
+            id: "test_child_id_1".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_parent_id".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_1.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        let child_content_2 = indexify_coordinator::ContentMetadata {
+            id: "test_child_id_2".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_parent_id".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_2.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        let child_content_1_child = indexify_coordinator::ContentMetadata {
+            id: "test_child_child_id_1".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_child_id_1".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_1.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        coordinator
+            .create_content_metadata(vec![
+                parent_content.clone(),
+                child_content_1.clone(),
+                child_content_2.clone(),
+                child_content_1_child.clone(),
+            ])
+            .await?;
+
+        let content_tree = coordinator
+            .shared_state
+            .get_content_tree_metadata(&parent_content.id)
+            .await?;
+        assert_eq!(content_tree.len(), 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_tombstone_content_tree() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+
+        //  Add a namespace
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        //  Create an extractor, executor and associated extraction policy
+        let extractor = mock_extractor();
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                extractor.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy_1 = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_1".to_string(),
+            name: "extraction_policy_name_1".to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: "ingestion".to_string(),
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy_1.clone(), extractor.clone())
+            .await?;
+
+        //  Create a different extractor, executor and associated extraction policy
+        let mut extractor_2 = mock_extractor();
+        let extractor_2_name = "MockExtractor2".to_string();
+        extractor_2.name = extractor_2_name.clone();
+        coordinator
+            .register_executor(
+                "localhost:8957",
+                "test_executor_id_2",
+                extractor_2.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy_2 = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_2".to_string(),
+            extractor: extractor_2_name.clone(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: extraction_policy_1.name,
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy_2.clone(), extractor_2)
+            .await?;
+
+        //  Build a content tree where the parent_content is the root
+        let parent_content = indexify_coordinator::ContentMetadata {
+            id: "test_parent_id".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: "ingestion".to_string(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        let child_content_1 = indexify_coordinator::ContentMetadata {
+            id: "test_child_id_1".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_parent_id".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_1.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        let child_content_2 = indexify_coordinator::ContentMetadata {
+            id: "test_child_id_2".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_parent_id".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_2.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        let child_content_1_child = indexify_coordinator::ContentMetadata {
+            id: "test_child_child_id_1".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_child_id_1".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_1.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+
+        //  Build a separate content tree where parent_content_2 is the root
+        let parent_content_2 = indexify_coordinator::ContentMetadata {
+            id: "test_parent_id_2".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: "ingestion".to_string(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        let child_content_2_1 = indexify_coordinator::ContentMetadata {
+            id: "test_child_id_2_1".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_parent_id_2".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_1.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+
+        coordinator
+            .create_content_metadata(vec![
+                parent_content.clone(),
+                child_content_1.clone(),
+                child_content_2.clone(),
+                child_content_1_child.clone(),
+                parent_content_2.clone(),
+                child_content_2_1.clone(),
+            ])
+            .await?;
+
+        coordinator
+            .tombstone_content_metadatas(
+                DEFAULT_TEST_NAMESPACE,
+                &[parent_content.id.clone(), parent_content_2.id.clone()],
+            )
+            .await?;
+        let content_tree = coordinator
+            .shared_state
+            .get_content_tree_metadata(&parent_content.id)
+            .await?;
+        let content_tree_2 = coordinator
+            .shared_state
+            .get_content_tree_metadata(&parent_content_2.id)
+            .await?;
+        for content in &content_tree {
+            assert!(
+                content.tombstoned,
+                "Content {} is not tombstoned",
+                content.id
+            );
+        }
+        for content in content_tree_2 {
+            assert!(
+                content.tombstoned,
+                "Content {} is not tombstoned",
+                content.id
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_match_tombstoned_content() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+
+        //  Add a namespace
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        //  Create an extractor, executor and associated extraction policy
+        let extractor = mock_extractor();
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                extractor.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy_1 = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_1".to_string(),
+            name: "extraction_policy_name_1".to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: "ingestion".to_string(),
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy_1.clone(), extractor.clone())
+            .await?;
+
+        //  Build a content tree where the parent_content is the root
+        let parent_content = indexify_coordinator::ContentMetadata {
+            id: "test_parent_id".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: "ingestion".to_string(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        let child_content_1 = indexify_coordinator::ContentMetadata {
+            id: "test_child_id_1".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_parent_id".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_1.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        let child_content_2 = indexify_coordinator::ContentMetadata {
+            id: "test_child_id_2".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_parent_id".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_1.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        coordinator
+            .create_content_metadata(vec![
+                parent_content.clone(),
+                child_content_1.clone(),
+                child_content_2.clone(),
+            ])
+            .await?;
+
+        //  before tombstone
+        let content_matching_policy = coordinator
+            .shared_state
+            .content_matching_policy(&extraction_policy_1.id)
+            .await?;
+        assert_eq!(content_matching_policy.len(), 1);
+        let policies_matching_content = coordinator
+            .shared_state
+            .filter_extraction_policy_for_content(&parent_content.id)
+            .await?;
+        assert_eq!(policies_matching_content.len(), 1);
+
+        coordinator
+            .shared_state
+            .tombstone_content_batch(DEFAULT_TEST_NAMESPACE, &[parent_content.id.clone()])
+            .await?;
+
+        //  after tombstone
+        let content_matching_policy = coordinator
+            .shared_state
+            .content_matching_policy(&extraction_policy_1.id)
+            .await?;
+        assert_eq!(content_matching_policy.len(), 0);
+        let policies_matching_content = coordinator
+            .shared_state
+            .filter_extraction_policy_for_content(&parent_content.id)
+            .await?;
+        assert_eq!(policies_matching_content.len(), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_gc_tasks_creation() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+
+        //  Add a namespace
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
 
         //  Create an extractor, executor and associated extraction policy
         let extractor = mock_extractor();
         coordinator
-            .register_executor("localhost:8956", "test_executor_id", extractor.clone())
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                extractor.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy_1 = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_1".to_string(),
+            name: "extraction_policy_name_1".to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: "ingestion".to_string(),
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy_1.clone(), extractor.clone())
+            .await?;
+
+        //  Create a different extractor, executor and associated extraction policy
+        let mut extractor_2 = mock_extractor();
+        let extractor_2_name = "MockExtractor2".to_string();
+        extractor_2.name = extractor_2_name.clone();
+        coordinator
+            .register_executor(
+                "localhost:8957",
+                "test_executor_id_2",
+                extractor_2.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy_2 = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_2".to_string(),
+            extractor: extractor_2_name.clone(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: extraction_policy_1.name,
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy_2.clone(), extractor_2)
+            .await?;
+
+        //  Build a content tree where the parent content id is the pointer
+        let parent_content = indexify_coordinator::ContentMetadata {
+            id: "test_parent_id".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: "ingestion".to_string(),
+            size_bytes: 100,
+            extraction_policy_ids: HashMap::from([(extraction_policy_1.id.clone(), 10)]),
+            ..Default::default()
+        };
+        let child_content_1 = indexify_coordinator::ContentMetadata {
+            id: "test_child_id_1".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_parent_id".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_1.id.clone(),
+            size_bytes: 100,
+            //  The task of the second policy is still running
+            extraction_policy_ids: HashMap::from([(extraction_policy_2.id.clone(), 0)]),
+            ..Default::default()
+        };
+        let child_content_2 = indexify_coordinator::ContentMetadata {
+            id: "test_child_id_2".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_parent_id".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_2.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        let child_content_1_child = indexify_coordinator::ContentMetadata {
+            id: "test_child_child_id_1".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_child_id_1".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            created_at: 0,
+            storage_url: "test_storage_url".to_string(),
+            labels: HashMap::new(),
+            source: extraction_policy_1.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        coordinator
+            .create_content_metadata(vec![
+                parent_content.clone(),
+                child_content_1.clone(),
+                child_content_2.clone(),
+                child_content_1_child.clone(),
+            ])
+            .await?;
+
+        let state_change = internal_api::StateChange {
+            object_id: parent_content.id.clone(),
+            ..Default::default()
+        };
+        let tasks = coordinator.handle_tombstone_content(state_change).await?;
+        assert_eq!(tasks.len(), 4);
+        for task in &tasks {
+            match task.content_id.as_str() {
+                "test_parent_id" | "test_child_id_1" => assert_eq!(
+                    task.output_tables,
+                    HashSet::from(["test_namespace.test.test_output".to_string()])
+                ),
+                "test_child_id_2" | "test_child_child_id_1" => {
+                    assert!(task.output_tables.is_empty())
+                }
+                _ => panic!("Unexpected content_id"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_update_extraction_policy() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        coordinator
+            .create_content_metadata(vec![indexify_coordinator::ContentMetadata {
+                id: "test".to_string(),
+                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                file_name: "test".to_string(),
+                mime: "text/plain".to_string(),
+                storage_url: "test".to_string(),
+                labels: HashMap::from([("topic".to_string(), "a".to_string())]),
+                source: "ingestion".to_string(),
+                size_bytes: 100,
+                ..Default::default()
+            }])
+            .await?;
+        coordinator
+            .create_policy(
+                internal_api::ExtractionPolicy {
+                    id: "test-binding-id".to_string(),
+                    name: "test".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    input_params: serde_json::json!({}),
+                    filters: HashMap::from([("topic".to_string(), "a".to_string())]),
+                    output_index_name_mapping: HashMap::from([(
+                        "test_output".to_string(),
+                        "test.test_output".to_string(),
+                    )]),
+                    index_name_table_mapping: HashMap::from([(
+                        "test.test_output".to_string(),
+                        "test_namespace.test.test_output".to_string(),
+                    )]),
+                    content_source: "ingestion".to_string(),
+                    ..Default::default()
+                },
+                mock_extractor(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+        let tasks = || shared_state.tasks_for_executor("test_executor_id", None);
+        assert_eq!(tasks().await?.len(), 1);
+
+        //  Content already extracted by the policy isn't extracted again
+        let filters = HashMap::from([("topic".to_string(), "b".to_string())]);
+        let updated_policy = coordinator
+            .update_policy(
+                "test-binding-id",
+                Some(filters.clone()),
+                None,
+                None,
+                None,
+                false,
+            )
+            .await?;
+        assert_eq!(updated_policy.version, 1);
+        let stored_policy = coordinator.get_extraction_policy("test-binding-id").await?;
+        assert_eq!(stored_policy.filters, filters);
+        assert_eq!(stored_policy.version, 1);
+        let events = shared_state.unprocessed_state_change_events().await?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].change_type,
+            internal_api::ChangeType::ExtractionPolicyUpdated
+        );
+        coordinator.run_scheduler().await?;
+        assert_eq!(tasks().await?.len(), 1);
+
+        //  Re-extraction creates tasks for the content matching the policy
+        let updated_policy = coordinator
+            .update_policy(
+                "test-binding-id",
+                Some(HashMap::new()),
+                None,
+                None,
+                None,
+                true,
+            )
+            .await?;
+        assert_eq!(updated_policy.version, 2);
+        coordinator.run_scheduler().await?;
+        assert_eq!(tasks().await?.len(), 2);
+
+        assert!(coordinator
+            .update_policy("missing-binding-id", None, None, None, None, false)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_delete_extraction_policy() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        let content = |id: &str| indexify_coordinator::ContentMetadata {
+            id: id.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            file_name: "test".to_string(),
+            mime: "text/plain".to_string(),
+            storage_url: "test".to_string(),
+            source: "ingestion".to_string(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        coordinator
+            .create_content_metadata(vec![content("test_1")])
+            .await?;
+        let policy = |id: &str, name: &str, content_source: &str| internal_api::ExtractionPolicy {
+            id: id.to_string(),
+            name: name.to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            input_params: serde_json::json!({}),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                format!("{}.test_output", name),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                format!("{}.test_output", name),
+                format!("test_namespace.{}.test_output", name),
+            )]),
+            content_source: content_source.to_string(),
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(
+                policy("test-binding-id", "test", "ingestion"),
+                mock_extractor(),
+            )
+            .await?;
+        coordinator
+            .create_policy(
+                policy("child-binding-id", "child", "test"),
+                mock_extractor(),
+            )
+            .await?;
+        coordinator
+            .create_index(
+                DEFAULT_TEST_NAMESPACE,
+                internal_api::Index {
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    name: "test.test_output".to_string(),
+                    table_name: "test_namespace.test.test_output".to_string(),
+                    schema: serde_json::json!({"dim": 384, "distance": "cosine"}).to_string(),
+                    extraction_policy: "test".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                },
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await?;
+        assert_eq!(tasks.len(), 1);
+
+        //  The content of the child policy comes from the policy
+        assert!(coordinator
+            .delete_policy(DEFAULT_TEST_NAMESPACE, "test", true)
+            .await
+            .is_err());
+        assert!(coordinator
+            .delete_policy(DEFAULT_TEST_NAMESPACE, "child", false)
+            .await?
+            .is_empty());
+
+        //  The task of the policy is unassigned once the executor is removed,
+        // and it's cancelled along with the policy
+        coordinator.remove_executor("test_executor_id").await?;
+        let dropped_tables = coordinator
+            .delete_policy(DEFAULT_TEST_NAMESPACE, "test", true)
+            .await?;
+        assert_eq!(
+            dropped_tables,
+            vec!["test_namespace.test.test_output".to_string()]
+        );
+        assert!(coordinator
+            .list_policies(DEFAULT_TEST_NAMESPACE)
+            .await?
+            .is_empty());
+        assert!(coordinator
+            .list_indexes(DEFAULT_TEST_NAMESPACE)
+            .await?
+            .is_empty());
+        let task = shared_state.task_with_id(&tasks[0].id).await?;
+        assert_eq!(task.outcome, internal_api::TaskOutcome::Failed);
+        assert!(shared_state.unassigned_tasks().await?.is_empty());
+
+        //  No tasks are created for the deleted policy
+        coordinator
+            .create_content_metadata(vec![content("test_2")])
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert!(shared_state.unassigned_tasks().await?.is_empty());
+
+        assert!(coordinator
+            .delete_policy(DEFAULT_TEST_NAMESPACE, "test", false)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_delete_namespace() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let extractor = mock_extractor();
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                extractor.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let extraction_policy = internal_api::ExtractionPolicy {
+            id: "extraction_policy_id_1".to_string(),
+            name: "extraction_policy_name_1".to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                "test.test_output".to_string(),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                "test.test_output".to_string(),
+                "test_namespace.test.test_output".to_string(),
+            )]),
+            content_source: "ingestion".to_string(),
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(extraction_policy.clone(), extractor)
+            .await?;
+
+        let parent_content = indexify_coordinator::ContentMetadata {
+            id: "test_parent_id".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            storage_url: "test_storage_url".to_string(),
+            source: "ingestion".to_string(),
+            size_bytes: 100,
+            extraction_policy_ids: HashMap::from([(extraction_policy.id.clone(), 10)]),
+            ..Default::default()
+        };
+        let child_content = indexify_coordinator::ContentMetadata {
+            id: "test_child_id".to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            parent_id: "test_parent_id".to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            storage_url: "test_storage_url".to_string(),
+            source: extraction_policy.id.clone(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        coordinator
+            .create_content_metadata(vec![parent_content, child_content])
+            .await?;
+
+        let tasks = coordinator.delete_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        assert_eq!(tasks.len(), 2);
+        let parent_task = tasks
+            .iter()
+            .find(|task| task.content_id == "test_parent_id")
+            .unwrap();
+        assert_eq!(
+            parent_task.output_tables,
+            HashSet::from(["test_namespace.test.test_output".to_string()])
+        );
+
+        assert!(coordinator
+            .get_namespace(DEFAULT_TEST_NAMESPACE)
+            .await?
+            .is_none());
+        assert!(coordinator
+            .list_policies(DEFAULT_TEST_NAMESPACE)
+            .await?
+            .is_empty());
+        let content = coordinator
+            .shared_state
+            .list_content(DEFAULT_TEST_NAMESPACE)
+            .await?;
+        assert_eq!(content.len(), 2);
+        assert!(content.iter().all(|content| content.tombstoned));
+
+        //  The namespace no longer exists
+        assert!(coordinator
+            .delete_namespace(DEFAULT_TEST_NAMESPACE)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_plan_extraction_policy() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        let content = (0..3)
+            .map(|i| indexify_coordinator::ContentMetadata {
+                id: format!("test{}", i),
+                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                parent_id: "".to_string(),
+                file_name: format!("test{}", i),
+                mime: "text/plain".to_string(),
+                created_at: 0,
+                storage_url: format!("test{}", i),
+                labels: HashMap::new(),
+                source: "ingestion".to_string(),
+                size_bytes: 100,
+                extraction_policy_ids: HashMap::new(),
+                external_id: "".to_string(),
+                preview: "".to_string(),
+                hash: "".to_string(),
+                expires_at: 0,
+            })
+            .collect();
+        coordinator.create_content_metadata(content).await?;
+        coordinator
+            .create_policy(
+                internal_api::ExtractionPolicy {
+                    id: "test-binding-id".to_string(),
+                    name: "test".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    input_params: serde_json::json!({}),
+                    filters: HashMap::new(),
+                    output_index_name_mapping: HashMap::from([(
+                        "test_output".to_string(),
+                        "test.test_output".to_string(),
+                    )]),
+                    index_name_table_mapping: HashMap::from([(
+                        "test.test_output".to_string(),
+                        "test_namespace.test.test_output".to_string(),
+                    )]),
+                    content_source: "ingestion".to_string(),
+                    version: 0,
+                    content_filter: Default::default(),
+                    backfill_from: None,
+                    retry_policy: Default::default(),
+                    priority: 0,
+                },
+                mock_extractor(),
+            )
+            .await?;
+
+        let (policy, plan) = coordinator
+            .plan_extraction_policy(
+                DEFAULT_TEST_NAMESPACE,
+                "test",
+                Some(Duration::from_secs(2)),
+                1,
+            )
+            .await?;
+        assert_eq!(policy.id, "test-binding-id");
+        assert_eq!(plan.total_tasks, 3);
+        assert_eq!(plan.total_bytes, 300);
+        assert_eq!(plan.executors, 1);
+        assert_eq!(plan.estimated_completion, Some(Duration::from_secs(6)));
+        assert_eq!(plan.tasks_per_executor.get("test_executor_id"), Some(&3));
+
+        assert!(coordinator
+            .plan_extraction_policy(DEFAULT_TEST_NAMESPACE, "missing", None, 1)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_dry_run_extraction_policy() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        let content = ["a", "b", "a"]
+            .iter()
+            .enumerate()
+            .map(|(i, topic)| indexify_coordinator::ContentMetadata {
+                id: format!("test{}", i),
+                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                file_name: format!("test{}", i),
+                mime: "text/plain".to_string(),
+                created_at: 3 - i as i64,
+                storage_url: format!("test{}", i),
+                labels: HashMap::from([("topic".to_string(), topic.to_string())]),
+                source: "ingestion".to_string(),
+                size_bytes: 100,
+                ..Default::default()
+            })
+            .collect();
+        coordinator.create_content_metadata(content).await?;
+        let mut extraction_policy = internal_api::ExtractionPolicy {
+            name: "test".to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            input_params: serde_json::json!({}),
+            filters: HashMap::from([("topic".to_string(), "a".to_string())]),
+            content_source: "ingestion".to_string(),
+            ..Default::default()
+        };
+        let content_list = coordinator.dry_run_policy(&extraction_policy).await?;
+        assert_eq!(
+            content_list
+                .iter()
+                .map(|content| content.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["test2", "test0"]
+        );
+
+        //  Neither the policy nor any tasks are created
+        coordinator.run_scheduler().await?;
+        assert!(coordinator
+            .list_policies(DEFAULT_TEST_NAMESPACE)
+            .await?
+            .is_empty());
+        assert!(shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await?
+            .is_empty());
+
+        extraction_policy.extractor = "missing".to_string();
+        assert!(coordinator
+            .dry_run_policy(&extraction_policy)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_backfill_of_extraction_policy() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        let content = |id: &str, created_at: i64| indexify_coordinator::ContentMetadata {
+            id: id.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            file_name: id.to_string(),
+            mime: "text/plain".to_string(),
+            created_at,
+            storage_url: id.to_string(),
+            source: "ingestion".to_string(),
+            size_bytes: 100,
+            ..Default::default()
+        };
+        coordinator
+            .create_content_metadata(vec![
+                content("test1", 1),
+                content("test2", 2),
+                content("test3", 3),
+            ])
+            .await?;
+        coordinator
+            .create_policy(
+                internal_api::ExtractionPolicy {
+                    id: "test-binding-id".to_string(),
+                    name: "test".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    input_params: serde_json::json!({}),
+                    output_index_name_mapping: HashMap::from([(
+                        "test_output".to_string(),
+                        "test.test_output".to_string(),
+                    )]),
+                    index_name_table_mapping: HashMap::from([(
+                        "test.test_output".to_string(),
+                        "test_namespace.test.test_output".to_string(),
+                    )]),
+                    content_source: "ingestion".to_string(),
+                    backfill_from: Some(2),
+                    ..Default::default()
+                },
+                mock_extractor(),
+            )
+            .await?;
+        let (_, plan) = coordinator
+            .plan_extraction_policy(DEFAULT_TEST_NAMESPACE, "test", None, 1)
+            .await?;
+        assert_eq!(plan.total_tasks, 2);
+
+        //  Only the existing content created since the backfill time is
+        // extracted
+        coordinator.run_scheduler().await?;
+        let extracted_content = || async {
+            let tasks = shared_state
+                .tasks_for_executor("test_executor_id", None)
+                .await?;
+            Ok::<_, anyhow::Error>(
+                tasks
+                    .into_iter()
+                    .map(|task| task.content_metadata.id)
+                    .collect::<HashSet<_>>(),
+            )
+        };
+        assert_eq!(
+            extracted_content().await?,
+            HashSet::from(["test2".to_string(), "test3".to_string()])
+        );
+
+        //  New content is extracted whatever its creation time
+        coordinator
+            .create_content_metadata(vec![content("test0", 0)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert!(extracted_content().await?.contains("test0"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_retry_failed_task() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        coordinator
+            .create_policy(
+                internal_api::ExtractionPolicy {
+                    id: "test-binding-id".to_string(),
+                    name: "test".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    input_params: serde_json::json!({}),
+                    output_index_name_mapping: HashMap::from([(
+                        "test_output".to_string(),
+                        "test.test_output".to_string(),
+                    )]),
+                    index_name_table_mapping: HashMap::from([(
+                        "test.test_output".to_string(),
+                        "test_namespace.test.test_output".to_string(),
+                    )]),
+                    content_source: "ingestion".to_string(),
+                    retry_policy: internal_api::RetryPolicy {
+                        max_attempts: 2,
+                        initial_backoff_secs: 0,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                mock_extractor(),
+            )
+            .await?;
+        let content = |id: &str| indexify_coordinator::ContentMetadata {
+            id: id.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            file_name: id.to_string(),
+            mime: "text/plain".to_string(),
+            storage_url: id.to_string(),
+            source: "ingestion".to_string(),
+            ..Default::default()
+        };
+        coordinator
+            .create_content_metadata(vec![content("test1"), content("test2")])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await?;
+        assert_eq!(tasks.len(), 2);
+        let fail = |task_id: String| {
+            let coordinator = coordinator.clone();
+            async move {
+                coordinator
+                    .update_task(
+                        &task_id,
+                        "test_executor_id",
+                        internal_api::TaskOutcome::Failed,
+                        vec![],
+                        None,
+                    )
+                    .await
+            }
+        };
+
+        //  A failure puts the task back to be assigned again
+        fail(tasks[0].id.clone()).await?;
+        let task = shared_state.task_with_id(&tasks[0].id).await?;
+        assert_eq!(task.outcome, internal_api::TaskOutcome::Unknown);
+        assert_eq!(task.attempts, 1);
+        assert!(task.retry_at.is_some());
+        assert_eq!(shared_state.unassigned_tasks().await?.len(), 1);
+
+        //  Failed tasks updated through the state machine directly are retried
+        // too, the decision doesn't depend on the coordinator which was leader
+        let mut task = tasks[1].clone();
+        task.outcome = internal_api::TaskOutcome::Failed;
+        shared_state
+            .update_task(task, Some("test_executor_id".to_string()), vec![], None)
+            .await?;
+        let task = shared_state.task_with_id(&tasks[1].id).await?;
+        assert!(task.retrying());
+        assert_eq!(shared_state.unassigned_tasks().await?.len(), 2);
+
+        assert_eq!(coordinator.assign_retried_tasks().await?, 2);
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await?;
+        assert_eq!(tasks.len(), 2);
+        assert!(shared_state.unassigned_tasks().await?.is_empty());
+
+        //  The task isn't retried once it ran out of attempts
+        fail(tasks[0].id.clone()).await?;
+        let task = shared_state.task_with_id(&tasks[0].id).await?;
+        assert_eq!(task.outcome, internal_api::TaskOutcome::Failed);
+        assert!(shared_state.unassigned_tasks().await?.is_empty());
+        assert_eq!(coordinator.assign_retried_tasks().await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_task_priority() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        let policy = |name: &str, priority: i32| internal_api::ExtractionPolicy {
+            id: format!("{}-binding-id", name),
+            name: name.to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            input_params: serde_json::json!({}),
+            output_index_name_mapping: HashMap::from([(
+                "test_output".to_string(),
+                format!("{}.test_output", name),
+            )]),
+            index_name_table_mapping: HashMap::from([(
+                format!("{}.test_output", name),
+                format!("test_namespace.{}.test_output", name),
+            )]),
+            content_source: "ingestion".to_string(),
+            priority,
+            ..Default::default()
+        };
+        coordinator
+            .create_policy(policy("backfill", 0), mock_extractor())
+            .await?;
+        coordinator
+            .create_policy(policy("chat", 10), mock_extractor())
+            .await?;
+        let content = (0..3)
+            .map(|i| indexify_coordinator::ContentMetadata {
+                id: format!("test{}", i),
+                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                file_name: format!("test{}", i),
+                mime: "text/plain".to_string(),
+                created_at: i,
+                storage_url: format!("test{}", i),
+                source: "ingestion".to_string(),
+                ..Default::default()
+            })
+            .collect();
+        coordinator.create_content_metadata(content).await?;
+        coordinator.run_scheduler().await?;
+
+        //  The executor is given the tasks of the policy with the higher
+        // priority first
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await?;
+        assert_eq!(tasks.len(), 6);
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", Some(3))
+            .await?;
+        assert!(tasks
+            .iter()
+            .all(|task| task.extraction_policy_id == "chat-binding-id" && task.priority == 10));
+        assert_eq!(
+            tasks
+                .iter()
+                .map(|task| task.content_metadata.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["test0", "test1", "test2"]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_task_priority_over_earlier_tasks() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
             .await?;
-        let extraction_policy_1 = internal_api::ExtractionPolicy {
-            id: "extraction_policy_id_1".to_string(),
-            name: "extraction_policy_name_1".to_string(),
+        let policy = |name: &str, priority: i32| internal_api::ExtractionPolicy {
+            id: format!("{}-binding-id", name),
+            name: name.to_string(),
             extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
             namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            input_params: serde_json::json!({}),
             output_index_name_mapping: HashMap::from([(
                 "test_output".to_string(),
-                "test.test_output".to_string(),
+                format!("{}.test_output", name),
             )]),
             index_name_table_mapping: HashMap::from([(
-                "test.test_output".to_string(),
-                "test_namespace.test.test_output".to_string(),
+                format!("{}.test_output", name),
+                format!("test_namespace.{}.test_output", name),
             )]),
             content_source: "ingestion".to_string(),
+            priority,
             ..Default::default()
         };
+        let content = |ids: std::ops::Range<i64>| {
+            ids.map(|i| indexify_coordinator::ContentMetadata {
+                id: format!("test{}", i),
+                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                file_name: format!("test{}", i),
+                mime: "text/plain".to_string(),
+                created_at: i,
+                storage_url: format!("test{}", i),
+                source: "ingestion".to_string(),
+                ..Default::default()
+            })
+            .collect()
+        };
+
+        //  Tasks of the low priority policy are allocated to the executor
+        // before the high priority policy is created
         coordinator
-            .create_policy(extraction_policy_1.clone(), extractor.clone())
+            .create_policy(policy("backfill", 0), mock_extractor())
             .await?;
-
-        //  Create content metadata
-        let content_metadata_1 = indexify_coordinator::ContentMetadata {
-            id: "test_id".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: "ingestion".to_string(),
-            size_bytes: 100,
-            ..Default::default()
-        };
+        coordinator.create_content_metadata(content(0..3)).await?;
+        coordinator.run_scheduler().await?;
         coordinator
-            .create_content_metadata(vec![content_metadata_1])
+            .create_policy(
+                internal_api::ExtractionPolicy {
+                    backfill_from: Some(3),
+                    ..policy("chat", 10)
+                },
+                mock_extractor(),
+            )
             .await?;
-
-        //  Assert that tasks are created
+        coordinator.create_content_metadata(content(3..6)).await?;
         coordinator.run_scheduler().await?;
+
         let tasks = shared_state
             .tasks_for_executor("test_executor_id", None)
-            .await
-            .unwrap();
-        assert_eq!(tasks.len(), 1);
+            .await?;
+        assert_eq!(tasks.len(), 9);
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", Some(4))
+            .await?;
+        assert_eq!(
+            tasks
+                .iter()
+                .map(|task| (
+                    task.extraction_policy_id.as_str(),
+                    task.content_metadata.id.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("chat-binding-id", "test3"),
+                ("chat-binding-id", "test4"),
+                ("chat-binding-id", "test5"),
+                ("backfill-binding-id", "test0"),
+            ]
+        );
+        Ok(())
+    }
 
-        //  Create a different extractor, executor and associated extraction policy
-        let mut extractor_2 = mock_extractor();
-        let extractor_2_name = "MockExtractor2".to_string();
-        extractor_2.name = extractor_2_name.clone();
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_index_migration() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
         coordinator
-            .register_executor("localhost:8957", "test_executor_id_2", extractor_2.clone())
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
             .await?;
-        let extraction_policy_2 = internal_api::ExtractionPolicy {
-            id: "extraction_policy_id_2".to_string(),
-            extractor: extractor_2_name.clone(),
+        coordinator
+            .create_content_metadata(vec![indexify_coordinator::ContentMetadata {
+                id: "test".to_string(),
+                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                parent_id: "".to_string(),
+                file_name: "test".to_string(),
+                mime: "text/plain".to_string(),
+                created_at: 0,
+                storage_url: "test".to_string(),
+                labels: HashMap::new(),
+                source: "ingestion".to_string(),
+                size_bytes: 100,
+                extraction_policy_ids: HashMap::new(),
+                external_id: "".to_string(),
+                preview: "".to_string(),
+                hash: "".to_string(),
+                expires_at: 0,
+            }])
+            .await?;
+
+        let policy = |name: &str| internal_api::ExtractionPolicy {
+            id: format!("{}-id", name),
+            name: name.to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
             namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            input_params: serde_json::json!({}),
+            filters: HashMap::new(),
             output_index_name_mapping: HashMap::from([(
                 "test_output".to_string(),
-                "test.test_output".to_string(),
+                format!("{}.test_output", name),
             )]),
             index_name_table_mapping: HashMap::from([(
-                "test.test_output".to_string(),
-                "test_namespace.test.test_output".to_string(),
+                format!("{}.test_output", name),
+                format!("{}.{}.test_output", DEFAULT_TEST_NAMESPACE, name),
             )]),
-            content_source: extraction_policy_1.name,
-            ..Default::default()
+            content_source: "ingestion".to_string(),
+            version: 0,
+            content_filter: Default::default(),
+            backfill_from: None,
+            retry_policy: Default::default(),
+            priority: 0,
+        };
+        let index = |name: &str, dim: usize| internal_api::Index {
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            name: format!("{}.test_output", name),
+            table_name: format!("{}.{}.test_output", DEFAULT_TEST_NAMESPACE, name),
+            schema: serde_json::to_string(&internal_api::EmbeddingSchema {
+                dim,
+                distance: "cosine".to_string(),
+                quantization: None,
+                named_vectors: vec![],
+                ttl_secs: None,
+                sparse: false,
+                index_params: None,
+            })
+            .unwrap(),
+            extraction_policy: name.to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
         };
+        let mut wide_extractor = mock_extractor();
+        wide_extractor.outputs.insert(
+            "test_output".to_string(),
+            internal_api::OutputSchema::Embedding(internal_api::EmbeddingSchema {
+                dim: 768,
+                distance: "cosine".to_string(),
+                quantization: None,
+                named_vectors: vec![],
+                ttl_secs: None,
+                sparse: false,
+                index_params: None,
+            }),
+        );
+
         coordinator
-            .create_policy(extraction_policy_2, extractor_2)
+            .create_policy(policy("test"), mock_extractor())
+            .await?;
+        coordinator
+            .create_index(DEFAULT_TEST_NAMESPACE, index("test", 384))
             .await?;
 
-        //  Create content metadata
-        let content_metadata_2 = indexify_coordinator::ContentMetadata {
-            id: "test_id".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_1.id,
-            size_bytes: 100,
-            ..Default::default()
-        };
+        //  The existing index can't start receiving embeddings of another dimension
+        assert!(coordinator
+            .create_policy(policy("test"), wide_extractor.clone())
+            .await
+            .is_err());
+        assert!(coordinator
+            .create_index(DEFAULT_TEST_NAMESPACE, index("test", 768))
+            .await
+            .is_err());
+        let mut quantized = index("test", 384);
+        quantized.schema = serde_json::to_string(&internal_api::EmbeddingSchema {
+            dim: 384,
+            distance: "cosine".to_string(),
+            quantization: Some(internal_api::IndexQuantization::Scalar {
+                quantile: None,
+                always_ram: false,
+            }),
+            named_vectors: vec![],
+            ttl_secs: None,
+            sparse: false,
+            index_params: None,
+        })
+        .unwrap();
+        assert!(coordinator
+            .create_index(DEFAULT_TEST_NAMESPACE, quantized)
+            .await
+            .is_err());
+
         coordinator
-            .create_content_metadata(vec![content_metadata_2])
+            .create_policy(policy("test_v2"), wide_extractor)
+            .await?;
+        coordinator
+            .create_index(DEFAULT_TEST_NAMESPACE, index("test_v2", 768))
+            .await?;
+        let progress = coordinator
+            .create_index_migration(
+                DEFAULT_TEST_NAMESPACE,
+                "test.test_output",
+                "test_v2.test_output",
+            )
             .await?;
+        assert_eq!(progress.migration.target_extraction_policy, "test_v2");
+        assert_eq!(progress.total_content, 1);
+        assert_eq!(progress.migrated_content, 0);
 
-        //  Run the scheduler
-        coordinator.run_scheduler().await?;
-        let tasks = shared_state
-            .tasks_for_executor("test_executor_id_2", None)
+        //  Nothing was extracted into the new index yet
+        assert!(coordinator
+            .complete_index_migration(DEFAULT_TEST_NAMESPACE, "test.test_output", false)
             .await
-            .unwrap();
-        assert_eq!(tasks.len(), 1);
+            .is_err());
+        let index = coordinator
+            .get_index(DEFAULT_TEST_NAMESPACE, "test.test_output")
+            .await?;
+        assert_eq!(index.name, "test.test_output");
 
+        let progress = coordinator
+            .complete_index_migration(DEFAULT_TEST_NAMESPACE, "test.test_output", true)
+            .await?;
+        assert!(progress.migration.completed);
+        let index = coordinator
+            .get_index(DEFAULT_TEST_NAMESPACE, "test.test_output")
+            .await?;
+        assert_eq!(index.name, "test_v2.test_output");
         Ok(())
     }
 
     #[tokio::test]
-    // #[tracing_test::traced_test]
-    async fn test_create_multiple_contents_and_extraction_policies() -> Result<(), anyhow::Error> {
-        let (coordinator, shared_state) = setup_coordinator().await;
-
-        // Add a namespace
+    #[tracing_test::traced_test]
+    async fn test_rebalance_job() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
         coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        coordinator
+            .create_content_metadata(vec![indexify_coordinator::ContentMetadata {
+                id: "test".to_string(),
+                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                parent_id: "".to_string(),
+                file_name: "test".to_string(),
+                mime: "text/plain".to_string(),
+                created_at: 0,
+                storage_url: "test".to_string(),
+                labels: HashMap::new(),
+                source: "ingestion".to_string(),
+                size_bytes: 100,
+                extraction_policy_ids: HashMap::new(),
+                external_id: "".to_string(),
+                preview: "".to_string(),
+                hash: "".to_string(),
+                expires_at: 0,
+            }])
+            .await?;
+        coordinator
+            .create_policy(
+                internal_api::ExtractionPolicy {
+                    id: "test-binding-id".to_string(),
+                    name: "test".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    input_params: serde_json::json!({}),
+                    filters: HashMap::new(),
+                    output_index_name_mapping: HashMap::from([(
+                        "test_output".to_string(),
+                        "test.test_output".to_string(),
+                    )]),
+                    index_name_table_mapping: HashMap::from([(
+                        "test.test_output".to_string(),
+                        "test_namespace.test.test_output".to_string(),
+                    )]),
+                    content_source: "ingestion".to_string(),
+                    version: 0,
+                    content_filter: Default::default(),
+                    backfill_from: None,
+                    retry_policy: Default::default(),
+                    priority: 0,
+                },
+                mock_extractor(),
+            )
+            .await?;
+        coordinator
+            .create_index(
+                DEFAULT_TEST_NAMESPACE,
+                internal_api::Index {
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    name: "test.test_output".to_string(),
+                    table_name: "test_namespace.test.test_output".to_string(),
+                    schema: serde_json::json!({"dim": 384, "distance": "cosine"}).to_string(),
+                    extraction_policy: "test".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                },
+            )
+            .await?;
 
-        //  Create content metadata
-        let content_metadata_1 = indexify_coordinator::ContentMetadata {
-            id: "test_id".to_string(),
+        let (job, content_ids) = coordinator
+            .create_rebalance_job(
+                DEFAULT_TEST_NAMESPACE,
+                "test.test_output",
+                "test_namespace.test.test_output.1",
+                4,
+                false,
+            )
+            .await?;
+        assert_eq!(content_ids, vec!["test".to_string()]);
+        assert_eq!(job.source_table, "test_namespace.test.test_output");
+        let content = coordinator
+            .list_index_content(DEFAULT_TEST_NAMESPACE, "test.test_output")
+            .await?;
+        assert_eq!(content.len(), 1);
+        assert_eq!(content[0].id, "test");
+        assert_eq!(job.total_content, 1);
+
+        //  New extraction results go to the new table, searches still read the old
+        // one
+        let policies = coordinator.list_policies(DEFAULT_TEST_NAMESPACE).await?;
+        assert_eq!(
+            policies[0].index_name_table_mapping.get("test.test_output"),
+            Some(&"test_namespace.test.test_output.1".to_string())
+        );
+        let index = coordinator
+            .get_index(DEFAULT_TEST_NAMESPACE, "test.test_output")
+            .await?;
+        assert_eq!(index.table_name, "test_namespace.test.test_output");
+
+        //  Only one job can run for an index at a time
+        assert!(coordinator
+            .create_rebalance_job(
+                DEFAULT_TEST_NAMESPACE,
+                "test.test_output",
+                "test_namespace.test.test_output.2",
+                0,
+                true,
+            )
+            .await
+            .is_err());
+
+        let job = coordinator
+            .update_rebalance_job(DEFAULT_TEST_NAMESPACE, "test.test_output", 1, true, "")
+            .await?;
+        assert!(job.completed);
+        assert_eq!(job.moved_vectors, 1);
+        let index = coordinator
+            .get_index(DEFAULT_TEST_NAMESPACE, "test.test_output")
+            .await?;
+        assert_eq!(index.table_name, "test_namespace.test.test_output.1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_blob_migration_job() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let content = |id: &str, storage_url: &str| indexify_coordinator::ContentMetadata {
+            id: id.to_string(),
             namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "".to_string(),
-            file_name: "test_file".to_string(),
+            file_name: id.to_string(),
             mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
+            storage_url: storage_url.to_string(),
             source: "ingestion".to_string(),
             size_bytes: 100,
             ..Default::default()
         };
         coordinator
-            .create_content_metadata(vec![content_metadata_1])
-            .await?;
-
-        //  Create an extractor, executor and associated extraction policy
-        let extractor = mock_extractor();
-        coordinator
-            .register_executor("localhost:8956", "test_executor_id", extractor.clone())
+            .create_content_metadata(vec![
+                content("a", "file:///tmp/blobs/a"),
+                content("b", "s3://bucket/b"),
+            ])
             .await?;
-        let extraction_policy_1 = internal_api::ExtractionPolicy {
-            id: "extraction_policy_id_1".to_string(),
-            name: "extraction_policy_name_1".to_string(),
-            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            output_index_name_mapping: HashMap::from([(
-                "test_output".to_string(),
-                "test.test_output".to_string(),
-            )]),
-            index_name_table_mapping: HashMap::from([(
-                "test.test_output".to_string(),
-                "test_namespace.test.test_output".to_string(),
-            )]),
-            content_source: "ingestion".to_string(),
-            ..Default::default()
-        };
-        coordinator
-            .create_policy(extraction_policy_1.clone(), extractor.clone())
+
+        assert!(coordinator
+            .create_blob_migration_job(DEFAULT_TEST_NAMESPACE, "ftp")
+            .await
+            .is_err());
+        let job = coordinator
+            .create_blob_migration_job(DEFAULT_TEST_NAMESPACE, "s3")
             .await?;
+        assert_eq!(job.total_content, 2);
+        assert_eq!(job.migrated_content, 1);
 
-        //  Assert that tasks are created
-        coordinator.run_scheduler().await?;
-        let tasks = shared_state
-            .tasks_for_executor("test_executor_id", None)
+        //  Only one job can run for a namespace at a time
+        assert!(coordinator
+            .create_blob_migration_job(DEFAULT_TEST_NAMESPACE, "s3")
             .await
-            .unwrap();
-        assert_eq!(tasks.len(), 1);
+            .is_err());
 
-        //  The second part of the test
+        let job = coordinator
+            .update_blob_migration_job(
+                DEFAULT_TEST_NAMESPACE,
+                HashMap::from([("a".to_string(), "s3://bucket/a".to_string())]),
+                true,
+                "",
+            )
+            .await?;
+        assert!(job.completed);
+        assert_eq!(job.migrated_content, 2);
+        let content = coordinator
+            .get_content_metadata(vec!["a".to_string()])
+            .await?;
+        assert_eq!(content[0].storage_url, "s3://bucket/a");
+        Ok(())
+    }
 
-        //  Create content metadata
-        let content_metadata_2 = indexify_coordinator::ContentMetadata {
-            id: "test_id".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "".to_string(),
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_external_id_unique_per_namespace() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator.create_namespace("other_namespace").await?;
+        let content = |id: &str, namespace: &str| indexify_coordinator::ContentMetadata {
+            id: id.to_string(),
+            namespace: namespace.to_string(),
             file_name: "test_file".to_string(),
             mime: "text/plain".to_string(),
-            created_at: 0,
             storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_1.id,
-            size_bytes: 100,
+            source: "ingestion".to_string(),
+            external_id: "doc-1".to_string(),
             ..Default::default()
         };
         coordinator
-            .create_content_metadata(vec![content_metadata_2])
+            .create_content_metadata(vec![content("test_id_1", DEFAULT_TEST_NAMESPACE)])
             .await?;
-        coordinator.run_scheduler().await?;
 
-        //  Create a different extractor, executor and associated extraction policy
-        let mut extractor_2 = mock_extractor();
-        let extractor_2_name = "MockExtractor2".to_string();
-        extractor_2.name = extractor_2_name.clone();
-        coordinator
-            .register_executor("localhost:8957", "test_executor_id_2", extractor_2.clone())
-            .await?;
-        let extraction_policy_2 = internal_api::ExtractionPolicy {
-            id: "extraction_policy_id_2".to_string(),
-            extractor: extractor_2_name.clone(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            output_index_name_mapping: HashMap::from([(
-                "test_output".to_string(),
-                "test.test_output".to_string(),
-            )]),
-            index_name_table_mapping: HashMap::from([(
-                "test.test_output".to_string(),
-                "test_namespace.test.test_output".to_string(),
-            )]),
-            content_source: extraction_policy_1.name,
-            ..Default::default()
-        };
+        //  Writing the same content again keeps its external id
         coordinator
-            .create_policy(extraction_policy_2, extractor_2)
+            .create_content_metadata(vec![content("test_id_1", DEFAULT_TEST_NAMESPACE)])
             .await?;
 
-        //  Run the scheduler
-        coordinator.run_scheduler().await?;
-        let tasks = shared_state
-            .tasks_for_executor("test_executor_id_2", None)
+        //  Other content in the namespace can't reuse the external id
+        assert!(coordinator
+            .create_content_metadata(vec![content("test_id_2", DEFAULT_TEST_NAMESPACE)])
             .await
-            .unwrap();
-        assert_eq!(tasks.len(), 1);
+            .is_err());
+
+        //  External ids are scoped to a namespace
+        coordinator
+            .create_content_metadata(vec![content("test_id_3", "other_namespace")])
+            .await?;
 
+        let content_list = coordinator
+            .list_content(DEFAULT_TEST_NAMESPACE, "", "", "doc-1", &HashMap::new(), "")
+            .await?;
+        assert_eq!(content_list.len(), 1);
+        assert_eq!(content_list[0].id, "test_id_1");
         Ok(())
     }
 
     #[tokio::test]
     #[tracing_test::traced_test]
-    async fn test_form_raft_cluster() -> Result<(), anyhow::Error> {
-        let cluster = RaftTestCluster::new(5, None).await?;
-        cluster.initialize(Duration::from_secs(10)).await?;
+    async fn test_operation_tokens() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator
+            .create_namespace_with_token(DEFAULT_TEST_NAMESPACE, "token-1")
+            .await?;
+        let operation = coordinator
+            .applied_operation(
+                "token-1",
+                internal_api::OperationKind::CreateNamespace,
+                DEFAULT_TEST_NAMESPACE,
+            )
+            .await?
+            .unwrap();
+        assert_eq!(operation.resource_id, DEFAULT_TEST_NAMESPACE);
+
+        //  A retry with the same token is a no-op
+        coordinator
+            .create_namespace_with_token(DEFAULT_TEST_NAMESPACE, "token-1")
+            .await?;
+
+        //  The token can't be reused for another operation
+        assert!(coordinator
+            .create_namespace_with_token("other_namespace", "token-1")
+            .await
+            .is_err());
+
+        //  Requests racing past the check are rejected by the state machine
+        let err = shared_state
+            .create_namespace_with_operation("other_namespace", Some(operation.clone()))
+            .await
+            .unwrap_err();
+        assert!(err
+            .downcast_ref::<crate::state::store::RequestIdConflict>()
+            .is_some());
+        assert!(coordinator
+            .get_namespace("other_namespace")
+            .await?
+            .is_none());
+
+        //  A retry racing past the check is skipped
+        shared_state
+            .create_namespace_with_operation(DEFAULT_TEST_NAMESPACE, Some(operation.clone()))
+            .await?;
+
+        //  Tokens are forgotten once they're older than a day, as other
+        // operations are recorded
+        shared_state
+            .create_namespace_with_operation(
+                "later_namespace",
+                Some(internal_api::OperationRecord {
+                    token: "token-2".to_string(),
+                    created_at: operation.created_at + 2 * 24 * 60 * 60,
+                    ..operation
+                }),
+            )
+            .await?;
+        assert!(shared_state
+            .get_operation_record("token-1")
+            .await?
+            .is_none());
+        assert!(shared_state
+            .get_operation_record("token-2")
+            .await?
+            .is_some());
         Ok(())
     }
 
     #[tokio::test]
     #[tracing_test::traced_test]
-    async fn test_leader_redirect() -> Result<(), anyhow::Error> {
-        let cluster = RaftTestCluster::new(3, None).await?;
-        cluster.initialize(Duration::from_secs(5)).await?;
-
-        //  assert that the seed node is the current leader
-        cluster.assert_is_leader(cluster.seed_node_id).await;
+    async fn test_ingest_intents() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let intent = |content_id: &str, created_at: u64| internal_api::IngestIntent {
+            content_id: content_id.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            storage_url: format!("file:///tmp/{}", content_id),
+            ingestion_server_id: "server_1".to_string(),
+            created_at,
+        };
+        coordinator
+            .create_ingest_intent(intent("finished", 10))
+            .await?;
+        coordinator
+            .create_ingest_intent(intent("abandoned", 10))
+            .await?;
+        coordinator
+            .create_ingest_intent(intent("recent", 100))
+            .await?;
+        assert_eq!(coordinator.list_ingest_intents(0).await?.len(), 3);
 
-        //  force leader promotion of node 2
-        cluster.force_current_leader_abdication().await?;
-        let new_leader_id = 2;
-        cluster.promote_node_to_leader(new_leader_id).await?;
-        cluster.assert_is_leader(new_leader_id).await;
+        //  Creating the content removes its intent
+        coordinator
+            .create_content_metadata(vec![indexify_coordinator::ContentMetadata {
+                id: "finished".to_string(),
+                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                file_name: "finished".to_string(),
+                mime: "text/plain".to_string(),
+                storage_url: "file:///tmp/finished".to_string(),
+                source: "ingestion".to_string(),
+                ..Default::default()
+            }])
+            .await?;
+        let intents = coordinator.list_ingest_intents(50).await?;
+        assert_eq!(intents, vec![intent("abandoned", 10)]);
 
-        //  check leader re-direct
-        let alt_node = cluster.get_raft_node(1)?;
-        let response = alt_node.check_cluster_membership().await?;
-        assert_eq!(response.handled_by, new_leader_id);
+        coordinator
+            .delete_ingest_intents(vec!["abandoned".to_string()])
+            .await?;
+        let intents = coordinator.list_ingest_intents(0).await?;
+        assert_eq!(intents, vec![intent("recent", 100)]);
         Ok(())
     }
 
     #[tokio::test]
     #[tracing_test::traced_test]
-    async fn test_create_and_read_content_tree() -> Result<(), anyhow::Error> {
+    async fn test_embedding_expirations() -> Result<(), anyhow::Error> {
         let (coordinator, _) = setup_coordinator().await;
-
-        //  Add a namespace
         coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let content =
+            |id: &str, parent_id: &str, expires_at: u64| indexify_coordinator::ContentMetadata {
+                id: id.to_string(),
+                parent_id: parent_id.to_string(),
+                namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                file_name: id.to_string(),
+                mime: "text/plain".to_string(),
+                storage_url: format!("file:///tmp/{}", id),
+                source: "ingestion".to_string(),
+                expires_at,
+                ..Default::default()
+            };
 
-        //  Create an extractor, executor and associated extraction policy
-        let extractor = mock_extractor();
+        //  Extracted content inherits the expiration of its parent, unless its
+        // own is earlier
         coordinator
-            .register_executor("localhost:8956", "test_executor_id", extractor.clone())
+            .create_content_metadata(vec![content("parent", "", 100)])
             .await?;
-        let extraction_policy_1 = internal_api::ExtractionPolicy {
-            id: "extraction_policy_id_1".to_string(),
-            name: "extraction_policy_name_1".to_string(),
-            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+        let created = coordinator
+            .create_content_metadata(vec![content("chunk_1", "parent", 0)])
+            .await?;
+        assert_eq!(created[0].expires_at, 100);
+        let created = coordinator
+            .create_content_metadata(vec![content("chunk_2", "parent", 50)])
+            .await?;
+        assert_eq!(created[0].expires_at, 50);
+
+        let expiration = |content_id: &str, expires_at: u64| internal_api::EmbeddingExpiration {
             namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            output_index_name_mapping: HashMap::from([(
-                "test_output".to_string(),
-                "test.test_output".to_string(),
-            )]),
-            index_name_table_mapping: HashMap::from([(
-                "test.test_output".to_string(),
-                "test_namespace.test.test_output".to_string(),
-            )]),
-            content_source: "ingestion".to_string(),
-            ..Default::default()
+            index_table: "default.embeddings.embedding".to_string(),
+            content_id: content_id.to_string(),
+            hash: "".to_string(),
+            expires_at,
         };
         coordinator
-            .create_policy(extraction_policy_1.clone(), extractor.clone())
+            .create_embedding_expirations(vec![
+                expiration("chunk_1", 100),
+                expiration("chunk_2", 50),
+            ])
             .await?;
+        assert_eq!(coordinator.list_embedding_expirations(0).await?.len(), 2);
+        let expired = coordinator.list_embedding_expirations(60).await?;
+        assert_eq!(expired, vec![expiration("chunk_2", 50)]);
 
-        //  Create a different extractor, executor and associated extraction policy
-        let mut extractor_2 = mock_extractor();
-        let extractor_2_name = "MockExtractor2".to_string();
-        extractor_2.name = extractor_2_name.clone();
+        //  Writing the embedding again replaces its expiration
         coordinator
-            .register_executor("localhost:8957", "test_executor_id_2", extractor_2.clone())
+            .create_embedding_expirations(vec![expiration("chunk_2", 200)])
             .await?;
-        let extraction_policy_2 = internal_api::ExtractionPolicy {
-            id: "extraction_policy_id_2".to_string(),
-            extractor: extractor_2_name.clone(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            output_index_name_mapping: HashMap::from([(
-                "test_output".to_string(),
-                "test.test_output".to_string(),
-            )]),
-            index_name_table_mapping: HashMap::from([(
-                "test.test_output".to_string(),
-                "test_namespace.test.test_output".to_string(),
-            )]),
-            content_source: extraction_policy_1.name,
-            ..Default::default()
-        };
+        assert_eq!(coordinator.list_embedding_expirations(150).await?.len(), 1);
+
+        coordinator.delete_embedding_expirations(expired).await?;
+        let expirations = coordinator.list_embedding_expirations(0).await?;
+        assert_eq!(expirations, vec![expiration("chunk_1", 100)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_processing_slas() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
         coordinator
-            .create_policy(extraction_policy_2.clone(), extractor_2)
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
             .await?;
-
-        //  Build a content tree where the parent content id is the pointer
-        let parent_content = indexify_coordinator::ContentMetadata {
-            id: "test_parent_id".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: "ingestion".to_string(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-        let child_content_1 = indexify_coordinator::ContentMetadata {
-            id: "test_child_id_1".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_parent_id".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_1.id.clone(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-        let child_content_2 = indexify_coordinator::ContentMetadata {
-            id: "test_child_id_2".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_parent_id".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_2.id.clone(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-        let child_content_1_child = indexify_coordinator::ContentMetadata {
-            id: "test_child_child_id_1".to_string(),
+        coordinator
+            .create_policy(
+                internal_api::ExtractionPolicy {
+                    id: "test-binding-id".to_string(),
+                    name: "test".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    input_params: serde_json::json!({}),
+                    filters: HashMap::new(),
+                    output_index_name_mapping: HashMap::new(),
+                    index_name_table_mapping: HashMap::new(),
+                    content_source: "ingestion".to_string(),
+                    version: 0,
+                    content_filter: Default::default(),
+                    backfill_from: None,
+                    retry_policy: Default::default(),
+                    priority: 0,
+                },
+                mock_extractor(),
+            )
+            .await?;
+        let sla = internal_api::ProcessingSla {
             namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_child_id_1".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_1.id.clone(),
-            size_bytes: 100,
+            extraction_policy: "test".to_string(),
+            target_secs: 600,
+            attainment_threshold: 0.95,
+            window_secs: 3600,
             ..Default::default()
         };
-        coordinator
-            .create_content_metadata(vec![
-                parent_content.clone(),
-                child_content_1.clone(),
-                child_content_2.clone(),
-                child_content_1_child.clone(),
-            ])
-            .await?;
 
-        let content_tree = coordinator
-            .shared_state
-            .get_content_tree_metadata(&parent_content.id)
-            .await?;
-        assert_eq!(content_tree.len(), 4);
+        //  The sla refers to the policy by id, like tasks do
+        let sla = coordinator.set_processing_sla(sla).await?;
+        assert_eq!(sla.extraction_policy_id, "test-binding-id");
+        assert_eq!(
+            coordinator
+                .list_processing_slas(DEFAULT_TEST_NAMESPACE)
+                .await?,
+            vec![sla.clone()]
+        );
+
+        assert!(coordinator
+            .set_processing_sla(internal_api::ProcessingSla {
+                extraction_policy: "missing".to_string(),
+                ..sla.clone()
+            })
+            .await
+            .is_err());
 
+        coordinator
+            .delete_processing_sla(DEFAULT_TEST_NAMESPACE, "test")
+            .await?;
+        assert!(coordinator
+            .list_processing_slas(DEFAULT_TEST_NAMESPACE)
+            .await?
+            .is_empty());
+        assert!(coordinator
+            .delete_processing_sla(DEFAULT_TEST_NAMESPACE, "test")
+            .await
+            .is_err());
         Ok(())
     }
 
     #[tokio::test]
     #[tracing_test::traced_test]
-    async fn test_tombstone_content_tree() -> Result<(), anyhow::Error> {
+    async fn test_evolve_structured_data_schema() -> Result<(), anyhow::Error> {
         let (coordinator, _) = setup_coordinator().await;
-
-        //  Add a namespace
         coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
-
-        //  Create an extractor, executor and associated extraction policy
-        let extractor = mock_extractor();
+        let extractor = |columns: Vec<(&str, internal_api::SchemaColumnType)>| {
+            let mut extractor = mock_extractor();
+            extractor.outputs = HashMap::from([(
+                "attributes".to_string(),
+                internal_api::OutputSchema::Attributes(
+                    columns
+                        .into_iter()
+                        .map(|(name, dtype)| (name.to_string(), dtype))
+                        .collect(),
+                ),
+            )]);
+            extractor
+        };
+        let v1 = extractor(vec![("label", internal_api::SchemaColumnType::Text)]);
         coordinator
-            .register_executor("localhost:8956", "test_executor_id", extractor.clone())
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                v1.clone(),
+                HashMap::new(),
+            )
             .await?;
-        let extraction_policy_1 = internal_api::ExtractionPolicy {
-            id: "extraction_policy_id_1".to_string(),
-            name: "extraction_policy_name_1".to_string(),
-            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            output_index_name_mapping: HashMap::from([(
-                "test_output".to_string(),
-                "test.test_output".to_string(),
-            )]),
-            index_name_table_mapping: HashMap::from([(
-                "test.test_output".to_string(),
-                "test_namespace.test.test_output".to_string(),
-            )]),
-            content_source: "ingestion".to_string(),
-            ..Default::default()
-        };
         coordinator
-            .create_policy(extraction_policy_1.clone(), extractor.clone())
+            .create_policy(
+                internal_api::ExtractionPolicy {
+                    id: "test-binding-id".to_string(),
+                    name: "test".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    content_source: "ingestion".to_string(),
+                    ..Default::default()
+                },
+                v1,
+            )
             .await?;
+        let schema = coordinator
+            .get_schema(DEFAULT_TEST_NAMESPACE, "ingestion")
+            .await?;
+        assert_eq!(schema.columns.len(), 1);
 
-        //  Create a different extractor, executor and associated extraction policy
-        let mut extractor_2 = mock_extractor();
-        let extractor_2_name = "MockExtractor2".to_string();
-        extractor_2.name = extractor_2_name.clone();
+        //  A new version of the extractor adds a field and changes the type of
+        // an existing one, only the new field is added
+        let v2 = extractor(vec![
+            ("label", internal_api::SchemaColumnType::Int),
+            ("score", internal_api::SchemaColumnType::Float),
+        ]);
         coordinator
-            .register_executor("localhost:8957", "test_executor_id_2", extractor_2.clone())
+            .register_executor("localhost:8957", "test_executor_id_2", v2, HashMap::new())
             .await?;
-        let extraction_policy_2 = internal_api::ExtractionPolicy {
-            id: "extraction_policy_id_2".to_string(),
-            extractor: extractor_2_name.clone(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            output_index_name_mapping: HashMap::from([(
-                "test_output".to_string(),
-                "test.test_output".to_string(),
-            )]),
-            index_name_table_mapping: HashMap::from([(
-                "test.test_output".to_string(),
-                "test_namespace.test.test_output".to_string(),
-            )]),
-            content_source: extraction_policy_1.name,
-            ..Default::default()
-        };
-        coordinator
-            .create_policy(extraction_policy_2.clone(), extractor_2)
+        let schema = coordinator
+            .get_schema(DEFAULT_TEST_NAMESPACE, "ingestion")
             .await?;
+        assert_eq!(schema.columns.len(), 2);
+        assert_eq!(
+            schema.columns["label"],
+            internal_api::SchemaColumnType::Text
+        );
+        assert_eq!(
+            schema.columns["score"],
+            internal_api::SchemaColumnType::Float
+        );
+        Ok(())
+    }
 
-        //  Build a content tree where the parent_content is the root
-        let parent_content = indexify_coordinator::ContentMetadata {
-            id: "test_parent_id".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: "ingestion".to_string(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-        let child_content_1 = indexify_coordinator::ContentMetadata {
-            id: "test_child_id_1".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_parent_id".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_1.id.clone(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-        let child_content_2 = indexify_coordinator::ContentMetadata {
-            id: "test_child_id_2".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_parent_id".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_2.id.clone(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-        let child_content_1_child = indexify_coordinator::ContentMetadata {
-            id: "test_child_child_id_1".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_child_id_1".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_1.id.clone(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-
-        //  Build a separate content tree where parent_content_2 is the root
-        let parent_content_2 = indexify_coordinator::ContentMetadata {
-            id: "test_parent_id_2".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: "ingestion".to_string(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-        let child_content_2_1 = indexify_coordinator::ContentMetadata {
-            id: "test_child_id_2_1".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_parent_id_2".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_1.id.clone(),
-            size_bytes: 100,
-            ..Default::default()
-        };
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_chunk_references() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        let table = "test_namespace.test.test_output";
+        let reference = coordinator
+            .add_chunk_reference(table, "hash_1", "content_1")
+            .await?;
+        assert_eq!(reference.owner(), Some(&"content_1".to_string()));
 
+        //  Adding identical content keeps the first content as the owner and
+        // adding the same content twice doesn't duplicate it
         coordinator
-            .create_content_metadata(vec![
-                parent_content.clone(),
-                child_content_1.clone(),
-                child_content_2.clone(),
-                child_content_1_child.clone(),
-                parent_content_2.clone(),
-                child_content_2_1.clone(),
-            ])
+            .add_chunk_reference(table, "hash_1", "content_2")
+            .await?;
+        let reference = coordinator
+            .add_chunk_reference(table, "hash_1", "content_2")
+            .await?;
+        assert_eq!(reference.content_ids, vec!["content_1", "content_2"]);
+
+        //  References are kept per index
+        let reference = coordinator
+            .add_chunk_reference("other_table", "hash_1", "content_2")
             .await?;
+        assert_eq!(reference.owner(), Some(&"content_2".to_string()));
 
         coordinator
-            .tombstone_content_metadatas(
-                DEFAULT_TEST_NAMESPACE,
-                &[parent_content.id.clone(), parent_content_2.id.clone()],
-            )
+            .remove_chunk_reference(table, "hash_1", "content_1")
             .await?;
-        let content_tree = coordinator
-            .shared_state
-            .get_content_tree_metadata(&parent_content.id)
+        let references = coordinator
+            .get_chunk_references(table, &["hash_1".to_string(), "hash_2".to_string()])
             .await?;
-        let content_tree_2 = coordinator
-            .shared_state
-            .get_content_tree_metadata(&parent_content_2.id)
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].owner(), Some(&"content_2".to_string()));
+
+        coordinator
+            .remove_chunk_reference(table, "hash_1", "content_2")
             .await?;
-        for content in &content_tree {
-            assert!(
-                content.tombstoned,
-                "Content {} is not tombstoned",
-                content.id
-            );
-        }
-        for content in content_tree_2 {
-            assert!(
-                content.tombstoned,
-                "Content {} is not tombstoned",
-                content.id
-            );
-        }
+        let references = coordinator
+            .get_chunk_references(table, &["hash_1".to_string()])
+            .await?;
+        assert!(references.is_empty());
         Ok(())
     }
 
     #[tokio::test]
     #[tracing_test::traced_test]
-    async fn test_match_tombstoned_content() -> Result<(), anyhow::Error> {
+    async fn test_query_templates() -> Result<(), anyhow::Error> {
         let (coordinator, _) = setup_coordinator().await;
-
-        //  Add a namespace
         coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let query_template = internal_api::QueryTemplate {
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            name: "by_topic".to_string(),
+            index: "test.test_output".to_string(),
+            query: "articles about {topic}".to_string(),
+            k: Some(3),
+            filters: HashMap::from([("source".to_string(), "news".to_string())]),
+            parameters: HashMap::new(),
+            scoring_plugin: None,
+        };
+
+        //  The index has to exist before a template can search it
+        assert!(coordinator
+            .set_query_template(query_template.clone())
+            .await
+            .is_err());
 
-        //  Create an extractor, executor and associated extraction policy
-        let extractor = mock_extractor();
         coordinator
-            .register_executor("localhost:8956", "test_executor_id", extractor.clone())
+            .create_index(
+                DEFAULT_TEST_NAMESPACE,
+                internal_api::Index {
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    name: "test.test_output".to_string(),
+                    table_name: "test_namespace.test.test_output".to_string(),
+                    schema: serde_json::json!({"dim": 384, "distance": "cosine"}).to_string(),
+                    extraction_policy: "test".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                },
+            )
             .await?;
-        let extraction_policy_1 = internal_api::ExtractionPolicy {
-            id: "extraction_policy_id_1".to_string(),
-            name: "extraction_policy_name_1".to_string(),
-            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            output_index_name_mapping: HashMap::from([(
-                "test_output".to_string(),
-                "test.test_output".to_string(),
-            )]),
-            index_name_table_mapping: HashMap::from([(
-                "test.test_output".to_string(),
-                "test_namespace.test.test_output".to_string(),
-            )]),
-            content_source: "ingestion".to_string(),
-            ..Default::default()
-        };
         coordinator
-            .create_policy(extraction_policy_1.clone(), extractor.clone())
+            .set_query_template(query_template.clone())
             .await?;
 
-        //  Build a content tree where the parent_content is the root
-        let parent_content = indexify_coordinator::ContentMetadata {
-            id: "test_parent_id".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: "ingestion".to_string(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-        let child_content_1 = indexify_coordinator::ContentMetadata {
-            id: "test_child_id_1".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_parent_id".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_1.id.clone(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-        let child_content_2 = indexify_coordinator::ContentMetadata {
-            id: "test_child_id_2".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_parent_id".to_string(),
+        let saved = coordinator
+            .get_query_template(DEFAULT_TEST_NAMESPACE, "by_topic")
+            .await?;
+        assert_eq!(saved, query_template);
+        assert_eq!(
+            coordinator
+                .list_query_templates(DEFAULT_TEST_NAMESPACE)
+                .await?
+                .len(),
+            1
+        );
+        assert!(coordinator
+            .list_query_templates("other_namespace")
+            .await?
+            .is_empty());
+
+        coordinator
+            .delete_query_template(DEFAULT_TEST_NAMESPACE, "by_topic")
+            .await?;
+        assert!(coordinator
+            .get_query_template(DEFAULT_TEST_NAMESPACE, "by_topic")
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_watch_state_changes() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator.create_namespace("other_namespace").await?;
+        let mut state_changes_rx = coordinator.watch_state_changes(
+            DEFAULT_TEST_NAMESPACE.to_string(),
+            HashSet::from(["NewContent".to_string()]),
+        );
+
+        let content = |id: &str, namespace: &str| indexify_coordinator::ContentMetadata {
+            id: id.to_string(),
+            namespace: namespace.to_string(),
             file_name: "test_file".to_string(),
             mime: "text/plain".to_string(),
-            created_at: 0,
             storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_1.id.clone(),
+            source: "ingestion".to_string(),
             size_bytes: 100,
             ..Default::default()
         };
         coordinator
-            .create_content_metadata(vec![
-                parent_content.clone(),
-                child_content_1.clone(),
-                child_content_2.clone(),
-            ])
-            .await?;
-
-        //  before tombstone
-        let content_matching_policy = coordinator
-            .shared_state
-            .content_matching_policy(&extraction_policy_1.id)
-            .await?;
-        assert_eq!(content_matching_policy.len(), 1);
-        let policies_matching_content = coordinator
-            .shared_state
-            .filter_extraction_policy_for_content(&parent_content.id)
+            .create_content_metadata(vec![content("other_content_id", "other_namespace")])
             .await?;
-        assert_eq!(policies_matching_content.len(), 1);
-
         coordinator
-            .shared_state
-            .tombstone_content_batch(DEFAULT_TEST_NAMESPACE, &[parent_content.id.clone()])
+            .create_content_metadata(vec![content("test_content_id", DEFAULT_TEST_NAMESPACE)])
             .await?;
 
-        //  after tombstone
-        let content_matching_policy = coordinator
-            .shared_state
-            .content_matching_policy(&extraction_policy_1.id)
+        //  Changes of other namespaces and of other types are filtered out
+        let (change, namespace) =
+            tokio::time::timeout(Duration::from_secs(5), state_changes_rx.recv())
+                .await?
+                .unwrap();
+        assert_eq!(change.object_id, "test_content_id");
+        assert_eq!(change.change_type, internal_api::ChangeType::NewContent);
+        assert_eq!(namespace.as_deref(), Some(DEFAULT_TEST_NAMESPACE));
+        Ok(())
+    }
+    #[tokio::test]
+    async fn test_list_executors_by_labels() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        let labels = |zone: &str| {
+            HashMap::from([
+                (
+                    internal_api::EXECUTOR_REGION_LABEL.to_string(),
+                    "us-east-1".to_string(),
+                ),
+                (
+                    internal_api::EXECUTOR_ZONE_LABEL.to_string(),
+                    zone.to_string(),
+                ),
+            ])
+        };
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "executor_a",
+                mock_extractor(),
+                labels("us-east-1a"),
+            )
             .await?;
-        assert_eq!(content_matching_policy.len(), 0);
-        let policies_matching_content = coordinator
-            .shared_state
-            .filter_extraction_policy_for_content(&parent_content.id)
+        coordinator
+            .register_executor(
+                "localhost:8957",
+                "executor_b",
+                mock_extractor(),
+                labels("us-east-1b"),
+            )
             .await?;
-        assert_eq!(policies_matching_content.len(), 0);
 
+        let executor_ids = |executors: Vec<internal_api::ExecutorMetadata>| {
+            let mut ids: Vec<_> = executors.into_iter().map(|executor| executor.id).collect();
+            ids.sort();
+            ids
+        };
+        let region = HashMap::from([(
+            internal_api::EXECUTOR_REGION_LABEL.to_string(),
+            "us-east-1".to_string(),
+        )]);
+        assert_eq!(
+            executor_ids(coordinator.list_executors(&region).await?),
+            vec!["executor_a", "executor_b"]
+        );
+        let executors = coordinator.list_executors(&labels("us-east-1b")).await?;
+        assert_eq!(executors[0].labels, labels("us-east-1b"));
+        assert_eq!(executor_ids(executors), vec!["executor_b"]);
+        let hardware_class = HashMap::from([(
+            internal_api::EXECUTOR_HARDWARE_CLASS_LABEL.to_string(),
+            "gpu".to_string(),
+        )]);
+        assert!(coordinator
+            .list_executors(&hardware_class)
+            .await?
+            .is_empty());
         Ok(())
     }
 
     #[tokio::test]
-    #[tracing_test::traced_test]
-    async fn test_gc_tasks_creation() -> Result<(), anyhow::Error> {
+    async fn test_namespace_quotas() -> Result<(), anyhow::Error> {
         let (coordinator, _) = setup_coordinator().await;
-
-        //  Add a namespace
         coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
-
-        //  Create an extractor, executor and associated extraction policy
-        let extractor = mock_extractor();
         coordinator
-            .register_executor("localhost:8956", "test_executor_id", extractor.clone())
+            .set_namespace_quotas(
+                DEFAULT_TEST_NAMESPACE,
+                internal_api::NamespaceQuotas {
+                    max_content: Some(1),
+                    max_extraction_policies: Some(1),
+                    ..Default::default()
+                },
+            )
             .await?;
-        let extraction_policy_1 = internal_api::ExtractionPolicy {
-            id: "extraction_policy_id_1".to_string(),
-            name: "extraction_policy_name_1".to_string(),
-            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+        assert!(coordinator
+            .set_namespace_quotas("missing_namespace", Default::default())
+            .await
+            .is_err());
+
+        let content = |id: &str, parent_id: &str| indexify_coordinator::ContentMetadata {
+            id: id.to_string(),
             namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            output_index_name_mapping: HashMap::from([(
-                "test_output".to_string(),
-                "test.test_output".to_string(),
-            )]),
-            index_name_table_mapping: HashMap::from([(
-                "test.test_output".to_string(),
-                "test_namespace.test.test_output".to_string(),
-            )]),
-            content_source: "ingestion".to_string(),
+            parent_id: parent_id.to_string(),
+            file_name: "test_file".to_string(),
+            mime: "text/plain".to_string(),
+            storage_url: "test_storage_url".to_string(),
+            source: "ingestion".to_string(),
+            size_bytes: 100,
             ..Default::default()
         };
         coordinator
-            .create_policy(extraction_policy_1.clone(), extractor.clone())
+            .create_content_metadata(vec![content("content_1", "")])
             .await?;
+        let err = coordinator
+            .create_content_metadata(vec![content("content_2", "")])
+            .await
+            .unwrap_err();
+        let quota_exceeded = err
+            .downcast_ref::<crate::state::store::NamespaceQuotaExceeded>()
+            .unwrap();
+        assert_eq!(quota_exceeded.quota, "content");
+        assert_eq!(quota_exceeded.limit, 1);
 
-        //  Create a different extractor, executor and associated extraction policy
-        let mut extractor_2 = mock_extractor();
-        let extractor_2_name = "MockExtractor2".to_string();
-        extractor_2.name = extractor_2_name.clone();
+        //  Extracted content isn't rejected, but counts toward the quota
         coordinator
-            .register_executor("localhost:8957", "test_executor_id_2", extractor_2.clone())
+            .create_content_metadata(vec![content("content_1_child", "content_1")])
             .await?;
-        let extraction_policy_2 = internal_api::ExtractionPolicy {
-            id: "extraction_policy_id_2".to_string(),
-            extractor: extractor_2_name.clone(),
+        assert_eq!(
+            coordinator
+                .get_namespace_stats(DEFAULT_TEST_NAMESPACE)
+                .await?
+                .content,
+            2
+        );
+
+        let extractor = mock_extractor();
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                extractor.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        let policy = |id: &str| internal_api::ExtractionPolicy {
+            id: id.to_string(),
+            name: id.to_string(),
+            extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
             namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            output_index_name_mapping: HashMap::from([(
-                "test_output".to_string(),
-                "test.test_output".to_string(),
-            )]),
-            index_name_table_mapping: HashMap::from([(
-                "test.test_output".to_string(),
-                "test_namespace.test.test_output".to_string(),
-            )]),
-            content_source: extraction_policy_1.name,
+            content_source: "ingestion".to_string(),
             ..Default::default()
         };
         coordinator
-            .create_policy(extraction_policy_2.clone(), extractor_2)
+            .create_policy(policy("extraction_policy_1"), extractor.clone())
             .await?;
+        let err = coordinator
+            .create_policy(policy("extraction_policy_2"), extractor.clone())
+            .await
+            .unwrap_err();
+        assert!(err
+            .downcast_ref::<crate::state::store::NamespaceQuotaExceeded>()
+            .is_some());
+        assert_eq!(
+            coordinator
+                .namespace_extraction_policy_count(DEFAULT_TEST_NAMESPACE)
+                .await,
+            1
+        );
+        Ok(())
+    }
 
-        //  Build a content tree where the parent content id is the pointer
-        let parent_content = indexify_coordinator::ContentMetadata {
-            id: "test_parent_id".to_string(),
+    #[tokio::test]
+    async fn test_reused_request_ids() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let content = |id: &str, storage_url: &str| indexify_coordinator::ContentMetadata {
+            id: id.to_string(),
             namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "".to_string(),
-            file_name: "test_file".to_string(),
+            file_name: id.to_string(),
             mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
+            storage_url: storage_url.to_string(),
             source: "ingestion".to_string(),
             size_bytes: 100,
             ..Default::default()
         };
-        let child_content_1 = indexify_coordinator::ContentMetadata {
-            id: "test_child_id_1".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_parent_id".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_1.id.clone(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-        let child_content_2 = indexify_coordinator::ContentMetadata {
-            id: "test_child_id_2".to_string(),
-            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_parent_id".to_string(),
-            file_name: "test_file".to_string(),
-            mime: "text/plain".to_string(),
-            created_at: 0,
-            storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_2.id.clone(),
-            size_bytes: 100,
-            ..Default::default()
-        };
-        let child_content_1_child = indexify_coordinator::ContentMetadata {
-            id: "test_child_child_id_1".to_string(),
+        coordinator
+            .create_content_metadata_with_request_id(
+                vec![content("content_1", "file:///a")],
+                Some("request_1".to_string()),
+            )
+            .await?;
+        //  A retry succeeds without creating the content again, even when the
+        // content was given another id
+        coordinator
+            .create_content_metadata_with_request_id(
+                vec![content("content_1_retry", "file:///a")],
+                Some("request_1".to_string()),
+            )
+            .await?;
+        let err = coordinator
+            .create_content_metadata_with_request_id(
+                vec![content("content_2", "file:///b")],
+                Some("request_1".to_string()),
+            )
+            .await
+            .unwrap_err();
+        assert!(err
+            .downcast_ref::<crate::state::store::RequestIdConflict>()
+            .is_some());
+        assert_eq!(
+            coordinator
+                .get_namespace_stats(DEFAULT_TEST_NAMESPACE)
+                .await?
+                .content,
+            1
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_namespace_stats() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let extractor = mock_extractor();
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                extractor.clone(),
+                HashMap::new(),
+            )
+            .await?;
+        coordinator
+            .create_policy(
+                internal_api::ExtractionPolicy {
+                    id: "extraction_policy_id".to_string(),
+                    name: "extraction_policy_name".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    content_source: "ingestion".to_string(),
+                    ..Default::default()
+                },
+                extractor,
+            )
+            .await?;
+        coordinator
+            .create_index(
+                DEFAULT_TEST_NAMESPACE,
+                internal_api::Index {
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    name: "test_index".to_string(),
+                    table_name: "test_namespace.test_index".to_string(),
+                    extraction_policy: "extraction_policy_name".to_string(),
+                    extractor: DEFAULT_TEST_EXTRACTOR.to_string(),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let content = |id: &str| indexify_coordinator::ContentMetadata {
+            id: id.to_string(),
             namespace: DEFAULT_TEST_NAMESPACE.to_string(),
-            parent_id: "test_child_id_1".to_string(),
             file_name: "test_file".to_string(),
             mime: "text/plain".to_string(),
-            created_at: 0,
             storage_url: "test_storage_url".to_string(),
-            labels: HashMap::new(),
-            source: extraction_policy_1.id.clone(),
+            source: "ingestion".to_string(),
             size_bytes: 100,
             ..Default::default()
         };
         coordinator
-            .create_content_metadata(vec![
-                parent_content.clone(),
-                child_content_1.clone(),
-                child_content_2.clone(),
-                child_content_1_child.clone(),
-            ])
+            .create_content_metadata(vec![content("content_1"), content("content_2")])
+            .await?;
+        //  Content written again isn't counted twice
+        coordinator
+            .create_content_metadata(vec![content("content_1")])
             .await?;
+        coordinator.run_scheduler().await?;
 
-        let state_change = internal_api::StateChange {
-            object_id: parent_content.id.clone(),
-            ..Default::default()
-        };
-        let tasks = coordinator.handle_tombstone_content(state_change).await?;
-        assert_eq!(tasks.len(), 4);
-        for task in &tasks {
-            match task.content_id.as_str() {
-                "test_parent_id" | "test_child_id_1" => assert!(!task.output_tables.is_empty()),
-                "test_child_id_2" | "test_child_child_id_1" => {
-                    assert!(task.output_tables.is_empty())
-                }
-                _ => panic!("Unexpected content_id"),
+        let stats = coordinator
+            .get_namespace_stats(DEFAULT_TEST_NAMESPACE)
+            .await?;
+        assert_eq!(
+            stats,
+            internal_api::NamespaceStats {
+                content: 2,
+                tasks_pending: 2,
+                tasks_failed: 0,
+                indexes: 1,
             }
-        }
+        );
+
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await?;
+        let mut failed_task = tasks[0].clone();
+        failed_task.outcome = internal_api::TaskOutcome::Failed;
+        shared_state
+            .update_task(
+                failed_task,
+                Some("test_executor_id".to_string()),
+                vec![],
+                None,
+            )
+            .await?;
+        let mut succeeded_task = tasks[1].clone();
+        succeeded_task.outcome = internal_api::TaskOutcome::Success;
+        shared_state
+            .update_task(
+                succeeded_task,
+                Some("test_executor_id".to_string()),
+                vec![],
+                None,
+            )
+            .await?;
+        let stats = coordinator
+            .get_namespace_stats(DEFAULT_TEST_NAMESPACE)
+            .await?;
+        assert_eq!(stats.tasks_pending, 0);
+        assert_eq!(stats.tasks_failed, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_node_health() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        coordinator.heartbeat("test_executor_id").await?;
+        assert_eq!(
+            coordinator.connected_executors(),
+            vec!["test_executor_id".to_string()]
+        );
+        assert_eq!(coordinator.get_raft_metrics().role(), "leader");
+        assert!(coordinator.last_leader_contact_ms() > 0);
 
+        coordinator.remove_executor("test_executor_id").await?;
+        assert!(coordinator.connected_executors().is_empty());
         Ok(())
     }
 }
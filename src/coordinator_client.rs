@@ -1,8 +1,13 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::{anyhow, Ok, Result};
 use axum::{http::StatusCode, Json};
-use indexify_internal_api::StructuredDataSchema;
+use indexify_internal_api::{SchemaInference, StructuredDataSchema};
 use indexify_proto::indexify_coordinator::{
     self,
     coordinator_service_client::CoordinatorServiceClient,
@@ -13,17 +18,128 @@ use tonic::transport::Channel;
 
 use crate::api::{IndexifyAPIError, RaftMetricsSnapshotResponse, TaskAssignments};
 
+//  Time a coordinator which failed to connect or failed a health check is only
+// tried after the other coordinators
+const UNHEALTHY_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Set on the statuses of writes which reached a coordinator that couldn't
+/// hand them to the leader
+pub const NOT_LEADER_METADATA_KEY: &str = "x-indexify-not-leader";
+
+//  Number of times a write is retried on the leader before the error is
+// returned
+const MAX_LEADER_HOPS: usize = 3;
+
+//  Time given to the coordinators to elect a leader before retrying a write
+// when none of them knows the leader
+const LEADER_ELECTION_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Client of the coordinator service which fails over between coordinator
+/// nodes. Coordinators are tried in order of preference, local coordinators
+/// first followed by the others in the configured order, skipping the ones
+/// which recently failed.
 #[derive(Debug)]
 pub struct CoordinatorClient {
-    addr: String,
+    addrs: Vec<String>,
     clients: Arc<Mutex<HashMap<String, CoordinatorServiceClient<Channel>>>>,
+    unhealthy: Arc<Mutex<HashMap<String, Instant>>>,
+    //  Coordinator address of the last known leader, writes are sent to it
+    leader: Arc<Mutex<Option<String>>>,
+}
+
+pub fn is_not_leader(status: &tonic::Status) -> bool {
+    status.code() == tonic::Code::Unavailable &&
+        status.metadata().get(NOT_LEADER_METADATA_KEY).is_some()
+}
+
+fn is_local(addr: &str) -> bool {
+    let host = addr
+        .rsplit_once(':')
+        .map(|(host, _)| host)
+        .unwrap_or(addr)
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    matches!(host, "localhost" | "127.0.0.1" | "0.0.0.0" | "::1")
+}
+
+//  Orders the coordinators with the local ones first, keeping the configured
+// order otherwise
+fn preference_order(addrs: Vec<String>) -> Vec<String> {
+    let (mut local, remote): (Vec<_>, Vec<_>) =
+        addrs.into_iter().unique().partition(|addr| is_local(addr));
+    local.extend(remote);
+    local
 }
 
 impl CoordinatorClient {
     pub fn new(addr: &str) -> Self {
+        Self::with_failover(vec![addr.to_string()])
+    }
+
+    pub fn with_failover(addrs: Vec<String>) -> Self {
         Self {
-            addr: addr.to_string(),
+            addrs: preference_order(addrs),
             clients: Arc::new(Mutex::new(HashMap::new())),
+            unhealthy: Arc::new(Mutex::new(HashMap::new())),
+            leader: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn addrs(&self) -> &[String] {
+        &self.addrs
+    }
+
+    async fn mark_unhealthy(&self, addr: &str) {
+        self.unhealthy
+            .lock()
+            .await
+            .insert(addr.to_string(), Instant::now());
+        //  Drop the channel so the next attempt reconnects
+        self.clients.lock().await.remove(addr);
+    }
+
+    //  Coordinators in the order they should be tried, the ones which
+    // recently failed are only tried when all the others failed as well
+    async fn candidates(&self) -> Vec<String> {
+        let unhealthy = self.unhealthy.lock().await;
+        let (healthy, unhealthy): (Vec<_>, Vec<_>) = self.addrs.iter().cloned().partition(|addr| {
+            unhealthy
+                .get(addr)
+                .map_or(true, |since| since.elapsed() >= UNHEALTHY_RETRY_INTERVAL)
+        });
+        healthy.into_iter().chain(unhealthy).collect()
+    }
+
+    /// Checks every coordinator with a cheap request and marks the ones
+    /// which don't respond in time as unhealthy.
+    pub async fn check_health(&self) {
+        for addr in &self.addrs {
+            let check = async {
+                let mut client = self.get_coordinator(addr).await?;
+                client
+                    .get_raft_metrics_snapshot(
+                        indexify_coordinator::GetRaftMetricsSnapshotRequest {},
+                    )
+                    .await?;
+                Ok(())
+            };
+            match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, check).await {
+                Result::Ok(Result::Ok(())) => {
+                    self.unhealthy.lock().await.remove(addr);
+                }
+                Result::Ok(Err(e)) => {
+                    tracing::warn!("coordinator at {} is unhealthy: {}", addr, e);
+                    self.mark_unhealthy(addr).await;
+                }
+                Err(_) => {
+                    tracing::warn!("coordinator at {} timed out health check", addr);
+                    self.mark_unhealthy(addr).await;
+                }
+            }
         }
     }
 
@@ -43,22 +159,92 @@ impl CoordinatorClient {
     }
 
     pub async fn get(&self) -> Result<CoordinatorServiceClient<Channel>> {
-        let mut clients = self.clients.lock().await;
-        if let Some(client) = clients.get(&self.addr) {
-            return Ok(client.clone());
+        let mut last_error = None;
+        for addr in self.candidates().await {
+            match self.get_coordinator(&addr).await {
+                Result::Ok(client) => return Ok(client),
+                Err(e) => {
+                    tracing::warn!("failing over from coordinator at {}: {}", addr, e);
+                    self.mark_unhealthy(&addr).await;
+                    last_error = Some(e);
+                }
+            }
         }
+        Err(last_error.unwrap_or(anyhow!("no coordinator addresses configured")))
+    }
 
-        let client = CoordinatorServiceClient::connect(format!("http://{}", &self.addr))
-            .await
-            .map_err(|e| {
-                anyhow!(
-                    "unable to connect to coordinator: {} at addr {}",
-                    e,
-                    self.addr
-                )
-            })?;
-        clients.insert(self.addr.to_string(), client.clone());
-        Ok(client)
+    //  Asks the coordinators which of them is the leader, None while a leader
+    // is being elected
+    async fn discover_leader(&self) -> Option<String> {
+        for addr in self.candidates().await {
+            let Result::Ok(mut client) = self.get_coordinator(&addr).await else {
+                continue;
+            };
+            let Result::Ok(response) = client
+                .list_cluster_members(indexify_coordinator::ListClusterMembersRequest {})
+                .await
+            else {
+                continue;
+            };
+            let leader = response
+                .into_inner()
+                .members
+                .into_iter()
+                .find(|member| member.leader)
+                .map(|member| member.coordinator_addr);
+            if leader.is_some() {
+                *self.leader.lock().await = leader.clone();
+                return leader;
+            }
+        }
+        None
+    }
+
+    async fn leader_or_any(&self) -> Result<CoordinatorServiceClient<Channel>> {
+        let leader = self.leader.lock().await.clone();
+        if let Some(addr) = leader {
+            if let Result::Ok(client) = self.get_coordinator(&addr).await {
+                return Ok(client);
+            }
+        }
+        self.get().await
+    }
+
+    /// Sends a write to the leader of the coordinators, or to any coordinator
+    /// if the leader isn't known yet. When the coordinator can't hand the
+    /// write to the leader, the leader is discovered and the write is retried
+    /// on it, at most `MAX_LEADER_HOPS` times.
+    pub async fn call_leader<Req, T, F, Fut>(
+        &self,
+        request: Req,
+        call: F,
+    ) -> Result<tonic::Response<T>>
+    where
+        Req: Clone,
+        F: Fn(CoordinatorServiceClient<Channel>, Req) -> Fut,
+        Fut: Future<Output = Result<tonic::Response<T>, tonic::Status>>,
+    {
+        let mut client = self.leader_or_any().await?;
+        let mut hops = 0;
+        loop {
+            let status = match call(client, request.clone()).await {
+                Result::Ok(response) => return Ok(response),
+                Err(status) => status,
+            };
+            if !is_not_leader(&status) || hops == MAX_LEADER_HOPS {
+                return Err(status.into());
+            }
+            hops += 1;
+            *self.leader.lock().await = None;
+            tracing::warn!("retrying write on the leader: {}", status.message());
+            client = match self.discover_leader().await {
+                Some(addr) => self.get_coordinator(&addr).await?,
+                None => {
+                    tokio::time::sleep(LEADER_ELECTION_BACKOFF).await;
+                    self.get().await?
+                }
+            };
+        }
     }
 
     pub async fn get_raft_metrics_snapshot(
@@ -101,6 +287,10 @@ impl CoordinatorClient {
             vote: raft_metrics.vote,
             last_log_index: raft_metrics.last_log_index,
             current_leader: raft_metrics.current_leader,
+            last_applied_index: raft_metrics.last_applied_index,
+            committed_index: raft_metrics.committed_index,
+            pending_log_entries: raft_metrics.pending_log_entries,
+            replication_lag: raft_metrics.replication_lag,
         };
         Ok(Json(snapshot_response)).map_err(IndexifyAPIError::internal_error)
     }
@@ -122,11 +312,45 @@ impl CoordinatorClient {
                 columns: serde_json::from_str(&schema.columns).unwrap(),
                 content_source: schema.content_source,
                 id: "".to_string(),
+                conflicts: serde_json::from_str(&schema.conflicts).unwrap_or_default(),
             })
             .collect_vec();
         Ok(schemas)
     }
 
+    /// Evolves the schema of a content source with the columns inferred from
+    /// extracted metadata, returning the schema stored by the coordinator
+    pub async fn evolve_structured_data_schema(
+        &self,
+        namespace: &str,
+        content_source: &str,
+        inference: &SchemaInference,
+    ) -> Result<StructuredDataSchema> {
+        let request = tonic::Request::new(
+            indexify_proto::indexify_coordinator::EvolveStructuredDataSchemaRequest {
+                namespace: namespace.to_string(),
+                content_source: content_source.to_string(),
+                columns: serde_json::to_string(&inference.columns)?,
+                conflicts: serde_json::to_string(&inference.conflicts)?,
+            },
+        );
+        let schema = self
+            .get()
+            .await?
+            .evolve_structured_data_schema(request)
+            .await?
+            .into_inner()
+            .schema
+            .ok_or(anyhow!("coordinator didn't return the evolved schema"))?;
+        Ok(StructuredDataSchema {
+            columns: serde_json::from_str(&schema.columns)?,
+            conflicts: serde_json::from_str(&schema.conflicts).unwrap_or_default(),
+            id: StructuredDataSchema::schema_id(namespace, &schema.content_source),
+            namespace: namespace.to_string(),
+            content_source: schema.content_source,
+        })
+    }
+
     pub async fn all_task_assignments(&self) -> Result<TaskAssignments> {
         let request = tonic::Request::new(
             indexify_proto::indexify_coordinator::GetAllTaskAssignmentRequest {},
@@ -137,3 +361,55 @@ impl CoordinatorClient {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preference_order() {
+        let addrs = vec![
+            "coordinator-us-east:8950".to_string(),
+            "coordinator-eu-west:8950".to_string(),
+            "localhost:8950".to_string(),
+            "coordinator-us-east:8950".to_string(),
+        ];
+        assert_eq!(
+            preference_order(addrs),
+            vec![
+                "localhost:8950",
+                "coordinator-us-east:8950",
+                "coordinator-eu-west:8950",
+            ]
+        );
+        assert!(is_local("[::1]:8950"));
+        assert!(!is_local("10.0.0.1:8950"));
+    }
+
+    #[test]
+    fn test_not_leader_status() {
+        let mut status = tonic::Status::unavailable("not the leader");
+        //  Unavailable is also returned when the coordinator can't be reached
+        assert!(!is_not_leader(&status));
+        status.metadata_mut().insert(
+            NOT_LEADER_METADATA_KEY,
+            tonic::metadata::MetadataValue::from_static("true"),
+        );
+        assert!(is_not_leader(&status));
+    }
+
+    #[tokio::test]
+    async fn test_failover_skips_unhealthy_coordinators() {
+        let client = CoordinatorClient::with_failover(vec![
+            "127.0.0.1:1".to_string(),
+            "[::1]:1".to_string(),
+        ]);
+        assert!(client.get().await.is_err());
+
+        //  Both failed, so they're still tried in the preferred order
+        assert_eq!(client.candidates().await, vec!["127.0.0.1:1", "[::1]:1"]);
+
+        client.unhealthy.lock().await.remove("[::1]:1");
+        assert_eq!(client.candidates().await, vec!["[::1]:1", "127.0.0.1:1"]);
+    }
+}
@@ -2,17 +2,21 @@ use std::collections::HashMap;
 
 use indexify_internal_api as internal_api;
 
+use crate::filter_expression::FilterExpr;
+
 /// filter for content metadata
 pub fn list_content_filter<'a>(
     content: impl IntoIterator<Item = internal_api::ContentMetadata> + 'a,
     source: &'a str,
     parent_id: &'a str,
+    external_id: &'a str,
     labels_eq: &'a HashMap<String, String>,
 ) -> impl Iterator<Item = internal_api::ContentMetadata> + 'a {
     content
         .into_iter()
         .filter(move |c| source.is_empty() || c.source == source)
         .filter(move |c| parent_id.is_empty() || c.parent_id == parent_id)
+        .filter(move |c| external_id.is_empty() || c.external_id == external_id)
         // c.labels HashMap<String, String> must exactly match labels_eq { ("key", "value") }
         // and not have any other labels
         .filter(move |c| {
@@ -28,6 +32,16 @@ pub fn list_content_filter<'a>(
         })
 }
 
+/// filter for content metadata matching a filter expression over labels
+pub fn content_filter_expression<'a>(
+    content: impl IntoIterator<Item = internal_api::ContentMetadata> + 'a,
+    filter: Option<&'a FilterExpr>,
+) -> impl Iterator<Item = internal_api::ContentMetadata> + 'a {
+    content
+        .into_iter()
+        .filter(move |c| filter.map_or(true, |filter| filter.matches_labels(&c.labels)))
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
@@ -73,13 +87,14 @@ mod test {
                 source: "source4".to_string(),
                 parent_id: "parent4".to_string(),
                 labels: HashMap::new(),
+                external_id: "doc-4".to_string(),
                 ..Default::default()
             },
         ];
 
         // no filters
         let filtered_content =
-            list_content_filter(content.clone(), "", "", &no_labels_filter).collect::<Vec<_>>();
+            list_content_filter(content.clone(), "", "", "", &no_labels_filter).collect::<Vec<_>>();
         assert_eq!(filtered_content.len(), 4);
         assert_eq!(filtered_content[0].id, "1");
         assert_eq!(filtered_content[1].id, "2");
@@ -88,7 +103,7 @@ mod test {
 
         // source filter
         let filtered_content =
-            list_content_filter(content.clone(), "source1", "", &no_labels_filter)
+            list_content_filter(content.clone(), "source1", "", "", &no_labels_filter)
                 .collect::<Vec<_>>();
         assert_eq!(filtered_content.len(), 2);
         assert_eq!(filtered_content[0].id, "1");
@@ -96,14 +111,14 @@ mod test {
 
         // parent_id and source filter
         let filtered_content =
-            list_content_filter(content.clone(), "source1", "parent2", &no_labels_filter)
+            list_content_filter(content.clone(), "source1", "parent2", "", &no_labels_filter)
                 .collect::<Vec<_>>();
         assert_eq!(filtered_content.len(), 1);
         assert_eq!(filtered_content[0].id, "3");
 
         // parent_id filter
         let filtered_content =
-            list_content_filter(content.clone(), "", "parent2", &no_labels_filter)
+            list_content_filter(content.clone(), "", "parent2", "", &no_labels_filter)
                 .collect::<Vec<_>>();
         assert_eq!(filtered_content.len(), 2);
         assert_eq!(filtered_content[0].id, "2");
@@ -111,7 +126,7 @@ mod test {
 
         // labels filter - empty - skips the labels filter
         let filtered_content =
-            list_content_filter(content.clone(), "", "", &no_labels_filter).collect::<Vec<_>>();
+            list_content_filter(content.clone(), "", "", "", &no_labels_filter).collect::<Vec<_>>();
         assert_eq!(filtered_content.len(), 4);
 
         // labels filter - exact match
@@ -121,7 +136,7 @@ mod test {
             labels
         };
         let filtered_content =
-            list_content_filter(content.clone(), "", "", &labels_eq).collect::<Vec<_>>();
+            list_content_filter(content.clone(), "", "", "", &labels_eq).collect::<Vec<_>>();
         assert_eq!(filtered_content.len(), 1);
         assert_eq!(filtered_content[0].id, "1");
 
@@ -133,9 +148,28 @@ mod test {
             labels
         };
         let filtered_content =
-            list_content_filter(content.clone(), "", "", &labels_eq).collect::<Vec<_>>();
+            list_content_filter(content.clone(), "", "", "", &labels_eq).collect::<Vec<_>>();
         assert_eq!(filtered_content.len(), 1);
         assert_eq!(filtered_content[0].id, "3");
+
+        // external_id filter
+        let filtered_content =
+            list_content_filter(content.clone(), "", "", "doc-4", &no_labels_filter)
+                .collect::<Vec<_>>();
+        assert_eq!(filtered_content.len(), 1);
+        assert_eq!(filtered_content[0].id, "4");
+
+        // filter expression
+        let filter = FilterExpr::parse("labels.key1 = value1 AND NOT labels.key2 = value2")
+            .unwrap()
+            .for_content_store()
+            .unwrap();
+        let filtered_content =
+            content_filter_expression(content.clone(), Some(&filter)).collect::<Vec<_>>();
+        assert_eq!(filtered_content.len(), 1);
+        assert_eq!(filtered_content[0].id, "1");
+        let filtered_content = content_filter_expression(content.clone(), None).collect::<Vec<_>>();
+        assert_eq!(filtered_content.len(), 4);
     }
 }
 
@@ -7,6 +7,7 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
+    time::Duration,
 };
 
 use anyhow::{anyhow, Result};
@@ -15,13 +16,43 @@ use indexify_internal_api as internal_api;
 use indexify_proto::indexify_coordinator::{
     self,
     coordinator_service_server::CoordinatorService,
+    AddChunkReferenceRequest,
+    AddChunkReferenceResponse,
+    AddClusterLearnerRequest,
+    BlobMigrationJob,
+    CompleteIndexMigrationRequest,
     CoordinatorCommand,
+    CreateBlobMigrationJobRequest,
+    CreateBlobMigrationJobResponse,
     CreateContentRequest,
     CreateContentResponse,
+    CreateEmbeddingExpirationsRequest,
+    CreateEmbeddingExpirationsResponse,
     CreateGcTasksRequest,
     CreateGcTasksResponse,
+    CreateIndexMigrationRequest,
     CreateIndexRequest,
     CreateIndexResponse,
+    CreateIngestIntentRequest,
+    CreateIngestIntentResponse,
+    CreateRebalanceJobRequest,
+    CreateRebalanceJobResponse,
+    DeleteEmbeddingExpirationsRequest,
+    DeleteEmbeddingExpirationsResponse,
+    DeleteIngestIntentsRequest,
+    DeleteIngestIntentsResponse,
+    DeleteIngestionPluginRequest,
+    DeleteIngestionPluginResponse,
+    DeleteProcessingSlaRequest,
+    DeleteProcessingSlaResponse,
+    DeleteQueryTemplateRequest,
+    DeleteQueryTemplateResponse,
+    DrainExecutorRequest,
+    DrainExecutorResponse,
+    DryRunExtractionPolicyRequest,
+    DryRunExtractionPolicyResponse,
+    EvolveStructuredDataSchemaRequest,
+    EvolveStructuredDataSchemaResponse,
     ExtractionPolicyRequest,
     ExtractionPolicyResponse,
     GcTask,
@@ -29,38 +60,88 @@ use indexify_proto::indexify_coordinator::{
     GetAllSchemaRequest,
     GetAllSchemaResponse,
     GetAllTaskAssignmentRequest,
+    GetBlobMigrationJobRequest,
+    GetChunkReferencesRequest,
+    GetChunkReferencesResponse,
     GetContentMetadataRequest,
     GetContentTreeMetadataRequest,
     GetExtractorCoordinatesRequest,
+    GetIndexMigrationRequest,
     GetIndexRequest,
     GetIndexResponse,
+    GetNamespaceQuotasRequest,
+    GetNamespaceQuotasResponse,
+    GetNamespaceStatsRequest,
+    GetNamespaceStatsResponse,
+    GetNamespaceStorageRequest,
+    GetNamespaceStorageResponse,
+    GetNodeHealthRequest,
+    GetNodeHealthResponse,
+    GetQueryTemplateRequest,
+    GetQueryTemplateResponse,
     GetRaftMetricsSnapshotRequest,
+    GetRebalanceJobRequest,
     GetSchemaRequest,
     GetSchemaResponse,
     HeartbeatRequest,
     HeartbeatResponse,
+    IndexMigrationResponse,
+    ListClusterMembersRequest,
+    ListClusterMembersResponse,
     ListContentRequest,
     ListContentResponse,
+    ListEmbeddingExpirationsRequest,
+    ListEmbeddingExpirationsResponse,
+    ListExecutorsRequest,
+    ListExecutorsResponse,
     ListExtractionPoliciesRequest,
     ListExtractionPoliciesResponse,
     ListExtractorsRequest,
     ListExtractorsResponse,
+    ListIndexContentRequest,
+    ListIndexContentResponse,
     ListIndexesRequest,
     ListIndexesResponse,
+    ListIngestIntentsRequest,
+    ListIngestIntentsResponse,
+    ListIngestionPluginsRequest,
+    ListIngestionPluginsResponse,
+    ListProcessingSlasRequest,
+    ListProcessingSlasResponse,
+    ListQueryTemplatesRequest,
+    ListQueryTemplatesResponse,
     ListStateChangesRequest,
     ListTasksRequest,
     ListTasksResponse,
+    PlanExtractionPolicyRequest,
+    PlanExtractionPolicyResponse,
+    PromoteClusterLearnerRequest,
     RaftMetricsSnapshotResponse,
+    ReadConsistency,
+    RebalanceJob,
     RegisterExecutorRequest,
     RegisterExecutorResponse,
     RegisterIngestionServerRequest,
     RegisterIngestionServerResponse,
+    RemoveChunkReferenceRequest,
+    RemoveChunkReferenceResponse,
+    RemoveClusterMemberRequest,
     RemoveIngestionServerRequest,
     RemoveIngestionServerResponse,
+    SetIngestionPluginRequest,
+    SetIngestionPluginResponse,
+    SetNamespaceQuotasRequest,
+    SetNamespaceQuotasResponse,
+    SetProcessingSlaRequest,
+    SetProcessingSlaResponse,
+    SetQueryTemplateRequest,
+    SetQueryTemplateResponse,
     TaskAssignments,
     TombstoneContentRequest,
     TombstoneContentResponse,
     Uint64List,
+    UpdateBlobMigrationJobRequest,
+    UpdateRebalanceJobRequest,
     UpdateTaskRequest,
     UpdateTaskResponse,
 };
@@ -78,18 +159,75 @@ use tonic::{Request, Response, Status, Streaming};
 use tracing::{error, info};
 
 use crate::{
-    coordinator::Coordinator,
-    coordinator_client::CoordinatorClient,
+    blob_storage::BlobStorage,
+    coordinator::{Coordinator, IndexMigrationProgress},
+    coordinator_client::{CoordinatorClient, NOT_LEADER_METADATA_KEY},
     garbage_collector::GarbageCollector,
-    server_config::ServerConfig,
-    state,
+    server_config::{RocksDbBackupConfig, ServerConfig, StateBackupConfig},
+    state::{
+        self,
+        forwardable_raft::NotLeaderError,
+        store::{NamespaceQuotaExceeded, RequestIdConflict},
+    },
     tonic_streamer::DropReceiver,
     utils::timestamp_secs,
 };
 
+//  How often the leader assigns the failed tasks whose retry backoff passed
+const TASK_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
 type HBResponseStream = Pin<Box<dyn Stream<Item = Result<HeartbeatResponse, Status>> + Send>>;
 type GCTasksResponseStream =
     Pin<Box<dyn tokio_stream::Stream<Item = Result<CoordinatorCommand, Status>> + Send + Sync>>;
+type StateChangeEventStream =
+    Pin<Box<dyn Stream<Item = Result<indexify_coordinator::StateChangeEvent, Status>> + Send>>;
+
+impl From<IndexMigrationProgress> for IndexMigrationResponse {
+    fn from(value: IndexMigrationProgress) -> Self {
+        Self {
+            namespace: value.migration.namespace,
+            source_index: value.migration.source_index,
+            target_index: value.migration.target_index,
+            target_extraction_policy: value.migration.target_extraction_policy,
+            created_at: value.migration.created_at,
+            completed: value.migration.completed,
+            total_content: value.total_content as u64,
+            migrated_content: value.migrated_content as u64,
+        }
+    }
+}
+
+//  Writes which reached a coordinator that couldn't hand them to the leader
+// are returned as unavailable, so the client retries them on the leader.
+// Writes over a quota of their namespace are returned as resource exhausted,
+// and writes reusing the request id of another write as already exists.
+fn write_error_status(e: anyhow::Error) -> Status {
+    if e.downcast_ref::<NamespaceQuotaExceeded>().is_some() {
+        return Status::resource_exhausted(e.to_string());
+    }
+    if e.downcast_ref::<RequestIdConflict>().is_some() {
+        return Status::already_exists(e.to_string());
+    }
+    if e.downcast_ref::<NotLeaderError>().is_none() {
+        return Status::aborted(e.to_string());
+    }
+    let mut status = Status::unavailable(e.to_string());
+    status.metadata_mut().insert(
+        NOT_LEADER_METADATA_KEY,
+        tonic::metadata::MetadataValue::from_static("true"),
+    );
+    status
+}
+
+fn structured_data_schema(
+    schema: internal_api::StructuredDataSchema,
+) -> indexify_coordinator::StructuredDataSchema {
+    indexify_coordinator::StructuredDataSchema {
+        columns: serde_json::to_string(&schema.columns).unwrap(),
+        content_source: schema.content_source,
+        conflicts: serde_json::to_string(&schema.conflicts).unwrap(),
+    }
+}
 
 pub struct CoordinatorServiceServer {
     coordinator: Arc<Coordinator>,
@@ -100,23 +238,31 @@ pub struct CoordinatorServiceServer {
 impl CoordinatorService for CoordinatorServiceServer {
     type GCTasksStreamStream = GCTasksResponseStream;
     type HeartbeatStream = HBResponseStream;
+    type WatchStateChangesStream = StateChangeEventStream;
 
     async fn create_content(
         &self,
         request: tonic::Request<CreateContentRequest>,
     ) -> Result<tonic::Response<CreateContentResponse>, tonic::Status> {
+        let request = request.into_inner();
         let content_meta = request
-            .into_inner()
             .content
             .ok_or(tonic::Status::aborted("content is missing"))?;
         let id = content_meta.id.clone();
         let content_list = vec![content_meta];
-        let _ = self
+        let request_id = Some(request.request_id).filter(|id| !id.is_empty());
+        let expires_at = self
             .coordinator
-            .create_content_metadata(content_list)
+            .create_content_metadata_with_request_id(content_list, request_id)
             .await
-            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
-        Ok(tonic::Response::new(CreateContentResponse { id }))
+            .map_err(write_error_status)?
+            .first()
+            .map(|content| content.expires_at)
+            .unwrap_or_default();
+        Ok(tonic::Response::new(CreateContentResponse {
+            id,
+            expires_at,
+        }))
     }
 
     async fn tombstone_content(
@@ -129,7 +275,7 @@ impl CoordinatorService for CoordinatorServiceServer {
         self.coordinator
             .tombstone_content_metadatas(&namespace, &content_ids)
             .await
-            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+            .map_err(write_error_status)?;
         Ok(tonic::Response::new(TombstoneContentResponse {}))
     }
 
@@ -140,7 +286,14 @@ impl CoordinatorService for CoordinatorServiceServer {
         let req = request.into_inner();
         let content_list = self
             .coordinator
-            .list_content(&req.namespace, &req.source, &req.parent_id, &req.labels_eq)
+            .list_content(
+                &req.namespace,
+                &req.source,
+                &req.parent_id,
+                &req.external_id,
+                &req.labels_eq,
+                &req.filter,
+            )
             .await
             .map_err(|e| tonic::Status::aborted(e.to_string()))?;
         Ok(tonic::Response::new(ListContentResponse {
@@ -168,12 +321,40 @@ impl CoordinatorService for CoordinatorServiceServer {
             .get_extractor(&request.extractor)
             .await
             .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        if let Some(operation) = self
+            .coordinator
+            .applied_operation(
+                &request.operation_token,
+                internal_api::OperationKind::CreateExtractionPolicy,
+                &id,
+            )
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+        {
+            //  The request is a retry, return the policy created by the original
+            // request
+            let extraction_policy = self
+                .coordinator
+                .get_extraction_policy(&id)
+                .await
+                .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+            return Ok(tonic::Response::new(ExtractionPolicyResponse {
+                created_at: operation.created_at as i64,
+                extractor: Some(extractor.into()),
+                index_name_table_mapping: extraction_policy.index_name_table_mapping.clone(),
+                output_index_name_mapping: extraction_policy.output_index_name_mapping.clone(),
+                extraction_policy: Some(extraction_policy.into()),
+            }));
+        }
         let mut index_name_table_mapping = HashMap::new();
         let mut output_index_name_mapping = HashMap::new();
 
         //  TODO: Just create an output to table mapping here directly instead of 2
         // separate mappings
-        for output_name in extractor.outputs.keys() {
+        let keyword_index_output = request
+            .keyword_index
+            .then_some(internal_api::KEYWORD_INDEX_OUTPUT.to_string());
+        for output_name in extractor.outputs.keys().chain(keyword_index_output.iter()) {
             let index_name = format!("{}.{}", request.name, output_name);
             let index_table_name =
                 format!("{}.{}.{}", request.namespace, request.name, output_name);
@@ -191,12 +372,21 @@ impl CoordinatorService for CoordinatorServiceServer {
             output_index_name_mapping: output_index_name_mapping.clone(),
             index_name_table_mapping: index_name_table_mapping.clone(),
             content_source: request.content_source,
+            version: 0,
+            content_filter: request.content_filter.map(Into::into).unwrap_or_default(),
+            backfill_from: request.backfill_from,
+            retry_policy: request.retry_policy.map(Into::into).unwrap_or_default(),
+            priority: request.priority,
         };
         let _ = self
             .coordinator
-            .create_policy(extraction_policy.clone(), extractor.clone())
+            .create_policy_with_token(
+                extraction_policy.clone(),
+                extractor.clone(),
+                &request.operation_token,
+            )
             .await
-            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+            .map_err(write_error_status)?;
         Ok(tonic::Response::new(ExtractionPolicyResponse {
             created_at: timestamp_secs() as i64,
             extractor: Some(extractor.into()),
@@ -206,6 +396,58 @@ impl CoordinatorService for CoordinatorServiceServer {
         }))
     }
 
+    async fn update_extraction_policy(
+        &self,
+        request: tonic::Request<indexify_coordinator::UpdateExtractionPolicyRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::UpdateExtractionPolicyResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let mut s = DefaultHasher::new();
+        request.namespace.hash(&mut s);
+        request.name.hash(&mut s);
+        let id = s.finish().to_string();
+        let input_params = request
+            .input_params
+            .map(|input_params| serde_json::from_str(&input_params))
+            .transpose()
+            .map_err(|e| tonic::Status::aborted(format!("unable to parse input_params: {}", e)))?;
+        let extraction_policy = self
+            .coordinator
+            .update_policy(
+                &id,
+                request.filters.map(|filters| filters.filters),
+                input_params,
+                request.content_filter.map(Into::into),
+                request.retry_policy.map(Into::into),
+                request.reextract,
+            )
+            .await
+            .map_err(write_error_status)?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::UpdateExtractionPolicyResponse {
+                extraction_policy: Some(extraction_policy.into()),
+            },
+        ))
+    }
+
+    async fn delete_extraction_policy(
+        &self,
+        request: tonic::Request<indexify_coordinator::DeleteExtractionPolicyRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::DeleteExtractionPolicyResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let dropped_index_tables = self
+            .coordinator
+            .delete_policy(&request.namespace, &request.name, request.drop_indexes)
+            .await
+            .map_err(write_error_status)?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::DeleteExtractionPolicyResponse {
+                dropped_index_tables,
+            },
+        ))
+    }
+
     async fn list_extraction_policies(
         &self,
         request: tonic::Request<ListExtractionPoliciesRequest>,
@@ -232,9 +474,9 @@ impl CoordinatorService for CoordinatorServiceServer {
     ) -> Result<tonic::Response<indexify_coordinator::CreateNamespaceResponse>, tonic::Status> {
         let request = request.into_inner();
         self.coordinator
-            .create_namespace(&request.name)
+            .create_namespace_with_token(&request.name, &request.operation_token)
             .await
-            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+            .map_err(write_error_status)?;
         Ok(tonic::Response::new(
             indexify_coordinator::CreateNamespaceResponse {
                 name: request.name,
@@ -243,6 +485,23 @@ impl CoordinatorService for CoordinatorServiceServer {
         ))
     }
 
+    async fn delete_ns(
+        &self,
+        request: tonic::Request<indexify_coordinator::DeleteNamespaceRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::DeleteNamespaceResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let gc_tasks = self
+            .coordinator
+            .delete_namespace(&request.name)
+            .await
+            .map_err(write_error_status)?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::DeleteNamespaceResponse {
+                gc_tasks: gc_tasks.len() as u64,
+            },
+        ))
+    }
+
     async fn list_ns(
         &self,
         _request: tonic::Request<indexify_coordinator::ListNamespaceRequest>,
@@ -305,14 +564,57 @@ impl CoordinatorService for CoordinatorServiceServer {
             .ok_or(tonic::Status::aborted("missing extractor"))?;
         let _resp = self
             .coordinator
-            .register_executor(&request.addr, &request.executor_id, extractor.into())
+            .register_executor(
+                &request.addr,
+                &request.executor_id,
+                extractor.into(),
+                request.labels,
+            )
             .await
-            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+            .map_err(write_error_status)?;
         Ok(tonic::Response::new(RegisterExecutorResponse {
             executor_id: request.executor_id,
         }))
     }
 
+    async fn list_executors(
+        &self,
+        request: tonic::Request<ListExecutorsRequest>,
+    ) -> Result<tonic::Response<ListExecutorsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let running_task_count = self.coordinator.executor_running_task_count().await;
+        let executors = self
+            .coordinator
+            .list_executors(&request.labels_eq)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+            .into_iter()
+            .map(|executor| {
+                let running_tasks = running_task_count
+                    .get(&executor.id)
+                    .cloned()
+                    .unwrap_or_default() as u64;
+                indexify_coordinator::Executor {
+                    running_tasks,
+                    ..executor.into()
+                }
+            })
+            .collect();
+        Ok(tonic::Response::new(ListExecutorsResponse { executors }))
+    }
+
+    async fn drain_executor(
+        &self,
+        request: tonic::Request<DrainExecutorRequest>,
+    ) -> Result<tonic::Response<DrainExecutorResponse>, tonic::Status> {
+        let request = request.into_inner();
+        self.coordinator
+            .drain_executor(&request.executor_id)
+            .await
+            .map_err(write_error_status)?;
+        Ok(tonic::Response::new(DrainExecutorResponse {}))
+    }
+
     async fn register_ingestion_server(
         &self,
         request: tonic::Request<RegisterIngestionServerRequest>,
@@ -321,7 +623,7 @@ impl CoordinatorService for CoordinatorServiceServer {
         self.coordinator
             .register_ingestion_server(&request.ingestion_server_id)
             .await
-            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+            .map_err(write_error_status)?;
 
         Ok(tonic::Response::new(RegisterIngestionServerResponse {}))
     }
@@ -334,7 +636,7 @@ impl CoordinatorService for CoordinatorServiceServer {
         self.coordinator
             .remove_ingestion_server(&request.ingestion_server_id)
             .await
-            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+            .map_err(write_error_status)?;
 
         Ok(tonic::Response::new(RemoveIngestionServerResponse {}))
     }
@@ -347,7 +649,7 @@ impl CoordinatorService for CoordinatorServiceServer {
         self.coordinator
             .create_gc_tasks(&request.content_id)
             .await
-            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+            .map_err(write_error_status)?;
         Ok(tonic::Response::new(CreateGcTasksResponse {}))
     }
 
@@ -528,9 +830,10 @@ impl CoordinatorService for CoordinatorServiceServer {
                 &request.executor_id,
                 outcome,
                 request.content_list,
+                Some(request.request_id).filter(|id| !id.is_empty()),
             )
             .await
-            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+            .map_err(write_error_status)?;
         Ok(tonic::Response::new(UpdateTaskResponse {}))
     }
 
@@ -556,6 +859,7 @@ impl CoordinatorService for CoordinatorServiceServer {
         request: Request<GetIndexRequest>,
     ) -> Result<Response<GetIndexResponse>, Status> {
         let request = request.into_inner();
+        self.ensure_read_consistency(request.consistency()).await?;
         let index = self
             .coordinator
             .get_index(&request.namespace, &request.name)
@@ -576,7 +880,7 @@ impl CoordinatorService for CoordinatorServiceServer {
         self.coordinator
             .create_index(&namespace, index)
             .await
-            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+            .map_err(write_error_status)?;
         Ok(tonic::Response::new(CreateIndexResponse {}))
     }
 
@@ -602,6 +906,7 @@ impl CoordinatorService for CoordinatorServiceServer {
         req: Request<GetContentMetadataRequest>,
     ) -> Result<Response<indexify_coordinator::GetContentMetadataResponse>, Status> {
         let req = req.into_inner();
+        self.ensure_read_consistency(req.consistency()).await?;
         let content_metadata_list = self
             .coordinator
             .get_content_metadata(req.content_list)
@@ -658,6 +963,25 @@ impl CoordinatorService for CoordinatorServiceServer {
         ))
     }
 
+    async fn watch_state_changes(
+        &self,
+        req: Request<indexify_coordinator::WatchStateChangesRequest>,
+    ) -> Result<Response<Self::WatchStateChangesStream>, Status> {
+        let req = req.into_inner();
+        let state_changes_rx = self
+            .coordinator
+            .watch_state_changes(req.namespace, req.change_types.into_iter().collect());
+        let stream = ReceiverStream::new(state_changes_rx).map(|(change, namespace)| {
+            Ok(indexify_coordinator::StateChangeEvent {
+                change: Some(change.into()),
+                namespace: namespace.unwrap_or_default(),
+            })
+        });
+        Ok(Response::new(
+            Box::pin(stream) as Self::WatchStateChangesStream
+        ))
+    }
+
     async fn list_tasks(
         &self,
         req: Request<ListTasksRequest>,
@@ -690,10 +1014,7 @@ impl CoordinatorService for CoordinatorServiceServer {
             .await
             .map_err(|e| tonic::Status::aborted(e.to_string()))?;
         Ok(Response::new(GetSchemaResponse {
-            schema: Some(indexify_coordinator::StructuredDataSchema {
-                columns: serde_json::to_string(&schema.columns).unwrap(),
-                content_source: schema.content_source,
-            }),
+            schema: Some(structured_data_schema(schema)),
         }))
     }
 
@@ -709,13 +1030,42 @@ impl CoordinatorService for CoordinatorServiceServer {
             .map_err(|e| tonic::Status::aborted(e.to_string()))?;
 
         Ok(Response::new(GetAllSchemaResponse {
-            schemas: schemas
-                .into_iter()
-                .map(|s| indexify_coordinator::StructuredDataSchema {
-                    columns: serde_json::to_string(&s.columns).unwrap(),
-                    content_source: s.content_source,
-                })
-                .collect(),
+            schemas: schemas.into_iter().map(structured_data_schema).collect(),
+        }))
+    }
+
+    async fn evolve_structured_data_schema(
+        &self,
+        req: Request<EvolveStructuredDataSchemaRequest>,
+    ) -> Result<Response<EvolveStructuredDataSchemaResponse>, Status> {
+        let req = req.into_inner();
+        let inference = internal_api::SchemaInference {
+            columns: serde_json::from_str(&req.columns)
+                .map_err(|e| tonic::Status::aborted(e.to_string()))?,
+            conflicts: serde_json::from_str(&req.conflicts)
+                .map_err(|e| tonic::Status::aborted(e.to_string()))?,
+        };
+        let schema = self
+            .coordinator
+            .evolve_structured_data_schema(&req.namespace, &req.content_source, inference)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(EvolveStructuredDataSchemaResponse {
+            schema: Some(structured_data_schema(schema)),
+        }))
+    }
+
+    async fn get_node_health(
+        &self,
+        _req: Request<GetNodeHealthRequest>,
+    ) -> Result<Response<GetNodeHealthResponse>, Status> {
+        let metrics = self.coordinator.get_raft_metrics();
+        Ok(Response::new(GetNodeHealthResponse {
+            node_id: metrics.openraft_metrics.id,
+            role: metrics.role().to_string(),
+            applied_index: metrics.last_applied_index(),
+            last_heartbeat_ms: self.coordinator.last_leader_contact_ms(),
+            executors: self.coordinator.connected_executors(),
         }))
     }
 
@@ -724,6 +1074,10 @@ impl CoordinatorService for CoordinatorServiceServer {
         _req: Request<GetRaftMetricsSnapshotRequest>,
     ) -> Result<Response<RaftMetricsSnapshotResponse>, Status> {
         let metrics = self.coordinator.get_raft_metrics();
+        let last_applied_index = metrics.last_applied_index();
+        let committed_index = metrics.committed_index();
+        let pending_log_entries = metrics.pending_log_entries();
+        let replication_lag = metrics.replication_lag();
         let metrics_snapshot = metrics.raft_metrics;
         let openraft_metrics = metrics.openraft_metrics;
 
@@ -773,6 +1127,10 @@ impl CoordinatorService for CoordinatorServiceServer {
             vote: openraft_metrics.vote.leader_id.node_id,
             last_log_index: openraft_metrics.last_log_index.unwrap_or(0),
             current_leader: openraft_metrics.current_leader.unwrap_or(0),
+            last_applied_index,
+            committed_index,
+            pending_log_entries,
+            replication_lag,
         };
 
         Ok(Response::new(response))
@@ -789,12 +1147,666 @@ impl CoordinatorService for CoordinatorServiceServer {
             .map_err(|e| tonic::Status::aborted(e.to_string()))?;
         Ok(Response::new(TaskAssignments { assignments }))
     }
+
+    async fn plan_extraction_policy(
+        &self,
+        req: Request<PlanExtractionPolicyRequest>,
+    ) -> Result<Response<PlanExtractionPolicyResponse>, Status> {
+        let req = req.into_inner();
+        let task_latency = if req.task_latency_ms == 0 {
+            None
+        } else {
+            Some(Duration::from_millis(req.task_latency_ms))
+        };
+        let (policy, plan) = self
+            .coordinator
+            .plan_extraction_policy(
+                &req.namespace,
+                &req.extraction_policy,
+                task_latency,
+                req.executor_concurrency as usize,
+            )
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(PlanExtractionPolicyResponse {
+            extractor: policy.extractor,
+            total_tasks: plan.total_tasks as u64,
+            total_bytes: plan.total_bytes,
+            queued_tasks: plan.queued_tasks as u64,
+            executors: plan.executors as u64,
+            task_latency_ms: plan.task_latency.as_millis() as u64,
+            estimated_completion_ms: plan
+                .estimated_completion
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            compute_time_ms: plan.compute_time.as_millis() as u64,
+            tasks_per_executor: plan
+                .tasks_per_executor
+                .into_iter()
+                .map(|(k, v)| (k, v as u64))
+                .collect(),
+        }))
+    }
+
+    async fn dry_run_extraction_policy(
+        &self,
+        req: Request<DryRunExtractionPolicyRequest>,
+    ) -> Result<Response<DryRunExtractionPolicyResponse>, Status> {
+        let req = req.into_inner();
+        let request = req
+            .extraction_policy
+            .ok_or_else(|| Status::invalid_argument("extraction policy is required"))?;
+        let input_params = serde_json::from_str(&request.input_params)
+            .map_err(|e| tonic::Status::aborted(format!("unable to parse input_params: {}", e)))?;
+        let extraction_policy = internal_api::ExtractionPolicy {
+            extractor: request.extractor,
+            name: request.name,
+            namespace: request.namespace,
+            filters: request.filters,
+            input_params,
+            content_source: request.content_source,
+            content_filter: request.content_filter.map(Into::into).unwrap_or_default(),
+            backfill_from: request.backfill_from,
+            ..Default::default()
+        };
+        let content_list = self
+            .coordinator
+            .dry_run_policy(&extraction_policy)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(DryRunExtractionPolicyResponse {
+            matching_content: content_list.len() as u64,
+            matching_bytes: content_list.iter().map(|content| content.size_bytes).sum(),
+            sample: content_list
+                .into_iter()
+                .take(req.sample_size as usize)
+                .map(Into::into)
+                .collect(),
+        }))
+    }
+
+    async fn create_index_migration(
+        &self,
+        req: Request<CreateIndexMigrationRequest>,
+    ) -> Result<Response<IndexMigrationResponse>, Status> {
+        let req = req.into_inner();
+        let progress = self
+            .coordinator
+            .create_index_migration(&req.namespace, &req.source_index, &req.target_index)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(progress.into()))
+    }
+
+    async fn get_index_migration(
+        &self,
+        req: Request<GetIndexMigrationRequest>,
+    ) -> Result<Response<IndexMigrationResponse>, Status> {
+        let req = req.into_inner();
+        let progress = self
+            .coordinator
+            .get_index_migration(&req.namespace, &req.source_index)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(progress.into()))
+    }
+
+    async fn complete_index_migration(
+        &self,
+        req: Request<CompleteIndexMigrationRequest>,
+    ) -> Result<Response<IndexMigrationResponse>, Status> {
+        let req = req.into_inner();
+        let progress = self
+            .coordinator
+            .complete_index_migration(&req.namespace, &req.source_index, req.force)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(progress.into()))
+    }
+
+    async fn create_rebalance_job(
+        &self,
+        req: Request<CreateRebalanceJobRequest>,
+    ) -> Result<Response<CreateRebalanceJobResponse>, Status> {
+        let req = req.into_inner();
+        let (job, content_ids) = self
+            .coordinator
+            .create_rebalance_job(
+                &req.namespace,
+                &req.index,
+                &req.target_table,
+                req.shard_count,
+                req.reextract,
+            )
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(CreateRebalanceJobResponse {
+            job: Some(job.into()),
+            content_ids,
+        }))
+    }
+
+    async fn get_rebalance_job(
+        &self,
+        req: Request<GetRebalanceJobRequest>,
+    ) -> Result<Response<RebalanceJob>, Status> {
+        let req = req.into_inner();
+        let job = self
+            .coordinator
+            .get_rebalance_job(&req.namespace, &req.index)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(job.into()))
+    }
+
+    async fn update_rebalance_job(
+        &self,
+        req: Request<UpdateRebalanceJobRequest>,
+    ) -> Result<Response<RebalanceJob>, Status> {
+        let req = req.into_inner();
+        let job = self
+            .coordinator
+            .update_rebalance_job(
+                &req.namespace,
+                &req.index,
+                req.moved_vectors,
+                req.completed,
+                &req.error,
+            )
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(job.into()))
+    }
+
+    async fn list_index_content(
+        &self,
+        req: Request<ListIndexContentRequest>,
+    ) -> Result<Response<ListIndexContentResponse>, Status> {
+        let req = req.into_inner();
+        let content_list = self
+            .coordinator
+            .list_index_content(&req.namespace, &req.index)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(ListIndexContentResponse {
+            content_list: content_list.into_iter().map(|c| c.into()).collect(),
+        }))
+    }
+
+    async fn set_query_template(
+        &self,
+        req: Request<SetQueryTemplateRequest>,
+    ) -> Result<Response<SetQueryTemplateResponse>, Status> {
+        let query_template = req
+            .into_inner()
+            .template
+            .ok_or_else(|| tonic::Status::aborted("query template is missing"))?;
+        self.coordinator
+            .set_query_template(query_template.into())
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(SetQueryTemplateResponse {}))
+    }
+
+    async fn get_query_template(
+        &self,
+        req: Request<GetQueryTemplateRequest>,
+    ) -> Result<Response<GetQueryTemplateResponse>, Status> {
+        let req = req.into_inner();
+        let query_template = self
+            .coordinator
+            .get_query_template(&req.namespace, &req.name)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(GetQueryTemplateResponse {
+            template: Some(query_template.into()),
+        }))
+    }
+
+    async fn list_query_templates(
+        &self,
+        req: Request<ListQueryTemplatesRequest>,
+    ) -> Result<Response<ListQueryTemplatesResponse>, Status> {
+        let req = req.into_inner();
+        let templates = self
+            .coordinator
+            .list_query_templates(&req.namespace)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+            .into_iter()
+            .map(|query_template| query_template.into())
+            .collect();
+        Ok(Response::new(ListQueryTemplatesResponse { templates }))
+    }
+
+    async fn delete_query_template(
+        &self,
+        req: Request<DeleteQueryTemplateRequest>,
+    ) -> Result<Response<DeleteQueryTemplateResponse>, Status> {
+        let req = req.into_inner();
+        self.coordinator
+            .delete_query_template(&req.namespace, &req.name)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(DeleteQueryTemplateResponse {}))
+    }
+
+    async fn create_ingest_intent(
+        &self,
+        req: Request<CreateIngestIntentRequest>,
+    ) -> Result<Response<CreateIngestIntentResponse>, Status> {
+        let intent = req
+            .into_inner()
+            .intent
+            .ok_or_else(|| tonic::Status::aborted("ingest intent is missing"))?;
+        self.coordinator
+            .create_ingest_intent(intent.into())
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(CreateIngestIntentResponse {}))
+    }
+
+    async fn list_ingest_intents(
+        &self,
+        req: Request<ListIngestIntentsRequest>,
+    ) -> Result<Response<ListIngestIntentsResponse>, Status> {
+        let intents = self
+            .coordinator
+            .list_ingest_intents(req.into_inner().created_before)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+            .into_iter()
+            .map(|intent| intent.into())
+            .collect();
+        Ok(Response::new(ListIngestIntentsResponse { intents }))
+    }
+
+    async fn delete_ingest_intents(
+        &self,
+        req: Request<DeleteIngestIntentsRequest>,
+    ) -> Result<Response<DeleteIngestIntentsResponse>, Status> {
+        self.coordinator
+            .delete_ingest_intents(req.into_inner().content_ids)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(DeleteIngestIntentsResponse {}))
+    }
+
+    async fn create_embedding_expirations(
+        &self,
+        req: Request<CreateEmbeddingExpirationsRequest>,
+    ) -> Result<Response<CreateEmbeddingExpirationsResponse>, Status> {
+        let expirations = req
+            .into_inner()
+            .expirations
+            .into_iter()
+            .map(|expiration| expiration.into())
+            .collect();
+        self.coordinator
+            .create_embedding_expirations(expirations)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(CreateEmbeddingExpirationsResponse {}))
+    }
+
+    async fn list_embedding_expirations(
+        &self,
+        req: Request<ListEmbeddingExpirationsRequest>,
+    ) -> Result<Response<ListEmbeddingExpirationsResponse>, Status> {
+        let expirations = self
+            .coordinator
+            .list_embedding_expirations(req.into_inner().expires_before)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+            .into_iter()
+            .map(|expiration| expiration.into())
+            .collect();
+        Ok(Response::new(ListEmbeddingExpirationsResponse {
+            expirations,
+        }))
+    }
+
+    async fn delete_embedding_expirations(
+        &self,
+        req: Request<DeleteEmbeddingExpirationsRequest>,
+    ) -> Result<Response<DeleteEmbeddingExpirationsResponse>, Status> {
+        let expirations = req
+            .into_inner()
+            .expirations
+            .into_iter()
+            .map(|expiration| expiration.into())
+            .collect();
+        self.coordinator
+            .delete_embedding_expirations(expirations)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(DeleteEmbeddingExpirationsResponse {}))
+    }
+
+    async fn add_chunk_reference(
+        &self,
+        req: Request<AddChunkReferenceRequest>,
+    ) -> Result<Response<AddChunkReferenceResponse>, Status> {
+        let req = req.into_inner();
+        let reference = self
+            .coordinator
+            .add_chunk_reference(&req.index_table, &req.hash, &req.content_id)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(AddChunkReferenceResponse {
+            reference: Some(reference.into()),
+        }))
+    }
+
+    async fn remove_chunk_reference(
+        &self,
+        req: Request<RemoveChunkReferenceRequest>,
+    ) -> Result<Response<RemoveChunkReferenceResponse>, Status> {
+        let req = req.into_inner();
+        self.coordinator
+            .remove_chunk_reference(&req.index_table, &req.hash, &req.content_id)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(RemoveChunkReferenceResponse {}))
+    }
+
+    async fn get_chunk_references(
+        &self,
+        req: Request<GetChunkReferencesRequest>,
+    ) -> Result<Response<GetChunkReferencesResponse>, Status> {
+        let req = req.into_inner();
+        let references = self
+            .coordinator
+            .get_chunk_references(&req.index_table, &req.hashes)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(GetChunkReferencesResponse {
+            references: references.into_iter().map(|r| r.into()).collect(),
+        }))
+    }
+
+    async fn set_ingestion_plugin(
+        &self,
+        req: Request<SetIngestionPluginRequest>,
+    ) -> Result<Response<SetIngestionPluginResponse>, Status> {
+        let plugin = req
+            .into_inner()
+            .plugin
+            .ok_or_else(|| tonic::Status::aborted("ingestion plugin is missing"))?;
+        self.coordinator
+            .set_ingestion_plugin(plugin.into())
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(SetIngestionPluginResponse {}))
+    }
+
+    async fn list_ingestion_plugins(
+        &self,
+        req: Request<ListIngestionPluginsRequest>,
+    ) -> Result<Response<ListIngestionPluginsResponse>, Status> {
+        let plugins = self
+            .coordinator
+            .list_ingestion_plugins(&req.into_inner().namespace)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+            .into_iter()
+            .map(|plugin| plugin.into())
+            .collect();
+        Ok(Response::new(ListIngestionPluginsResponse { plugins }))
+    }
+
+    async fn delete_ingestion_plugin(
+        &self,
+        req: Request<DeleteIngestionPluginRequest>,
+    ) -> Result<Response<DeleteIngestionPluginResponse>, Status> {
+        let req = req.into_inner();
+        let plugin = self
+            .coordinator
+            .delete_ingestion_plugin(&req.namespace, &req.name)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(DeleteIngestionPluginResponse {
+            plugin: Some(plugin.into()),
+        }))
+    }
+
+    async fn set_processing_sla(
+        &self,
+        req: Request<SetProcessingSlaRequest>,
+    ) -> Result<Response<SetProcessingSlaResponse>, Status> {
+        let sla = req
+            .into_inner()
+            .sla
+            .ok_or_else(|| tonic::Status::aborted("processing sla is missing"))?;
+        let sla = self
+            .coordinator
+            .set_processing_sla(sla.into())
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(SetProcessingSlaResponse {
+            sla: Some(sla.into()),
+        }))
+    }
+
+    async fn list_processing_slas(
+        &self,
+        req: Request<ListProcessingSlasRequest>,
+    ) -> Result<Response<ListProcessingSlasResponse>, Status> {
+        let slas = self
+            .coordinator
+            .list_processing_slas(&req.into_inner().namespace)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+            .into_iter()
+            .map(|sla| sla.into())
+            .collect();
+        Ok(Response::new(ListProcessingSlasResponse { slas }))
+    }
+
+    async fn delete_processing_sla(
+        &self,
+        req: Request<DeleteProcessingSlaRequest>,
+    ) -> Result<Response<DeleteProcessingSlaResponse>, Status> {
+        let req = req.into_inner();
+        self.coordinator
+            .delete_processing_sla(&req.namespace, &req.extraction_policy)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(DeleteProcessingSlaResponse {}))
+    }
+
+    async fn get_namespace_storage(
+        &self,
+        req: Request<GetNamespaceStorageRequest>,
+    ) -> Result<Response<GetNamespaceStorageResponse>, Status> {
+        let stored_bytes = self
+            .coordinator
+            .namespace_stored_bytes(&req.into_inner().namespace)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(GetNamespaceStorageResponse { stored_bytes }))
+    }
+
+    async fn set_namespace_quotas(
+        &self,
+        req: Request<SetNamespaceQuotasRequest>,
+    ) -> Result<Response<SetNamespaceQuotasResponse>, Status> {
+        let req = req.into_inner();
+        self.coordinator
+            .set_namespace_quotas(&req.namespace, req.quotas.unwrap_or_default().into())
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(SetNamespaceQuotasResponse {}))
+    }
+
+    async fn get_namespace_quotas(
+        &self,
+        req: Request<GetNamespaceQuotasRequest>,
+    ) -> Result<Response<GetNamespaceQuotasResponse>, Status> {
+        let namespace = req.into_inner().namespace;
+        let quotas = self
+            .coordinator
+            .get_namespace_quotas(&namespace)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        let stored_bytes = self
+            .coordinator
+            .namespace_stored_bytes(&namespace)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        let stats = self
+            .coordinator
+            .get_namespace_stats(&namespace)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(GetNamespaceQuotasResponse {
+            quotas: Some(quotas.into()),
+            content: stats.content,
+            stored_bytes,
+            extraction_policies: self
+                .coordinator
+                .namespace_extraction_policy_count(&namespace)
+                .await,
+        }))
+    }
+
+    async fn get_namespace_stats(
+        &self,
+        req: Request<GetNamespaceStatsRequest>,
+    ) -> Result<Response<GetNamespaceStatsResponse>, Status> {
+        let namespace = req.into_inner().namespace;
+        let stats = self
+            .coordinator
+            .get_namespace_stats(&namespace)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        let stored_bytes = self
+            .coordinator
+            .namespace_stored_bytes(&namespace)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(GetNamespaceStatsResponse {
+            content: stats.content,
+            stored_bytes,
+            tasks_pending: stats.tasks_pending,
+            tasks_failed: stats.tasks_failed,
+            indexes: stats.indexes,
+        }))
+    }
+
+    async fn create_blob_migration_job(
+        &self,
+        req: Request<CreateBlobMigrationJobRequest>,
+    ) -> Result<Response<CreateBlobMigrationJobResponse>, Status> {
+        let req = req.into_inner();
+        let job = self
+            .coordinator
+            .create_blob_migration_job(&req.namespace, &req.target)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(CreateBlobMigrationJobResponse {
+            job: Some(job.into()),
+        }))
+    }
+
+    async fn get_blob_migration_job(
+        &self,
+        req: Request<GetBlobMigrationJobRequest>,
+    ) -> Result<Response<BlobMigrationJob>, Status> {
+        let job = self
+            .coordinator
+            .get_blob_migration_job(&req.into_inner().namespace)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(job.into()))
+    }
+
+    async fn update_blob_migration_job(
+        &self,
+        req: Request<UpdateBlobMigrationJobRequest>,
+    ) -> Result<Response<BlobMigrationJob>, Status> {
+        let req = req.into_inner();
+        let job = self
+            .coordinator
+            .update_blob_migration_job(&req.namespace, req.storage_urls, req.completed, &req.error)
+            .await
+            .map_err(write_error_status)?;
+        Ok(Response::new(job.into()))
+    }
+
+    async fn list_cluster_members(
+        &self,
+        _req: Request<ListClusterMembersRequest>,
+    ) -> Result<Response<ListClusterMembersResponse>, Status> {
+        self.cluster_members().await
+    }
+
+    async fn add_cluster_learner(
+        &self,
+        req: Request<AddClusterLearnerRequest>,
+    ) -> Result<Response<ListClusterMembersResponse>, Status> {
+        let req = req.into_inner();
+        self.coordinator
+            .add_cluster_learner(req.node_id, &req.address, &req.coordinator_addr)
+            .await
+            .map_err(write_error_status)?;
+        self.cluster_members().await
+    }
+
+    async fn promote_cluster_learner(
+        &self,
+        req: Request<PromoteClusterLearnerRequest>,
+    ) -> Result<Response<ListClusterMembersResponse>, Status> {
+        self.coordinator
+            .promote_cluster_learner(req.into_inner().node_id)
+            .await
+            .map_err(write_error_status)?;
+        self.cluster_members().await
+    }
+
+    async fn remove_cluster_member(
+        &self,
+        req: Request<RemoveClusterMemberRequest>,
+    ) -> Result<Response<ListClusterMembersResponse>, Status> {
+        self.coordinator
+            .remove_cluster_member(req.into_inner().node_id)
+            .await
+            .map_err(write_error_status)?;
+        self.cluster_members().await
+    }
+}
+
+impl CoordinatorServiceServer {
+    //  Bounded reads are served right away, the freshness of the state is up
+    // to the follower reads config
+    async fn ensure_read_consistency(&self, consistency: ReadConsistency) -> Result<(), Status> {
+        match consistency {
+            ReadConsistency::Bounded => Ok(()),
+            ReadConsistency::Linearizable => self
+                .coordinator
+                .ensure_linearizable_read()
+                .await
+                .map_err(|e| tonic::Status::unavailable(e.to_string())),
+        }
+    }
+
+    async fn cluster_members(&self) -> Result<Response<ListClusterMembersResponse>, Status> {
+        let members = self
+            .coordinator
+            .list_cluster_members()
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(ListClusterMembersResponse {
+            members: members.into_iter().map(Into::into).collect(),
+        }))
+    }
 }
 
 pub struct CoordinatorServer {
     addr: SocketAddr,
     coordinator: Arc<Coordinator>,
     shared_state: Arc<state::App>,
+    config: Arc<ServerConfig>,
 }
 
 impl CoordinatorServer {
@@ -820,6 +1832,7 @@ impl CoordinatorServer {
             addr,
             coordinator,
             shared_state,
+            config,
         })
     }
 
@@ -836,6 +1849,24 @@ impl CoordinatorServer {
             .initialize_raft()
             .await
             .map_err(|e| anyhow!("unable to initialize shared state: {}", e.to_string()))?;
+        if let Some(backup_config) = self.config.state_store.backup.clone() {
+            let blob_storage = BlobStorage::new_with_config(self.config.blob_storage.clone());
+            tokio::spawn(run_state_backups(
+                shutdown_rx.clone(),
+                self.shared_state.clone(),
+                blob_storage,
+                backup_config,
+            ));
+        }
+        if let Some(backup_config) = self.config.state_store.rocksdb_backup.clone() {
+            let blob_storage = BlobStorage::new_with_config(self.config.blob_storage.clone());
+            tokio::spawn(run_rocksdb_backups(
+                shutdown_rx.clone(),
+                self.shared_state.clone(),
+                blob_storage,
+                backup_config,
+            ));
+        }
         let leader_change_watcher = self.coordinator.get_leader_change_watcher();
         let coordinator_clone = self.coordinator.clone();
         let state_watcher_rx = self.coordinator.get_state_watcher();
@@ -876,9 +1907,18 @@ async fn run_scheduler(
     coordinator: Arc<Coordinator>,
 ) -> Result<()> {
     let is_leader = AtomicBool::new(false);
+    let mut task_retry_interval = tokio::time::interval(TASK_RETRY_INTERVAL);
+    task_retry_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
     loop {
         tokio::select! {
+            _ = task_retry_interval.tick() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.assign_retried_tasks().await {
+                        error!("error assigning retried tasks: {:?}", err);
+                    }
+                }
+            },
             _ = state_watcher_rx.changed() => {
                 if is_leader.load(Ordering::Relaxed) {
                    let _state_change = state_watcher_rx.borrow_and_update().clone();
@@ -901,6 +1941,60 @@ async fn run_scheduler(
     Ok(())
 }
 
+//  Only the leader uploads backups, every node holds the same state machine
+async fn run_state_backups(
+    shutdown_rx: Receiver<()>,
+    shared_state: Arc<state::App>,
+    blob_storage: BlobStorage,
+    config: StateBackupConfig,
+) {
+    let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        if shutdown_rx.has_changed().unwrap_or(true) {
+            info!("state backups shutting down");
+            break;
+        }
+        if !*shared_state.leader_change_rx.borrow() {
+            continue;
+        }
+        match shared_state
+            .backup_state(&blob_storage, config.retain)
+            .await
+        {
+            Ok(url) => info!("uploaded state machine backup to {}", url),
+            Err(e) => error!("unable to back up state machine: {}", e),
+        }
+    }
+}
+
+//  Every node backs up its own database, the raft log of a node can hold
+// entries which aren't committed yet
+async fn run_rocksdb_backups(
+    shutdown_rx: Receiver<()>,
+    shared_state: Arc<state::App>,
+    blob_storage: BlobStorage,
+    config: RocksDbBackupConfig,
+) {
+    let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        if shutdown_rx.has_changed().unwrap_or(true) {
+            info!("rocksdb backups shutting down");
+            break;
+        }
+        match shared_state
+            .backup_rocksdb(&blob_storage, config.retain)
+            .await
+        {
+            Ok(backup_id) => info!("uploaded rocksdb backup {}", backup_id),
+            Err(e) => error!("unable to back up rocksdb: {}", e),
+        }
+    }
+}
+
 #[tracing::instrument]
 async fn shutdown_signal(shutdown_tx: Sender<()>) {
     let ctrl_c = async {
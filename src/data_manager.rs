@@ -1,36 +1,173 @@
 use std::{
-    collections::{hash_map::DefaultHasher, HashMap},
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
     fmt,
     hash::{Hash, Hasher},
     path::Path,
     str::FromStr,
-    sync::Arc,
-    time::SystemTime,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant, SystemTime},
 };
 
 use anyhow::{anyhow, Result};
 use bytes::Bytes;
-use futures::Stream;
+use futures::{future::join_all, Stream, StreamExt, TryStreamExt};
 use indexify_internal_api as internal_api;
 use indexify_proto::indexify_coordinator::{self};
 use itertools::Itertools;
 use nanoid::nanoid;
-use tracing::{error, info};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tracing::{error, info, warn};
 
 use crate::{
     api::{self, BeginExtractedContentIngest},
-    blob_storage::{BlobStorage, BlobStorageWriter, PutResult, StoragePartWriter},
+    blob_storage::{
+        self,
+        compression,
+        content_addressed_hash,
+        content_addressed_key,
+        namespace_key,
+        BlobBackend,
+        BlobMeta,
+        BlobStorage,
+        BlobStorageWriter,
+        PutResult,
+        StoragePartWriter,
+    },
     coordinator_client::CoordinatorClient,
+    filter_expression::FilterExpr,
+    geo_filters,
     grpc_helper::GrpcHelper,
+    index_snapshots::{self, IndexSnapshot, SnapshotChunk, SnapshotHeader},
+    keyword_index::is_keyword_schema,
+    metadata_export::{self, ExportRow},
     metadata_storage::{
+        merge_patch,
         query_engine::{run_query, StructuredDataRow},
         ExtractedMetadata,
         MetadataReaderTS,
         MetadataStorageTS,
+        MetadataVersion,
+        PagedMetadata,
+        Provenance,
+        IMPORT_EXTRACTOR,
+        MANUAL_EXTRACTOR,
     },
+    metrics::lifecycle_events,
+    namespace_templates,
+    processing_slas::{self, SlaAttainment, SlaTracker},
+    utils::timestamp_secs,
     vector_index::{ScoredText, VectorIndexManager},
+    wasm_plugins::{
+        PluginDecision,
+        PluginInput,
+        PluginLimits,
+        ScoredCandidate,
+        ScoringCandidate,
+        ScoringInput,
+        WasmPluginRuntime,
+        DEFAULT_PLUGIN_MAX_FUEL,
+        DEFAULT_PLUGIN_MAX_MEMORY_BYTES,
+        INGESTION_PLUGIN_INSPECT_BYTES,
+    },
 };
 
+const DEFAULT_REBALANCE_BATCH_SIZE: usize = 100;
+
+//  Number of content copied by a blob migration between progress updates,
+// content copied since the last update is copied again when a job resumes
+const BLOB_MIGRATION_BATCH_SIZE: usize = 100;
+
+pub const DEFAULT_SEARCH_LIMIT: u64 = 5;
+
+const DEFAULT_RECENCY_WEIGHT: f32 = 0.3;
+
+//  Number of candidates fetched per requested result when boosting recent
+// content, so newer but slightly less similar content can make it into the
+// top k.
+const RECENCY_BOOST_OVERSAMPLING: u64 = 3;
+
+//  Number of candidates fetched per requested result when filtering search
+// results by location
+pub const GEO_FILTER_OVERSAMPLING: u64 = 5;
+
+//  Number of candidates fetched per requested result when limiting the
+// results per parent content, so the top k can still be filled after dropping
+// chunks of documents which already have enough results
+pub const MAX_PER_PARENT_OVERSAMPLING: u64 = 3;
+
+//  Number of candidates fetched per requested result when filtering search
+// results with a filter expression
+pub const FILTER_EXPRESSION_OVERSAMPLING: u64 = 5;
+
+//  Number of candidates fetched per requested result when collapsing
+// duplicate search results
+pub const DEDUPE_OVERSAMPLING: u64 = 3;
+
+//  Maximum number of queries of a batch search which are run at the same time
+const BATCH_SEARCH_CONCURRENCY: usize = 8;
+
+//  Maximum number of extracted contents of a task which are written at the
+// same time
+const EXTRACTED_CONTENT_WRITE_CONCURRENCY: usize = 16;
+
+//  Processing slas are looked up for every finished task, they are cached for
+// this long to avoid asking the coordinator every time
+const PROCESSING_SLA_CACHE_TTL: Duration = Duration::from_secs(30);
+
+//  Maximum number of parents followed to find the ingested content an
+// extracted content was derived from
+const MAX_CONTENT_TREE_DEPTH: usize = 32;
+
+//  Time a coordinator is given to report its health before it's reported as
+// unreachable
+const NODE_HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+
+//  Number of characters of the preview stored with text content
+pub const CONTENT_PREVIEW_CHARS: usize = 200;
+
+//  Bytes of the content needed for the preview, enough for the characters
+// even if all of them take up four bytes in UTF-8
+pub const CONTENT_PREVIEW_BYTES: usize = CONTENT_PREVIEW_CHARS * 4;
+
+fn is_text_like(content_type: &str) -> bool {
+    let content_type = content_type.split(';').next().unwrap_or_default().trim();
+    content_type.starts_with("text/") ||
+        content_type == "application/json" ||
+        content_type == "application/xml" ||
+        content_type.ends_with("+json") ||
+        content_type.ends_with("+xml")
+}
+
+//  Expiration of content ingested now with the TTL, zero if it has none
+fn content_expiration(ttl_secs: Option<u64>) -> u64 {
+    ttl_secs.map_or(0, |ttl_secs| timestamp_secs().saturating_add(ttl_secs))
+}
+
+//  Id of a request made to the coordinator on behalf of a client request, so
+// a retried client request is only applied once. Ids sent by clients are only
+// unique to a namespace and an operation, which are made part of the id.
+// `part` tells apart the requests made for the same client request. Only
+// generated when the client sent no id
+fn coordinator_request_id(
+    namespace: &str,
+    operation: &str,
+    client_request_id: Option<&str>,
+    part: Option<&str>,
+) -> String {
+    match (client_request_id, part) {
+        (Some(id), Some(part)) => format!("{}/{}/{}/{}", namespace, operation, id, part),
+        (Some(id), None) => format!("{}/{}/{}", namespace, operation, id),
+        (None, _) => nanoid!(),
+    }
+}
+
+/// A write was rejected by the coordinators because its request id was used
+/// already for a different write
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("{0}")]
+pub struct RequestIdConflict(pub String);
+
 fn index_in_features(
     output_index_map: &HashMap<String, String>,
     features: &[api::Feature],
@@ -44,12 +181,94 @@ fn index_in_features(
     })
 }
 
+/// An extractor produced an embedding whose dimension differs from the
+/// dimension of the index its output is written to, e.g. after the model of
+/// the extractor was changed without migrating the index.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error(
+    "extractor {extractor} of extraction policy {extraction_policy} produced an embedding of \
+     dimension {actual} for output {output} of content {content_id}, but index {index} has \
+     dimension {expected}"
+)]
+pub struct EmbeddingDimensionMismatch {
+    pub extractor: String,
+    pub extraction_policy: String,
+    pub content_id: String,
+    pub output: String,
+    pub index: String,
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl EmbeddingDimensionMismatch {
+    fn check(
+        extractor: &str,
+        extraction_policy: &str,
+        content_id: &str,
+        output: &str,
+        index: &str,
+        expected: usize,
+        actual: usize,
+    ) -> Result<(), Self> {
+        if expected == actual {
+            return Ok(());
+        }
+        Err(Self {
+            extractor: extractor.to_string(),
+            extraction_policy: extraction_policy.to_string(),
+            content_id: content_id.to_string(),
+            output: output.to_string(),
+            index: index.to_string(),
+            expected,
+            actual,
+        })
+    }
+}
+
+/// A write was rejected by the coordinators because it would take the
+/// namespace over one of its quotas
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("{0}")]
+pub struct NamespaceQuotaExceeded(pub String);
+
+//  Writes rejected for going over a quota of their namespace, or for reusing
+// the request id of another write, are told apart from writes which failed
+fn coordinator_write_error(context: &str, e: anyhow::Error) -> anyhow::Error {
+    match e.downcast_ref::<tonic::Status>() {
+        Some(status) if status.code() == tonic::Code::ResourceExhausted => {
+            NamespaceQuotaExceeded(status.message().to_string()).into()
+        }
+        Some(status) if status.code() == tonic::Code::AlreadyExists => {
+            RequestIdConflict(status.message().to_string()).into()
+        }
+        _ => anyhow!("{} {}", context, e),
+    }
+}
+
+//  The extractor which produced the embeddings of an index, to re-extract them
+// when rebuilding it
+struct IndexExtractor {
+    name: String,
+    input_params: Option<serde_json::Value>,
+    output: String,
+}
+
 pub struct DataManager {
     pub vector_index_manager: Arc<VectorIndexManager>,
     metadata_index_manager: MetadataStorageTS,
     metadata_reader: MetadataReaderTS,
     blob_storage: Arc<BlobStorage>,
     coordinator_client: Arc<CoordinatorClient>,
+    //  Embedding schemas of the vector index tables, by table name
+    index_schemas: RwLock<HashMap<String, internal_api::EmbeddingSchema>>,
+    //  Structured data schemas of the content sources, by schema id
+    structured_data_schemas: RwLock<HashMap<String, internal_api::StructuredDataSchema>>,
+    ingestion_server_id: String,
+    wasm_plugins: Arc<WasmPluginRuntime>,
+    //  Processing slas of a namespace and when they were fetched
+    processing_slas: RwLock<HashMap<String, (Instant, Vec<internal_api::ProcessingSla>)>>,
+    sla_tracker: SlaTracker,
+    sla_webhook_client: reqwest::Client,
 }
 
 impl fmt::Debug for DataManager {
@@ -65,6 +284,7 @@ impl DataManager {
         metadata_reader: MetadataReaderTS,
         blob_storage: Arc<BlobStorage>,
         coordinator_client: Arc<CoordinatorClient>,
+        ingestion_server_id: &str,
     ) -> Self {
         DataManager {
             vector_index_manager,
@@ -72,6 +292,13 @@ impl DataManager {
             metadata_reader,
             blob_storage,
             coordinator_client,
+            index_schemas: RwLock::new(HashMap::new()),
+            structured_data_schemas: RwLock::new(HashMap::new()),
+            ingestion_server_id: ingestion_server_id.to_string(),
+            wasm_plugins: Arc::new(WasmPluginRuntime::new()),
+            processing_slas: RwLock::new(HashMap::new()),
+            sla_tracker: SlaTracker::default(),
+            sla_webhook_client: reqwest::Client::new(),
         }
     }
 
@@ -91,7 +318,11 @@ impl DataManager {
     }
 
     #[tracing::instrument]
-    pub async fn create_namespace(&self, namespace: &api::DataNamespace) -> Result<()> {
+    pub async fn create_namespace(
+        &self,
+        namespace: &api::DataNamespace,
+        operation_token: Option<String>,
+    ) -> Result<()> {
         info!("creating data namespace: {}", namespace.name);
         let policies = namespace
             .extraction_policies
@@ -105,12 +336,28 @@ impl DataManager {
         let request = indexify_coordinator::CreateNamespaceRequest {
             name: namespace.name.clone(),
             policies,
+            operation_token: operation_token.unwrap_or_default(),
         };
         let _resp = self
             .coordinator_client
-            .get()
-            .await?
-            .create_ns(request)
+            .call_leader(request, |mut client, req| async move {
+                client.create_ns(req).await
+            })
+            .await?;
+        Ok(())
+    }
+
+    #[tracing::instrument]
+    pub async fn delete_namespace(&self, name: &str) -> Result<()> {
+        info!("deleting data namespace: {}", name);
+        let req = indexify_coordinator::DeleteNamespaceRequest {
+            name: name.to_string(),
+        };
+        self.coordinator_client
+            .call_leader(
+                req,
+                |mut client, req| async move { client.delete_ns(req).await },
+            )
             .await?;
         Ok(())
     }
@@ -131,45 +378,178 @@ impl DataManager {
         namespace.try_into()
     }
 
-    pub async fn create_extraction_policy(
+    /// Creates the namespace and the extraction policies of the template.
+    /// Policies are created in the order of the template so that content
+    /// sources exist before the policies reading from them.
+    pub async fn instantiate_namespace_template(
+        &self,
+        template: &api::NamespaceTemplate,
+        request: &api::InstantiateNamespaceTemplate,
+    ) -> Result<api::InstantiateNamespaceTemplateResponse> {
+        let policies = namespace_templates::render_policies(template, &request.parameters)?;
+        info!(
+            "instantiating namespace template {} as namespace {}",
+            template.name, request.namespace
+        );
+        self.create_namespace(
+            &api::DataNamespace {
+                name: request.namespace.clone(),
+                extraction_policies: vec![],
+            },
+            None,
+        )
+        .await?;
+        let mut extraction_policies = Vec::new();
+        let mut index_names = Vec::new();
+        for policy in &policies {
+            index_names.extend(
+                self.create_extraction_policy(&request.namespace, policy)
+                    .await?,
+            );
+            extraction_policies.push(policy.name.clone());
+        }
+        Ok(api::InstantiateNamespaceTemplateResponse {
+            namespace: request.namespace.clone(),
+            extraction_policies,
+            index_names,
+        })
+    }
+
+    /// Streams the state changes applied on the coordinator from now on
+    pub async fn watch_state_changes(
+        &self,
+        namespace: Option<String>,
+        change_types: Vec<String>,
+    ) -> Result<tonic::Streaming<indexify_coordinator::StateChangeEvent>> {
+        let req = indexify_coordinator::WatchStateChangesRequest {
+            namespace: namespace.unwrap_or_default(),
+            change_types,
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .watch_state_changes(req)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    pub async fn update_extraction_policy(
         &self,
         namespace: &str,
-        ep_req: &api::ExtractionPolicyRequest,
-    ) -> Result<Vec<String>> {
+        name: &str,
+        request: &api::UpdateExtractionPolicyRequest,
+    ) -> Result<api::ExtractionPolicy> {
         info!(
-            "adding extractor bindings namespace: {}, extractor: {}, binding: {}",
-            namespace, ep_req.extractor, ep_req.name,
+            "updating extraction policy namespace: {}, policy: {}",
+            namespace, name
         );
-        let input_params_serialized = serde_json::to_string(&ep_req.input_params)
-            .map_err(|e| anyhow!("unable to serialize input params to str {}", e))?;
-        let req = indexify_coordinator::ExtractionPolicyRequest {
+        let req = indexify_coordinator::UpdateExtractionPolicyRequest {
             namespace: namespace.to_string(),
-            extractor: ep_req.extractor.clone(),
-            name: ep_req.name.clone(),
-            filters: ep_req.filters_eq.clone().unwrap_or_default(),
-            input_params: input_params_serialized,
-            content_source: ep_req
-                .content_source
+            name: name.to_string(),
+            filters: request
+                .filters_eq
                 .clone()
-                .unwrap_or("ingestion".to_string()),
-            created_at: SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)?
-                .as_secs() as i64,
+                .map(|filters| indexify_coordinator::ExtractionPolicyFilters { filters }),
+            input_params: request
+                .input_params
+                .as_ref()
+                .map(|input_params| input_params.to_string()),
+            reextract: request.reextract,
+            content_filter: request.content_filter.clone().map(Into::into),
+            retry_policy: request.retry_policy.clone().map(Into::into),
         };
         let response = self
             .coordinator_client
-            .get()
+            .call_leader(req, |mut client, req| async move {
+                client.update_extraction_policy(req).await
+            })
             .await?
-            .create_extraction_policy(req)
+            .into_inner();
+        response
+            .extraction_policy
+            .ok_or_else(|| anyhow!("extraction policy missing from response"))?
+            .try_into()
+    }
+
+    /// Deletes the extraction policy, its indexes are archived or dropped
+    /// along with their tables in the vector and keyword stores
+    pub async fn delete_extraction_policy(
+        &self,
+        namespace: &str,
+        name: &str,
+        indexes: api::DeletedPolicyIndexes,
+    ) -> Result<api::DeleteExtractionPolicyResponse> {
+        info!(
+            "deleting extraction policy namespace: {}, policy: {}, indexes: {:?}",
+            namespace, name, indexes
+        );
+        let req = indexify_coordinator::DeleteExtractionPolicyRequest {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            drop_indexes: indexes == api::DeletedPolicyIndexes::Drop,
+        };
+        let response = self
+            .coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.delete_extraction_policy(req).await
+            })
             .await?
             .into_inner();
+        //  The indexes are removed from the coordinator first, so they aren't
+        // searched while their tables are dropped
+        for table in &response.dropped_index_tables {
+            self.vector_index_manager.drop_index(table).await?;
+        }
+        Ok(api::DeleteExtractionPolicyResponse {
+            dropped_index_tables: response.dropped_index_tables,
+        })
+    }
+
+    pub async fn create_extraction_policy(
+        &self,
+        namespace: &str,
+        ep_req: &api::ExtractionPolicyRequest,
+    ) -> Result<Vec<String>> {
+        info!(
+            "adding extractor bindings namespace: {}, extractor: {}, binding: {}",
+            namespace, ep_req.extractor, ep_req.name,
+        );
+        let quantization: Option<internal_api::IndexQuantization> =
+            ep_req.quantization.clone().map(Into::into);
+        if let Some(quantization) = &quantization {
+            quantization.validate()?;
+        }
+        if let Some(index_params) = &ep_req.index_params {
+            if !index_params.is_object() {
+                return Err(anyhow!(
+                    "index params must be an object, got {}",
+                    index_params
+                ));
+            }
+        }
+        let req = Self::extraction_policy_request(namespace, ep_req)?;
+        let response = self
+            .coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.create_extraction_policy(req).await
+            })
+            .await
+            .map_err(|e| coordinator_write_error("unable to create extraction policy", e))?
+            .into_inner();
         let mut index_names = Vec::new();
         let extractor = response
             .extractor
             .ok_or(anyhow!("extractor {:?} not found", ep_req.extractor))?;
         for (name, output_schema) in &extractor.embedding_schemas {
-            let embedding_schema: internal_api::EmbeddingSchema =
+            let mut embedding_schema: internal_api::EmbeddingSchema =
                 serde_json::from_str(output_schema)?;
+            embedding_schema.quantization = quantization.clone();
+            if let Some(distance) = &ep_req.distance {
+                embedding_schema.distance = distance.to_string();
+            }
+            embedding_schema.ttl_secs = ep_req.embedding_ttl_secs;
+            embedding_schema.index_params = ep_req.index_params.clone();
             let index_name = response.output_index_name_mapping.get(name).unwrap();
             let table_name = response.index_name_table_mapping.get(index_name).unwrap();
             index_names.push(index_name.clone());
@@ -178,6 +558,36 @@ impl DataManager {
                 .vector_index_manager
                 .create_index(table_name, embedding_schema.clone())
                 .await?;
+            for vector_table in embedding_schema.named_vector_tables(table_name) {
+                self.vector_index_manager
+                    .create_index(&vector_table, embedding_schema.clone())
+                    .await?;
+            }
+            self.index_schemas
+                .write()
+                .unwrap()
+                .insert(table_name.clone(), embedding_schema.clone());
+            self.create_index_metadata(
+                namespace,
+                index_name,
+                table_name,
+                schema_json,
+                &ep_req.name,
+                &extractor.name,
+            )
+            .await?;
+        }
+        if let Some(index_name) = response
+            .output_index_name_mapping
+            .get(internal_api::KEYWORD_INDEX_OUTPUT)
+        {
+            let table_name = response.index_name_table_mapping.get(index_name).unwrap();
+            index_names.push(index_name.clone());
+            let keyword_schema = internal_api::KeywordIndexSchema::default();
+            let schema_json = serde_json::to_value(&keyword_schema)?;
+            self.vector_index_manager
+                .create_keyword_index(table_name, keyword_schema)
+                .await?;
             self.create_index_metadata(
                 namespace,
                 index_name,
@@ -192,325 +602,2827 @@ impl DataManager {
         Ok(index_names)
     }
 
-    async fn create_index_metadata(
+    fn extraction_policy_request(
+        namespace: &str,
+        ep_req: &api::ExtractionPolicyRequest,
+    ) -> Result<indexify_coordinator::ExtractionPolicyRequest> {
+        let input_params_serialized = serde_json::to_string(&ep_req.input_params)
+            .map_err(|e| anyhow!("unable to serialize input params to str {}", e))?;
+        let created_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs() as i64;
+        Ok(indexify_coordinator::ExtractionPolicyRequest {
+            namespace: namespace.to_string(),
+            extractor: ep_req.extractor.clone(),
+            name: ep_req.name.clone(),
+            filters: ep_req.filters_eq.clone().unwrap_or_default(),
+            input_params: input_params_serialized,
+            content_source: ep_req
+                .content_source
+                .clone()
+                .unwrap_or("ingestion".to_string()),
+            created_at,
+            operation_token: ep_req.operation_token.clone().unwrap_or_default(),
+            keyword_index: ep_req.keyword_index,
+            content_filter: ep_req.content_filter.clone().map(Into::into),
+            backfill_from: match ep_req.backfill {
+                api::Backfill::All => None,
+                api::Backfill::None => Some(created_at),
+                api::Backfill::Since(since) => Some(since),
+            },
+            retry_policy: ep_req.retry_policy.clone().map(Into::into),
+            priority: ep_req.priority,
+        })
+    }
+
+    /// Finds the existing content the proposed extraction policy would be
+    /// applied to, without creating the policy
+    pub async fn dry_run_extraction_policy(
+        &self,
+        namespace: &str,
+        ep_req: &api::ExtractionPolicyRequest,
+        sample_size: u64,
+    ) -> Result<api::ExtractionPolicyDryRunResponse> {
+        let req = indexify_coordinator::DryRunExtractionPolicyRequest {
+            extraction_policy: Some(Self::extraction_policy_request(namespace, ep_req)?),
+            sample_size,
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .dry_run_extraction_policy(req)
+            .await?
+            .into_inner();
+        Ok(api::ExtractionPolicyDryRunResponse {
+            extraction_policy: ep_req.name.clone(),
+            matching_content: response.matching_content,
+            matching_bytes: response.matching_bytes,
+            sample: response.sample.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Starts migrating an index to a new extraction policy, which applies
+    /// the given extractor to the same content as the policy writing the
+    /// index. The new policy writes to a parallel index which replaces the
+    /// existing one once the migration is completed.
+    pub async fn create_index_migration(
         &self,
         namespace: &str,
         index_name: &str,
-        table_name: &str,
-        schema: serde_json::Value,
-        extraction_policy: &str,
-        extractor: &str,
-    ) -> Result<()> {
-        let index = indexify_coordinator::CreateIndexRequest {
-            index: Some(indexify_coordinator::Index {
+        migration_req: &api::CreateIndexMigration,
+    ) -> Result<indexify_coordinator::IndexMigrationResponse> {
+        let mut client = self.coordinator_client.get().await?;
+        let index = client
+            .get_index(indexify_coordinator::GetIndexRequest {
+                namespace: namespace.to_string(),
                 name: index_name.to_string(),
-                table_name: table_name.to_string(),
+                consistency: indexify_coordinator::ReadConsistency::Bounded as i32,
+            })
+            .await?
+            .into_inner()
+            .index
+            .ok_or(anyhow!("index {} not found", index_name))?;
+        let source_policy = client
+            .list_extraction_policies(indexify_coordinator::ListExtractionPoliciesRequest {
                 namespace: namespace.to_string(),
-                schema: serde_json::to_value(schema).unwrap().to_string(),
-                extraction_policy: extraction_policy.to_string(),
-                extractor: extractor.to_string(),
-            }),
+            })
+            .await?
+            .into_inner()
+            .policies
+            .into_iter()
+            .find(|policy| policy.name == index.extraction_policy)
+            .ok_or(anyhow!(
+                "extraction policy {} not found",
+                index.extraction_policy
+            ))?;
+        let ep_req = api::ExtractionPolicyRequest {
+            extractor: migration_req.extractor.clone(),
+            name: migration_req.extraction_policy.clone(),
+            filters_eq: Some(source_policy.filters),
+            input_params: migration_req.input_params.clone(),
+            content_source: Some(source_policy.content_source),
+            operation_token: None,
+            keyword_index: false,
+            quantization: migration_req.quantization.clone(),
+            distance: migration_req.distance.clone(),
+            //  The new index keeps the TTL of the index it replaces
+            embedding_ttl_secs: serde_json::from_str::<internal_api::EmbeddingSchema>(
+                &index.schema,
+            )
+            .ok()
+            .and_then(|schema| schema.ttl_secs),
+            index_params: migration_req.index_params.clone(),
+            content_filter: source_policy.content_filter.map(Into::into),
+            //  The new index is populated from the same content as the index it
+            // replaces
+            backfill: source_policy
+                .backfill_from
+                .map_or(api::Backfill::All, api::Backfill::Since),
+            retry_policy: source_policy.retry_policy.map(Into::into),
+            priority: source_policy.priority,
         };
-        let req = GrpcHelper::into_req(index);
-        let _resp = self
+        let index_names = self.create_extraction_policy(namespace, &ep_req).await?;
+        let target_index = match &migration_req.output {
+            Some(output) => format!("{}.{}", migration_req.extraction_policy, output),
+            None if index_names.len() == 1 => index_names[0].clone(),
+            None => {
+                return Err(anyhow!(
+                    "extractor {} has {} embedding outputs, specify the output to migrate to",
+                    migration_req.extractor,
+                    index_names.len()
+                ))
+            }
+        };
+        if !index_names.contains(&target_index) {
+            return Err(anyhow!(
+                "extraction policy {} does not write to index {}",
+                migration_req.extraction_policy,
+                target_index
+            ));
+        }
+        let req = indexify_coordinator::CreateIndexMigrationRequest {
+            namespace: namespace.to_string(),
+            source_index: index_name.to_string(),
+            target_index,
+        };
+        let response = client.create_index_migration(req).await?.into_inner();
+        Ok(response)
+    }
+
+    pub async fn get_index_migration(
+        &self,
+        namespace: &str,
+        index_name: &str,
+    ) -> Result<indexify_coordinator::IndexMigrationResponse> {
+        let req = indexify_coordinator::GetIndexMigrationRequest {
+            namespace: namespace.to_string(),
+            source_index: index_name.to_string(),
+        };
+        let response = self
             .coordinator_client
             .get()
             .await?
-            .create_index(req)
-            .await?;
-        Ok(())
+            .get_index_migration(req)
+            .await?
+            .into_inner();
+        Ok(response)
     }
 
-    pub async fn list_content(
+    pub async fn complete_index_migration(
         &self,
         namespace: &str,
-        source_filter: &str,
-        parent_id_filter: &str,
-        labels_eq_filter: Option<&HashMap<String, String>>,
-    ) -> Result<Vec<api::ContentMetadata>> {
-        let req = indexify_coordinator::ListContentRequest {
+        index_name: &str,
+        force: bool,
+    ) -> Result<indexify_coordinator::IndexMigrationResponse> {
+        let req = indexify_coordinator::CompleteIndexMigrationRequest {
             namespace: namespace.to_string(),
-            source: source_filter.to_string(),
-            parent_id: parent_id_filter.to_string(),
-            labels_eq: labels_eq_filter.unwrap_or(&HashMap::new()).clone(),
+            source_index: index_name.to_string(),
+            force,
         };
         let response = self
             .coordinator_client
-            .get()
+            .call_leader(req, |mut client, req| async move {
+                client.complete_index_migration(req).await
+            })
             .await?
-            .list_content(req)
-            .await?;
-        let content_list = response
-            .into_inner()
-            .content_list
-            .into_iter()
-            .map(|c| c.into())
-            .collect_vec();
-        Ok(content_list)
+            .into_inner();
+        Ok(response)
     }
 
-    #[tracing::instrument(skip(self, content_list))]
-    pub async fn add_texts(&self, namespace: &str, content_list: Vec<api::Content>) -> Result<()> {
-        for text in content_list {
-            let stream = futures::stream::once(async { Ok(Bytes::from(text.bytes)) });
-            let content_metadata = self
-                .write_content_bytes(
-                    namespace,
-                    Box::pin(stream),
-                    &text.labels,
-                    text.content_type,
-                    None,
-                    None,
-                    "ingestion",
-                )
-                .await?;
+    /// Starts moving the vectors of an index into a new table split into
+    /// `shard_count` shards, so they spread over capacity added to the vector
+    /// store. Vectors are copied in the background in batches, waiting
+    /// between batches to stay under `max_vectors_per_second`.
+    pub async fn rebalance_index(
+        self: &Arc<Self>,
+        namespace: &str,
+        index_name: &str,
+        rebalance_req: &api::RebalanceIndex,
+    ) -> Result<indexify_coordinator::RebalanceJob> {
+        if rebalance_req.shard_count == 0 {
+            return Err(anyhow!("shard_count has to be at least 1"));
+        }
+        let index = self.get_index_metadata(namespace, index_name).await?;
+        if is_keyword_schema(&index.schema) {
+            return Err(anyhow!(
+                "index {} is a keyword index, only embedding indexes can be rebalanced",
+                index_name
+            ));
+        }
+        self.start_rebalance_job(namespace, index, rebalance_req.clone(), false, None)
+            .await
+    }
+
+    /// Starts rebuilding an index from scratch into a new table, either from
+    /// the embeddings stored in its current table or by running the
+    /// extractor of its policy on the content again. Searches read the
+    /// current table until the rebuild completes.
+    pub async fn rebuild_index(
+        self: &Arc<Self>,
+        namespace: &str,
+        index_name: &str,
+        rebuild_req: &api::RebuildIndex,
+    ) -> Result<indexify_coordinator::RebalanceJob> {
+        if rebuild_req.shard_count == Some(0) {
+            return Err(anyhow!("shard_count has to be at least 1"));
+        }
+        let index = self.get_index_metadata(namespace, index_name).await?;
+        let job_req = api::RebalanceIndex {
+            shard_count: rebuild_req.shard_count.unwrap_or_default(),
+            batch_size: rebuild_req.batch_size,
+            max_vectors_per_second: rebuild_req.max_vectors_per_second,
+        };
+        let reextract = rebuild_req.source == api::RebuildSource::Extractor;
+        self.start_rebalance_job(namespace, index, job_req, reextract, None)
+            .await
+    }
+
+    /// Writes the embeddings of an index, with its named vectors and the
+    /// chunk references between identical content, to a snapshot in the blob
+    /// store.
+    pub async fn snapshot_index(
+        &self,
+        namespace: &str,
+        index_name: &str,
+    ) -> Result<api::IndexSnapshotResponse> {
+        let index = self.get_index_metadata(namespace, index_name).await?;
+        if is_keyword_schema(&index.schema) {
+            return Err(anyhow!(
+                "index {} is a keyword index, it's rebuilt from the text of the content",
+                index_name
+            ));
+        }
+        //  The vectors of an index being rebalanced are split over two tables
+        if let Ok(job) = self.get_rebalance_job(namespace, index_name).await {
+            if !job.completed && job.error.is_empty() {
+                return Err(anyhow!(
+                    "index {} is being rebalanced or rebuilt, take the snapshot once the job completes",
+                    index_name
+                ));
+            }
+        }
+        let schema: internal_api::EmbeddingSchema = serde_json::from_str(&index.schema)?;
+        if schema.sparse {
+            return Err(anyhow!(
+                "index {} is a sparse index, sparse indexes can't be snapshotted yet",
+                index_name
+            ));
+        }
+        let content_list = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_index_content(indexify_coordinator::ListIndexContentRequest {
+                namespace: namespace.to_string(),
+                index: index.name.clone(),
+            })
+            .await?
+            .into_inner()
+            .content_list;
+
+        let created_at = timestamp_secs();
+        let header = SnapshotHeader {
+            namespace: namespace.to_string(),
+            index: index.name.clone(),
+            table_name: index.table_name.clone(),
+            schema: index.schema.clone(),
+            vector_store: self.vector_index_manager.vector_store(),
+            created_at,
+        };
+        let key = index_snapshots::snapshot_key(namespace, &index.name, created_at);
+        let mut writer = self.blob_store_writer(namespace, &key).await?;
+        writer
+            .writer
+            .write_all(&index_snapshots::to_line(&header)?)
+            .await?;
+        let mut num_vectors = 0;
+        for batch in content_list.chunks(DEFAULT_REBALANCE_BATCH_SIZE) {
+            let chunks = self
+                .snapshot_chunks(&index.table_name, &schema, batch)
+                .await?;
+            for chunk in &chunks {
+                writer
+                    .writer
+                    .write_all(&index_snapshots::to_line(chunk)?)
+                    .await?;
+            }
+            num_vectors += chunks.len() as u64;
+        }
+        writer.writer.shutdown().await?;
+        info!(
+            "wrote snapshot of index {} with {} vectors to {}",
+            index.name, num_vectors, writer.url
+        );
+        Ok(api::IndexSnapshotResponse {
+            index: index.name,
+            url: writer.url,
+            num_vectors,
+            created_at,
+        })
+    }
+
+    async fn snapshot_chunks(
+        &self,
+        table: &str,
+        schema: &internal_api::EmbeddingSchema,
+        content_list: &[indexify_coordinator::ContentMetadata],
+    ) -> Result<Vec<SnapshotChunk>> {
+        let content_ids = content_list.iter().map(|c| c.id.clone()).collect_vec();
+        let mut named_vectors: HashMap<String, HashMap<String, Vec<f32>>> = HashMap::new();
+        for vector in &schema.named_vectors {
+            let points = self
+                .vector_index_manager
+                .get_points(
+                    &internal_api::EmbeddingSchema::named_vector_table(table, vector),
+                    content_ids.clone(),
+                )
+                .await?;
+            for point in points {
+                named_vectors
+                    .entry(point.content_id)
+                    .or_default()
+                    .insert(vector.clone(), point.embedding);
+            }
+        }
+        let hashes: HashMap<&String, &String> = content_list
+            .iter()
+            .filter(|c| !c.hash.is_empty())
+            .map(|c| (&c.id, &c.hash))
+            .collect();
+        let references: HashMap<String, Vec<String>> = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_chunk_references(indexify_coordinator::GetChunkReferencesRequest {
+                index_table: table.to_string(),
+                hashes: hashes.values().map(|hash| hash.to_string()).collect(),
+            })
+            .await?
+            .into_inner()
+            .references
+            .into_iter()
+            .map(|reference| (reference.hash, reference.content_ids))
+            .collect();
+        let points = self
+            .vector_index_manager
+            .get_points(table, content_ids)
+            .await?;
+        Ok(points
+            .into_iter()
+            .map(|point| {
+                let hash = hashes
+                    .get(&point.content_id)
+                    .map(|hash| hash.to_string())
+                    .unwrap_or_default();
+                SnapshotChunk {
+                    content_ids: references.get(&hash).cloned().unwrap_or_default(),
+                    vectors: named_vectors.remove(&point.content_id).unwrap_or_default(),
+                    content_id: point.content_id,
+                    hash,
+                    embedding: point.embedding,
+                    metadata: point.metadata,
+                }
+            })
+            .collect())
+    }
+
+    /// Starts restoring an index from a snapshot into a new table. Content
+    /// deleted since the snapshot was taken isn't restored, the embeddings of
+    /// content added since are copied from the current table. Searches read
+    /// the current table until the restore completes.
+    pub async fn restore_index(
+        self: &Arc<Self>,
+        namespace: &str,
+        index_name: &str,
+        restore_req: &api::RestoreIndex,
+    ) -> Result<indexify_coordinator::RebalanceJob> {
+        if restore_req.shard_count == Some(0) {
+            return Err(anyhow!("shard_count has to be at least 1"));
+        }
+        let index = self.get_index_metadata(namespace, index_name).await?;
+        if is_keyword_schema(&index.schema) {
+            return Err(anyhow!(
+                "index {} is a keyword index, it's rebuilt from the text of the content",
+                index_name
+            ));
+        }
+        let bytes = self
+            .blob_storage
+            .content_reader()
+            .bytes(&restore_req.url)
+            .await
+            .map_err(|e| anyhow!("unable to read snapshot {}: {}", restore_req.url, e))?;
+        let snapshot = IndexSnapshot::parse(&bytes)?;
+        let schema: internal_api::EmbeddingSchema = serde_json::from_str(&index.schema)?;
+        let snapshot_schema: internal_api::EmbeddingSchema =
+            serde_json::from_str(&snapshot.header.schema)?;
+        if snapshot_schema.dim != schema.dim || snapshot_schema.distance != schema.distance {
+            return Err(anyhow!(
+                "snapshot of index {} doesn't match the schema of index {}",
+                snapshot.header.index,
+                index_name
+            ));
+        }
+        let job_req = api::RebalanceIndex {
+            shard_count: restore_req.shard_count.unwrap_or_default(),
+            batch_size: restore_req.batch_size,
+            max_vectors_per_second: restore_req.max_vectors_per_second,
+        };
+        self.start_rebalance_job(namespace, index, job_req, false, Some(Arc::new(snapshot)))
+            .await
+    }
+
+    async fn get_index_metadata(
+        &self,
+        namespace: &str,
+        index_name: &str,
+    ) -> Result<indexify_coordinator::Index> {
+        self.coordinator_client
+            .get()
+            .await?
+            .get_index(indexify_coordinator::GetIndexRequest {
+                namespace: namespace.to_string(),
+                name: index_name.to_string(),
+                consistency: indexify_coordinator::ReadConsistency::Bounded as i32,
+            })
+            .await?
+            .into_inner()
+            .index
+            .ok_or(anyhow!("index {} not found", index_name))
+    }
+
+    //  A shard count of zero creates the target table with the default
+    // sharding of the vector store
+    async fn start_rebalance_job(
+        self: &Arc<Self>,
+        namespace: &str,
+        index: indexify_coordinator::Index,
+        rebalance_req: api::RebalanceIndex,
+        reextract: bool,
+        snapshot: Option<Arc<IndexSnapshot>>,
+    ) -> Result<indexify_coordinator::RebalanceJob> {
+        if !is_keyword_schema(&index.schema) &&
+            serde_json::from_str::<internal_api::EmbeddingSchema>(&index.schema)?.sparse
+        {
+            return Err(anyhow!(
+                "index {} is a sparse index, sparse indexes can't be moved to a new table yet",
+                index.name
+            ));
+        }
+        let mut client = self.coordinator_client.get().await?;
+
+        //  An unfinished job is resumed with its target table, since new vectors
+        // have been written to it since the job was started
+        let previous_job = client
+            .get_rebalance_job(indexify_coordinator::GetRebalanceJobRequest {
+                namespace: namespace.to_string(),
+                index: index.name.clone(),
+            })
+            .await
+            .ok()
+            .map(|response| response.into_inner());
+        let target_table = match previous_job {
+            Some(job) if !job.completed => job.target_table,
+            _ => format!("{}.{}.{}", namespace, index.name, timestamp_secs()),
+        };
+        let shard_count = (rebalance_req.shard_count > 0).then_some(rebalance_req.shard_count);
+        if is_keyword_schema(&index.schema) {
+            let schema: internal_api::KeywordIndexSchema = serde_json::from_str(&index.schema)?;
+            self.vector_index_manager
+                .create_keyword_index(&target_table, schema)
+                .await?;
+        } else {
+            let embedding_schema: internal_api::EmbeddingSchema =
+                serde_json::from_str(&index.schema)?;
+            self.vector_index_manager
+                .create_sharded_index(&target_table, embedding_schema.clone(), shard_count)
+                .await?;
+            for vector_table in embedding_schema.named_vector_tables(&target_table) {
+                self.vector_index_manager
+                    .create_sharded_index(&vector_table, embedding_schema.clone(), shard_count)
+                    .await?;
+            }
+            self.index_schemas
+                .write()
+                .unwrap()
+                .insert(target_table.clone(), embedding_schema);
+        }
+
+        let response = client
+            .create_rebalance_job(indexify_coordinator::CreateRebalanceJobRequest {
+                namespace: namespace.to_string(),
+                index: index.name.clone(),
+                target_table,
+                shard_count: rebalance_req.shard_count,
+                reextract,
+            })
+            .await?
+            .into_inner();
+        let job = response
+            .job
+            .ok_or(anyhow!("rebalancing job was not created"))?;
+
+        let data_manager = self.clone();
+        let background_job = job.clone();
+        tokio::spawn(async move {
+            data_manager
+                .run_rebalance_job(
+                    background_job,
+                    index,
+                    response.content_ids,
+                    rebalance_req,
+                    snapshot,
+                )
+                .await;
+        });
+        Ok(job)
+    }
+
+    pub async fn get_rebalance_job(
+        &self,
+        namespace: &str,
+        index_name: &str,
+    ) -> Result<indexify_coordinator::RebalanceJob> {
+        let req = indexify_coordinator::GetRebalanceJobRequest {
+            namespace: namespace.to_string(),
+            index: index_name.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_rebalance_job(req)
+            .await?
+            .into_inner();
+        Ok(response)
+    }
+
+    /// Starts copying the blobs of the content of the namespace to the target
+    /// backend, or to the backend new blobs of the namespace are written to
+    /// if there's no target. Content is switched over to its copy in batches as
+    /// the blobs are copied in the background, the blobs it was stored in
+    /// are kept.
+    pub async fn migrate_blobs(
+        self: &Arc<Self>,
+        namespace: &str,
+        target: Option<BlobBackend>,
+    ) -> Result<indexify_coordinator::BlobMigrationJob> {
+        let target = target.unwrap_or_else(|| self.blob_storage.namespace_backend(namespace));
+        let job = self
+            .coordinator_client
+            .get()
+            .await?
+            .create_blob_migration_job(indexify_coordinator::CreateBlobMigrationJobRequest {
+                namespace: namespace.to_string(),
+                target: target.to_string(),
+            })
+            .await?
+            .into_inner()
+            .job
+            .ok_or(anyhow!("blob migration job was not created"))?;
+        let data_manager = self.clone();
+        let namespace = namespace.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = data_manager.copy_blobs(&namespace, target).await {
+                error!("unable to migrate blobs of namespace {}: {}", namespace, e);
+                if let Err(e) = data_manager
+                    .update_blob_migration_job(&namespace, HashMap::new(), false, &e.to_string())
+                    .await
+                {
+                    error!("unable to report failure of blob migration job: {}", e);
+                }
+            }
+        });
+        Ok(job)
+    }
+
+    pub async fn get_blob_migration_job(
+        &self,
+        namespace: &str,
+    ) -> Result<indexify_coordinator::BlobMigrationJob> {
+        let req = indexify_coordinator::GetBlobMigrationJobRequest {
+            namespace: namespace.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_blob_migration_job(req)
+            .await?
+            .into_inner();
+        Ok(response)
+    }
+
+    async fn copy_blobs(&self, namespace: &str, target: BlobBackend) -> Result<()> {
+        let content_list = self.list_content(namespace, "", "", "", None, "").await?;
+        let reader = self.blob_storage.content_reader();
+        //  Content with the same bytes shares its blob, which is only copied
+        // once
+        let mut copies: HashMap<String, String> = HashMap::new();
+        let mut storage_urls = HashMap::new();
+        for content in content_list {
+            if BlobBackend::of_url(&content.storage_url).map_or(true, |backend| backend == target) {
+                continue;
+            }
+            let url = match copies.get(&content.storage_url) {
+                Some(url) => url.clone(),
+                None => {
+                    let key = migrated_blob_key(&content);
+                    //  Compressed blobs are copied as they're stored, their
+                    // checksum is the one of the decompressed bytes
+                    let mut data = reader.get_stored(&content.storage_url);
+                    if !compression::is_compressed(&content.storage_url) {
+                        data = blob_storage::verify_checksum(
+                            &content.storage_url,
+                            &content.hash,
+                            data,
+                        );
+                    }
+                    let res = self
+                        .blob_storage
+                        .put_in_backend(target, namespace, &key, data)
+                        .await
+                        .map_err(|e| {
+                            anyhow!("unable to copy blob {}: {}", content.storage_url, e)
+                        })?;
+                    copies.insert(content.storage_url.clone(), res.url.clone());
+                    res.url
+                }
+            };
+            storage_urls.insert(content.id, url);
+            if storage_urls.len() >= BLOB_MIGRATION_BATCH_SIZE {
+                self.update_blob_migration_job(
+                    namespace,
+                    std::mem::take(&mut storage_urls),
+                    false,
+                    "",
+                )
+                .await?;
+            }
+        }
+        self.update_blob_migration_job(namespace, storage_urls, true, "")
+            .await
+    }
+
+    async fn update_blob_migration_job(
+        &self,
+        namespace: &str,
+        storage_urls: HashMap<String, String>,
+        completed: bool,
+        error: &str,
+    ) -> Result<()> {
+        let req = indexify_coordinator::UpdateBlobMigrationJobRequest {
+            namespace: namespace.to_string(),
+            storage_urls,
+            completed,
+            error: error.to_string(),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.update_blob_migration_job(req).await
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_cluster_members(&self) -> Result<Vec<indexify_coordinator::ClusterMember>> {
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_cluster_members(indexify_coordinator::ListClusterMembersRequest {})
+            .await?
+            .into_inner();
+        Ok(response.members)
+    }
+
+    /// Asks every member of the raft cluster of the coordinators for its
+    /// health, members which don't answer within `NODE_HEALTH_TIMEOUT` are
+    /// reported as unreachable
+    pub async fn cluster_health(&self) -> Result<api::ClusterHealth> {
+        let members = self.list_cluster_members().await?;
+        let nodes = join_all(members.iter().map(|member| async move {
+            let health = tokio::time::timeout(NODE_HEALTH_TIMEOUT, async {
+                let mut client = self
+                    .coordinator_client
+                    .get_coordinator(&member.coordinator_addr)
+                    .await?;
+                let response = client
+                    .get_node_health(indexify_coordinator::GetNodeHealthRequest {})
+                    .await?;
+                Ok::<_, anyhow::Error>(response.into_inner())
+            })
+            .await
+            .map_err(|_| anyhow!("timed out"))
+            .and_then(|health| health);
+            if let Err(e) = &health {
+                warn!(
+                    "unable to get the health of coordinator {}: {}",
+                    member.node_id, e
+                );
+            }
+            let health = health.ok();
+            api::CoordinatorNodeHealth {
+                node_id: member.node_id,
+                coordinator_addr: member.coordinator_addr.clone(),
+                voter: member.voter,
+                reachable: health.is_some(),
+                role: health.as_ref().map(|health| health.role.clone()),
+                applied_index: health.as_ref().map(|health| health.applied_index),
+                last_heartbeat_ms: health.as_ref().map(|health| health.last_heartbeat_ms),
+                executors: health.map(|health| health.executors).unwrap_or_default(),
+            }
+        }))
+        .await;
+        let leader = members
+            .iter()
+            .find(|member| member.leader)
+            .map(|member| member.node_id);
+        let voters = nodes.iter().filter(|node| node.voter).count();
+        let reachable_voters = nodes
+            .iter()
+            .filter(|node| node.voter && node.reachable)
+            .count();
+        Ok(api::ClusterHealth {
+            healthy: leader.is_some() && reachable_voters > voters / 2,
+            leader,
+            nodes,
+        })
+    }
+
+    /// Adds a node to the raft cluster of the coordinators as a learner,
+    /// returning once it has caught up with the log
+    pub async fn add_cluster_learner(
+        &self,
+        node_id: u64,
+        address: &str,
+        coordinator_addr: &str,
+    ) -> Result<Vec<indexify_coordinator::ClusterMember>> {
+        let req = indexify_coordinator::AddClusterLearnerRequest {
+            node_id,
+            address: address.to_string(),
+            coordinator_addr: coordinator_addr.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.add_cluster_learner(req).await
+            })
+            .await?
+            .into_inner();
+        Ok(response.members)
+    }
+
+    pub async fn promote_cluster_learner(
+        &self,
+        node_id: u64,
+    ) -> Result<Vec<indexify_coordinator::ClusterMember>> {
+        let response = self
+            .coordinator_client
+            .call_leader(
+                indexify_coordinator::PromoteClusterLearnerRequest { node_id },
+                |mut client, req| async move { client.promote_cluster_learner(req).await },
+            )
+            .await?
+            .into_inner();
+        Ok(response.members)
+    }
+
+    pub async fn remove_cluster_member(
+        &self,
+        node_id: u64,
+    ) -> Result<Vec<indexify_coordinator::ClusterMember>> {
+        let response = self
+            .coordinator_client
+            .call_leader(
+                indexify_coordinator::RemoveClusterMemberRequest { node_id },
+                |mut client, req| async move { client.remove_cluster_member(req).await },
+            )
+            .await?
+            .into_inner();
+        Ok(response.members)
+    }
+
+    pub async fn set_query_template(
+        &self,
+        namespace: &str,
+        query_template: &api::QueryTemplate,
+    ) -> Result<()> {
+        //  The scoring plugin is set separately and kept when the template is
+        // updated
+        let scoring_plugin = self
+            .get_query_template(namespace, &query_template.name)
+            .await
+            .ok()
+            .and_then(|existing| existing.scoring_plugin);
+        let req = indexify_coordinator::SetQueryTemplateRequest {
+            template: Some(indexify_coordinator::QueryTemplate {
+                namespace: namespace.to_string(),
+                name: query_template.name.clone(),
+                index: query_template.index.clone(),
+                query: query_template.query.clone(),
+                k: query_template.k.unwrap_or_default(),
+                filters: query_template.filters.clone(),
+                parameters: query_template.parameters.clone(),
+                scoring_plugin: scoring_plugin.map(|plugin| plugin.into()),
+            }),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.set_query_template(req).await
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_query_template(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<internal_api::QueryTemplate> {
+        let req = indexify_coordinator::GetQueryTemplateRequest {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        };
+        let query_template = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_query_template(req)
+            .await?
+            .into_inner()
+            .template
+            .ok_or(anyhow!("query template {} not found", name))?;
+        Ok(query_template.into())
+    }
+
+    pub async fn list_query_templates(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<internal_api::QueryTemplate>> {
+        let req = indexify_coordinator::ListQueryTemplatesRequest {
+            namespace: namespace.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_query_templates(req)
+            .await?
+            .into_inner();
+        Ok(response
+            .templates
+            .into_iter()
+            .map(|query_template| query_template.into())
+            .collect())
+    }
+
+    pub async fn delete_query_template(&self, namespace: &str, name: &str) -> Result<()> {
+        let scoring_plugin = self
+            .get_query_template(namespace, name)
+            .await
+            .ok()
+            .and_then(|query_template| query_template.scoring_plugin);
+        let req = indexify_coordinator::DeleteQueryTemplateRequest {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.delete_query_template(req).await
+            })
+            .await?;
+        if let Some(scoring_plugin) = scoring_plugin {
+            self.blob_storage.delete(&scoring_plugin.wasm_url).await?;
+        }
+        Ok(())
+    }
+
+    /// Validates the WASM module of the query template's scoring plugin,
+    /// stores it in the blob store and saves it with the template, replacing
+    /// the previous plugin.
+    pub async fn set_scoring_plugin(
+        &self,
+        namespace: &str,
+        name: &str,
+        wasm: Bytes,
+        max_fuel: Option<u64>,
+        max_memory_bytes: Option<u64>,
+    ) -> Result<internal_api::ScoringPlugin> {
+        self.validate_plugin(&wasm)?;
+        let mut query_template = self.get_query_template(namespace, name).await?;
+        let key = format!("{}_scoring_{}.wasm", name, nanoid!(8));
+        let stream = futures::stream::once(async { Ok(wasm) });
+        let res = self
+            .write_to_blob_store(namespace, &key, Box::pin(stream))
+            .await
+            .map_err(|e| anyhow!("unable to write plugin to blob store: {}", e))?;
+        let scoring_plugin = internal_api::ScoringPlugin {
+            wasm_url: res.url,
+            max_fuel: max_fuel.unwrap_or(DEFAULT_PLUGIN_MAX_FUEL),
+            max_memory_bytes: max_memory_bytes.unwrap_or(DEFAULT_PLUGIN_MAX_MEMORY_BYTES),
+        };
+        let previous = query_template
+            .scoring_plugin
+            .replace(scoring_plugin.clone());
+        let req = indexify_coordinator::SetQueryTemplateRequest {
+            template: Some(query_template.into()),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.set_query_template(req).await
+            })
+            .await?;
+        if let Some(previous) = previous {
+            if let Err(e) = self.blob_storage.delete(&previous.wasm_url).await {
+                error!(
+                    "unable to delete replaced scoring plugin of query template {}: {}",
+                    name, e
+                );
+            }
+        }
+        Ok(scoring_plugin)
+    }
+
+    pub async fn delete_scoring_plugin(&self, namespace: &str, name: &str) -> Result<()> {
+        let mut query_template = self.get_query_template(namespace, name).await?;
+        let scoring_plugin = query_template
+            .scoring_plugin
+            .take()
+            .ok_or(anyhow!("query template {} has no scoring plugin", name))?;
+        let req = indexify_coordinator::SetQueryTemplateRequest {
+            template: Some(query_template.into()),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.set_query_template(req).await
+            })
+            .await?;
+        self.blob_storage.delete(&scoring_plugin.wasm_url).await?;
+        Ok(())
+    }
+
+    pub fn validate_plugin(&self, wasm: &[u8]) -> Result<()> {
+        self.wasm_plugins.compile(wasm).map(|_| ())
+    }
+
+    //  Returns the compiled module of a plugin, fetching it from the blob store
+    // the first time it's used
+    async fn plugin_module(&self, wasm_url: &str) -> Result<Arc<wasmi::Module>> {
+        if let Some(module) = self.wasm_plugins.cached_module(wasm_url) {
+            return Ok(module);
+        }
+        let wasm = self.blob_storage.content_reader().bytes(wasm_url).await?;
+        self.wasm_plugins.cache_module(wasm_url, &wasm)
+    }
+
+    /// Validates the WASM module of the plugin, stores it in the blob store
+    /// and registers the plugin with the coordinator, replacing the plugin
+    /// with the same name.
+    pub async fn register_ingestion_plugin(
+        &self,
+        namespace: &str,
+        name: &str,
+        wasm: Bytes,
+        max_fuel: Option<u64>,
+        max_memory_bytes: Option<u64>,
+    ) -> Result<internal_api::IngestionPlugin> {
+        self.validate_plugin(&wasm)?;
+        let previous = self
+            .list_ingestion_plugins(namespace)
+            .await?
+            .into_iter()
+            .find(|plugin| plugin.name == name);
+        let key = format!("{}_{}.wasm", name, nanoid!(8));
+        let stream = futures::stream::once(async { Ok(wasm) });
+        let res = self
+            .write_to_blob_store(namespace, &key, Box::pin(stream))
+            .await
+            .map_err(|e| anyhow!("unable to write plugin to blob store: {}", e))?;
+        let plugin = internal_api::IngestionPlugin {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            wasm_url: res.url,
+            max_fuel: max_fuel.unwrap_or(DEFAULT_PLUGIN_MAX_FUEL),
+            max_memory_bytes: max_memory_bytes.unwrap_or(DEFAULT_PLUGIN_MAX_MEMORY_BYTES),
+        };
+        let req = indexify_coordinator::SetIngestionPluginRequest {
+            plugin: Some(plugin.clone().into()),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.set_ingestion_plugin(req).await
+            })
+            .await?;
+        if let Some(previous) = previous {
+            if let Err(e) = self.blob_storage.delete(&previous.wasm_url).await {
+                error!("unable to delete module of replaced plugin {}: {}", name, e);
+            }
+        }
+        Ok(plugin)
+    }
+
+    pub async fn list_ingestion_plugins(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<internal_api::IngestionPlugin>> {
+        let req = indexify_coordinator::ListIngestionPluginsRequest {
+            namespace: namespace.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_ingestion_plugins(req)
+            .await?
+            .into_inner();
+        Ok(response
+            .plugins
+            .into_iter()
+            .map(|plugin| plugin.into())
+            .collect())
+    }
+
+    pub async fn delete_ingestion_plugin(&self, namespace: &str, name: &str) -> Result<()> {
+        let req = indexify_coordinator::DeleteIngestionPluginRequest {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        };
+        let plugin = self
+            .coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.delete_ingestion_plugin(req).await
+            })
+            .await?
+            .into_inner()
+            .plugin
+            .ok_or(anyhow!("ingestion plugin {} not found", name))?;
+        self.blob_storage.delete(&plugin.wasm_url).await?;
+        Ok(())
+    }
+
+    /// Runs the plugins in order on the beginning of the content. Fails if a
+    /// plugin rejects the content, and adds the labels of plugins annotating
+    /// it to `labels`.
+    async fn run_ingestion_plugins(
+        &self,
+        plugins: Vec<internal_api::IngestionPlugin>,
+        namespace: &str,
+        file_name: &str,
+        content_type: &str,
+        labels: &mut HashMap<String, String>,
+        head: Bytes,
+    ) -> Result<()> {
+        for plugin in plugins {
+            let module = self.plugin_module(&plugin.wasm_url).await?;
+            let runtime = self.wasm_plugins.clone();
+            let namespace = namespace.to_string();
+            let file_name = file_name.to_string();
+            let content_type = content_type.to_string();
+            let input_labels = labels.clone();
+            let head = head.clone();
+            let name = plugin.name.clone();
+            let decision = tokio::task::spawn_blocking(move || {
+                let input = PluginInput {
+                    namespace: &namespace,
+                    file_name: &file_name,
+                    mime_type: &content_type,
+                    labels: &input_labels,
+                };
+                runtime.validate(&module, (&plugin).into(), &input, &head)
+            })
+            .await?
+            .map_err(|e| anyhow!("ingestion plugin {} failed: {}", name, e))?;
+            match decision {
+                PluginDecision::Accept => (),
+                PluginDecision::Reject { reason } => {
+                    return Err(anyhow!(
+                        "content rejected by ingestion plugin {}: {}",
+                        name,
+                        reason
+                    ));
+                }
+                PluginDecision::Annotate {
+                    labels: plugin_labels,
+                } => labels.extend(plugin_labels),
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the query of a saved template with the given parameters and
+    /// searches the template's index, only keeping the results whose labels
+    /// match the template's filters. The results are then rescored by the
+    /// template's scoring plugin, if it has one.
+    pub async fn run_query_template(
+        &self,
+        namespace: &str,
+        name: &str,
+        parameters: &HashMap<String, String>,
+        k: Option<u64>,
+    ) -> Result<Vec<ScoredText>> {
+        let query_template = self.get_query_template(namespace, name).await?;
+        let query = query_template.render(parameters)?;
+        let k = k.or(query_template.k).unwrap_or(DEFAULT_SEARCH_LIMIT);
+        let results = self
+            .search(namespace, &query_template.index, &query, k, None)
+            .await?;
+        let results: Vec<ScoredText> = results
+            .into_iter()
+            .filter(|result| {
+                query_template
+                    .filters
+                    .iter()
+                    .all(|(key, value)| result.labels.get(key) == Some(value))
+            })
+            .collect();
+        match query_template.scoring_plugin {
+            Some(scoring_plugin) => self
+                .run_scoring_plugin(&scoring_plugin, &query, results)
+                .await
+                .map_err(|e| anyhow!("scoring plugin of query template {} failed: {}", name, e)),
+            None => Ok(results),
+        }
+    }
+
+    async fn run_scoring_plugin(
+        &self,
+        scoring_plugin: &internal_api::ScoringPlugin,
+        query: &str,
+        results: Vec<ScoredText>,
+    ) -> Result<Vec<ScoredText>> {
+        if results.is_empty() {
+            return Ok(results);
+        }
+        let module = self.plugin_module(&scoring_plugin.wasm_url).await?;
+        let runtime = self.wasm_plugins.clone();
+        let limits: PluginLimits = scoring_plugin.into();
+        let query = query.to_string();
+        tokio::task::spawn_blocking(move || -> Result<Vec<ScoredText>> {
+            let candidates = results
+                .iter()
+                .map(|result| ScoringCandidate {
+                    content_id: &result.content_id,
+                    score: result.confidence_score,
+                    labels: &result.labels,
+                    mime_type: &result.mime_type,
+                    created_at: result.created_at,
+                    parent_id: &result.parent_id,
+                })
+                .collect();
+            let input = ScoringInput {
+                query: &query,
+                candidates,
+            };
+            let scores = runtime.score(&module, limits, &input)?;
+            Ok(apply_scores(results, scores))
+        })
+        .await?
+    }
+
+    async fn run_rebalance_job(
+        &self,
+        job: indexify_coordinator::RebalanceJob,
+        index: indexify_coordinator::Index,
+        content_ids: Vec<String>,
+        rebalance_req: api::RebalanceIndex,
+        snapshot: Option<Arc<IndexSnapshot>>,
+    ) {
+        let mut moved_vectors = 0;
+        let result = self
+            .move_vectors(
+                &job,
+                &index,
+                content_ids,
+                &rebalance_req,
+                snapshot.as_deref(),
+                &mut moved_vectors,
+            )
+            .await;
+        if let Err(e) = result {
+            error!("unable to rebalance index {}: {}", job.index, e);
+            if let Err(e) = self
+                .update_rebalance_job(&job, moved_vectors, false, &e.to_string())
+                .await
+            {
+                error!("unable to report failure of rebalancing job: {}", e);
+            }
+        }
+    }
+
+    async fn move_vectors(
+        &self,
+        job: &indexify_coordinator::RebalanceJob,
+        index: &indexify_coordinator::Index,
+        content_ids: Vec<String>,
+        rebalance_req: &api::RebalanceIndex,
+        snapshot: Option<&IndexSnapshot>,
+        moved_vectors: &mut u64,
+    ) -> Result<()> {
+        let batch_size = rebalance_req
+            .batch_size
+            .unwrap_or(DEFAULT_REBALANCE_BATCH_SIZE)
+            .max(1);
+        let keyword_index = is_keyword_schema(&index.schema);
+        let named_vectors = match keyword_index {
+            true => vec![],
+            false => {
+                serde_json::from_str::<internal_api::EmbeddingSchema>(&index.schema)?.named_vectors
+            }
+        };
+        let extractor = match job.reextract && !keyword_index {
+            true => Some(self.index_extractor(job, index).await?),
+            false => None,
+        };
+        let existing: HashSet<String> = match snapshot {
+            Some(_) => content_ids.iter().cloned().collect(),
+            None => HashSet::new(),
+        };
+        let mut not_found = Vec::new();
+        for batch in content_ids.chunks(batch_size) {
+            let started_at = Instant::now();
+            let copied: HashSet<String> = if keyword_index {
+                self.rebuild_keyword_batch(job, batch.to_vec()).await?
+            } else if let Some(extractor) = &extractor {
+                self.reextract_batch(job, index, extractor, batch.to_vec())
+                    .await?
+            } else if let Some(snapshot) = snapshot {
+                self.restore_batch(job, &named_vectors, snapshot, &existing, batch)
+                    .await?
+            } else {
+                self.copy_embeddings(job, &named_vectors, batch.to_vec())
+                    .await?
+                    .into_iter()
+                    .collect()
+            };
+            *moved_vectors += copied.len() as u64;
+            not_found.extend(batch.iter().filter(|id| !copied.contains(*id)).cloned());
+            self.update_rebalance_job(job, *moved_vectors, false, "")
+                .await?;
+
+            if let Some(max_vectors_per_second) = rebalance_req.max_vectors_per_second {
+                let batch_duration =
+                    Duration::from_secs_f64(batch.len() as f64 / max_vectors_per_second as f64);
+                if let Some(wait) = batch_duration.checked_sub(started_at.elapsed()) {
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        }
+
+        //  Tasks which were already running when the job started write to the
+        // source table, pick up what they wrote while the rest was copied.
+        // Keyword indexes and re-extracted content are read from the blob
+        // store, so there's nothing left to pick up.
+        if !keyword_index && extractor.is_none() {
+            for batch in not_found.chunks(batch_size) {
+                let copied = self
+                    .copy_embeddings(job, &named_vectors, batch.to_vec())
+                    .await?;
+                *moved_vectors += copied.len() as u64;
+            }
+        }
+        self.update_rebalance_job(job, *moved_vectors, true, "")
+            .await?;
+        self.move_embedding_expirations(&job.source_table, &job.target_table)
+            .await?;
+        for vector in &named_vectors {
+            self.vector_index_manager
+                .drop_index(&internal_api::EmbeddingSchema::named_vector_table(
+                    &job.source_table,
+                    vector,
+                ))
+                .await?;
+        }
+        self.vector_index_manager
+            .drop_index(&job.source_table)
+            .await
+    }
+
+    //  Expirations of the embeddings of the source table apply to the table
+    // they were moved to
+    async fn move_embedding_expirations(
+        &self,
+        source_table: &str,
+        target_table: &str,
+    ) -> Result<()> {
+        let mut client = self.coordinator_client.get().await?;
+        let expirations = client
+            .list_embedding_expirations(indexify_coordinator::ListEmbeddingExpirationsRequest {
+                expires_before: 0,
+            })
+            .await?
+            .into_inner()
+            .expirations
+            .into_iter()
+            .filter(|expiration| expiration.index_table == source_table)
+            .collect_vec();
+        if expirations.is_empty() {
+            return Ok(());
+        }
+        let moved = expirations
+            .iter()
+            .map(|expiration| indexify_coordinator::EmbeddingExpiration {
+                index_table: target_table.to_string(),
+                ..expiration.clone()
+            })
+            .collect();
+        client
+            .create_embedding_expirations(indexify_coordinator::CreateEmbeddingExpirationsRequest {
+                expirations: moved,
+            })
+            .await?;
+        client
+            .delete_embedding_expirations(indexify_coordinator::DeleteEmbeddingExpirationsRequest {
+                expirations,
+            })
+            .await?;
+        Ok(())
+    }
+
+    //  Copies the embeddings of the content and their named vectors from the
+    // source table of the job to the target table
+    async fn copy_embeddings(
+        &self,
+        job: &indexify_coordinator::RebalanceJob,
+        named_vectors: &[String],
+        content_ids: Vec<String>,
+    ) -> Result<Vec<String>> {
+        for vector in named_vectors {
+            self.vector_index_manager
+                .copy_embeddings(
+                    &internal_api::EmbeddingSchema::named_vector_table(&job.source_table, vector),
+                    &internal_api::EmbeddingSchema::named_vector_table(&job.target_table, vector),
+                    content_ids.clone(),
+                )
+                .await?;
+        }
+        self.vector_index_manager
+            .copy_embeddings(&job.source_table, &job.target_table, content_ids)
+            .await
+    }
+
+    //  Writes the embeddings the snapshot holds for the content to the target
+    // table and adds the chunk references of the embeddings shared with
+    // identical content. Returns the content which was restored, including
+    // content sharing an embedding restored under another content.
+    async fn restore_batch(
+        &self,
+        job: &indexify_coordinator::RebalanceJob,
+        named_vectors: &[String],
+        snapshot: &IndexSnapshot,
+        existing: &HashSet<String>,
+        content_ids: &[String],
+    ) -> Result<HashSet<String>> {
+        let mut restored = HashSet::new();
+        let mut chunks = Vec::new();
+        for content_id in content_ids {
+            let Some(chunk) = snapshot.chunk(content_id) else {
+                continue;
+            };
+            restored.insert(content_id.clone());
+            //  A shared embedding is restored once, with the first content
+            // which still exists
+            if chunk.restore_owner(existing) != Some(content_id) {
+                continue;
+            }
+            for vector in named_vectors {
+                if let Some(embedding) = chunk.vectors.get(vector) {
+                    self.vector_index_manager
+                        .add_embedding(
+                            &internal_api::EmbeddingSchema::named_vector_table(
+                                &job.target_table,
+                                vector,
+                            ),
+                            vec![internal_api::ExtractedEmbeddings {
+                                content_id: content_id.clone(),
+                                embedding: embedding.clone(),
+                                metadata: chunk.metadata.clone(),
+                            }],
+                        )
+                        .await?;
+                }
+            }
+            chunks.push(internal_api::ExtractedEmbeddings {
+                content_id: content_id.clone(),
+                embedding: chunk.embedding.clone(),
+                metadata: chunk.metadata.clone(),
+            });
+            if chunk.hash.is_empty() {
+                continue;
+            }
+            let mut client = self.coordinator_client.get().await?;
+            for id in chunk
+                .content_ids()
+                .into_iter()
+                .filter(|id| existing.contains(*id))
+            {
+                client
+                    .add_chunk_reference(indexify_coordinator::AddChunkReferenceRequest {
+                        index_table: job.target_table.clone(),
+                        hash: chunk.hash.clone(),
+                        content_id: id.clone(),
+                    })
+                    .await
+                    .map_err(|e| anyhow!("unable to add chunk reference: {}", e.to_string()))?;
+            }
+        }
+        self.vector_index_manager
+            .add_embedding(&job.target_table, chunks)
+            .await?;
+        Ok(restored)
+    }
+
+    async fn rebuild_keyword_batch(
+        &self,
+        job: &indexify_coordinator::RebalanceJob,
+        content_ids: Vec<String>,
+    ) -> Result<HashSet<String>> {
+        let mut written = HashSet::new();
+        for content_meta in self.content_metadata_batch(content_ids).await? {
+            if !content_meta.mime.starts_with("text/") {
+                continue;
+            }
+            self.vector_index_manager
+                .add_content_text(&job.target_table, &content_meta)
+                .await?;
+            written.insert(content_meta.id);
+        }
+        Ok(written)
+    }
+
+    //  Runs the extractor of the policy on the content again and writes the
+    // embeddings to the target table. Content sharing the embedding of
+    // identical content is skipped, its owner writes the embedding.
+    async fn reextract_batch(
+        &self,
+        job: &indexify_coordinator::RebalanceJob,
+        index: &indexify_coordinator::Index,
+        extractor: &IndexExtractor,
+        content_ids: Vec<String>,
+    ) -> Result<HashSet<String>> {
+        let mut written = HashSet::new();
+        for content_meta in self.content_metadata_batch(content_ids).await? {
+            if !self
+                .owns_embedding(&job.source_table, &content_meta)
+                .await?
+            {
+                continue;
+            }
+            let bytes = self
+                .blob_storage
+                .content_reader()
+                .verified_bytes(&content_meta.storage_url, &content_meta.hash)
+                .await?;
+            let content = api::Content {
+                content_type: content_meta.mime.clone(),
+                bytes: bytes.to_vec(),
+                features: vec![],
+                labels: content_meta.labels.clone(),
+            };
+            let embedding = self
+                .vector_index_manager
+                .extract_embedding(
+                    &extractor.name,
+                    content,
+                    extractor.input_params.clone(),
+                    &extractor.output,
+                )
+                .await
+                .map_err(|e| {
+                    anyhow!(
+                        "unable to extract embedding of content {} for index {}: {}",
+                        content_meta.id,
+                        index.name,
+                        e
+                    )
+                })?;
+            let Some(embedding) = embedding else {
+                continue;
+            };
+            if let Some(schema) = self.index_schema(&job.namespace, &job.target_table).await? {
+                EmbeddingDimensionMismatch::check(
+                    &extractor.name,
+                    &index.extraction_policy,
+                    &content_meta.id,
+                    &extractor.output,
+                    &index.name,
+                    schema.dim,
+                    embedding.values.len(),
+                )?;
+            }
+            let metadata = self
+                .metadata_index_manager
+                .get_metadata_for_content(&content_meta.namespace, &content_meta.id)
+                .await?;
+            let metadata = Self::combine_metadata(metadata, &[]);
+            self.vector_index_manager
+                .add_embedding(
+                    &job.target_table,
+                    vec![internal_api::ExtractedEmbeddings {
+                        content_id: content_meta.id.clone(),
+                        embedding: embedding.values,
+                        metadata: metadata.clone(),
+                    }],
+                )
+                .await?;
+            self.write_named_vectors(
+                &job.namespace,
+                &job.target_table,
+                &content_meta.id,
+                &embedding.vectors,
+                metadata,
+            )
+            .await?;
+            written.insert(content_meta.id);
+        }
+        Ok(written)
+    }
+
+    async fn index_extractor(
+        &self,
+        job: &indexify_coordinator::RebalanceJob,
+        index: &indexify_coordinator::Index,
+    ) -> Result<IndexExtractor> {
+        let policy = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_extraction_policies(indexify_coordinator::ListExtractionPoliciesRequest {
+                namespace: job.namespace.clone(),
+            })
+            .await?
+            .into_inner()
+            .policies
+            .into_iter()
+            .find(|policy| policy.name == index.extraction_policy)
+            .ok_or(anyhow!(
+                "extraction policy {} not found",
+                index.extraction_policy
+            ))?;
+        let input_params: serde_json::Value = match policy.input_params.as_str() {
+            "" => serde_json::Value::Null,
+            params => serde_json::from_str(params)?,
+        };
+        //  Index names are the name of the policy followed by the output
+        let output = index
+            .name
+            .strip_prefix(&format!("{}.", policy.name))
+            .unwrap_or(&index.name)
+            .to_string();
+        Ok(IndexExtractor {
+            name: policy.extractor,
+            input_params: (!input_params.is_null()).then_some(input_params),
+            output,
+        })
+    }
+
+    async fn content_metadata_batch(
+        &self,
+        content_ids: Vec<String>,
+    ) -> Result<Vec<indexify_coordinator::ContentMetadata>> {
+        let content_list = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_content_metadata(indexify_coordinator::GetContentMetadataRequest {
+                content_list: content_ids,
+                consistency: indexify_coordinator::ReadConsistency::Bounded as i32,
+            })
+            .await?
+            .into_inner()
+            .content_list;
+        Ok(content_list.into_values().collect())
+    }
+
+    async fn update_rebalance_job(
+        &self,
+        job: &indexify_coordinator::RebalanceJob,
+        moved_vectors: u64,
+        completed: bool,
+        error: &str,
+    ) -> Result<()> {
+        let req = indexify_coordinator::UpdateRebalanceJobRequest {
+            namespace: job.namespace.clone(),
+            index: job.index.clone(),
+            moved_vectors,
+            completed,
+            error: error.to_string(),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.update_rebalance_job(req).await
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn create_index_metadata(
+        &self,
+        namespace: &str,
+        index_name: &str,
+        table_name: &str,
+        schema: serde_json::Value,
+        extraction_policy: &str,
+        extractor: &str,
+    ) -> Result<()> {
+        let index = indexify_coordinator::CreateIndexRequest {
+            index: Some(indexify_coordinator::Index {
+                name: index_name.to_string(),
+                table_name: table_name.to_string(),
+                namespace: namespace.to_string(),
+                schema: serde_json::to_value(schema).unwrap().to_string(),
+                extraction_policy: extraction_policy.to_string(),
+                extractor: extractor.to_string(),
+            }),
+        };
+        let req = GrpcHelper::into_req(index);
+        let _resp = self
+            .coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.create_index(req).await
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_content(
+        &self,
+        namespace: &str,
+        source_filter: &str,
+        parent_id_filter: &str,
+        external_id_filter: &str,
+        labels_eq_filter: Option<&HashMap<String, String>>,
+        filter: &str,
+    ) -> Result<Vec<api::ContentMetadata>> {
+        let req = indexify_coordinator::ListContentRequest {
+            namespace: namespace.to_string(),
+            source: source_filter.to_string(),
+            parent_id: parent_id_filter.to_string(),
+            labels_eq: labels_eq_filter.unwrap_or(&HashMap::new()).clone(),
+            external_id: external_id_filter.to_string(),
+            filter: filter.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_content(req)
+            .await?;
+        let content_list = response
+            .into_inner()
+            .content_list
+            .into_iter()
+            .map(|c| c.into())
+            .collect_vec();
+        Ok(content_list)
+    }
+
+    pub async fn get_content_metadata_by_external_id(
+        &self,
+        namespace: &str,
+        external_id: &str,
+    ) -> Result<api::ContentMetadata> {
+        self.list_content(namespace, "", "", external_id, None, "")
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("content with external id {} not found", external_id))
+    }
+
+    #[tracing::instrument(skip(self, texts))]
+    pub async fn add_texts(
+        &self,
+        namespace: &str,
+        texts: Vec<api::Text>,
+        request_id: Option<&str>,
+    ) -> Result<()> {
+        for (i, text) in texts.into_iter().enumerate() {
+            let stream = futures::stream::once(async { Ok(Bytes::from(text.text)) });
+            let mut content_metadata = self
+                .write_content_bytes(
+                    namespace,
+                    Box::pin(stream),
+                    &text.labels,
+                    mime::TEXT_PLAIN.to_string(),
+                    None,
+                    None,
+                    "ingestion",
+                )
+                .await?;
+            content_metadata.external_id = text.external_id.unwrap_or_default();
+            content_metadata.expires_at = content_expiration(text.ttl_secs);
             let req = indexify_coordinator::CreateContentRequest {
                 content: Some(content_metadata),
+                request_id: coordinator_request_id(
+                    namespace,
+                    "add_texts",
+                    request_id,
+                    Some(&i.to_string()),
+                ),
             };
             self.coordinator_client
-                .get()
-                .await?
-                .create_content(GrpcHelper::into_req(req))
+                .call_leader(req, |mut client, req| async move {
+                    client.create_content(GrpcHelper::into_req(req)).await
+                })
                 .await
                 .map_err(|e| {
-                    anyhow!(
-                        "unable to write content metadata to coordinator {}",
-                        e.to_string()
-                    )
+                    coordinator_write_error("unable to write content metadata to coordinator", e)
                 })?;
         }
         Ok(())
     }
 
-    #[tracing::instrument]
-    pub async fn delete_content(&self, gc_task: &indexify_coordinator::GcTask) -> Result<()> {
-        //  Remove content from blob storage
-        self.blob_storage.delete(&gc_task.blob_store_path).await?;
+    #[tracing::instrument]
+    pub async fn delete_content(&self, gc_task: &indexify_coordinator::GcTask) -> Result<()> {
+        //  The content is only deleted from the coordinator after this task, so
+        // its hash can still be looked up, content which isn't found anymore
+        // isn't hashed.
+        let hash = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_content_metadata(indexify_coordinator::GetContentMetadataRequest {
+                content_list: vec![gc_task.content_id.clone()],
+                consistency: indexify_coordinator::ReadConsistency::Bounded as i32,
+            })
+            .await
+            .ok()
+            .and_then(|response| {
+                response
+                    .into_inner()
+                    .content_list
+                    .remove(&gc_task.content_id)
+            })
+            .map(|content| content.hash)
+            .unwrap_or_default();
+
+        //  Remove content from blob storage
+        self.delete_content_blob(
+            &gc_task.namespace,
+            &gc_task.content_id,
+            &hash,
+            &gc_task.blob_store_path,
+        )
+        .await?;
+
+        //  Remove features and embeddings from vector stores
+        for table in &gc_task.output_tables {
+            self.remove_embedding(&gc_task.namespace, table, &hash, &gc_task.content_id)
+                .await?;
+        }
+
+        //  Remove the metadata extracted from the content, and the metadata
+        // added to it through the API
+        self.metadata_index_manager
+            .delete_metadata_for_content(&gc_task.namespace, &gc_task.content_id)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn ingest_remote_file(
+        &self,
+        namespace: &str,
+        file: &str,
+        mime: &str,
+        labels: HashMap<String, String>,
+        external_id: Option<String>,
+        request_id: Option<&str>,
+    ) -> Result<String> {
+        if !(["https://", "http://", "s3://", "gs://", "file://"]
+            .iter()
+            .any(|s| file.starts_with(*s)))
+        {
+            return Err(anyhow!(
+                "invalid file path, must be a url, s3, gcs or file path"
+            ));
+        }
+        let _ = mime::Mime::from_str(mime).map_err(|e| anyhow!("invalid mime type {}", e))?;
+        let current_ts_secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs();
+        let id = nanoid!(16);
+        let content_metadata = indexify_coordinator::ContentMetadata {
+            id: id.clone(),
+            file_name: file.to_string(),
+            storage_url: file.to_string(),
+            parent_id: "".to_string(),
+            created_at: current_ts_secs as i64,
+            mime: mime.to_string(),
+            namespace: namespace.to_string(),
+            labels,
+            source: "ingestion".to_string(),
+            size_bytes: 0,
+            external_id: external_id.unwrap_or_default(),
+            ..Default::default()
+        };
+        let req: indexify_coordinator::CreateContentRequest =
+            indexify_coordinator::CreateContentRequest {
+                content: Some(content_metadata),
+                request_id: coordinator_request_id(
+                    namespace,
+                    "ingest_remote_file",
+                    request_id,
+                    None,
+                ),
+            };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.create_content(GrpcHelper::into_req(req)).await
+            })
+            .await
+            .map_err(|e| {
+                coordinator_write_error("unable to write content metadata to coordinator", e)
+            })?;
+        Ok(id)
+    }
+
+    pub async fn get_content_metadata(
+        &self,
+        namespace: &str,
+        content_ids: Vec<String>,
+    ) -> Result<Vec<api::ContentMetadata>> {
+        self.get_content_metadata_with_consistency(
+            namespace,
+            content_ids,
+            api::ReadConsistency::Bounded,
+        )
+        .await
+    }
+
+    /// Reads the content, a linearizable read reflects every write committed
+    /// before it even when the coordinator serving it lags behind the leader
+    pub async fn get_content_metadata_with_consistency(
+        &self,
+        _namespace: &str,
+        content_ids: Vec<String>,
+        consistency: api::ReadConsistency,
+    ) -> Result<Vec<api::ContentMetadata>> {
+        let req = indexify_coordinator::GetContentMetadataRequest {
+            content_list: content_ids,
+            consistency: indexify_coordinator::ReadConsistency::from(consistency) as i32,
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_content_metadata(req)
+            .await?;
+        let content_metadata_list = response.into_inner().content_list;
+        let mut content_list = Vec::new();
+        for c in content_metadata_list.values().cloned() {
+            content_list.push(c.into())
+        }
+        Ok(content_list)
+    }
+
+    pub async fn get_content_tree_metadata(
+        &self,
+        _namespace: &str,
+        content_id: String,
+    ) -> Result<Vec<api::ContentMetadata>> {
+        let req = indexify_coordinator::GetContentTreeMetadataRequest { content_id };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_content_tree_metadata(req)
+            .await?;
+        let content_list: Vec<api::ContentMetadata> = response
+            .into_inner()
+            .content_list
+            .into_iter()
+            .map(|content| content.into())
+            .collect();
+        Ok(content_list)
+    }
+
+    #[tracing::instrument(skip(self, data))]
+    pub async fn upload_file(
+        &self,
+        namespace: &str,
+        data: impl Stream<Item = Result<Bytes>> + Send + Unpin,
+        name: &str,
+        external_id: Option<String>,
+        ttl_secs: Option<u64>,
+        request_id: Option<&str>,
+    ) -> Result<()> {
+        let ext = Path::new(name)
+            .extension()
+            .unwrap_or_default()
+            .to_str()
+            .unwrap_or_default();
+        let content_mime = mime_guess::from_ext(ext).first_or_octet_stream();
+        let labels = HashMap::new();
+
+        let mut content_metadata = self
+            .write_content_bytes(
+                namespace,
+                data,
+                &labels,
+                content_mime.to_string(),
+                Some(name),
+                None,
+                "ingestion",
+            )
+            .await
+            .map_err(|e| anyhow!("unable to write content to blob store: {}", e))?;
+        content_metadata.external_id = external_id.unwrap_or_default();
+        content_metadata.expires_at = content_expiration(ttl_secs);
+        let req = indexify_coordinator::CreateContentRequest {
+            content: Some(content_metadata),
+            request_id: coordinator_request_id(namespace, "upload_file", request_id, Some(name)),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.create_content(GrpcHelper::into_req(req)).await
+            })
+            .await
+            .map_err(|e| {
+                coordinator_write_error("unable to write content metadata to coordinator", e)
+            })?;
+        Ok(())
+    }
+
+    pub fn make_file_name(file_name: Option<&str>) -> String {
+        file_name.map(|f| f.to_string()).unwrap_or(nanoid!())
+    }
 
-        //  Remove features and embeddings from vector stores
-        for table in &gc_task.output_tables {
-            self.vector_index_manager
-                .remove_embedding(table, &gc_task.content_id)
-                .await?;
+    /// Returns the first characters of text-like content, or an empty
+    /// string for other content. `bytes` is the beginning of the content,
+    /// at least `CONTENT_PREVIEW_BYTES` long unless the content is shorter.
+    pub fn make_preview(content_type: &str, bytes: &[u8]) -> String {
+        if !is_text_like(content_type) {
+            return "".to_string();
         }
+        let text = String::from_utf8_lossy(bytes);
+        let text = text.trim_start();
+        match text.char_indices().nth(CONTENT_PREVIEW_CHARS) {
+            Some((end, _)) => text[..end].trim_end().to_string(),
+            None => text.trim_end().to_string(),
+        }
+    }
 
-        //  Remove any metadata
-        self.metadata_index_manager
-            .remove_metadata(&gc_task.namespace, &gc_task.content_id)
-            .await?;
-
-        Ok(())
+    pub fn make_id(namespace: &str, file_name: &str, parent_id: &Option<String>) -> String {
+        let mut s = DefaultHasher::new();
+        namespace.hash(&mut s);
+        file_name.hash(&mut s);
+        if let Some(parent_id) = &parent_id {
+            parent_id.hash(&mut s);
+        }
+        format!("{:x}", s.finish())
     }
 
-    pub async fn ingest_remote_file(
+    async fn write_content_bytes(
         &self,
         namespace: &str,
-        file: &str,
-        mime: &str,
-        labels: HashMap<String, String>,
-    ) -> Result<String> {
-        if !(["https://", "http://", "s3://", "file://"]
-            .iter()
-            .any(|s| file.starts_with(*s)))
-        {
-            return Err(anyhow!("invalid file path, must be a url, s3 or file path"));
-        }
-        let _ = mime::Mime::from_str(mime).map_err(|e| anyhow!("invalid mime type {}", e))?;
+        data: impl Stream<Item = Result<Bytes>> + Send + Unpin,
+        labels: &HashMap<String, String>,
+        content_type: String,
+        file_name: Option<&str>,
+        parent_id: Option<String>,
+        source: &str,
+    ) -> Result<indexify_coordinator::ContentMetadata> {
         let current_ts_secs = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)?
             .as_secs();
-        let id = nanoid!(16);
-        let content_metadata = indexify_coordinator::ContentMetadata {
-            id: id.clone(),
-            file_name: file.to_string(),
-            storage_url: file.to_string(),
-            parent_id: "".to_string(),
+        let file_name = DataManager::make_file_name(file_name);
+
+        let id = DataManager::make_id(namespace, &file_name, &parent_id);
+
+        //  Uploaded content goes through the plugins of the namespace before
+        // anything is written, the bytes they inspect are put back in front of
+        // the stream
+        let mut labels = labels.clone();
+        let mut head_chunks = Vec::new();
+        let mut data = data;
+        if source == "ingestion" {
+            let plugins = self.list_ingestion_plugins(namespace).await?;
+            if !plugins.is_empty() {
+                let mut head_len = 0;
+                while head_len < INGESTION_PLUGIN_INSPECT_BYTES {
+                    match data.next().await {
+                        Some(chunk) => {
+                            let chunk = chunk?;
+                            head_len += chunk.len();
+                            head_chunks.push(chunk);
+                        }
+                        None => break,
+                    }
+                }
+                let mut head = head_chunks.concat();
+                head.truncate(INGESTION_PLUGIN_INSPECT_BYTES);
+                self.run_ingestion_plugins(
+                    plugins,
+                    namespace,
+                    &file_name,
+                    &content_type,
+                    &mut labels,
+                    head.into(),
+                )
+                .await?;
+            }
+        }
+        let data = futures::stream::iter(head_chunks.into_iter().map(Ok)).chain(data);
+
+        //  Keep the beginning of the content while it's streamed to the blob
+        // store to generate the preview from, and hash and count all of it
+        let preview_bytes = Arc::new(Mutex::new(Vec::new()));
+        let hasher = Arc::new(Mutex::new(Sha256::new()));
+        let size_bytes = Arc::new(Mutex::new(0u64));
+        let data = data.inspect({
+            let preview_bytes = preview_bytes.clone();
+            let hasher = hasher.clone();
+            let size_bytes = size_bytes.clone();
+            move |chunk| {
+                if let Ok(chunk) = chunk {
+                    hasher.lock().unwrap().update(chunk);
+                    *size_bytes.lock().unwrap() += chunk.len() as u64;
+                    let mut preview_bytes = preview_bytes.lock().unwrap();
+                    let remaining = CONTENT_PREVIEW_BYTES.saturating_sub(preview_bytes.len());
+                    preview_bytes.extend_from_slice(&chunk[..remaining.min(chunk.len())]);
+                }
+            }
+        });
+        self.create_ingest_intent(
+            namespace,
+            &id,
+            &self
+                .blob_storage
+                .url_in_namespace(namespace, &namespace_key(namespace, &file_name)),
+        )
+        .await?;
+        //  The checksum, preview and size of the content are those of its
+        // uncompressed bytes
+        let compression_level = self.blob_storage.compression_level(namespace);
+        let data = match compression_level {
+            Some(level) => compression::compress(data.boxed(), level),
+            None => data.boxed(),
+        };
+        self.write_to_blob_store(namespace, &file_name, data)
+            .await
+            .map_err(|e| anyhow!("unable to write text to blob store: {}", e))?;
+        let preview = DataManager::make_preview(&content_type, &preview_bytes.lock().unwrap());
+        let hash = DataManager::content_hash(hasher.lock().unwrap().clone());
+        let storage_url = self
+            .store_content_addressed(
+                namespace,
+                &id,
+                &file_name,
+                &hash,
+                compression_level.is_some(),
+            )
+            .await?;
+        let size_bytes = *size_bytes.lock().unwrap();
+
+        Ok(indexify_coordinator::ContentMetadata {
+            id,
+            file_name,
+            storage_url,
+            parent_id: parent_id.unwrap_or_default(),
             created_at: current_ts_secs as i64,
-            mime: mime.to_string(),
+            mime: content_type,
             namespace: namespace.to_string(),
             labels,
-            source: "ingestion".to_string(),
-            size_bytes: 0,
-            ..Default::default()
+            source: source.to_string(),
+            size_bytes,
+            extraction_policy_ids: HashMap::new(),
+            external_id: "".to_string(),
+            preview,
+            hash,
+            expires_at: 0,
+        })
+    }
+
+    pub fn content_hash(hasher: Sha256) -> String {
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Moves the blob of the content, written under its file name, to the key
+    /// derived from the hash of its bytes, and adds the content to the
+    /// references of the blob. The file name of the content maps to the blob
+    /// through its storage url. Returns the url of the blob.
+    pub async fn store_content_addressed(
+        &self,
+        namespace: &str,
+        content_id: &str,
+        file_name: &str,
+        hash: &str,
+        compressed: bool,
+    ) -> Result<String> {
+        let mut key = content_addressed_key(namespace, hash);
+        if compressed {
+            key = compression::compressed_key(&key);
+        }
+        let url = self
+            .blob_storage
+            .rename_in_namespace(namespace, &namespace_key(namespace, file_name), &key)
+            .await
+            .map_err(|e| anyhow!("unable to move content to its hash: {}", e))?;
+        let req = indexify_coordinator::AddChunkReferenceRequest {
+            index_table: blob_reference_table(namespace),
+            hash: blob_reference_hash(hash, &url),
+            content_id: content_id.to_string(),
         };
-        let req: indexify_coordinator::CreateContentRequest =
-            indexify_coordinator::CreateContentRequest {
-                content: Some(content_metadata),
-            };
         self.coordinator_client
-            .get()
+            .call_leader(req, |mut client, req| async move {
+                client.add_chunk_reference(req).await
+            })
+            .await
+            .map_err(|e| anyhow!("unable to add blob reference: {}", e.to_string()))?;
+        Ok(url)
+    }
+
+    //  Blobs stored under their hash are shared by the content with the same
+    // bytes, they're only deleted with the last content referencing them.
+    // Blobs written under the file name of their content aren't shared.
+    async fn delete_content_blob(
+        &self,
+        namespace: &str,
+        content_id: &str,
+        hash: &str,
+        url: &str,
+    ) -> Result<()> {
+        let key = url.trim_end_matches(compression::COMPRESSED_BLOB_SUFFIX);
+        if hash.is_empty() || !key.ends_with(&content_addressed_key(namespace, hash)) {
+            return self.blob_storage.delete(url).await;
+        }
+        let index_table = blob_reference_table(namespace);
+        let hash = blob_reference_hash(hash, url);
+        let req = indexify_coordinator::RemoveChunkReferenceRequest {
+            index_table: index_table.clone(),
+            hash: hash.clone(),
+            content_id: content_id.to_string(),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.remove_chunk_reference(req).await
+            })
+            .await
+            .map_err(|e| anyhow!("unable to remove blob reference: {}", e.to_string()))?;
+        let shared = self
+            .get_chunk_reference(&index_table, &hash)
             .await?
-            .create_content(GrpcHelper::into_req(req))
+            .is_some_and(|reference| !reference.content_ids.is_empty());
+        if shared {
+            return Ok(());
+        }
+        self.blob_storage.delete(url).await
+    }
+
+    /// Records in the coordinator that the blob of the content is being
+    /// written, so it can be cleaned up by any server if the content is never
+    /// created.
+    pub async fn create_ingest_intent(
+        &self,
+        namespace: &str,
+        content_id: &str,
+        storage_url: &str,
+    ) -> Result<()> {
+        let intent = internal_api::IngestIntent {
+            content_id: content_id.to_string(),
+            namespace: namespace.to_string(),
+            storage_url: storage_url.to_string(),
+            ingestion_server_id: self.ingestion_server_id.clone(),
+            created_at: timestamp_secs(),
+        };
+        let req = indexify_coordinator::CreateIngestIntentRequest {
+            intent: Some(intent.into()),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.create_ingest_intent(req).await
+            })
             .await
-            .map_err(|e| {
-                anyhow!(
-                    "unable to write content metadata to coordinator {}",
-                    e.to_string()
-                )
-            })?;
-        Ok(id)
+            .map_err(|e| anyhow!("unable to create ingest intent: {}", e.to_string()))?;
+        Ok(())
     }
 
-    pub async fn get_content_metadata(
+    /// Deletes the blobs of uploads which started more than `timeout` ago
+    /// without their content being created, whichever server started them.
+    /// Returns the number of uploads cleaned up.
+    pub async fn cleanup_abandoned_ingests(&self, timeout: Duration) -> Result<usize> {
+        let req = indexify_coordinator::ListIngestIntentsRequest {
+            created_before: timestamp_secs().saturating_sub(timeout.as_secs()),
+        };
+        let intents = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_ingest_intents(req)
+            .await?
+            .into_inner()
+            .intents;
+        if intents.is_empty() {
+            return Ok(0);
+        }
+        let mut content_ids = Vec::new();
+        for intent in intents {
+            info!(
+                "deleting abandoned upload of content {} started by server {}",
+                intent.content_id, intent.ingestion_server_id
+            );
+            if let Err(e) = self.blob_storage.delete(&intent.storage_url).await {
+                error!(
+                    "unable to delete abandoned upload {}: {}",
+                    intent.storage_url, e
+                );
+                continue;
+            }
+            content_ids.push(intent.content_id);
+        }
+        let cleaned_up = content_ids.len();
+        self.coordinator_client
+            .call_leader(
+                indexify_coordinator::DeleteIngestIntentsRequest { content_ids },
+                |mut client, req| async move { client.delete_ingest_intents(req).await },
+            )
+            .await?;
+        Ok(cleaned_up)
+    }
+
+    /// Finds the blobs of content in the namespace which no content is stored
+    /// in, left behind by ingests which failed after their blob was moved to
+    /// the hash of its bytes, and deletes them if `delete` is set. Blobs
+    /// written less than `min_age` ago are skipped, their content may still
+    /// be created. Returns the orphaned blobs.
+    pub async fn reconcile_orphaned_blobs(
         &self,
-        _namespace: &str,
-        content_ids: Vec<String>,
-    ) -> Result<Vec<api::ContentMetadata>> {
-        let req = indexify_coordinator::GetContentMetadataRequest {
-            content_list: content_ids,
+        namespace: &str,
+        min_age: Duration,
+        delete: bool,
+    ) -> Result<Vec<BlobMeta>> {
+        let modified_before = timestamp_secs().saturating_sub(min_age.as_secs());
+        let blobs = self.blob_storage.list_namespace(namespace).await?;
+        //  Snapshots, exports and plugins are stored under the namespace too,
+        // only blobs at content addressed keys are blobs of content
+        let blobs = blobs
+            .into_iter()
+            .filter(|blob| blob.last_modified < modified_before)
+            .filter(|blob| content_addressed_hash(namespace, &blob.key).is_some())
+            .collect_vec();
+        if blobs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let index_table = blob_reference_table(namespace);
+        let mut candidates = Vec::new();
+        for blob in blobs {
+            let hash = content_addressed_hash(namespace, &blob.key).unwrap_or_default();
+            let reference_hash = blob_reference_hash(hash, &blob.url);
+            let content_ids = self
+                .get_chunk_reference(&index_table, &reference_hash)
+                .await?
+                .map(|reference| reference.content_ids)
+                .unwrap_or_default();
+            candidates.push((blob, reference_hash, content_ids));
+        }
+
+        //  Listed after the references of the blobs, so content referencing a
+        // blob either has an ingest intent or was created by the time the
+        // content of the namespace is listed
+        let intents = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_ingest_intents(indexify_coordinator::ListIngestIntentsRequest {
+                created_before: 0,
+            })
+            .await?
+            .into_inner()
+            .intents;
+        let content_list = self.list_content(namespace, "", "", "", None, "").await?;
+        let storage_urls: HashSet<&str> = content_list
+            .iter()
+            .map(|content| content.storage_url.as_str())
+            .collect();
+        let live_content_ids: HashSet<&str> = content_list
+            .iter()
+            .map(|content| content.id.as_str())
+            .chain(intents.iter().map(|intent| intent.content_id.as_str()))
+            .collect();
+
+        let mut orphans = Vec::new();
+        for (blob, reference_hash, content_ids) in candidates {
+            if storage_urls.contains(blob.url.as_str()) ||
+                content_ids
+                    .iter()
+                    .any(|content_id| live_content_ids.contains(content_id.as_str()))
+            {
+                continue;
+            }
+            if !delete {
+                warn!(
+                    "found orphaned blob {} of namespace {}",
+                    blob.url, namespace
+                );
+                orphans.push(blob);
+                continue;
+            }
+            info!(
+                "deleting orphaned blob {} of namespace {}",
+                blob.url, namespace
+            );
+            if let Err(e) = self.blob_storage.delete(&blob.url).await {
+                error!("unable to delete orphaned blob {}: {}", blob.url, e);
+                continue;
+            }
+            //  The content which never got created would otherwise keep a
+            // blob stored under the same hash later from being deleted
+            for content_id in content_ids {
+                let req = indexify_coordinator::RemoveChunkReferenceRequest {
+                    index_table: index_table.clone(),
+                    hash: reference_hash.clone(),
+                    content_id,
+                };
+                self.coordinator_client
+                    .call_leader(req, |mut client, req| async move {
+                        client.remove_chunk_reference(req).await
+                    })
+                    .await
+                    .map_err(|e| anyhow!("unable to remove blob reference: {}", e.to_string()))?;
+            }
+            orphans.push(blob);
+        }
+        Ok(orphans)
+    }
+
+    //  Embeddings whose content or index has a TTL are recorded in the
+    // coordinator with their expiration, to be removed by the sweeper
+    async fn record_embedding_expiration(
+        &self,
+        index_table: &str,
+        content_meta: &indexify_coordinator::ContentMetadata,
+    ) -> Result<()> {
+        let ttl_secs = self
+            .index_schema(&content_meta.namespace, index_table)
+            .await?
+            .and_then(|schema| schema.ttl_secs);
+        let Some(expires_at) = internal_api::EmbeddingExpiration::expires_at(
+            content_meta.expires_at,
+            ttl_secs,
+            timestamp_secs(),
+        ) else {
+            return Ok(());
         };
-        let response = self
+        let expiration = internal_api::EmbeddingExpiration {
+            namespace: content_meta.namespace.clone(),
+            index_table: index_table.to_string(),
+            content_id: content_meta.id.clone(),
+            hash: content_meta.hash.clone(),
+            expires_at,
+        };
+        self.coordinator_client
+            .call_leader(
+                indexify_coordinator::CreateEmbeddingExpirationsRequest {
+                    expirations: vec![expiration.into()],
+                },
+                |mut client, req| async move { client.create_embedding_expirations(req).await },
+            )
+            .await
+            .map_err(|e| anyhow!("unable to record embedding expiration: {}", e.to_string()))?;
+        Ok(())
+    }
+
+    /// Removes the embeddings which expired from their indexes, whichever
+    /// server wrote them. Returns the number of embeddings removed.
+    pub async fn remove_expired_embeddings(&self) -> Result<usize> {
+        let req = indexify_coordinator::ListEmbeddingExpirationsRequest {
+            expires_before: timestamp_secs(),
+        };
+        let expirations = self
             .coordinator_client
             .get()
             .await?
-            .get_content_metadata(req)
+            .list_embedding_expirations(req)
+            .await?
+            .into_inner()
+            .expirations;
+        if expirations.is_empty() {
+            return Ok(0);
+        }
+        let mut removed = Vec::new();
+        for expiration in expirations {
+            if let Err(e) = self
+                .remove_embedding(
+                    &expiration.namespace,
+                    &expiration.index_table,
+                    &expiration.hash,
+                    &expiration.content_id,
+                )
+                .await
+            {
+                error!(
+                    "unable to remove expired embedding of content {} from {}: {}",
+                    expiration.content_id, expiration.index_table, e
+                );
+                continue;
+            }
+            removed.push(expiration);
+        }
+        let removed_count = removed.len();
+        self.coordinator_client
+            .call_leader(
+                indexify_coordinator::DeleteEmbeddingExpirationsRequest {
+                    expirations: removed,
+                },
+                |mut client, req| async move { client.delete_embedding_expirations(req).await },
+            )
             .await?;
-        let content_metadata_list = response.into_inner().content_list;
-        let mut content_list = Vec::new();
-        for c in content_metadata_list.values().cloned() {
-            content_list.push(c.into())
+        Ok(removed_count)
+    }
+
+    /// Purges the metadata written longer ago than the retention of its
+    /// namespace, whichever server wrote it. Returns the number of rows
+    /// purged.
+    pub async fn purge_expired_metadata(
+        &self,
+        retention_secs: &HashMap<String, u64>,
+    ) -> Result<u64> {
+        let mut purged = 0;
+        for (namespace, retention_secs) in retention_secs {
+            let created_before = timestamp_secs().saturating_sub(*retention_secs);
+            match self
+                .metadata_index_manager
+                .purge_metadata(namespace, created_before)
+                .await
+            {
+                Ok(count) => purged += count,
+                Err(e) => error!("unable to purge metadata of namespace {}: {}", namespace, e),
+            }
         }
-        Ok(content_list)
+        Ok(purged)
+    }
+
+    pub async fn finish_extracted_content_write(
+        &self,
+        begin_ingest: BeginExtractedContentIngest,
+    ) -> Result<()> {
+        let outcome: indexify_coordinator::TaskOutcome = begin_ingest.task_outcome.into();
+
+        let req = indexify_coordinator::UpdateTaskRequest {
+            executor_id: begin_ingest.executor_id,
+            task_id: begin_ingest.task_id,
+            outcome: outcome as i32,
+            content_list: Vec::new(),
+            content_id: begin_ingest.parent_content_id.clone(),
+            extraction_policy_name: begin_ingest.extraction_policy.clone(),
+            request_id: coordinator_request_id(
+                &begin_ingest.namespace,
+                "finish_extracted_content",
+                begin_ingest.request_id.as_deref(),
+                None,
+            ),
+        };
+        let res = self.coordinator_client.get().await?.update_task(req).await;
+        if let Err(err) = res {
+            error!("unable to update task: {}", err.to_string());
+        }
+        if let Err(err) = self
+            .record_processing_sla(
+                &begin_ingest.namespace,
+                &begin_ingest.extraction_policy,
+                &begin_ingest.parent_content_id,
+                outcome,
+            )
+            .await
+        {
+            error!("unable to record processing sla: {}", err.to_string());
+        }
+        Ok(())
+    }
+
+    /// Sets the processing sla of an extraction policy, replacing its current
+    /// sla.
+    pub async fn set_processing_sla(
+        &self,
+        sla: internal_api::ProcessingSla,
+    ) -> Result<internal_api::ProcessingSla> {
+        let req = indexify_coordinator::SetProcessingSlaRequest {
+            sla: Some(sla.clone().into()),
+        };
+        let sla = self
+            .coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.set_processing_sla(req).await
+            })
+            .await?
+            .into_inner()
+            .sla
+            .ok_or_else(|| anyhow!("coordinator didn't return the processing sla"))?;
+        self.processing_slas.write().unwrap().remove(&sla.namespace);
+        Ok(sla.into())
     }
 
-    pub async fn get_content_tree_metadata(
+    pub async fn list_processing_slas(
         &self,
-        _namespace: &str,
-        content_id: String,
-    ) -> Result<Vec<api::ContentMetadata>> {
-        let req = indexify_coordinator::GetContentTreeMetadataRequest { content_id };
+        namespace: &str,
+    ) -> Result<Vec<internal_api::ProcessingSla>> {
+        let req = indexify_coordinator::ListProcessingSlasRequest {
+            namespace: namespace.to_string(),
+        };
         let response = self
             .coordinator_client
             .get()
             .await?
-            .get_content_tree_metadata(req)
-            .await?;
-        let content_list: Vec<api::ContentMetadata> = response
-            .into_inner()
-            .content_list
-            .into_iter()
-            .map(|content| content.into())
-            .collect();
-        Ok(content_list)
+            .list_processing_slas(req)
+            .await?
+            .into_inner();
+        Ok(response.slas.into_iter().map(|sla| sla.into()).collect())
     }
 
-    #[tracing::instrument(skip(self, data))]
-    pub async fn upload_file(
+    pub async fn delete_processing_sla(
         &self,
         namespace: &str,
-        data: impl Stream<Item = Result<Bytes>> + Send + Unpin,
-        name: &str,
+        extraction_policy: &str,
     ) -> Result<()> {
-        let ext = Path::new(name)
-            .extension()
-            .unwrap_or_default()
-            .to_str()
-            .unwrap_or_default();
-        let content_mime = mime_guess::from_ext(ext).first_or_octet_stream();
-        let labels = HashMap::new();
-
-        let content_metadata = self
-            .write_content_bytes(
-                namespace,
-                data,
-                &labels,
-                content_mime.to_string(),
-                Some(name),
-                None,
-                "ingestion",
-            )
-            .await
-            .map_err(|e| anyhow!("unable to write content to blob store: {}", e))?;
-        let req = indexify_coordinator::CreateContentRequest {
-            content: Some(content_metadata),
+        let req = indexify_coordinator::DeleteProcessingSlaRequest {
+            namespace: namespace.to_string(),
+            extraction_policy: extraction_policy.to_string(),
         };
         self.coordinator_client
-            .get()
-            .await?
-            .create_content(GrpcHelper::into_req(req))
-            .await
-            .map_err(|e| {
-                anyhow!(
-                    "unable to write content metadata to coordinator {}",
-                    e.to_string()
-                )
-            })?;
+            .call_leader(req, |mut client, req| async move {
+                client.delete_processing_sla(req).await
+            })
+            .await?;
+        self.processing_slas.write().unwrap().remove(namespace);
+        self.sla_tracker.remove(
+            &internal_api::ProcessingSla {
+                namespace: namespace.to_string(),
+                extraction_policy: extraction_policy.to_string(),
+                ..Default::default()
+            }
+            .id(),
+        );
         Ok(())
     }
 
-    pub fn make_file_name(file_name: Option<&str>) -> String {
-        file_name.map(|f| f.to_string()).unwrap_or(nanoid!())
+    /// Returns the processing slas of the namespace with their attainment
+    /// over the tasks written by this server.
+    pub async fn processing_sla_attainment(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<(internal_api::ProcessingSla, SlaAttainment)>> {
+        let now = timestamp_secs();
+        Ok(self
+            .list_processing_slas(namespace)
+            .await?
+            .into_iter()
+            .map(|sla| {
+                let attainment = self.sla_tracker.attainment(&sla, now);
+                (sla, attainment)
+            })
+            .collect())
     }
 
-    pub fn make_id(namespace: &str, file_name: &str, parent_id: &Option<String>) -> String {
-        let mut s = DefaultHasher::new();
-        namespace.hash(&mut s);
-        file_name.hash(&mut s);
-        if let Some(parent_id) = &parent_id {
-            parent_id.hash(&mut s);
+    async fn cached_processing_slas(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<internal_api::ProcessingSla>> {
+        if let Some((fetched_at, slas)) = self.processing_slas.read().unwrap().get(namespace) {
+            if fetched_at.elapsed() < PROCESSING_SLA_CACHE_TTL {
+                return Ok(slas.clone());
+            }
         }
-        format!("{:x}", s.finish())
+        let slas = self.list_processing_slas(namespace).await?;
+        self.processing_slas
+            .write()
+            .unwrap()
+            .insert(namespace.to_string(), (Instant::now(), slas.clone()));
+        Ok(slas)
     }
 
-    async fn write_content_bytes(
+    //  Extracted content counts from the time the content it was extracted
+    // from was ingested
+    async fn ingested_at(&self, content_id: &str) -> Result<u64> {
+        let mut content_id = content_id.to_string();
+        for _ in 0..MAX_CONTENT_TREE_DEPTH {
+            let req = indexify_coordinator::GetContentMetadataRequest {
+                content_list: vec![content_id.clone()],
+                consistency: indexify_coordinator::ReadConsistency::Bounded as i32,
+            };
+            let content = self
+                .coordinator_client
+                .get()
+                .await?
+                .get_content_metadata(req)
+                .await?
+                .into_inner()
+                .content_list
+                .remove(&content_id)
+                .ok_or_else(|| anyhow!("content {} not found", content_id))?;
+            if content.parent_id.is_empty() {
+                return Ok(content.created_at.max(0) as u64);
+            }
+            content_id = content.parent_id;
+        }
+        Err(anyhow!(
+            "content {} is nested more than {} levels deep",
+            content_id,
+            MAX_CONTENT_TREE_DEPTH
+        ))
+    }
+
+    async fn record_processing_sla(
         &self,
         namespace: &str,
-        data: impl Stream<Item = Result<Bytes>> + Send + Unpin,
-        labels: &HashMap<String, String>,
-        content_type: String,
-        file_name: Option<&str>,
-        parent_id: Option<String>,
-        source: &str,
-    ) -> Result<indexify_coordinator::ContentMetadata> {
-        let current_ts_secs = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)?
-            .as_secs();
-        let file_name = DataManager::make_file_name(file_name);
-
-        let id = DataManager::make_id(namespace, &file_name, &parent_id);
-
-        let res = self
-            .write_to_blob_store(namespace, &file_name, data)
-            .await
-            .map_err(|e| anyhow!("unable to write text to blob store: {}", e))?;
-
-        let labels = labels
-            .clone()
+        extraction_policy_id: &str,
+        content_id: &str,
+        outcome: indexify_coordinator::TaskOutcome,
+    ) -> Result<()> {
+        let sla = match self
+            .cached_processing_slas(namespace)
+            .await?
             .into_iter()
-            .map(|(k, v)| (k, v.to_string()))
-            .collect();
-        Ok(indexify_coordinator::ContentMetadata {
-            id,
-            file_name,
-            storage_url: res.url,
-            parent_id: parent_id.unwrap_or_default(),
-            created_at: current_ts_secs as i64,
-            mime: content_type,
+            .find(|sla| sla.extraction_policy_id == extraction_policy_id)
+        {
+            Some(sla) => sla,
+            None => return Ok(()),
+        };
+        let now = timestamp_secs();
+        let latency_secs = match outcome {
+            indexify_coordinator::TaskOutcome::Success => {
+                Some(now.saturating_sub(self.ingested_at(content_id).await?))
+            }
+            indexify_coordinator::TaskOutcome::Failed => None,
+            indexify_coordinator::TaskOutcome::Unknown => return Ok(()),
+        };
+        let alert = match self.sla_tracker.record(&sla, latency_secs, now) {
+            Some(alert) => alert,
+            None => return Ok(()),
+        };
+        info!(
+            "processing sla of extraction policy {} {:?} at attainment {:.3}",
+            sla.extraction_policy, alert.event, alert.attainment
+        );
+        if sla.webhook_url.is_empty() {
+            return Ok(());
+        }
+        let client = self.sla_webhook_client.clone();
+        tokio::spawn(async move {
+            if let Err(err) = processing_slas::send_alert(&client, &sla.webhook_url, &alert).await {
+                error!("{}", err.to_string());
+            }
+        });
+        Ok(())
+    }
+
+    //  Index schemas never change once the index is created, so they are
+    // cached to avoid asking the coordinator on every write.
+    async fn index_schema(
+        &self,
+        namespace: &str,
+        index_table: &str,
+    ) -> Result<Option<internal_api::EmbeddingSchema>> {
+        if let Some(schema) = self.index_schemas.read().unwrap().get(index_table) {
+            return Ok(Some(schema.clone()));
+        }
+        let req = indexify_coordinator::ListIndexesRequest {
             namespace: namespace.to_string(),
-            labels,
-            source: source.to_string(),
-            size_bytes: res.size_bytes,
-            extraction_policy_ids: HashMap::new(),
-        })
+        };
+        let indexes = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_indexes(req)
+            .await?
+            .into_inner()
+            .indexes;
+        let mut index_schemas = self.index_schemas.write().unwrap();
+        for index in indexes {
+            if let Ok(schema) = serde_json::from_str::<internal_api::EmbeddingSchema>(&index.schema)
+            {
+                index_schemas.insert(index.table_name, schema);
+            }
+        }
+        Ok(index_schemas.get(index_table).cloned())
     }
 
-    pub async fn finish_extracted_content_write(
+    /// Infers the columns of extracted metadata and evolves the schema of the
+    /// content source with the columns it doesn't have yet. Values which
+    /// don't fit the type of their column are recorded as conflicts.
+    async fn evolve_structured_data_schema(
         &self,
-        begin_ingest: BeginExtractedContentIngest,
+        namespace: &str,
+        content_source: &str,
+        metadata: &serde_json::Value,
     ) -> Result<()> {
-        let outcome: indexify_coordinator::TaskOutcome = begin_ingest.task_outcome.into();
+        let schema = self
+            .structured_data_schema(namespace, content_source)
+            .await?;
+        let inference = schema.infer(metadata);
+        if inference.is_empty() {
+            return Ok(());
+        }
+        for (column, dtype) in &inference.conflicts {
+            warn!(
+                "metadata of type {:?} written to column {} of content source {} in namespace {}, expected {:?}",
+                dtype, column, content_source, namespace, schema.columns[column]
+            );
+        }
+        let schema = self
+            .coordinator_client
+            .evolve_structured_data_schema(namespace, content_source, &inference)
+            .await?;
+        self.structured_data_schemas
+            .write()
+            .unwrap()
+            .insert(schema.id.clone(), schema);
+        Ok(())
+    }
 
-        let req = indexify_coordinator::UpdateTaskRequest {
-            executor_id: begin_ingest.executor_id,
-            task_id: begin_ingest.task_id,
-            outcome: outcome as i32,
-            content_list: Vec::new(),
-            content_id: begin_ingest.parent_content_id,
-            extraction_policy_name: begin_ingest.extraction_policy,
-        };
-        let res = self.coordinator_client.get().await?.update_task(req).await;
-        if let Err(err) = res {
-            error!("unable to update task: {}", err.to_string());
+    //  Schemas only change when written through the coordinator, which returns
+    // the evolved schema, so they are cached like the index schemas.
+    async fn structured_data_schema(
+        &self,
+        namespace: &str,
+        content_source: &str,
+    ) -> Result<internal_api::StructuredDataSchema> {
+        let id = internal_api::StructuredDataSchema::schema_id(namespace, content_source);
+        if let Some(schema) = self.structured_data_schemas.read().unwrap().get(&id) {
+            return Ok(schema.clone());
+        }
+        let schemas = self
+            .coordinator_client
+            .get_structured_schemas(namespace)
+            .await?;
+        let mut structured_data_schemas = self.structured_data_schemas.write().unwrap();
+        for mut schema in schemas {
+            schema.id =
+                internal_api::StructuredDataSchema::schema_id(namespace, &schema.content_source);
+            structured_data_schemas.insert(schema.id.clone(), schema);
+        }
+        Ok(structured_data_schemas
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| internal_api::StructuredDataSchema::new(content_source, namespace)))
+    }
+
+    /// Checks the dimension of every embedding feature against the index of
+    /// its output before anything is written, mismatches fail with an
+    /// `EmbeddingDimensionMismatch` naming the extractor, policy and content.
+    async fn validate_embedding_dimensions(
+        &self,
+        extractor_name: &str,
+        extraction_policy: &str,
+        content_meta: &indexify_coordinator::ContentMetadata,
+        features: &[api::Feature],
+        output_index_map: &HashMap<String, String>,
+    ) -> Result<()> {
+        for feature in features {
+            if !matches!(feature.feature_type, api::FeatureType::Embedding) {
+                continue;
+            }
+            let Some(index_table) = output_index_map.get(&feature.name) else {
+                continue;
+            };
+            let embedding: internal_api::Embedding =
+                serde_json::from_value(feature.data.clone())
+                    .map_err(|e| anyhow!("unable to get embedding from extracted data {}", e))?;
+            let Some(schema) = self
+                .index_schema(&content_meta.namespace, index_table)
+                .await?
+            else {
+                continue;
+            };
+            if schema.sparse {
+                embedding
+                    .sparse_vector()
+                    .validate(schema.dim)
+                    .map_err(|e| {
+                        anyhow!(
+                            "extractor {} of extraction policy {} produced an invalid embedding for output {} of content {}: {}",
+                            extractor_name,
+                            extraction_policy,
+                            feature.name,
+                            content_meta.id,
+                            e
+                        )
+                    })?;
+                continue;
+            }
+            EmbeddingDimensionMismatch::check(
+                extractor_name,
+                extraction_policy,
+                &content_meta.id,
+                &feature.name,
+                index_table,
+                schema.dim,
+                embedding.values.len(),
+            )?;
+            for vector in &schema.named_vectors {
+                if let Some(values) = embedding.vectors.get(vector) {
+                    EmbeddingDimensionMismatch::check(
+                        extractor_name,
+                        extraction_policy,
+                        &content_meta.id,
+                        &feature.name,
+                        &internal_api::EmbeddingSchema::named_vector_table(index_table, vector),
+                        schema.dim,
+                        values.len(),
+                    )?;
+                }
+            }
         }
         Ok(())
     }
@@ -538,6 +3450,48 @@ impl DataManager {
         Ok(())
     }
 
+    //  Writes the named vectors of a chunk to the tables of the named vectors
+    // of the index, vectors the index doesn't declare aren't stored
+    async fn write_named_vectors(
+        &self,
+        namespace: &str,
+        index_table: &str,
+        content_id: &str,
+        vectors: &HashMap<String, Vec<f32>>,
+        metadata: serde_json::Value,
+    ) -> Result<()> {
+        if vectors.is_empty() {
+            return Ok(());
+        }
+        let Some(schema) = self.index_schema(namespace, index_table).await? else {
+            return Ok(());
+        };
+        for vector in &schema.named_vectors {
+            let Some(values) = vectors.get(vector) else {
+                continue;
+            };
+            let embeddings = internal_api::ExtractedEmbeddings {
+                content_id: content_id.to_string(),
+                embedding: values.clone(),
+                metadata: metadata.clone(),
+            };
+            self.vector_index_manager
+                .add_embedding(
+                    &internal_api::EmbeddingSchema::named_vector_table(index_table, vector),
+                    vec![embeddings],
+                )
+                .await
+                .map_err(|e| {
+                    anyhow!(
+                        "unable to add named vector {} to vector index: {}",
+                        vector,
+                        e
+                    )
+                })?;
+        }
+        Ok(())
+    }
+
     // Combine metadata from existing metadata and new features into single json
     // object
     fn combine_metadata(
@@ -574,6 +3528,13 @@ impl DataManager {
         let metadata_updated = features
             .iter()
             .any(|feature| matches!(feature.feature_type, api::FeatureType::Metadata));
+        //  The metadata the policy extracted from the content before is replaced,
+        // and kept in the history of the content
+        if metadata_updated {
+            self.metadata_index_manager
+                .archive_metadata(&content_meta.namespace, &content_meta.id, extraction_policy)
+                .await?;
+        }
         let existing_metadata = self
             .metadata_index_manager
             .get_metadata_for_content(&content_meta.namespace, &content_meta.id)
@@ -591,7 +3552,9 @@ impl DataManager {
         if metadata_updated && !new_metadata.as_object().unwrap().is_empty() {
             // For all embeddings not updated with new values, update their metadata
             for index in index_tables {
-                if !index_in_features(output_index_map, &features, index) {
+                if !index_in_features(output_index_map, &features, index) &&
+                    self.owns_embedding(index, content_meta).await?
+                {
                     info!(
                         "updating metadata for content {} index {}",
                         content_meta.id.clone(),
@@ -615,6 +3578,17 @@ impl DataManager {
         metadata: &serde_json::Value,
         output_index_map: &HashMap<String, String>,
     ) -> Result<()> {
+        self.validate_embedding_dimensions(
+            extractor_name,
+            extraction_policy,
+            content_meta,
+            &features,
+            output_index_map,
+        )
+        .await?;
+        //  The metadata of the content is written at once after the other
+        // features, so a failed write doesn't leave part of it behind
+        let mut extracted_metadata = Vec::new();
         for feature in &features {
             match feature.feature_type {
                 api::FeatureType::Embedding => {
@@ -622,34 +3596,237 @@ impl DataManager {
                         serde_json::from_value(feature.data.clone()).map_err(|e| {
                             anyhow!("unable to get embedding from extracted data {}", e)
                         })?;
-                    self.write_extracted_embedding(
-                        &feature.name,
-                        &embedding_payload.values,
-                        &content_meta.id,
-                        output_index_map,
-                        metadata.clone(),
-                    )
-                    .await?;
+                    let sparse_table = match output_index_map.get(&feature.name) {
+                        Some(index_table) => self
+                            .index_schema(&content_meta.namespace, index_table)
+                            .await?
+                            .filter(|schema| schema.sparse)
+                            .map(|_| index_table),
+                        None => None,
+                    };
+                    let owns_embedding = match output_index_map.get(&feature.name) {
+                        Some(_) if sparse_table.is_some() => false,
+                        Some(index_table) => {
+                            self.add_chunk_reference(index_table, content_meta).await?
+                        }
+                        None => true,
+                    };
+                    //  Sparse embeddings aren't shared through chunk references,
+                    // every content stores its own
+                    if let Some(index_table) = sparse_table {
+                        self.vector_index_manager
+                            .add_sparse_embedding(
+                                index_table,
+                                &content_meta.id,
+                                embedding_payload.sparse_vector(),
+                                metadata.clone(),
+                            )
+                            .await
+                            .map_err(|e| {
+                                anyhow!("unable to add sparse embedding to vector index {}", e)
+                            })?;
+                    }
+                    //  Content with the same bytes shares the embedding already
+                    // written for the first content
+                    if owns_embedding {
+                        self.write_extracted_embedding(
+                            &feature.name,
+                            &embedding_payload.values,
+                            &content_meta.id,
+                            output_index_map,
+                            metadata.clone(),
+                        )
+                        .await?;
+                        if let Some(index_table) = output_index_map.get(&feature.name) {
+                            self.write_named_vectors(
+                                &content_meta.namespace,
+                                index_table,
+                                &content_meta.id,
+                                &embedding_payload.vectors,
+                                metadata.clone(),
+                            )
+                            .await?;
+                        }
+                    }
+                    if let Some(index_table) = output_index_map.get(&feature.name) {
+                        self.record_embedding_expiration(index_table, content_meta)
+                            .await?;
+                        lifecycle_events::index_written(
+                            &content_meta.namespace,
+                            index_table,
+                            &content_meta.id,
+                            extractor_name,
+                            extraction_policy,
+                        );
+                    }
                 }
                 api::FeatureType::Metadata => {
-                    let extracted_attributes = ExtractedMetadata::new(
+                    info!("adding metadata to index {}", feature.data.to_string());
+                    extracted_metadata.push(ExtractedMetadata::new(
                         &content_meta.id,
                         &content_meta.parent_id,
                         &content_meta.source,
                         feature.data.clone(),
                         extractor_name,
                         extraction_policy,
-                    );
-                    info!("adding metadata to index {}", feature.data.to_string());
-                    self.metadata_index_manager
-                        .add_metadata(&content_meta.namespace, extracted_attributes)
-                        .await?;
+                    ));
                 }
                 _ => {
                     error!("unsupported feature type: {:?}", feature.feature_type);
                 }
             }
         }
+        if extracted_metadata.is_empty() {
+            return Ok(());
+        }
+        self.metadata_index_manager
+            .add_metadata_batch(&content_meta.namespace, extracted_metadata.clone())
+            .await?;
+        //  The metadata is stored even if the schema can't be evolved
+        for metadata in &extracted_metadata {
+            if let Err(e) = self
+                .evolve_structured_data_schema(
+                    &content_meta.namespace,
+                    &content_meta.source,
+                    &metadata.metadata,
+                )
+                .await
+            {
+                error!(
+                    "unable to evolve schema of content source {}: {}",
+                    content_meta.source, e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the content to the chunk reference of its hash in the index.
+    /// Returns whether the content owns the reference, in which case its
+    /// embedding has to be written to the index.
+    async fn add_chunk_reference(
+        &self,
+        index_table: &str,
+        content_meta: &indexify_coordinator::ContentMetadata,
+    ) -> Result<bool> {
+        if content_meta.hash.is_empty() {
+            return Ok(true);
+        }
+        let req = indexify_coordinator::AddChunkReferenceRequest {
+            index_table: index_table.to_string(),
+            hash: content_meta.hash.clone(),
+            content_id: content_meta.id.clone(),
+        };
+        let reference = self
+            .coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.add_chunk_reference(req).await
+            })
+            .await
+            .map_err(|e| anyhow!("unable to add chunk reference: {}", e.to_string()))?
+            .into_inner()
+            .reference
+            .map(internal_api::ChunkReference::from);
+        Ok(reference
+            .as_ref()
+            .and_then(|reference| reference.owner())
+            .map_or(true, |owner| *owner == content_meta.id))
+    }
+
+    async fn get_chunk_reference(
+        &self,
+        index_table: &str,
+        hash: &str,
+    ) -> Result<Option<internal_api::ChunkReference>> {
+        let req = indexify_coordinator::GetChunkReferencesRequest {
+            index_table: index_table.to_string(),
+            hashes: vec![hash.to_string()],
+        };
+        let reference = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_chunk_references(req)
+            .await?
+            .into_inner()
+            .references
+            .into_iter()
+            .next()
+            .map(internal_api::ChunkReference::from);
+        Ok(reference)
+    }
+
+    //  Whether the embedding of the content is stored in the index under its
+    // own id rather than under the id of identical content
+    async fn owns_embedding(
+        &self,
+        index_table: &str,
+        content_meta: &indexify_coordinator::ContentMetadata,
+    ) -> Result<bool> {
+        if content_meta.hash.is_empty() {
+            return Ok(true);
+        }
+        let reference = self
+            .get_chunk_reference(index_table, &content_meta.hash)
+            .await?;
+        Ok(reference
+            .as_ref()
+            .and_then(|reference| reference.owner())
+            .map_or(true, |owner| *owner == content_meta.id))
+    }
+
+    /// Removes the embedding of the content from the index. An embedding
+    /// shared with identical content is handed over to the next content of
+    /// the chunk reference before it's removed.
+    async fn remove_embedding(
+        &self,
+        namespace: &str,
+        index_table: &str,
+        hash: &str,
+        content_id: &str,
+    ) -> Result<()> {
+        //  The named vectors of the chunk are handed over and removed with it
+        let mut tables = vec![index_table.to_string()];
+        if let Some(schema) = self.index_schema(namespace, index_table).await? {
+            tables.extend(schema.named_vector_tables(index_table));
+        }
+        let reference = match hash {
+            "" => None,
+            hash => self.get_chunk_reference(index_table, hash).await?,
+        };
+        if let Some(reference) =
+            reference.filter(|reference| reference.content_ids.iter().any(|id| id == content_id))
+        {
+            let owner = reference.owner().map(|id| id.as_str()) == Some(content_id);
+            if owner {
+                if let Some(next_owner) = reference.content_ids.get(1) {
+                    for table in &tables {
+                        self.vector_index_manager
+                            .copy_embedding(table, content_id, next_owner)
+                            .await?;
+                    }
+                }
+            }
+            let req = indexify_coordinator::RemoveChunkReferenceRequest {
+                index_table: index_table.to_string(),
+                hash: hash.to_string(),
+                content_id: content_id.to_string(),
+            };
+            self.coordinator_client
+                .call_leader(req, |mut client, req| async move {
+                    client.remove_chunk_reference(req).await
+                })
+                .await
+                .map_err(|e| anyhow!("unable to remove chunk reference: {}", e.to_string()))?;
+            if !owner {
+                return Ok(());
+            }
+        }
+        for table in &tables {
+            self.vector_index_manager
+                .delete_by_content_id(table, content_id)
+                .await?;
+        }
         Ok(())
     }
 
@@ -661,18 +3838,28 @@ impl DataManager {
     ) -> Result<()> {
         let req = indexify_coordinator::CreateContentRequest {
             content: Some(content_meta.clone()),
+            request_id: coordinator_request_id(
+                &content_meta.namespace,
+                "extracted_content",
+                ingest_metadata.request_id.as_deref(),
+                Some(&content_meta.id),
+            ),
         };
-        self.coordinator_client
-            .get()
-            .await?
-            .create_content(GrpcHelper::into_req(req))
+        let response = self
+            .coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.create_content(GrpcHelper::into_req(req)).await
+            })
             .await
             .map_err(|e| {
-                anyhow!(
-                    "unable to write content metadata to coordinator {}",
-                    e.to_string()
-                )
-            })?;
+                coordinator_write_error("unable to write content metadata to coordinator", e)
+            })?
+            .into_inner();
+        //  The embeddings of extracted content expire with its parent
+        let content_meta = &indexify_coordinator::ContentMetadata {
+            expires_at: response.expires_at,
+            ..content_meta.clone()
+        };
         let combined_metadata = Self::combine_metadata(Vec::new(), &features);
         self.write_extracted_features(
             &ingest_metadata.extractor,
@@ -682,7 +3869,39 @@ impl DataManager {
             &combined_metadata,
             &ingest_metadata.output_to_index_table_mapping,
         )
-        .await
+        .await?;
+        if let Some(index_table) = ingest_metadata
+            .output_to_index_table_mapping
+            .get(internal_api::KEYWORD_INDEX_OUTPUT)
+        {
+            self.write_keyword_index(index_table, content_meta, ingest_metadata)
+                .await?;
+        }
+        Ok(())
+    }
+
+    //  Only text content is added to keyword indexes
+    async fn write_keyword_index(
+        &self,
+        index_table: &str,
+        content_meta: &indexify_coordinator::ContentMetadata,
+        ingest_metadata: &BeginExtractedContentIngest,
+    ) -> Result<()> {
+        if !content_meta.mime.starts_with("text/") {
+            return Ok(());
+        }
+        self.vector_index_manager
+            .add_content_text(index_table, content_meta)
+            .await
+            .map_err(|e| anyhow!("unable to add text to keyword index {}", e))?;
+        lifecycle_events::index_written(
+            &content_meta.namespace,
+            index_table,
+            &content_meta.id,
+            &ingest_metadata.extractor,
+            &ingest_metadata.extraction_policy,
+        );
+        Ok(())
     }
 
     pub async fn write_extracted_content(
@@ -706,97 +3925,771 @@ impl DataManager {
                     &ingest_metadata.extraction_policy,
                 )
                 .await?;
-            features.insert(content_metadata.id.clone(), content.features);
-            new_content_metadata.push(content_metadata.clone());
+            features.insert(content_metadata.id.clone(), content.features);
+            new_content_metadata.push(content_metadata.clone());
+        }
+        //  Content is written concurrently, so its embeddings are upserted to
+        // the vector store in the same batches
+        let ingest_metadata = &ingest_metadata;
+        let features = &features;
+        futures::stream::iter(new_content_metadata.into_iter().map(Ok::<_, anyhow::Error>))
+            .try_for_each_concurrent(
+                EXTRACTED_CONTENT_WRITE_CONCURRENCY,
+                |content_meta| async move {
+                    self.create_content_and_write_features(
+                        &content_meta,
+                        ingest_metadata,
+                        features.get(&content_meta.id).unwrap().clone(),
+                    )
+                    .await
+                },
+            )
+            .await
+    }
+
+    pub async fn query_content_source(
+        &self,
+        namespace: &str,
+        query: &str,
+    ) -> Result<Vec<StructuredDataRow>> {
+        let schemas = self
+            .coordinator_client
+            .get_structured_schemas(namespace)
+            .await?;
+        let metadata_reader = self.metadata_reader.clone();
+        let namespace = namespace.to_string();
+        let query = query.to_string();
+        tokio::task::spawn_blocking(move || {
+            futures::executor::block_on(async move {
+                run_query(query, metadata_reader, schemas, namespace).await
+            })
+        })
+        .await?
+    }
+
+    /// Loads the index, and the tables of its named vectors, into memory so
+    /// the first searches after a restart or rebuild aren't slowed down.
+    pub async fn warm_up_index(
+        &self,
+        namespace: &str,
+        index_name: &str,
+    ) -> Result<api::IndexWarmUp> {
+        let index = self.get_index_metadata(namespace, index_name).await?;
+        let started_at = Instant::now();
+        let vector_store = if is_keyword_schema(&index.schema) {
+            self.vector_index_manager.warm_up(&index.table_name).await?;
+            "keyword".to_string()
+        } else {
+            let schema: internal_api::EmbeddingSchema = serde_json::from_str(&index.schema)?;
+            self.vector_index_manager.warm_up(&index.table_name).await?;
+            for table in schema.named_vector_tables(&index.table_name) {
+                self.vector_index_manager.warm_up(&table).await?;
+            }
+            self.vector_index_manager.vector_store()
+        };
+        let duration_ms = started_at.elapsed().as_millis() as u64;
+        info!(
+            "warmed up index {} of namespace {} in {}ms",
+            index.name, namespace, duration_ms
+        );
+        Ok(api::IndexWarmUp {
+            name: index.name,
+            vector_store,
+            duration_ms,
+        })
+    }
+
+    /// Makes the embeddings written to the index and its named vectors so far
+    /// visible to searches, for searches which have to read their writes.
+    pub async fn refresh_index(&self, namespace: &str, index_name: &str) -> Result<()> {
+        let index = self.get_index_metadata(namespace, index_name).await?;
+        self.vector_index_manager.refresh(&index.table_name).await?;
+        if !is_keyword_schema(&index.schema) {
+            let schema: internal_api::EmbeddingSchema = serde_json::from_str(&index.schema)?;
+            for table in schema.named_vector_tables(&index.table_name) {
+                self.vector_index_manager.refresh(&table).await?;
+            }
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument]
+    pub async fn list_indexes(&self, namespace: &str) -> Result<Vec<api::Index>> {
+        let req = indexify_coordinator::ListIndexesRequest {
+            namespace: namespace.to_string(),
+        };
+        let resp = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_indexes(req)
+            .await?;
+        let mut api_indexes = Vec::new();
+        for index in resp.into_inner().indexes {
+            let api_index = index.try_into()?;
+            api_indexes.push(api_index);
+        }
+        Ok(api_indexes)
+    }
+
+    pub async fn get_index_stats(
+        &self,
+        namespace: &str,
+        index_name: &str,
+    ) -> Result<api::IndexStats> {
+        let index = self.get_index_metadata(namespace, index_name).await?;
+        let stats = self
+            .vector_index_manager
+            .index_stats(&index.table_name)
+            .await?;
+        if is_keyword_schema(&index.schema) {
+            return Ok(api::IndexStats {
+                name: index.name,
+                index_type: api::IndexType::Keyword,
+                vector_store: "keyword".to_string(),
+                num_vectors: stats.num_vectors,
+                dim: None,
+                shard_count: stats.shard_count,
+                segment_count: stats.segment_count,
+                approximate_memory_bytes: stats.memory_bytes,
+            });
+        }
+        let schema: internal_api::EmbeddingSchema = serde_json::from_str(&index.schema)?;
+        let approximate_memory_bytes = stats
+            .memory_bytes
+            .unwrap_or(stats.num_vectors * schema.vector_bytes());
+        Ok(api::IndexStats {
+            name: index.name,
+            index_type: api::IndexType::Embedding,
+            vector_store: self.vector_index_manager.vector_store(),
+            num_vectors: stats.num_vectors,
+            dim: Some(schema.dim),
+            shard_count: stats.shard_count,
+            segment_count: stats.segment_count,
+            approximate_memory_bytes: Some(approximate_memory_bytes),
+        })
+    }
+
+    #[tracing::instrument]
+    pub async fn search(
+        &self,
+        namespace: &str,
+        index_name: &str,
+        query: &str,
+        k: u64,
+        vector: Option<&str>,
+    ) -> Result<Vec<ScoredText>> {
+        let req = indexify_coordinator::GetIndexRequest {
+            namespace: namespace.to_string(),
+            name: index_name.to_string(),
+            consistency: indexify_coordinator::ReadConsistency::Bounded as i32,
+        };
+        let index = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_index(req)
+            .await?
+            .into_inner()
+            .index
+            .ok_or(anyhow!("Index not found"))?;
+        self.vector_index_manager
+            .search(index, query, k as usize, vector)
+            .await
+    }
+
+    /// Runs each query against the index, returning the results in the order
+    /// of the queries. The index is looked up once and the queries are run
+    /// concurrently, a failed query doesn't fail the others.
+    pub async fn batch_search(
+        &self,
+        namespace: &str,
+        index_name: &str,
+        queries: &[String],
+        k: u64,
+    ) -> Result<Vec<Result<Vec<ScoredText>>>> {
+        let req = indexify_coordinator::GetIndexRequest {
+            namespace: namespace.to_string(),
+            name: index_name.to_string(),
+            consistency: indexify_coordinator::ReadConsistency::Bounded as i32,
+        };
+        let index = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_index(req)
+            .await?
+            .into_inner()
+            .index
+            .ok_or(anyhow!("Index not found"))?;
+        let searches = queries.iter().map(|query| {
+            self.vector_index_manager
+                .search(index.clone(), query, k as usize, None)
+        });
+        Ok(futures::stream::iter(searches)
+            .buffered(BATCH_SEARCH_CONCURRENCY)
+            .collect()
+            .await)
+    }
+
+    /// Runs the query against the index with the same name in each of the
+    /// namespaces, or in every namespace if none are given. Namespaces which
+    /// don't have the index are skipped.
+    pub async fn search_namespaces(
+        &self,
+        namespaces: &[String],
+        index_name: &str,
+        query: &str,
+        k: u64,
+    ) -> Result<Vec<(String, Result<Vec<ScoredText>>)>> {
+        let namespaces = if namespaces.is_empty() {
+            self.list_namespaces()
+                .await?
+                .into_iter()
+                .map(|namespace| namespace.name)
+                .collect_vec()
+        } else {
+            namespaces.to_vec()
+        };
+        let searches = namespaces.into_iter().map(|namespace| async move {
+            let results = match self.list_indexes(&namespace).await {
+                Ok(indexes) if !indexes.iter().any(|index| index.name == index_name) => {
+                    return None;
+                }
+                Ok(_) => self.search(&namespace, index_name, query, k, None).await,
+                Err(e) => Err(e),
+            };
+            Some((namespace, results))
+        });
+        Ok(join_all(searches).await.into_iter().flatten().collect())
+    }
+
+    pub async fn search_with_recency_boost(
+        &self,
+        namespace: &str,
+        index_name: &str,
+        query: &str,
+        k: u64,
+        vector: Option<&str>,
+        recency_boost: &api::RecencyBoost,
+    ) -> Result<Vec<ScoredText>> {
+        let results = self
+            .search(
+                namespace,
+                index_name,
+                query,
+                k * RECENCY_BOOST_OVERSAMPLING,
+                vector,
+            )
+            .await?;
+        Ok(apply_recency_boost(
+            results,
+            recency_boost,
+            timestamp_secs() as i64,
+            k as usize,
+        ))
+    }
+
+    #[tracing::instrument]
+    pub async fn metadata_lookup(
+        &self,
+        namespace: &str,
+        content_id: &str,
+    ) -> Result<Vec<ExtractedMetadata>, anyhow::Error> {
+        self.metadata_index_manager
+            .get_metadata_for_content(namespace, content_id)
+            .await
+    }
+
+    /// Writes the metadata of every content source of the namespace, or only
+    /// of the requested one, to a Parquet file in the blob store. The text of
+    /// text content is added to its metadata if requested.
+    pub async fn export_metadata(
+        &self,
+        namespace: &str,
+        request: &api::ExportMetadataRequest,
+    ) -> Result<api::ExportMetadataResponse> {
+        let content_sources = match &request.content_source {
+            Some(content_source) => vec![content_source.clone()],
+            None => self
+                .coordinator_client
+                .get_structured_schemas(namespace)
+                .await?
+                .into_iter()
+                .map(|schema| schema.content_source)
+                .collect(),
+        };
+        let created_at = timestamp_secs();
+        let mut files = Vec::new();
+        for content_source in content_sources {
+            let metadata = self
+                .metadata_index_manager
+                .list_metadata(
+                    namespace,
+                    &content_source,
+                    &Provenance::default(),
+                    &[],
+                    &api::MetadataPage::default(),
+                )
+                .await?
+                .metadata;
+            if metadata.is_empty() {
+                continue;
+            }
+            let texts = match request.include_text {
+                true => self.content_texts(&metadata).await?,
+                false => HashMap::new(),
+            };
+            let rows = metadata
+                .into_iter()
+                .map(|metadata| ExportRow {
+                    text: texts.get(&metadata.content_id).cloned(),
+                    metadata,
+                })
+                .collect_vec();
+            let bytes = metadata_export::to_parquet(&rows, request.include_text)?;
+            let key = metadata_export::export_key(namespace, &content_source, created_at);
+            let mut writer = self.blob_store_writer(namespace, &key).await?;
+            writer.writer.write_all(&bytes).await?;
+            writer.writer.shutdown().await?;
+            info!(
+                "exported {} metadata rows of content source {} to {}",
+                rows.len(),
+                content_source,
+                writer.url
+            );
+            files.push(api::MetadataExportFile {
+                content_source,
+                url: writer.url,
+                num_rows: rows.len() as u64,
+            });
+        }
+        Ok(api::ExportMetadataResponse { files, created_at })
+    }
+
+    //  Text of the text content the metadata was extracted from, by content id
+    async fn content_texts(
+        &self,
+        metadata: &[ExtractedMetadata],
+    ) -> Result<HashMap<String, String>> {
+        let content_ids = metadata
+            .iter()
+            .map(|metadata| metadata.content_id.clone())
+            .unique()
+            .collect_vec();
+        let mut texts = HashMap::new();
+        for batch in content_ids.chunks(DEFAULT_REBALANCE_BATCH_SIZE) {
+            for content_meta in self.content_metadata_batch(batch.to_vec()).await? {
+                if !content_meta.mime.starts_with("text/") {
+                    continue;
+                }
+                let bytes = self
+                    .blob_storage
+                    .content_reader()
+                    .verified_bytes(&content_meta.storage_url, &content_meta.hash)
+                    .await?;
+                texts.insert(
+                    content_meta.id,
+                    String::from_utf8_lossy(&bytes).into_owned(),
+                );
+            }
+        }
+        Ok(texts)
+    }
+
+    /// Returns the current and previous versions of the metadata of the
+    /// content, oldest first
+    pub async fn metadata_history(
+        &self,
+        namespace: &str,
+        content_id: &str,
+    ) -> Result<Vec<MetadataVersion>> {
+        self.metadata_index_manager
+            .metadata_history(namespace, content_id)
+            .await
+    }
+
+    /// Merges the patch into the manual metadata of the content, which is
+    /// kept apart from the extracted metadata so extractors writing again
+    /// don't overwrite it.
+    pub async fn update_metadata(
+        &self,
+        content: &api::ContentMetadata,
+        patch: &serde_json::Value,
+    ) -> Result<ExtractedMetadata> {
+        let id = ExtractedMetadata::manual_id(&content.id);
+        let mut metadata = self
+            .metadata_index_manager
+            .get_metadata_for_content(&content.namespace, &content.id)
+            .await?
+            .into_iter()
+            .find(|metadata| metadata.id == id)
+            .unwrap_or_else(|| {
+                let mut metadata = ExtractedMetadata::new(
+                    &content.id,
+                    &content.parent_id,
+                    &content.source,
+                    serde_json::json!({}),
+                    MANUAL_EXTRACTOR,
+                    "",
+                );
+                metadata.id = id;
+                metadata
+            });
+        merge_patch(&mut metadata.metadata, patch);
+        self.metadata_index_manager
+            .add_metadata(&content.namespace, metadata.clone())
+            .await?;
+        if let Err(e) = self
+            .evolve_structured_data_schema(&content.namespace, &content.source, patch)
+            .await
+        {
+            error!(
+                "unable to evolve schema of content source {}: {}",
+                content.source, e
+            );
+        }
+        Ok(metadata)
+    }
+
+    /// Imports metadata produced outside of Indexify for content looked up by
+    /// id or external id. Records whose content isn't found or whose fields
+    /// don't fit the schema of the content source are rejected, the others are
+    /// written in a single transaction.
+    pub async fn import_metadata(
+        &self,
+        namespace: &str,
+        records: &[api::ImportedMetadata],
+    ) -> Result<api::ImportMetadataResponse> {
+        let contents = join_all(
+            records
+                .iter()
+                .map(|record| self.imported_content(namespace, record)),
+        )
+        .await;
+        let mut metadata = Vec::new();
+        let mut rejected = Vec::new();
+        for (index, (record, content)) in records.iter().zip(contents).enumerate() {
+            let content = match content {
+                Ok(content) => content,
+                Err(e) => {
+                    rejected.push(api::RejectedMetadata {
+                        index,
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let conflicts = self
+                .structured_data_schema(namespace, &content.source)
+                .await?
+                .infer(&record.metadata)
+                .conflicts;
+            if !conflicts.is_empty() {
+                rejected.push(api::RejectedMetadata {
+                    index,
+                    reason: format!(
+                        "fields {} don't match the schema of content source {}",
+                        conflicts.keys().join(", "),
+                        content.source
+                    ),
+                });
+                continue;
+            }
+            let mut imported = ExtractedMetadata::new(
+                &content.id,
+                &content.parent_id,
+                &content.source,
+                record.metadata.clone(),
+                IMPORT_EXTRACTOR,
+                "",
+            );
+            imported.id = ExtractedMetadata::import_id(&content.id);
+            metadata.push(imported);
+        }
+        let imported = metadata.len();
+        if imported > 0 {
+            self.metadata_index_manager
+                .add_metadata_batch(namespace, metadata.clone())
+                .await?;
         }
-        for content_meta in new_content_metadata {
-            self.create_content_and_write_features(
-                &content_meta,
-                &ingest_metadata,
-                features.get(&content_meta.id).unwrap().clone(),
-            )
-            .await?
+        //  Imported metadata can add columns to the schema, it fits the
+        // existing ones
+        for metadata in &metadata {
+            if let Err(e) = self
+                .evolve_structured_data_schema(
+                    namespace,
+                    &metadata.content_source,
+                    &metadata.metadata,
+                )
+                .await
+            {
+                error!(
+                    "unable to evolve schema of content source {}: {}",
+                    metadata.content_source, e
+                );
+            }
         }
-        Ok(())
+        Ok(api::ImportMetadataResponse { imported, rejected })
     }
 
-    pub async fn query_content_source(
+    async fn imported_content(
         &self,
         namespace: &str,
-        query: &str,
-    ) -> Result<Vec<StructuredDataRow>> {
-        let schemas = self
-            .coordinator_client
-            .get_structured_schemas(namespace)
-            .await?;
-        let metadata_reader = self.metadata_reader.clone();
-        let namespace = namespace.to_string();
-        let query = query.to_string();
-        tokio::task::spawn_blocking(move || {
-            futures::executor::block_on(async move {
-                run_query(query, metadata_reader, schemas, namespace).await
-            })
+        record: &api::ImportedMetadata,
+    ) -> Result<api::ContentMetadata> {
+        if !record.metadata.is_object() {
+            return Err(anyhow!("metadata has to be a JSON object"));
+        }
+        match (&record.content_id, &record.external_id) {
+            (Some(content_id), _) => self
+                .get_content_metadata(namespace, vec![content_id.clone()])
+                .await?
+                .into_iter()
+                .find(|content| content.namespace == namespace)
+                .ok_or_else(|| anyhow!("content {} not found", content_id)),
+            (None, Some(external_id)) => {
+                self.get_content_metadata_by_external_id(namespace, external_id)
+                    .await
+            }
+            (None, None) => Err(anyhow!("content_id or external_id is required")),
+        }
+    }
+
+    /// Returns a page of the metadata of the content written by the extractor
+    /// or extraction policy of the provenance
+    pub async fn metadata_lookup_by_provenance(
+        &self,
+        namespace: &str,
+        content_id: &str,
+        provenance: &api::MetadataProvenance,
+        page: &api::MetadataPage,
+    ) -> Result<PagedMetadata> {
+        let provenance = self.resolve_provenance(namespace, provenance).await?;
+        self.metadata_index_manager
+            .get_metadata_page_for_content(namespace, content_id, &provenance, page)
+            .await
+    }
+
+    //  Metadata records the id of the extraction policy which wrote it, policies
+    // are looked up by name so the id doesn't have to be known
+    async fn resolve_provenance(
+        &self,
+        namespace: &str,
+        provenance: &api::MetadataProvenance,
+    ) -> Result<Provenance> {
+        let extraction_policy = match &provenance.extraction_policy {
+            Some(policy) => Some(
+                self.coordinator_client
+                    .get()
+                    .await?
+                    .list_extraction_policies(indexify_coordinator::ListExtractionPoliciesRequest {
+                        namespace: namespace.to_string(),
+                    })
+                    .await?
+                    .into_inner()
+                    .policies
+                    .into_iter()
+                    .find(|p| p.name == *policy || p.id == *policy)
+                    .map(|p| p.id)
+                    .ok_or(anyhow!(
+                        "extraction policy {} not found in namespace {}",
+                        policy,
+                        namespace
+                    ))?,
+            ),
+            None => None,
+        };
+        Ok(Provenance {
+            extractor: provenance.extractor.clone(),
+            extraction_policy,
         })
-        .await?
     }
 
-    #[tracing::instrument]
-    pub async fn list_indexes(&self, namespace: &str) -> Result<Vec<api::Index>> {
-        let req = indexify_coordinator::ListIndexesRequest {
-            namespace: namespace.to_string(),
+    /// Lists a page of the metadata extracted from the content of a content
+    /// source matching the filters, which the metadata store evaluates and
+    /// pages. Only the metadata with a location matching the geo filter is
+    /// kept, geo filters are evaluated here so the metadata matching the other
+    /// filters is paged after reading all of it.
+    pub async fn query_metadata(
+        &self,
+        namespace: &str,
+        content_source: &str,
+        provenance: &api::MetadataProvenance,
+        filters: &[api::MetadataFilter],
+        geo_filter: Option<&api::GeoFilter>,
+        page: &api::MetadataPage,
+    ) -> Result<PagedMetadata> {
+        let provenance = self.resolve_provenance(namespace, provenance).await?;
+        let Some(geo_filter) = geo_filter else {
+            return self
+                .metadata_index_manager
+                .list_metadata(namespace, content_source, &provenance, filters, page)
+                .await;
         };
-        let resp = self
-            .coordinator_client
-            .get()
+        let metadata: Vec<ExtractedMetadata> = self
+            .metadata_index_manager
+            .list_metadata(
+                namespace,
+                content_source,
+                &provenance,
+                filters,
+                &api::MetadataPage::default(),
+            )
             .await?
-            .list_indexes(req)
-            .await?;
-        let mut api_indexes = Vec::new();
-        for index in resp.into_inner().indexes {
-            let api_index = index.try_into()?;
-            api_indexes.push(api_index);
+            .metadata
+            .into_iter()
+            .filter(|m| geo_filters::matches(geo_filter, &m.metadata))
+            .collect();
+        Ok(PagedMetadata {
+            total: metadata.len(),
+            metadata: page.apply(metadata),
+        })
+    }
+
+    /// Keeps the search results whose content has a location matching the geo
+    /// filter. The metadata extracted from a piece of content is combined
+    /// before looking up the location.
+    pub async fn filter_by_location(
+        &self,
+        namespace: &str,
+        results: Vec<ScoredText>,
+        geo_filter: &api::GeoFilter,
+    ) -> Result<Vec<ScoredText>> {
+        let metadata = join_all(
+            results
+                .iter()
+                .map(|result| self.metadata_lookup(namespace, &result.content_id)),
+        )
+        .await;
+        let mut filtered_results = Vec::new();
+        for (result, metadata) in results.into_iter().zip(metadata) {
+            let metadata = Self::combine_metadata(metadata?, &[]);
+            if geo_filters::matches(geo_filter, &metadata) {
+                filtered_results.push(result);
+            }
         }
-        Ok(api_indexes)
+        Ok(filtered_results)
     }
 
-    #[tracing::instrument]
-    pub async fn search(
+    /// Keeps the search results matching the filter expression. The extracted
+    /// metadata of the results is only looked up when the expression has
+    /// metadata predicates.
+    pub async fn filter_by_expression(
+        &self,
+        namespace: &str,
+        results: Vec<ScoredText>,
+        filter: &FilterExpr,
+    ) -> Result<Vec<ScoredText>> {
+        let metadata = if filter.references_metadata() {
+            join_all(
+                results
+                    .iter()
+                    .map(|result| self.metadata_lookup(namespace, &result.content_id)),
+            )
+            .await
+            .into_iter()
+            .map(|metadata| Ok(Self::combine_metadata(metadata?, &[])))
+            .collect::<Result<Vec<_>>>()?
+        } else {
+            vec![serde_json::Value::Null; results.len()]
+        };
+        Ok(results
+            .into_iter()
+            .zip(metadata)
+            .filter(|(result, metadata)| filter.matches(&result.labels, metadata, &result.text))
+            .map(|(result, _)| result)
+            .collect())
+    }
+
+    /// Collapses search results with identical text, and with a similarity
+    /// threshold results with similar embeddings, into the first of them.
+    /// Keyword indexes have no embeddings and are only deduplicated by text.
+    /// The embeddings compared are those of the named vector searched.
+    pub async fn dedupe_results(
         &self,
         namespace: &str,
         index_name: &str,
-        query: &str,
-        k: u64,
+        results: Vec<ScoredText>,
+        similarity_threshold: Option<f32>,
+        vector: Option<&str>,
     ) -> Result<Vec<ScoredText>> {
-        let req = indexify_coordinator::GetIndexRequest {
-            namespace: namespace.to_string(),
-            name: index_name.to_string(),
+        let mut embeddings = HashMap::new();
+        if similarity_threshold.is_some() {
+            let req = indexify_coordinator::GetIndexRequest {
+                namespace: namespace.to_string(),
+                name: index_name.to_string(),
+                consistency: indexify_coordinator::ReadConsistency::Bounded as i32,
+            };
+            let index = self
+                .coordinator_client
+                .get()
+                .await?
+                .get_index(req)
+                .await?
+                .into_inner()
+                .index
+                .ok_or(anyhow!("Index not found"))?;
+            if !is_keyword_schema(&index.schema) {
+                let table_name = match vector {
+                    Some(vector) => {
+                        internal_api::EmbeddingSchema::named_vector_table(&index.table_name, vector)
+                    }
+                    None => index.table_name.clone(),
+                };
+                embeddings = self
+                    .vector_index_manager
+                    .get_points(
+                        &table_name,
+                        results.iter().map(|r| r.content_id.clone()).collect(),
+                    )
+                    .await?
+                    .into_iter()
+                    .map(|chunk| (chunk.content_id, chunk.embedding))
+                    .collect();
+            }
+        }
+        Ok(collapse_duplicates(
+            results,
+            &embeddings,
+            similarity_threshold,
+        ))
+    }
+
+    #[tracing::instrument]
+    pub async fn list_executors(
+        &self,
+        labels_eq: Option<&HashMap<String, String>>,
+    ) -> Result<Vec<api::Executor>> {
+        let req = indexify_coordinator::ListExecutorsRequest {
+            labels_eq: labels_eq.cloned().unwrap_or_default(),
         };
-        let index = self
+        let response = self
             .coordinator_client
             .get()
             .await?
-            .get_index(req)
+            .list_executors(req)
             .await?
-            .into_inner()
-            .index
-            .ok_or(anyhow!("Index not found"))?;
-        self.vector_index_manager
-            .search(index, query, k as usize)
-            .await
+            .into_inner();
+        response
+            .executors
+            .into_iter()
+            .map(|executor| executor.try_into())
+            .collect()
     }
 
-    #[tracing::instrument]
-    pub async fn metadata_lookup(
-        &self,
-        namespace: &str,
-        content_id: &str,
-    ) -> Result<Vec<ExtractedMetadata>, anyhow::Error> {
-        self.metadata_index_manager
-            .get_metadata_for_content(namespace, content_id)
-            .await
+    pub async fn drain_executor(&self, executor_id: &str) -> Result<()> {
+        let req = indexify_coordinator::DrainExecutorRequest {
+            executor_id: executor_id.to_string(),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.drain_executor(req).await
+            })
+            .await?;
+        Ok(())
     }
 
-    #[tracing::instrument]
     pub async fn list_extractors(&self) -> Result<Vec<api::ExtractorDescription>> {
         let req = indexify_coordinator::ListExtractorsRequest {};
         let response = self
@@ -822,12 +4715,230 @@ impl DataManager {
         name: &str,
         file: impl Stream<Item = Result<Bytes>> + Send + Unpin,
     ) -> Result<PutResult> {
-        self.blob_storage.put(name, file).await
+        self.blob_storage
+            .put_in_namespace(namespace, &namespace_key(namespace, name), file)
+            .await
     }
 
     pub async fn blob_store_writer(&self, namespace: &str, key: &str) -> Result<StoragePartWriter> {
-        self.blob_storage.writer(namespace, key).await
+        self.blob_storage
+            .writer(namespace, &namespace_key(namespace, key))
+            .await
+    }
+
+    pub async fn set_namespace_quotas(
+        &self,
+        namespace: &str,
+        quotas: api::NamespaceQuotas,
+    ) -> Result<()> {
+        let req = indexify_coordinator::SetNamespaceQuotasRequest {
+            namespace: namespace.to_string(),
+            quotas: Some(internal_api::NamespaceQuotas::from(quotas).into()),
+        };
+        self.coordinator_client
+            .call_leader(req, |mut client, req| async move {
+                client.set_namespace_quotas(req).await
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_namespace_quotas(
+        &self,
+        namespace: &str,
+    ) -> Result<api::NamespaceQuotasResponse> {
+        let req = indexify_coordinator::GetNamespaceQuotasRequest {
+            namespace: namespace.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_namespace_quotas(req)
+            .await?
+            .into_inner();
+        Ok(api::NamespaceQuotasResponse {
+            namespace: namespace.to_string(),
+            quotas: internal_api::NamespaceQuotas::from(response.quotas.unwrap_or_default()).into(),
+            content: response.content,
+            stored_bytes: response.stored_bytes,
+            extraction_policies: response.extraction_policies,
+        })
+    }
+
+    pub async fn get_namespace_stats(&self, namespace: &str) -> Result<api::NamespaceStats> {
+        let req = indexify_coordinator::GetNamespaceStatsRequest {
+            namespace: namespace.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_namespace_stats(req)
+            .await?
+            .into_inner();
+        Ok(api::NamespaceStats {
+            namespace: namespace.to_string(),
+            content: response.content,
+            stored_bytes: response.stored_bytes,
+            tasks_pending: response.tasks_pending,
+            tasks_failed: response.tasks_failed,
+            indexes: response.indexes,
+        })
+    }
+}
+
+//  Blob references are chunk references of a table no index can have, the
+// tables of indexes are prefixed with their namespace
+fn blob_reference_table(namespace: &str) -> String {
+    format!("blobs|{}", namespace)
+}
+
+//  Blobs are copied to the key they'd have been written to in the target, so
+// blobs of content stored before blobs were kept under their namespace and
+// hash are moved into that layout. Content stored before content was hashed
+// is copied to a key of its id.
+fn migrated_blob_key(content: &api::ContentMetadata) -> String {
+    if content.hash.is_empty() {
+        return namespace_key(&content.namespace, &content.id);
+    }
+    let key = content_addressed_key(&content.namespace, &content.hash);
+    if compression::is_compressed(&content.storage_url) {
+        return compression::compressed_key(&key);
+    }
+    key
+}
+
+//  Compressed and uncompressed blobs of the same bytes are separate blobs,
+// each is referenced by the content stored in it
+fn blob_reference_hash(hash: &str, url: &str) -> String {
+    if compression::is_compressed(url) {
+        compression::compressed_key(hash)
+    } else {
+        hash.to_string()
+    }
+}
+
+/// Keeps the results scored by a scoring plugin with their new scores, best
+/// first. Results the plugin didn't return are dropped.
+pub fn apply_scores(results: Vec<ScoredText>, scores: Vec<ScoredCandidate>) -> Vec<ScoredText> {
+    let scores: HashMap<String, f32> = scores
+        .into_iter()
+        .map(|scored| (scored.content_id, scored.score))
+        .collect();
+    let mut results: Vec<ScoredText> = results
+        .into_iter()
+        .filter_map(|mut result| {
+            result.confidence_score = *scores.get(&result.content_id)?;
+            Some(result)
+        })
+        .collect();
+    results.sort_by(|a, b| b.confidence_score.total_cmp(&a.confidence_score));
+    results
+}
+
+/// Keeps at most `max_per_parent` results extracted from the same content,
+/// in the order of the results. Results which weren't extracted from other
+/// content are grouped by their own id.
+pub fn limit_results_per_parent(
+    results: Vec<ScoredText>,
+    max_per_parent: usize,
+) -> Vec<ScoredText> {
+    let mut results_per_parent: HashMap<String, usize> = HashMap::new();
+    results
+        .into_iter()
+        .filter(|result| {
+            let parent_id = if result.parent_id.is_empty() {
+                &result.content_id
+            } else {
+                &result.parent_id
+            };
+            let count = results_per_parent.entry(parent_id.clone()).or_default();
+            *count += 1;
+            *count <= max_per_parent
+        })
+        .collect()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot = a.iter().zip(b).map(|(a, b)| a * b).sum::<f32>();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        return 0.0;
+    }
+    dot / norms
+}
+
+/// Collapses each result into the first earlier result with the same text,
+/// or with an embedding at least `similarity_threshold` similar to it. The
+/// ids of collapsed content are added to the duplicates of the kept result.
+/// Results without text are only collapsed by similarity.
+pub fn collapse_duplicates(
+    results: Vec<ScoredText>,
+    embeddings: &HashMap<String, Vec<f32>>,
+    similarity_threshold: Option<f32>,
+) -> Vec<ScoredText> {
+    let mut kept: Vec<ScoredText> = Vec::new();
+    let mut kept_by_hash: HashMap<String, usize> = HashMap::new();
+    for result in results {
+        let hash = (!result.text.is_empty())
+            .then(|| format!("{:x}", Sha256::digest(result.text.as_bytes())));
+        let duplicate_of = hash
+            .as_ref()
+            .and_then(|hash| kept_by_hash.get(hash).copied())
+            .or_else(|| {
+                let threshold = similarity_threshold?;
+                let embedding = embeddings.get(&result.content_id)?;
+                kept.iter().position(|kept| {
+                    embeddings.get(&kept.content_id).map_or(false, |kept| {
+                        cosine_similarity(kept, embedding) >= threshold
+                    })
+                })
+            });
+        match duplicate_of {
+            Some(position) => {
+                if let Some(hash) = hash {
+                    kept_by_hash.entry(hash).or_insert(position);
+                }
+                let kept = &mut kept[position];
+                kept.duplicate_content_ids.push(result.content_id);
+                kept.duplicate_content_ids
+                    .extend(result.duplicate_content_ids);
+            }
+            None => {
+                if let Some(hash) = hash {
+                    kept_by_hash.insert(hash, kept.len());
+                }
+                kept.push(result);
+            }
+        }
+    }
+    kept
+}
+
+/// Replaces the confidence score of the results with a blend of the
+/// similarity and how recently the content was created, and returns the top
+/// k results by the new score.
+fn apply_recency_boost(
+    mut results: Vec<ScoredText>,
+    recency_boost: &api::RecencyBoost,
+    now: i64,
+    k: usize,
+) -> Vec<ScoredText> {
+    let weight = recency_boost
+        .weight
+        .unwrap_or(DEFAULT_RECENCY_WEIGHT)
+        .clamp(0.0, 1.0);
+    let half_life = recency_boost.half_life_secs.max(1) as f64;
+    for result in results.iter_mut() {
+        let age = (now - result.created_at).max(0) as f64;
+        let recency = 0.5_f64.powf(age / half_life) as f32;
+        result.confidence_score = (1.0 - weight) * result.confidence_score + weight * recency;
     }
+    results.sort_by(|a, b| b.confidence_score.total_cmp(&a.confidence_score));
+    results.truncate(k);
+    results
 }
 
 #[cfg(test)]
@@ -836,6 +4947,39 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_embedding_dimension_mismatch() {
+        assert!(EmbeddingDimensionMismatch::check(
+            "minilm",
+            "embeddings",
+            "content",
+            "embedding",
+            "index",
+            384,
+            384
+        )
+        .is_ok());
+        let err: anyhow::Error = EmbeddingDimensionMismatch::check(
+            "minilm",
+            "embeddings",
+            "content",
+            "embedding",
+            "index",
+            384,
+            768,
+        )
+        .unwrap_err()
+        .into();
+        let mismatch = err.downcast_ref::<EmbeddingDimensionMismatch>().unwrap();
+        assert_eq!(mismatch.content_id, "content");
+        assert_eq!((mismatch.expected, mismatch.actual), (384, 768));
+        assert_eq!(
+            err.to_string(),
+            "extractor minilm of extraction policy embeddings produced an embedding of dimension \
+             768 for output embedding of content content, but index index has dimension 384"
+        );
+    }
+
     #[test]
     fn test_combine_metadata() {
         let features = vec![
@@ -870,4 +5014,192 @@ mod tests {
 
         assert_eq!(combined, expected);
     }
+
+    #[test]
+    fn test_collapse_duplicates() {
+        let result = |content_id: &str, text: &str| ScoredText {
+            text: text.to_string(),
+            content_id: content_id.to_string(),
+            mime_type: mime::TEXT_PLAIN.to_string(),
+            labels: HashMap::new(),
+            confidence_score: 1.0,
+            external_id: String::from(""),
+            created_at: 0,
+            parent_id: String::from(""),
+            duplicate_content_ids: Vec::new(),
+        };
+        let results = || {
+            vec![
+                result("a", "same paragraph"),
+                result("b", "other paragraph"),
+                result("c", "same paragraph"),
+                result("d", "nearly the same paragraph"),
+                result("e", ""),
+                result("f", ""),
+            ]
+        };
+        let embeddings = HashMap::from([
+            ("a".to_string(), vec![1.0, 0.0]),
+            ("b".to_string(), vec![0.0, 1.0]),
+            ("d".to_string(), vec![0.99, 0.1]),
+        ]);
+
+        //  Only identical text is collapsed without a threshold, results
+        // without text are kept
+        let deduped = collapse_duplicates(results(), &embeddings, None);
+        assert_eq!(
+            deduped.iter().map(|r| r.content_id.as_str()).collect_vec(),
+            vec!["a", "b", "d", "e", "f"]
+        );
+        assert_eq!(deduped[0].duplicate_content_ids, vec!["c"]);
+
+        let deduped = collapse_duplicates(results(), &embeddings, Some(0.95));
+        assert_eq!(
+            deduped.iter().map(|r| r.content_id.as_str()).collect_vec(),
+            vec!["a", "b", "e", "f"]
+        );
+        assert_eq!(deduped[0].duplicate_content_ids, vec!["c", "d"]);
+        assert!(deduped[1].duplicate_content_ids.is_empty());
+    }
+
+    #[test]
+    fn test_apply_scores() {
+        let result = |content_id: &str| ScoredText {
+            text: String::from(""),
+            content_id: content_id.to_string(),
+            mime_type: mime::TEXT_PLAIN.to_string(),
+            labels: HashMap::new(),
+            confidence_score: 0.5,
+            external_id: String::from(""),
+            created_at: 0,
+            parent_id: String::from(""),
+            duplicate_content_ids: Vec::new(),
+        };
+        let scored = |content_id: &str, score: f32| ScoredCandidate {
+            content_id: content_id.to_string(),
+            score,
+        };
+        let results = apply_scores(
+            vec![result("a"), result("b"), result("c")],
+            vec![scored("a", 0.1), scored("c", 0.9)],
+        );
+        assert_eq!(
+            results.iter().map(|r| r.content_id.as_str()).collect_vec(),
+            vec!["c", "a"]
+        );
+        assert_eq!(results[0].confidence_score, 0.9);
+    }
+
+    #[test]
+    fn test_limit_results_per_parent() {
+        let result = |content_id: &str, parent_id: &str| ScoredText {
+            text: String::from(""),
+            content_id: content_id.to_string(),
+            mime_type: mime::TEXT_PLAIN.to_string(),
+            labels: HashMap::new(),
+            confidence_score: 1.0,
+            external_id: String::from(""),
+            created_at: 0,
+            parent_id: parent_id.to_string(),
+            duplicate_content_ids: Vec::new(),
+        };
+        let results = vec![
+            result("a1", "a"),
+            result("a2", "a"),
+            result("b1", "b"),
+            result("a3", "a"),
+            result("c", ""),
+            result("b2", "b"),
+        ];
+        let limited = limit_results_per_parent(results, 1);
+        assert_eq!(
+            limited.iter().map(|r| r.content_id.as_str()).collect_vec(),
+            vec!["a1", "b1", "c"]
+        );
+    }
+
+    #[test]
+    fn test_coordinator_request_id() {
+        assert_eq!(
+            coordinator_request_id("ns", "add_texts", Some("abc"), None),
+            "ns/add_texts/abc"
+        );
+        assert_eq!(
+            coordinator_request_id("ns", "add_texts", Some("abc"), Some("1")),
+            "ns/add_texts/abc/1"
+        );
+        //  The same client id sent to another namespace or endpoint is another
+        // request
+        assert_ne!(
+            coordinator_request_id("ns", "add_texts", Some("abc"), None),
+            coordinator_request_id("other", "add_texts", Some("abc"), None)
+        );
+        assert_ne!(
+            coordinator_request_id("ns", "add_texts", Some("abc"), None),
+            coordinator_request_id("ns", "upload_file", Some("abc"), None)
+        );
+        assert_ne!(
+            coordinator_request_id("ns", "add_texts", None, Some("1")),
+            coordinator_request_id("ns", "add_texts", None, Some("1"))
+        );
+    }
+
+    #[test]
+    fn test_make_preview() {
+        let text = format!("  {}", "é".repeat(CONTENT_PREVIEW_CHARS + 10));
+        let preview = DataManager::make_preview("text/plain; charset=utf-8", text.as_bytes());
+        assert_eq!(preview, "é".repeat(CONTENT_PREVIEW_CHARS));
+        assert_eq!(
+            DataManager::make_preview("application/json", b"{\"key\": 1}"),
+            "{\"key\": 1}"
+        );
+        assert_eq!(
+            DataManager::make_preview("application/pdf", b"%PDF-1.7"),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_apply_recency_boost() {
+        let result = |content_id: &str, confidence_score: f32, created_at: i64| ScoredText {
+            text: String::from(""),
+            content_id: content_id.to_string(),
+            mime_type: mime::TEXT_PLAIN.to_string(),
+            labels: HashMap::new(),
+            confidence_score,
+            external_id: String::from(""),
+            created_at,
+            parent_id: String::from(""),
+            duplicate_content_ids: Vec::new(),
+        };
+        let results = || {
+            vec![
+                result("old", 1.0, 0),
+                result("new", 0.625, 1000),
+                result("older", 0.75, -1000),
+            ]
+        };
+        let recency_boost = api::RecencyBoost {
+            half_life_secs: 1000,
+            weight: Some(0.5),
+        };
+
+        let boosted = apply_recency_boost(results(), &recency_boost, 1000, 2);
+        assert_eq!(boosted.len(), 2);
+        assert_eq!(boosted[0].content_id, "new");
+        assert_eq!(boosted[0].confidence_score, 0.8125);
+        assert_eq!(boosted[1].content_id, "old");
+        assert_eq!(boosted[1].confidence_score, 0.75);
+
+        //  Without any weight the similarity order is kept
+        let recency_boost = api::RecencyBoost {
+            half_life_secs: 1000,
+            weight: Some(0.0),
+        };
+        let boosted = apply_recency_boost(results(), &recency_boost, 1000, 3);
+        assert_eq!(
+            boosted.iter().map(|r| r.content_id.as_str()).collect_vec(),
+            vec!["old", "older", "new"]
+        );
+    }
 }
@@ -0,0 +1,326 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+
+use crate::{
+    server_config::EmbeddingWriteBufferConfig,
+    vectordbs::{VectorChunk, VectorDBTS},
+};
+
+//  A write without chunks is a flush, it's written right away along with the
+// writes buffered before it
+struct PendingWrite {
+    chunks: Vec<VectorChunk>,
+    done: oneshot::Sender<Result<(), String>>,
+}
+
+impl PendingWrite {
+    fn is_flush(&self) -> bool {
+        self.chunks.is_empty()
+    }
+}
+
+/// Buffers the embeddings written to every index and upserts them to the
+/// vector store in batches. Writers wait until the batch holding their
+/// embeddings is written, so a finished write is durable like an unbuffered
+/// one.
+pub struct EmbeddingWriteBuffer {
+    vector_db: VectorDBTS,
+    config: EmbeddingWriteBufferConfig,
+    queues: Mutex<HashMap<String, mpsc::UnboundedSender<PendingWrite>>>,
+}
+
+impl EmbeddingWriteBuffer {
+    pub fn new(vector_db: VectorDBTS, config: EmbeddingWriteBufferConfig) -> Self {
+        Self {
+            vector_db,
+            config,
+            queues: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn write(&self, index: &str, chunks: Vec<VectorChunk>) -> Result<()> {
+        if chunks.is_empty() {
+            return Ok(());
+        }
+        let (done_tx, done_rx) = oneshot::channel();
+        self.queue(index)
+            .send(PendingWrite {
+                chunks,
+                done: done_tx,
+            })
+            .map_err(|_| anyhow!("write buffer of index {} is closed", index))?;
+        done_rx
+            .await
+            .map_err(|_| anyhow!("write buffer of index {} dropped the write", index))?
+            .map_err(|e| anyhow!(e))
+    }
+
+    /// Writes the embeddings buffered for the index without waiting for the
+    /// batch to fill up, returning once they're written.
+    pub async fn flush(&self, index: &str) {
+        let queue = match self.queues.lock().unwrap().get(index) {
+            Some(queue) => queue.clone(),
+            None => return,
+        };
+        let (done_tx, done_rx) = oneshot::channel();
+        let flush = PendingWrite {
+            chunks: vec![],
+            done: done_tx,
+        };
+        //  A closed queue has no buffered writes left. Failed writes are
+        // reported to their writers, not to the flush.
+        if queue.send(flush).is_ok() {
+            let _ = done_rx.await;
+        }
+    }
+
+    /// Stops buffering writes to the index once the buffered ones are
+    /// written, e.g. after the index was dropped.
+    pub fn close(&self, index: &str) {
+        self.queues.lock().unwrap().remove(index);
+    }
+
+    fn queue(&self, index: &str) -> mpsc::UnboundedSender<PendingWrite> {
+        let mut queues = self.queues.lock().unwrap();
+        if let Some(queue) = queues.get(index) {
+            if !queue.is_closed() {
+                return queue.clone();
+            }
+        }
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_index_writer(
+            self.vector_db.clone(),
+            index.to_string(),
+            self.config.clone(),
+            rx,
+        ));
+        queues.insert(index.to_string(), tx.clone());
+        tx
+    }
+}
+
+async fn run_index_writer(
+    vector_db: VectorDBTS,
+    index: String,
+    config: EmbeddingWriteBufferConfig,
+    mut rx: mpsc::UnboundedReceiver<PendingWrite>,
+) {
+    let max_batch_size = config.max_batch_size.max(1);
+    let max_delay = Duration::from_millis(config.max_delay_ms);
+    let mut in_flight: Option<JoinHandle<()>> = None;
+    while let Some(first) = rx.recv().await {
+        let mut size = first.chunks.len();
+        let mut flush = first.is_flush();
+        let mut batch = vec![first];
+        let deadline = tokio::time::sleep(max_delay);
+        tokio::pin!(deadline);
+        while size < max_batch_size && !flush {
+            tokio::select! {
+                write = rx.recv() => match write {
+                    Some(write) => {
+                        size += write.chunks.len();
+                        flush = write.is_flush();
+                        batch.push(write);
+                    }
+                    None => break,
+                },
+                _ = &mut deadline => break,
+            }
+        }
+        //  The next batch is collected while the previous one is written. The
+        // batches of an index are still written in order, so the last write
+        // of a content wins.
+        if let Some(previous) = in_flight.take() {
+            let _ = previous.await;
+        }
+        in_flight = Some(tokio::spawn(write_batch(
+            vector_db.clone(),
+            index.clone(),
+            batch,
+        )));
+    }
+    if let Some(previous) = in_flight.take() {
+        let _ = previous.await;
+    }
+}
+
+async fn write_batch(vector_db: VectorDBTS, index: String, batch: Vec<PendingWrite>) {
+    let mut chunks = Vec::new();
+    let mut waiters = Vec::new();
+    for write in batch {
+        chunks.extend(write.chunks);
+        waiters.push(write.done);
+    }
+    let result = match chunks.is_empty() {
+        true => Ok(()),
+        false => vector_db
+            .add_embedding(&index, dedupe_chunks(chunks))
+            .await
+            .map_err(|e| format!("unable to write embeddings to index {}: {}", index, e)),
+    };
+    for waiter in waiters {
+        let _ = waiter.send(result.clone());
+    }
+}
+
+//  Vector stores may reject a batch upserting the same id twice, only the
+// last embedding of a content is kept
+fn dedupe_chunks(chunks: Vec<VectorChunk>) -> Vec<VectorChunk> {
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut deduped: Vec<VectorChunk> = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        match positions.get(&chunk.content_id) {
+            Some(&position) => deduped[position] = chunk,
+            None => {
+                positions.insert(chunk.content_id.clone(), deduped.len());
+                deduped.push(chunk);
+            }
+        }
+    }
+    deduped
+}
+
+impl std::fmt::Debug for EmbeddingWriteBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmbeddingWriteBuffer")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::future::join_all;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        server_config::EmbeddedHnswConfig,
+        vectordbs::{hnsw::EmbeddedHnsw, CreateIndexParams, IndexDistance, VectorDb},
+    };
+
+    fn chunk(content_id: &str, embedding: Vec<f32>) -> VectorChunk {
+        VectorChunk::new(content_id.to_string(), embedding, json!({}))
+    }
+
+    #[test]
+    fn test_dedupe_chunks() {
+        let chunks = dedupe_chunks(vec![
+            chunk("a", vec![1., 0.]),
+            chunk("b", vec![0., 1.]),
+            chunk("a", vec![0.5, 0.5]),
+        ]);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].content_id, "a");
+        assert_eq!(chunks[0].embedding, vec![0.5, 0.5]);
+        assert_eq!(chunks[1].content_id, "b");
+    }
+
+    #[tokio::test]
+    async fn test_buffered_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let vector_db: VectorDBTS = Arc::new(
+            EmbeddedHnsw::new(EmbeddedHnswConfig {
+                path: dir.path().to_str().unwrap().to_string(),
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        vector_db
+            .create_index(CreateIndexParams {
+                vectordb_index_name: "index".into(),
+                vector_dim: 2,
+                distance: IndexDistance::Cosine,
+                unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
+            })
+            .await
+            .unwrap();
+        let buffer = Arc::new(EmbeddingWriteBuffer::new(
+            vector_db.clone(),
+            EmbeddingWriteBufferConfig {
+                max_batch_size: 4,
+                max_delay_ms: 10,
+            },
+        ));
+
+        let writes = (0..10).map(|i| {
+            let buffer = buffer.clone();
+            async move {
+                buffer
+                    .write("index", vec![chunk(&i.to_string(), vec![i as f32, 1.])])
+                    .await
+            }
+        });
+        for result in join_all(writes).await {
+            result.unwrap();
+        }
+        assert_eq!(vector_db.num_vectors("index").await.unwrap(), 10);
+
+        //  Writes to a missing index fail for every writer of the batch
+        assert!(buffer
+            .write("missing", vec![chunk("a", vec![1., 0.])])
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_flush() {
+        let dir = tempfile::tempdir().unwrap();
+        let vector_db: VectorDBTS = Arc::new(
+            EmbeddedHnsw::new(EmbeddedHnswConfig {
+                path: dir.path().to_str().unwrap().to_string(),
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        vector_db
+            .create_index(CreateIndexParams {
+                vectordb_index_name: "index".into(),
+                vector_dim: 2,
+                distance: IndexDistance::Cosine,
+                unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
+            })
+            .await
+            .unwrap();
+        let buffer = Arc::new(EmbeddingWriteBuffer::new(
+            vector_db.clone(),
+            EmbeddingWriteBufferConfig {
+                max_batch_size: 100,
+                max_delay_ms: 60_000,
+            },
+        ));
+
+        //  Flushing an index without buffered writes returns right away
+        buffer.flush("index").await;
+
+        let write = tokio::spawn({
+            let buffer = buffer.clone();
+            async move { buffer.write("index", vec![chunk("a", vec![1., 0.])]).await }
+        });
+        tokio::task::yield_now().await;
+        assert_eq!(vector_db.num_vectors("index").await.unwrap(), 0);
+
+        //  The write is buffered until the batch is full or the delay passes,
+        // unless the index is flushed
+        buffer.flush("index").await;
+        assert_eq!(vector_db.num_vectors("index").await.unwrap(), 1);
+        write.await.unwrap().unwrap();
+    }
+}
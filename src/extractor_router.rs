@@ -5,23 +5,32 @@ use indexify_internal_api as internal_api;
 use indexify_proto::indexify_coordinator::GetExtractorCoordinatesRequest;
 use internal_api::ExtractResponse;
 
-use crate::{api::Content, coordinator_client::CoordinatorClient};
+use crate::{
+    api::Content,
+    coordinator_client::CoordinatorClient,
+    remote_extractors::RemoteExtractors,
+};
 
 const CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
 
 pub struct ExtractorRouter {
     coordinator_client: Arc<CoordinatorClient>,
+    remote_extractors: Arc<RemoteExtractors>,
     client: reqwest::Client,
 }
 
 impl ExtractorRouter {
-    pub fn new(coordinator_client: Arc<CoordinatorClient>) -> Result<Self> {
+    pub fn new(
+        coordinator_client: Arc<CoordinatorClient>,
+        remote_extractors: Arc<RemoteExtractors>,
+    ) -> Result<Self> {
         let request_client = reqwest::Client::builder()
             .connect_timeout(CONNECT_TIMEOUT)
             .build()
             .map_err(|e| anyhow!("unable to create request client: {}", e))?;
         Ok(Self {
             coordinator_client,
+            remote_extractors,
             client: request_client,
         })
     }
@@ -41,6 +50,9 @@ impl ExtractorRouter {
             },
             input_params,
         };
+        if let Some(remote_extractor) = self.remote_extractors.get(extractor_name) {
+            return remote_extractor.extract(&request).await;
+        }
 
         let req = GetExtractorCoordinatesRequest {
             extractor: extractor_name.to_string(),
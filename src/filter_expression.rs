@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// A boolean filter over the labels, the extracted metadata and the text of
+/// content, parsed from expressions like
+///
+/// `labels.topic = "news" AND NOT (metadata.author.name = "bob" OR text ~
+/// "breaking news")`
+///
+/// `AND` binds tighter than `OR`, and `NOT` tighter than both. Keywords are
+/// case insensitive. The predicates are
+/// - `labels.<key> = <value>` and `labels.<key> != <value>`
+/// - `metadata.<path> = <value>` and `metadata.<path> != <value>`, where the
+///   path is a list of dot separated keys into the extracted metadata and the
+///   value is a quoted string, a number, `true` or `false`
+/// - `text ~ "<phrase>"`, matching text containing the words of the phrase in
+///   order, ignoring case and punctuation
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterExpr {
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+    Not(Box<FilterExpr>),
+    Label {
+        key: String,
+        op: Comparison,
+        value: String,
+    },
+    Metadata {
+        path: Vec<String>,
+        op: Comparison,
+        value: Value,
+    },
+    Phrase(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+}
+
+impl FilterExpr {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let tokens = tokenize(expression)?;
+        if tokens.is_empty() {
+            return Err(anyhow!("filter expression is empty"));
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(anyhow!(
+                "unexpected {} in filter expression",
+                token.describe()
+            )),
+        }
+    }
+
+    /// Whether the expression needs the extracted metadata of the content to
+    /// be evaluated
+    pub fn references_metadata(&self) -> bool {
+        self.any_predicate(&|expr| matches!(expr, FilterExpr::Metadata { .. }))
+    }
+
+    /// Whether the expression needs the text of the content to be evaluated
+    pub fn references_text(&self) -> bool {
+        self.any_predicate(&|expr| matches!(expr, FilterExpr::Phrase(_)))
+    }
+
+    fn any_predicate(&self, predicate: &impl Fn(&FilterExpr) -> bool) -> bool {
+        match self {
+            FilterExpr::And(left, right) | FilterExpr::Or(left, right) => {
+                left.any_predicate(predicate) || right.any_predicate(predicate)
+            }
+            FilterExpr::Not(expr) => expr.any_predicate(predicate),
+            expr => predicate(expr),
+        }
+    }
+
+    /// Compiles the expression for the content store of the coordinator,
+    /// which only knows the labels of content
+    pub fn for_content_store(self) -> Result<Self> {
+        if self.references_metadata() || self.references_text() {
+            return Err(anyhow!(
+                "only label predicates can be used to filter content, metadata and text predicates can only be used when searching an index"
+            ));
+        }
+        Ok(self)
+    }
+
+    /// Evaluates the expression. Missing labels and metadata fields are not
+    /// equal to any value.
+    pub fn matches(&self, labels: &HashMap<String, String>, metadata: &Value, text: &str) -> bool {
+        match self {
+            FilterExpr::And(left, right) => {
+                left.matches(labels, metadata, text) && right.matches(labels, metadata, text)
+            }
+            FilterExpr::Or(left, right) => {
+                left.matches(labels, metadata, text) || right.matches(labels, metadata, text)
+            }
+            FilterExpr::Not(expr) => !expr.matches(labels, metadata, text),
+            FilterExpr::Label { key, op, value } => {
+                let equal = labels.get(key) == Some(value);
+                equal == (*op == Comparison::Eq)
+            }
+            FilterExpr::Metadata { path, op, value } => {
+                let equal = lookup(metadata, path)
+                    .map(|actual| values_equal(actual, value))
+                    .unwrap_or(false);
+                equal == (*op == Comparison::Eq)
+            }
+            FilterExpr::Phrase(phrase) => contains_phrase(text, phrase),
+        }
+    }
+
+    pub fn matches_labels(&self, labels: &HashMap<String, String>) -> bool {
+        self.matches(labels, &Value::Null, "")
+    }
+}
+
+fn lookup<'a>(metadata: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(metadata, |value, key| value.as_object()?.get(key))
+}
+
+fn values_equal(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Array(values), expected) => values.iter().any(|v| values_equal(v, expected)),
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (actual, expected) => actual == expected,
+    }
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+fn contains_phrase(text: &str, phrase: &str) -> bool {
+    let phrase = words(phrase);
+    if phrase.is_empty() {
+        return true;
+    }
+    words(text)
+        .windows(phrase.len())
+        .any(|window| window == phrase.as_slice())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LeftParen,
+    RightParen,
+    Eq,
+    Ne,
+    Tilde,
+    Quoted(String),
+    Word(String),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::LeftParen => "'('".to_string(),
+            Token::RightParen => "')'".to_string(),
+            Token::Eq => "'='".to_string(),
+            Token::Ne => "'!='".to_string(),
+            Token::Tilde => "'~'".to_string(),
+            Token::Quoted(s) => format!("\"{}\"", s),
+            Token::Word(s) => format!("'{}'", s),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LeftParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RightParen);
+            }
+            '=' => {
+                chars.next();
+                tokens.push(Token::Eq);
+            }
+            '~' => {
+                chars.next();
+                tokens.push(Token::Tilde);
+            }
+            '!' => {
+                chars.next();
+                if chars.next() != Some('=') {
+                    return Err(anyhow!("expected '=' after '!' in filter expression"));
+                }
+                tokens.push(Token::Ne);
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => value.push(escaped),
+                            None => {
+                                return Err(anyhow!("unterminated string in filter expression"))
+                            }
+                        },
+                        Some(c) => value.push(c),
+                        None => return Err(anyhow!("unterminated string in filter expression")),
+                    }
+                }
+                tokens.push(Token::Quoted(value));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "()=!~\"".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| anyhow!("unexpected end of filter expression"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn next_is_keyword(&self, keyword: &str) -> bool {
+        self.peek().map_or(false, |token| token.is_keyword(keyword))
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr> {
+        let mut expr = self.parse_and()?;
+        while self.next_is_keyword("or") {
+            self.pos += 1;
+            expr = FilterExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr> {
+        let mut expr = self.parse_unary()?;
+        while self.next_is_keyword("and") {
+            self.pos += 1;
+            expr = FilterExpr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<FilterExpr> {
+        if self.next_is_keyword("not") {
+            self.pos += 1;
+            return Ok(FilterExpr::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.peek() == Some(&Token::LeftParen) {
+            self.pos += 1;
+            let expr = self.parse_or()?;
+            return match self.next()? {
+                Token::RightParen => Ok(expr),
+                token => Err(anyhow!(
+                    "expected ')' but found {} in filter expression",
+                    token.describe()
+                )),
+            };
+        }
+        self.parse_predicate()
+    }
+
+    fn parse_predicate(&mut self) -> Result<FilterExpr> {
+        let field = match self.next()? {
+            Token::Word(field) => field,
+            token => {
+                return Err(anyhow!(
+                    "expected a field but found {} in filter expression",
+                    token.describe()
+                ))
+            }
+        };
+        let op = self.next()?;
+        if field.eq_ignore_ascii_case("text") {
+            if op != Token::Tilde {
+                return Err(anyhow!(
+                    "text can only be matched with '~' in filter expression"
+                ));
+            }
+            return match self.next()? {
+                Token::Quoted(phrase) => Ok(FilterExpr::Phrase(phrase)),
+                token => Err(anyhow!(
+                    "expected a quoted phrase but found {} in filter expression",
+                    token.describe()
+                )),
+            };
+        }
+        let op = match op {
+            Token::Eq => Comparison::Eq,
+            Token::Ne => Comparison::Ne,
+            token => {
+                return Err(anyhow!(
+                    "expected '=' or '!=' after {} but found {} in filter expression",
+                    field,
+                    token.describe()
+                ))
+            }
+        };
+        let value = self.next()?;
+        if let Some(key) = field.strip_prefix("labels.").filter(|key| !key.is_empty()) {
+            let value = match value {
+                Token::Quoted(value) | Token::Word(value) => value,
+                token => {
+                    return Err(anyhow!(
+                        "expected a value but found {} in filter expression",
+                        token.describe()
+                    ))
+                }
+            };
+            return Ok(FilterExpr::Label {
+                key: key.to_string(),
+                op,
+                value,
+            });
+        }
+        if let Some(path) = field.strip_prefix("metadata.") {
+            let path = path
+                .split('.')
+                .map(|key| key.to_string())
+                .collect::<Vec<_>>();
+            if path.iter().any(|key| key.is_empty()) {
+                return Err(anyhow!(
+                    "invalid metadata path {} in filter expression",
+                    field
+                ));
+            }
+            let value = match value {
+                Token::Quoted(value) => Value::String(value),
+                Token::Word(word) => match word.as_str() {
+                    "true" => Value::Bool(true),
+                    "false" => Value::Bool(false),
+                    _ => serde_json::from_str::<serde_json::Number>(&word)
+                        .map(Value::Number)
+                        .unwrap_or(Value::String(word)),
+                },
+                token => {
+                    return Err(anyhow!(
+                        "expected a value but found {} in filter expression",
+                        token.describe()
+                    ))
+                }
+            };
+            return Ok(FilterExpr::Metadata { path, op, value });
+        }
+        Err(anyhow!(
+            "unknown field {} in filter expression, expected labels.<key>, metadata.<path> or text",
+            field
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_precedence() {
+        let expr =
+            FilterExpr::parse("labels.a = 1 OR labels.b = 2 and not labels.c != \"x y\"").unwrap();
+        let label = |key: &str, op, value: &str| FilterExpr::Label {
+            key: key.to_string(),
+            op,
+            value: value.to_string(),
+        };
+        assert_eq!(
+            expr,
+            FilterExpr::Or(
+                Box::new(label("a", Comparison::Eq, "1")),
+                Box::new(FilterExpr::And(
+                    Box::new(label("b", Comparison::Eq, "2")),
+                    Box::new(FilterExpr::Not(Box::new(label("c", Comparison::Ne, "x y")))),
+                )),
+            )
+        );
+
+        assert!(FilterExpr::parse("").is_err());
+        assert!(FilterExpr::parse("labels.a = 1 AND").is_err());
+        assert!(FilterExpr::parse("(labels.a = 1").is_err());
+        assert!(FilterExpr::parse("source = web").is_err());
+        assert!(FilterExpr::parse("text = \"hello\"").is_err());
+        assert!(FilterExpr::parse("labels.a = \"unterminated").is_err());
+    }
+
+    #[test]
+    fn test_matches() {
+        let labels = HashMap::from([("topic".to_string(), "news".to_string())]);
+        let metadata = json!({"author": {"name": "alice"}, "pages": 12, "tags": ["a", "b"]});
+        let text = "Breaking: the quick, brown fox jumps!";
+
+        let matches = |expression: &str| {
+            FilterExpr::parse(expression)
+                .unwrap()
+                .matches(&labels, &metadata, text)
+        };
+        assert!(matches("labels.topic = news"));
+        assert!(!matches("labels.topic = sports"));
+        assert!(matches("labels.missing != news"));
+        assert!(matches("metadata.author.name = \"alice\""));
+        assert!(matches("metadata.pages = 12.0 AND metadata.tags = b"));
+        assert!(!matches("metadata.author.missing = alice"));
+        assert!(matches("text ~ \"QUICK brown fox\""));
+        assert!(!matches("text ~ \"brown quick\""));
+        assert!(matches(
+            "labels.topic = sports OR (metadata.pages != 3 AND NOT text ~ \"lazy dog\")"
+        ));
+
+        let expr = FilterExpr::parse("labels.topic = news AND text ~ \"fox\"").unwrap();
+        assert!(expr.references_text());
+        assert!(!expr.references_metadata());
+        assert!(expr.for_content_store().is_err());
+        assert!(FilterExpr::parse("NOT labels.topic = news")
+            .unwrap()
+            .for_content_store()
+            .is_ok());
+    }
+}
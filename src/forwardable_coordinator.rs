@@ -58,4 +58,20 @@ impl ForwardableCoordinator {
 
         Ok(())
     }
+
+    pub async fn delete_namespace(
+        &self,
+        leader_addr: &str,
+        namespace: &str,
+    ) -> Result<(), anyhow::Error> {
+        let req = indexify_coordinator::DeleteNamespaceRequest {
+            name: namespace.to_string(),
+        };
+
+        let mut client = self.coordinator_client.get_coordinator(leader_addr).await?;
+
+        client.delete_ns(req).await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,226 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::api;
+
+const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl GeoPoint {
+    /// Reads a location from a metadata value, either an object with
+    /// `lat`/`latitude` and `lon`/`lng`/`longitude` keys or a GeoJSON point.
+    /// Coordinates can be numbers or numeric strings.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let object = value.as_object()?;
+        if object.get("type").and_then(Value::as_str) == Some("Point") {
+            let coordinates = object.get("coordinates")?.as_array()?;
+            return Self::new(
+                coordinate(coordinates.get(1)?)?,
+                coordinate(coordinates.first()?)?,
+            );
+        }
+        let lat = ["lat", "latitude"]
+            .iter()
+            .find_map(|key| object.get(*key).and_then(coordinate))?;
+        let lon = ["lon", "lng", "longitude"]
+            .iter()
+            .find_map(|key| object.get(*key).and_then(coordinate))?;
+        Self::new(lat, lon)
+    }
+
+    fn new(lat: f64, lon: f64) -> Option<Self> {
+        ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon))
+            .then_some(Self { lat, lon })
+    }
+
+    /// Great circle distance in meters
+    pub fn distance(&self, other: &GeoPoint) -> f64 {
+        let d_lat = (other.lat - self.lat).to_radians();
+        let d_lon = (other.lon - self.lon).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) +
+            self.lat.to_radians().cos() *
+                other.lat.to_radians().cos() *
+                (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_METERS * a.sqrt().asin()
+    }
+}
+
+fn coordinate(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Checks that exactly one area is set and that its coordinates are valid
+pub fn validate(filter: &api::GeoFilter) -> Result<()> {
+    match (&filter.radius, &filter.bounding_box) {
+        (Some(radius), None) => {
+            GeoPoint::new(radius.lat, radius.lon)
+                .ok_or_else(|| anyhow!("invalid geo filter center"))?;
+            if radius.radius_meters.is_nan() || radius.radius_meters < 0.0 {
+                return Err(anyhow!("geo filter radius can't be negative"));
+            }
+            Ok(())
+        }
+        (None, Some(bounding_box)) => {
+            GeoPoint::new(bounding_box.min_lat, bounding_box.min_lon)
+                .and(GeoPoint::new(bounding_box.max_lat, bounding_box.max_lon))
+                .ok_or_else(|| anyhow!("invalid geo filter bounding box"))?;
+            if bounding_box.min_lat > bounding_box.max_lat {
+                return Err(anyhow!(
+                    "min_lat of the geo filter bounding box is greater than max_lat"
+                ));
+            }
+            Ok(())
+        }
+        _ => Err(anyhow!(
+            "geo filter has to specify exactly one of radius and bounding_box"
+        )),
+    }
+}
+
+/// Returns true if the location in the metadata is within the area of the
+/// filter. Metadata without a location never matches.
+pub fn matches(filter: &api::GeoFilter, metadata: &Value) -> bool {
+    let location = match &filter.field {
+        Some(field) => metadata.get(field).and_then(GeoPoint::from_value),
+        None => GeoPoint::from_value(metadata),
+    };
+    let Some(location) = location else {
+        return false;
+    };
+    if let Some(radius) = &filter.radius {
+        let center = GeoPoint {
+            lat: radius.lat,
+            lon: radius.lon,
+        };
+        return location.distance(&center) <= radius.radius_meters;
+    }
+    if let Some(bounding_box) = &filter.bounding_box {
+        let within_lat = (bounding_box.min_lat..=bounding_box.max_lat).contains(&location.lat);
+        let within_lon = if bounding_box.min_lon <= bounding_box.max_lon {
+            (bounding_box.min_lon..=bounding_box.max_lon).contains(&location.lon)
+        } else {
+            location.lon >= bounding_box.min_lon || location.lon <= bounding_box.max_lon
+        };
+        return within_lat && within_lon;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn radius_filter(
+        field: Option<&str>,
+        lat: f64,
+        lon: f64,
+        radius_meters: f64,
+    ) -> api::GeoFilter {
+        api::GeoFilter {
+            field: field.map(|f| f.to_string()),
+            radius: Some(api::GeoRadius {
+                lat,
+                lon,
+                radius_meters,
+            }),
+            bounding_box: None,
+        }
+    }
+
+    fn bounding_box_filter(
+        min_lat: f64,
+        min_lon: f64,
+        max_lat: f64,
+        max_lon: f64,
+    ) -> api::GeoFilter {
+        api::GeoFilter {
+            field: None,
+            radius: None,
+            bounding_box: Some(api::GeoBoundingBox {
+                min_lat,
+                min_lon,
+                max_lat,
+                max_lon,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_location_formats() {
+        let expected = Some(GeoPoint {
+            lat: 51.5,
+            lon: -0.12,
+        });
+        assert_eq!(
+            GeoPoint::from_value(&json!({"lat": 51.5, "lon": -0.12})),
+            expected
+        );
+        assert_eq!(
+            GeoPoint::from_value(&json!({"latitude": "51.5", "longitude": "-0.12"})),
+            expected
+        );
+        assert_eq!(
+            GeoPoint::from_value(&json!({"type": "Point", "coordinates": [-0.12, 51.5]})),
+            expected
+        );
+        assert_eq!(GeoPoint::from_value(&json!({"lat": 51.5})), None);
+        assert_eq!(
+            GeoPoint::from_value(&json!({"lat": 91.0, "lon": 0.0})),
+            None
+        );
+    }
+
+    #[test]
+    fn test_radius_filter() {
+        //  London to Paris is about 344km
+        let london = json!({"location": {"lat": 51.5074, "lon": -0.1278}, "name": "london"});
+        let paris_center = (48.8566, 2.3522);
+        let filter = radius_filter(Some("location"), paris_center.0, paris_center.1, 350_000.0);
+        assert!(matches(&filter, &london));
+        let filter = radius_filter(Some("location"), paris_center.0, paris_center.1, 300_000.0);
+        assert!(!matches(&filter, &london));
+
+        //  The location is looked up in the given field only
+        let filter = radius_filter(None, paris_center.0, paris_center.1, 350_000.0);
+        assert!(!matches(&filter, &london));
+        assert!(!matches(&filter, &json!({"name": "no location"})));
+    }
+
+    #[test]
+    fn test_bounding_box_filter() {
+        let filter = bounding_box_filter(40.0, -10.0, 60.0, 10.0);
+        assert!(matches(&filter, &json!({"lat": 51.5, "lon": -0.12})));
+        assert!(!matches(&filter, &json!({"lat": 35.0, "lon": 0.0})));
+
+        //  Boxes crossing the antimeridian wrap around
+        let filter = bounding_box_filter(-50.0, 170.0, -30.0, -170.0);
+        assert!(matches(&filter, &json!({"lat": -41.0, "lon": 175.0})));
+        assert!(matches(&filter, &json!({"lat": -41.0, "lon": -175.0})));
+        assert!(!matches(&filter, &json!({"lat": -41.0, "lon": 0.0})));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&radius_filter(None, 0.0, 0.0, 10.0)).is_ok());
+        assert!(validate(&radius_filter(None, 100.0, 0.0, 10.0)).is_err());
+        assert!(validate(&radius_filter(None, 0.0, 0.0, -1.0)).is_err());
+        assert!(validate(&bounding_box_filter(10.0, 0.0, 0.0, 10.0)).is_err());
+        let mut filter = radius_filter(None, 0.0, 0.0, 10.0);
+        filter.bounding_box = bounding_box_filter(0.0, 0.0, 10.0, 10.0).bounding_box;
+        assert!(validate(&filter).is_err());
+        filter.radius = None;
+        filter.bounding_box = None;
+        assert!(validate(&filter).is_err());
+    }
+}
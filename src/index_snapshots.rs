@@ -0,0 +1,157 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// First line of a snapshot, describing the index it was taken of.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    pub namespace: String,
+    pub index: String,
+    pub table_name: String,
+    //  Schema of the index as stored by the coordinator
+    pub schema: String,
+    pub vector_store: String,
+    pub created_at: u64,
+}
+
+/// An embedding of the snapshot, one per line after the header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotChunk {
+    pub content_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub hash: String,
+    //  Content sharing the embedding through a chunk reference, the owner
+    // first. Empty if the embedding isn't shared.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content_ids: Vec<String>,
+    pub embedding: Vec<f32>,
+    pub metadata: serde_json::Value,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub vectors: HashMap<String, Vec<f32>>,
+}
+
+impl SnapshotChunk {
+    /// All the content the embedding belongs to
+    pub fn content_ids(&self) -> Vec<&String> {
+        match self.content_ids.is_empty() {
+            true => vec![&self.content_id],
+            false => self.content_ids.iter().collect(),
+        }
+    }
+
+    /// The content the embedding is restored under, the first content of
+    /// the chunk which still exists.
+    pub fn restore_owner(&self, existing: &HashSet<String>) -> Option<&String> {
+        self.content_ids()
+            .into_iter()
+            .find(|id| existing.contains(*id))
+    }
+}
+
+/// Embeddings of an index and the chunk references between them, stored in
+/// the blob store as JSON lines so an index can be restored later.
+#[derive(Debug)]
+pub struct IndexSnapshot {
+    pub header: SnapshotHeader,
+    chunks: Vec<SnapshotChunk>,
+    //  Position of the chunk of every content sharing an embedding
+    positions: HashMap<String, usize>,
+}
+
+impl IndexSnapshot {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let mut lines = bytes.split(|b| *b == b'\n').filter(|line| !line.is_empty());
+        let header: SnapshotHeader = parse_line(lines.next(), "header")?;
+        let mut chunks = Vec::new();
+        let mut positions = HashMap::new();
+        for line in lines {
+            let chunk: SnapshotChunk = parse_line(Some(line), "chunk")?;
+            for content_id in chunk.content_ids() {
+                positions.insert(content_id.clone(), chunks.len());
+            }
+            chunks.push(chunk);
+        }
+        Ok(Self {
+            header,
+            chunks,
+            positions,
+        })
+    }
+
+    pub fn chunk(&self, content_id: &str) -> Option<&SnapshotChunk> {
+        self.positions
+            .get(content_id)
+            .map(|position| &self.chunks[*position])
+    }
+}
+
+pub fn snapshot_key(namespace: &str, index: &str, created_at: u64) -> String {
+    format!("{}_{}_{}.snapshot.jsonl", namespace, index, created_at)
+}
+
+/// Serializes the header or a chunk of a snapshot as a line
+pub fn to_line<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+fn parse_line<T: DeserializeOwned>(line: Option<&[u8]>, kind: &str) -> Result<T> {
+    let line = line.ok_or(anyhow!("snapshot has no {}", kind))?;
+    serde_json::from_slice(line).map_err(|e| anyhow!("unable to parse snapshot {}: {}", kind, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let header = SnapshotHeader {
+            namespace: "default".to_string(),
+            index: "embeddings.embedding".to_string(),
+            table_name: "default.embeddings.embedding".to_string(),
+            schema: json!({"dim": 2, "distance": "cosine"}).to_string(),
+            vector_store: "qdrant".to_string(),
+            created_at: 10,
+        };
+        let shared = SnapshotChunk {
+            content_id: "a".to_string(),
+            hash: "hash_a".to_string(),
+            content_ids: vec!["a".to_string(), "b".to_string()],
+            embedding: vec![1., 0.],
+            metadata: json!({"topic": "a"}),
+            vectors: HashMap::from([("title".to_string(), vec![0., 1.])]),
+        };
+        let single = SnapshotChunk {
+            content_id: "c".to_string(),
+            hash: "".to_string(),
+            content_ids: vec![],
+            embedding: vec![0., 1.],
+            metadata: json!({}),
+            vectors: HashMap::new(),
+        };
+        let mut bytes = to_line(&header).unwrap();
+        bytes.extend(to_line(&shared).unwrap());
+        bytes.extend(to_line(&single).unwrap());
+
+        let snapshot = IndexSnapshot::parse(&bytes).unwrap();
+        assert_eq!(snapshot.header, header);
+        assert_eq!(snapshot.chunks.len(), 2);
+        assert_eq!(snapshot.chunk("b"), Some(&shared));
+        assert_eq!(snapshot.chunk("c"), Some(&single));
+        assert!(snapshot.chunk("d").is_none());
+
+        //  The embedding is restored under the next content once the owner is
+        // deleted
+        let existing = HashSet::from(["b".to_string(), "c".to_string()]);
+        assert_eq!(shared.restore_owner(&existing), Some(&"b".to_string()));
+        assert_eq!(single.restore_owner(&existing), Some(&"c".to_string()));
+        assert!(shared.restore_owner(&HashSet::new()).is_none());
+
+        assert!(IndexSnapshot::parse(b"").is_err());
+    }
+}
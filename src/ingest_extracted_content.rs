@@ -3,13 +3,14 @@ use std::collections::HashMap;
 use anyhow::{anyhow, Result};
 use axum_typed_websockets::{Message, WebSocket};
 use indexify_proto::indexify_coordinator;
+use sha2::{Digest, Sha256};
 use tokio::io::AsyncWriteExt;
 use tracing::info;
 
 use crate::{
     api::*,
     blob_storage::StoragePartWriter,
-    data_manager::DataManager,
+    data_manager::{DataManager, CONTENT_PREVIEW_BYTES},
     server::NamespaceEndpointState,
 };
 
@@ -18,7 +19,9 @@ struct Writing {
     created_at: i64,
     file_name: String,
     file_size: u64,
+    hasher: Sha256,
     id: String, // DataManager ID
+    preview_bytes: Vec<u8>,
     writer: StoragePartWriter,
 }
 
@@ -83,11 +86,17 @@ impl IngestExtractedContentState {
             .data_manager
             .blob_store_writer(&metadata.namespace, &file_name)
             .await?;
+        self.state
+            .data_manager
+            .create_ingest_intent(&metadata.namespace, &id, &writer.url)
+            .await?;
         self.frame_state = FrameState::Writing(Writing {
             created_at: ts as i64,
             file_name,
             file_size: 0,
+            hasher: Sha256::new(),
             id,
+            preview_bytes: Vec::new(),
             writer,
         });
         Ok(())
@@ -126,6 +135,12 @@ impl IngestExtractedContentState {
             )),
             FrameState::Writing(frame_state) => {
                 frame_state.file_size += payload.bytes.len() as u64;
+                frame_state.hasher.update(&payload.bytes);
+                let remaining =
+                    CONTENT_PREVIEW_BYTES.saturating_sub(frame_state.preview_bytes.len());
+                frame_state
+                    .preview_bytes
+                    .extend_from_slice(&payload.bytes[..remaining.min(payload.bytes.len())]);
                 frame_state
                     .writer
                     .writer
@@ -161,6 +176,20 @@ impl IngestExtractedContentState {
                 frame_state.writer.writer.shutdown().await?;
 
                 let metadata = self.ingest_metadata.as_ref().unwrap();
+                let preview =
+                    DataManager::make_preview(&payload.content_type, &frame_state.preview_bytes);
+                let hash = DataManager::content_hash(frame_state.hasher.clone());
+                let storage_url = self
+                    .state
+                    .data_manager
+                    .store_content_addressed(
+                        &metadata.namespace,
+                        &frame_state.id,
+                        &frame_state.file_name,
+                        &hash,
+                        false,
+                    )
+                    .await?;
                 let content_metadata = indexify_coordinator::ContentMetadata {
                     id: frame_state.id.clone(),
                     file_name: frame_state.file_name.clone(),
@@ -168,11 +197,15 @@ impl IngestExtractedContentState {
                     namespace: metadata.namespace.clone(),
                     mime: payload.content_type,
                     size_bytes: frame_state.file_size,
-                    storage_url: frame_state.writer.url.clone(),
+                    storage_url,
                     labels: payload.labels,
                     source: metadata.extraction_policy.clone(),
                     created_at: frame_state.created_at,
                     extraction_policy_ids: HashMap::new(),
+                    external_id: "".to_string(),
+                    preview,
+                    hash,
+                    expires_at: 0,
                 };
                 self.state
                     .data_manager
@@ -200,6 +233,7 @@ impl IngestExtractedContentState {
                 .await?
                 .get_content_metadata(indexify_coordinator::GetContentMetadataRequest {
                     content_list: vec![content_id.clone()],
+                    consistency: indexify_coordinator::ReadConsistency::Bounded as i32,
                 })
                 .await?
                 .into_inner()
@@ -325,7 +359,9 @@ mod tests {
         blob_storage::{BlobStorage, ContentReader},
         coordinator_client::CoordinatorClient,
         data_manager::DataManager,
+        keyword_index::KeywordIndex,
         metadata_storage::{self, MetadataReaderTS, MetadataStorageTS},
+        remote_extractors::RemoteExtractors,
         server::NamespaceEndpointState,
         server_config::{IndexStoreKind, ServerConfig},
         vector_index::VectorIndexManager,
@@ -341,9 +377,13 @@ mod tests {
         config.index_config.qdrant_config = Some(Default::default());
         config.blob_storage = crate::blob_storage::BlobStorageConfig {
             s3: None,
+            gcs: None,
             disk: Some(crate::blob_storage::DiskStorageConfig {
                 path: "/tmp/indexify-test".to_string(),
             }),
+            cache: None,
+            compression: None,
+            namespaces: Default::default(),
         };
         config
     }
@@ -381,10 +421,24 @@ mod tests {
     async fn new_endpoint_state() -> Result<NamespaceEndpointState> {
         let config = make_test_config();
         let vector_db = vectordbs::create_vectordb(config.index_config.clone()).await?;
+        let keyword_index = Arc::new(KeywordIndex::new(
+            &config
+                .index_config
+                .keyword_index_config
+                .clone()
+                .unwrap_or_default(),
+        )?);
         let coordinator_client = Arc::new(CoordinatorClient::new(&config.coordinator_addr));
         let vector_index_manager = Arc::new(
-            VectorIndexManager::new(coordinator_client.clone(), vector_db.clone())
-                .map_err(|e| anyhow!("unable to create vector index {}", e))?,
+            VectorIndexManager::new(
+                coordinator_client.clone(),
+                vector_db.clone(),
+                keyword_index,
+                Arc::new(RemoteExtractors::default()),
+                config.index_config.write_buffer.clone(),
+                Arc::new(ContentReader::new()),
+            )
+            .map_err(|e| anyhow!("unable to create vector index {}", e))?,
         );
         let metadata_index_manager: MetadataStorageTS =
             metadata_storage::from_config(&config.metadata_storage)?;
@@ -397,11 +451,13 @@ mod tests {
             metadata_reader,
             blob_storage,
             coordinator_client.clone(),
+            "test_ingestion_server",
         ));
         let namespace_endpoint_state = NamespaceEndpointState {
             data_manager: data_manager.clone(),
             coordinator_client: coordinator_client.clone(),
             content_reader: Arc::new(ContentReader::new()),
+            remote_extractors: Arc::new(RemoteExtractors::default()),
         };
         Ok(namespace_endpoint_state)
     }
@@ -443,6 +499,7 @@ mod tests {
             executor_id: "test".to_string(),
             task_outcome: TaskOutcome::Success,
             index_tables: vec!["test".to_string()],
+            request_id: None,
         };
         ingest_state.begin(payload.clone());
         let new_payload = ingest_state.ingest_metadata.clone().unwrap();
@@ -521,6 +578,11 @@ mod tests {
         let schema = indexify_internal_api::EmbeddingSchema {
             dim: 3,
             distance: "cosine".to_string(),
+            quantization: None,
+            named_vectors: vec![],
+            ttl_secs: None,
+            sparse: false,
+            index_params: None,
         };
 
         let _ = ingest_state
@@ -548,6 +610,7 @@ mod tests {
             executor_id: "test".to_string(),
             task_outcome: TaskOutcome::Success,
             index_tables: vec!["test_index1".to_string()],
+            request_id: None,
         };
 
         ingest_state.begin(payload.clone());
@@ -647,6 +710,11 @@ mod tests {
         let schema = indexify_internal_api::EmbeddingSchema {
             dim: 3,
             distance: "cosine".to_string(),
+            quantization: None,
+            named_vectors: vec![],
+            ttl_secs: None,
+            sparse: false,
+            index_params: None,
         };
 
         let _ = ingest_state
@@ -674,6 +742,7 @@ mod tests {
             executor_id: "test".to_string(),
             task_outcome: TaskOutcome::Success,
             index_tables: vec!["test_index1".to_string()],
+            request_id: None,
         };
 
         ingest_state.begin(payload.clone());
@@ -0,0 +1,307 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use anyhow::{anyhow, Result};
+use indexify_internal_api as internal_api;
+use tantivy::{
+    collector::TopDocs,
+    doc,
+    query::QueryParser,
+    schema::{Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, STORED, STRING},
+    tokenizer::TokenizerManager,
+    Index,
+    IndexReader,
+    IndexWriter,
+    ReloadPolicy,
+    Term,
+};
+use tracing::info;
+
+use crate::{
+    server_config::KeywordIndexConfig,
+    vectordbs::{IndexStats, SearchResult},
+};
+
+const CONTENT_ID_FIELD: &str = "content_id";
+
+const TEXT_FIELD: &str = "text";
+
+const WRITER_MEMORY_BUDGET: usize = 50_000_000;
+
+/// Returns true if the index schema is the schema of a keyword index rather
+/// than an embedding index.
+pub fn is_keyword_schema(schema: &str) -> bool {
+    serde_json::from_str::<internal_api::KeywordIndexSchema>(schema).is_ok()
+}
+
+struct KeywordTable {
+    index: Index,
+    writer: Mutex<IndexWriter>,
+    reader: IndexReader,
+    content_id: Field,
+    text: Field,
+}
+
+impl KeywordTable {
+    fn new(index: Index) -> Result<Self> {
+        let schema = index.schema();
+        let content_id = schema.get_field(CONTENT_ID_FIELD)?;
+        let text = schema.get_field(TEXT_FIELD)?;
+        let writer = index.writer_with_num_threads(1, WRITER_MEMORY_BUDGET)?;
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        Ok(Self {
+            index,
+            writer: Mutex::new(writer),
+            reader,
+            content_id,
+            text,
+        })
+    }
+}
+
+/// Full text indexes stored on local disk with tantivy, one directory per
+/// index table. Every piece of content is stored as a single document keyed
+/// by the content id, so writing the same content again replaces it.
+pub struct KeywordIndex {
+    path: PathBuf,
+    tables: RwLock<HashMap<String, Arc<KeywordTable>>>,
+}
+
+impl fmt::Debug for KeywordIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeywordIndex")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl KeywordIndex {
+    pub fn new(config: &KeywordIndexConfig) -> Result<Self> {
+        std::fs::create_dir_all(&config.path)?;
+        Ok(Self {
+            path: PathBuf::from(&config.path),
+            tables: RwLock::new(HashMap::new()),
+        })
+    }
+
+    pub fn create_index(
+        &self,
+        table_name: &str,
+        schema: &internal_api::KeywordIndexSchema,
+    ) -> Result<()> {
+        if self.has_index(table_name) {
+            return Ok(());
+        }
+        if TokenizerManager::default().get(&schema.tokenizer).is_none() {
+            return Err(anyhow!("unknown tokenizer: {}", schema.tokenizer));
+        }
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field(CONTENT_ID_FIELD, STRING | STORED);
+        //  Positions are indexed so that phrase queries work
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(&schema.tokenizer)
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+        schema_builder.add_text_field(TEXT_FIELD, text_options);
+
+        let path = self.path.join(table_name);
+        std::fs::create_dir_all(&path)?;
+        let index = Index::create_in_dir(&path, schema_builder.build())?;
+        info!("created keyword index: {}", table_name);
+        self.tables
+            .write()
+            .unwrap()
+            .insert(table_name.to_string(), Arc::new(KeywordTable::new(index)?));
+        Ok(())
+    }
+
+    pub fn has_index(&self, table_name: &str) -> bool {
+        self.tables.read().unwrap().contains_key(table_name) ||
+            self.path.join(table_name).join("meta.json").exists()
+    }
+
+    //  Indexes created before a restart are opened on first use
+    fn table(&self, table_name: &str) -> Result<Arc<KeywordTable>> {
+        if let Some(table) = self.tables.read().unwrap().get(table_name) {
+            return Ok(table.clone());
+        }
+        let mut tables = self.tables.write().unwrap();
+        if let Some(table) = tables.get(table_name) {
+            return Ok(table.clone());
+        }
+        if !self.path.join(table_name).join("meta.json").exists() {
+            return Err(anyhow!("keyword index {} not found", table_name));
+        }
+        let index = Index::open_in_dir(self.path.join(table_name))?;
+        let table = Arc::new(KeywordTable::new(index)?);
+        tables.insert(table_name.to_string(), table.clone());
+        Ok(table)
+    }
+
+    /// Adds the text of each content id to the index, replacing any text
+    /// previously written for the same content.
+    pub fn add_texts(&self, table_name: &str, texts: Vec<(String, String)>) -> Result<()> {
+        let table = self.table(table_name)?;
+        let mut writer = table.writer.lock().unwrap();
+        for (content_id, text) in texts {
+            writer.delete_term(Term::from_field_text(table.content_id, &content_id));
+            writer.add_document(doc!(
+                table.content_id => content_id,
+                table.text => text,
+            ))?;
+        }
+        writer.commit()?;
+        table.reader.reload()?;
+        Ok(())
+    }
+
+    pub fn remove_content(&self, table_name: &str, content_id: &str) -> Result<()> {
+        let table = self.table(table_name)?;
+        let mut writer = table.writer.lock().unwrap();
+        writer.delete_term(Term::from_field_text(table.content_id, content_id));
+        writer.commit()?;
+        table.reader.reload()?;
+        Ok(())
+    }
+
+    /// Runs the query with tantivy's query syntax, which supports exact
+    /// phrases, boolean operators and required or excluded terms. Results
+    /// are scored with BM25.
+    pub fn search(&self, table_name: &str, query: &str, k: usize) -> Result<Vec<SearchResult>> {
+        let table = self.table(table_name)?;
+        let query_parser = QueryParser::for_index(&table.index, vec![table.text]);
+        let query = query_parser
+            .parse_query(query)
+            .map_err(|e| anyhow!("invalid keyword query: {}", e))?;
+        let searcher = table.reader.searcher();
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(k))?;
+        let mut results = Vec::new();
+        for (score, address) in top_docs {
+            let document = searcher.doc(address)?;
+            if let Some(content_id) = document
+                .get_first(table.content_id)
+                .and_then(|value| value.as_text())
+            {
+                results.push(SearchResult {
+                    content_id: content_id.to_string(),
+                    confidence_score: score,
+                });
+            }
+        }
+        Ok(results)
+    }
+
+    pub fn stats(&self, table_name: &str) -> Result<IndexStats> {
+        let table = self.table(table_name)?;
+        let searcher = table.reader.searcher();
+        Ok(IndexStats {
+            num_vectors: searcher.num_docs(),
+            shard_count: Some(1),
+            segment_count: Some(searcher.segment_readers().len() as u64),
+            memory_bytes: None,
+        })
+    }
+
+    /// Opens an index created before a restart, so the first search doesn't
+    /// have to.
+    pub fn warm_up(&self, table_name: &str) -> Result<()> {
+        self.table(table_name)?;
+        Ok(())
+    }
+
+    pub fn drop_index(&self, table_name: &str) -> Result<()> {
+        self.tables.write().unwrap().remove(table_name);
+        let path = self.path.join(table_name);
+        if path.exists() {
+            std::fs::remove_dir_all(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn content_ids(results: Vec<SearchResult>) -> Vec<String> {
+        results.into_iter().map(|r| r.content_id).collect()
+    }
+
+    #[test]
+    fn test_keyword_index() -> Result<()> {
+        let dir = tempdir()?;
+        let keyword_index = KeywordIndex::new(&KeywordIndexConfig {
+            path: dir.path().to_str().unwrap().to_string(),
+        })?;
+        let table = "default.chunks.keyword_index";
+        keyword_index.create_index(table, &internal_api::KeywordIndexSchema::default())?;
+        assert!(keyword_index.has_index(table));
+        keyword_index.add_texts(
+            table,
+            vec![
+                ("1".to_string(), "the quick brown fox".to_string()),
+                ("2".to_string(), "the lazy brown dog".to_string()),
+                ("3".to_string(), "a quick lazy cat".to_string()),
+            ],
+        )?;
+
+        let mut results = content_ids(keyword_index.search(table, "brown", 10)?);
+        results.sort();
+        assert_eq!(results, vec!["1", "2"]);
+        assert_eq!(
+            content_ids(keyword_index.search(table, "quick AND lazy", 10)?),
+            vec!["3"]
+        );
+        assert_eq!(
+            content_ids(keyword_index.search(table, "\"brown dog\"", 10)?),
+            vec!["2"]
+        );
+        assert_eq!(
+            content_ids(keyword_index.search(table, "+brown -fox", 10)?),
+            vec!["2"]
+        );
+
+        //  Writing the same content again replaces the text
+        keyword_index.add_texts(table, vec![("1".to_string(), "a red fox".to_string())])?;
+        assert_eq!(
+            content_ids(keyword_index.search(table, "brown", 10)?),
+            vec!["2"]
+        );
+
+        keyword_index.remove_content(table, "2")?;
+        assert!(keyword_index.search(table, "brown", 10)?.is_empty());
+        assert_eq!(keyword_index.stats(table)?.num_vectors, 2);
+
+        keyword_index.drop_index(table)?;
+        assert!(!keyword_index.has_index(table));
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_keyword_schema() {
+        let keyword_schema =
+            serde_json::to_string(&internal_api::KeywordIndexSchema::default()).unwrap();
+        assert!(is_keyword_schema(&keyword_schema));
+        let embedding_schema = serde_json::to_string(&internal_api::EmbeddingSchema {
+            dim: 384,
+            distance: "cosine".to_string(),
+            quantization: None,
+            named_vectors: vec![],
+            ttl_secs: None,
+            sparse: false,
+            index_params: None,
+        })
+        .unwrap();
+        assert!(!is_keyword_schema(&embedding_schema));
+    }
+}
@@ -8,6 +8,7 @@ use tracing_subscriber::{
 
 pub mod coordinator_filters;
 pub mod coordinator_service;
+pub mod filter_expression;
 pub mod metrics;
 pub mod server;
 pub mod server_config;
@@ -22,19 +23,28 @@ mod cmd;
 mod coordinator;
 mod coordinator_client;
 mod data_manager;
+mod embedding_write_buffer;
 mod extractor_router;
 mod forwardable_coordinator;
 mod garbage_collector;
+mod geo_filters;
 mod grpc_helper;
+mod index_snapshots;
+mod metadata_export;
 mod metadata_storage;
+mod namespace_templates;
+mod processing_slas;
+mod remote_extractors;
 mod scheduler;
 mod test_util;
 //mod tls;
 mod ingest_extracted_content;
+mod keyword_index;
 mod tonic_streamer;
 mod utils;
 mod vector_index;
 mod vectordbs;
+mod wasm_plugins;
 
 //  test modules
 #[cfg(test)]
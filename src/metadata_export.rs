@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use arrow_array::{ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+
+use crate::metadata_storage::ExtractedMetadata;
+
+/// Metadata of a content to export, with the text of the content if it was
+/// requested and the content is text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportRow {
+    pub metadata: ExtractedMetadata,
+    pub text: Option<String>,
+}
+
+pub fn export_key(namespace: &str, content_source: &str, created_at: u64) -> String {
+    format!(
+        "{}_{}_{}.metadata.parquet",
+        namespace, content_source, created_at
+    )
+}
+
+fn schema(include_text: bool) -> Schema {
+    let mut fields = vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("content_id", DataType::Utf8, false),
+        Field::new("parent_content_id", DataType::Utf8, false),
+        Field::new("content_source", DataType::Utf8, false),
+        Field::new("extractor", DataType::Utf8, false),
+        Field::new("extraction_policy", DataType::Utf8, false),
+        //  Metadata has no fixed schema, engines reading the file parse it as
+        // JSON, e.g. with json_extract in DuckDB or from_json in Spark
+        Field::new("metadata", DataType::Utf8, false),
+    ];
+    if include_text {
+        fields.push(Field::new("text", DataType::Utf8, true));
+    }
+    Schema::new(fields)
+}
+
+/// Writes the rows as a single Parquet file compressed with snappy. The text
+/// column is only written if `include_text` is set.
+pub fn to_parquet(rows: &[ExportRow], include_text: bool) -> Result<Vec<u8>> {
+    let schema = Arc::new(schema(include_text));
+    let column = |value: fn(&ExportRow) -> &str| -> ArrayRef {
+        Arc::new(StringArray::from_iter_values(rows.iter().map(value)))
+    };
+    let metadata = rows
+        .iter()
+        .map(|row| row.metadata.metadata.to_string())
+        .collect::<Vec<_>>();
+    let mut columns = vec![
+        column(|row| row.metadata.id.as_str()),
+        column(|row| row.metadata.content_id.as_str()),
+        column(|row| row.metadata.parent_content_id.as_str()),
+        column(|row| row.metadata.content_source.as_str()),
+        column(|row| row.metadata.extractor_name.as_str()),
+        column(|row| row.metadata.extraction_policy.as_str()),
+        Arc::new(StringArray::from_iter_values(metadata)) as ArrayRef,
+    ];
+    if include_text {
+        columns.push(Arc::new(StringArray::from(
+            rows.iter()
+                .map(|row| row.text.as_deref())
+                .collect::<Vec<_>>(),
+        )));
+    }
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut bytes, schema, Some(properties))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::Array;
+    use bytes::Bytes;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_to_parquet() {
+        let rows = vec![
+            ExportRow {
+                metadata: ExtractedMetadata::new(
+                    "a",
+                    "",
+                    "invoices",
+                    json!({"invoice_number": "INV-1", "total": 10.5}),
+                    "tensorlake/invoice",
+                    "invoices",
+                ),
+                text: Some("invoice 1".to_string()),
+            },
+            ExportRow {
+                metadata: ExtractedMetadata::new(
+                    "b",
+                    "a",
+                    "invoices",
+                    json!({"invoice_number": "INV-2"}),
+                    "tensorlake/invoice",
+                    "invoices",
+                ),
+                text: None,
+            },
+        ];
+        let bytes = to_parquet(&rows, true).unwrap();
+        let batches = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(bytes))
+            .unwrap()
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 2);
+        let column = |name: &str| {
+            batch
+                .column_by_name(name)
+                .unwrap()
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(column("content_id").value(1), "b");
+        assert_eq!(column("parent_content_id").value(1), "a");
+        let metadata: serde_json::Value =
+            serde_json::from_str(column("metadata").value(0)).unwrap();
+        assert_eq!(metadata, rows[0].metadata.metadata);
+        assert_eq!(column("text").value(0), "invoice 1");
+        assert!(column("text").is_null(1));
+
+        let bytes = to_parquet(&rows, false).unwrap();
+        let builder = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(bytes)).unwrap();
+        assert!(builder.schema().field_with_name("text").is_err());
+    }
+}
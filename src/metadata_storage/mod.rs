@@ -1,13 +1,20 @@
-use std::{pin::Pin, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    pin::Pin,
+    sync::Arc,
+};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use gluesql::core::store::DataRow;
 use nanoid::nanoid;
 use serde::{Deserialize, Serialize};
 
 use self::{postgres::PostgresIndexManager, sqlite::SqliteIndexManager};
-use crate::server_config::{MetadataStoreConfig, MetadataStoreKind};
+use crate::{
+    api::{MetadataFilter, MetadataPage},
+    server_config::{MetadataStoreConfig, MetadataStoreKind},
+};
 pub mod postgres;
 pub mod query_engine;
 pub mod sqlite;
@@ -17,6 +24,53 @@ fn table_name(namespace: &str) -> String {
     format!("metadata_{}", namespace)
 }
 
+fn history_table_name(namespace: &str) -> String {
+    format!("metadata_history_{}", namespace)
+}
+
+/// Top level metadata fields with a secondary index, by namespace. The field
+/// names are written into the index expressions, so they are restricted to
+/// letters, digits and underscores.
+#[derive(Debug, Clone, Default)]
+pub struct IndexedFields(HashMap<String, HashSet<String>>);
+
+impl IndexedFields {
+    pub fn new(fields: &HashMap<String, Vec<String>>) -> Result<Self> {
+        for field in fields.values().flatten() {
+            if field.is_empty() || !field.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(anyhow!(
+                    "indexed metadata field {:?} can only contain letters, digits and underscores",
+                    field
+                ));
+            }
+        }
+        Ok(Self(
+            fields
+                .iter()
+                .map(|(namespace, fields)| (namespace.clone(), fields.iter().cloned().collect()))
+                .collect(),
+        ))
+    }
+
+    pub fn fields(&self, namespace: &str) -> impl Iterator<Item = &String> {
+        self.0.get(namespace).into_iter().flatten()
+    }
+
+    pub fn contains(&self, namespace: &str, field: &str) -> bool {
+        self.0
+            .get(namespace)
+            .map_or(false, |fields| fields.contains(field))
+    }
+}
+
+/// Extractor name of the metadata added to content through the API, e.g.
+/// annotations or corrections of the extracted metadata
+pub const MANUAL_EXTRACTOR: &str = "manual";
+
+/// Extractor name of the metadata imported through the API from outside of
+/// Indexify, e.g. from a legacy system
+pub const IMPORT_EXTRACTOR: &str = "import";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ExtractedMetadata {
     pub id: String,
@@ -47,6 +101,99 @@ impl ExtractedMetadata {
             extraction_policy: extraction_policy.into(),
         }
     }
+
+    /// Id of the metadata added to the content through the API, a content has
+    /// a single record of manual metadata which every update is merged into
+    pub fn manual_id(content_id: &str) -> String {
+        format!("{}_{}", content_id, MANUAL_EXTRACTOR)
+    }
+
+    /// Id of the metadata imported for the content, importing metadata for
+    /// the content again replaces it
+    pub fn import_id(content_id: &str) -> String {
+        format!("{}_{}", content_id, IMPORT_EXTRACTOR)
+    }
+
+    pub fn is_manual(&self) -> bool {
+        self.extractor_name == MANUAL_EXTRACTOR
+    }
+}
+
+/// Merges a patch into metadata as a JSON merge patch (RFC 7396), objects are
+/// merged recursively, null removes a field and any other value replaces it.
+pub fn merge_patch(metadata: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *metadata = patch.clone();
+        return;
+    };
+    if !metadata.is_object() {
+        *metadata = serde_json::Value::Object(serde_json::Map::new());
+    }
+    let fields = metadata.as_object_mut().unwrap();
+    for (key, value) in patch {
+        if value.is_null() {
+            fields.remove(key);
+        } else {
+            merge_patch(
+                fields.entry(key.clone()).or_insert(serde_json::Value::Null),
+                value,
+            );
+        }
+    }
+}
+
+/// Restricts metadata to the metadata written by an extractor or an
+/// extraction policy, matching either if both are set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    pub extractor: Option<String>,
+    pub extraction_policy: Option<String>,
+}
+
+impl Provenance {
+    //  Conditions on the extractor and policy columns of the metadata table,
+    // with placeholders numbered from `first_param`, and their values
+    fn conditions(&self, policy_column: &str, first_param: usize) -> (String, Vec<String>) {
+        let columns = [
+            ("extractor", &self.extractor),
+            (policy_column, &self.extraction_policy),
+        ];
+        let mut conditions = String::new();
+        let mut args = Vec::new();
+        for (column, value) in columns {
+            if let Some(value) = value {
+                conditions.push_str(&format!(" AND {} = ${}", column, first_param + args.len()));
+                args.push(value.clone());
+            }
+        }
+        (conditions, args)
+    }
+}
+
+/// A page of metadata, with the number of rows on all the pages
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PagedMetadata {
+    pub metadata: Vec<ExtractedMetadata>,
+    pub total: usize,
+}
+
+//  Limit and offset of the page as query parameters, a page without a limit
+// extends to the last row
+fn page_bounds(page: &MetadataPage) -> (i64, i64) {
+    let limit = page
+        .limit
+        .map_or(i64::MAX, |limit| i64::try_from(limit).unwrap_or(i64::MAX));
+    let offset = i64::try_from(page.offset).unwrap_or(i64::MAX);
+    (limit, offset)
+}
+
+/// A version of the metadata of a content. Versions written again by their
+/// extraction policy are archived, the current versions aren't.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataVersion {
+    pub metadata: ExtractedMetadata,
+    pub created_at: u64,
+    pub archived_at: Option<u64>,
 }
 
 pub type MetadataStorageTS = Arc<dyn MetadataStorage + Sync + Send>;
@@ -59,6 +206,14 @@ pub trait MetadataStorage {
 
     async fn add_metadata(&self, namespace: &str, metadata: ExtractedMetadata) -> Result<()>;
 
+    /// Adds the metadata in a single transaction, none of it is written if
+    /// writing any of it fails
+    async fn add_metadata_batch(
+        &self,
+        namespace: &str,
+        metadata: Vec<ExtractedMetadata>,
+    ) -> Result<()>;
+
     async fn remove_metadata(&self, namespace: &str, id: &str) -> Result<()>;
 
     async fn get_metadata_for_content(
@@ -67,8 +222,51 @@ pub trait MetadataStorage {
         content_id: &str,
     ) -> Result<Vec<ExtractedMetadata>>;
 
+    /// Returns a page of the metadata of the content with the provenance,
+    /// oldest first
+    async fn get_metadata_page_for_content(
+        &self,
+        namespace: &str,
+        content_id: &str,
+        provenance: &Provenance,
+        page: &MetadataPage,
+    ) -> Result<PagedMetadata>;
+
+    /// Deletes the current and archived metadata of the content
     async fn delete_metadata_for_content(&self, namespace: &str, content_id: &str) -> Result<()>;
 
+    /// Deletes the current and archived metadata written before the time,
+    /// returning the number of deleted rows
+    async fn purge_metadata(&self, namespace: &str, created_before: u64) -> Result<u64>;
+
+    /// Moves the metadata the extraction policy wrote for the content to the
+    /// history of the content, before the policy writes it again
+    async fn archive_metadata(
+        &self,
+        namespace: &str,
+        content_id: &str,
+        extraction_policy: &str,
+    ) -> Result<()>;
+
+    /// Returns the current and archived versions of the metadata of the
+    /// content, oldest first
+    async fn metadata_history(
+        &self,
+        namespace: &str,
+        content_id: &str,
+    ) -> Result<Vec<MetadataVersion>>;
+
+    /// Returns a page of the metadata extracted from content of the content
+    /// source with the provenance which matches every filter, oldest first
+    async fn list_metadata(
+        &self,
+        namespace: &str,
+        content_source: &str,
+        provenance: &Provenance,
+        filters: &[MetadataFilter],
+        page: &MetadataPage,
+    ) -> Result<PagedMetadata>;
+
     #[cfg(test)]
     async fn drop_metadata_table(&self, namespace: &str) -> Result<()>;
 }
@@ -106,16 +304,30 @@ pub trait MetadataReader {
 }
 
 pub fn from_config(config: &MetadataStoreConfig) -> Result<MetadataStorageTS> {
+    let indexed_fields = IndexedFields::new(&config.indexed_fields)?;
     match config.metadata_store {
-        MetadataStoreKind::Postgres => Ok(PostgresIndexManager::new(&config.conn_url)?),
-        MetadataStoreKind::Sqlite => Ok(SqliteIndexManager::new(&config.conn_url)?),
+        MetadataStoreKind::Postgres => Ok(PostgresIndexManager::with_indexed_fields(
+            &config.conn_url,
+            indexed_fields,
+        )?),
+        MetadataStoreKind::Sqlite => Ok(SqliteIndexManager::with_indexed_fields(
+            &config.conn_url,
+            indexed_fields,
+        )?),
     }
 }
 
 pub fn from_config_reader(config: &MetadataStoreConfig) -> Result<MetadataReaderTS> {
+    let indexed_fields = IndexedFields::new(&config.indexed_fields)?;
     match config.metadata_store {
-        MetadataStoreKind::Postgres => Ok(PostgresIndexManager::new(&config.conn_url)?),
-        MetadataStoreKind::Sqlite => Ok(SqliteIndexManager::new(&config.conn_url)?),
+        MetadataStoreKind::Postgres => Ok(PostgresIndexManager::with_indexed_fields(
+            &config.conn_url,
+            indexed_fields,
+        )?),
+        MetadataStoreKind::Sqlite => Ok(SqliteIndexManager::with_indexed_fields(
+            &config.conn_url,
+            indexed_fields,
+        )?),
     }
 }
 
@@ -149,6 +361,114 @@ async fn test_metadata_storage(index_manager: MetadataStorageTS) {
     assert_eq!(metadata_out.len(), 1);
     assert_eq!(metadata_out[0], metadata);
 
+    let metadata_out = index_manager
+        .list_metadata(
+            namespace,
+            "test_content_source",
+            &Provenance::default(),
+            &[],
+            &MetadataPage::default(),
+        )
+        .await
+        .unwrap()
+        .metadata;
+    assert_eq!(metadata_out, vec![metadata.clone()]);
+    let metadata_out = index_manager
+        .list_metadata(
+            namespace,
+            "other_content_source",
+            &Provenance::default(),
+            &[],
+            &MetadataPage::default(),
+        )
+        .await
+        .unwrap()
+        .metadata;
+    assert!(metadata_out.is_empty());
+    for (provenance, expected) in [
+        (
+            Provenance {
+                extractor: Some("test_extractor".into()),
+                extraction_policy: None,
+            },
+            vec![metadata.clone()],
+        ),
+        (
+            Provenance {
+                extractor: Some("test_extractor".into()),
+                extraction_policy: Some("test_extractor_policy".into()),
+            },
+            vec![metadata.clone()],
+        ),
+        (
+            Provenance {
+                extractor: None,
+                extraction_policy: Some("other_policy".into()),
+            },
+            vec![],
+        ),
+    ] {
+        let metadata_out = index_manager
+            .list_metadata(
+                namespace,
+                "test_content_source",
+                &provenance,
+                &[],
+                &MetadataPage::default(),
+            )
+            .await
+            .unwrap()
+            .metadata;
+        assert_eq!(metadata_out, expected, "provenance {:?}", provenance);
+        let metadata_out = index_manager
+            .get_metadata_page_for_content(
+                namespace,
+                "test_content_id",
+                &provenance,
+                &MetadataPage::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(metadata_out.total, expected.len());
+        assert_eq!(
+            metadata_out.metadata, expected,
+            "provenance {:?}",
+            provenance
+        );
+    }
+
+    test_metadata_filters(index_manager.clone(), namespace).await;
+    test_metadata_pages(index_manager.clone(), namespace).await;
+
+    //  Metadata written again by its extraction policy is kept as history
+    index_manager
+        .archive_metadata(namespace, "test_content_id", "test_extractor_policy")
+        .await
+        .unwrap();
+    let updated = ExtractedMetadata {
+        id: "test_id_2".into(),
+        metadata: serde_json::json!({"test": "updated"}),
+        ..metadata.clone()
+    };
+    index_manager
+        .add_metadata(namespace, updated.clone())
+        .await
+        .unwrap();
+    let metadata_out = index_manager
+        .get_metadata_for_content(namespace, "test_content_id")
+        .await
+        .unwrap();
+    assert_eq!(metadata_out, vec![updated.clone()]);
+    let history = index_manager
+        .metadata_history(namespace, "test_content_id")
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].metadata, metadata);
+    assert!(history[0].archived_at.is_some());
+    assert_eq!(history[1].metadata, updated);
+    assert!(history[1].archived_at.is_none());
+
     // Delete the metadata from the database
     index_manager
         .delete_metadata_for_content(namespace, "test_content_id")
@@ -161,4 +481,263 @@ async fn test_metadata_storage(index_manager: MetadataStorageTS) {
         .unwrap();
 
     assert_eq!(metadata_out.len(), 0);
+    let history = index_manager
+        .metadata_history(namespace, "test_content_id")
+        .await
+        .unwrap();
+    assert!(history.is_empty());
+}
+
+#[cfg(test)]
+async fn test_metadata_filters(index_manager: MetadataStorageTS, namespace: &str) {
+    use crate::api::MetadataPredicate;
+
+    let documents = [
+        serde_json::json!({"page": 1, "author": {"name": "a"}, "draft": true}),
+        serde_json::json!({"page": 2.5, "author": {"name": "b"}, "tags": ["x"]}),
+        serde_json::json!({"page": "3", "author": {"name": "c"}, "draft": null}),
+    ];
+    for (i, document) in documents.iter().enumerate() {
+        let metadata = ExtractedMetadata {
+            id: format!("filter_id_{}", i),
+            content_id: format!("filter_content_id_{}", i),
+            parent_content_id: "".into(),
+            content_source: "filter_content_source".into(),
+            metadata: document.clone(),
+            extractor_name: "test_extractor".into(),
+            extraction_policy: "test_extractor_policy".into(),
+        };
+        index_manager
+            .add_metadata(namespace, metadata)
+            .await
+            .unwrap();
+    }
+    let filter = |field: &str, predicate: MetadataPredicate| MetadataFilter {
+        field: field.to_string(),
+        predicate,
+    };
+    let cases = [
+        (vec![], vec![0, 1, 2]),
+        (
+            vec![filter("author.name", MetadataPredicate::Eq("b".into()))],
+            vec![1],
+        ),
+        (
+            vec![filter("page", MetadataPredicate::Eq(1.into()))],
+            vec![0],
+        ),
+        (
+            vec![filter("draft", MetadataPredicate::Eq(true.into()))],
+            vec![0],
+        ),
+        (
+            vec![filter(
+                "tags",
+                MetadataPredicate::Eq(serde_json::json!(["x"])),
+            )],
+            vec![1],
+        ),
+        //  Ranges only compare values of the same type
+        (
+            vec![filter("page", MetadataPredicate::Gt(1.into()))],
+            vec![1],
+        ),
+        (
+            vec![filter("page", MetadataPredicate::Gte(1.into()))],
+            vec![0, 1],
+        ),
+        (
+            vec![filter("page", MetadataPredicate::Lt("4".into()))],
+            vec![2],
+        ),
+        (
+            vec![
+                filter("page", MetadataPredicate::Gte(1.into())),
+                filter("page", MetadataPredicate::Lte(2.into())),
+            ],
+            vec![0],
+        ),
+        (vec![filter("draft", MetadataPredicate::Exists)], vec![0, 2]),
+        (
+            vec![filter("author.email", MetadataPredicate::Exists)],
+            vec![],
+        ),
+    ];
+    for (filters, expected) in cases {
+        let mut content_ids = index_manager
+            .list_metadata(
+                namespace,
+                "filter_content_source",
+                &Provenance::default(),
+                &filters,
+                &MetadataPage::default(),
+            )
+            .await
+            .unwrap()
+            .metadata
+            .into_iter()
+            .map(|metadata| metadata.content_id)
+            .collect::<Vec<_>>();
+        content_ids.sort();
+        let expected = expected
+            .iter()
+            .map(|i| format!("filter_content_id_{}", i))
+            .collect::<Vec<_>>();
+        assert_eq!(content_ids, expected, "filters {:?}", filters);
+    }
+}
+
+#[cfg(test)]
+async fn test_metadata_pages(index_manager: MetadataStorageTS, namespace: &str) {
+    use crate::api::MetadataPredicate;
+
+    for i in 0..5 {
+        index_manager
+            .add_metadata(
+                namespace,
+                ExtractedMetadata {
+                    id: format!("page_id_{}", i),
+                    content_id: "page_content_id".into(),
+                    parent_content_id: "".into(),
+                    content_source: "page_content_source".into(),
+                    metadata: serde_json::json!({"page": i}),
+                    extractor_name: "test_extractor".into(),
+                    extraction_policy: "test_extractor_policy".into(),
+                },
+            )
+            .await
+            .unwrap();
+    }
+    //  Pages starting in, at the end of and past the rows
+    let cases = [
+        (0, None, vec![0, 1, 2, 3, 4]),
+        (0, Some(2), vec![0, 1]),
+        (2, Some(2), vec![2, 3]),
+        (4, Some(2), vec![4]),
+        (5, Some(2), vec![]),
+        (7, None, vec![]),
+        (3, None, vec![3, 4]),
+        (0, Some(0), vec![]),
+    ];
+    for (offset, limit, expected) in cases {
+        let page = MetadataPage { offset, limit };
+        let expected = expected
+            .iter()
+            .map(|i| format!("page_id_{}", i))
+            .collect::<Vec<_>>();
+        let listed = index_manager
+            .list_metadata(
+                namespace,
+                "page_content_source",
+                &Provenance::default(),
+                &[],
+                &page,
+            )
+            .await
+            .unwrap();
+        let content_page = index_manager
+            .get_metadata_page_for_content(
+                namespace,
+                "page_content_id",
+                &Provenance::default(),
+                &page,
+            )
+            .await
+            .unwrap();
+        for paged in [listed, content_page] {
+            assert_eq!(paged.total, 5, "page {:?}", page);
+            assert_eq!(
+                paged
+                    .metadata
+                    .into_iter()
+                    .map(|metadata| metadata.id)
+                    .collect::<Vec<_>>(),
+                expected,
+                "page {:?}",
+                page
+            );
+        }
+    }
+
+    //  The total only counts the rows matching the filters
+    let paged = index_manager
+        .list_metadata(
+            namespace,
+            "page_content_source",
+            &Provenance::default(),
+            &[MetadataFilter {
+                field: "page".into(),
+                predicate: MetadataPredicate::Gte(3.into()),
+            }],
+            &MetadataPage {
+                offset: 0,
+                limit: Some(1),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(paged.total, 2);
+    assert_eq!(paged.metadata.len(), 1);
+    index_manager
+        .delete_metadata_for_content(namespace, "page_content_id")
+        .await
+        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_merge_patch() {
+        let mut metadata = json!({
+            "title": "invoice",
+            "author": {"name": "a", "email": "a@example.com"},
+            "pages": 3,
+        });
+        merge_patch(
+            &mut metadata,
+            &json!({
+                "title": "corrected invoice",
+                "author": {"email": null},
+                "pages": null,
+                "approved": true,
+            }),
+        );
+        assert_eq!(
+            metadata,
+            json!({
+                "title": "corrected invoice",
+                "author": {"name": "a"},
+                "approved": true,
+            })
+        );
+
+        let mut metadata = serde_json::Value::Null;
+        merge_patch(&mut metadata, &json!({"label": "cat", "score": null}));
+        assert_eq!(metadata, json!({"label": "cat"}));
+    }
+
+    #[test]
+    fn test_indexed_fields() {
+        let indexed_fields = IndexedFields::new(&HashMap::from([(
+            "default".to_string(),
+            vec!["author".to_string(), "invoice_number".to_string()],
+        )]))
+        .unwrap();
+        assert!(indexed_fields.contains("default", "author"));
+        assert!(!indexed_fields.contains("default", "title"));
+        assert!(!indexed_fields.contains("other", "author"));
+        assert_eq!(indexed_fields.fields("other").count(), 0);
+
+        for field in ["author.name", "author'", ""] {
+            assert!(IndexedFields::new(&HashMap::from([(
+                "default".to_string(),
+                vec![field.to_string()]
+            )]))
+            .is_err());
+        }
+    }
 }
@@ -201,6 +201,7 @@ mod tests {
                 .collect(),
             content_source: cs.to_string(),
             id: nanoid!(16),
+            conflicts: Default::default(),
         }
     }
 
@@ -1,39 +1,89 @@
 use std::{
-    fs::OpenOptions,
-    sync::{atomic::AtomicBool, Arc},
+    collections::HashSet,
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::Duration,
 };
 
 use async_trait::async_trait;
 use futures::StreamExt;
 use gluesql::core::error::Error::StorageMsg as GlueStorageError;
-use sqlx::{sqlite::SqlitePoolOptions, Pool, Sqlite};
+use serde_json::Value;
+use sqlx::{
+    query::Query,
+    sqlite::{SqliteArguments, SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+    Pool,
+    Row,
+    Sqlite,
+};
 
 use super::{
-    sqlx::{row_to_extracted_metadata, row_to_metadata_scan_item},
+    history_table_name,
+    page_bounds,
+    sqlx::{row_to_extracted_metadata, row_to_metadata_scan_item, row_to_metadata_version},
     table_name,
     ExtractedMetadata,
+    IndexedFields,
+    MetadataFilter,
     MetadataReader,
     MetadataScanStream,
     MetadataStorage,
+    MetadataVersion,
+    PagedMetadata,
+    Provenance,
+};
+use crate::{
+    api::{MetadataPage, MetadataPredicate},
+    utils::{timestamp_secs, PostgresIndexName},
 };
-use crate::utils::{timestamp_secs, PostgresIndexName};
+
+const IN_MEMORY_URL: &str = "sqlite::memory:";
 
 pub struct SqliteIndexManager {
     pool: Pool<Sqlite>,
-    default_table_created: AtomicBool,
+    //  Namespaces whose metadata table was created by this process
+    created_tables: RwLock<HashSet<String>>,
+    indexed_fields: IndexedFields,
 }
 
 impl SqliteIndexManager {
+    /// Opens the database file at the path, creating it and its directory if
+    /// they don't exist. `sqlite::memory:` opens a database which only lives
+    /// as long as the process, e.g. for tests.
     pub fn new(conn_url: &str) -> anyhow::Result<Arc<Self>> {
-        //  Create the file if it does not exist
-        let _ = OpenOptions::new().write(true).create(true).open(conn_url)?;
-        let conn_url = format!("sqlite://{}", conn_url);
-        let pool = SqlitePoolOptions::new()
-            .max_connections(5)
-            .connect_lazy(&conn_url)?;
+        Self::with_indexed_fields(conn_url, IndexedFields::default())
+    }
+
+    pub fn with_indexed_fields(
+        conn_url: &str,
+        indexed_fields: IndexedFields,
+    ) -> anyhow::Result<Arc<Self>> {
+        let pool = if conn_url == IN_MEMORY_URL {
+            //  Every connection to an in-memory database opens a new one, the
+            // pool has to keep a single connection open
+            SqlitePoolOptions::new()
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+                .connect_lazy_with(SqliteConnectOptions::from_str(IN_MEMORY_URL)?)
+        } else {
+            if let Some(dir) = std::path::Path::new(conn_url).parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            //  The write ahead log lets the pool read while metadata is written
+            let options = SqliteConnectOptions::new()
+                .filename(conn_url)
+                .create_if_missing(true)
+                .journal_mode(SqliteJournalMode::Wal)
+                .busy_timeout(Duration::from_secs(5));
+            SqlitePoolOptions::new()
+                .max_connections(5)
+                .connect_lazy_with(options)
+        };
         Ok(Arc::new(Self {
             pool,
-            default_table_created: AtomicBool::new(false),
+            created_tables: RwLock::new(HashSet::new()),
+            indexed_fields,
         }))
     }
 }
@@ -57,14 +107,61 @@ impl MetadataStorage for SqliteIndexManager {
         );"
         );
         let _ = sqlx::query(&query).execute(&self.pool).await?;
+        for (suffix, columns) in [
+            ("content_id", "namespace, content_id"),
+            ("content_source", "namespace, content_source"),
+            ("created_at", "namespace, created_at"),
+        ] {
+            let query = format!(
+                "CREATE INDEX IF NOT EXISTS {table_name}_{suffix} ON {table_name} ({columns});"
+            );
+            let _ = sqlx::query(&query).execute(&self.pool).await?;
+        }
+        //  Filters on an indexed field compare the same expression
+        for field in self.indexed_fields.fields(namespace) {
+            let query = format!(
+                "CREATE INDEX IF NOT EXISTS {table_name}_data_{field} ON {table_name} (json_extract(data, '$.\"{field}\"'));"
+            );
+            let _ = sqlx::query(&query).execute(&self.pool).await?;
+        }
+        //  Previous versions of metadata, with the time they were archived
+        let history_table_name = PostgresIndexName::new(&history_table_name(namespace));
+        let query = format!(
+            "CREATE TABLE IF NOT EXISTS {history_table_name} (
+            id TEXT,
+            namespace TEXT,
+            extractor TEXT,
+            extractor_policy_name TEXT,
+            content_source TEXT,
+            index_name TEXT,
+            data JSONB,
+            content_id TEXT,
+            parent_content_id TEXT,
+            created_at BIGINT,
+            archived_at BIGINT
+        );"
+        );
+        let _ = sqlx::query(&query).execute(&self.pool).await?;
+        let query = format!(
+            "CREATE INDEX IF NOT EXISTS {history_table_name}_content_id ON {history_table_name} (namespace, content_id);"
+        );
+        let _ = sqlx::query(&query).execute(&self.pool).await?;
+        self.created_tables
+            .write()
+            .unwrap()
+            .insert(namespace.to_string());
         Ok(())
     }
 
     #[cfg(test)]
     async fn drop_metadata_table(&self, namespace: &str) -> anyhow::Result<()> {
         let table_name = PostgresIndexName::new(&table_name(namespace));
-        let query = format!("DROP TABLE IF EXISTS {table_name};");
-        let _ = sqlx::query(&query).execute(&self.pool).await?;
+        let history_table_name = PostgresIndexName::new(&history_table_name(namespace));
+        for table_name in [table_name.to_string(), history_table_name.to_string()] {
+            let query = format!("DROP TABLE IF EXISTS {table_name};");
+            let _ = sqlx::query(&query).execute(&self.pool).await?;
+        }
+        self.created_tables.write().unwrap().remove(namespace);
         Ok(())
     }
 
@@ -72,15 +169,18 @@ impl MetadataStorage for SqliteIndexManager {
         &self,
         namespace: &str,
         metadata: ExtractedMetadata,
+    ) -> anyhow::Result<()> {
+        self.add_metadata_batch(namespace, vec![metadata]).await
+    }
+
+    async fn add_metadata_batch(
+        &self,
+        namespace: &str,
+        metadata: Vec<ExtractedMetadata>,
     ) -> anyhow::Result<()> {
         let index_name = PostgresIndexName::new(&table_name(namespace));
-        if !self
-            .default_table_created
-            .load(std::sync::atomic::Ordering::Relaxed)
-        {
+        if !self.created_tables.read().unwrap().contains(namespace) {
             self.create_metadata_table(namespace).await?;
-            self.default_table_created
-                .store(true, std::sync::atomic::Ordering::Relaxed);
         }
         let query = format!(
             "
@@ -92,19 +192,24 @@ impl MetadataStorage for SqliteIndexManager {
             ON CONFLICT (id) DO UPDATE SET data = EXCLUDED.data;
         "
         );
-        let _ = sqlx::query(&query)
-            .bind(metadata.id)
-            .bind(namespace)
-            .bind(metadata.extractor_name)
-            .bind(metadata.extraction_policy)
-            .bind(metadata.content_source)
-            .bind(index_name.to_string())
-            .bind(metadata.metadata)
-            .bind(metadata.content_id)
-            .bind(metadata.parent_content_id)
-            .bind(timestamp_secs() as i64)
-            .execute(&self.pool)
-            .await?;
+        let created_at = timestamp_secs() as i64;
+        let mut tx = self.pool.begin().await?;
+        for metadata in metadata {
+            sqlx::query(&query)
+                .bind(metadata.id)
+                .bind(namespace)
+                .bind(metadata.extractor_name)
+                .bind(metadata.extraction_policy)
+                .bind(metadata.content_source)
+                .bind(index_name.to_string())
+                .bind(metadata.metadata)
+                .bind(metadata.content_id)
+                .bind(metadata.parent_content_id)
+                .bind(created_at)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
         Ok(())
     }
 
@@ -121,8 +226,9 @@ impl MetadataStorage for SqliteIndexManager {
         content_id: &str,
     ) -> anyhow::Result<Vec<ExtractedMetadata>> {
         let index_table_name = PostgresIndexName::new(&table_name(namespace));
-        let query =
-            format!("SELECT * FROM {index_table_name} WHERE namespace = $1 and content_id = $2");
+        let query = format!(
+            "SELECT * FROM {index_table_name} WHERE namespace = $1 and content_id = $2 ORDER BY created_at, id"
+        );
 
         let extracted_attributes = sqlx::query(&query)
             .bind(namespace)
@@ -136,23 +242,308 @@ impl MetadataStorage for SqliteIndexManager {
         Ok(extracted_attributes)
     }
 
+    async fn get_metadata_page_for_content(
+        &self,
+        namespace: &str,
+        content_id: &str,
+        provenance: &Provenance,
+        page: &MetadataPage,
+    ) -> anyhow::Result<PagedMetadata> {
+        let index_table_name = PostgresIndexName::new(&table_name(namespace));
+        let (provenance_conditions, provenance_args) =
+            provenance.conditions("extractor_policy_name", 3);
+        let mut args = vec![
+            QueryArg::Text(namespace.to_string()),
+            QueryArg::Text(content_id.to_string()),
+        ];
+        args.extend(provenance_args.into_iter().map(QueryArg::Text));
+        select_page(
+            &self.pool,
+            &index_table_name,
+            &format!("namespace = $1 and content_id = $2{provenance_conditions}"),
+            &args,
+            page,
+        )
+        .await
+    }
+
     async fn delete_metadata_for_content(
         &self,
         namespace: &str,
         content_id: &str,
     ) -> anyhow::Result<()> {
-        let index_table_name = PostgresIndexName::new(&table_name(namespace));
-        let query =
-            format!("DELETE FROM {index_table_name} WHERE namespace = $1 and content_id = $2");
+        //  Content is garbage collected from namespaces which never had
+        // metadata written to them
+        if !self.created_tables.read().unwrap().contains(namespace) {
+            self.create_metadata_table(namespace).await?;
+        }
+        for table_name in [table_name(namespace), history_table_name(namespace)] {
+            let table_name = PostgresIndexName::new(&table_name);
+            let query =
+                format!("DELETE FROM {table_name} WHERE namespace = $1 and content_id = $2");
+            sqlx::query(&query)
+                .bind(namespace)
+                .bind(content_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn purge_metadata(&self, namespace: &str, created_before: u64) -> anyhow::Result<u64> {
+        if !self.created_tables.read().unwrap().contains(namespace) {
+            self.create_metadata_table(namespace).await?;
+        }
+        let mut purged = 0;
+        for table_name in [table_name(namespace), history_table_name(namespace)] {
+            let table_name = PostgresIndexName::new(&table_name);
+            let query =
+                format!("DELETE FROM {table_name} WHERE namespace = $1 and created_at < $2");
+            purged += sqlx::query(&query)
+                .bind(namespace)
+                .bind(created_before as i64)
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+        }
+        Ok(purged)
+    }
 
+    async fn archive_metadata(
+        &self,
+        namespace: &str,
+        content_id: &str,
+        extraction_policy: &str,
+    ) -> anyhow::Result<()> {
+        if !self.created_tables.read().unwrap().contains(namespace) {
+            self.create_metadata_table(namespace).await?;
+        }
+        let table_name = PostgresIndexName::new(&table_name(namespace));
+        let history_table_name = PostgresIndexName::new(&history_table_name(namespace));
+        let condition = "namespace = $1 AND content_id = $2 AND extractor_policy_name = $3";
+        let mut tx = self.pool.begin().await?;
+        let query = format!(
+            "INSERT INTO {history_table_name} SELECT *, $4 FROM {table_name} WHERE {condition};"
+        );
         sqlx::query(&query)
             .bind(namespace)
             .bind(content_id)
-            .execute(&self.pool)
+            .bind(extraction_policy)
+            .bind(timestamp_secs() as i64)
+            .execute(&mut *tx)
             .await?;
-
+        let query = format!("DELETE FROM {table_name} WHERE {condition};");
+        sqlx::query(&query)
+            .bind(namespace)
+            .bind(content_id)
+            .bind(extraction_policy)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
         Ok(())
     }
+
+    async fn metadata_history(
+        &self,
+        namespace: &str,
+        content_id: &str,
+    ) -> anyhow::Result<Vec<MetadataVersion>> {
+        if !self.created_tables.read().unwrap().contains(namespace) {
+            self.create_metadata_table(namespace).await?;
+        }
+        let mut versions = Vec::new();
+        for (table_name, archived) in [
+            (history_table_name(namespace), true),
+            (table_name(namespace), false),
+        ] {
+            let table_name = PostgresIndexName::new(&table_name);
+            let query =
+                format!("SELECT * FROM {table_name} WHERE namespace = $1 AND content_id = $2");
+            let rows = sqlx::query(&query)
+                .bind(namespace)
+                .bind(content_id)
+                .fetch_all(&self.pool)
+                .await?;
+            versions.extend(
+                rows.iter()
+                    .map(|row| row_to_metadata_version(row, archived)),
+            );
+        }
+        versions
+            .sort_by_key(|version| (version.archived_at.unwrap_or(u64::MAX), version.created_at));
+        Ok(versions)
+    }
+
+    async fn list_metadata(
+        &self,
+        namespace: &str,
+        content_source: &str,
+        provenance: &Provenance,
+        filters: &[MetadataFilter],
+        page: &MetadataPage,
+    ) -> anyhow::Result<PagedMetadata> {
+        let index_table_name = PostgresIndexName::new(&table_name(namespace));
+        let (provenance_conditions, provenance_args) =
+            provenance.conditions("extractor_policy_name", 3);
+        let (conditions, filter_args) = filter_conditions(
+            filters,
+            |field| self.indexed_fields.contains(namespace, field),
+            3 + provenance_args.len(),
+        );
+        let mut args = vec![
+            QueryArg::Text(namespace.to_string()),
+            QueryArg::Text(content_source.to_string()),
+        ];
+        args.extend(provenance_args.into_iter().map(QueryArg::Text));
+        args.extend(filter_args);
+        select_page(
+            &self.pool,
+            &index_table_name,
+            &format!("namespace = $1 and content_source = $2{provenance_conditions}{conditions}"),
+            &args,
+            page,
+        )
+        .await
+    }
+}
+
+fn bind_args<'q>(
+    mut query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    args: &[QueryArg],
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    for arg in args {
+        query = match arg {
+            QueryArg::Text(text) => query.bind(text.clone()),
+            QueryArg::Integer(integer) => query.bind(*integer),
+            QueryArg::Real(real) => query.bind(*real),
+        };
+    }
+    query
+}
+
+//  Page of the metadata in the table matching the conditions, oldest first.
+// The matching rows are only counted separately if some of them can be out of
+// the page.
+async fn select_page(
+    pool: &Pool<Sqlite>,
+    table_name: &PostgresIndexName,
+    conditions: &str,
+    args: &[QueryArg],
+    page: &MetadataPage,
+) -> anyhow::Result<PagedMetadata> {
+    let (limit, offset) = page_bounds(page);
+    let query = format!(
+        "SELECT * FROM {table_name} WHERE {conditions} ORDER BY created_at, id LIMIT ${} OFFSET ${}",
+        args.len() + 1,
+        args.len() + 2
+    );
+    let metadata: Vec<ExtractedMetadata> = bind_args(sqlx::query(&query), args)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .map(row_to_extracted_metadata)
+        .collect();
+    if page.limit.is_none() && page.offset == 0 {
+        return Ok(PagedMetadata {
+            total: metadata.len(),
+            metadata,
+        });
+    }
+    let query = format!("SELECT COUNT(*) FROM {table_name} WHERE {conditions}");
+    let total: i64 = bind_args(sqlx::query(&query), args)
+        .fetch_one(pool)
+        .await?
+        .get(0);
+    Ok(PagedMetadata {
+        metadata,
+        total: total as usize,
+    })
+}
+
+enum QueryArg {
+    Text(String),
+    Integer(i64),
+    Real(f64),
+}
+
+impl QueryArg {
+    fn number(value: &serde_json::Number) -> Self {
+        match value.as_i64() {
+            Some(integer) => QueryArg::Integer(integer),
+            None => QueryArg::Real(value.as_f64().unwrap_or_default()),
+        }
+    }
+}
+
+//  JSON path of the field, with every key quoted
+fn json_path(filter: &MetadataFilter) -> String {
+    filter
+        .path()
+        .into_iter()
+        .fold("$".to_string(), |path, key| format!("{}.\"{}\"", path, key))
+}
+
+//  Conditions of the metadata filters over the JSON data, with placeholders
+// numbered from `first_param`. SQLite orders numbers before strings, so the
+// type of the field is checked before comparing it. Indexed fields are
+// extracted with the path of their index so the planner can use it.
+fn filter_conditions(
+    filters: &[MetadataFilter],
+    is_indexed: impl Fn(&str) -> bool,
+    first_param: usize,
+) -> (String, Vec<QueryArg>) {
+    let mut conditions = String::new();
+    let mut args = Vec::new();
+    for filter in filters {
+        let path = if is_indexed(&filter.field) {
+            format!("'{}'", json_path(filter))
+        } else {
+            args.push(QueryArg::Text(json_path(filter)));
+            format!("${}", first_param + args.len() - 1)
+        };
+        let field_type = format!("json_type(data, {path})");
+        let field = format!("json_extract(data, {path})");
+        let op = filter.predicate.operator();
+        let condition = match &filter.predicate {
+            MetadataPredicate::Exists => format!("{field_type} {op}"),
+            MetadataPredicate::Eq(Value::Null) => format!("{field_type} = 'null'"),
+            MetadataPredicate::Eq(Value::Bool(value)) => format!("{field_type} = '{value}'"),
+            MetadataPredicate::Eq(value @ (Value::Array(_) | Value::Object(_))) => {
+                args.push(QueryArg::Text(value.to_string()));
+                format!(
+                    "{field_type} IN ('array', 'object') AND json({field}) = json(${})",
+                    first_param + args.len() - 1
+                )
+            }
+            MetadataPredicate::Eq(value) |
+            MetadataPredicate::Gt(value) |
+            MetadataPredicate::Gte(value) |
+            MetadataPredicate::Lt(value) |
+            MetadataPredicate::Lte(value) => {
+                let types = match value {
+                    Value::Number(number) => {
+                        args.push(QueryArg::number(number));
+                        "'integer', 'real'"
+                    }
+                    value => {
+                        args.push(QueryArg::Text(
+                            value.as_str().unwrap_or_default().to_string(),
+                        ));
+                        "'text'"
+                    }
+                };
+                format!(
+                    "{field_type} IN ({types}) AND {field} {op} ${}",
+                    first_param + args.len() - 1
+                )
+            }
+        };
+        conditions.push_str(&format!(" AND ({})", condition));
+    }
+    (conditions, args)
 }
 
 #[async_trait(?Send)]
@@ -163,11 +554,10 @@ impl MetadataReader for SqliteIndexManager {
         id: &str,
     ) -> anyhow::Result<Option<ExtractedMetadata>> {
         let table_name = PostgresIndexName::new(&table_name(namespace));
-        let query = format!("SELECT * FROM {table_name} WHERE namespace = $2 and id = $3");
+        let query = format!("SELECT * FROM {table_name} WHERE namespace = $1 and id = $2");
         let metadata = sqlx::query(&query)
             .bind(namespace)
             .bind(id)
-            .bind(table_name.to_string())
             .fetch_all(&self.pool)
             .await?
             .first()
@@ -213,10 +603,11 @@ impl MetadataReader for SqliteIndexManager {
 
 #[cfg(test)]
 mod tests {
-    use sqlx::Row;
-
     use super::*;
-    use crate::{metadata_storage::test_metadata_storage, utils::PostgresIndexName};
+    use crate::{
+        metadata_storage::{test_metadata_filters, test_metadata_storage},
+        utils::PostgresIndexName,
+    };
 
     #[tokio::test]
     async fn test_create_index() {
@@ -242,4 +633,180 @@ mod tests {
         let index_manager = SqliteIndexManager::new("sqlite::memory:").unwrap();
         test_metadata_storage(index_manager).await;
     }
+
+    #[tokio::test]
+    async fn test_indexed_fields() {
+        let indexed_fields = IndexedFields::new(&std::collections::HashMap::from([(
+            "test_namespace".to_string(),
+            vec!["page".to_string(), "draft".to_string(), "tags".to_string()],
+        )]))
+        .unwrap();
+        let index_manager =
+            SqliteIndexManager::with_indexed_fields("sqlite::memory:", indexed_fields).unwrap();
+        test_metadata_filters(index_manager.clone(), "test_namespace").await;
+
+        //  Lookups of an indexed field use its index
+        let filters = [MetadataFilter {
+            field: "page".to_string(),
+            predicate: MetadataPredicate::Eq(1.into()),
+        }];
+        let (conditions, args) = filter_conditions(
+            &filters,
+            |field| {
+                index_manager
+                    .indexed_fields
+                    .contains("test_namespace", field)
+            },
+            3,
+        );
+        assert!(args.is_empty());
+        let query = format!(
+            "EXPLAIN QUERY PLAN SELECT * FROM metadata_test_namespace WHERE namespace = $1 and content_source = $2{conditions}"
+        );
+        let plan = sqlx::query(&query)
+            .bind("test_namespace")
+            .bind("filter_content_source")
+            .fetch_all(&index_manager.pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get::<String, _>("detail"))
+            .collect::<Vec<_>>();
+        assert!(
+            plan.iter()
+                .any(|detail| detail.contains("metadata_test_namespace_data_page")),
+            "{:?}",
+            plan
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_metadata_without_table() {
+        let index_manager = SqliteIndexManager::new("sqlite::memory:").unwrap();
+        index_manager
+            .delete_metadata_for_content("test_namespace", "content_id")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_add_metadata_batch() {
+        let index_manager = SqliteIndexManager::new("sqlite::memory:").unwrap();
+        let namespace = "test_namespace";
+        let metadata = |key: &str| {
+            ExtractedMetadata::new(
+                "content_id",
+                "",
+                "ingestion",
+                serde_json::json!({ key: true }),
+                "extractor",
+                "policy",
+            )
+        };
+        index_manager
+            .add_metadata_batch(namespace, vec![metadata("a"), metadata("b")])
+            .await
+            .unwrap();
+        let written = index_manager
+            .get_metadata_for_content(namespace, "content_id")
+            .await
+            .unwrap();
+        assert_eq!(written.len(), 2);
+
+        //  A failed write rolls back the metadata written before it
+        let table_name = PostgresIndexName::new(&table_name(namespace));
+        sqlx::query(&format!(
+            "CREATE TRIGGER reject_d BEFORE INSERT ON {table_name}
+            WHEN json_extract(NEW.data, '$.d') IS NOT NULL
+            BEGIN SELECT RAISE(ABORT, 'rejected'); END;"
+        ))
+        .execute(&index_manager.pool)
+        .await
+        .unwrap();
+        assert!(index_manager
+            .add_metadata_batch(namespace, vec![metadata("c"), metadata("d")])
+            .await
+            .is_err());
+        let written = index_manager
+            .get_metadata_for_content(namespace, "content_id")
+            .await
+            .unwrap();
+        assert_eq!(written.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_purge_metadata() {
+        let index_manager = SqliteIndexManager::new("sqlite::memory:").unwrap();
+        let namespace = "test_namespace";
+        for content_id in ["old", "new"] {
+            let metadata = ExtractedMetadata::new(
+                content_id,
+                "",
+                "ingestion",
+                serde_json::json!({"email": "jane@example.com"}),
+                "extractor",
+                "policy",
+            );
+            index_manager
+                .add_metadata(namespace, metadata)
+                .await
+                .unwrap();
+        }
+        index_manager
+            .archive_metadata(namespace, "old", "policy")
+            .await
+            .unwrap();
+        let table_name = PostgresIndexName::new(&history_table_name(namespace));
+        sqlx::query(&format!("UPDATE {table_name} SET created_at = 100"))
+            .execute(&index_manager.pool)
+            .await
+            .unwrap();
+
+        let purged = index_manager.purge_metadata(namespace, 1000).await.unwrap();
+        assert_eq!(purged, 1);
+        assert!(index_manager
+            .metadata_history(namespace, "old")
+            .await
+            .unwrap()
+            .is_empty());
+        let remaining = index_manager
+            .get_metadata_for_content(namespace, "new")
+            .await
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metadata").join("indexify.db");
+        let path = path.to_str().unwrap();
+        let metadata = ExtractedMetadata::new(
+            "content_id",
+            "",
+            "ingestion",
+            serde_json::json!({"topic": "sqlite"}),
+            "extractor",
+            "policy",
+        );
+        {
+            let index_manager = SqliteIndexManager::new(path).unwrap();
+            for namespace in ["namespace_a", "namespace_b"] {
+                index_manager
+                    .add_metadata(namespace, metadata.clone())
+                    .await
+                    .unwrap();
+            }
+        }
+
+        //  The metadata of every namespace is kept in the file
+        let index_manager = SqliteIndexManager::new(path).unwrap();
+        for namespace in ["namespace_a", "namespace_b"] {
+            let metadata_out = index_manager
+                .get_metadata_for_id(namespace, &metadata.id)
+                .await
+                .unwrap();
+            assert_eq!(metadata_out.as_ref(), Some(&metadata));
+        }
+    }
 }
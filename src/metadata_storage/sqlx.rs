@@ -7,7 +7,7 @@ use gluesql::core::{
 };
 use itertools::Itertools;
 
-use super::ExtractedMetadata;
+use super::{ExtractedMetadata, MetadataVersion};
 
 pub fn row_to_extracted_metadata<'a, T: sqlx::Row>(row: &'a T) -> ExtractedMetadata
 where
@@ -33,6 +33,23 @@ where
     }
 }
 
+/// Version of the metadata in a row of the metadata table, or of the history
+/// table which has the time it was archived as its last column
+pub fn row_to_metadata_version<'a, T: sqlx::Row>(row: &'a T, archived: bool) -> MetadataVersion
+where
+    usize: sqlx::ColumnIndex<T>,
+    String: sqlx::Decode<'a, T::Database> + sqlx::Type<T::Database>,
+    i64: sqlx::Decode<'a, T::Database> + sqlx::Type<T::Database>,
+    sqlx::types::Json<serde_json::Value>: sqlx::Decode<'a, T::Database> + sqlx::Type<T::Database>,
+{
+    let created_at: i64 = row.get(9);
+    MetadataVersion {
+        metadata: row_to_extracted_metadata(row),
+        created_at: created_at as u64,
+        archived_at: archived.then(|| row.get::<i64, _>(10) as u64),
+    }
+}
+
 pub fn row_to_metadata_scan_item<'a, T: sqlx::Row>(row: &'a T) -> GlueResult<(Key, DataRow)>
 where
     usize: sqlx::ColumnIndex<T>,
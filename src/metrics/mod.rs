@@ -306,3 +306,107 @@ pub mod raft_metrics {
         }
     }
 }
+
+/// Structured events for lifecycle transitions of content, tasks and indexes.
+///
+/// Every event is recorded twice: as an event on the active OpenTelemetry
+/// span, so it is exported with traces, and as a `tracing` event with the
+/// `indexify::events` target, so it ends up in the logs. Both carry the same
+/// attributes, which use the `indexify.` prefix.
+pub mod lifecycle_events {
+    use opentelemetry::{trace::get_active_span, KeyValue};
+    use tracing::{info, warn};
+
+    pub const CONTENT_CREATED: &str = "indexify.content.created";
+    pub const TASK_FAILED: &str = "indexify.task.failed";
+    pub const INDEX_WRITTEN: &str = "indexify.index.written";
+
+    const NAMESPACE: &str = "indexify.namespace";
+    const CONTENT_ID: &str = "indexify.content_id";
+    const SOURCE: &str = "indexify.source";
+    const MIME_TYPE: &str = "indexify.mime_type";
+    const TASK_ID: &str = "indexify.task_id";
+    const EXTRACTOR: &str = "indexify.extractor";
+    const EXTRACTION_POLICY: &str = "indexify.extraction_policy";
+    const INDEX: &str = "indexify.index";
+
+    fn add_span_event(name: &'static str, attributes: Vec<KeyValue>) {
+        get_active_span(|span| span.add_event(name, attributes));
+    }
+
+    pub fn content_created(namespace: &str, content_id: &str, source: &str, mime_type: &str) {
+        info!(
+            target: "indexify::events",
+            event = CONTENT_CREATED,
+            namespace,
+            content_id,
+            source,
+            mime_type,
+        );
+        add_span_event(
+            CONTENT_CREATED,
+            vec![
+                KeyValue::new(NAMESPACE, namespace.to_string()),
+                KeyValue::new(CONTENT_ID, content_id.to_string()),
+                KeyValue::new(SOURCE, source.to_string()),
+                KeyValue::new(MIME_TYPE, mime_type.to_string()),
+            ],
+        );
+    }
+
+    pub fn task_failed(
+        namespace: &str,
+        task_id: &str,
+        content_id: &str,
+        extractor: &str,
+        extraction_policy: &str,
+    ) {
+        warn!(
+            target: "indexify::events",
+            event = TASK_FAILED,
+            namespace,
+            task_id,
+            content_id,
+            extractor,
+            extraction_policy,
+        );
+        add_span_event(
+            TASK_FAILED,
+            vec![
+                KeyValue::new(NAMESPACE, namespace.to_string()),
+                KeyValue::new(TASK_ID, task_id.to_string()),
+                KeyValue::new(CONTENT_ID, content_id.to_string()),
+                KeyValue::new(EXTRACTOR, extractor.to_string()),
+                KeyValue::new(EXTRACTION_POLICY, extraction_policy.to_string()),
+            ],
+        );
+    }
+
+    pub fn index_written(
+        namespace: &str,
+        index: &str,
+        content_id: &str,
+        extractor: &str,
+        extraction_policy: &str,
+    ) {
+        info!(
+            target: "indexify::events",
+            event = INDEX_WRITTEN,
+            namespace,
+            index,
+            content_id,
+            extractor,
+            extraction_policy,
+        );
+        add_span_event(
+            INDEX_WRITTEN,
+            vec![
+                KeyValue::new(NAMESPACE, namespace.to_string()),
+                KeyValue::new(INDEX, index.to_string()),
+                KeyValue::new(CONTENT_ID, content_id.to_string()),
+                KeyValue::new(EXTRACTOR, extractor.to_string()),
+                KeyValue::new(EXTRACTION_POLICY, extraction_policy.to_string()),
+            ],
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use indexify_internal_api as internal_api;
+use serde_json::{json, Value};
+
+use crate::api;
+
+fn parameter(name: &str, description: &str, default: &str) -> api::NamespaceTemplateParameter {
+    api::NamespaceTemplateParameter {
+        name: name.to_string(),
+        description: description.to_string(),
+        default: default.to_string(),
+    }
+}
+
+fn policy(
+    name: &str,
+    extractor: &str,
+    content_source: &str,
+    input_params: Option<Value>,
+    keyword_index: bool,
+) -> api::ExtractionPolicyRequest {
+    api::ExtractionPolicyRequest {
+        extractor: extractor.to_string(),
+        name: name.to_string(),
+        filters_eq: None,
+        input_params,
+        content_source: Some(content_source.to_string()),
+        operation_token: None,
+        keyword_index,
+        quantization: None,
+        distance: None,
+        embedding_ttl_secs: None,
+        index_params: None,
+        content_filter: None,
+        backfill: api::Backfill::All,
+        retry_policy: None,
+        priority: 0,
+    }
+}
+
+fn chunking_parameters() -> Vec<api::NamespaceTemplateParameter> {
+    vec![
+        parameter(
+            "chunker",
+            "Extractor splitting text into chunks",
+            "tensorlake/chunk-extractor",
+        ),
+        parameter("chunk_size", "Size of the chunks in characters", "1000"),
+        parameter(
+            "chunk_overlap",
+            "Number of characters shared by consecutive chunks",
+            "100",
+        ),
+        parameter(
+            "embedder",
+            "Extractor embedding the chunks",
+            "tensorlake/minilm-l6",
+        ),
+    ]
+}
+
+fn chunk_input_params() -> Option<Value> {
+    Some(json!({"chunk_size": "{chunk_size}", "overlap": "{chunk_overlap}"}))
+}
+
+/// The templates which ship with the server
+pub fn builtin_templates() -> Vec<api::NamespaceTemplate> {
+    vec![
+        api::NamespaceTemplate {
+            name: "pdf_knowledge_base".to_string(),
+            description: "Extracts the text of PDFs, splits it into chunks and embeds the chunks"
+                .to_string(),
+            parameters: [
+                vec![parameter(
+                    "pdf_extractor",
+                    "Extractor converting PDFs to text",
+                    "tensorlake/pdf-extractor",
+                )],
+                chunking_parameters(),
+            ]
+            .concat(),
+            extraction_policies: vec![
+                policy("pdf_to_text", "{pdf_extractor}", "ingestion", None, false),
+                policy(
+                    "chunks",
+                    "{chunker}",
+                    "pdf_to_text",
+                    chunk_input_params(),
+                    false,
+                ),
+                policy("embeddings", "{embedder}", "chunks", None, false),
+            ],
+        },
+        api::NamespaceTemplate {
+            name: "text_knowledge_base".to_string(),
+            description:
+                "Splits ingested text into chunks which are embedded and indexed for keyword search"
+                    .to_string(),
+            parameters: chunking_parameters(),
+            extraction_policies: vec![
+                policy(
+                    "chunks",
+                    "{chunker}",
+                    "ingestion",
+                    chunk_input_params(),
+                    true,
+                ),
+                policy("embeddings", "{embedder}", "chunks", None, false),
+            ],
+        },
+        api::NamespaceTemplate {
+            name: "audio_transcripts".to_string(),
+            description:
+                "Transcribes audio, splits the transcripts into chunks and embeds the chunks"
+                    .to_string(),
+            parameters: [
+                vec![parameter(
+                    "transcriber",
+                    "Extractor transcribing audio to text",
+                    "tensorlake/whisper-asr",
+                )],
+                chunking_parameters(),
+            ]
+            .concat(),
+            extraction_policies: vec![
+                policy("transcripts", "{transcriber}", "ingestion", None, false),
+                policy(
+                    "chunks",
+                    "{chunker}",
+                    "transcripts",
+                    chunk_input_params(),
+                    false,
+                ),
+                policy("embeddings", "{embedder}", "chunks", None, false),
+            ],
+        },
+    ]
+}
+
+pub fn get_template(name: &str) -> Result<api::NamespaceTemplate> {
+    builtin_templates()
+        .into_iter()
+        .find(|template| template.name == name)
+        .ok_or_else(|| anyhow!("namespace template {} not found", name))
+}
+
+/// Returns the extraction policies of the template with the placeholders
+/// filled in, in the order they have to be created.
+pub fn render_policies(
+    template: &api::NamespaceTemplate,
+    overrides: &HashMap<String, String>,
+) -> Result<Vec<api::ExtractionPolicyRequest>> {
+    //  Catch misspelled parameters instead of silently using the default
+    for name in overrides.keys() {
+        if !template.parameters.iter().any(|p| &p.name == name) {
+            return Err(anyhow!(
+                "unknown parameter {} for namespace template {}",
+                name,
+                template.name
+            ));
+        }
+    }
+    let mut parameters: HashMap<String, String> = template
+        .parameters
+        .iter()
+        .map(|p| (p.name.clone(), p.default.clone()))
+        .collect();
+    parameters.extend(overrides.clone());
+
+    let render = |text: &str| {
+        internal_api::fill_placeholders(text, |name| parameters.get(name)).map_err(|name| {
+            anyhow!(
+                "missing parameter {} for namespace template {}",
+                name,
+                template.name
+            )
+        })
+    };
+    let mut policies = Vec::new();
+    for policy in &template.extraction_policies {
+        let mut policy = policy.clone();
+        policy.extractor = render(&policy.extractor)?;
+        policy.content_source = policy.content_source.as_deref().map(render).transpose()?;
+        policy.input_params = policy
+            .input_params
+            .as_ref()
+            .map(|input_params| render_value(input_params, &render))
+            .transpose()?;
+        policies.push(policy);
+    }
+    Ok(policies)
+}
+
+fn render_value(value: &Value, render: &impl Fn(&str) -> Result<String>) -> Result<Value> {
+    match value {
+        Value::String(text) => {
+            let rendered = render(text)?;
+            let is_placeholder = text.starts_with('{') && text.ends_with('}') && rendered != *text;
+            if is_placeholder {
+                if let Ok(value) = serde_json::from_str::<Value>(&rendered) {
+                    return Ok(value);
+                }
+            }
+            Ok(Value::String(rendered))
+        }
+        Value::Array(values) => Ok(Value::Array(
+            values
+                .iter()
+                .map(|v| render_value(v, render))
+                .collect::<Result<_>>()?,
+        )),
+        Value::Object(map) => Ok(Value::Object(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), render_value(v, render)?)))
+                .collect::<Result<_>>()?,
+        )),
+        _ => Ok(value.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_policies_with_defaults() {
+        let template = get_template("pdf_knowledge_base").unwrap();
+        let policies = render_policies(&template, &HashMap::new()).unwrap();
+        let names: Vec<&str> = policies.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["pdf_to_text", "chunks", "embeddings"]);
+        assert_eq!(policies[0].extractor, "tensorlake/pdf-extractor");
+        assert_eq!(policies[1].content_source.as_deref(), Some("pdf_to_text"));
+        assert_eq!(
+            policies[1].input_params,
+            Some(json!({"chunk_size": 1000, "overlap": 100}))
+        );
+        assert_eq!(policies[2].extractor, "tensorlake/minilm-l6");
+    }
+
+    #[test]
+    fn test_render_policies_with_overrides() {
+        let template = get_template("text_knowledge_base").unwrap();
+        let overrides = HashMap::from([
+            ("embedder".to_string(), "acme/embedder".to_string()),
+            ("chunk_size".to_string(), "500".to_string()),
+        ]);
+        let policies = render_policies(&template, &overrides).unwrap();
+        assert!(policies[0].keyword_index);
+        assert_eq!(
+            policies[0].input_params,
+            Some(json!({"chunk_size": 500, "overlap": 100}))
+        );
+        assert_eq!(policies[1].extractor, "acme/embedder");
+
+        let overrides = HashMap::from([("chunksize".to_string(), "500".to_string())]);
+        assert!(render_policies(&template, &overrides).is_err());
+        assert!(get_template("unknown").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use indexify_internal_api as internal_api;
+use serde::Serialize;
+
+pub const DEFAULT_SLA_ATTAINMENT_THRESHOLD: f64 = 0.95;
+
+pub const DEFAULT_SLA_WINDOW_SECS: u64 = 3600;
+
+//  Attainment over fewer samples than this doesn't trigger alerts, a single
+// slow content right after startup would otherwise breach any sla
+pub const MIN_SLA_SAMPLES: u64 = 20;
+
+//  Samples kept per sla, the oldest samples are dropped first once a window
+// holds more
+const MAX_SLA_SAMPLES: usize = 100_000;
+
+const SLA_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Sample {
+    recorded_at: u64,
+    //  None if the policy failed to process the content
+    latency_secs: Option<u64>,
+}
+
+#[derive(Default)]
+struct SlaWindow {
+    samples: VecDeque<Sample>,
+    breached: bool,
+}
+
+impl SlaWindow {
+    fn prune(&mut self, window_secs: u64, now: u64) {
+        let oldest = now.saturating_sub(window_secs);
+        while self
+            .samples
+            .front()
+            .map_or(false, |sample| sample.recorded_at < oldest)
+        {
+            self.samples.pop_front();
+        }
+        while self.samples.len() > MAX_SLA_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    //  Latencies are kept rather than whether they met the target, so
+    // changing the target of an sla applies to the current window
+    fn attainment(&self, sla: &internal_api::ProcessingSla) -> SlaAttainment {
+        let met = self
+            .samples
+            .iter()
+            .filter(|sample| {
+                sample
+                    .latency_secs
+                    .map_or(false, |latency| latency <= sla.target_secs)
+            })
+            .count() as u64;
+        let samples = self.samples.len() as u64;
+        SlaAttainment {
+            samples,
+            met,
+            attainment: (samples > 0).then(|| met as f64 / samples as f64),
+            breached: self.breached,
+        }
+    }
+}
+
+/// Attainment of an sla over its current window.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SlaAttainment {
+    pub samples: u64,
+    pub met: u64,
+    //  None until content was processed in the window
+    pub attainment: Option<f64>,
+    pub breached: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaAlertKind {
+    Breached,
+    Recovered,
+}
+
+/// Body of the webhook posted when the attainment of an sla drops below its
+/// threshold, and again once it recovers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SlaAlert {
+    pub event: SlaAlertKind,
+    pub namespace: String,
+    pub extraction_policy: String,
+    pub target_secs: u64,
+    pub attainment_threshold: f64,
+    pub window_secs: u64,
+    pub attainment: f64,
+    pub samples: u64,
+    pub timestamp: u64,
+}
+
+/// Tracks the processing latency of the content of extraction policies with
+/// an sla over a sliding window. Every ingestion server tracks the tasks
+/// whose results it writes.
+#[derive(Default)]
+pub struct SlaTracker {
+    //  Keyed by the id of the sla
+    windows: Mutex<HashMap<String, SlaWindow>>,
+}
+
+impl SlaTracker {
+    /// Records that the policy of the sla finished processing a content,
+    /// `latency_secs` after the content was ingested, or failed to process it.
+    /// Returns the alert to send if attainment crossed the threshold.
+    pub fn record(
+        &self,
+        sla: &internal_api::ProcessingSla,
+        latency_secs: Option<u64>,
+        now: u64,
+    ) -> Option<SlaAlert> {
+        let mut windows = self.windows.lock().unwrap();
+        let window = windows.entry(sla.id()).or_default();
+        window.samples.push_back(Sample {
+            recorded_at: now,
+            latency_secs,
+        });
+        window.prune(sla.window_secs, now);
+        let attainment = window.attainment(sla);
+        let value = attainment.attainment.unwrap_or(1.0);
+        let event = if !window.breached &&
+            attainment.samples >= MIN_SLA_SAMPLES &&
+            value < sla.attainment_threshold
+        {
+            SlaAlertKind::Breached
+        } else if window.breached && value >= sla.attainment_threshold {
+            SlaAlertKind::Recovered
+        } else {
+            return None;
+        };
+        window.breached = event == SlaAlertKind::Breached;
+        Some(SlaAlert {
+            event,
+            namespace: sla.namespace.clone(),
+            extraction_policy: sla.extraction_policy.clone(),
+            target_secs: sla.target_secs,
+            attainment_threshold: sla.attainment_threshold,
+            window_secs: sla.window_secs,
+            attainment: value,
+            samples: attainment.samples,
+            timestamp: now,
+        })
+    }
+
+    pub fn attainment(&self, sla: &internal_api::ProcessingSla, now: u64) -> SlaAttainment {
+        let mut windows = self.windows.lock().unwrap();
+        match windows.get_mut(&sla.id()) {
+            Some(window) => {
+                window.prune(sla.window_secs, now);
+                window.attainment(sla)
+            }
+            None => SlaAttainment::default(),
+        }
+    }
+
+    /// Forgets the samples of a deleted sla.
+    pub fn remove(&self, sla_id: &str) {
+        self.windows.lock().unwrap().remove(sla_id);
+    }
+}
+
+impl std::fmt::Debug for SlaTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SlaTracker").finish()
+    }
+}
+
+pub async fn send_alert(
+    client: &reqwest::Client,
+    webhook_url: &str,
+    alert: &SlaAlert,
+) -> Result<()> {
+    let response = client
+        .post(webhook_url)
+        .timeout(SLA_WEBHOOK_TIMEOUT)
+        .json(alert)
+        .send()
+        .await
+        .map_err(|e| anyhow!("unable to send sla alert to {}: {}", webhook_url, e))?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "sla webhook {} responded with {}",
+            webhook_url,
+            response.status()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sla() -> internal_api::ProcessingSla {
+        internal_api::ProcessingSla {
+            namespace: "default".to_string(),
+            extraction_policy: "embeddings".to_string(),
+            extraction_policy_id: "embeddings-id".to_string(),
+            target_secs: 10,
+            attainment_threshold: 0.9,
+            window_secs: 100,
+            webhook_url: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sla_alerts() {
+        let sla = sla();
+        let tracker = SlaTracker::default();
+
+        //  No alert before there are enough samples
+        assert!(tracker.record(&sla, None, 0).is_none());
+        assert!(tracker.record(&sla, Some(20), 0).is_none());
+        for _ in 2..MIN_SLA_SAMPLES - 1 {
+            assert!(tracker.record(&sla, Some(5), 0).is_none());
+        }
+        let alert = tracker.record(&sla, Some(20), 0).unwrap();
+        assert_eq!(alert.event, SlaAlertKind::Breached);
+        assert_eq!(alert.samples, MIN_SLA_SAMPLES);
+        assert_eq!(alert.attainment, 0.85);
+        let attainment = tracker.attainment(&sla, 0);
+        assert_eq!(attainment.met, MIN_SLA_SAMPLES - 3);
+        assert!(attainment.breached);
+
+        //  Only crossing the threshold alerts
+        assert!(tracker.record(&sla, None, 0).is_none());
+
+        //  The misses drop out of the window
+        let alert = tracker.record(&sla, Some(1), 101).unwrap();
+        assert_eq!(alert.event, SlaAlertKind::Recovered);
+        assert_eq!(alert.samples, 1);
+        assert!(!tracker.attainment(&sla, 101).breached);
+
+        tracker.remove(&sla.id());
+        assert_eq!(tracker.attainment(&sla, 101), SlaAttainment::default());
+    }
+
+    #[test]
+    fn test_sla_target_change() {
+        let sla = sla();
+        let tracker = SlaTracker::default();
+        tracker.record(&sla, Some(5), 0);
+        tracker.record(&sla, Some(15), 0);
+        assert_eq!(tracker.attainment(&sla, 0).attainment, Some(0.5));
+
+        let relaxed = internal_api::ProcessingSla {
+            target_secs: 20,
+            ..sla
+        };
+        assert_eq!(tracker.attainment(&relaxed, 0).attainment, Some(1.0));
+    }
+}
@@ -0,0 +1,420 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use indexify_internal_api as internal_api;
+use indexify_proto::indexify_coordinator::{self, HeartbeatRequest, RegisterExecutorRequest};
+use tokio::sync::{watch, Semaphore};
+use tracing::{error, info};
+
+use crate::{
+    api::{self, BeginExtractedContentIngest},
+    blob_storage::ContentReader,
+    coordinator_client::CoordinatorClient,
+    data_manager::DataManager,
+    server_config::RemoteExtractorConfig,
+};
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+//  Exponential backoff between retries of a failed extraction request
+fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_millis(500)
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_RETRY_DELAY)
+}
+
+enum RequestError {
+    Retryable(anyhow::Error),
+    Fatal(anyhow::Error),
+}
+
+#[derive(Debug)]
+pub struct RemoteExtractor {
+    config: RemoteExtractorConfig,
+    client: reqwest::Client,
+    permits: Semaphore,
+}
+
+impl RemoteExtractor {
+    pub fn new(config: RemoteExtractorConfig) -> Result<Self> {
+        if config.max_concurrency == 0 {
+            return Err(anyhow!(
+                "remote extractor {} needs a max_concurrency of at least 1",
+                config.name
+            ));
+        }
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| anyhow!("unable to create request client: {}", e))?;
+        let permits = Semaphore::new(config.max_concurrency);
+        Ok(Self {
+            config,
+            client,
+            permits,
+        })
+    }
+
+    pub fn description(&self) -> internal_api::ExtractorDescription {
+        internal_api::ExtractorDescription {
+            name: self.config.name.clone(),
+            description: self.config.description.clone(),
+            input_params: self.config.input_params.clone(),
+            outputs: self.config.outputs.clone(),
+            input_mime_types: self.config.input_mime_types.clone(),
+        }
+    }
+
+    pub async fn extract(
+        &self,
+        request: &internal_api::ExtractRequest,
+    ) -> Result<internal_api::ExtractResponse> {
+        let _permit = self.permits.acquire().await?;
+        self.extract_with_retries(request).await
+    }
+
+    async fn extract_with_retries(
+        &self,
+        request: &internal_api::ExtractRequest,
+    ) -> Result<internal_api::ExtractResponse> {
+        let mut attempt = 0;
+        loop {
+            match self.send(request).await {
+                Ok(response) => return Ok(response),
+                Err(RequestError::Fatal(e)) => return Err(e),
+                Err(RequestError::Retryable(e)) => {
+                    if attempt >= self.config.max_retries {
+                        return Err(e);
+                    }
+                    let delay = retry_delay(attempt);
+                    attempt += 1;
+                    info!(
+                        "request to remote extractor {} failed: {}, retrying in {:?}",
+                        self.config.name, e, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    async fn send(
+        &self,
+        request: &internal_api::ExtractRequest,
+    ) -> Result<internal_api::ExtractResponse, RequestError> {
+        let mut req = self.client.post(&self.config.url).json(request);
+        if let Some(token) = &self.config.bearer_token {
+            req = req.bearer_auth(token);
+        }
+        for (name, value) in &self.config.headers {
+            req = req.header(name, value);
+        }
+        let resp = req.send().await.map_err(|e| {
+            RequestError::Retryable(anyhow!(
+                "unable to reach remote extractor {}: {}",
+                self.config.name,
+                e
+            ))
+        })?;
+        let status = resp.status();
+        if !status.is_success() {
+            let err = anyhow!(
+                "remote extractor {} failed: status: {}, error: {}",
+                self.config.name,
+                status,
+                resp.text().await.unwrap_or_default()
+            );
+            if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Err(RequestError::Retryable(err));
+            }
+            return Err(RequestError::Fatal(err));
+        }
+        resp.json::<internal_api::ExtractResponse>()
+            .await
+            .map_err(|e| {
+                RequestError::Fatal(anyhow!(
+                    "unable to decode response of remote extractor {}: {}",
+                    self.config.name,
+                    e
+                ))
+            })
+    }
+
+    //  Registers the extractor with the coordinator as an executor and runs
+    //  the tasks the coordinator hands out until the heartbeat stream ends.
+    async fn run_tasks(
+        self: &Arc<Self>,
+        executor_id: &str,
+        coordinator_client: &CoordinatorClient,
+        data_manager: &Arc<DataManager>,
+        content_reader: &Arc<ContentReader>,
+        in_flight: &Arc<Mutex<HashSet<String>>>,
+    ) -> Result<()> {
+        let mut client = coordinator_client.get().await?;
+        client
+            .register_executor(RegisterExecutorRequest {
+                executor_id: executor_id.to_string(),
+                addr: self.config.url.clone(),
+                extractor: Some(self.description().into()),
+                labels: self.config.labels.clone(),
+            })
+            .await?;
+        info!(
+            "registered remote extractor {} as executor {}",
+            self.config.name, executor_id
+        );
+
+        let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+        let heartbeat_executor_id = executor_id.to_string();
+        let heartbeat_in_flight = in_flight.clone();
+        let request = tonic::Request::new(async_stream::stream! {
+            loop {
+                interval.tick().await;
+                let pending_tasks = heartbeat_in_flight.lock().unwrap().len() as i64;
+                yield HeartbeatRequest {
+                    executor_id: heartbeat_executor_id.clone(),
+                    pending_tasks,
+                };
+            }
+        });
+        let mut stream = client.heartbeat(request).await?.into_inner();
+        while let Some(response) = stream.message().await? {
+            for task in response.tasks {
+                //  The coordinator returns every task that's still assigned to
+                //  the executor, including the ones that are already running
+                if !in_flight.lock().unwrap().insert(task.id.clone()) {
+                    continue;
+                }
+                let extractor = self.clone();
+                let executor_id = executor_id.to_string();
+                let data_manager = data_manager.clone();
+                let content_reader = content_reader.clone();
+                let in_flight = in_flight.clone();
+                tokio::spawn(async move {
+                    let task_id = task.id.clone();
+                    if let Err(e) = extractor
+                        .run_task(task, &executor_id, &data_manager, &content_reader)
+                        .await
+                    {
+                        error!("unable to finish task {}: {}", task_id, e);
+                    }
+                    in_flight.lock().unwrap().remove(&task_id);
+                });
+            }
+        }
+        Ok(())
+    }
+
+    async fn run_task(
+        &self,
+        task: indexify_coordinator::Task,
+        executor_id: &str,
+        data_manager: &DataManager,
+        content_reader: &ContentReader,
+    ) -> Result<()> {
+        let content_meta = task
+            .content_metadata
+            .clone()
+            .ok_or_else(|| anyhow!("task {} has no content", task.id))?;
+        let mut begin_ingest = BeginExtractedContentIngest {
+            task_id: task.id.clone(),
+            namespace: task.namespace.clone(),
+            output_to_index_table_mapping: task.output_index_mapping.clone(),
+            parent_content_id: content_meta.id.clone(),
+            executor_id: executor_id.to_string(),
+            task_outcome: internal_api::TaskOutcome::Success,
+            extraction_policy: task.extraction_policy_id.clone(),
+            extractor: task.extractor.clone(),
+            index_tables: task.index_tables.clone(),
+            request_id: None,
+        };
+        if let Err(e) = self
+            .ingest_task_output(
+                &task,
+                &content_meta,
+                &begin_ingest,
+                data_manager,
+                content_reader,
+            )
+            .await
+        {
+            error!(
+                "remote extractor {} failed task {}: {}",
+                self.config.name, task.id, e
+            );
+            begin_ingest.task_outcome = internal_api::TaskOutcome::Failed;
+        }
+        data_manager
+            .finish_extracted_content_write(begin_ingest)
+            .await
+    }
+
+    async fn ingest_task_output(
+        &self,
+        task: &indexify_coordinator::Task,
+        content_meta: &indexify_coordinator::ContentMetadata,
+        begin_ingest: &BeginExtractedContentIngest,
+        data_manager: &DataManager,
+        content_reader: &ContentReader,
+    ) -> Result<()> {
+        let input_params = match task.input_params.as_str() {
+            "" => None,
+            params => Some(serde_json::from_str(params)?),
+        };
+        let _permit = self.permits.acquire().await?;
+        let bytes = content_reader
+            .verified_bytes(&content_meta.storage_url, &content_meta.hash)
+            .await?;
+        let request = internal_api::ExtractRequest {
+            content: internal_api::Content {
+                content_type: content_meta.mime.clone(),
+                bytes: bytes.to_vec(),
+                features: vec![],
+                labels: content_meta.labels.clone(),
+            },
+            input_params,
+        };
+        let response = self.extract_with_retries(&request).await?;
+        if !response.content.is_empty() {
+            let content_list = response.content.into_iter().map(Into::into).collect();
+            data_manager
+                .write_extracted_content(
+                    begin_ingest.clone(),
+                    api::ExtractedContent { content_list },
+                )
+                .await?;
+        }
+        if !response.features.is_empty() {
+            let features = response.features.into_iter().map(Into::into).collect();
+            data_manager
+                .write_existing_content_features(
+                    &task.extractor,
+                    &task.extraction_policy_id,
+                    content_meta,
+                    features,
+                    &task.output_index_mapping,
+                    &task.index_tables,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RemoteExtractors {
+    extractors: HashMap<String, Arc<RemoteExtractor>>,
+}
+
+impl RemoteExtractors {
+    pub fn new(configs: &[RemoteExtractorConfig]) -> Result<Self> {
+        let mut extractors = HashMap::new();
+        for config in configs {
+            let name = config.name.clone();
+            let extractor = Arc::new(RemoteExtractor::new(config.clone())?);
+            if extractors.insert(name.clone(), extractor).is_some() {
+                return Err(anyhow!("remote extractor {} is configured twice", name));
+            }
+        }
+        Ok(Self { extractors })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<RemoteExtractor>> {
+        self.extractors.get(name).cloned()
+    }
+
+    //  Starts a task runner for every remote extractor. Each API server
+    //  registers its own executor per extractor, so the coordinator spreads
+    //  the tasks across the servers.
+    pub fn start(
+        &self,
+        coordinator_client: Arc<CoordinatorClient>,
+        data_manager: Arc<DataManager>,
+        content_reader: Arc<ContentReader>,
+        ingestion_server_id: &str,
+        shutdown_rx: watch::Receiver<bool>,
+    ) {
+        for extractor in self.extractors.values() {
+            let extractor = extractor.clone();
+            let executor_id = format!("remote-{}-{}", extractor.config.name, ingestion_server_id);
+            let coordinator_client = coordinator_client.clone();
+            let data_manager = data_manager.clone();
+            let content_reader = content_reader.clone();
+            let mut shutdown_rx = shutdown_rx.clone();
+            let in_flight = Arc::new(Mutex::new(HashSet::new()));
+            tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        _ = shutdown_rx.changed() => {
+                            info!("shutting down remote extractor {}", extractor.config.name);
+                            break;
+                        }
+                        res = extractor.run_tasks(
+                            &executor_id,
+                            &coordinator_client,
+                            &data_manager,
+                            &content_reader,
+                            &in_flight,
+                        ) => {
+                            if let Err(e) = res {
+                                error!(
+                                    "remote extractor {} lost the coordinator: {}, reconnecting...",
+                                    extractor.config.name, e
+                                );
+                            }
+                        }
+                    }
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(0), Duration::from_millis(500));
+        assert_eq!(retry_delay(1), Duration::from_secs(1));
+        assert_eq!(retry_delay(3), Duration::from_secs(4));
+        assert_eq!(retry_delay(10), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(64), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_remote_extractors_from_config() {
+        let config: RemoteExtractorConfig = serde_json::from_value(serde_json::json!({
+            "name": "minilm",
+            "url": "http://localhost:9000/extract",
+            "outputs": {
+                "embedding": {"embedding": {"dim": 384, "distance": "cosine"}}
+            },
+            "bearer_token": "secret",
+        }))
+        .unwrap();
+        assert_eq!(config.max_concurrency, 4);
+        assert_eq!(config.input_mime_types, vec!["*/*".to_string()]);
+
+        let extractors = RemoteExtractors::new(&[config.clone()]).unwrap();
+        let description = extractors.get("minilm").unwrap().description();
+        assert_eq!(description.name, "minilm");
+        assert!(description.outputs.contains_key("embedding"));
+        assert!(extractors.get("other").is_none());
+
+        assert!(RemoteExtractors::new(&[config.clone(), config.clone()]).is_err());
+        let mut config = config;
+        config.max_concurrency = 0;
+        assert!(RemoteExtractors::new(&[config]).is_err());
+    }
+}
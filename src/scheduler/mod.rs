@@ -1,5 +1,5 @@
 use std::{
-    collections::{hash_map::DefaultHasher, HashMap},
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
     hash::{Hash, Hasher},
 };
 
@@ -9,6 +9,8 @@ use indexify_internal_api::StateChange;
 use internal_api::OutputSchema;
 use tracing::info;
 
+pub mod simulation;
+
 use crate::{
     state::SharedState,
     task_allocator::{planner::plan::TaskAllocationPlan, TaskAllocator},
@@ -96,10 +98,25 @@ impl Scheduler {
     ) -> Result<Vec<internal_api::Task>> {
         let tasks = match &state_change.change_type {
             internal_api::ChangeType::NewExtractionPolicy => {
+                //  The policy may have been deleted before the state change
+                // was processed
+                let Some(extraction_policy) = self
+                    .shared_state
+                    .get_extraction_policies_from_ids(HashSet::from([state_change
+                        .object_id
+                        .clone()]))
+                    .await?
+                    .and_then(|policies| policies.into_iter().next())
+                else {
+                    return Ok(Vec::new());
+                };
                 let content_list = self
                     .shared_state
-                    .content_matching_policy(&state_change.object_id)
-                    .await?;
+                    .content_matching_extraction_policy(&extraction_policy)
+                    .await?
+                    .into_iter()
+                    .filter(|content| extraction_policy.backfills_content(content))
+                    .collect();
                 self.create_task_list(&state_change.object_id, content_list)
                     .await?
             }
@@ -129,9 +146,18 @@ impl Scheduler {
 
     pub async fn allocate_tasks(
         &self,
-        tasks: Vec<internal_api::Task>,
+        mut tasks: Vec<internal_api::Task>,
     ) -> Result<TaskAllocationPlan> {
-        let task_ids = tasks.iter().map(|task| task.id.clone()).collect();
+        //  Tasks of policies with a higher priority are allocated first, so
+        // they go to the least loaded executors
+        tasks.sort_by(|a, b| {
+            b.priority.cmp(&a.priority).then(
+                a.content_metadata
+                    .created_at
+                    .cmp(&b.content_metadata.created_at),
+            )
+        });
+        let task_ids = tasks.into_iter().map(|task| task.id).collect();
         self.task_allocator
             .allocate_tasks(task_ids)
             .await
@@ -208,10 +234,28 @@ impl Scheduler {
                 .unwrap();
             output_mapping.insert(name.clone(), table_name.clone());
         }
+        //  The keyword index isn't an extractor output, it's written by the
+        // ingestion server with the text content produced by the task
+        if let Some(index_name) = extraction_policy
+            .output_index_name_mapping
+            .get(internal_api::KEYWORD_INDEX_OUTPUT)
+        {
+            if let Some(table_name) = extraction_policy.index_name_table_mapping.get(index_name) {
+                output_mapping.insert(
+                    internal_api::KEYWORD_INDEX_OUTPUT.to_string(),
+                    table_name.clone(),
+                );
+            }
+        }
         let mut hasher = DefaultHasher::new();
         extraction_policy.name.hash(&mut hasher);
         extraction_policy.namespace.hash(&mut hasher);
         content.id.hash(&mut hasher);
+        //  Content extracted again after the policy is updated gets a new task
+        // rather than replacing the task of the earlier version
+        if extraction_policy.version > 0 {
+            extraction_policy.version.hash(&mut hasher);
+        }
         let id = format!("{:x}", hasher.finish());
         let task = internal_api::Task {
             id,
@@ -223,6 +267,9 @@ impl Scheduler {
             input_params: extraction_policy.input_params.clone(),
             outcome: internal_api::TaskOutcome::Unknown,
             index_tables: index_tables.to_vec(),
+            attempts: 0,
+            retry_at: None,
+            priority: extraction_policy.priority,
         };
         info!("created task: {:?}", task);
         Ok(task)
@@ -0,0 +1,233 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::state::store::{ExecutorId, ExtractorName, TaskId};
+
+/// Latency assumed for an extractor which hasn't completed any tasks yet.
+pub const DEFAULT_TASK_LATENCY: Duration = Duration::from_secs(1);
+
+/// Weight of the running average relative to the latest observation.
+const LATENCY_HISTORY_WEIGHT: u32 = 4;
+
+/// Keeps an exponentially weighted moving average of how long tasks take per
+/// extractor, measured from the time a task is first handed to an executor
+/// until the executor reports an outcome.
+///
+/// The stats are kept in memory on the coordinator and are only used for
+/// planning, so losing them on restart just means falling back to the
+/// default latency until new tasks complete.
+#[derive(Default)]
+pub struct TaskLatencyStats {
+    dispatched_at: Mutex<HashMap<TaskId, Instant>>,
+    average_latency: Mutex<HashMap<ExtractorName, Duration>>,
+}
+
+impl TaskLatencyStats {
+    pub fn record_dispatch(&self, task_id: &str) {
+        self.dispatched_at
+            .lock()
+            .unwrap()
+            .entry(task_id.to_string())
+            .or_insert_with(Instant::now);
+    }
+
+    pub fn record_completion(&self, task_id: &str, extractor: &str) {
+        let dispatched_at = self.dispatched_at.lock().unwrap().remove(task_id);
+        if let Some(dispatched_at) = dispatched_at {
+            self.observe(extractor, dispatched_at.elapsed());
+        }
+    }
+
+    pub fn observe(&self, extractor: &str, latency: Duration) {
+        let mut average_latency = self.average_latency.lock().unwrap();
+        let average = average_latency
+            .entry(extractor.to_string())
+            .or_insert(latency);
+        *average = (*average * LATENCY_HISTORY_WEIGHT + latency) / (LATENCY_HISTORY_WEIGHT + 1);
+    }
+
+    pub fn average_latency(&self, extractor: &str) -> Option<Duration> {
+        self.average_latency.lock().unwrap().get(extractor).cloned()
+    }
+}
+
+/// The capacity of a single executor as seen by the simulation.
+#[derive(Debug, Clone)]
+pub struct ExecutorCapacity {
+    pub executor_id: ExecutorId,
+    // Tasks already assigned to the executor which have to drain before any
+    // new work is started.
+    pub running_task_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct SimulationInput {
+    // Size of the content each task will process.
+    pub task_sizes: Vec<u64>,
+    pub executors: Vec<ExecutorCapacity>,
+    pub task_latency: Duration,
+    // Number of tasks an executor works on at the same time.
+    pub executor_concurrency: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchedulePlan {
+    pub total_tasks: usize,
+    pub total_bytes: u64,
+    pub queued_tasks: usize,
+    pub executors: usize,
+    pub task_latency: Duration,
+    // Wall clock time until the last simulated task finishes. None if there are
+    // no executors to run the tasks on.
+    pub estimated_completion: Option<Duration>,
+    // Executor time spent on the new tasks, summed across executors.
+    pub compute_time: Duration,
+    pub tasks_per_executor: HashMap<ExecutorId, usize>,
+}
+
+/// Simulates handing out the tasks to the executors the same way the load
+/// aware distributor does, always picking the executor slot which frees up
+/// first, and returns how long it would take to drain all of them.
+pub fn simulate(input: SimulationInput) -> SchedulePlan {
+    let total_tasks = input.task_sizes.len();
+    let total_bytes = input.task_sizes.iter().sum();
+    let queued_tasks = input.executors.iter().map(|e| e.running_task_count).sum();
+    let compute_time = input.task_latency * total_tasks as u32;
+    let mut plan = SchedulePlan {
+        total_tasks,
+        total_bytes,
+        queued_tasks,
+        executors: input.executors.len(),
+        task_latency: input.task_latency,
+        estimated_completion: None,
+        compute_time,
+        tasks_per_executor: HashMap::new(),
+    };
+    if input.executors.is_empty() {
+        return plan;
+    }
+
+    //  Every executor gets `executor_concurrency` slots. A slot is keyed by the
+    // time it becomes free, tasks already running on the executor are spread
+    // across its slots before any new task is placed.
+    let concurrency = input.executor_concurrency.max(1);
+    let mut slots = BinaryHeap::new();
+    for executor in &input.executors {
+        for slot in 0..concurrency {
+            let queued = executor.running_task_count / concurrency +
+                usize::from(slot < executor.running_task_count % concurrency);
+            let free_at = input.task_latency * queued as u32;
+            slots.push(Reverse((free_at, executor.executor_id.clone())));
+        }
+        plan.tasks_per_executor
+            .insert(executor.executor_id.clone(), 0);
+    }
+
+    let mut completion = slots
+        .iter()
+        .map(|Reverse((free_at, _))| *free_at)
+        .max()
+        .unwrap_or_default();
+    for _ in 0..total_tasks {
+        let Reverse((free_at, executor_id)) = slots.pop().unwrap();
+        let finished_at = free_at + input.task_latency;
+        completion = completion.max(finished_at);
+        *plan
+            .tasks_per_executor
+            .entry(executor_id.clone())
+            .or_default() += 1;
+        slots.push(Reverse((finished_at, executor_id)));
+    }
+    plan.estimated_completion = Some(completion);
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn executors(running: &[usize]) -> Vec<ExecutorCapacity> {
+        running
+            .iter()
+            .enumerate()
+            .map(|(i, running_task_count)| ExecutorCapacity {
+                executor_id: format!("executor{}", i),
+                running_task_count: *running_task_count,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_simulate_spreads_tasks_evenly() {
+        let plan = simulate(SimulationInput {
+            task_sizes: vec![10; 10],
+            executors: executors(&[0, 0]),
+            task_latency: Duration::from_secs(2),
+            executor_concurrency: 1,
+        });
+        assert_eq!(plan.total_tasks, 10);
+        assert_eq!(plan.total_bytes, 100);
+        assert_eq!(plan.estimated_completion, Some(Duration::from_secs(10)));
+        assert_eq!(plan.compute_time, Duration::from_secs(20));
+        assert_eq!(plan.tasks_per_executor.get("executor0"), Some(&5));
+        assert_eq!(plan.tasks_per_executor.get("executor1"), Some(&5));
+    }
+
+    #[test]
+    fn test_simulate_accounts_for_running_tasks() {
+        let plan = simulate(SimulationInput {
+            task_sizes: vec![1; 4],
+            executors: executors(&[4, 0]),
+            task_latency: Duration::from_secs(1),
+            executor_concurrency: 1,
+        });
+        // The idle executor takes all the work while the busy one drains.
+        assert_eq!(plan.queued_tasks, 4);
+        assert_eq!(plan.tasks_per_executor.get("executor0"), Some(&0));
+        assert_eq!(plan.tasks_per_executor.get("executor1"), Some(&4));
+        assert_eq!(plan.estimated_completion, Some(Duration::from_secs(4)));
+    }
+
+    #[test]
+    fn test_simulate_with_concurrency() {
+        let plan = simulate(SimulationInput {
+            task_sizes: vec![1; 8],
+            executors: executors(&[0]),
+            task_latency: Duration::from_secs(1),
+            executor_concurrency: 4,
+        });
+        assert_eq!(plan.estimated_completion, Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_simulate_without_executors() {
+        let plan = simulate(SimulationInput {
+            task_sizes: vec![1; 3],
+            executors: vec![],
+            task_latency: Duration::from_secs(1),
+            executor_concurrency: 1,
+        });
+        assert_eq!(plan.estimated_completion, None);
+        assert_eq!(plan.total_tasks, 3);
+    }
+
+    #[test]
+    fn test_latency_stats() {
+        let stats = TaskLatencyStats::default();
+        assert_eq!(stats.average_latency("extractor"), None);
+        stats.observe("extractor", Duration::from_secs(10));
+        assert_eq!(
+            stats.average_latency("extractor"),
+            Some(Duration::from_secs(10))
+        );
+        stats.observe("extractor", Duration::from_secs(20));
+        assert_eq!(
+            stats.average_latency("extractor"),
+            Some(Duration::from_secs(12))
+        );
+    }
+}
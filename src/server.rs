@@ -1,12 +1,22 @@
-use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    future::Future,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use anyhow::{anyhow, Result};
 use axum::{
     body::Body,
     extract::{DefaultBodyLimit, Multipart, Path, Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Response},
-    routing::{delete, get, post},
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+        Response,
+    },
+    routing::{delete, get, patch, post, put},
     Extension,
     Json,
     Router,
@@ -15,7 +25,15 @@ use axum_otel_metrics::HttpMetricsLayerBuilder;
 use axum_server::Handle;
 use axum_tracing_opentelemetry::middleware::OtelAxumLayer;
 use axum_typed_websockets::WebSocketUpgrade;
-use hyper::{header::CONTENT_TYPE, Method};
+use bytes::Bytes;
+use futures::{
+    stream::{BoxStream, FuturesUnordered},
+    Stream,
+};
+use hyper::{
+    header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE},
+    Method,
+};
 use indexify_internal_api as internal_api;
 use indexify_proto::indexify_coordinator::{
     self,
@@ -38,19 +56,48 @@ use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
     api::{self, *},
-    blob_storage::{BlobStorage, ContentReader},
+    api_utils,
+    blob_storage::{self, BlobStorage, ContentReader},
     caching::caches_extension::Caches,
-    coordinator_client::CoordinatorClient,
-    data_manager::DataManager,
+    coordinator_client::{CoordinatorClient, HEALTH_CHECK_INTERVAL},
+    data_manager::{
+        limit_results_per_parent,
+        DataManager,
+        NamespaceQuotaExceeded,
+        RequestIdConflict,
+        DEDUPE_OVERSAMPLING,
+        DEFAULT_SEARCH_LIMIT,
+        FILTER_EXPRESSION_OVERSAMPLING,
+        GEO_FILTER_OVERSAMPLING,
+        MAX_PER_PARENT_OVERSAMPLING,
+    },
     extractor_router::ExtractorRouter,
+    filter_expression::FilterExpr,
+    geo_filters,
     ingest_extracted_content::IngestExtractedContentState,
+    keyword_index::KeywordIndex,
     metadata_storage::{self, MetadataReaderTS, MetadataStorageTS},
-    server_config::ServerConfig,
-    vector_index::VectorIndexManager,
+    namespace_templates,
+    processing_slas,
+    remote_extractors::RemoteExtractors,
+    server_config::{OrphanedBlobsConfig, ServerConfig},
+    vector_index::{ScoredText, VectorIndexManager},
     vectordbs,
 };
 
-const DEFAULT_SEARCH_LIMIT: u64 = 5;
+const ABANDONED_INGEST_CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+const EXPIRED_EMBEDDINGS_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+const METADATA_RETENTION_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+const DEFAULT_PRESIGNED_URL_EXPIRY: Duration = Duration::from_secs(60 * 60);
+
+//  Upper bound on the number of records of a single metadata import request
+const MAX_METADATA_IMPORT_RECORDS: usize = 1000;
+
+//  Upper bound on the number of queries of a single batch search request
+const MAX_BATCH_SEARCH_QUERIES: usize = 256;
 
 #[derive(RustEmbed)]
 #[folder = "ui/build"]
@@ -61,6 +108,7 @@ pub struct NamespaceEndpointState {
     pub data_manager: Arc<DataManager>,
     pub coordinator_client: Arc<CoordinatorClient>,
     pub content_reader: Arc<ContentReader>,
+    pub remote_extractors: Arc<RemoteExtractors>,
 }
 
 #[derive(OpenApi)]
@@ -69,26 +117,90 @@ pub struct NamespaceEndpointState {
             create_namespace,
             list_namespaces,
             get_namespace,
+            delete_namespace,
             add_texts,
             list_indexes,
             index_search,
+            index_search_stream,
+            batch_search,
+            cross_namespace_search,
             list_extractors,
             create_extraction_policy,
             list_executors,
+            drain_executor,
             list_content,
             get_content_metadata,
+            get_content_metadata_by_external_id,
+            get_extracted_metadata_by_external_id,
             upload_file,
             list_tasks,
-            extract_content
+            watch_state_changes,
+            extract_content,
+            update_extraction_policy,
+            delete_extraction_policy,
+            plan_extraction_policy,
+            dry_run_extraction_policy,
+            create_index_migration,
+            get_index_migration,
+            complete_index_migration,
+            rebalance_index,
+            get_rebalance_job,
+            rebuild_index,
+            snapshot_index,
+            restore_index,
+            get_index_stats,
+            warm_up_index,
+            create_query_template,
+            list_query_templates,
+            get_query_template,
+            delete_query_template,
+            run_query_template,
+            set_scoring_plugin,
+            delete_scoring_plugin,
+            set_ingestion_plugin,
+            list_ingestion_plugins,
+            delete_ingestion_plugin,
+            set_processing_sla,
+            delete_processing_sla,
+            list_processing_slas,
+            query_metadata,
+            update_metadata,
+            import_metadata,
+            get_metadata_history,
+            export_metadata,
+            list_namespace_templates,
+            get_namespace_template,
+            instantiate_namespace_template,
+            presigned_content_url,
+            get_namespace_storage,
+            get_namespace_quotas,
+            set_namespace_quotas,
+            get_namespace_stats,
+            migrate_blobs,
+            get_blob_migration_job,
+            list_cluster_members,
+            get_cluster_health,
+            add_cluster_learner,
+            promote_cluster_learner,
+            remove_cluster_member
         ),
         components(
             schemas(CreateNamespace, CreateNamespaceResponse, IndexDistance,
                 TextAddRequest, TextAdditionResponse, Text, IndexSearchResponse,
-                DocumentFragment, ListIndexesResponse, ExtractorOutputSchema, Index, SearchRequest, ListNamespacesResponse, ListExtractorsResponse
-            , ExtractorDescription, DataNamespace, ExtractionPolicy, ExtractionPolicyRequest, ExtractionPolicyResponse, Executor,
-            MetadataResponse, ExtractedMetadata, ListExecutorsResponse, EmbeddingSchema, ExtractResponse, ExtractRequest,
-            Content, Feature, FeatureType, GetContentMetadataResponse, ListTasksResponse, internal_api::Task, internal_api::TaskOutcome,
+                DocumentFragment, ListIndexesResponse, ExtractorOutputSchema, Index, IndexType, SearchRequest, ListNamespacesResponse, ListExtractorsResponse
+            , ExtractorDescription, DataNamespace, ExtractionPolicy, ExtractionPolicyRequest, ContentFilter, LabelRange, RetryPolicy, BackoffStrategy, ExtractionPolicyResponse, UpdateExtractionPolicyRequest, UpdateExtractionPolicyResponse, DeletedPolicyIndexes, DeleteExtractionPolicyParams, DeleteExtractionPolicyResponse, Executor,
+            MetadataResponse, ExtractedMetadata, UpdateMetadataRequest, UpdateMetadataResponse, MetadataVersion, MetadataHistoryResponse, ExportMetadataRequest, MetadataExportFile, ExportMetadataResponse, ImportedMetadata, ImportMetadataRequest, RejectedMetadata, ImportMetadataResponse, MetadataProvenance, MetadataPage, ReadConsistency, ReadConsistencyParams, ListExecutorsResponse, ListExecutorsFilters, EmbeddingSchema, ExtractResponse, ExtractRequest,
+            Content, Feature, FeatureType, GetContentMetadataResponse, PresignedUrlParams, PresignedUrlResponse, NamespaceStorage, NamespaceQuotas, NamespaceQuotasResponse, NamespaceStats, BlobBackend, MigrateBlobs, BlobMigrationJobResponse, ClusterMember, ListClusterMembersResponse, CoordinatorNodeHealth, ClusterHealth, AddClusterLearner, ListTasksResponse, internal_api::Task, internal_api::TaskOutcome,
             internal_api::Content, internal_api::ContentMetadata, ListContentResponse, GetNamespaceResponse, ExtractionPolicyResponse,
+            ExtractionPolicyPlanResponse, ExtractionPolicyDryRunResponse, StreamingSearchRequest, SearchResultEvent, SearchErrorEvent, StateChangeEvent,
+            CreateIndexMigration, CompleteIndexMigration, IndexMigrationResponse, RebalanceIndex, RebuildIndex, RebuildSource, RestoreIndex, IndexSnapshotResponse, IndexStats, IndexWarmUp,
+            RebalanceJobResponse, UploadFileParams, RecencyBoost, CrossNamespaceSearchRequest,
+            CrossNamespaceSearchResponse, NamespaceSearchResult, BatchSearchRequest, BatchSearchResult, BatchSearchResponse, NamespaceSearchError, QueryTemplate, ListQueryTemplatesResponse, RunQueryTemplate,
+            GeoFilter, GeoRadius, GeoBoundingBox, MetadataQueryRequest, MetadataFilter, MetadataPredicate, NamespaceTemplate, SearchDedupe,
+            NamespaceTemplateParameter, ListNamespaceTemplatesResponse, InstantiateNamespaceTemplate,
+            InstantiateNamespaceTemplateResponse, IngestionPlugin, ListIngestionPluginsResponse,
+            WasmPluginParams, ScoringPlugin, IndexQuantization, ProcessingSlaRequest, ProcessingSla,
+            ProcessingSlaStatus, ListProcessingSlasResponse,
         )
         ),
         tags(
@@ -124,10 +236,29 @@ impl Server {
             false => info!("starting indexify server with TLS disabled"),
         }
         let vector_db = vectordbs::create_vectordb(self.config.index_config.clone()).await?;
-        let coordinator_client = Arc::new(CoordinatorClient::new(&self.config.coordinator_addr));
+        let keyword_index = Arc::new(KeywordIndex::new(
+            &self
+                .config
+                .index_config
+                .keyword_index_config
+                .clone()
+                .unwrap_or_default(),
+        )?);
+        let coordinator_client = Arc::new(CoordinatorClient::with_failover(
+            self.config.coordinator_endpoints(),
+        ));
+        let remote_extractors = Arc::new(RemoteExtractors::new(&self.config.remote_extractors)?);
+        let content_reader = Arc::new(ContentReader::from_config(&self.config.blob_storage)?);
         let vector_index_manager = Arc::new(
-            VectorIndexManager::new(coordinator_client.clone(), vector_db.clone())
-                .map_err(|e| anyhow!("unable to create vector index {}", e))?,
+            VectorIndexManager::new(
+                coordinator_client.clone(),
+                vector_db.clone(),
+                keyword_index,
+                remote_extractors.clone(),
+                self.config.index_config.write_buffer.clone(),
+                content_reader.clone(),
+            )
+            .map_err(|e| anyhow!("unable to create vector index {}", e))?,
         );
         let metadata_index_manager: MetadataStorageTS =
             metadata_storage::from_config(&self.config.metadata_storage)?;
@@ -136,14 +267,15 @@ impl Server {
         let blob_storage = Arc::new(BlobStorage::new_with_config(
             self.config.blob_storage.clone(),
         ));
+        let ingestion_server_id = nanoid::nanoid!(16);
         let data_manager = Arc::new(DataManager::new(
             vector_index_manager,
             metadata_index_manager,
             metadata_reader,
             blob_storage.clone(),
             coordinator_client.clone(),
+            &ingestion_server_id,
         ));
-        let ingestion_server_id = nanoid::nanoid!(16);
 
         self.start_gc_tasks_stream(
             coordinator_client.clone(),
@@ -151,10 +283,33 @@ impl Server {
             data_manager.clone(),
             shutdown_rx.clone(),
         );
+        self.start_abandoned_ingest_cleanup(data_manager.clone(), shutdown_rx.clone());
+        self.start_expired_embeddings_sweeper(data_manager.clone(), shutdown_rx.clone());
+        if !self.config.metadata_storage.retention_secs.is_empty() {
+            self.start_metadata_retention_sweeper(data_manager.clone(), shutdown_rx.clone());
+        }
+        if let Some(orphaned_blobs) = self.config.orphaned_blobs.clone() {
+            self.start_orphaned_blob_reconciliation(
+                data_manager.clone(),
+                orphaned_blobs,
+                shutdown_rx.clone(),
+            );
+        }
+        if coordinator_client.addrs().len() > 1 {
+            self.start_coordinator_health_checks(coordinator_client.clone(), shutdown_rx.clone());
+        }
+        remote_extractors.start(
+            coordinator_client.clone(),
+            data_manager.clone(),
+            content_reader.clone(),
+            &ingestion_server_id,
+            shutdown_rx.clone(),
+        );
         let namespace_endpoint_state = NamespaceEndpointState {
             data_manager: data_manager.clone(),
             coordinator_client: coordinator_client.clone(),
-            content_reader: Arc::new(ContentReader::new()),
+            content_reader,
+            remote_extractors,
         };
         let caches = Caches::new(self.config.cache.clone());
         let cors = CorsLayer::new()
@@ -173,10 +328,120 @@ impl Server {
                 "/namespaces/:namespace/extraction_policies",
                 post(create_extraction_policy).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/extraction_policies/dry_run",
+                post(dry_run_extraction_policy).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/extraction_policies/:extraction_policy",
+                patch(update_extraction_policy)
+                    .delete(delete_extraction_policy)
+                    .with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/extraction_policies/:extraction_policy/plan",
+                get(plan_extraction_policy).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/namespaces/:namespace/indexes",
                 get(list_indexes).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/indexes/:index/migration",
+                post(create_index_migration).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/indexes/:index/migration",
+                get(get_index_migration).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/indexes/:index/migration/complete",
+                post(complete_index_migration).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/indexes/:index/rebalance",
+                post(rebalance_index).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/indexes/:index/rebalance",
+                get(get_rebalance_job).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/indexes/:index/stats",
+                get(get_index_stats).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/indexes/:index/snapshot",
+                post(snapshot_index).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/indexes/:index/restore",
+                post(restore_index).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/indexes/:index/warm_up",
+                post(warm_up_index).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/indexes/:index/rebuild",
+                post(rebuild_index).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/indexes/:index/rebuild",
+                get(get_rebalance_job).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/query_templates",
+                post(create_query_template).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/query_templates",
+                get(list_query_templates).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/query_templates/:name",
+                get(get_query_template).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/query_templates/:name",
+                delete(delete_query_template).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/query_templates/:name/run",
+                post(run_query_template).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/query_templates/:name/scoring_plugin",
+                put(set_scoring_plugin).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/query_templates/:name/scoring_plugin",
+                delete(delete_scoring_plugin).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/ingestion_plugins",
+                get(list_ingestion_plugins).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/ingestion_plugins/:name",
+                put(set_ingestion_plugin).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/ingestion_plugins/:name",
+                delete(delete_ingestion_plugin).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/extraction_policies/:extraction_policy/sla",
+                put(set_processing_sla).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/extraction_policies/:extraction_policy/sla",
+                delete(delete_processing_sla).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/slas",
+                get(list_processing_slas).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/namespaces/:namespace/add_texts",
                 post(add_texts).with_state(namespace_endpoint_state.clone()),
@@ -197,14 +462,44 @@ impl Server {
                 "/namespaces/:namespace/content/:content_id/metadata",
                 get(get_extracted_metadata).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/content/:content_id/metadata",
+                patch(update_metadata).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/metadata/import",
+                post(import_metadata).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/metadata/export",
+                post(export_metadata).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/content/:content_id/metadata/history",
+                get(get_metadata_history).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/namespaces/:namespace/content/:content_id/download",
                 get(download_content).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/content/:content_id/presigned_url",
+                get(presigned_content_url).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/namespaces/:namespace/content/:content_id/content-tree",
                 get(get_content_tree_metadata).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/external_content/:external_id",
+                get(get_content_metadata_by_external_id)
+                    .with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/external_content/:external_id/metadata",
+                get(get_extracted_metadata_by_external_id)
+                    .with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/namespaces/:namespace/upload_file",
                 post(upload_file).with_state(namespace_endpoint_state.clone()),
@@ -217,10 +512,22 @@ impl Server {
                 "/namespaces/:namespace/search",
                 post(index_search).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/batch_search",
+                post(batch_search).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/search_stream",
+                post(index_search_stream).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/namespaces/:namespace/sql_query",
                 post(run_sql_query).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/metadata/query",
+                post(query_metadata).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/namespaces/:namespace/schemas",
                 get(list_schemas).with_state(namespace_endpoint_state.clone()),
@@ -235,12 +542,46 @@ impl Server {
             )
             .route(
                 "/namespaces/:namespace",
-                get(get_namespace).with_state(namespace_endpoint_state.clone()),
+                get(get_namespace)
+                    .delete(delete_namespace)
+                    .with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/storage",
+                get(get_namespace_storage).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/quotas",
+                get(get_namespace_quotas)
+                    .put(set_namespace_quotas)
+                    .with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/stats",
+                get(get_namespace_stats).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/blobs/migrate",
+                post(migrate_blobs).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/blobs/migrate",
+                get(get_blob_migration_job).with_state(namespace_endpoint_state.clone()),
+            )
+            .route("/namespace_templates", get(list_namespace_templates))
+            .route("/namespace_templates/:name", get(get_namespace_template))
+            .route(
+                "/namespace_templates/:name/instantiate",
+                post(instantiate_namespace_template).with_state(namespace_endpoint_state.clone()),
             )
             .route(
                 "/executors",
                 get(list_executors).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/executors/:executor_id/drain",
+                post(drain_executor).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/write_content",
                 get(ingest_extracted_content).with_state(namespace_endpoint_state.clone()),
@@ -253,6 +594,10 @@ impl Server {
                 "/state_changes",
                 get(list_state_changes).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/state_changes/watch",
+                get(watch_state_changes).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/namespaces/:namespace/tasks",
                 get(list_tasks).with_state(namespace_endpoint_state.clone()),
@@ -265,10 +610,34 @@ impl Server {
                 "/task_assignments",
                 get(list_task_assignments).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/admin/search",
+                post(cross_namespace_search).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/metrics/raft",
                 get(get_raft_metrics_snapshot).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/cluster/members",
+                get(list_cluster_members).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/cluster/health",
+                get(get_cluster_health).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/cluster/members/:node_id",
+                delete(remove_cluster_member).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/cluster/learners",
+                post(add_cluster_learner).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/cluster/learners/:node_id/promote",
+                post(promote_cluster_learner).with_state(namespace_endpoint_state.clone()),
+            )
             .route("/ui", get(ui_index_handler))
             .route("/ui/*rest", get(ui_handler))
             .layer(OtelAxumLayer::default())
@@ -289,10 +658,13 @@ impl Server {
 
         // Create the default namespace. It's idempotent so we can keep trying
         while let Err(err) = data_manager
-            .create_namespace(&DataNamespace {
-                name: "default".to_string(),
-                extraction_policies: vec![],
-            })
+            .create_namespace(
+                &DataNamespace {
+                    name: "default".to_string(),
+                    extraction_policies: vec![],
+                },
+                None,
+            )
             .await
         {
             info!("failed to create default namespace: {}", err);
@@ -313,6 +685,146 @@ impl Server {
         Ok(())
     }
 
+    //  Every server periodically cleans up uploads which were abandoned by
+    // any of the servers, so in-flight uploads of a server which went away
+    // don't leak blobs.
+    fn start_abandoned_ingest_cleanup(
+        &self,
+        data_manager: Arc<DataManager>,
+        shutdown_rx: watch::Receiver<bool>,
+    ) {
+        let timeout = Duration::from_secs(self.config.ingest_intent_timeout_secs);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(ABANDONED_INGEST_CLEANUP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if *shutdown_rx.borrow() {
+                    info!("shutting down abandoned ingest cleanup loop");
+                    break;
+                }
+                match data_manager.cleanup_abandoned_ingests(timeout).await {
+                    Ok(0) => {}
+                    Ok(cleaned_up) => info!("cleaned up {} abandoned uploads", cleaned_up),
+                    Err(e) => tracing::error!("unable to clean up abandoned uploads: {}", e),
+                }
+            }
+        });
+    }
+
+    //  Embeddings past their TTL are removed by every server, the expirations
+    // are kept in the coordinator
+    fn start_expired_embeddings_sweeper(
+        &self,
+        data_manager: Arc<DataManager>,
+        shutdown_rx: watch::Receiver<bool>,
+    ) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(EXPIRED_EMBEDDINGS_SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if *shutdown_rx.borrow() {
+                    info!("shutting down expired embeddings sweeper");
+                    break;
+                }
+                match data_manager.remove_expired_embeddings().await {
+                    Ok(0) => {}
+                    Ok(removed) => info!("removed {} expired embeddings", removed),
+                    Err(e) => tracing::error!("unable to remove expired embeddings: {}", e),
+                }
+            }
+        });
+    }
+
+    //  Metadata is purged independently of the content it was extracted from,
+    // so namespaces can keep content longer than fields extracted from it
+    fn start_metadata_retention_sweeper(
+        &self,
+        data_manager: Arc<DataManager>,
+        shutdown_rx: watch::Receiver<bool>,
+    ) {
+        let retention_secs = self.config.metadata_storage.retention_secs.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(METADATA_RETENTION_SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if *shutdown_rx.borrow() {
+                    info!("shutting down metadata retention sweeper");
+                    break;
+                }
+                match data_manager.purge_expired_metadata(&retention_secs).await {
+                    Ok(0) => {}
+                    Ok(purged) => info!("purged {} expired metadata rows", purged),
+                    Err(e) => tracing::error!("unable to purge expired metadata: {}", e),
+                }
+            }
+        });
+    }
+
+    //  Blobs are reconciled one namespace at a time, a namespace failing to
+    // list doesn't keep the others from being reconciled
+    fn start_orphaned_blob_reconciliation(
+        &self,
+        data_manager: Arc<DataManager>,
+        config: OrphanedBlobsConfig,
+        shutdown_rx: watch::Receiver<bool>,
+    ) {
+        let min_age = Duration::from_secs(self.config.ingest_intent_timeout_secs);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+            loop {
+                interval.tick().await;
+                if *shutdown_rx.borrow() {
+                    info!("shutting down orphaned blob reconciliation");
+                    break;
+                }
+                let namespaces = match data_manager.list_namespaces().await {
+                    Ok(namespaces) => namespaces,
+                    Err(e) => {
+                        tracing::error!("unable to list namespaces to reconcile blobs: {}", e);
+                        continue;
+                    }
+                };
+                for namespace in namespaces {
+                    match data_manager
+                        .reconcile_orphaned_blobs(&namespace.name, min_age, config.delete)
+                        .await
+                    {
+                        Ok(orphans) if orphans.is_empty() => {}
+                        Ok(orphans) => info!(
+                            "found {} orphaned blobs of {} bytes in namespace {}",
+                            orphans.len(),
+                            orphans.iter().map(|blob| blob.size_bytes).sum::<u64>(),
+                            namespace.name
+                        ),
+                        Err(e) => tracing::error!(
+                            "unable to reconcile blobs of namespace {}: {}",
+                            namespace.name,
+                            e
+                        ),
+                    }
+                }
+            }
+        });
+    }
+
+    fn start_coordinator_health_checks(
+        &self,
+        coordinator_client: Arc<CoordinatorClient>,
+        shutdown_rx: watch::Receiver<bool>,
+    ) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                if *shutdown_rx.borrow() {
+                    info!("shutting down coordinator health checks");
+                    break;
+                }
+                coordinator_client.check_health().await;
+            }
+        });
+    }
+
     fn start_gc_tasks_stream(
         &self,
         coordinator_client: Arc<CoordinatorClient>,
@@ -444,7 +956,7 @@ async fn create_namespace(
     };
     state
         .data_manager
-        .create_namespace(&data_namespace)
+        .create_namespace(&data_namespace, payload.operation_token.clone())
         .await
         .map_err(|e| {
             IndexifyAPIError::new(
@@ -480,6 +992,71 @@ async fn list_namespaces(
     }))
 }
 
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespace_templates",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Built-in namespace templates", body = ListNamespaceTemplatesResponse),
+    ),
+)]
+async fn list_namespace_templates() -> Json<ListNamespaceTemplatesResponse> {
+    Json(ListNamespaceTemplatesResponse {
+        templates: namespace_templates::builtin_templates(),
+    })
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespace_templates/{name}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Namespace template with the given name", body = NamespaceTemplate),
+        (status = NOT_FOUND, description = "Namespace template not found")
+    ),
+)]
+async fn get_namespace_template(
+    Path(name): Path<String>,
+) -> Result<Json<NamespaceTemplate>, IndexifyAPIError> {
+    let template = namespace_templates::get_template(&name)
+        .map_err(|e| IndexifyAPIError::new(StatusCode::NOT_FOUND, &e.to_string()))?;
+    Ok(Json(template))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespace_templates/{name}/instantiate",
+    request_body = InstantiateNamespaceTemplate,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Namespace created from the template", body = InstantiateNamespaceTemplateResponse),
+        (status = NOT_FOUND, description = "Namespace template not found"),
+        (status = BAD_REQUEST, description = "Invalid template parameters"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to create the namespace")
+    ),
+)]
+#[axum::debug_handler]
+async fn instantiate_namespace_template(
+    Path(name): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<InstantiateNamespaceTemplate>,
+) -> Result<Json<InstantiateNamespaceTemplateResponse>, IndexifyAPIError> {
+    let template = namespace_templates::get_template(&name)
+        .map_err(|e| IndexifyAPIError::new(StatusCode::NOT_FOUND, &e.to_string()))?;
+    //  Check the parameters before anything is created
+    namespace_templates::render_policies(&template, &payload.parameters)
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    let response = state
+        .data_manager
+        .instantiate_namespace_template(&template, &payload)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(response))
+}
+
 #[tracing::instrument]
 #[utoipa::path(
     get,
@@ -506,33 +1083,980 @@ async fn get_namespace(
     }))
 }
 
+#[tracing::instrument]
 #[utoipa::path(
-    post,
-    path = "/namespace/{namespace}/extraction_policies",
-    request_body = ExtractionPolicyRequest,
+    delete,
+    path = "/namespaces/{namespace}",
     tag = "indexify",
     responses(
-        (status = 200, description = "Extractor policy added successfully", body = ExtractionPolicyResponse),
-        (status = INTERNAL_SERVER_ERROR, description = "Unable to add extraction policy to namespace")
+        (status = 200, description = "Namespace deleted, its content is garbage collected in the background"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to delete namespace")
     ),
 )]
-#[axum::debug_handler]
-async fn create_extraction_policy(
-    // FIXME: this throws a 500 when the binding already exists
-    // FIXME: also throws a 500 when the index name already exists
+async fn delete_namespace(
     Path(namespace): Path<String>,
     State(state): State<NamespaceEndpointState>,
-    Json(payload): Json<ExtractionPolicyRequest>,
-) -> Result<Json<ExtractionPolicyResponse>, IndexifyAPIError> {
-    let index_names = state
+) -> Result<Json<()>, IndexifyAPIError> {
+    state
         .data_manager
-        .create_extraction_policy(&namespace, &payload)
+        .delete_namespace(&namespace)
         .await
-        .map_err(IndexifyAPIError::internal_error)?
-        .into_iter()
-        .collect();
-    Ok(Json(ExtractionPolicyResponse { index_names }))
-}
+        .map_err(|e| {
+            IndexifyAPIError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("failed to delete namespace: {}", e),
+            )
+        })?;
+    Ok(Json(()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/storage",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Bytes of content stored in the namespace and its quota", body = NamespaceStorage),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to get the storage of the namespace")
+    ),
+)]
+async fn get_namespace_storage(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<NamespaceStorage>, IndexifyAPIError> {
+    let quotas = state
+        .data_manager
+        .get_namespace_quotas(&namespace)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(NamespaceStorage {
+        namespace,
+        stored_bytes: quotas.stored_bytes,
+        quota_bytes: quotas.quotas.max_stored_bytes,
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/quotas",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Quotas of the namespace and how much of them is used", body = NamespaceQuotasResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to get the quotas of the namespace")
+    ),
+)]
+async fn get_namespace_quotas(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<NamespaceQuotasResponse>, IndexifyAPIError> {
+    let quotas = state
+        .data_manager
+        .get_namespace_quotas(&namespace)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(quotas))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/stats",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Content, tasks and indexes of the namespace", body = NamespaceStats),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to get the stats of the namespace")
+    ),
+)]
+async fn get_namespace_stats(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<NamespaceStats>, IndexifyAPIError> {
+    let stats = state
+        .data_manager
+        .get_namespace_stats(&namespace)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(stats))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    put,
+    path = "/namespaces/{namespace}/quotas",
+    request_body = NamespaceQuotas,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Quotas of the namespace set"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to set the quotas of the namespace")
+    ),
+)]
+#[axum::debug_handler]
+async fn set_namespace_quotas(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<NamespaceQuotas>,
+) -> Result<Json<()>, IndexifyAPIError> {
+    state
+        .data_manager
+        .set_namespace_quotas(&namespace, payload)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/blobs/migrate",
+    request_body = MigrateBlobs,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Migration of the blobs of the namespace started", body = BlobMigrationJobResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to migrate blobs")
+    ),
+)]
+#[axum::debug_handler]
+async fn migrate_blobs(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<MigrateBlobs>,
+) -> Result<Json<BlobMigrationJobResponse>, IndexifyAPIError> {
+    let job = state
+        .data_manager
+        .migrate_blobs(&namespace, payload.target.map(Into::into))
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(job.into()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/blobs/migrate",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Progress of migrating the blobs of the namespace", body = BlobMigrationJobResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to get blob migration job")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_blob_migration_job(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<BlobMigrationJobResponse>, IndexifyAPIError> {
+    let job = state
+        .data_manager
+        .get_blob_migration_job(&namespace)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(job.into()))
+}
+
+//  Uploads to a namespace over one of the quotas enforced by the coordinators,
+// and uploads reusing the request id of another upload, are told apart from
+// malformed uploads
+fn ingestion_error(context: &str, e: anyhow::Error) -> IndexifyAPIError {
+    let status_code = if e.downcast_ref::<NamespaceQuotaExceeded>().is_some() {
+        StatusCode::TOO_MANY_REQUESTS
+    } else if e.downcast_ref::<RequestIdConflict>().is_some() {
+        StatusCode::CONFLICT
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+    IndexifyAPIError::new(status_code, &format!("{}: {}", context, e))
+}
+
+#[utoipa::path(
+    post,
+    path = "/namespace/{namespace}/extraction_policies",
+    request_body = ExtractionPolicyRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Extractor policy added successfully", body = ExtractionPolicyResponse),
+        (status = TOO_MANY_REQUESTS, description = "The namespace has as many extraction policies as its quota allows"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to add extraction policy to namespace")
+    ),
+)]
+#[axum::debug_handler]
+async fn create_extraction_policy(
+    // FIXME: this throws a 500 when the binding already exists
+    // FIXME: also throws a 500 when the index name already exists
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<ExtractionPolicyRequest>,
+) -> Result<Json<ExtractionPolicyResponse>, IndexifyAPIError> {
+    if let Some(quantization) = &payload.quantization {
+        internal_api::IndexQuantization::from(quantization.clone())
+            .validate()
+            .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    }
+    let index_names = state
+        .data_manager
+        .create_extraction_policy(&namespace, &payload)
+        .await
+        .map_err(|e| {
+            if e.downcast_ref::<NamespaceQuotaExceeded>().is_some() {
+                IndexifyAPIError::new(StatusCode::TOO_MANY_REQUESTS, &e.to_string())
+            } else {
+                IndexifyAPIError::internal_error(e)
+            }
+        })?
+        .into_iter()
+        .collect();
+    Ok(Json(ExtractionPolicyResponse { index_names }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    patch,
+    path = "/namespaces/{namespace}/extraction_policies/{extraction_policy}",
+    request_body = UpdateExtractionPolicyRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Extraction policy updated", body = UpdateExtractionPolicyResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to update extraction policy")
+    ),
+)]
+#[axum::debug_handler]
+async fn update_extraction_policy(
+    Path((namespace, extraction_policy)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<UpdateExtractionPolicyRequest>,
+) -> Result<Json<UpdateExtractionPolicyResponse>, IndexifyAPIError> {
+    let extraction_policy = state
+        .data_manager
+        .update_extraction_policy(&namespace, &extraction_policy, &payload)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(UpdateExtractionPolicyResponse { extraction_policy }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    delete,
+    path = "/namespaces/{namespace}/extraction_policies/{extraction_policy}",
+    params(DeleteExtractionPolicyParams),
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Extraction policy deleted", body = DeleteExtractionPolicyResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to delete extraction policy")
+    ),
+)]
+#[axum::debug_handler]
+async fn delete_extraction_policy(
+    Path((namespace, extraction_policy)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Query(params): Query<DeleteExtractionPolicyParams>,
+) -> Result<Json<DeleteExtractionPolicyResponse>, IndexifyAPIError> {
+    let response = state
+        .data_manager
+        .delete_extraction_policy(&namespace, &extraction_policy, params.indexes)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(response))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/extraction_policies/dry_run",
+    request_body = ExtractionPolicyRequest,
+    params(DryRunExtractionPolicy),
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Existing content the extraction policy would be applied to", body = ExtractionPolicyDryRunResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to dry run extraction policy")
+    ),
+)]
+#[axum::debug_handler]
+async fn dry_run_extraction_policy(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Query(query): Query<DryRunExtractionPolicy>,
+    Json(payload): Json<ExtractionPolicyRequest>,
+) -> Result<Json<ExtractionPolicyDryRunResponse>, IndexifyAPIError> {
+    let response = state
+        .data_manager
+        .dry_run_extraction_policy(&namespace, &payload, query.sample_size.unwrap_or(10))
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(response))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/extraction_policies/{extraction_policy}/plan",
+    params(PlanExtractionPolicy),
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Estimated schedule for applying the extraction policy to existing content", body = ExtractionPolicyPlanResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to plan extraction policy")
+    ),
+)]
+#[axum::debug_handler]
+async fn plan_extraction_policy(
+    Path((namespace, extraction_policy)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Query(query): Query<PlanExtractionPolicy>,
+) -> Result<Json<ExtractionPolicyPlanResponse>, IndexifyAPIError> {
+    let plan = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .plan_extraction_policy(indexify_coordinator::PlanExtractionPolicyRequest {
+            namespace,
+            extraction_policy: extraction_policy.clone(),
+            task_latency_ms: query.task_latency_ms.unwrap_or_default(),
+            executor_concurrency: query.executor_concurrency.unwrap_or(1),
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()))?
+        .into_inner();
+    let estimated_completion_ms = if plan.executors == 0 {
+        None
+    } else {
+        Some(plan.estimated_completion_ms)
+    };
+    Ok(Json(ExtractionPolicyPlanResponse {
+        extraction_policy,
+        extractor: plan.extractor,
+        total_tasks: plan.total_tasks,
+        total_bytes: plan.total_bytes,
+        queued_tasks: plan.queued_tasks,
+        executors: plan.executors,
+        task_latency_ms: plan.task_latency_ms,
+        estimated_completion_ms,
+        compute_time_ms: plan.compute_time_ms,
+        tasks_per_executor: plan.tasks_per_executor,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/indexes/{index}/migration",
+    request_body = CreateIndexMigration,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Index migration started", body = IndexMigrationResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to start index migration")
+    ),
+)]
+#[axum::debug_handler]
+async fn create_index_migration(
+    Path((namespace, index)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<CreateIndexMigration>,
+) -> Result<Json<IndexMigrationResponse>, IndexifyAPIError> {
+    let migration = state
+        .data_manager
+        .create_index_migration(&namespace, &index, &payload)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(migration.into()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/indexes/{index}/migration",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Progress of the index migration", body = IndexMigrationResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to get index migration")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_index_migration(
+    Path((namespace, index)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<IndexMigrationResponse>, IndexifyAPIError> {
+    let migration = state
+        .data_manager
+        .get_index_migration(&namespace, &index)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(migration.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/indexes/{index}/migration/complete",
+    request_body = CompleteIndexMigration,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Index now resolves to the migrated index", body = IndexMigrationResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to complete index migration")
+    ),
+)]
+#[axum::debug_handler]
+async fn complete_index_migration(
+    Path((namespace, index)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<CompleteIndexMigration>,
+) -> Result<Json<IndexMigrationResponse>, IndexifyAPIError> {
+    let migration = state
+        .data_manager
+        .complete_index_migration(&namespace, &index, payload.force)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(migration.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/indexes/{index}/rebalance",
+    request_body = RebalanceIndex,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Rebalancing of the index started", body = RebalanceJobResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to rebalance index")
+    ),
+)]
+#[axum::debug_handler]
+async fn rebalance_index(
+    Path((namespace, index)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<RebalanceIndex>,
+) -> Result<Json<RebalanceJobResponse>, IndexifyAPIError> {
+    let job = state
+        .data_manager
+        .rebalance_index(&namespace, &index, &payload)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(job.into()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/indexes/{index}/stats",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Statistics of the index", body = IndexStats),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to get index statistics")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_index_stats(
+    Path((namespace, index)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<IndexStats>, IndexifyAPIError> {
+    let stats = state
+        .data_manager
+        .get_index_stats(&namespace, &index)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(stats))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/indexes/{index}/warm_up",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Index loaded into memory", body = IndexWarmUp),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to warm up index")
+    ),
+)]
+#[axum::debug_handler]
+async fn warm_up_index(
+    Path((namespace, index)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<IndexWarmUp>, IndexifyAPIError> {
+    let warm_up = state
+        .data_manager
+        .warm_up_index(&namespace, &index)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(warm_up))
+}
+
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/indexes/{index}/rebuild",
+    request_body = RebuildIndex,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Rebuilding of the index started", body = RebalanceJobResponse),
+        (status = BAD_REQUEST, description = "Invalid rebuild request"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to rebuild index")
+    ),
+)]
+#[axum::debug_handler]
+async fn rebuild_index(
+    Path((namespace, index)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<RebuildIndex>,
+) -> Result<Json<RebalanceJobResponse>, IndexifyAPIError> {
+    if payload.shard_count == Some(0) {
+        return Err(IndexifyAPIError::new(
+            StatusCode::BAD_REQUEST,
+            "shard_count has to be at least 1",
+        ));
+    }
+    let job = state
+        .data_manager
+        .rebuild_index(&namespace, &index, &payload)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(job.into()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/indexes/{index}/snapshot",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Snapshot of the index written to the blob store", body = IndexSnapshotResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to snapshot index")
+    ),
+)]
+#[axum::debug_handler]
+async fn snapshot_index(
+    Path((namespace, index)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<IndexSnapshotResponse>, IndexifyAPIError> {
+    let snapshot = state
+        .data_manager
+        .snapshot_index(&namespace, &index)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(snapshot))
+}
+
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/indexes/{index}/restore",
+    request_body = RestoreIndex,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Restoring of the index started", body = RebalanceJobResponse),
+        (status = BAD_REQUEST, description = "Invalid restore request"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to restore index")
+    ),
+)]
+#[axum::debug_handler]
+async fn restore_index(
+    Path((namespace, index)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<RestoreIndex>,
+) -> Result<Json<RebalanceJobResponse>, IndexifyAPIError> {
+    if payload.shard_count == Some(0) {
+        return Err(IndexifyAPIError::new(
+            StatusCode::BAD_REQUEST,
+            "shard_count has to be at least 1",
+        ));
+    }
+    let job = state
+        .data_manager
+        .restore_index(&namespace, &index, &payload)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(job.into()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/indexes/{index}/rebalance",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Progress of rebalancing the index", body = RebalanceJobResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to get rebalancing job")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_rebalance_job(
+    Path((namespace, index)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<RebalanceJobResponse>, IndexifyAPIError> {
+    let job = state
+        .data_manager
+        .get_rebalance_job(&namespace, &index)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(job.into()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/query_templates",
+    request_body = QueryTemplate,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Query template saved successfully", body = QueryTemplate),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to save query template")
+    ),
+)]
+#[axum::debug_handler]
+async fn create_query_template(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<QueryTemplate>,
+) -> Result<Json<QueryTemplate>, IndexifyAPIError> {
+    state
+        .data_manager
+        .set_query_template(&namespace, &payload)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(payload))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/query_templates",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "List of query templates in the namespace", body = ListQueryTemplatesResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list query templates")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_query_templates(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ListQueryTemplatesResponse>, IndexifyAPIError> {
+    let query_templates = state
+        .data_manager
+        .list_query_templates(&namespace)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .into_iter()
+        .map(|query_template| query_template.into())
+        .collect();
+    Ok(Json(ListQueryTemplatesResponse { query_templates }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/query_templates/{name}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Query template", body = QueryTemplate),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to get query template")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_query_template(
+    Path((namespace, name)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<QueryTemplate>, IndexifyAPIError> {
+    let query_template = state
+        .data_manager
+        .get_query_template(&namespace, &name)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(query_template.into()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    delete,
+    path = "/namespaces/{namespace}/query_templates/{name}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Query template deleted successfully"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to delete query template")
+    ),
+)]
+#[axum::debug_handler]
+async fn delete_query_template(
+    Path((namespace, name)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<(), IndexifyAPIError> {
+    state
+        .data_manager
+        .delete_query_template(&namespace, &name)
+        .await
+        .map_err(IndexifyAPIError::internal_error)
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/query_templates/{name}/run",
+    request_body = RunQueryTemplate,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Search results of the query template", body = IndexSearchResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to run query template")
+    ),
+)]
+#[axum::debug_handler]
+async fn run_query_template(
+    Path((namespace, name)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<RunQueryTemplate>,
+) -> Result<Json<IndexSearchResponse>, IndexifyAPIError> {
+    let results = state
+        .data_manager
+        .run_query_template(&namespace, &name, &payload.parameters, payload.k)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    let document_fragments: Vec<DocumentFragment> = results
+        .into_iter()
+        .map(|text| DocumentFragment {
+            content_id: text.content_id,
+            mime_type: text.mime_type,
+            text: text.text,
+            labels: text.labels,
+            confidence_score: text.confidence_score,
+            external_id: text.external_id,
+            duplicate_content_ids: text.duplicate_content_ids,
+        })
+        .collect();
+    Ok(Json(IndexSearchResponse {
+        results: document_fragments,
+    }))
+}
+
+#[tracing::instrument(skip(body))]
+#[utoipa::path(
+    put,
+    path = "/namespaces/{namespace}/query_templates/{name}/scoring_plugin",
+    request_body(content_type = "application/wasm", content = Vec<u8>),
+    params(WasmPluginParams),
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Scoring plugin saved successfully", body = ScoringPlugin),
+        (status = BAD_REQUEST, description = "Invalid WASM module"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to save scoring plugin")
+    ),
+)]
+#[axum::debug_handler]
+async fn set_scoring_plugin(
+    Path((namespace, name)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Query(params): Query<WasmPluginParams>,
+    body: Bytes,
+) -> Result<Json<ScoringPlugin>, IndexifyAPIError> {
+    state
+        .data_manager
+        .validate_plugin(&body)
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    let scoring_plugin = state
+        .data_manager
+        .set_scoring_plugin(
+            &namespace,
+            &name,
+            body,
+            params.max_fuel,
+            params.max_memory_bytes,
+        )
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(scoring_plugin.into()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    delete,
+    path = "/namespaces/{namespace}/query_templates/{name}/scoring_plugin",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Scoring plugin deleted successfully"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to delete scoring plugin")
+    ),
+)]
+#[axum::debug_handler]
+async fn delete_scoring_plugin(
+    Path((namespace, name)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<(), IndexifyAPIError> {
+    state
+        .data_manager
+        .delete_scoring_plugin(&namespace, &name)
+        .await
+        .map_err(IndexifyAPIError::internal_error)
+}
+
+#[tracing::instrument(skip(body))]
+#[utoipa::path(
+    put,
+    path = "/namespaces/{namespace}/ingestion_plugins/{name}",
+    request_body(content_type = "application/wasm", content = Vec<u8>),
+    params(WasmPluginParams),
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Ingestion plugin saved successfully", body = IngestionPlugin),
+        (status = BAD_REQUEST, description = "Invalid WASM module"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to save ingestion plugin")
+    ),
+)]
+#[axum::debug_handler]
+async fn set_ingestion_plugin(
+    Path((namespace, name)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Query(params): Query<WasmPluginParams>,
+    body: Bytes,
+) -> Result<Json<IngestionPlugin>, IndexifyAPIError> {
+    state
+        .data_manager
+        .validate_plugin(&body)
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    let plugin = state
+        .data_manager
+        .register_ingestion_plugin(
+            &namespace,
+            &name,
+            body,
+            params.max_fuel,
+            params.max_memory_bytes,
+        )
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(plugin.into()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/ingestion_plugins",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "List of ingestion plugins in the namespace", body = ListIngestionPluginsResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list ingestion plugins")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_ingestion_plugins(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ListIngestionPluginsResponse>, IndexifyAPIError> {
+    let plugins = state
+        .data_manager
+        .list_ingestion_plugins(&namespace)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .into_iter()
+        .map(|plugin| plugin.into())
+        .collect();
+    Ok(Json(ListIngestionPluginsResponse { plugins }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    delete,
+    path = "/namespaces/{namespace}/ingestion_plugins/{name}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Ingestion plugin deleted successfully"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to delete ingestion plugin")
+    ),
+)]
+#[axum::debug_handler]
+async fn delete_ingestion_plugin(
+    Path((namespace, name)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<(), IndexifyAPIError> {
+    state
+        .data_manager
+        .delete_ingestion_plugin(&namespace, &name)
+        .await
+        .map_err(IndexifyAPIError::internal_error)
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    put,
+    path = "/namespaces/{namespace}/extraction_policies/{extraction_policy}/sla",
+    request_body = ProcessingSlaRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Processing sla saved successfully", body = ProcessingSla),
+        (status = BAD_REQUEST, description = "Invalid processing sla"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to save processing sla")
+    ),
+)]
+#[axum::debug_handler]
+async fn set_processing_sla(
+    Path((namespace, extraction_policy)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<ProcessingSlaRequest>,
+) -> Result<Json<ProcessingSla>, IndexifyAPIError> {
+    let sla = internal_api::ProcessingSla {
+        namespace,
+        extraction_policy,
+        extraction_policy_id: "".to_string(),
+        target_secs: payload.target_secs,
+        attainment_threshold: payload
+            .attainment_threshold
+            .unwrap_or(processing_slas::DEFAULT_SLA_ATTAINMENT_THRESHOLD),
+        window_secs: payload
+            .window_secs
+            .unwrap_or(processing_slas::DEFAULT_SLA_WINDOW_SECS.max(payload.target_secs)),
+        webhook_url: payload.webhook_url.unwrap_or_default(),
+    };
+    sla.validate()
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    let sla = state
+        .data_manager
+        .set_processing_sla(sla)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(sla.into()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    delete,
+    path = "/namespaces/{namespace}/extraction_policies/{extraction_policy}/sla",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Processing sla deleted successfully"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to delete processing sla")
+    ),
+)]
+#[axum::debug_handler]
+async fn delete_processing_sla(
+    Path((namespace, extraction_policy)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<(), IndexifyAPIError> {
+    state
+        .data_manager
+        .delete_processing_sla(&namespace, &extraction_policy)
+        .await
+        .map_err(IndexifyAPIError::internal_error)
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/slas",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Processing slas of the namespace and their attainment", body = ListProcessingSlasResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list processing slas")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_processing_slas(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ListProcessingSlasResponse>, IndexifyAPIError> {
+    let slas = state
+        .data_manager
+        .processing_sla_attainment(&namespace)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .into_iter()
+        .map(|(sla, attainment)| ProcessingSlaStatus {
+            sla: sla.into(),
+            processed: attainment.samples,
+            within_target: attainment.met,
+            attainment: attainment.attainment,
+            breached: attainment.breached,
+        })
+        .collect();
+    Ok(Json(ListProcessingSlasResponse { slas }))
+}
 
 #[tracing::instrument(skip(state, payload))]
 #[utoipa::path(
@@ -542,7 +2066,8 @@ async fn create_extraction_policy(
     tag = "indexify",
     responses(
         (status = 200, description = "Texts were successfully added to the namespace", body = TextAdditionResponse),
-        (status = BAD_REQUEST, description = "Unable to add texts")
+        (status = BAD_REQUEST, description = "Unable to add texts"),
+        (status = CONFLICT, description = "The request id was used for different texts")
     ),
 )]
 #[axum::debug_handler]
@@ -551,26 +2076,11 @@ async fn add_texts(
     State(state): State<NamespaceEndpointState>,
     Json(payload): Json<TextAddRequest>,
 ) -> Result<Json<TextAdditionResponse>, IndexifyAPIError> {
-    let content = payload
-        .documents
-        .iter()
-        .map(|d| api::Content {
-            content_type: mime::TEXT_PLAIN.to_string(),
-            bytes: d.text.as_bytes().to_vec(),
-            labels: d.labels.clone(),
-            features: vec![],
-        })
-        .collect();
     state
         .data_manager
-        .add_texts(&namespace, content)
+        .add_texts(&namespace, payload.documents, payload.request_id.as_deref())
         .await
-        .map_err(|e| {
-            IndexifyAPIError::new(
-                StatusCode::BAD_REQUEST,
-                &format!("failed to add text: {}", e),
-            )
-        })?;
+        .map_err(|e| ingestion_error("failed to add text", e))?;
     Ok(Json(TextAdditionResponse::default()))
 }
 
@@ -582,14 +2092,16 @@ async fn ingest_remote_file(
 ) -> Result<Json<IngestRemoteFileResponse>, IndexifyAPIError> {
     let content_id = state
         .data_manager
-        .ingest_remote_file(&namespace, &payload.url, &payload.mime_type, payload.labels)
+        .ingest_remote_file(
+            &namespace,
+            &payload.url,
+            &payload.mime_type,
+            payload.labels,
+            payload.external_id,
+            payload.request_id.as_deref(),
+        )
         .await
-        .map_err(|e| {
-            IndexifyAPIError::new(
-                StatusCode::BAD_REQUEST,
-                &format!("failed to add text: {}", e),
-            )
-        })?;
+        .map_err(|e| ingestion_error("failed to add text", e))?;
     Ok(Json(IngestRemoteFileResponse { content_id }))
 }
 
@@ -609,16 +2121,28 @@ async fn list_content(
     State(state): State<NamespaceEndpointState>,
     filter: Query<super::api::ListContentFilters>,
 ) -> Result<Json<ListContentResponse>, IndexifyAPIError> {
-    let content_list = state
+    if !filter.filter.is_empty() {
+        FilterExpr::parse(&filter.filter)
+            .and_then(|filter| filter.for_content_store())
+            .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    }
+    let mut content_list = state
         .data_manager
         .list_content(
             &namespace,
             &filter.source,
             &filter.parent_id,
+            &filter.external_id,
             filter.labels_eq.as_ref(),
+            &filter.filter,
         )
         .await
         .map_err(IndexifyAPIError::internal_error)?;
+    if !filter.include_preview {
+        for content in content_list.iter_mut() {
+            content.preview = None;
+        }
+    }
     Ok(Json(ListContentResponse { content_list }))
 }
 
@@ -670,6 +2194,7 @@ async fn delete_content(
     get,
     path = "/namespaces/{namespace}/content/{content_id}",
     tag = "indexify",
+    params(ReadConsistencyParams),
     responses(
         (status = 200, description = "Reads a specific content in the namespace", body = GetRawContentResponse),
         (status = BAD_REQUEST, description = "Unable to read content")
@@ -678,11 +2203,12 @@ async fn delete_content(
 #[axum::debug_handler]
 async fn get_content_metadata(
     Path((namespace, content_id)): Path<(String, String)>,
+    Query(params): Query<ReadConsistencyParams>,
     State(state): State<NamespaceEndpointState>,
 ) -> Result<Json<GetContentMetadataResponse>, IndexifyAPIError> {
     let content_list = state
         .data_manager
-        .get_content_metadata(&namespace, vec![content_id])
+        .get_content_metadata_with_consistency(&namespace, vec![content_id], params.consistency)
         .await
         .map_err(IndexifyAPIError::internal_error)?;
     let content_metadata = content_list
@@ -694,6 +2220,67 @@ async fn get_content_metadata(
     }))
 }
 
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/external_content/{external_id}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Reads the content with the given external id", body = GetContentMetadataResponse),
+        (status = NOT_FOUND, description = "Content not found")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_content_metadata_by_external_id(
+    Path((namespace, external_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<GetContentMetadataResponse>, IndexifyAPIError> {
+    let content_metadata = state
+        .data_manager
+        .get_content_metadata_by_external_id(&namespace, &external_id)
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::NOT_FOUND, &e.to_string()))?;
+    Ok(Json(GetContentMetadataResponse { content_metadata }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/external_content/{external_id}/metadata",
+    tag = "indexify",
+    params(MetadataProvenance, MetadataPage),
+    responses(
+        (status = 200, description = "Extracted metadata of the content with the given external id", body = MetadataResponse),
+        (status = NOT_FOUND, description = "Content not found")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_extracted_metadata_by_external_id(
+    Path((namespace, external_id)): Path<(String, String)>,
+    Query(provenance): Query<MetadataProvenance>,
+    Query(page): Query<MetadataPage>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<MetadataResponse>, IndexifyAPIError> {
+    let content_metadata = state
+        .data_manager
+        .get_content_metadata_by_external_id(&namespace, &external_id)
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::NOT_FOUND, &e.to_string()))?;
+    let extracted_metadata = state
+        .data_manager
+        .metadata_lookup_by_provenance(&namespace, &content_metadata.id, &provenance, &page)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(MetadataResponse {
+        total: extracted_metadata.total,
+        metadata: extracted_metadata
+            .metadata
+            .into_iter()
+            .map(|r| r.into())
+            .collect(),
+    }))
+}
+
 #[tracing::instrument]
 #[utoipa::path(
     get,
@@ -719,10 +2306,55 @@ async fn get_content_tree_metadata(
     }))
 }
 
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/content/{content_id}/presigned_url",
+    params(PresignedUrlParams),
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Signs a url the content can be downloaded from directly until it expires", body = PresignedUrlResponse),
+        (status = BAD_REQUEST, description = "The content is stored in a blob storage which can't pre-sign urls")
+    ),
+)]
+#[axum::debug_handler]
+async fn presigned_content_url(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Query(params): Query<PresignedUrlParams>,
+) -> Result<Json<PresignedUrlResponse>, IndexifyAPIError> {
+    let content_list = state
+        .data_manager
+        .get_content_metadata(&namespace, vec![content_id])
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    let content_metadata = content_list
+        .first()
+        .ok_or_else(|| IndexifyAPIError::not_found("content not found"))?;
+    let expires_in = params
+        .expires_in_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_PRESIGNED_URL_EXPIRY);
+    let url = state
+        .content_reader
+        .presigned_url(&content_metadata.storage_url, expires_in)
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    let expires_at = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_err(|e| IndexifyAPIError::internal_error(e.into()))? +
+        expires_in;
+    Ok(Json(PresignedUrlResponse {
+        url,
+        expires_at: expires_at.as_secs(),
+    }))
+}
+
 #[axum::debug_handler]
 async fn download_content(
     Path((namespace, content_id)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, IndexifyAPIError> {
     let content_list = state
         .data_manager
@@ -734,21 +2366,60 @@ async fn download_content(
         .ok_or(anyhow!("content not found"))
         .map_err(|e| IndexifyAPIError::not_found(&e.to_string()))?
         .clone();
-    let mut resp_builder =
-        Response::builder().header("Content-Type", content_metadata.mime_type.clone());
-    if content_metadata.size > 0 {
-        resp_builder = resp_builder.header("Content-Length", content_metadata.size);
-    }
-    resp_builder
-        .body(Body::from_stream(async_stream::stream! {
-            let storage_url = &content_metadata.storage_url.clone();
-            let content_reader = state.content_reader.clone();
-            let reader = content_reader.get(storage_url);
-            let mut content_stream = reader.get(storage_url);
-            while let Some(buf)  = content_stream.next().await {
-                yield buf;
+    let size = content_metadata.size;
+    let range = match headers.get(RANGE).and_then(|range| range.to_str().ok()) {
+        Some(range) if size > 0 => match api_utils::parse_byte_range(range, size) {
+            Ok(range) => range,
+            Err(e) => {
+                return Response::builder()
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(CONTENT_RANGE, format!("bytes */{}", size))
+                    .body(Body::from(e.to_string()))
+                    .map_err(|e| {
+                        IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())
+                    });
             }
-        }))
+        },
+        _ => None,
+    };
+    let mut resp_builder = Response::builder()
+        .header(CONTENT_TYPE, content_metadata.mime_type.clone())
+        .header(ACCEPT_RANGES, "bytes");
+    //  Content of a known size is read as the client consumes it, rather than
+    // read ahead of the client into memory
+    let content_stream: BoxStream<'static, Result<Bytes>> = match (&range, size) {
+        (Some(range), _) => {
+            resp_builder = resp_builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(
+                    CONTENT_RANGE,
+                    format!("bytes {}-{}/{}", range.start, range.end - 1, size),
+                )
+                .header(CONTENT_LENGTH, range.end - range.start);
+            state
+                .content_reader
+                .get_range(&content_metadata.storage_url, range.clone())
+        }
+        //  Full downloads are verified against the checksum of the content, a
+        // corrupted blob aborts the response after its last chunk
+        (None, 0) => blob_storage::verify_checksum(
+            &content_metadata.storage_url,
+            &content_metadata.hash,
+            state.content_reader.get_all(&content_metadata.storage_url),
+        ),
+        (None, size) => {
+            resp_builder = resp_builder.header(CONTENT_LENGTH, size);
+            blob_storage::verify_checksum(
+                &content_metadata.storage_url,
+                &content_metadata.hash,
+                state
+                    .content_reader
+                    .get_range(&content_metadata.storage_url, 0..size),
+            )
+        }
+    };
+    resp_builder
+        .body(Body::from_stream(content_stream))
         .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))
 }
 
@@ -757,16 +2428,19 @@ async fn download_content(
     post,
     path = "/namespaces/{namespace}/upload_file",
     request_body(content_type = "multipart/form-data", content = Vec<u8>),
+    params(UploadFileParams),
     tag = "indexify",
     responses(
         (status = 200, description = "Uploads a file to the namespace"),
-        (status = BAD_REQUEST, description = "Unable to upload file")
+        (status = BAD_REQUEST, description = "Unable to upload file"),
+        (status = CONFLICT, description = "The request id was used for a different file")
     ),
 )]
 #[axum::debug_handler]
 async fn upload_file(
     Path(namespace): Path<String>,
     State(state): State<NamespaceEndpointState>,
+    params: Query<UploadFileParams>,
     mut files: Multipart,
 ) -> Result<(), IndexifyAPIError> {
     while let Some(file) = files.next_field().await.unwrap() {
@@ -781,14 +2455,16 @@ async fn upload_file(
         let stream = file.map(|res| res.map_err(|err| anyhow::anyhow!(err)));
         state
             .data_manager
-            .upload_file(&namespace, stream, &name)
+            .upload_file(
+                &namespace,
+                stream,
+                &name,
+                params.external_id.clone(),
+                params.ttl_secs,
+                params.request_id.as_deref(),
+            )
             .await
-            .map_err(|e| {
-                IndexifyAPIError::new(
-                    StatusCode::BAD_REQUEST,
-                    &format!("failed to upload file: {}", e),
-                )
-            })?;
+            .map_err(|e| ingestion_error("failed to upload file", e))?;
     }
     Ok(())
 }
@@ -805,6 +2481,7 @@ async fn ingest_extracted_content(
     get,
     path = "/executors",
     tag = "indexify",
+    params(ListExecutorsFilters),
     responses(
         (status = 200, description = "List of currently running executors", body = ListExecutorsResponse),
         (status = INTERNAL_SERVER_ERROR, description = "Unable to load executors")
@@ -812,9 +2489,38 @@ async fn ingest_extracted_content(
 )]
 #[axum::debug_handler]
 async fn list_executors(
-    State(_state): State<NamespaceEndpointState>,
+    State(state): State<NamespaceEndpointState>,
+    filter: Query<ListExecutorsFilters>,
 ) -> Result<Json<ListExecutorsResponse>, IndexifyAPIError> {
-    Ok(Json(ListExecutorsResponse { executors: vec![] }))
+    let executors = state
+        .data_manager
+        .list_executors(filter.labels_eq.as_ref())
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(ListExecutorsResponse { executors }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/executors/{executor_id}/drain",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "No new tasks are assigned to the executor"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to drain the executor")
+    ),
+)]
+#[axum::debug_handler]
+async fn drain_executor(
+    Path(executor_id): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<()>, IndexifyAPIError> {
+    state
+        .data_manager
+        .drain_executor(&executor_id)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(()))
 }
 
 #[tracing::instrument]
@@ -876,6 +2582,55 @@ async fn list_state_changes(
     Ok(Json(ListStateChangesResponse { state_changes }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/state_changes/watch",
+    params(WatchStateChanges),
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Server sent events with a `state_change` event per state change applied from now on", body = StateChangeEvent),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to watch state changes")
+    ),
+)]
+#[axum::debug_handler]
+async fn watch_state_changes(
+    State(state): State<NamespaceEndpointState>,
+    Query(query): Query<WatchStateChanges>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, IndexifyAPIError> {
+    let change_types = query
+        .change_types
+        .map(|change_types| {
+            change_types
+                .split(',')
+                .map(|change_type| change_type.trim().to_string())
+                .filter(|change_type| !change_type.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut state_changes = state
+        .data_manager
+        .watch_state_changes(query.namespace, change_types)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    let stream = async_stream::stream! {
+        loop {
+            match state_changes.message().await {
+                Ok(Some(event)) => {
+                    yield Event::default()
+                        .event("state_change")
+                        .json_data(StateChangeEvent::from(event));
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    yield Ok(Event::default().event("error").data(e.message()));
+                    break;
+                }
+            }
+        }
+    };
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
 #[tracing::instrument]
 #[utoipa::path(
     get,
@@ -928,8 +2683,11 @@ async fn extract_content(
     State(namespace_endpoint): State<NamespaceEndpointState>,
     Json(request): Json<ExtractRequest>,
 ) -> Result<Json<ExtractResponse>, IndexifyAPIError> {
-    let extractor_router = ExtractorRouter::new(namespace_endpoint.coordinator_client.clone())
-        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))?;
+    let extractor_router = ExtractorRouter::new(
+        namespace_endpoint.coordinator_client.clone(),
+        namespace_endpoint.remote_extractors.clone(),
+    )
+    .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))?;
     let content_list = extractor_router
         .extract_content(&request.name, request.content, request.input_params)
         .await
@@ -964,67 +2722,364 @@ async fn list_task_assignments(
 
 #[tracing::instrument]
 #[utoipa::path(
-    get,
-    path = "/namespaces/{namespace}/indexes",
+    get,
+    path = "/namespaces/{namespace}/indexes",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "List of indexes in a namespace", body = ListIndexesResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list indexes in namespace")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_indexes(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ListIndexesResponse>, IndexifyAPIError> {
+    let indexes = state
+        .data_manager
+        .list_indexes(&namespace)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .into_iter()
+        .collect();
+    Ok(Json(ListIndexesResponse { indexes }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/namespace/{namespace}/search",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Index search results", body = IndexSearchResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to search index")
+    ),
+)]
+#[axum::debug_handler]
+async fn index_search(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Json(query): Json<SearchRequest>,
+) -> Result<Json<IndexSearchResponse>, IndexifyAPIError> {
+    let k = query.k.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    //  Results are filtered by location, filter expression and parent content
+    // after the search, so more candidates are fetched to still fill the top k
+    let mut search_k = k;
+    let filter = query
+        .filter
+        .as_deref()
+        .map(FilterExpr::parse)
+        .transpose()
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    if filter.is_some() {
+        search_k *= FILTER_EXPRESSION_OVERSAMPLING;
+    }
+    if let Some(geo_filter) = &query.geo_filter {
+        geo_filters::validate(geo_filter)
+            .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+        search_k *= GEO_FILTER_OVERSAMPLING;
+    }
+    if let Some(max_per_parent) = query.max_per_parent {
+        if max_per_parent == 0 {
+            return Err(IndexifyAPIError::new(
+                StatusCode::BAD_REQUEST,
+                "max_per_parent has to be greater than 0",
+            ));
+        }
+        search_k *= MAX_PER_PARENT_OVERSAMPLING;
+    }
+    if let Some(dedupe) = &query.dedupe {
+        if let Some(threshold) = dedupe.similarity_threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(IndexifyAPIError::new(
+                    StatusCode::BAD_REQUEST,
+                    "similarity_threshold has to be between 0 and 1",
+                ));
+            }
+        }
+        search_k *= DEDUPE_OVERSAMPLING;
+    }
+    if query.read_your_writes {
+        state
+            .data_manager
+            .refresh_index(&namespace, &query.index)
+            .await
+            .map_err(IndexifyAPIError::internal_error)?;
+    }
+    let mut results = match &query.recency_boost {
+        Some(recency_boost) => {
+            state
+                .data_manager
+                .search_with_recency_boost(
+                    &namespace,
+                    &query.index,
+                    &query.query,
+                    search_k,
+                    query.vector.as_deref(),
+                    recency_boost,
+                )
+                .await
+        }
+        None => {
+            state
+                .data_manager
+                .search(
+                    &namespace,
+                    &query.index,
+                    &query.query,
+                    search_k,
+                    query.vector.as_deref(),
+                )
+                .await
+        }
+    }
+    .map_err(IndexifyAPIError::internal_error)?;
+    if let Some(geo_filter) = &query.geo_filter {
+        results = state
+            .data_manager
+            .filter_by_location(&namespace, results, geo_filter)
+            .await
+            .map_err(IndexifyAPIError::internal_error)?;
+    }
+    if let Some(filter) = &filter {
+        results = state
+            .data_manager
+            .filter_by_expression(&namespace, results, filter)
+            .await
+            .map_err(IndexifyAPIError::internal_error)?;
+    }
+    if let Some(dedupe) = &query.dedupe {
+        results = state
+            .data_manager
+            .dedupe_results(
+                &namespace,
+                &query.index,
+                results,
+                dedupe.similarity_threshold,
+                query.vector.as_deref(),
+            )
+            .await
+            .map_err(IndexifyAPIError::internal_error)?;
+    }
+    if let Some(max_per_parent) = query.max_per_parent {
+        results = limit_results_per_parent(results, max_per_parent as usize);
+    }
+    results.truncate(k as usize);
+    let document_fragments: Vec<DocumentFragment> = results
+        .iter()
+        .filter(|text| {
+            query.external_ids.is_empty() || query.external_ids.contains(&text.external_id)
+        })
+        .map(|text| DocumentFragment {
+            content_id: text.content_id.clone(),
+            mime_type: text.mime_type.clone(),
+            text: text.text.clone(),
+            labels: text.labels.clone(),
+            confidence_score: text.confidence_score,
+            external_id: text.external_id.clone(),
+            duplicate_content_ids: text.duplicate_content_ids.clone(),
+        })
+        .collect();
+    Ok(Json(IndexSearchResponse {
+        results: document_fragments,
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/batch_search",
+    request_body = BatchSearchRequest,
     tag = "indexify",
     responses(
-        (status = 200, description = "List of indexes in a namespace", body = ListIndexesResponse),
-        (status = INTERNAL_SERVER_ERROR, description = "Unable to list indexes in namespace")
+        (status = 200, description = "Search results of each query", body = BatchSearchResponse),
+        (status = BAD_REQUEST, description = "Too many queries"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to search index")
     ),
 )]
 #[axum::debug_handler]
-async fn list_indexes(
+async fn batch_search(
     Path(namespace): Path<String>,
     State(state): State<NamespaceEndpointState>,
-) -> Result<Json<ListIndexesResponse>, IndexifyAPIError> {
-    let indexes = state
+    Json(query): Json<BatchSearchRequest>,
+) -> Result<Json<BatchSearchResponse>, IndexifyAPIError> {
+    if query.queries.len() > MAX_BATCH_SEARCH_QUERIES {
+        return Err(IndexifyAPIError::new(
+            StatusCode::BAD_REQUEST,
+            &format!(
+                "batch search accepts at most {} queries",
+                MAX_BATCH_SEARCH_QUERIES
+            ),
+        ));
+    }
+    let k = query.k.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let query_results = state
         .data_manager
-        .list_indexes(&namespace)
+        .batch_search(&namespace, &query.index, &query.queries, k)
         .await
-        .map_err(IndexifyAPIError::internal_error)?
+        .map_err(IndexifyAPIError::internal_error)?;
+    let results = query
+        .queries
         .into_iter()
+        .zip(query_results)
+        .map(|(query, query_result)| match query_result {
+            Ok(texts) => BatchSearchResult {
+                query,
+                results: texts
+                    .into_iter()
+                    .map(|text| DocumentFragment {
+                        content_id: text.content_id,
+                        mime_type: text.mime_type,
+                        text: text.text,
+                        labels: text.labels,
+                        confidence_score: text.confidence_score,
+                        external_id: text.external_id,
+                        duplicate_content_ids: text.duplicate_content_ids,
+                    })
+                    .collect(),
+                error: None,
+            },
+            Err(e) => BatchSearchResult {
+                query,
+                results: vec![],
+                error: Some(e.to_string()),
+            },
+        })
         .collect();
-    Ok(Json(ListIndexesResponse { indexes }))
+    Ok(Json(BatchSearchResponse { results }))
 }
 
+#[tracing::instrument]
 #[utoipa::path(
     post,
-    path = "/namespace/{namespace}/search",
+    path = "/admin/search",
+    request_body = CrossNamespaceSearchRequest,
     tag = "indexify",
     responses(
-        (status = 200, description = "Index search results", body = IndexSearchResponse),
-        (status = INTERNAL_SERVER_ERROR, description = "Unable to search index")
+        (status = 200, description = "Search results across namespaces", body = CrossNamespaceSearchResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to search namespaces")
     ),
 )]
 #[axum::debug_handler]
-async fn index_search(
-    Path(namespace): Path<String>,
+async fn cross_namespace_search(
     State(state): State<NamespaceEndpointState>,
-    Json(query): Json<SearchRequest>,
-) -> Result<Json<IndexSearchResponse>, IndexifyAPIError> {
-    let results = state
+    Json(query): Json<CrossNamespaceSearchRequest>,
+) -> Result<Json<CrossNamespaceSearchResponse>, IndexifyAPIError> {
+    let k = query.k.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let namespace_results = state
         .data_manager
-        .search(
-            &namespace,
-            &query.index,
-            &query.query,
-            query.k.unwrap_or(DEFAULT_SEARCH_LIMIT),
-        )
+        .search_namespaces(&query.namespaces, &query.index, &query.query, k)
         .await
         .map_err(IndexifyAPIError::internal_error)?;
-    let document_fragments: Vec<DocumentFragment> = results
-        .iter()
-        .map(|text| DocumentFragment {
-            content_id: text.content_id.clone(),
-            mime_type: text.mime_type.clone(),
-            text: text.text.clone(),
-            labels: text.labels.clone(),
-            confidence_score: text.confidence_score,
-        })
-        .collect();
-    Ok(Json(IndexSearchResponse {
-        results: document_fragments,
-    }))
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    for (namespace, namespace_result) in namespace_results {
+        match namespace_result {
+            Ok(texts) => results.extend(texts.into_iter().map(|text| NamespaceSearchResult {
+                namespace: namespace.clone(),
+                result: DocumentFragment {
+                    content_id: text.content_id,
+                    mime_type: text.mime_type,
+                    text: text.text,
+                    labels: text.labels,
+                    confidence_score: text.confidence_score,
+                    external_id: text.external_id,
+                    duplicate_content_ids: text.duplicate_content_ids,
+                },
+            })),
+            Err(e) => errors.push(NamespaceSearchError {
+                namespace,
+                message: e.to_string(),
+            }),
+        }
+    }
+    results.sort_by(|a, b| {
+        b.result
+            .confidence_score
+            .total_cmp(&a.result.confidence_score)
+    });
+    results.truncate(k as usize);
+    Ok(Json(CrossNamespaceSearchResponse { results, errors }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/search_stream",
+    request_body = StreamingSearchRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Server sent events with a `result` event per hit, an `error` event per failed index and a final `done` event", body = SearchResultEvent),
+    ),
+)]
+#[axum::debug_handler]
+async fn index_search_stream(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Json(query): Json<StreamingSearchRequest>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let k = query.k.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let data_manager = state.data_manager.clone();
+    let stream = search_events(query.indexes, move |index| {
+        let data_manager = data_manager.clone();
+        let namespace = namespace.clone();
+        let query = query.query.clone();
+        async move {
+            data_manager
+                .search(&namespace, &index, &query, k, None)
+                .await
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+//  Events of the streaming search, `search` returns the hits of an index
+fn search_events<F, Fut>(
+    indexes: Vec<String>,
+    search: F,
+) -> impl Stream<Item = Result<Event, axum::Error>>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Vec<ScoredText>>>,
+{
+    async_stream::stream! {
+        //  Search all the indexes concurrently and forward the hits of each index
+        // as soon as its backend responds.
+        let mut searches = indexes
+            .into_iter()
+            .map(|index| {
+                let results = search(index.clone());
+                async move { (index, results.await) }
+            })
+            .collect::<FuturesUnordered<_>>();
+        while let Some((index, results)) = searches.next().await {
+            match results {
+                Ok(results) => {
+                    for text in results {
+                        let event = SearchResultEvent {
+                            index: index.clone(),
+                            result: DocumentFragment {
+                                content_id: text.content_id,
+                                mime_type: text.mime_type,
+                                text: text.text,
+                                labels: text.labels,
+                                confidence_score: text.confidence_score,
+                                external_id: text.external_id,
+                                duplicate_content_ids: text.duplicate_content_ids,
+                            },
+                        };
+                        yield Event::default().event("result").json_data(event);
+                    }
+                }
+                Err(e) => {
+                    let event = SearchErrorEvent {
+                        index,
+                        message: e.to_string(),
+                    };
+                    yield Event::default().event("error").json_data(event);
+                }
+            }
+        }
+        yield Ok(Event::default().event("done").data(""));
+    }
 }
 
 #[axum::debug_handler]
@@ -1094,6 +3149,7 @@ async fn list_schemas(
             content_source: schema.content_source.to_string(),
             namespace: namespace.clone(),
             id: internal_api::StructuredDataSchema::schema_id(&namespace, &schema.content_source),
+            conflicts: serde_json::from_str(&schema.conflicts).unwrap_or_default(),
         };
 
         ddls.insert(schema.content_source.to_string(), schema.to_ddl());
@@ -1108,7 +3164,7 @@ async fn list_schemas(
     get,
     path = "/namespace/{namespace}/content/{content_id}/metadata",
     tag = "indexify",
-    params(MetadataRequest),
+    params(MetadataRequest, MetadataProvenance, MetadataPage),
     responses(
         (status = 200, description = "List of Events in a namespace", body = MetadataResponse),
         (status = INTERNAL_SERVER_ERROR, description = "Unable to list events in namespace")
@@ -1117,15 +3173,205 @@ async fn list_schemas(
 #[tracing::instrument]
 async fn get_extracted_metadata(
     Path((namespace, content_id)): Path<(String, String)>,
+    Query(provenance): Query<MetadataProvenance>,
+    Query(page): Query<MetadataPage>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<MetadataResponse>, IndexifyAPIError> {
+    let extracted_metadata = state
+        .data_manager
+        .metadata_lookup_by_provenance(&namespace, &content_id, &provenance, &page)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(MetadataResponse {
+        total: extracted_metadata.total,
+        metadata: extracted_metadata
+            .metadata
+            .into_iter()
+            .map(|r| r.into())
+            .collect(),
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/metadata/export",
+    request_body = ExportMetadataRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Metadata of the namespace written to Parquet files in the blob store", body = ExportMetadataResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to export metadata")
+    ),
+)]
+#[axum::debug_handler]
+async fn export_metadata(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Json(request): Json<ExportMetadataRequest>,
+) -> Result<Json<ExportMetadataResponse>, IndexifyAPIError> {
+    let export = state
+        .data_manager
+        .export_metadata(&namespace, &request)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(export))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/content/{content_id}/metadata/history",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Current and previous versions of the metadata of the content", body = MetadataHistoryResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to read the metadata history")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_metadata_history(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<MetadataHistoryResponse>, IndexifyAPIError> {
+    let versions = state
+        .data_manager
+        .metadata_history(&namespace, &content_id)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(MetadataHistoryResponse {
+        versions: versions.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    patch,
+    path = "/namespaces/{namespace}/content/{content_id}/metadata",
+    request_body = UpdateMetadataRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Metadata added to the content by hand", body = UpdateMetadataResponse),
+        (status = BAD_REQUEST, description = "Metadata isn't an object"),
+        (status = NOT_FOUND, description = "Content not found"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to update metadata")
+    ),
+)]
+#[axum::debug_handler]
+async fn update_metadata(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Json(request): Json<UpdateMetadataRequest>,
+) -> Result<Json<UpdateMetadataResponse>, IndexifyAPIError> {
+    if !request.metadata.is_object() {
+        return Err(IndexifyAPIError::new(
+            StatusCode::BAD_REQUEST,
+            "metadata has to be a JSON object",
+        ));
+    }
+    let content = state
+        .data_manager
+        .get_content_metadata(&namespace, vec![content_id.clone()])
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .into_iter()
+        .find(|content| content.namespace == namespace)
+        .ok_or_else(|| {
+            IndexifyAPIError::new(
+                StatusCode::NOT_FOUND,
+                &format!("content {} not found", content_id),
+            )
+        })?;
+    let metadata = state
+        .data_manager
+        .update_metadata(&content, &request.metadata)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(UpdateMetadataResponse {
+        metadata: metadata.into(),
+    }))
+}
+
+#[tracing::instrument(skip(request))]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/metadata/import",
+    request_body = ImportMetadataRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Metadata imported from outside of Indexify, with the records which were rejected", body = ImportMetadataResponse),
+        (status = BAD_REQUEST, description = "Too many records"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to import metadata")
+    ),
+)]
+#[axum::debug_handler]
+async fn import_metadata(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Json(request): Json<ImportMetadataRequest>,
+) -> Result<Json<ImportMetadataResponse>, IndexifyAPIError> {
+    if request.records.len() > MAX_METADATA_IMPORT_RECORDS {
+        return Err(IndexifyAPIError::new(
+            StatusCode::BAD_REQUEST,
+            &format!(
+                "metadata import accepts at most {} records",
+                MAX_METADATA_IMPORT_RECORDS
+            ),
+        ));
+    }
+    let response = state
+        .data_manager
+        .import_metadata(&namespace, &request.records)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(response))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/metadata/query",
+    request_body = MetadataQueryRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Metadata extracted from the content source", body = MetadataResponse),
+        (status = BAD_REQUEST, description = "Invalid geo filter or metadata filter"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to query metadata")
+    ),
+)]
+#[axum::debug_handler]
+async fn query_metadata(
+    Path(namespace): Path<String>,
     State(state): State<NamespaceEndpointState>,
+    Json(query): Json<MetadataQueryRequest>,
 ) -> Result<Json<MetadataResponse>, IndexifyAPIError> {
+    if let Some(geo_filter) = &query.geo_filter {
+        geo_filters::validate(geo_filter)
+            .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    }
+    for filter in &query.filters {
+        filter
+            .validate()
+            .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    }
+    let content_source = query.content_source.as_deref().unwrap_or("ingestion");
     let extracted_metadata = state
         .data_manager
-        .metadata_lookup(&namespace, &content_id)
+        .query_metadata(
+            &namespace,
+            content_source,
+            &query.provenance,
+            &query.filters,
+            query.geo_filter.as_ref(),
+            &query.page,
+        )
         .await
         .map_err(IndexifyAPIError::internal_error)?;
     Ok(Json(MetadataResponse {
-        metadata: extracted_metadata.into_iter().map(|r| r.into()).collect(),
+        total: extracted_metadata.total,
+        metadata: extracted_metadata
+            .metadata
+            .into_iter()
+            .map(|r| r.into())
+            .collect(),
     }))
 }
 
@@ -1137,6 +3383,134 @@ async fn get_raft_metrics_snapshot(
     state.coordinator_client.get_raft_metrics_snapshot().await
 }
 
+#[utoipa::path(
+    get,
+    path = "/cluster/health",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "The cluster of the coordinators has a leader and a majority of its voters can be reached", body = ClusterHealth),
+        (status = SERVICE_UNAVAILABLE, description = "The cluster of the coordinators is unhealthy", body = ClusterHealth),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to get the members of the cluster")
+    ),
+)]
+#[axum::debug_handler]
+#[tracing::instrument]
+async fn get_cluster_health(
+    State(state): State<NamespaceEndpointState>,
+) -> Result<(StatusCode, Json<ClusterHealth>), IndexifyAPIError> {
+    let health = state
+        .data_manager
+        .cluster_health()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    let status = if health.healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    Ok((status, Json(health)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cluster/members",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Voters and learners of the raft cluster of the coordinators", body = ListClusterMembersResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list cluster members")
+    ),
+)]
+#[axum::debug_handler]
+#[tracing::instrument]
+async fn list_cluster_members(
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ListClusterMembersResponse>, IndexifyAPIError> {
+    let members = state
+        .data_manager
+        .list_cluster_members()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(ListClusterMembersResponse {
+        members: members.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cluster/learners",
+    request_body = AddClusterLearner,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Node added to the cluster as a learner", body = ListClusterMembersResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to add the learner")
+    ),
+)]
+#[axum::debug_handler]
+#[tracing::instrument]
+async fn add_cluster_learner(
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<AddClusterLearner>,
+) -> Result<Json<ListClusterMembersResponse>, IndexifyAPIError> {
+    let members = state
+        .data_manager
+        .add_cluster_learner(payload.node_id, &payload.address, &payload.coordinator_addr)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(ListClusterMembersResponse {
+        members: members.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cluster/learners/{node_id}/promote",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Learner promoted to a voter", body = ListClusterMembersResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to promote the learner")
+    ),
+)]
+#[axum::debug_handler]
+#[tracing::instrument]
+async fn promote_cluster_learner(
+    Path(node_id): Path<u64>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ListClusterMembersResponse>, IndexifyAPIError> {
+    let members = state
+        .data_manager
+        .promote_cluster_learner(node_id)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(ListClusterMembersResponse {
+        members: members.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cluster/members/{node_id}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Node removed from the cluster", body = ListClusterMembersResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to remove the node")
+    ),
+)]
+#[axum::debug_handler]
+#[tracing::instrument]
+async fn remove_cluster_member(
+    Path(node_id): Path<u64>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ListClusterMembersResponse>, IndexifyAPIError> {
+    let members = state
+        .data_manager
+        .remove_cluster_member(node_id)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(ListClusterMembersResponse {
+        members: members.into_iter().map(Into::into).collect(),
+    }))
+}
+
 #[axum::debug_handler]
 #[tracing::instrument(skip_all)]
 async fn ui_index_handler() -> impl IntoResponse {
@@ -1188,3 +3562,100 @@ async fn shutdown_signal(handle: Handle) {
     handle.shutdown();
     info!("signal received, shutting down server gracefully");
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::to_bytes;
+    use hyper::Request;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn scored_text(content_id: &str, confidence_score: f32) -> ScoredText {
+        ScoredText {
+            text: format!("text of {}", content_id),
+            content_id: content_id.to_string(),
+            mime_type: "text/plain".to_string(),
+            labels: HashMap::new(),
+            confidence_score,
+            external_id: "".to_string(),
+            created_at: 0,
+            parent_id: "".to_string(),
+            duplicate_content_ids: vec![],
+        }
+    }
+
+    //  Name and data of every event of the body
+    fn parse_events(body: &str) -> Vec<(String, String)> {
+        body.split("\n\n")
+            .filter(|event| !event.is_empty())
+            .map(|event| {
+                let mut name = String::new();
+                let mut data = String::new();
+                for line in event.lines() {
+                    if let Some(value) = line.strip_prefix("event:") {
+                        name = value.trim().to_string();
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data = value.trim_start().to_string();
+                    }
+                }
+                (name, data)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_search_stream_events() {
+        let app = Router::new().route(
+            "/namespaces/:namespace/search_stream",
+            post(|Json(query): Json<StreamingSearchRequest>| async move {
+                Sse::new(search_events(query.indexes, |index| async move {
+                    match index.as_str() {
+                        "index_a" => Ok(vec![
+                            scored_text("content_1", 0.9),
+                            scored_text("content_2", 0.8),
+                        ]),
+                        _ => Err(anyhow!("index {} not found", index)),
+                    }
+                }))
+            }),
+        );
+        let request = Request::post("/namespaces/default/search_stream")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::json!({"indexes": ["index_a", "index_b"], "query": "hello"})
+                    .to_string(),
+            ))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/event-stream");
+
+        //  The body ends once every index was searched
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let events = parse_events(std::str::from_utf8(&body).unwrap());
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[3], ("done".to_string(), "".to_string()));
+
+        let results = events
+            .iter()
+            .filter(|(name, _)| name == "result")
+            .map(|(_, data)| serde_json::from_str::<SearchResultEvent>(data).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results
+                .iter()
+                .map(|event| (event.index.as_str(), event.result.content_id.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("index_a", "content_1"), ("index_a", "content_2")]
+        );
+        let errors = events
+            .iter()
+            .filter(|(name, _)| name == "error")
+            .map(|(_, data)| serde_json::from_str::<SearchErrorEvent>(data).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].index, "index_b");
+        assert_eq!(errors[0].message, "index index_b not found");
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use anyhow::{anyhow, Result};
+use bytes::Bytes;
+
+use super::NodeId;
+use crate::blob_storage::{BlobMeta, BlobStorage, BlobStorageWriter};
+
+/// Prefix of the keys of the backups of the state machine in blob storage.
+/// Keys end with the time the backup was taken, zero padded so they sort
+/// from the oldest to the newest backup.
+const STATE_BACKUP_PREFIX: &str = "indexify-state-backups/";
+
+/// Prefix of the keys of the RocksDB backups of the coordinators, followed by
+/// the id of the node. The files of the backup directory of the node are
+/// mirrored under it.
+const ROCKSDB_BACKUP_PREFIX: &str = "indexify-rocksdb-backups/";
+
+pub fn rocksdb_backup_prefix(node_id: NodeId) -> String {
+    format!("{}{}/", ROCKSDB_BACKUP_PREFIX, node_id)
+}
+
+pub fn backup_key(created_at: u64) -> String {
+    format!("{}{:020}.backup", STATE_BACKUP_PREFIX, created_at)
+}
+
+/// Uploads the backup, then deletes the oldest backups so only the `retain`
+/// latest ones are kept. Returns the url of the backup.
+pub async fn upload_backup(
+    blob_storage: &BlobStorage,
+    backup: Vec<u8>,
+    created_at: u64,
+    retain: usize,
+) -> Result<String> {
+    let data = futures::stream::iter(vec![Ok(Bytes::from(backup))]);
+    let res = blob_storage.put(&backup_key(created_at), data).await?;
+    let backups = list_backups(blob_storage).await?;
+    for backup in backups.iter().rev().skip(retain.max(1)) {
+        blob_storage.delete(&backup.url).await?;
+    }
+    Ok(res.url)
+}
+
+/// Lists the backups in blob storage from the oldest to the newest
+pub async fn list_backups(blob_storage: &BlobStorage) -> Result<Vec<BlobMeta>> {
+    let mut backups = blob_storage.list(STATE_BACKUP_PREFIX).await?;
+    backups.retain(|backup| backup.key.ends_with(".backup"));
+    backups.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(backups)
+}
+
+/// Uploads the files of the backup directory which aren't in blob storage
+/// yet, and deletes the ones removed from the directory since the last
+/// upload. Returns the number of files uploaded.
+pub async fn upload_backup_dir(
+    blob_storage: &BlobStorage,
+    backup_dir: &Path,
+    prefix: &str,
+) -> Result<usize> {
+    let uploaded_blobs: HashMap<String, BlobMeta> = blob_storage
+        .list(prefix)
+        .await?
+        .into_iter()
+        .map(|blob| (blob.key.clone(), blob))
+        .collect();
+    let mut keys = HashSet::new();
+    let mut uploaded = 0;
+    for entry in walkdir::WalkDir::new(backup_dir) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let key = format!(
+            "{}{}",
+            prefix,
+            entry.path().strip_prefix(backup_dir)?.to_string_lossy()
+        );
+        //  The files of a backup don't change once it's taken, only the files
+        // of the newer backups are uploaded
+        let size_bytes = entry.metadata()?.len();
+        if uploaded_blobs
+            .get(&key)
+            .map_or(true, |blob| blob.size_bytes != size_bytes)
+        {
+            let bytes = tokio::fs::read(entry.path()).await?;
+            let data = futures::stream::iter(vec![Ok(Bytes::from(bytes))]);
+            blob_storage.put(&key, data).await?;
+            uploaded += 1;
+        }
+        keys.insert(key);
+    }
+    for (key, blob) in &uploaded_blobs {
+        if !keys.contains(key) {
+            blob_storage.delete(&blob.url).await?;
+        }
+    }
+    Ok(uploaded)
+}
+
+/// Downloads the files mirrored under `prefix` to the backup directory.
+/// Returns false if there are no files under `prefix`.
+pub async fn download_backup_dir(
+    blob_storage: &BlobStorage,
+    prefix: &str,
+    backup_dir: &Path,
+) -> Result<bool> {
+    let blobs = blob_storage.list(prefix).await?;
+    for blob in &blobs {
+        let relative_path = blob
+            .key
+            .strip_prefix(prefix)
+            .ok_or_else(|| anyhow!("blob {} isn't under {}", blob.key, prefix))?;
+        let path = backup_dir.join(relative_path);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let bytes = blob_storage.content_reader().bytes(&blob.url).await?;
+        tokio::fs::write(&path, bytes).await?;
+    }
+    Ok(!blobs.is_empty())
+}
+
+/// Reads the newest backup in blob storage, None if there are no backups
+pub async fn latest_backup(blob_storage: &BlobStorage) -> Result<Option<Bytes>> {
+    let Some(backup) = list_backups(blob_storage).await?.pop() else {
+        return Ok(None);
+    };
+    let bytes = blob_storage.content_reader().bytes(&backup.url).await?;
+    Ok(Some(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob_storage::{BlobStorageConfig, DiskStorageConfig};
+
+    #[tokio::test]
+    async fn test_upload_backup_keeps_latest_backups() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let blob_storage = BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            gcs: None,
+            disk: Some(DiskStorageConfig {
+                path: dir.path().to_str().unwrap().to_string(),
+            }),
+            cache: None,
+            compression: None,
+            namespaces: HashMap::new(),
+        });
+        assert!(latest_backup(&blob_storage).await?.is_none());
+        for created_at in [100, 90, 1000] {
+            upload_backup(
+                &blob_storage,
+                created_at.to_string().into_bytes(),
+                created_at,
+                2,
+            )
+            .await?;
+        }
+        let backups = list_backups(&blob_storage).await?;
+        assert_eq!(
+            backups
+                .iter()
+                .map(|backup| backup.key.as_str())
+                .collect::<Vec<_>>(),
+            vec![backup_key(100), backup_key(1000)]
+        );
+        assert_eq!(latest_backup(&blob_storage).await?.unwrap(), "1000");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mirror_backup_dir() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let blob_storage = BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            gcs: None,
+            disk: Some(DiskStorageConfig {
+                path: dir.path().join("blobs").to_str().unwrap().to_string(),
+            }),
+            cache: None,
+            compression: None,
+            namespaces: HashMap::new(),
+        });
+        let backup_dir = dir.path().join("backups");
+        std::fs::create_dir_all(backup_dir.join("shared"))?;
+        std::fs::write(backup_dir.join("shared/1.sst"), "first")?;
+        std::fs::write(backup_dir.join("shared/2.sst"), "second")?;
+        let prefix = rocksdb_backup_prefix(1);
+        assert_eq!(
+            upload_backup_dir(&blob_storage, &backup_dir, &prefix).await?,
+            2
+        );
+
+        //  Only the new files are uploaded, and the removed ones are deleted
+        std::fs::remove_file(backup_dir.join("shared/1.sst"))?;
+        std::fs::write(backup_dir.join("shared/3.sst"), "third")?;
+        assert_eq!(
+            upload_backup_dir(&blob_storage, &backup_dir, &prefix).await?,
+            1
+        );
+
+        let restored_dir = dir.path().join("restored");
+        assert!(download_backup_dir(&blob_storage, &prefix, &restored_dir).await?);
+        assert!(!restored_dir.join("shared/1.sst").exists());
+        assert_eq!(
+            std::fs::read_to_string(restored_dir.join("shared/3.sst"))?,
+            "third"
+        );
+        assert!(
+            !download_backup_dir(&blob_storage, &rocksdb_backup_prefix(2), &restored_dir).await?
+        );
+        Ok(())
+    }
+}
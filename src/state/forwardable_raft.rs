@@ -1,9 +1,14 @@
-use std::{collections::BTreeMap, fmt::Debug};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+};
 
 use anyhow;
+use openraft::ChangeMembers;
 
 use super::{
     network::Network,
+    proposal_batcher::ProposalBatcher,
     typ::{CheckIsLeaderError, ForwardToLeader, InitializeError, RaftError},
     BasicNode,
     NodeId,
@@ -13,7 +18,7 @@ use super::{
     StateMachineUpdateRequest,
     TokioRuntime,
 };
-use crate::state::store::requests::StateMachineUpdateResponse;
+use crate::state::store::requests::{RequestPayload, StateMachineUpdateResponse};
 
 openraft::declare_raft_types!(
   pub TypeConfig:
@@ -26,16 +31,33 @@ openraft::declare_raft_types!(
       AsyncRuntime = TokioRuntime
 );
 
+/// Returned for writes which reached a node that isn't the leader and
+/// couldn't be handed to the leader, e.g. while a new leader is elected
+#[derive(Debug, thiserror::Error)]
+#[error("not the leader: {0}")]
+pub struct NotLeaderError(pub String);
+
 #[derive(Clone)]
 pub struct ForwardableRaft {
     id: NodeId,
     pub raft: Raft, //  the OpenRaft instance
     network: Network,
+    proposal_batcher: ProposalBatcher,
 }
 
 impl ForwardableRaft {
-    pub fn new(id: NodeId, raft: Raft, network: Network) -> Self {
-        Self { id, raft, network }
+    pub fn new(
+        id: NodeId,
+        raft: Raft,
+        network: Network,
+        proposal_batcher: ProposalBatcher,
+    ) -> Self {
+        Self {
+            id,
+            raft,
+            network,
+            proposal_batcher,
+        }
     }
 
     pub async fn client_write(
@@ -43,6 +65,26 @@ impl ForwardableRaft {
         request: StateMachineUpdateRequest,
     ) -> anyhow::Result<StateMachineUpdateResponse> {
         //  check whether this node is not the leader
+        if let Some(forward_to_leader) = self.ensure_leader().await? {
+            let leader_address = forward_to_leader
+                .leader_node
+                .ok_or_else(|| NotLeaderError("could not get leader address".to_string()))?;
+            return self.network.forward(&leader_address.addr, request).await;
+        }
+
+        self.proposal_batcher.client_write(request).await?;
+        let response = StateMachineUpdateResponse {
+            handled_by: self.id,
+        };
+        Ok(response)
+    }
+
+    /// Changes the membership of the cluster on the leader, forwarding the
+    /// change if this node isn't the leader
+    pub async fn change_membership(
+        &self,
+        request: StateMachineUpdateRequest,
+    ) -> anyhow::Result<StateMachineUpdateResponse> {
         if let Some(forward_to_leader) = self.ensure_leader().await? {
             let leader_address = forward_to_leader
                 .leader_node
@@ -50,7 +92,7 @@ impl ForwardableRaft {
             return self.network.forward(&leader_address.addr, request).await;
         }
 
-        self.raft.client_write(request).await?;
+        apply_membership_change(&self.raft, request).await?;
         let response = StateMachineUpdateResponse {
             handled_by: self.id,
         };
@@ -70,6 +112,22 @@ impl ForwardableRaft {
         self.raft.shutdown().await
     }
 
+    /// The index of the log committed on the leader, asking the leader for it
+    /// if this node isn't the leader. Once this node applied the log up to
+    /// it, reads from its state reflect every write committed before the call
+    pub async fn read_index(&self) -> anyhow::Result<Option<u64>> {
+        match self.raft.ensure_linearizable().await {
+            Ok(read_log_id) => Ok(read_log_id.map(|log_id| log_id.index)),
+            Err(RaftError::APIError(CheckIsLeaderError::ForwardToLeader(forward_to_leader))) => {
+                let leader_address = forward_to_leader
+                    .leader_node
+                    .ok_or_else(|| anyhow::anyhow!("could not get leader address"))?;
+                self.network.read_index(&leader_address.addr).await
+            }
+            Err(e) => Err(anyhow::anyhow!("Error occurred: {}", e.to_string())),
+        }
+    }
+
     /// Use this to detect whether the current node is the leader
     pub async fn ensure_leader(&self) -> anyhow::Result<Option<ForwardToLeader>> {
         let result = self.raft.ensure_linearizable().await;
@@ -80,3 +138,79 @@ impl ForwardableRaft {
         }
     }
 }
+
+/// Applies a membership change requested by an operator, this node has to be
+/// the leader. The change is then written to the log, so the state machine
+/// tracks the coordinator addresses of the members.
+pub async fn apply_membership_change(
+    raft: &Raft,
+    request: StateMachineUpdateRequest,
+) -> anyhow::Result<()> {
+    let membership = raft.metrics().borrow().membership_config.clone();
+    let membership = membership.membership();
+    let voters = membership.voter_ids().collect::<BTreeSet<_>>();
+    let learners = membership.learner_ids().collect::<BTreeSet<_>>();
+    match &request.payload {
+        RequestPayload::AddLearner {
+            node_id, address, ..
+        } => {
+            if voters.contains(node_id) || learners.contains(node_id) {
+                return Err(anyhow::anyhow!(
+                    "node {} is already a member of the cluster",
+                    node_id
+                ));
+            }
+            //  Returns once the learner has caught up with the log
+            raft.add_learner(
+                *node_id,
+                BasicNode {
+                    addr: address.clone(),
+                },
+                true,
+            )
+            .await?;
+        }
+        RequestPayload::PromoteLearner { node_id } => {
+            if !learners.contains(node_id) {
+                return Err(anyhow::anyhow!(
+                    "node {} isn't a learner of the cluster",
+                    node_id
+                ));
+            }
+            raft.change_membership(
+                ChangeMembers::AddVoterIds(BTreeSet::from([*node_id])),
+                false,
+            )
+            .await?;
+        }
+        RequestPayload::RemoveNode { node_id } => {
+            if voters.contains(node_id) {
+                if voters.len() == 1 {
+                    return Err(anyhow::anyhow!(
+                        "node {} is the last voter of the cluster",
+                        node_id
+                    ));
+                }
+                raft.change_membership(
+                    ChangeMembers::RemoveVoters(BTreeSet::from([*node_id])),
+                    false,
+                )
+                .await?;
+            } else if learners.contains(node_id) {
+                raft.change_membership(
+                    ChangeMembers::RemoveNodes(BTreeSet::from([*node_id])),
+                    false,
+                )
+                .await?;
+            } else {
+                return Err(anyhow::anyhow!(
+                    "node {} isn't a member of the cluster",
+                    node_id
+                ));
+            }
+        }
+        _ => return Err(anyhow::anyhow!("request isn't a membership change")),
+    }
+    raft.client_write(request).await?;
+    Ok(())
+}
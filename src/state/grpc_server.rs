@@ -1,4 +1,10 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use async_trait::async_trait;
 use indexify_proto::indexify_raft::{raft_api_server::RaftApi, RaftReply, RaftRequest};
@@ -6,39 +12,61 @@ use openraft::{
     error::{CheckIsLeaderError, ForwardToLeader, RaftError},
     BasicNode,
 };
-use requests::{RequestPayload, StateMachineUpdateRequest, StateMachineUpdateResponse};
+use requests::{
+    ReadIndexResponse,
+    RequestPayload,
+    StateMachineUpdateRequest,
+    StateMachineUpdateResponse,
+};
 use tonic::{Request, Status};
 use tracing::info;
 
-use super::{raft_client::RaftClient, NodeId};
+use super::{
+    forwardable_raft::apply_membership_change,
+    proposal_batcher::ProposalBatcher,
+    raft_client::RaftClient,
+    NodeId,
+};
 use crate::{
     grpc_helper::GrpcHelper,
     metrics::{raft_metrics, CounterGuard},
-    state::{store::requests, Raft},
+    state::{
+        store::{requests, NamespaceQuotaExceeded, RequestIdConflict},
+        Raft,
+    },
+    utils::timestamp_millis,
 };
 
 pub struct RaftGrpcServer {
     id: NodeId,
     raft: Arc<Raft>,
+    proposal_batcher: ProposalBatcher,
     raft_client: Arc<RaftClient>,
     address: String,
     coordinator_address: String,
+    //  Unix time in milliseconds of the last entries or heartbeat received
+    // from the leader
+    last_leader_contact: Arc<AtomicU64>,
 }
 
 impl RaftGrpcServer {
     pub fn new(
         id: NodeId,
         raft: Arc<Raft>,
+        proposal_batcher: ProposalBatcher,
         raft_client: Arc<RaftClient>,
         address: String,
         coordinator_addr: String,
+        last_leader_contact: Arc<AtomicU64>,
     ) -> Self {
         Self {
             id,
             raft,
+            proposal_batcher,
             raft_client,
             address,
             coordinator_address: coordinator_addr,
+            last_leader_contact,
         }
     }
 
@@ -114,6 +142,7 @@ impl RaftGrpcServer {
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         self.raft
             .client_write(state_machine_req)
@@ -130,6 +159,7 @@ impl RaftGrpcServer {
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         self.raft
             .client_write(state_machine_req)
@@ -161,10 +191,23 @@ impl RaftGrpcServer {
         let response = StateMachineUpdateResponse {
             handled_by: self.id,
         };
-        self.raft
+        self.proposal_batcher
             .client_write(request)
             .await
-            .map_err(|e| GrpcHelper::internal_err(e.to_string()))?;
+            .map_err(|e| {
+                //  The forwarding node decodes the rejection from the message
+                if let Some(e) = e.downcast_ref::<NamespaceQuotaExceeded>() {
+                    return Status::resource_exhausted(
+                        serde_json::to_string(e).unwrap_or_else(|_| e.to_string()),
+                    );
+                }
+                if let Some(e) = e.downcast_ref::<RequestIdConflict>() {
+                    return Status::already_exists(
+                        serde_json::to_string(e).unwrap_or_else(|_| e.to_string()),
+                    );
+                }
+                GrpcHelper::internal_err(e.to_string())
+            })?;
         GrpcHelper::ok_response(response)
     }
 }
@@ -178,7 +221,7 @@ impl RaftApi for RaftGrpcServer {
         self.incr_recv_bytes(&request);
 
         if (self.ensure_leader().await?).is_some() {
-            return Err(GrpcHelper::internal_err(
+            return Err(Status::unavailable(
                 "The node we thought was the leader is not the leader",
             ));
         }
@@ -196,6 +239,21 @@ impl RaftApi for RaftGrpcServer {
                 .await;
         }
 
+        if matches!(
+            req.payload,
+            RequestPayload::AddLearner { .. } |
+                RequestPayload::PromoteLearner { .. } |
+                RequestPayload::RemoveNode { .. }
+        ) {
+            apply_membership_change(&self.raft, req)
+                .await
+                .map_err(|e| GrpcHelper::internal_err(e.to_string()))?;
+            let response = StateMachineUpdateResponse {
+                handled_by: self.id,
+            };
+            return GrpcHelper::ok_response(response);
+        }
+
         self.handle_client_write(req).await
     }
 
@@ -211,6 +269,9 @@ impl RaftApi for RaftGrpcServer {
             .append_entries(ae_req)
             .await
             .map_err(|e| GrpcHelper::internal_err(e.to_string()))?;
+        //  Heartbeats of the leader are append entries requests without entries
+        self.last_leader_contact
+            .store(timestamp_millis(), Ordering::Relaxed);
 
         GrpcHelper::ok_response(resp)
     }
@@ -303,6 +364,7 @@ impl RaftApi for RaftGrpcServer {
                 },
                 new_state_changes: vec![],
                 state_changes_processed: vec![],
+                request_ids: vec![],
             })
             .map_err(|e| GrpcHelper::internal_err(e.to_string()))?;
 
@@ -316,4 +378,22 @@ impl RaftApi for RaftGrpcServer {
         self.add_node_to_cluster_if_absent(node_id, &address, &coordinator_addr)
             .await
     }
+
+    /// Confirms this node is still the leader with a quorum of the cluster
+    /// and returns the index of the log committed when the request was
+    /// received
+    async fn read_index(
+        &self,
+        request: Request<RaftRequest>,
+    ) -> Result<tonic::Response<RaftReply>, Status> {
+        self.incr_recv_bytes(&request);
+        let read_log_id = self
+            .raft
+            .ensure_linearizable()
+            .await
+            .map_err(|e| GrpcHelper::internal_err(e.to_string()))?;
+        GrpcHelper::ok_response(ReadIndexResponse {
+            read_index: read_log_id.map(|log_id| log_id.index),
+        })
+    }
 }
@@ -5,9 +5,12 @@ use std::{
     cell::RefCell,
     collections::{BTreeMap, HashMap, HashSet},
     io::Cursor,
-    path::Path,
-    sync::Arc,
-    time::SystemTime,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime},
 };
 
 use anyhow::{anyhow, Result};
@@ -25,7 +28,7 @@ use openraft::{
 };
 use serde::Serialize;
 use store::{
-    requests::{RequestPayload, StateChangeProcessed, StateMachineUpdateRequest},
+    requests::{ClientRequestId, RequestPayload, StateChangeProcessed, StateMachineUpdateRequest},
     ExecutorId,
     ExecutorIdRef,
     Response,
@@ -43,20 +46,27 @@ use tracing::{error, info, warn};
 
 use self::{
     forwardable_raft::ForwardableRaft,
+    proposal_batcher::ProposalBatcher,
     store::{StateMachineColumns, StateMachineStore},
 };
 use crate::{
+    blob_storage::BlobStorage,
     coordinator_filters::matches_mime_type,
     garbage_collector::GarbageCollector,
     metrics::raft_metrics::{self, network::MetricsSnapshot},
-    server_config::ServerConfig,
-    state::{raft_client::RaftClient, store::new_storage},
-    utils::timestamp_secs,
+    server_config::{FollowerReadsConfig, ServerConfig},
+    state::{
+        raft_client::RaftClient,
+        store::{new_storage, restore_rocksdb_backup},
+    },
+    utils::{timestamp_millis, timestamp_secs},
 };
 
+pub mod backup;
 pub mod forwardable_raft;
 pub mod grpc_server;
 pub mod network;
+pub mod proposal_batcher;
 pub mod raft_client;
 pub mod store;
 
@@ -101,12 +111,85 @@ pub mod typ {
 
 const MEMBERSHIP_CHECK_INTERVAL: tokio::time::Duration = tokio::time::Duration::from_secs(3);
 
+//  Time a read waits for this node to apply the log up to the read index
+const READ_INDEX_APPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Serialize)]
 pub struct RaftMetrics {
     pub openraft_metrics: openraft::RaftMetrics<NodeId, BasicNode>,
     pub raft_metrics: MetricsSnapshot,
 }
 
+impl RaftMetrics {
+    fn last_log_index(&self) -> u64 {
+        self.openraft_metrics.last_log_index.unwrap_or(0)
+    }
+
+    pub fn last_applied_index(&self) -> u64 {
+        self.openraft_metrics
+            .last_applied
+            .map(|log_id| log_id.index)
+            .unwrap_or(0)
+    }
+
+    /// The highest index of the log replicated to a quorum of the voters,
+    /// only the leader tracks the replication to the other members
+    pub fn committed_index(&self) -> Option<u64> {
+        let replication = self.openraft_metrics.replication.as_ref()?;
+        let mut matched = self
+            .openraft_metrics
+            .membership_config
+            .membership()
+            .voter_ids()
+            .map(|node_id| {
+                if node_id == self.openraft_metrics.id {
+                    return self.last_log_index();
+                }
+                replication
+                    .get(&node_id)
+                    .copied()
+                    .flatten()
+                    .map(|log_id| log_id.index)
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        matched.sort_unstable_by(|a, b| b.cmp(a));
+        matched.get(matched.len() / 2).copied()
+    }
+
+    /// Role of the node in the raft cluster, e.g. leader or follower
+    pub fn role(&self) -> &'static str {
+        match self.openraft_metrics.state {
+            openraft::ServerState::Leader => "leader",
+            openraft::ServerState::Follower => "follower",
+            openraft::ServerState::Learner => "learner",
+            openraft::ServerState::Candidate => "candidate",
+            openraft::ServerState::Shutdown => "shutdown",
+        }
+    }
+
+    pub fn pending_log_entries(&self) -> u64 {
+        self.last_log_index()
+            .saturating_sub(self.last_applied_index())
+    }
+
+    /// Entries of the log of the leader each other member is missing, empty
+    /// if this node isn't the leader
+    pub fn replication_lag(&self) -> HashMap<NodeId, u64> {
+        let Some(replication) = &self.openraft_metrics.replication else {
+            return HashMap::new();
+        };
+        replication
+            .iter()
+            .filter(|(node_id, _)| **node_id != self.openraft_metrics.id)
+            .map(|(node_id, matched)| {
+                let matched = matched.map(|log_id| log_id.index).unwrap_or(0);
+                (*node_id, self.last_log_index().saturating_sub(matched))
+            })
+            .collect()
+    }
+}
+
 pub struct App {
     pub id: NodeId,
     pub addr: String,
@@ -123,7 +206,13 @@ pub struct App {
     pub network: Network,
     pub node_addr: String,
     pub state_machine: Arc<StateMachineStore>,
+    rocksdb_backup_dir: PathBuf,
     pub garbage_collector: Arc<GarbageCollector>,
+    follower_reads: Option<FollowerReadsConfig>,
+    //  The last read index confirmed by the leader and when it was requested
+    read_index: Mutex<Option<(Instant, Option<u64>)>>,
+    //  Unix time in milliseconds this node last heard from the leader
+    last_leader_contact: Arc<AtomicU64>,
 }
 #[derive(Clone)]
 pub struct RaftConfigOverrides {
@@ -142,6 +231,10 @@ impl App {
             election_timeout_min: 1500,
             election_timeout_max: 3000,
             enable_heartbeat: true,
+            snapshot_policy: openraft::SnapshotPolicy::LogsSinceLast(
+                server_config.state_store.log.snapshot_interval,
+            ),
+            max_in_snapshot_log_to_keep: server_config.state_store.log.retained_log_entries,
             ..Default::default()
         };
 
@@ -165,10 +258,49 @@ impl App {
             .clone();
         let db_path_str = db_path.as_str().to_owned() + "/db";
         let sm_blob_store_path_str = db_path.as_str().to_owned() + "/sm-blob";
+        let rocksdb_backup_dir = PathBuf::from(db_path.as_str().to_owned() + "/rocksdb-backups");
         let db_path: &Path = Path::new(&db_path_str);
         let sm_blob_store_path: &Path = Path::new(&sm_blob_store_path_str);
 
-        let (log_store, state_machine) = new_storage(db_path, sm_blob_store_path).await;
+        let restore_rocksdb_on_startup = server_config
+            .state_store
+            .rocksdb_backup
+            .as_ref()
+            .is_some_and(|backup| backup.restore_on_startup);
+        if restore_rocksdb_on_startup && !db_path.exists() {
+            let blob_storage = BlobStorage::new_with_config(server_config.blob_storage.clone());
+            let prefix = backup::rocksdb_backup_prefix(server_config.node_id);
+            if backup::download_backup_dir(&blob_storage, &prefix, &rocksdb_backup_dir).await? {
+                info!("restoring the database from the latest rocksdb backup");
+                restore_rocksdb_backup(&rocksdb_backup_dir, db_path, sm_blob_store_path)?;
+            } else {
+                warn!("no rocksdb backup to restore from");
+            }
+        }
+
+        let has_state = db_path.exists();
+        let (log_store, state_machine) = new_storage(
+            db_path,
+            sm_blob_store_path,
+            server_config.state_store.log.compact_purged_logs,
+            server_config.state_store.log.state_change_log_entries,
+        )
+        .await;
+        let restore_on_startup = server_config
+            .state_store
+            .backup
+            .as_ref()
+            .is_some_and(|backup| backup.restore_on_startup);
+        if restore_on_startup && !has_state {
+            let blob_storage = BlobStorage::new_with_config(server_config.blob_storage.clone());
+            match backup::latest_backup(&blob_storage).await? {
+                Some(bytes) => {
+                    info!("restoring state machine from the latest backup");
+                    state_machine.restore_backup(&bytes).await?;
+                }
+                None => warn!("no state machine backup to restore from"),
+            }
+        }
         let state_change_rx = state_machine.state_change_rx.clone();
 
         let raft_client = Arc::new(RaftClient::new());
@@ -184,8 +316,16 @@ impl App {
         .await
         .map_err(|e| anyhow!("unable to create raft: {}", e.to_string()))?;
 
-        let forwardable_raft =
-            ForwardableRaft::new(server_config.node_id, raft.clone(), network.clone());
+        let proposal_batcher = ProposalBatcher::new(
+            raft.clone(),
+            server_config.state_store.max_proposal_batch_size,
+        );
+        let forwardable_raft = ForwardableRaft::new(
+            server_config.node_id,
+            raft.clone(),
+            network.clone(),
+            proposal_batcher.clone(),
+        );
 
         let mut nodes = BTreeMap::new();
         nodes.insert(
@@ -201,12 +341,15 @@ impl App {
             .map_err(|e| anyhow!("unable to create raft address : {}", e.to_string()))?;
 
         info!("starting raft server at {}", addr.to_string());
+        let last_leader_contact = Arc::new(AtomicU64::new(0));
         let raft_srvr = RaftApiServer::new(RaftGrpcServer::new(
             server_config.node_id,
             Arc::new(raft.clone()),
+            proposal_batcher,
             Arc::clone(&raft_client),
             addr.to_string(),
             server_config.coordinator_addr.clone(),
+            last_leader_contact.clone(),
         ));
         let (leader_change_tx, leader_change_rx) = tokio::sync::watch::channel::<bool>(false);
 
@@ -229,7 +372,11 @@ impl App {
             network,
             node_addr: format!("{}:{}", server_config.listen_if, server_config.raft_port),
             state_machine,
+            rocksdb_backup_dir,
             garbage_collector,
+            follower_reads: server_config.state_store.follower_reads.clone(),
+            read_index: Mutex::new(None),
+            last_leader_contact,
         });
 
         let raft_clone = app.forwardable_raft.clone();
@@ -300,10 +447,137 @@ impl App {
         }
     }
 
+    /// Uploads a backup of the state machine to blob storage, keeping the
+    /// `retain` latest backups. Returns the url of the backup.
+    pub async fn backup_state(&self, blob_storage: &BlobStorage, retain: usize) -> Result<String> {
+        let backup = self.state_machine.backup().await?;
+        backup::upload_backup(blob_storage, backup, timestamp_secs(), retain).await
+    }
+
+    /// Adds an incremental backup of the database of this node to its RocksDB
+    /// backup directory and uploads the files of the directory which aren't
+    /// in blob storage yet. Returns the id of the backup.
+    pub async fn backup_rocksdb(&self, blob_storage: &BlobStorage, retain: usize) -> Result<u32> {
+        let state_machine = self.state_machine.clone();
+        let backup_dir = self.rocksdb_backup_dir.clone();
+        let backup_id =
+            tokio::task::spawn_blocking(move || state_machine.backup_rocksdb(&backup_dir, retain))
+                .await??;
+        backup::upload_backup_dir(
+            blob_storage,
+            &self.rocksdb_backup_dir,
+            &backup::rocksdb_backup_prefix(self.id),
+        )
+        .await?;
+        Ok(backup_id)
+    }
+
+    /// Waits until the state of this node reflects the writes committed
+    /// `max_staleness_ms` of the follower reads config before, the reads
+    /// served from the local state are otherwise unbounded
+    async fn ensure_read_freshness(&self) -> Result<()> {
+        match &self.follower_reads {
+            Some(follower_reads) => {
+                self.wait_for_read_index(Duration::from_millis(follower_reads.max_staleness_ms))
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Unix time in milliseconds this node last heard from the leader, 0 if
+    /// it never did. The leader doesn't wait on itself, so it reports the
+    /// current time.
+    pub fn last_leader_contact_ms(&self) -> u64 {
+        if self
+            .forwardable_raft
+            .raft
+            .metrics()
+            .borrow()
+            .state
+            .is_leader()
+        {
+            return timestamp_millis();
+        }
+        self.last_leader_contact.load(Ordering::Relaxed)
+    }
+
+    /// Waits until the state of this node reflects every write committed
+    /// before the call, regardless of the follower reads config
+    pub async fn ensure_linearizable_read(&self) -> Result<()> {
+        self.wait_for_read_index(Duration::ZERO).await
+    }
+
+    /// Waits until this node applied the log up to a read index requested at
+    /// most `max_staleness` ago, requesting a new read index from the leader
+    /// if the last one is older
+    pub async fn wait_for_read_index(&self, max_staleness: Duration) -> Result<()> {
+        let read_index = {
+            //  Concurrent reads wait for the same request to the leader
+            let mut last_read_index = self.read_index.lock().await;
+            match *last_read_index {
+                Some((requested_at, read_index)) if requested_at.elapsed() <= max_staleness => {
+                    read_index
+                }
+                _ => {
+                    let requested_at = Instant::now();
+                    let read_index = self.forwardable_raft.read_index().await?;
+                    *last_read_index = Some((requested_at, read_index));
+                    read_index
+                }
+            }
+        };
+        self.forwardable_raft
+            .raft
+            .wait(Some(READ_INDEX_APPLY_TIMEOUT))
+            .applied_index_at_least(read_index, "read index")
+            .await
+            .map_err(|e| anyhow!("unable to catch up with the read index: {}", e))?;
+        Ok(())
+    }
+
     pub fn get_state_change_watcher(&self) -> Receiver<StateChange> {
         self.state_change_rx.clone()
     }
 
+    pub fn subscribe_to_state_changes(&self) -> broadcast::Receiver<StateChange> {
+        self.state_machine.subscribe_to_state_changes()
+    }
+
+    /// The namespace of the object the state change is about, None for
+    /// changes which don't belong to a namespace or whose object was deleted
+    pub async fn state_change_namespace(&self, change: &StateChange) -> Result<Option<String>> {
+        let namespace = match change.change_type {
+            internal_api::ChangeType::NewContent | internal_api::ChangeType::TombstoneContent => {
+                self.state_machine
+                    .get_from_cf::<internal_api::ContentMetadata, _>(
+                        StateMachineColumns::ContentTable,
+                        &change.object_id,
+                    )
+                    .await?
+                    .map(|content| content.namespace)
+            }
+            internal_api::ChangeType::NewExtractionPolicy |
+            internal_api::ChangeType::ExtractionPolicyUpdated => self
+                .state_machine
+                .get_from_cf::<ExtractionPolicy, _>(
+                    StateMachineColumns::ExtractionPolicies,
+                    &change.object_id,
+                )
+                .await?
+                .map(|extraction_policy| extraction_policy.namespace),
+            internal_api::ChangeType::TaskCompleted => self
+                .state_machine
+                .get_from_cf::<internal_api::Task, _>(StateMachineColumns::Tasks, &change.object_id)
+                .await?
+                .map(|task| task.namespace),
+            internal_api::ChangeType::ExecutorAdded |
+            internal_api::ChangeType::ExecutorRemoved |
+            internal_api::ChangeType::NewGargabeCollectionTask => None,
+        };
+        Ok(namespace)
+    }
+
     pub async fn stop(&self) -> Result<()> {
         info!("stopping raft server");
         let _ = self.forwardable_raft.shutdown().await;
@@ -351,6 +625,7 @@ impl App {
             payload: RequestPayload::MarkStateChangesProcessed { state_changes },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         let _resp = self.forwardable_raft.client_write(req).await?;
         Ok(())
@@ -396,15 +671,8 @@ impl App {
             {
                 continue;
             }
-            for (name, value) in &extraction_policy.filters {
-                let is_match = content_metadata
-                    .labels
-                    .get(name)
-                    .map(|v| v == value)
-                    .unwrap_or(false);
-                if !is_match {
-                    continue;
-                }
+            if !extraction_policy.matches_content(&content_metadata) {
+                continue;
             }
             // check if the mimetype matches
             let extractor = self
@@ -449,6 +717,16 @@ impl App {
         policy_id: &str,
     ) -> Result<Vec<internal_api::ContentMetadata>> {
         let extraction_policy = self.get_extraction_policy(policy_id).await?;
+        self.content_matching_extraction_policy(&extraction_policy)
+            .await
+    }
+
+    /// Returns the content the extraction policy applies to, the policy
+    /// doesn't have to be stored
+    pub async fn content_matching_extraction_policy(
+        &self,
+        extraction_policy: &ExtractionPolicy,
+    ) -> Result<Vec<internal_api::ContentMetadata>> {
         // get the extractor so we can check the mimetype
         let extractor = self
             .extractor_with_name(&extraction_policy.extractor)
@@ -502,14 +780,7 @@ impl App {
             {
                 continue;
             }
-            let is_match = &extraction_policy.filters.iter().all(|(name, value)| {
-                content
-                    .labels
-                    .get(name)
-                    .map(|v| v == value)
-                    .unwrap_or(false)
-            });
-            if extraction_policy.filters.is_empty() || *is_match {
+            if extraction_policy.matches_content(&content) {
                 matched_content_list.push(content);
             }
         }
@@ -576,6 +847,7 @@ impl App {
         &self,
         namespace: &str,
     ) -> Result<Vec<internal_api::ContentMetadata>> {
+        self.ensure_read_freshness().await?;
         let content_ids = self
             .state_machine
             .get_content_namespace_table()
@@ -597,6 +869,7 @@ impl App {
                 timestamp_secs(),
             )],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         let _resp = self
             .forwardable_raft
@@ -606,15 +879,38 @@ impl App {
         Ok(())
     }
 
+    pub async fn drain_executor(&self, executor_id: &str) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::DrainExecutor {
+                executor_id: executor_id.to_string(),
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
     pub async fn create_extraction_policy(
         &self,
         extraction_policy: ExtractionPolicy,
         updated_structured_data_schema: Option<StructuredDataSchema>,
     ) -> Result<()> {
-        // TODO: Add delete_extraction_policy. This will only
-        // remove the actual object from the forward and reverse indexes. Leave
-        // artifacts in place
+        self.create_extraction_policy_with_operation(
+            extraction_policy,
+            updated_structured_data_schema,
+            None,
+        )
+        .await
+    }
 
+    pub async fn create_extraction_policy_with_operation(
+        &self,
+        extraction_policy: ExtractionPolicy,
+        updated_structured_data_schema: Option<StructuredDataSchema>,
+        operation: Option<internal_api::OperationRecord>,
+    ) -> Result<()> {
         //  Check if the extraction policy has already been created. If so, don't create
         // it
         let existing_policies = self
@@ -643,6 +939,7 @@ impl App {
                     &extraction_policy.name,
                     &extraction_policy.namespace,
                 ),
+                operation,
             },
             new_state_changes: vec![StateChange::new(
                 extraction_policy.id.clone(),
@@ -650,16 +947,70 @@ impl App {
                 timestamp_secs(),
             )],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         let _resp = self.forwardable_raft.client_write(req).await?;
         Ok(())
     }
 
+    /// Writes the updated policy. Re-extraction creates tasks for all the
+    /// content matching the policy, as for a new policy.
+    pub async fn update_extraction_policy(
+        &self,
+        extraction_policy: ExtractionPolicy,
+        reextract: bool,
+    ) -> Result<()> {
+        let mut new_state_changes = vec![StateChange::new(
+            extraction_policy.id.clone(),
+            internal_api::ChangeType::ExtractionPolicyUpdated,
+            timestamp_secs(),
+        )];
+        if reextract {
+            new_state_changes.push(StateChange::new(
+                extraction_policy.id.clone(),
+                internal_api::ChangeType::NewExtractionPolicy,
+                timestamp_secs(),
+            ));
+        }
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::UpdateExtractionPolicy { extraction_policy },
+            new_state_changes,
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    /// Deletes the policy along with the indexes with the ids, and replaces
+    /// the tasks of the policy which are cancelled or no longer write to the
+    /// removed indexes
+    pub async fn delete_extraction_policy(
+        &self,
+        extraction_policy: ExtractionPolicy,
+        index_ids: Vec<String>,
+        tasks: Vec<internal_api::Task>,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::DeleteExtractionPolicy {
+                extraction_policy,
+                index_ids,
+                tasks,
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
     pub async fn update_task(
         &self,
         task: internal_api::Task,
         executor_id: Option<String>,
         content_meta_list: Vec<internal_api::ContentMetadata>,
+        request_id: Option<String>,
     ) -> Result<()> {
         let mut state_changes = vec![];
         for content in &content_meta_list {
@@ -669,15 +1020,28 @@ impl App {
                 timestamp_secs(),
             ));
         }
+        if task.terminal_state() {
+            state_changes.push(StateChange::new(
+                task.id.clone(),
+                internal_api::ChangeType::TaskCompleted,
+                timestamp_secs(),
+            ));
+        }
+        let payload = RequestPayload::UpdateTask {
+            task: task.clone(),
+            executor_id: executor_id.clone(),
+            content_metadata: content_meta_list.clone(),
+            update_time: SystemTime::now(),
+        };
+        let request_ids = request_id
+            .map(|id| ClientRequestId::new(id, &payload))
+            .into_iter()
+            .collect();
         let req = StateMachineUpdateRequest {
-            payload: RequestPayload::UpdateTask {
-                task: task.clone(),
-                executor_id: executor_id.clone(),
-                content_metadata: content_meta_list.clone(),
-                update_time: SystemTime::now(),
-            },
+            payload,
             new_state_changes: state_changes,
             state_changes_processed: vec![],
+            request_ids,
         };
         let _resp = self.forwardable_raft.client_write(req).await?;
         Ok(())
@@ -777,6 +1141,7 @@ impl App {
             payload: RequestPayload::CreateOrAssignGarbageCollectionTask { gc_tasks },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         self.forwardable_raft.client_write(request).await?;
         Ok(())
@@ -791,6 +1156,7 @@ impl App {
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         self.forwardable_raft.client_write(req).await?;
         Ok(())
@@ -831,18 +1197,46 @@ impl App {
     }
 
     pub async fn create_namespace(&self, namespace: &str) -> Result<()> {
+        self.create_namespace_with_operation(namespace, None).await
+    }
+
+    pub async fn create_namespace_with_operation(
+        &self,
+        namespace: &str,
+        operation: Option<internal_api::OperationRecord>,
+    ) -> Result<()> {
         let req = StateMachineUpdateRequest {
             payload: RequestPayload::CreateNamespace {
                 name: namespace.to_string(),
                 structured_data_schema: StructuredDataSchema::new("ingestion", namespace),
+                operation,
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         let _resp = self.forwardable_raft.client_write(req).await?;
         Ok(())
     }
 
+    pub async fn delete_namespace(
+        &self,
+        namespace: &str,
+        gc_tasks: Vec<internal_api::GarbageCollectionTask>,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::DeleteNamespace {
+                name: namespace.to_string(),
+                gc_tasks,
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
     pub async fn list_namespaces(&self) -> Result<Vec<internal_api::Namespace>> {
         //  Fetch the namespaces from the db
         let namespaces: Vec<String> = self
@@ -889,6 +1283,7 @@ impl App {
         addr: &str,
         executor_id: &str,
         extractor: internal_api::ExtractorDescription,
+        labels: HashMap<String, String>,
     ) -> Result<String> {
         let state_change = StateChange::new(
             executor_id.to_string(),
@@ -901,9 +1296,11 @@ impl App {
                 executor_id: executor_id.to_string(),
                 extractor,
                 ts_secs: timestamp_secs(),
+                labels,
             },
             new_state_changes: vec![state_change.clone()],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         let _resp = self.forwardable_raft.client_write(req).await?;
         Ok(state_change.id)
@@ -960,6 +1357,20 @@ impl App {
                 state_change_id: state_change_id.to_string(),
                 processed_at: timestamp_secs(),
             }],
+            request_ids: vec![],
+        };
+        self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    /// Assigns tasks which aren't created by a state change, e.g. failed tasks
+    /// which are retried
+    pub async fn assign_tasks(&self, assignments: HashMap<TaskId, ExecutorId>) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::AssignTask { assignments },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
         };
         self.forwardable_raft.client_write(req).await?;
         Ok(())
@@ -968,6 +1379,17 @@ impl App {
     pub async fn create_content_batch(
         &self,
         content_metadata: Vec<internal_api::ContentMetadata>,
+    ) -> Result<()> {
+        self.create_content_batch_with_request_id(content_metadata, None)
+            .await
+    }
+
+    /// Creates the content, a retry with the same request id isn't applied
+    /// again
+    pub async fn create_content_batch_with_request_id(
+        &self,
+        content_metadata: Vec<internal_api::ContentMetadata>,
+        request_id: Option<String>,
     ) -> Result<()> {
         let mut state_changes = vec![];
         for content in &content_metadata {
@@ -977,16 +1399,23 @@ impl App {
                 timestamp_secs(),
             ));
         }
+        let payload = RequestPayload::CreateContent { content_metadata };
+        let request_ids = request_id
+            .map(|id| ClientRequestId::new(id, &payload))
+            .into_iter()
+            .collect();
         let req = StateMachineUpdateRequest {
-            payload: RequestPayload::CreateContent { content_metadata },
+            payload,
             new_state_changes: state_changes,
             state_changes_processed: vec![],
+            request_ids,
         };
-        let _ = self
-            .forwardable_raft
-            .client_write(req)
-            .await
-            .map_err(|e| anyhow!("unable to create content metadata: {}", e.to_string()))?;
+        let _ = self.forwardable_raft.client_write(req).await.map_err(|e| {
+            if e.is::<store::NamespaceQuotaExceeded>() || e.is::<store::RequestIdConflict>() {
+                return e;
+            }
+            anyhow!("unable to create content metadata: {}", e.to_string())
+        })?;
         Ok(())
     }
 
@@ -1010,6 +1439,7 @@ impl App {
             },
             new_state_changes: state_changes,
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         let _ = self
             .forwardable_raft
@@ -1023,6 +1453,7 @@ impl App {
         &self,
         content_id: &str,
     ) -> Result<internal_api::ContentMetadata> {
+        self.ensure_read_freshness().await?;
         let content_metadata = self
             .state_machine
             .get_from_cf::<internal_api::ContentMetadata, _>(
@@ -1038,6 +1469,7 @@ impl App {
         &self,
         content_ids: Vec<String>,
     ) -> Result<Vec<internal_api::ContentMetadata>> {
+        self.ensure_read_freshness().await?;
         let content_ids: HashSet<String> = content_ids.into_iter().collect();
         self.state_machine.get_content_from_ids(content_ids).await
     }
@@ -1046,6 +1478,7 @@ impl App {
         &self,
         content_id: &str,
     ) -> Result<Vec<internal_api::ContentMetadata>> {
+        self.ensure_read_freshness().await?;
         self.state_machine
             .get_content_tree_metadata(content_id)
             .await
@@ -1063,6 +1496,7 @@ impl App {
                 state_change_id: state_change_id.to_string(),
                 processed_at: timestamp_secs(),
             }],
+            request_ids: vec![],
         };
         let _resp = self.forwardable_raft.client_write(req).await?;
         Ok(())
@@ -1163,75 +1597,517 @@ impl App {
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         let _resp = self.forwardable_raft.client_write(req).await?;
         Ok(())
     }
 
-    pub async fn list_state_changes(&self) -> Result<Vec<StateChange>> {
-        let state_changes = self
-            .state_machine
-            .get_all_rows_from_cf::<StateChange>(StateMachineColumns::StateChanges)
-            .await?
-            .into_iter()
-            .map(|(_, value)| value)
-            .collect();
-        Ok(state_changes)
+    pub async fn get_index_migration(
+        &self,
+        id: &str,
+    ) -> Result<Option<internal_api::IndexMigration>> {
+        self.state_machine
+            .get_from_cf::<internal_api::IndexMigration, _>(
+                StateMachineColumns::IndexMigrations,
+                id,
+            )
+            .await
     }
 
-    pub async fn get_structured_data_schema(
+    pub async fn set_index_migration(&self, migration: internal_api::IndexMigration) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetIndexMigration { migration },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn get_rebalance_job(&self, id: &str) -> Result<Option<internal_api::RebalanceJob>> {
+        self.state_machine
+            .get_from_cf::<internal_api::RebalanceJob, _>(StateMachineColumns::RebalanceJobs, id)
+            .await
+    }
+
+    pub async fn set_rebalance_job(
         &self,
-        namespace: &str,
-        content_source: &str,
-    ) -> Result<StructuredDataSchema> {
-        let id = StructuredDataSchema::schema_id(namespace, content_source);
-        let schema = self
-            .state_machine
-            .get_from_cf::<StructuredDataSchema, _>(StateMachineColumns::StructuredDataSchemas, &id)
-            .await?
-            .ok_or_else(|| anyhow!("Schema with id {} not found", id))?;
-        Ok(schema)
+        job: internal_api::RebalanceJob,
+        index: Option<internal_api::Index>,
+        extraction_policy: Option<ExtractionPolicy>,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetRebalanceJob {
+                job,
+                index,
+                extraction_policy,
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
     }
 
-    pub async fn get_schemas_for_namespace(
+    pub async fn get_blob_migration_job(
         &self,
         namespace: &str,
-    ) -> Result<Vec<StructuredDataSchema>> {
-        let schemas_for_ns = self
-            .state_machine
-            .get_schemas_by_namespace()
+    ) -> Result<Option<internal_api::BlobMigrationJob>> {
+        self.state_machine
+            .get_from_cf::<internal_api::BlobMigrationJob, _>(
+                StateMachineColumns::BlobMigrationJobs,
+                namespace,
+            )
             .await
-            .get(namespace)
-            .cloned()
-            .unwrap_or(HashSet::new());
-        let schemas = self.state_machine.get_schemas(schemas_for_ns).await?;
-        Ok(schemas)
     }
 
-    pub async fn get_unfinished_tasks_by_extractor(
+    pub async fn set_blob_migration_job(
         &self,
-    ) -> HashMap<store::ExtractorName, HashSet<TaskId>> {
-        self.state_machine.get_unfinished_tasks_by_extractor().await
+        job: internal_api::BlobMigrationJob,
+        storage_urls: HashMap<String, String>,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetBlobMigrationJob { job, storage_urls },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
     }
 
-    pub async fn insert_executor_running_task_count(&mut self, executor_id: &str, task_count: u64) {
+    pub async fn get_operation_record(
+        &self,
+        token: &str,
+    ) -> Result<Option<internal_api::OperationRecord>> {
         self.state_machine
-            .insert_executor_running_task_count(executor_id, task_count)
-            .await;
+            .get_from_cf::<internal_api::OperationRecord, _>(
+                StateMachineColumns::OperationRecords,
+                token,
+            )
+            .await
     }
 
-    pub fn start_periodic_membership_check(self: &Arc<Self>, mut shutdown_rx: Receiver<()>) {
-        let app_clone = Arc::clone(self);
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(MEMBERSHIP_CHECK_INTERVAL);
-            loop {
-                tokio::select! {
-                    _ = shutdown_rx.changed() => {
-                        info!("shutting down periodic membership check");
-                        break;
-                    }
-                    _ = interval.tick() => {
-                        if app_clone.is_seed_node() {
+    pub async fn get_query_template(
+        &self,
+        id: &str,
+    ) -> Result<Option<internal_api::QueryTemplate>> {
+        self.state_machine
+            .get_from_cf::<internal_api::QueryTemplate, _>(StateMachineColumns::QueryTemplates, id)
+            .await
+    }
+
+    pub async fn list_query_templates(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<internal_api::QueryTemplate>> {
+        let query_templates = self
+            .state_machine
+            .get_all_rows_from_cf::<internal_api::QueryTemplate>(
+                StateMachineColumns::QueryTemplates,
+            )
+            .await?
+            .into_iter()
+            .map(|(_, query_template)| query_template)
+            .filter(|query_template| query_template.namespace == namespace)
+            .collect();
+        Ok(query_templates)
+    }
+
+    pub async fn set_query_template(
+        &self,
+        query_template: internal_api::QueryTemplate,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetQueryTemplate { query_template },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn get_ingestion_plugin(
+        &self,
+        id: &str,
+    ) -> Result<Option<internal_api::IngestionPlugin>> {
+        self.state_machine
+            .get_from_cf::<internal_api::IngestionPlugin, _>(
+                StateMachineColumns::IngestionPlugins,
+                id,
+            )
+            .await
+    }
+
+    pub async fn list_ingestion_plugins(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<internal_api::IngestionPlugin>> {
+        let plugins = self
+            .state_machine
+            .get_all_rows_from_cf::<internal_api::IngestionPlugin>(
+                StateMachineColumns::IngestionPlugins,
+            )
+            .await?
+            .into_iter()
+            .map(|(_, plugin)| plugin)
+            .filter(|plugin| plugin.namespace == namespace)
+            .collect();
+        Ok(plugins)
+    }
+
+    pub async fn set_ingestion_plugin(&self, plugin: internal_api::IngestionPlugin) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetIngestionPlugin { plugin },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn delete_ingestion_plugin(&self, id: &str) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::DeleteIngestionPlugin { id: id.to_string() },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn get_processing_sla(
+        &self,
+        id: &str,
+    ) -> Result<Option<internal_api::ProcessingSla>> {
+        self.state_machine
+            .get_from_cf::<internal_api::ProcessingSla, _>(StateMachineColumns::ProcessingSlas, id)
+            .await
+    }
+
+    pub async fn list_processing_slas(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<internal_api::ProcessingSla>> {
+        let slas = self
+            .state_machine
+            .get_all_rows_from_cf::<internal_api::ProcessingSla>(
+                StateMachineColumns::ProcessingSlas,
+            )
+            .await?
+            .into_iter()
+            .map(|(_, sla)| sla)
+            .filter(|sla| sla.namespace == namespace)
+            .collect();
+        Ok(slas)
+    }
+
+    pub async fn set_processing_sla(&self, sla: internal_api::ProcessingSla) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetProcessingSla { sla },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn delete_processing_sla(&self, id: &str) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::DeleteProcessingSla { id: id.to_string() },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn delete_query_template(&self, id: &str) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::DeleteQueryTemplate { id: id.to_string() },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn create_ingest_intent(&self, intent: internal_api::IngestIntent) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::CreateIngestIntent { intent },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn list_ingest_intents(&self) -> Result<Vec<internal_api::IngestIntent>> {
+        let intents = self
+            .state_machine
+            .get_all_rows_from_cf::<internal_api::IngestIntent>(StateMachineColumns::IngestIntents)
+            .await?
+            .into_iter()
+            .map(|(_, intent)| intent)
+            .collect();
+        Ok(intents)
+    }
+
+    pub async fn delete_ingest_intents(&self, content_ids: Vec<String>) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::DeleteIngestIntents { content_ids },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn create_embedding_expirations(
+        &self,
+        expirations: Vec<internal_api::EmbeddingExpiration>,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::CreateEmbeddingExpirations { expirations },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn list_embedding_expirations(
+        &self,
+    ) -> Result<Vec<internal_api::EmbeddingExpiration>> {
+        let expirations = self
+            .state_machine
+            .get_all_rows_from_cf::<internal_api::EmbeddingExpiration>(
+                StateMachineColumns::EmbeddingExpirations,
+            )
+            .await?
+            .into_iter()
+            .map(|(_, expiration)| expiration)
+            .collect();
+        Ok(expirations)
+    }
+
+    pub async fn delete_embedding_expirations(&self, keys: Vec<String>) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::DeleteEmbeddingExpirations { keys },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn add_chunk_reference(
+        &self,
+        index_table: &str,
+        hash: &str,
+        content_id: &str,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::AddChunkReference {
+                index_table: index_table.to_string(),
+                hash: hash.to_string(),
+                content_id: content_id.to_string(),
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn remove_chunk_reference(
+        &self,
+        index_table: &str,
+        hash: &str,
+        content_id: &str,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::RemoveChunkReference {
+                index_table: index_table.to_string(),
+                hash: hash.to_string(),
+                content_id: content_id.to_string(),
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn get_chunk_reference(
+        &self,
+        index_table: &str,
+        hash: &str,
+    ) -> Result<Option<internal_api::ChunkReference>> {
+        self.state_machine
+            .get_from_cf::<internal_api::ChunkReference, _>(
+                StateMachineColumns::ChunkReferences,
+                internal_api::ChunkReference::key(index_table, hash),
+            )
+            .await
+    }
+
+    /// Bytes of the content stored in the namespace
+    pub async fn namespace_stored_bytes(&self, namespace: &str) -> Result<u64> {
+        let stored_bytes = self
+            .state_machine
+            .get_from_cf::<u64, _>(StateMachineColumns::NamespaceStorage, namespace)
+            .await?;
+        Ok(stored_bytes.unwrap_or(0))
+    }
+
+    pub async fn set_namespace_quotas(
+        &self,
+        namespace: &str,
+        quotas: internal_api::NamespaceQuotas,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetNamespaceQuotas {
+                namespace: namespace.to_string(),
+                quotas,
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    /// Quotas of the namespace, a namespace without quotas isn't limited
+    pub async fn get_namespace_quotas(
+        &self,
+        namespace: &str,
+    ) -> Result<internal_api::NamespaceQuotas> {
+        let quotas = self
+            .state_machine
+            .get_from_cf::<internal_api::NamespaceQuotas, _>(
+                StateMachineColumns::NamespaceQuotas,
+                namespace,
+            )
+            .await?;
+        Ok(quotas.unwrap_or_default())
+    }
+
+    /// Counters of the namespace kept up to date by the state machine
+    pub async fn get_namespace_stats(
+        &self,
+        namespace: &str,
+    ) -> Result<internal_api::NamespaceStats> {
+        let stats = self
+            .state_machine
+            .get_from_cf::<internal_api::NamespaceStats, _>(
+                StateMachineColumns::NamespaceStats,
+                namespace,
+            )
+            .await?;
+        Ok(stats.unwrap_or_default())
+    }
+
+    pub async fn namespace_extraction_policy_count(&self, namespace: &str) -> u64 {
+        self.state_machine
+            .get_namespace_extraction_policy_count(&namespace.to_string())
+            .await as u64
+    }
+
+    pub async fn list_state_changes(&self) -> Result<Vec<StateChange>> {
+        let state_changes = self
+            .state_machine
+            .get_all_rows_from_cf::<StateChange>(StateMachineColumns::StateChanges)
+            .await?
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect();
+        Ok(state_changes)
+    }
+
+    pub async fn get_structured_data_schema(
+        &self,
+        namespace: &str,
+        content_source: &str,
+    ) -> Result<StructuredDataSchema> {
+        let id = StructuredDataSchema::schema_id(namespace, content_source);
+        let schema = self
+            .state_machine
+            .get_from_cf::<StructuredDataSchema, _>(StateMachineColumns::StructuredDataSchemas, &id)
+            .await?
+            .ok_or_else(|| anyhow!("Schema with id {} not found", id))?;
+        Ok(schema)
+    }
+
+    pub async fn evolve_structured_data_schemas(
+        &self,
+        schemas: Vec<StructuredDataSchema>,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::EvolveStructuredDataSchemas { schemas },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn get_schemas_for_namespace(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<StructuredDataSchema>> {
+        let schemas_for_ns = self
+            .state_machine
+            .get_schemas_by_namespace()
+            .await
+            .get(namespace)
+            .cloned()
+            .unwrap_or(HashSet::new());
+        let schemas = self.state_machine.get_schemas(schemas_for_ns).await?;
+        Ok(schemas)
+    }
+
+    pub async fn get_unfinished_tasks_by_extractor(
+        &self,
+    ) -> HashMap<store::ExtractorName, HashSet<TaskId>> {
+        self.state_machine.get_unfinished_tasks_by_extractor().await
+    }
+
+    pub async fn insert_executor_running_task_count(&mut self, executor_id: &str, task_count: u64) {
+        self.state_machine
+            .insert_executor_running_task_count(executor_id, task_count)
+            .await;
+    }
+
+    pub fn start_periodic_membership_check(self: &Arc<Self>, mut shutdown_rx: Receiver<()>) {
+        let app_clone = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(MEMBERSHIP_CHECK_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.changed() => {
+                        info!("shutting down periodic membership check");
+                        break;
+                    }
+                    _ = interval.tick() => {
+                        if app_clone.is_seed_node() {
                             continue;
                         }
                         if let Err(e) = app_clone.check_cluster_membership().await {
@@ -1280,6 +2156,65 @@ impl App {
     pub async fn get_coordinator_addr(&self, node_id: NodeId) -> Result<Option<String>> {
         self.state_machine.get_coordinator_addr(node_id).await
     }
+
+    /// Lists the voters and learners of the cluster as this node knows them
+    pub async fn cluster_members(&self) -> Result<Vec<internal_api::ClusterMember>> {
+        let metrics = self.forwardable_raft.raft.metrics().borrow().clone();
+        let membership = metrics.membership_config.membership();
+        let voters = membership.voter_ids().collect::<HashSet<_>>();
+        let mut members = Vec::new();
+        for (node_id, node) in membership.nodes() {
+            members.push(internal_api::ClusterMember {
+                node_id: *node_id,
+                address: node.addr.clone(),
+                coordinator_addr: self
+                    .get_coordinator_addr(*node_id)
+                    .await?
+                    .unwrap_or_default(),
+                voter: voters.contains(node_id),
+                leader: metrics.current_leader == Some(*node_id),
+            });
+        }
+        Ok(members)
+    }
+
+    /// Adds a node to the cluster as a learner, which replicates the log but
+    /// doesn't vote until it's promoted
+    pub async fn add_learner(
+        &self,
+        node_id: NodeId,
+        address: &str,
+        coordinator_addr: &str,
+    ) -> Result<()> {
+        self.change_membership(RequestPayload::AddLearner {
+            node_id,
+            address: address.to_string(),
+            coordinator_addr: coordinator_addr.to_string(),
+        })
+        .await
+    }
+
+    pub async fn promote_learner(&self, node_id: NodeId) -> Result<()> {
+        self.change_membership(RequestPayload::PromoteLearner { node_id })
+            .await
+    }
+
+    /// Removes a voter or a learner from the cluster
+    pub async fn remove_node(&self, node_id: NodeId) -> Result<()> {
+        self.change_membership(RequestPayload::RemoveNode { node_id })
+            .await
+    }
+
+    async fn change_membership(&self, payload: RequestPayload) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload,
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        self.forwardable_raft.change_membership(req).await?;
+        Ok(())
+    }
 }
 
 async fn watch_for_leader_change(
@@ -1351,6 +2286,7 @@ mod tests {
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         let read_back = |node: Arc<App>| async move {
             match node.get_index("id").await {
@@ -1377,6 +2313,7 @@ mod tests {
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
 
         let read_back = |node: Arc<App>| async move {
@@ -1390,6 +2327,79 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_replication_metrics() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(3, None).await?;
+        cluster.initialize(Duration::from_secs(2)).await?;
+        let leader = cluster.get_raft_node(cluster.seed_node_id)?;
+        let follower = cluster.get_raft_node((cluster.seed_node_id + 1) % 3)?;
+        leader.create_namespace("namespace").await?;
+        let last_log_index = leader.get_raft_metrics().openraft_metrics.last_log_index;
+        //  The metrics are updated after the write is acknowledged
+        leader
+            .forwardable_raft
+            .raft
+            .wait(Some(Duration::from_secs(2)))
+            .applied_index_at_least(last_log_index, "namespace applied")
+            .await?;
+
+        let metrics = leader.get_raft_metrics();
+        let last_log_index = last_log_index.unwrap();
+        assert_eq!(metrics.committed_index(), Some(last_log_index));
+        assert_eq!(metrics.last_applied_index(), last_log_index);
+        assert_eq!(metrics.pending_log_entries(), 0);
+        let replication_lag = metrics.replication_lag();
+        assert_eq!(replication_lag.len(), 2);
+        assert!(!replication_lag.contains_key(&leader.id));
+
+        let metrics = follower.get_raft_metrics();
+        assert_eq!(metrics.committed_index(), None);
+        assert!(metrics.replication_lag().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_follower_read_index() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(3, None).await?;
+        cluster.initialize(Duration::from_secs(2)).await?;
+        let leader = cluster.get_raft_node(cluster.seed_node_id)?;
+        let follower = cluster.get_raft_node((cluster.seed_node_id + 1) % 3)?;
+        leader.create_namespace("namespace").await?;
+
+        //  The write is visible on the follower without polling once it applied
+        // the log up to the read index
+        follower.wait_for_read_index(Duration::ZERO).await?;
+        assert!(follower.namespace("namespace").await?.is_some());
+        assert_eq!(
+            follower.forwardable_raft.read_index().await?,
+            leader.forwardable_raft.read_index().await?
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_linearizable_read_on_follower() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(3, None).await?;
+        cluster.initialize(Duration::from_secs(2)).await?;
+        let leader = cluster.get_raft_node(cluster.seed_node_id)?;
+        let follower = cluster.get_raft_node((cluster.seed_node_id + 1) % 3)?;
+        let index = Index {
+            name: "name".into(),
+            namespace: "namespace".into(),
+            ..Default::default()
+        };
+        leader
+            .create_index("namespace", index.clone(), "id".into())
+            .await?;
+
+        follower.ensure_linearizable_read().await?;
+        assert_eq!(follower.get_index("id").await?, index);
+        Ok(())
+    }
+
     /// Test to determine that an index that was created can be read back
     #[tokio::test]
     #[tracing_test::traced_test]
@@ -1427,6 +2437,7 @@ mod tests {
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
 
         let read_back = {
@@ -1460,6 +2471,7 @@ mod tests {
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
 
         let read_back = {
@@ -1482,6 +2494,7 @@ mod tests {
             payload: RequestPayload::AssignTask { assignments },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
 
         let read_back = |node: Arc<App>| async move {
@@ -1529,7 +2542,7 @@ mod tests {
             ..Default::default()
         };
         let addr = "addr";
-        node.register_executor(addr, executor_id, extractor.clone())
+        node.register_executor(addr, executor_id, extractor.clone(), HashMap::new())
             .await?;
 
         //  Set an extraction policy for the content that will force task creation
@@ -1567,6 +2580,7 @@ mod tests {
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         let read_back = {
             move |node: Arc<App>| async move {
@@ -1588,6 +2602,7 @@ mod tests {
             payload: RequestPayload::AssignTask { assignments },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         };
         let read_back = |node: Arc<App>| async move {
             match node.tasks_for_executor("executor_id", None).await {
@@ -1616,7 +2631,7 @@ mod tests {
                 .take(3)
                 .collect();
         let node = cluster.get_raft_node(0)?;
-        node.update_task(task, Some(executor_id.into()), content_meta_list)
+        node.update_task(task, Some(executor_id.into()), content_meta_list, None)
             .await?;
 
         //  Read the task back and expect to find the outcome of the task set to Success
@@ -1643,7 +2658,7 @@ mod tests {
             ..Default::default()
         };
         let addr = "addr";
-        node.register_executor(addr, executor_id, extractor.clone())
+        node.register_executor(addr, executor_id, extractor.clone(), HashMap::new())
             .await?;
 
         //  Read the executors from multiple functions
@@ -1719,6 +2734,104 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_batched_content_creation() -> Result<(), anyhow::Error> {
+        let content_count = 100;
+
+        let cluster = RaftTestCluster::new(1, None).await?;
+        cluster.initialize(Duration::from_secs(2)).await?;
+        let node = cluster.get_raft_node(0)?;
+        let last_log_index = |node: &App| {
+            node.get_raft_metrics()
+                .openraft_metrics
+                .last_log_index
+                .unwrap_or_default()
+        };
+        let log_index_before = last_log_index(&node);
+
+        //  Content created concurrently is committed in fewer log entries
+        futures::future::try_join_all((0..content_count).map(|i| {
+            node.create_content_batch(vec![indexify_internal_api::ContentMetadata {
+                id: format!("id{}", i),
+                ..Default::default()
+            }])
+        }))
+        .await?;
+        assert!(last_log_index(&node) - log_index_before < content_count);
+
+        let read_content = node.list_content("").await?;
+        assert_eq!(read_content.len(), content_count as usize);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_content_preview_from_extracted_content() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(1, None).await?;
+        cluster.initialize(Duration::from_secs(2)).await?;
+        let node = cluster.get_raft_node(0)?;
+
+        let parent = indexify_internal_api::ContentMetadata {
+            id: "parent".to_string(),
+            parent_id: "".to_string(),
+            content_type: "application/pdf".to_string(),
+            ..Default::default()
+        };
+        node.create_content_batch(vec![parent]).await?;
+
+        //  The first extracted text becomes the preview of the parent
+        let chunk = |id: &str, preview: &str| indexify_internal_api::ContentMetadata {
+            id: id.to_string(),
+            parent_id: "parent".to_string(),
+            content_type: "text/plain".to_string(),
+            preview: preview.to_string(),
+            ..Default::default()
+        };
+        node.create_content_batch(vec![chunk("chunk1", "first chunk")])
+            .await?;
+        node.create_content_batch(vec![chunk("chunk2", "second chunk")])
+            .await?;
+        let parent = node.get_conent_metadata("parent").await?;
+        assert_eq!(parent.preview, "first chunk");
+        let chunk = node.get_conent_metadata("chunk2").await?;
+        assert_eq!(chunk.preview, "second chunk");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_namespace_stored_bytes() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(1, None).await?;
+        cluster.initialize(Duration::from_secs(2)).await?;
+        let node = cluster.get_raft_node(0)?;
+
+        let content =
+            |id: &str, namespace: &str, size_bytes: u64| indexify_internal_api::ContentMetadata {
+                id: id.to_string(),
+                namespace: namespace.to_string(),
+                size_bytes,
+                ..Default::default()
+            };
+        node.create_content_batch(vec![
+            content("content1", "namespace1", 100),
+            content("content2", "namespace1", 50),
+            content("content3", "namespace2", 10),
+        ])
+        .await?;
+        assert_eq!(node.namespace_stored_bytes("namespace1").await?, 150);
+        assert_eq!(node.namespace_stored_bytes("namespace2").await?, 10);
+        assert_eq!(node.namespace_stored_bytes("namespace3").await?, 0);
+
+        //  Content written again replaces its bytes
+        node.create_content_batch(vec![content("content1", "namespace1", 30)])
+            .await?;
+        assert_eq!(node.namespace_stored_bytes("namespace1").await?, 80);
+
+        Ok(())
+    }
+
     #[tokio::test]
     #[tracing_test::traced_test]
     async fn test_create_read_and_match_extraction_policies() -> Result<(), anyhow::Error> {
@@ -1751,7 +2864,7 @@ mod tests {
             ..Default::default()
         };
         let addr = "addr";
-        node.register_executor(addr, executor_id, extractor.clone())
+        node.register_executor(addr, executor_id, extractor.clone(), HashMap::new())
             .await?;
 
         //  Create the extraction policy under the namespace of the content
@@ -1798,6 +2911,76 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_content_filter_of_extraction_policy() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(1, None).await?;
+        cluster.initialize(Duration::from_secs(2)).await?;
+        let node = cluster.get_raft_node(0)?;
+
+        let content = |id: &str, content_type: &str, pages: &str, size_bytes: u64| {
+            indexify_internal_api::ContentMetadata {
+                id: id.into(),
+                namespace: "namespace".into(),
+                labels: HashMap::from([("pages".to_string(), pages.to_string())]),
+                content_type: content_type.into(),
+                source: "source".into(),
+                size_bytes,
+                ..Default::default()
+            }
+        };
+        let content_list = vec![
+            content("pdf", "application/pdf", "12", 100),
+            content("png", "image/png", "1", 100),
+            content("long_pdf", "application/pdf", "300", 100),
+            content("large_pdf", "application/pdf; version=1.7", "12", 10_000),
+        ];
+        node.create_content_batch(content_list.clone()).await?;
+
+        let extractor = indexify_internal_api::ExtractorDescription {
+            name: "extractor".into(),
+            input_mime_types: vec!["*/*".into()],
+            ..Default::default()
+        };
+        node.register_executor("addr", "executor_id", extractor.clone(), HashMap::new())
+            .await?;
+        let extraction_policy = indexify_internal_api::ExtractionPolicy {
+            id: "policy".into(),
+            namespace: "namespace".into(),
+            content_source: "source".into(),
+            extractor: extractor.name,
+            content_filter: indexify_internal_api::ContentFilter {
+                mime_types: vec!["application/*".into()],
+                label_ranges: vec![indexify_internal_api::LabelRange {
+                    label: "pages".into(),
+                    min: None,
+                    max: Some(100.0),
+                }],
+                min_size_bytes: None,
+                max_size_bytes: Some(1000),
+            },
+            ..Default::default()
+        };
+        node.create_extraction_policy(extraction_policy.clone(), None)
+            .await?;
+
+        let matched_content = node.content_matching_policy(&extraction_policy.id).await?;
+        assert_eq!(
+            matched_content
+                .iter()
+                .map(|content| content.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["pdf"]
+        );
+        for content in &content_list {
+            let matched_policies = node
+                .filter_extraction_policy_for_content(&content.id)
+                .await?;
+            assert_eq!(matched_policies.len(), usize::from(content.id == "pdf"));
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     #[tracing_test::traced_test]
     async fn test_create_and_read_namespaces() -> Result<(), anyhow::Error> {
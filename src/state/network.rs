@@ -1,6 +1,7 @@
 use std::{error::Error, fmt::Display, sync::Arc};
 
 use anyerror::AnyError;
+use indexify_proto::indexify_raft::RaftRequest;
 use openraft::{
     error::{NetworkError, RemoteError, Unreachable},
     network::{RaftNetwork, RaftNetworkFactory},
@@ -16,7 +17,11 @@ use openraft::{
 };
 use tonic::IntoRequest;
 
-use super::store::requests::StateMachineUpdateResponse;
+use super::store::{
+    requests::{ReadIndexResponse, StateMachineUpdateResponse},
+    NamespaceQuotaExceeded,
+    RequestIdConflict,
+};
 use crate::{
     grpc_helper::GrpcHelper,
     metrics::{
@@ -25,6 +30,7 @@ use crate::{
         CounterGuard,
     },
     state::{
+        forwardable_raft::NotLeaderError,
         raft_client::RaftClient,
         store::requests::{RequestPayload, StateMachineUpdateRequest},
         typ::{InstallSnapshotError, RPCError, RaftError},
@@ -68,17 +74,37 @@ impl Network {
             .raft_client
             .get(target_addr)
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to get raft client: {}", e))?;
+            .map_err(|e| NotLeaderError(format!("Failed to get raft client: {}", e)))?;
 
         let tonic_request = GrpcHelper::encode_raft_request(&request)?.into_request();
 
         let bytes_sent = tonic_request.get_ref().data.len() as u64;
         raft_metrics::network::incr_sent_bytes(target_addr, bytes_sent);
 
+        //  The leader stepped down or went away before the request reached it
         let response = client
             .forward(tonic_request)
             .await
-            .map_err(|e| GrpcHelper::internal_err(e.to_string()))?;
+            .map_err(|e| match e.code() {
+                tonic::Code::Unavailable => {
+                    anyhow::Error::new(NotLeaderError(e.message().to_string()))
+                }
+                //  The leader rejected the request for going over a namespace quota
+                tonic::Code::ResourceExhausted => {
+                    match serde_json::from_str::<NamespaceQuotaExceeded>(e.message()) {
+                        Ok(quota_exceeded) => quota_exceeded.into(),
+                        Err(_) => GrpcHelper::internal_err(e.to_string()).into(),
+                    }
+                }
+                //  The leader rejected the request for reusing the id of another request
+                tonic::Code::AlreadyExists => {
+                    match serde_json::from_str::<RequestIdConflict>(e.message()) {
+                        Ok(conflict) => conflict.into(),
+                        Err(_) => GrpcHelper::internal_err(e.to_string()).into(),
+                    }
+                }
+                _ => GrpcHelper::internal_err(e.to_string()).into(),
+            })?;
 
         let result: Result<StateMachineUpdateResponse, _> =
             serde_json::from_str(&response.into_inner().data);
@@ -116,6 +142,7 @@ impl Network {
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
+            request_ids: vec![],
         })?
         .into_request();
 
@@ -138,6 +165,37 @@ impl Network {
 
         Ok(reply)
     }
+
+    /// Asks the leader for the index of the log a follower has to apply
+    /// before its state reflects every write committed so far
+    pub async fn read_index(&self, target_addr: &str) -> Result<Option<u64>, anyhow::Error> {
+        let mut client = self
+            .raft_client
+            .get(target_addr)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get raft client: {}", e))?;
+
+        let response = client
+            .read_index(
+                RaftRequest {
+                    data: String::new(),
+                }
+                .into_request(),
+            )
+            .await
+            .map_err(|e| GrpcHelper::internal_err(e.to_string()))?;
+
+        let reply = serde_json::from_str::<ReadIndexResponse>(&response.into_inner().data)
+            .map_err(|e| {
+                raft_metrics::network::incr_sent_failures(target_addr);
+                anyhow::anyhow!(
+                    "Failed to parse the response received from sending a read_index request: {}",
+                    e.to_string()
+                )
+            })?;
+
+        Ok(reply.read_index)
+    }
 }
 
 impl RaftNetworkFactory<TypeConfig> for Network {
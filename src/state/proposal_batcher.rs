@@ -0,0 +1,194 @@
+use tokio::sync::{mpsc, oneshot};
+use tracing::error;
+
+use super::{store::requests::RequestPayload, Raft, StateMachineUpdateRequest};
+
+/// Number of batchable requests waiting to be proposed before callers wait
+/// for room in the queue
+const PROPOSAL_QUEUE_SIZE: usize = 4096;
+
+struct PendingProposal {
+    request: StateMachineUpdateRequest,
+    result_tx: oneshot::Sender<anyhow::Result<()>>,
+}
+
+/// Proposes the requests written on the leader to raft, coalescing requests
+/// which can be applied together into a single log entry. Requests queued
+/// while the previous batch is being committed are proposed as the next
+/// batch, so writes aren't delayed when the leader isn't busy. A batch which
+/// is rejected for going over a namespace quota, or which holds a request
+/// applied already or reusing the id of another request, is proposed again
+/// request by request, so only the requests over the quota or reusing an id
+/// are rejected and only the retried requests are skipped.
+#[derive(Clone)]
+pub struct ProposalBatcher {
+    raft: Raft,
+    proposal_tx: Option<mpsc::Sender<PendingProposal>>,
+}
+
+impl ProposalBatcher {
+    /// Every request is proposed on its own if `max_batch_size` is at most 1
+    pub fn new(raft: Raft, max_batch_size: usize) -> Self {
+        if max_batch_size <= 1 {
+            return Self {
+                raft,
+                proposal_tx: None,
+            };
+        }
+        let (proposal_tx, proposal_rx) = mpsc::channel(PROPOSAL_QUEUE_SIZE);
+        tokio::spawn(propose_batches(raft.clone(), proposal_rx, max_batch_size));
+        Self {
+            raft,
+            proposal_tx: Some(proposal_tx),
+        }
+    }
+
+    /// Proposes the request and waits until it's committed and applied
+    pub async fn client_write(&self, request: StateMachineUpdateRequest) -> anyhow::Result<()> {
+        let proposal_tx = match &self.proposal_tx {
+            Some(proposal_tx) if is_batchable(&request.payload) => proposal_tx,
+            _ => return propose(&self.raft, request).await,
+        };
+        let (result_tx, result_rx) = oneshot::channel();
+        proposal_tx
+            .send(PendingProposal { request, result_tx })
+            .await
+            .map_err(|_| anyhow::anyhow!("proposal batcher stopped"))?;
+        result_rx
+            .await
+            .map_err(|_| anyhow::anyhow!("proposal batcher stopped"))?
+    }
+}
+
+/// Proposes the request and waits until it's applied, failing with
+/// `NamespaceQuotaExceeded` or `RequestIdConflict` if the state machine
+/// rejected it. A retried request which was applied already succeeds without
+/// being applied again.
+async fn propose(raft: &Raft, request: StateMachineUpdateRequest) -> anyhow::Result<()> {
+    let response = raft.client_write(request).await?;
+    if let Some(e) = response.data.quota_exceeded {
+        return Err(e.into());
+    }
+    match response.data.request_id_conflict {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+//  Only requests whose payloads are concatenated without changing the result
+// of applying them are batched
+fn is_batchable(payload: &RequestPayload) -> bool {
+    matches!(payload, RequestPayload::CreateContent { .. })
+}
+
+/// Merges batchable requests into a single request, keeping the order of the
+/// content, of the state changes and of the request ids
+fn merge_requests(requests: Vec<StateMachineUpdateRequest>) -> StateMachineUpdateRequest {
+    let mut merged_content = Vec::new();
+    let mut new_state_changes = Vec::new();
+    let mut state_changes_processed = Vec::new();
+    let mut request_ids = Vec::new();
+    for request in requests {
+        if let RequestPayload::CreateContent { content_metadata } = request.payload {
+            merged_content.extend(content_metadata);
+        }
+        new_state_changes.extend(request.new_state_changes);
+        state_changes_processed.extend(request.state_changes_processed);
+        request_ids.extend(request.request_ids);
+    }
+    StateMachineUpdateRequest {
+        payload: RequestPayload::CreateContent {
+            content_metadata: merged_content,
+        },
+        new_state_changes,
+        state_changes_processed,
+        request_ids,
+    }
+}
+
+async fn propose_batches(
+    raft: Raft,
+    mut proposal_rx: mpsc::Receiver<PendingProposal>,
+    max_batch_size: usize,
+) {
+    while let Some(proposal) = proposal_rx.recv().await {
+        let mut batch = vec![proposal];
+        while batch.len() < max_batch_size {
+            match proposal_rx.try_recv() {
+                Ok(proposal) => batch.push(proposal),
+                Err(_) => break,
+            }
+        }
+        let (requests, result_txs): (Vec<_>, Vec<_>) = batch
+            .into_iter()
+            .map(|proposal| (proposal.request, proposal.result_tx))
+            .unzip();
+        if requests.len() > 1 {
+            let merged_request = merge_requests(requests.clone());
+            match raft.client_write(merged_request).await {
+                Ok(response)
+                    if response.data.quota_exceeded.is_some() ||
+                        response.data.duplicate_request ||
+                        response.data.request_id_conflict.is_some() => {}
+                Ok(_) => {
+                    for result_tx in result_txs {
+                        let _ = result_tx.send(Ok(()));
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    error!("unable to propose batch of requests: {}", e);
+                    for result_tx in result_txs {
+                        let _ = result_tx.send(Err(anyhow::anyhow!(e.to_string())));
+                    }
+                    continue;
+                }
+            }
+        }
+        for (request, result_tx) in requests.into_iter().zip(result_txs) {
+            let _ = result_tx.send(propose(&raft, request).await);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexify_internal_api::{ChangeType, ContentMetadata, StateChange};
+
+    use super::*;
+
+    #[test]
+    fn test_merge_requests() {
+        let request = |id: &str| StateMachineUpdateRequest {
+            payload: RequestPayload::CreateContent {
+                content_metadata: vec![ContentMetadata {
+                    id: id.to_string(),
+                    ..Default::default()
+                }],
+            },
+            new_state_changes: vec![StateChange::new(id.to_string(), ChangeType::NewContent, 0)],
+            state_changes_processed: vec![],
+            request_ids: vec![],
+        };
+        assert!(is_batchable(&request("a").payload));
+        let merged = merge_requests(vec![request("a"), request("b")]);
+        let RequestPayload::CreateContent { content_metadata } = merged.payload else {
+            panic!("unexpected payload");
+        };
+        assert_eq!(
+            content_metadata
+                .iter()
+                .map(|content| content.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(
+            merged
+                .new_state_changes
+                .iter()
+                .map(|change| change.object_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+    }
+}
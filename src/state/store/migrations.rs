@@ -0,0 +1,244 @@
+use anyhow::{anyhow, Result};
+use indexify_internal_api as internal_api;
+use rocksdb::OptimisticTransactionDB;
+use strum::IntoEnumIterator;
+use tracing::info;
+
+use super::{
+    serializer::{JsonEncode, JsonEncoder},
+    StateMachineColumns,
+    TaskId,
+};
+
+/// Version of the schema of the raft log and of the state machine columns,
+/// bumped with a migration whenever a release changes how `RequestPayload` or
+/// the structs stored by the state machine are encoded
+pub const SCHEMA_VERSION: u64 = 2;
+
+/// Version of databases written before the schema was versioned
+pub const UNVERSIONED_SCHEMA: u64 = 1;
+
+/// Key of the schema version in the column family of the raft log store
+const SCHEMA_VERSION_KEY: &[u8] = b"schema_version";
+
+/// Rewrites the database from one schema version to the next. Migrations are
+/// run again if a node stops before the new version is written, so they have
+/// to leave a database they migrated already unchanged.
+pub type Migration = fn(&OptimisticTransactionDB) -> Result<()>;
+
+/// Migrations by the version they migrate from, the first one migrates
+/// version 1 to version 2
+const MIGRATIONS: [Migration; (SCHEMA_VERSION - 1) as usize] = [queue_assigned_tasks];
+
+/// Migrates the database to the current schema version when a node starts.
+/// A database written by a later release, whose schema this release can't
+/// read, isn't opened.
+pub fn migrate(db: &OptimisticTransactionDB) -> Result<()> {
+    migrate_to(db, SCHEMA_VERSION, &MIGRATIONS)
+}
+
+/// Migrates a database holding data of the schema `version` to the current
+/// version, used when the data is restored from a backup
+pub fn migrate_from(db: &OptimisticTransactionDB, version: u64) -> Result<()> {
+    check_schema_version(version)?;
+    write_schema_version(db, version)?;
+    migrate(db)
+}
+
+fn migrate_to(
+    db: &OptimisticTransactionDB,
+    target_version: u64,
+    migrations: &[Migration],
+) -> Result<()> {
+    let mut version = match read_schema_version(db)? {
+        Some(version) => version,
+        None if is_empty(db)? => target_version,
+        None => UNVERSIONED_SCHEMA,
+    };
+    if version > target_version {
+        return Err(schema_version_error(version, target_version));
+    }
+    while version < target_version {
+        info!(
+            "migrating the state machine from schema version {} to {}",
+            version,
+            version + 1
+        );
+        let migration = migrations
+            .get((version - 1) as usize)
+            .ok_or_else(|| anyhow!("no migration from schema version {}", version))?;
+        migration(db)?;
+        version += 1;
+        //  Written after every migration, so a node stopped midway continues
+        // from the first migration which wasn't done
+        write_schema_version(db, version)?;
+    }
+    write_schema_version(db, version)
+}
+
+/// Fails if data of the schema `version` can't be read by this release
+pub fn check_schema_version(version: u64) -> Result<()> {
+    if version > SCHEMA_VERSION {
+        return Err(schema_version_error(version, SCHEMA_VERSION));
+    }
+    Ok(())
+}
+
+fn schema_version_error(version: u64, target_version: u64) -> anyhow::Error {
+    anyhow!(
+        "the data has schema version {}, this release only reads up to version {}",
+        version,
+        target_version
+    )
+}
+
+pub fn read_schema_version(db: &OptimisticTransactionDB) -> Result<Option<u64>> {
+    let cf = store_cf(db)?;
+    let Some(value) = db.get_cf(cf, SCHEMA_VERSION_KEY)? else {
+        return Ok(None);
+    };
+    let bytes: [u8; 8] = value
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow!("the schema version of the database is corrupted"))?;
+    Ok(Some(u64::from_be_bytes(bytes)))
+}
+
+fn write_schema_version(db: &OptimisticTransactionDB, version: u64) -> Result<()> {
+    db.put_cf(store_cf(db)?, SCHEMA_VERSION_KEY, version.to_be_bytes())?;
+    Ok(())
+}
+
+fn store_cf(db: &OptimisticTransactionDB) -> Result<&rocksdb::ColumnFamily> {
+    db.cf_handle("store")
+        .ok_or_else(|| anyhow!("failed to get column family handle for store"))
+}
+
+fn column_cf(
+    db: &OptimisticTransactionDB,
+    column: StateMachineColumns,
+) -> Result<&rocksdb::ColumnFamily> {
+    db.cf_handle(column.as_ref())
+        .ok_or_else(|| anyhow!("failed to get column family handle for {}", column))
+}
+
+//  Version 2 queues the tasks assigned to an executor by their priority, the
+// queues of tasks assigned before are built from the task assignments
+fn queue_assigned_tasks(db: &OptimisticTransactionDB) -> Result<()> {
+    let assignments_cf = column_cf(db, StateMachineColumns::TaskAssignments)?;
+    let tasks_cf = column_cf(db, StateMachineColumns::Tasks)?;
+    let queue_cf = column_cf(db, StateMachineColumns::ExecutorTaskQueue)?;
+    for item in db.iterator_cf(assignments_cf, rocksdb::IteratorMode::Start) {
+        let (executor_id, task_ids) = item?;
+        let executor_id = String::from_utf8(executor_id.to_vec())?;
+        let task_ids: Vec<TaskId> = JsonEncoder::decode(&task_ids)?;
+        for task_id in task_ids {
+            let Some(task) = db.get_cf(tasks_cf, &task_id)? else {
+                continue;
+            };
+            let task: internal_api::Task = JsonEncoder::decode(&task)?;
+            db.put_cf(queue_cf, task.queue_key(&executor_id), task.id.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+//  A database is new if neither the raft log store nor any state machine
+// column holds data
+fn is_empty(db: &OptimisticTransactionDB) -> Result<bool> {
+    let columns = ["store".to_string(), "logs".to_string()]
+        .into_iter()
+        .chain(StateMachineColumns::iter().map(|column| column.to_string()));
+    for column in columns {
+        let cf = db
+            .cf_handle(&column)
+            .ok_or_else(|| anyhow!("failed to get column family handle for {}", column))?;
+        if db
+            .iterator_cf(cf, rocksdb::IteratorMode::Start)
+            .next()
+            .is_some()
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::store::new_storage;
+
+    fn migrate_executors(db: &OptimisticTransactionDB) -> Result<()> {
+        let cf = db
+            .cf_handle(StateMachineColumns::Executors.as_ref())
+            .unwrap();
+        db.put_cf(cf, b"migrated", b"true")?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_migrate_schema() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (_, state_machine) =
+            new_storage(dir.path().join("db"), dir.path().join("sm-blob"), true, 0).await;
+        let db = &state_machine.db;
+        //  A new database starts at the current version
+        assert_eq!(read_schema_version(db)?, Some(SCHEMA_VERSION));
+
+        let migrations = [&MIGRATIONS[..], &[migrate_executors as Migration]].concat();
+        migrate_to(db, SCHEMA_VERSION + 1, &migrations)?;
+        assert_eq!(read_schema_version(db)?, Some(SCHEMA_VERSION + 1));
+        let cf = StateMachineColumns::Executors.cf(db);
+        assert!(db.get_cf(cf, b"migrated")?.is_some());
+
+        //  The database can't be opened by an earlier release
+        assert!(migrate(db).is_err());
+
+        //  Databases written before the schema was versioned are migrated from
+        // the first version
+        db.delete_cf(store_cf(db)?, SCHEMA_VERSION_KEY)?;
+        db.delete_cf(cf, b"migrated")?;
+        db.put_cf(cf, b"executor", b"{}")?;
+        migrate_to(db, UNVERSIONED_SCHEMA + 1, &[migrate_executors])?;
+        assert!(db.get_cf(cf, b"migrated")?.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_queue_assigned_tasks() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (_, state_machine) =
+            new_storage(dir.path().join("db"), dir.path().join("sm-blob"), true, 0).await;
+        let db = &state_machine.db;
+        let task = internal_api::Task {
+            id: "task1".to_string(),
+            priority: 10,
+            ..Default::default()
+        };
+        db.put_cf(
+            StateMachineColumns::Tasks.cf(db),
+            &task.id,
+            JsonEncoder::encode(&task)?,
+        )?;
+        db.put_cf(
+            StateMachineColumns::TaskAssignments.cf(db),
+            "executor1",
+            JsonEncoder::encode(&vec![task.id.clone()])?,
+        )?;
+
+        //  Running the migration again leaves the queue unchanged
+        queue_assigned_tasks(db)?;
+        queue_assigned_tasks(db)?;
+        let queue = db
+            .iterator_cf(
+                StateMachineColumns::ExecutorTaskQueue.cf(db),
+                rocksdb::IteratorMode::Start,
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(queue.len(), 1);
+        assert_eq!(&*queue[0].0, task.queue_key("executor1").as_bytes());
+        assert_eq!(&*queue[0].1, b"task1");
+        Ok(())
+    }
+}
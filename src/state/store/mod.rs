@@ -30,7 +30,15 @@ use openraft::{
     StoredMembership,
     Vote,
 };
-use rocksdb::{ColumnFamily, ColumnFamilyDescriptor, Direction, OptimisticTransactionDB, Options};
+use rocksdb::{
+    backup::{BackupEngine, BackupEngineOptions, RestoreOptions},
+    ColumnFamily,
+    ColumnFamilyDescriptor,
+    Direction,
+    Env,
+    OptimisticTransactionDB,
+    Options,
+};
 use serde::{de::DeserializeOwned, Deserialize};
 use strum::{AsRefStr, IntoEnumIterator};
 use thiserror::Error;
@@ -40,7 +48,7 @@ use tracing::debug;
 type Node = BasicNode;
 
 use self::{
-    requests::RequestPayload,
+    requests::{RequestPayload, StateMachineUpdateRequest},
     serializer::{JsonEncode, JsonEncoder},
     state_machine_objects::{IndexifyState, IndexifyStateSnapshot},
 };
@@ -59,6 +67,16 @@ pub type ExtractorName = String;
 pub type ContentType = String;
 pub type SchemaId = String;
 
+/// Number of state changes a watcher can fall behind by before it misses
+/// changes
+const STATE_CHANGES_CHANNEL_SIZE: usize = 1024;
+
+/// Directory of the RocksDB backup directory holding the snapshot of the state
+/// machine taken with each backup, by backup id
+const BACKUP_SNAPSHOTS_DIR: &str = "snapshots";
+
+pub mod migrations;
+pub mod replay;
 pub mod requests;
 pub mod serializer;
 pub mod state_machine_objects;
@@ -73,6 +91,41 @@ pub enum StateMachineError {
 
     #[error("RocksDB transaction error: {0}")]
     TransactionError(String),
+
+    //  The request is rejected without changing the state
+    #[error(transparent)]
+    QuotaExceeded(#[from] NamespaceQuotaExceeded),
+
+    //  The request is a retry of a request which was applied already, it's
+    // skipped without changing the state
+    #[error("request {0} was applied already")]
+    DuplicateRequest(String),
+
+    //  The request reuses the id or the operation token of a different request
+    // which was applied, it's rejected without changing the state
+    #[error(transparent)]
+    RequestIdConflict(#[from] RequestIdConflict),
+}
+
+/// A request was rejected because applying it would take a namespace over one
+/// of its quotas
+#[derive(Error, Debug, Clone, PartialEq, serde::Serialize, Deserialize)]
+#[error(
+    "namespace {namespace} is limited to {limit} {quota}, the request would take it to {requested}"
+)]
+pub struct NamespaceQuotaExceeded {
+    pub namespace: String,
+    pub quota: String,
+    pub limit: u64,
+    pub requested: u64,
+}
+
+/// A request was rejected because its request id, or its operation token, was
+/// applied already for a different request
+#[derive(Error, Debug, Clone, PartialEq, serde::Serialize, Deserialize)]
+#[error("request id {request_id} was used for a different request")]
+pub struct RequestIdConflict {
+    pub request_id: String,
 }
 
 #[derive(AsRefStr, strum::Display, strum::EnumIter)]
@@ -90,6 +143,23 @@ pub enum StateMachineColumns {
     StructuredDataSchemas,              //  SchemaId -> StructuredDataSchema
     ExtractionPoliciesAppliedOnContent, //  ContentId -> Vec<ExtractionPolicyIds>
     CoordinatorAddress,                 //  NodeId -> Coordinator address
+    IndexMigrations,                    //  IndexId -> IndexMigration
+    RebalanceJobs,                      //  IndexId -> RebalanceJob
+    QueryTemplates,                     //  QueryTemplateId -> QueryTemplate
+    OperationRecords,                   //  OperationToken -> OperationRecord
+    IngestIntents,                      //  ContentId -> IngestIntent
+    ChunkReferences,                    //  IndexTable|Hash -> ChunkReference
+    IngestionPlugins,                   //  IngestionPluginId -> IngestionPlugin
+    ProcessingSlas,                     //  ProcessingSlaId -> ProcessingSla
+    EmbeddingExpirations,               //  IndexTable|ContentId -> EmbeddingExpiration
+    NamespaceStorage,                   //  Namespace -> Stored bytes
+    BlobMigrationJobs,                  //  Namespace -> BlobMigrationJob
+    StateChangeLog,                     //  LogIndex -> StateChangeLogEntry
+    NamespaceQuotas,                    //  Namespace -> NamespaceQuotas
+    NamespaceStats,                     //  Namespace -> NamespaceStats
+    AppliedRequests,                    //  RequestId -> Request fingerprint
+    AppliedRequestsBySequence,          //  Sequence number -> RequestId
+    ExecutorTaskQueue,                  //  ExecutorId|Priority|CreatedAt|TaskId -> TaskId
 }
 
 impl StateMachineColumns {
@@ -105,6 +175,12 @@ impl StateMachineColumns {
 #[derive(serde::Serialize, Deserialize, Debug, Clone)]
 pub struct Response {
     pub value: Option<String>,
+    pub quota_exceeded: Option<NamespaceQuotaExceeded>,
+    //  The request was a retry of a request which was applied already
+    #[serde(default)]
+    pub duplicate_request: bool,
+    #[serde(default)]
+    pub request_id_conflict: Option<RequestIdConflict>,
 }
 
 #[derive(serde::Serialize, Deserialize, Debug, Clone)]
@@ -115,6 +191,38 @@ pub struct StoredSnapshot {
     pub data: Vec<u8>,
 }
 
+/// A backup of the state machine, the snapshot of its reverse indexes along
+/// with the keys and values of every state machine column
+#[derive(serde::Serialize, Deserialize, Debug)]
+struct StateMachineBackup {
+    snapshot: StoredSnapshot,
+    columns: HashMap<String, Vec<(Vec<u8>, Vec<u8>)>>,
+    //  Not set in backups taken before the schema was versioned
+    #[serde(default)]
+    schema_version: Option<u64>,
+}
+
+impl StateMachineBackup {
+    fn decode(backup: &[u8]) -> Result<Self> {
+        let mut decoder = ZlibDecoder::new(backup);
+        let mut decompressed = Vec::new();
+        decoder.read_to_end(&mut decompressed)?;
+        Ok(JsonEncoder::decode(&decompressed)?)
+    }
+}
+
+/// An entry of the raft log applied to the state machine, kept in the state
+/// change log after the entry is purged from the raft log. The request holds
+/// the state changes it created and processed, along with every value it
+/// wrote, so the entry is applied again the same way when it's replayed.
+#[derive(serde::Serialize, Deserialize, Clone)]
+pub struct StateChangeLogEntry {
+    pub log_id: LogId<NodeId>,
+    //  Not set for blank and membership entries, they're recorded so gaps in
+    // the log can be told apart from entries which don't change the state
+    pub request: Option<StateMachineUpdateRequest>,
+}
+
 pub struct StateMachineData {
     pub last_applied_log_id: RwLock<Option<LogId<NodeId>>>,
 
@@ -126,6 +234,10 @@ pub struct StateMachineData {
     state_change_tx: Arc<tokio::sync::watch::Sender<StateChange>>,
 
     gc_tasks_tx: broadcast::Sender<indexify_internal_api::GarbageCollectionTask>,
+
+    //  Every applied state change, unlike the watch channel which only keeps
+    // the latest one
+    state_changes_tx: broadcast::Sender<StateChange>,
 }
 
 pub struct StateMachineStore {
@@ -138,15 +250,24 @@ pub struct StateMachineStore {
     pub state_change_rx: tokio::sync::watch::Receiver<StateChange>,
 
     snapshot_file_path: PathBuf,
+
+    //  Held while the snapshot file is written, and while the database is
+    // backed up so the snapshot of the backup matches its log
+    snapshot_file_lock: Mutex<()>,
+
+    //  Number of applied entries kept in the state change log
+    state_change_log_entries: u64,
 }
 
 impl StateMachineStore {
     async fn new(
         db: Arc<OptimisticTransactionDB>,
         snapshot_file_path: PathBuf,
+        state_change_log_entries: u64,
     ) -> Result<StateMachineStore, StorageError<NodeId>> {
         let (tx, rx) = tokio::sync::watch::channel(StateChange::default());
         let (gc_tasks_tx, _) = broadcast::channel(8);
+        let (state_changes_tx, _) = broadcast::channel(STATE_CHANGES_CHANNEL_SIZE);
         let sm = Self {
             data: StateMachineData {
                 last_applied_log_id: RwLock::new(None),
@@ -154,11 +275,14 @@ impl StateMachineStore {
                 indexify_state: IndexifyState::default(),
                 state_change_tx: Arc::new(tx),
                 gc_tasks_tx,
+                state_changes_tx,
             },
             snapshot_idx: Mutex::new(0),
             db,
             state_change_rx: rx,
             snapshot_file_path,
+            snapshot_file_lock: Mutex::new(()),
+            state_change_log_entries,
         };
 
         let snapshot = sm.get_current_snapshot_()?;
@@ -247,6 +371,7 @@ impl StateMachineStore {
         })?;
         let compressed_size = compressed_data.len();
 
+        let _snapshot_file_guard = self.snapshot_file_lock.lock().unwrap();
         //  Create a temp file, write to temp file and then swap inode pointers
         let temp_file_path = self.snapshot_file_path.with_extension("tmp");
         let mut temp_file = File::create(&temp_file_path).map_err(|e| StorageError::IO {
@@ -276,6 +401,131 @@ impl StateMachineStore {
         Ok(())
     }
 
+    /// Returns a zlib compressed backup of the state machine, which a node
+    /// without any state can be restored from with `restore_backup`
+    pub async fn backup(&self) -> Result<Vec<u8>> {
+        //  The columns are read after the last applied log id, so they may
+        // hold the writes of entries after it, which are applied again once
+        // a restored node catches up with the log
+        let snapshot = self.snapshot_().await?;
+        let db_snapshot = self.db.snapshot();
+        let mut columns = HashMap::new();
+        for column in StateMachineColumns::iter() {
+            let entries = db_snapshot
+                .iterator_cf(column.cf(&self.db), rocksdb::IteratorMode::Start)
+                .map(|entry| entry.map(|(key, value)| (key.to_vec(), value.to_vec())))
+                .collect::<Result<Vec<_>, _>>()?;
+            columns.insert(column.to_string(), entries);
+        }
+        let backup = JsonEncoder::encode(&StateMachineBackup {
+            snapshot,
+            columns,
+            schema_version: Some(migrations::SCHEMA_VERSION),
+        })?;
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&backup)?;
+        Ok(encoder.finish()?)
+    }
+
+    /// Writes the columns of the backup to the state machine, migrating them
+    /// if the backup was taken by an earlier release, and installs its
+    /// snapshot, the node then starts from the last log id of the backup
+    pub async fn restore_backup(&self, backup: &[u8]) -> Result<()> {
+        let backup = StateMachineBackup::decode(backup)?;
+        let schema_version = backup
+            .schema_version
+            .unwrap_or(migrations::UNVERSIONED_SCHEMA);
+        migrations::check_schema_version(schema_version)?;
+        for (column, entries) in &backup.columns {
+            let cf = self
+                .db
+                .cf_handle(column)
+                .ok_or_else(|| anyhow::anyhow!("backup has unknown column {}", column))?;
+            for (key, value) in entries {
+                self.db.put_cf(cf, key, value)?;
+            }
+        }
+        migrations::migrate_from(&self.db, schema_version)?;
+        self.set_current_snapshot_(backup.snapshot.clone())?;
+        self.update_state_machine_(backup.snapshot).await?;
+        Ok(())
+    }
+
+    /// Adds an incremental backup of the database, the raft log along with the
+    /// state machine columns, to the RocksDB backup directory. The snapshot of
+    /// the state machine a node restarts from is kept with the backup, and
+    /// only the `retain` latest backups are kept. Returns the id of the backup.
+    pub fn backup_rocksdb(&self, backup_dir: &Path, retain: usize) -> Result<u32> {
+        //  The log is purged up to a snapshot only after the snapshot file is
+        // written, so the log of the backup holds every entry after the
+        // snapshot as long as no snapshot is written during the backup
+        let _snapshot_file_guard = self.snapshot_file_lock.lock().unwrap();
+        let snapshot = if self.snapshot_file_path.exists() {
+            Some(fs::read(&self.snapshot_file_path)?)
+        } else {
+            None
+        };
+        let mut engine = open_backup_engine(backup_dir)?;
+        engine.create_new_backup_flush(&*self.db, true)?;
+        engine.purge_old_backups(retain.max(1))?;
+        let backup_ids: HashSet<u32> = engine
+            .get_backup_info()
+            .iter()
+            .map(|info| info.backup_id)
+            .collect();
+        let backup_id = backup_ids
+            .iter()
+            .max()
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("the backup wasn't created"))?;
+
+        let snapshots_dir = backup_dir.join(BACKUP_SNAPSHOTS_DIR);
+        fs::create_dir_all(&snapshots_dir)?;
+        if let Some(snapshot) = snapshot {
+            fs::write(snapshots_dir.join(backup_id.to_string()), snapshot)?;
+        }
+        for entry in fs::read_dir(&snapshots_dir)? {
+            let entry = entry?;
+            let purged = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse::<u32>().ok())
+                .map_or(true, |id| !backup_ids.contains(&id));
+            if purged {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(backup_id)
+    }
+
+    /// Appends the entry to the state change log, removing the entries which
+    /// no longer fit in it
+    fn record_state_change_log_entry(
+        &self,
+        log_id: LogId<NodeId>,
+        request: Option<StateMachineUpdateRequest>,
+    ) -> Result<()> {
+        if self.state_change_log_entries == 0 {
+            return Ok(());
+        }
+        let cf = StateMachineColumns::StateChangeLog.cf(&self.db);
+        let entry = StateChangeLogEntry { log_id, request };
+        self.db
+            .put_cf(cf, id_to_bin(log_id.index), JsonEncoder::encode(&entry)?)?;
+        let Some(last_removed) = log_id.index.checked_sub(self.state_change_log_entries) else {
+            return Ok(());
+        };
+        for res in self.db.iterator_cf(cf, rocksdb::IteratorMode::Start) {
+            let (index, _) = res?;
+            if bin_to_id(&index) > last_removed {
+                break;
+            }
+            self.db.delete_cf(cf, index)?;
+        }
+        Ok(())
+    }
+
     /// Register to task deletion events
     pub async fn subscribe_to_gc_task_events(
         &self,
@@ -283,6 +533,11 @@ impl StateMachineStore {
         self.data.gc_tasks_tx.subscribe()
     }
 
+    /// Register to the state changes applied from now on
+    pub fn subscribe_to_state_changes(&self) -> broadcast::Receiver<StateChange> {
+        self.data.state_changes_tx.subscribe()
+    }
+
     //  START FORWARD INDEX READER METHODS INTERFACES
     /// This method fetches a key from a specific column family
     pub async fn get_from_cf<T, K>(
@@ -414,6 +669,12 @@ impl StateMachineStore {
         self.data.indexify_state.get_extraction_policies_table()
     }
 
+    pub async fn get_namespace_extraction_policy_count(&self, namespace: &NamespaceName) -> usize {
+        self.data
+            .indexify_state
+            .get_namespace_extraction_policy_count(namespace)
+    }
+
     pub async fn get_extractor_executors_table(
         &self,
     ) -> HashMap<ExtractorName, HashSet<ExecutorId>> {
@@ -449,9 +710,8 @@ impl StateMachineStore {
     //  END REVERSE INDEX WRITER METHOD INTERFACES
 }
 
-impl RaftSnapshotBuilder<TypeConfig> for Arc<StateMachineStore> {
-    async fn build_snapshot(&mut self) -> Result<Snapshot<TypeConfig>, StorageError<NodeId>> {
-        debug!("Called build_snapshot");
+impl StateMachineStore {
+    async fn snapshot_(&self) -> StorageResult<StoredSnapshot> {
         let (last_applied_log, last_membership) = {
             let guard = self.data.last_applied_log_id.read().await;
             let last_applied_log = *guard;
@@ -483,16 +743,23 @@ impl RaftSnapshotBuilder<TypeConfig> for Arc<StateMachineStore> {
             snapshot_id,
         };
 
-        let snapshot = StoredSnapshot {
-            meta: meta.clone(),
-            data: indexify_state_json.clone(),
-        };
+        Ok(StoredSnapshot {
+            meta,
+            data: indexify_state_json,
+        })
+    }
+}
+
+impl RaftSnapshotBuilder<TypeConfig> for Arc<StateMachineStore> {
+    async fn build_snapshot(&mut self) -> Result<Snapshot<TypeConfig>, StorageError<NodeId>> {
+        debug!("Called build_snapshot");
+        let snapshot = self.snapshot_().await?;
 
-        self.set_current_snapshot_(snapshot)?;
+        self.set_current_snapshot_(snapshot.clone())?;
 
         Ok(Snapshot {
-            meta,
-            snapshot: Box::new(Cursor::new(indexify_state_json)),
+            meta: snapshot.meta,
+            snapshot: Box::new(Cursor::new(snapshot.data)),
         })
     }
 }
@@ -530,22 +797,45 @@ impl RaftStateMachine<TypeConfig> for Arc<StateMachineStore> {
                 *guard = Some(ent.log_id);
             }
             let resp_value = None;
+            let mut quota_exceeded = None;
+            let mut duplicate_request = false;
+            let mut request_id_conflict = None;
+            let logged_request = match &ent.payload {
+                EntryPayload::Normal(req) => Some(req.clone()),
+                _ => None,
+            };
             match ent.payload {
                 EntryPayload::Blank => {}
-                EntryPayload::Normal(req) => {
-                    change_events.extend(req.new_state_changes.clone());
-
-                    if let Err(e) = self
+                EntryPayload::Normal(mut req) => {
+                    let applied = match self
                         .data
                         .indexify_state
-                        .apply_state_machine_updates(req.clone(), &self.db)
+                        .apply_state_machine_updates(&mut req, &self.db)
                     {
-                        panic!("error applying state machine update: {}", e);
+                        Ok(applied) => applied,
+                        Err(StateMachineError::QuotaExceeded(e)) => {
+                            quota_exceeded = Some(e);
+                            false
+                        }
+                        Err(StateMachineError::DuplicateRequest(_)) => {
+                            duplicate_request = true;
+                            false
+                        }
+                        Err(StateMachineError::RequestIdConflict(e)) => {
+                            request_id_conflict = Some(e);
+                            false
+                        }
+                        Err(e) => panic!("error applying state machine update: {}", e),
                     };
+                    //  Replayed operations aren't written again, so they don't produce
+                    // any state changes either
+                    if applied {
+                        change_events.extend(req.new_state_changes.clone());
+                    }
 
                     //  if the payload is a GC task, send it via channel
-                    if let RequestPayload::CreateOrAssignGarbageCollectionTask { gc_tasks } =
-                        req.payload
+                    if let RequestPayload::CreateOrAssignGarbageCollectionTask { gc_tasks } |
+                    RequestPayload::DeleteNamespace { gc_tasks, .. } = req.payload
                     {
                         for gc_task in gc_tasks {
                             if let Err(e) = self.data.gc_tasks_tx.send(gc_task.clone()) {
@@ -559,10 +849,24 @@ impl RaftStateMachine<TypeConfig> for Arc<StateMachineStore> {
                     *guard = StoredMembership::new(Some(ent.log_id), mem.clone());
                 }
             }
+            if let Err(e) = self.record_state_change_log_entry(ent.log_id, logged_request) {
+                tracing::error!(
+                    "unable to record entry {} in the state change log: {}",
+                    ent.log_id,
+                    e
+                );
+            }
 
-            replies.push(Response { value: resp_value });
+            replies.push(Response {
+                value: resp_value,
+                quota_exceeded,
+                duplicate_request,
+                request_id_conflict,
+            });
         }
         for change_event in change_events {
+            //  There are no receivers unless a client is watching the changes
+            let _ = self.data.state_changes_tx.send(change_event.clone());
             if let Err(err) = self.data.state_change_tx.send(change_event) {
                 tracing::error!("error sending state change event: {}", err);
             }
@@ -614,18 +918,19 @@ impl RaftStateMachine<TypeConfig> for Arc<StateMachineStore> {
 #[derive(Debug, Clone)]
 pub struct LogStore {
     db: Arc<OptimisticTransactionDB>,
+    compact_purged_logs: bool,
 }
 type StorageResult<T> = Result<T, StorageError<NodeId>>;
 
 /// converts an id to a byte vector for storing in the database.
 /// Note that we're using big endian encoding to ensure correct sorting of keys
-fn id_to_bin(id: u64) -> Vec<u8> {
+pub(crate) fn id_to_bin(id: u64) -> Vec<u8> {
     let mut buf = Vec::with_capacity(8);
     buf.write_u64::<BigEndian>(id).unwrap();
     buf
 }
 
-fn bin_to_id(buf: &[u8]) -> u64 {
+pub(crate) fn bin_to_id(buf: &[u8]) -> u64 {
     (&buf[0..8]).read_u64::<BigEndian>().unwrap()
 }
 
@@ -838,7 +1143,30 @@ impl RaftLogStorage<TypeConfig> for LogStore {
         let to = id_to_bin(log_id.index + 1);
         self.db
             .delete_file_in_range_cf(self.logs(), &from, &to)
-            .map_err(|e| StorageIOError::write_logs(&e).into())
+            .map_err(|e| StorageIOError::write_logs(&e))?;
+
+        //  Only the files made of purged entries alone are deleted with the
+        // range, the entries still in the memtable or in files shared with
+        // later entries are deleted one by one
+        let txn = self.db.transaction();
+        for res in self
+            .db
+            .iterator_cf(self.logs(), rocksdb::IteratorMode::Start)
+        {
+            let (id, _) = res.map_err(|e| StorageIOError::write_logs(&e))?;
+            if bin_to_id(&id) > log_id.index {
+                break;
+            }
+            txn.delete_cf(self.logs(), id)
+                .map_err(|e| StorageIOError::write_logs(&e))?;
+        }
+        txn.commit().map_err(|e| StorageIOError::write_logs(&e))?;
+
+        if self.compact_purged_logs {
+            self.db
+                .compact_range_cf(self.logs(), Some(from.as_slice()), Some(to.as_slice()));
+        }
+        Ok(())
     }
 
     async fn get_log_reader(&mut self) -> Self::LogReader {
@@ -846,9 +1174,42 @@ impl RaftLogStorage<TypeConfig> for LogStore {
     }
 }
 
+fn open_backup_engine(backup_dir: &Path) -> Result<BackupEngine> {
+    let options = BackupEngineOptions::new(backup_dir)?;
+    Ok(BackupEngine::open(&options, &Env::new()?)?)
+}
+
+/// Restores the latest backup of the RocksDB backup directory to a node
+/// without a database, along with the snapshot of the state machine it
+/// restarts from
+pub(crate) fn restore_rocksdb_backup(
+    backup_dir: &Path,
+    db_path: &Path,
+    snapshot_path: &Path,
+) -> Result<()> {
+    let mut engine = open_backup_engine(backup_dir)?;
+    let backup_id = engine
+        .get_backup_info()
+        .iter()
+        .map(|info| info.backup_id)
+        .max()
+        .ok_or_else(|| anyhow::anyhow!("there are no backups in {}", backup_dir.display()))?;
+    engine.restore_from_backup(db_path, db_path, &RestoreOptions::default(), backup_id)?;
+    //  Backups taken before the first snapshot restart from the whole log
+    let snapshot = backup_dir
+        .join(BACKUP_SNAPSHOTS_DIR)
+        .join(backup_id.to_string());
+    if snapshot.exists() {
+        fs::copy(snapshot, snapshot_path)?;
+    }
+    Ok(())
+}
+
 pub(crate) async fn new_storage<P: AsRef<Path>>(
     db_path: P,
     snapshot_path: P,
+    compact_purged_logs: bool,
+    state_change_log_entries: u64,
 ) -> (LogStore, Arc<StateMachineStore>) {
     //  TODO: Don't return sm from here, just return the StateMachineReader and use
     // that in AppState. Don't take locks unless reading from reverse indexes on the
@@ -874,28 +1235,63 @@ pub(crate) async fn new_storage<P: AsRef<Path>>(
     let db: OptimisticTransactionDB =
         OptimisticTransactionDB::open_cf_descriptors(&db_opts, db_path, all_column_families)
             .unwrap();
+    //  Before the state machine reads the database, so it only reads data of
+    // the current schema
+    migrations::migrate(&db)
+        .unwrap_or_else(|e| panic!("failed to migrate the state machine database: {}", e));
 
     let db = Arc::new(db);
 
-    let log_store = LogStore { db: db.clone() };
+    let log_store = LogStore {
+        db: db.clone(),
+        compact_purged_logs,
+    };
 
     let snapshot_path = PathBuf::from(snapshot_path.as_ref());
 
-    let sm_store = StateMachineStore::new(db, snapshot_path).await.unwrap();
+    let sm_store = StateMachineStore::new(db, snapshot_path, state_change_log_entries)
+        .await
+        .unwrap();
 
     (log_store, Arc::new(sm_store))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
-
-    use openraft::{raft::InstallSnapshotRequest, testing::log_id, SnapshotMeta, Vote};
+    use std::{collections::HashSet, time::Duration};
+
+    use indexify_internal_api::{
+        ChangeType,
+        ContentMetadata,
+        NamespaceQuotas,
+        NamespaceStats,
+        StateChange,
+    };
+    use openraft::{
+        raft::InstallSnapshotRequest,
+        storage::RaftLogStorage,
+        testing::log_id,
+        Entry,
+        EntryPayload,
+        RaftLogReader,
+        RaftSnapshotBuilder,
+        SnapshotMeta,
+        Vote,
+    };
 
+    use super::{
+        id_to_bin,
+        new_storage,
+        restore_rocksdb_backup,
+        StateMachineColumns,
+        StateMachineError,
+        BACKUP_SNAPSHOTS_DIR,
+    };
     use crate::{
         state::{
             self,
             store::{
+                requests::{ClientRequestId, RequestPayload, StateMachineUpdateRequest},
                 serializer::{JsonEncode, JsonEncoder},
                 state_machine_objects::IndexifyStateSnapshot,
             },
@@ -934,4 +1330,262 @@ mod tests {
             .await?;
         Ok(())
     }
+    #[tokio::test]
+    async fn test_purge_logs() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (mut log_store, _) =
+            new_storage(dir.path().join("db"), dir.path().join("sm-blob"), true, 0).await;
+        for index in 0..10 {
+            let entry = Entry::<state::TypeConfig> {
+                log_id: log_id(1, 0, index),
+                payload: EntryPayload::Blank,
+            };
+            log_store.db.put_cf(
+                log_store.logs(),
+                id_to_bin(index),
+                JsonEncoder::encode(&entry).expect("Failed to serialize the entry"),
+            )?;
+        }
+
+        log_store.purge(log_id(1, 0, 5)).await?;
+        let entries = log_store.try_get_log_entries(0..10).await?;
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.log_id.index)
+                .collect::<Vec<_>>(),
+            vec![6, 7, 8, 9]
+        );
+        let log_state = log_store.get_log_state().await?;
+        assert_eq!(log_state.last_purged_log_id, Some(log_id(1, 0, 5)));
+        assert_eq!(log_state.last_log_id, Some(log_id(1, 0, 9)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (_, state_machine) =
+            new_storage(dir.path().join("db"), dir.path().join("sm-blob"), true, 0).await;
+        let cf = StateMachineColumns::Namespaces.cf(&state_machine.db);
+        state_machine.db.put_cf(cf, b"test", b"{}")?;
+        *state_machine.data.last_applied_log_id.write().await = Some(log_id(1, 0, 6));
+        let backup = state_machine.backup().await?;
+
+        let (_, restored) = new_storage(
+            dir.path().join("restored-db"),
+            dir.path().join("restored-sm-blob"),
+            true,
+            0,
+        )
+        .await;
+        restored.restore_backup(&backup).await?;
+        let cf = StateMachineColumns::Namespaces.cf(&restored.db);
+        assert_eq!(restored.db.get_cf(cf, b"test")?, Some(b"{}".to_vec()));
+        assert_eq!(
+            *restored.data.last_applied_log_id.read().await,
+            Some(log_id(1, 0, 6))
+        );
+        //  The node starts from the snapshot of the backup after a restart
+        assert!(restored.get_current_snapshot_()?.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rocksdb_backup_and_restore() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let backup_dir = dir.path().join("backups");
+        let (_, state_machine) =
+            new_storage(dir.path().join("db"), dir.path().join("sm-blob"), true, 0).await;
+        let cf = StateMachineColumns::Namespaces.cf(&state_machine.db);
+        state_machine.db.put_cf(cf, b"first", b"{}")?;
+        let first_backup = state_machine.backup_rocksdb(&backup_dir, 1)?;
+        *state_machine.data.last_applied_log_id.write().await = Some(log_id(1, 0, 6));
+        state_machine.clone().build_snapshot().await?;
+        state_machine.db.put_cf(cf, b"second", b"{}")?;
+        let second_backup = state_machine.backup_rocksdb(&backup_dir, 1)?;
+        assert!(second_backup > first_backup);
+        //  Only the snapshot of the retained backup is kept
+        assert_eq!(
+            std::fs::read_dir(backup_dir.join(BACKUP_SNAPSHOTS_DIR))?.count(),
+            1
+        );
+
+        restore_rocksdb_backup(
+            &backup_dir,
+            &dir.path().join("restored-db"),
+            &dir.path().join("restored-sm-blob"),
+        )?;
+        let (_, restored) = new_storage(
+            dir.path().join("restored-db"),
+            dir.path().join("restored-sm-blob"),
+            true,
+            0,
+        )
+        .await;
+        let cf = StateMachineColumns::Namespaces.cf(&restored.db);
+        assert_eq!(restored.db.get_cf(cf, b"second")?, Some(b"{}".to_vec()));
+        assert_eq!(
+            *restored.data.last_applied_log_id.read().await,
+            Some(log_id(1, 0, 6))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retried_requests_are_skipped() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (_, state_machine) =
+            new_storage(dir.path().join("db"), dir.path().join("sm-blob"), true, 0).await;
+        let content = |id: &str| RequestPayload::CreateContent {
+            content_metadata: vec![ContentMetadata {
+                id: id.to_string(),
+                namespace: "test".to_string(),
+                storage_url: format!("file:///{}", id),
+                ..Default::default()
+            }],
+        };
+        let request_id = |request_id: &str, content_id: &str| {
+            ClientRequestId::new(request_id.to_string(), &content(content_id))
+        };
+        let create_content = |id: &str, request_ids: Vec<ClientRequestId>| {
+            StateMachineUpdateRequest {
+                payload: content(id),
+                //  Every attempt creates its own state changes
+                new_state_changes: vec![StateChange::new(
+                    id.to_string(),
+                    ChangeType::NewContent,
+                    0,
+                )],
+                state_changes_processed: vec![],
+                request_ids,
+            }
+        };
+        let indexify_state = &state_machine.data.indexify_state;
+        assert!(indexify_state.apply_state_machine_updates(
+            &mut create_content("content_1", vec![request_id("request_1", "content_1")]),
+            &state_machine.db
+        )?);
+        assert!(matches!(
+            indexify_state.apply_state_machine_updates(
+                &mut create_content("content_1", vec![request_id("request_1", "content_1")]),
+                &state_machine.db
+            ),
+            Err(StateMachineError::DuplicateRequest(request_id)) if request_id == "request_1"
+        ));
+        //  A batch holding a retried request isn't applied either
+        assert!(matches!(
+            indexify_state.apply_state_machine_updates(
+                &mut create_content(
+                    "content_2",
+                    vec![
+                        request_id("request_2", "content_2"),
+                        request_id("request_1", "content_1")
+                    ]
+                ),
+                &state_machine.db
+            ),
+            Err(StateMachineError::DuplicateRequest(_))
+        ));
+        assert!(indexify_state.apply_state_machine_updates(
+            &mut create_content("content_2", vec![request_id("request_2", "content_2")]),
+            &state_machine.db
+        )?);
+        //  A different write reusing the id of an applied write is rejected
+        // instead of being skipped as a retry
+        assert!(matches!(
+            indexify_state.apply_state_machine_updates(
+                &mut create_content("content_3", vec![request_id("request_1", "content_3")]),
+                &state_machine.db
+            ),
+            Err(StateMachineError::RequestIdConflict(conflict))
+                if conflict.request_id == "request_1"
+        ));
+
+        let cf = StateMachineColumns::StateChanges.cf(&state_machine.db);
+        let state_changes = state_machine
+            .db
+            .iterator_cf(cf, rocksdb::IteratorMode::Start)
+            .count();
+        assert_eq!(state_changes, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_content_quota_after_deletes() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (_, state_machine) =
+            new_storage(dir.path().join("db"), dir.path().join("sm-blob"), true, 0).await;
+        let indexify_state = &state_machine.data.indexify_state;
+        let apply = |payload: RequestPayload| {
+            indexify_state.apply_state_machine_updates(
+                &mut StateMachineUpdateRequest {
+                    payload,
+                    new_state_changes: vec![],
+                    state_changes_processed: vec![],
+                    request_ids: vec![],
+                },
+                &state_machine.db,
+            )
+        };
+        let set_quotas = || RequestPayload::SetNamespaceQuotas {
+            namespace: "test".to_string(),
+            quotas: NamespaceQuotas {
+                max_content: Some(1),
+                ..Default::default()
+            },
+        };
+        let create_content = |id: &str| RequestPayload::CreateContent {
+            content_metadata: vec![ContentMetadata {
+                id: id.to_string(),
+                namespace: "test".to_string(),
+                size_bytes: 100,
+                ..Default::default()
+            }],
+        };
+        apply(set_quotas())?;
+        apply(create_content("content_1"))?;
+        assert!(matches!(
+            apply(create_content("content_2")),
+            Err(StateMachineError::QuotaExceeded(_))
+        ));
+
+        //  Deleted content doesn't count toward the quota anymore
+        apply(RequestPayload::TombstoneContent {
+            namespace: "test".to_string(),
+            content_ids: HashSet::from(["content_1".to_string()]),
+        })?;
+        apply(RequestPayload::RemoveTombstonedContent {
+            content_id: "content_1".to_string(),
+        })?;
+        apply(create_content("content_2"))?;
+
+        //  A namespace created again with the same name starts over
+        apply(RequestPayload::DeleteNamespace {
+            name: "test".to_string(),
+            gc_tasks: vec![],
+        })?;
+        let db = &state_machine.db;
+        assert!(db
+            .get_cf(StateMachineColumns::NamespaceStats.cf(db), "test")?
+            .is_none());
+        assert!(db
+            .get_cf(StateMachineColumns::NamespaceStorage.cf(db), "test")?
+            .is_none());
+        apply(set_quotas())?;
+        apply(create_content("content_3"))?;
+        let stats = db
+            .get_cf(StateMachineColumns::NamespaceStats.cf(db), "test")?
+            .unwrap();
+        assert_eq!(JsonEncoder::decode::<NamespaceStats>(&stats)?.content, 1);
+        let stored_bytes = db
+            .get_cf(StateMachineColumns::NamespaceStorage.cf(db), "test")?
+            .unwrap();
+        assert_eq!(JsonEncoder::decode::<u64>(&stored_bytes)?, 100);
+        assert!(matches!(
+            apply(create_content("content_4")),
+            Err(StateMachineError::QuotaExceeded(_))
+        ));
+        Ok(())
+    }
 }
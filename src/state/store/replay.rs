@@ -0,0 +1,295 @@
+use std::{collections::BTreeMap, ops::RangeInclusive, path::Path, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use indexify_internal_api::StateChange;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use strum::IntoEnumIterator;
+
+use super::{
+    new_storage,
+    requests::StateChangeProcessed,
+    serializer::{JsonEncode, JsonEncoder},
+    StateChangeLogEntry,
+    StateMachineBackup,
+    StateMachineColumns,
+    StateMachineError,
+    StateMachineStore,
+};
+
+/// The state change log and column digests read out of a backup of the state
+/// machine
+pub struct BackupContents {
+    pub last_applied_index: Option<u64>,
+    pub state_change_log: Vec<StateChangeLogEntry>,
+    pub column_digests: BTreeMap<String, String>,
+}
+
+impl BackupContents {
+    pub fn decode(backup: &[u8]) -> Result<Self> {
+        let backup = StateMachineBackup::decode(backup)?;
+        let mut state_change_log = Vec::new();
+        let mut column_digests = BTreeMap::new();
+        for (column, entries) in &backup.columns {
+            if *column == StateMachineColumns::StateChangeLog.to_string() {
+                for (_, value) in entries {
+                    state_change_log.push(JsonEncoder::decode::<StateChangeLogEntry>(value)?);
+                }
+            } else {
+                column_digests.insert(column.clone(), digest(entries));
+            }
+        }
+        state_change_log.sort_by_key(|entry| entry.log_id.index);
+        Ok(Self {
+            last_applied_index: backup.snapshot.meta.last_log_id.map(|log_id| log_id.index),
+            state_change_log,
+            column_digests,
+        })
+    }
+}
+
+/// An entry of the state change log applied again by a replay
+#[derive(Serialize)]
+pub struct ReplayedEntry {
+    pub log_index: u64,
+    pub payload: Option<String>,
+    //  False if the request was an operation or a retry of a request which
+    // had been applied already, or was rejected for going over a quota of its
+    // namespace
+    pub applied: bool,
+    pub new_state_changes: Vec<StateChange>,
+    pub state_changes_processed: Vec<StateChangeProcessed>,
+}
+
+/// A state machine, separate from the one of any node, which the entries of
+/// a state change log are applied to one by one
+pub struct StateChangeReplay {
+    state_machine: Arc<StateMachineStore>,
+}
+
+impl StateChangeReplay {
+    /// Creates the state machine in `path`, which has to be empty, starting
+    /// from the state of `base_backup` or from an empty state if it's not
+    /// given
+    pub async fn new(path: &Path, base_backup: Option<&[u8]>) -> Result<Self> {
+        if path.exists() && path.read_dir()?.next().is_some() {
+            return Err(anyhow!(
+                "the state of the replay can't be created in {}, it isn't empty",
+                path.display()
+            ));
+        }
+        let (_, state_machine) = new_storage(path.join("db"), path.join("sm-blob"), false, 0).await;
+        if let Some(base_backup) = base_backup {
+            state_machine.restore_backup(base_backup).await?;
+        }
+        Ok(Self { state_machine })
+    }
+
+    pub async fn last_applied_index(&self) -> Option<u64> {
+        self.state_machine
+            .data
+            .last_applied_log_id
+            .read()
+            .await
+            .map(|log_id| log_id.index)
+    }
+
+    /// Applies the entries of the state change log within `range` which come
+    /// after the last applied entry. Fails before applying any entry if the
+    /// log doesn't hold every entry from the last applied one on.
+    pub async fn replay(
+        &self,
+        state_change_log: Vec<StateChangeLogEntry>,
+        range: RangeInclusive<u64>,
+    ) -> Result<Vec<ReplayedEntry>> {
+        let first_index = self.last_applied_index().await.map_or(1, |index| index + 1);
+        let entries: Vec<_> = state_change_log
+            .into_iter()
+            .filter(|entry| {
+                entry.log_id.index >= first_index && range.contains(&entry.log_id.index)
+            })
+            .collect();
+        let mut expected_index = first_index.max(*range.start());
+        if expected_index != first_index {
+            return Err(anyhow!(
+                "the replay starts at entry {}, the entries from {} would be skipped",
+                expected_index,
+                first_index
+            ));
+        }
+        for entry in &entries {
+            if entry.log_id.index != expected_index {
+                return Err(anyhow!(
+                    "entry {} is missing from the state change log",
+                    expected_index
+                ));
+            }
+            expected_index += 1;
+        }
+
+        let mut replayed = Vec::new();
+        for entry in entries {
+            *self.state_machine.data.last_applied_log_id.write().await = Some(entry.log_id);
+            let Some(mut request) = entry.request else {
+                replayed.push(ReplayedEntry {
+                    log_index: entry.log_id.index,
+                    payload: None,
+                    applied: false,
+                    new_state_changes: vec![],
+                    state_changes_processed: vec![],
+                });
+                continue;
+            };
+            let applied = match self
+                .state_machine
+                .data
+                .indexify_state
+                .apply_state_machine_updates(&mut request, &self.state_machine.db)
+            {
+                Ok(applied) => applied,
+                Err(
+                    StateMachineError::QuotaExceeded(_) |
+                    StateMachineError::DuplicateRequest(_) |
+                    StateMachineError::RequestIdConflict(_),
+                ) => false,
+                Err(e) => return Err(e.into()),
+            };
+            replayed.push(ReplayedEntry {
+                log_index: entry.log_id.index,
+                payload: Some(request.payload.as_ref().to_string()),
+                applied,
+                new_state_changes: request.new_state_changes,
+                state_changes_processed: request.state_changes_processed,
+            });
+        }
+        Ok(replayed)
+    }
+
+    /// Digests of the contents of the state machine columns, by column
+    pub fn column_digests(&self) -> Result<BTreeMap<String, String>> {
+        let db = &self.state_machine.db;
+        let mut column_digests = BTreeMap::new();
+        for column in StateMachineColumns::iter() {
+            if matches!(column, StateMachineColumns::StateChangeLog) {
+                continue;
+            }
+            let entries = db
+                .iterator_cf(column.cf(db), rocksdb::IteratorMode::Start)
+                .map(|entry| entry.map(|(key, value)| (key.to_vec(), value.to_vec())))
+                .collect::<Result<Vec<_>, _>>()?;
+            column_digests.insert(column.to_string(), digest(&entries));
+        }
+        Ok(column_digests)
+    }
+}
+
+fn digest(entries: &[(Vec<u8>, Vec<u8>)]) -> String {
+    let mut hasher = Sha256::new();
+    for (key, value) in entries {
+        hasher.update((key.len() as u64).to_be_bytes());
+        hasher.update(key);
+        hasher.update((value.len() as u64).to_be_bytes());
+        hasher.update(value);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use indexify_internal_api::{ChangeType, ContentMetadata};
+    use openraft::testing::log_id;
+
+    use super::*;
+    use crate::state::store::requests::{RequestPayload, StateMachineUpdateRequest};
+
+    fn create_content(index: u64, id: &str) -> StateChangeLogEntry {
+        StateChangeLogEntry {
+            log_id: log_id(1, 0, index),
+            request: Some(StateMachineUpdateRequest {
+                payload: RequestPayload::CreateContent {
+                    content_metadata: vec![ContentMetadata {
+                        id: id.to_string(),
+                        namespace: "test".to_string(),
+                        ..Default::default()
+                    }],
+                },
+                new_state_changes: vec![StateChange::new(
+                    id.to_string(),
+                    ChangeType::NewContent,
+                    index,
+                )],
+                state_changes_processed: vec![],
+                request_ids: vec![],
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_state_change_log() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (_, state_machine) =
+            new_storage(dir.path().join("db"), dir.path().join("sm-blob"), true, 10).await;
+        let log = vec![
+            StateChangeLogEntry {
+                log_id: log_id(1, 0, 1),
+                request: None,
+            },
+            create_content(2, "content_1"),
+            create_content(3, "content_2"),
+        ];
+        for entry in &log {
+            state_machine.record_state_change_log_entry(entry.log_id, entry.request.clone())?;
+            if let Some(request) = &entry.request {
+                state_machine
+                    .data
+                    .indexify_state
+                    .apply_state_machine_updates(&mut request.clone(), &state_machine.db)?;
+            }
+            *state_machine.data.last_applied_log_id.write().await = Some(entry.log_id);
+        }
+        let backup = BackupContents::decode(&state_machine.backup().await?)?;
+        assert_eq!(backup.last_applied_index, Some(3));
+        assert_eq!(backup.state_change_log.len(), 3);
+
+        let replay = StateChangeReplay::new(&dir.path().join("replay"), None).await?;
+        let replayed = replay
+            .replay(backup.state_change_log.clone(), 1..=2)
+            .await?;
+        assert_eq!(
+            replayed
+                .iter()
+                .map(|entry| entry.log_index)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(replayed[1].payload.as_deref(), Some("CreateContent"));
+        assert_eq!(replayed[1].new_state_changes[0].object_id, "content_1");
+        assert_ne!(replay.column_digests()?, backup.column_digests);
+
+        //  The replay continues from the last replayed entry
+        assert!(replay.replay(log[2..].to_vec(), 4..=10).await.is_err());
+        replay.replay(log[2..].to_vec(), 1..=3).await?;
+        assert_eq!(replay.column_digests()?, backup.column_digests);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_state_change_log_keeps_latest_entries() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (_, state_machine) =
+            new_storage(dir.path().join("db"), dir.path().join("sm-blob"), true, 2).await;
+        for index in 1..=5 {
+            state_machine.record_state_change_log_entry(log_id(1, 0, index), None)?;
+        }
+        let backup = BackupContents::decode(&state_machine.backup().await?)?;
+        assert_eq!(
+            backup
+                .state_change_log
+                .iter()
+                .map(|entry| entry.log_id.index)
+                .collect::<Vec<_>>(),
+            vec![4, 5]
+        );
+        Ok(())
+    }
+}
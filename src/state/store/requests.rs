@@ -1,5 +1,6 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap, HashSet},
+    hash::{Hash, Hasher},
     time::SystemTime,
 };
 
@@ -15,6 +16,28 @@ pub struct StateMachineUpdateRequest {
     pub payload: RequestPayload,
     pub new_state_changes: Vec<StateChange>,
     pub state_changes_processed: Vec<StateChangeProcessed>,
+    //  Ids the clients gave the writes in the request, more than one when
+    // writes were batched. A request holding an id which was applied already
+    // is a retry and isn't applied again.
+    #[serde(default)]
+    pub request_ids: Vec<ClientRequestId>,
+}
+
+/// Id a client gave a write, with the fingerprint of the write so that a
+/// retry of the write is told apart from another write reusing the id
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClientRequestId {
+    pub id: String,
+    pub fingerprint: String,
+}
+
+impl ClientRequestId {
+    pub fn new(id: String, payload: &RequestPayload) -> Self {
+        Self {
+            id,
+            fingerprint: payload.fingerprint(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -23,7 +46,7 @@ pub struct StateChangeProcessed {
     pub processed_at: u64,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, strum::AsRefStr)]
 pub enum RequestPayload {
     //  NOTE: This isn't strictly a state machine update. It's used to change cluster membership.
     JoinCluster {
@@ -31,18 +54,47 @@ pub enum RequestPayload {
         address: String,
         coordinator_addr: String,
     },
+    //  Membership changes requested by an operator, handled by the leader like
+    // JoinCluster
+    AddLearner {
+        node_id: NodeId,
+        address: String,
+        coordinator_addr: String,
+    },
+    PromoteLearner {
+        node_id: NodeId,
+    },
+    RemoveNode {
+        node_id: NodeId,
+    },
     RegisterExecutor {
         addr: String,
         executor_id: String,
         extractor: internal_api::ExtractorDescription,
         ts_secs: u64,
+        #[serde(default)]
+        labels: HashMap<String, String>,
     },
     RemoveExecutor {
         executor_id: String,
     },
+    DrainExecutor {
+        executor_id: String,
+    },
     CreateNamespace {
         name: String,
         structured_data_schema: internal_api::StructuredDataSchema,
+        operation: Option<internal_api::OperationRecord>,
+    },
+    //  The content of the namespace is tombstoned and deleted by the garbage
+    // collection tasks, which are created along with the deletion
+    DeleteNamespace {
+        name: String,
+        gc_tasks: Vec<internal_api::GarbageCollectionTask>,
+    },
+    SetNamespaceQuotas {
+        namespace: String,
+        quotas: internal_api::NamespaceQuotas,
     },
     CreateTasks {
         tasks: Vec<internal_api::Task>,
@@ -74,12 +126,86 @@ pub enum RequestPayload {
         extraction_policy: internal_api::ExtractionPolicy,
         updated_structured_data_schema: Option<internal_api::StructuredDataSchema>,
         new_structured_data_schema: internal_api::StructuredDataSchema,
+        operation: Option<internal_api::OperationRecord>,
+    },
+    //  Replaces the stored policy, its id, namespace and name don't change
+    UpdateExtractionPolicy {
+        extraction_policy: internal_api::ExtractionPolicy,
+    },
+    //  The indexes with the ids are removed along with the policy. The tasks
+    // of the policy are replaced, its unassigned tasks are cancelled and the
+    // tables of the removed indexes are taken out of the output mappings of
+    // the tasks which are running.
+    DeleteExtractionPolicy {
+        extraction_policy: internal_api::ExtractionPolicy,
+        index_ids: Vec<String>,
+        tasks: Vec<internal_api::Task>,
+    },
+    //  Columns are added to the stored schemas, existing columns are kept
+    EvolveStructuredDataSchemas {
+        schemas: Vec<internal_api::StructuredDataSchema>,
     },
     CreateIndex {
         index: internal_api::Index,
         namespace: String,
         id: String,
     },
+    SetIndexMigration {
+        migration: internal_api::IndexMigration,
+    },
+    //  The index and extraction policy are updated along with the job when
+    // the job switches them over to the new table.
+    SetRebalanceJob {
+        job: internal_api::RebalanceJob,
+        index: Option<internal_api::Index>,
+        extraction_policy: Option<internal_api::ExtractionPolicy>,
+    },
+    //  The storage urls of the content copied by the job, by content id, are
+    // switched over along with the progress of the job
+    SetBlobMigrationJob {
+        job: internal_api::BlobMigrationJob,
+        storage_urls: HashMap<String, String>,
+    },
+    SetQueryTemplate {
+        query_template: internal_api::QueryTemplate,
+    },
+    DeleteQueryTemplate {
+        id: String,
+    },
+    CreateIngestIntent {
+        intent: internal_api::IngestIntent,
+    },
+    DeleteIngestIntents {
+        content_ids: Vec<String>,
+    },
+    AddChunkReference {
+        index_table: String,
+        hash: String,
+        content_id: String,
+    },
+    RemoveChunkReference {
+        index_table: String,
+        hash: String,
+        content_id: String,
+    },
+    SetIngestionPlugin {
+        plugin: internal_api::IngestionPlugin,
+    },
+    DeleteIngestionPlugin {
+        id: String,
+    },
+    SetProcessingSla {
+        sla: internal_api::ProcessingSla,
+    },
+    DeleteProcessingSla {
+        id: String,
+    },
+    CreateEmbeddingExpirations {
+        expirations: Vec<internal_api::EmbeddingExpiration>,
+    },
+    DeleteEmbeddingExpirations {
+        keys: Vec<String>,
+    },
     UpdateTask {
         task: internal_api::Task,
         executor_id: Option<String>,
@@ -91,7 +217,74 @@ pub enum RequestPayload {
     },
 }
 
+impl RequestPayload {
+    /// The operation token carried by the request, with the id of the
+    /// resource the request creates. Requests with a token which was already
+    /// applied to the same resource are skipped.
+    pub fn operation(&self) -> Option<(&internal_api::OperationRecord, &str)> {
+        match self {
+            RequestPayload::CreateNamespace {
+                name, operation, ..
+            } => operation
+                .as_ref()
+                .map(|operation| (operation, name.as_str())),
+            RequestPayload::CreateExtractionPolicy {
+                extraction_policy,
+                operation,
+                ..
+            } => operation
+                .as_ref()
+                .map(|operation| (operation, extraction_policy.id.as_str())),
+            _ => None,
+        }
+    }
+
+    /// Hash of the writes of the payload. Values which differ between attempts
+    /// of the same write, like the generated content ids and the timestamps,
+    /// are left out.
+    pub fn fingerprint(&self) -> String {
+        let mut s = DefaultHasher::new();
+        self.as_ref().hash(&mut s);
+        match self {
+            RequestPayload::CreateContent { content_metadata } => {
+                hash_content(content_metadata, &mut s);
+            }
+            RequestPayload::UpdateTask {
+                task,
+                content_metadata,
+                ..
+            } => {
+                task.id.hash(&mut s);
+                task.outcome.hash(&mut s);
+                hash_content(content_metadata, &mut s);
+            }
+            _ => {}
+        }
+        format!("{:x}", s.finish())
+    }
+}
+
+fn hash_content(content_metadata: &[internal_api::ContentMetadata], s: &mut DefaultHasher) {
+    for content in content_metadata {
+        content.namespace.hash(s);
+        content.parent_id.hash(s);
+        content.storage_url.hash(s);
+        content.content_type.hash(s);
+        content.source.hash(s);
+        content.hash.hash(s);
+        content.external_id.hash(s);
+        content.labels.iter().collect::<BTreeMap<_, _>>().hash(s);
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StateMachineUpdateResponse {
     pub handled_by: NodeId,
 }
+
+/// The index of the log the leader confirmed as committed, a follower serves
+/// reads once it applied the log up to it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReadIndexResponse {
+    pub read_index: Option<u64>,
+}
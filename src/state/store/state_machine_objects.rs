@@ -11,16 +11,20 @@ use internal_api::{ExtractorDescription, StateChange};
 use itertools::Itertools;
 use rocksdb::OptimisticTransactionDB;
 use serde::de::DeserializeOwned;
-use tracing::{error, warn};
+use tracing::warn;
 
 use super::{
-    requests::{RequestPayload, StateChangeProcessed, StateMachineUpdateRequest},
+    bin_to_id,
+    id_to_bin,
+    requests::{ClientRequestId, RequestPayload, StateChangeProcessed, StateMachineUpdateRequest},
     serializer::JsonEncode,
     ContentId,
     ExecutorId,
     ExtractorName,
     JsonEncoder,
     NamespaceName,
+    NamespaceQuotaExceeded,
+    RequestIdConflict,
     SchemaId,
     StateChangeId,
     StateMachineColumns,
@@ -29,6 +33,14 @@ use super::{
 };
 use crate::state::NodeId;
 
+/// Number of request ids remembered to skip retried requests, the ids of the
+/// oldest requests are forgotten first
+const APPLIED_REQUEST_IDS: u64 = 1_000_000;
+
+/// Seconds an operation token is remembered after its operation, a retry
+/// after that is applied again
+const OPERATION_RECORD_TTL_SECS: u64 = 24 * 60 * 60;
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
 pub struct UnassignedTasks {
     unassigned_tasks: Arc<RwLock<HashSet<TaskId>>>,
@@ -158,6 +170,11 @@ impl ExtractionPoliciesTable {
             .remove(extraction_policy_id);
     }
 
+    pub fn remove_namespace(&self, namespace: &NamespaceName) {
+        let mut guard = self.extraction_policies_table.write().unwrap();
+        guard.remove(namespace);
+    }
+
     pub fn inner(&self) -> HashMap<NamespaceName, HashSet<String>> {
         let guard = self.extraction_policies_table.read().unwrap();
         guard.clone()
@@ -224,11 +241,21 @@ impl NamespaceIndexTable {
             .insert(index_id.to_owned());
     }
 
+    pub fn get(&self, namespace: &NamespaceName) -> HashSet<String> {
+        let guard = self.namespace_index_table.read().unwrap();
+        guard.get(namespace).cloned().unwrap_or_default()
+    }
+
     pub fn remove(&self, namespace: &NamespaceName, index_id: &String) {
         let mut guard = self.namespace_index_table.write().unwrap();
         guard.entry(namespace.clone()).or_default().remove(index_id);
     }
 
+    pub fn remove_namespace(&self, namespace: &NamespaceName) {
+        let mut guard = self.namespace_index_table.write().unwrap();
+        guard.remove(namespace);
+    }
+
     pub fn inner(&self) -> HashMap<NamespaceName, HashSet<String>> {
         let guard = self.namespace_index_table.read().unwrap();
         guard.clone()
@@ -355,6 +382,11 @@ impl SchemasByNamespace {
             .insert(schema_id.clone());
     }
 
+    pub fn get(&self, namespace: &NamespaceName) -> HashSet<SchemaId> {
+        let guard = self.schemas_by_namespace.read().unwrap();
+        guard.get(namespace).cloned().unwrap_or_default()
+    }
+
     pub fn remove(&self, namespace: &NamespaceName, schema_id: &SchemaId) {
         let mut guard = self.schemas_by_namespace.write().unwrap();
         guard
@@ -363,6 +395,11 @@ impl SchemasByNamespace {
             .remove(schema_id);
     }
 
+    pub fn remove_namespace(&self, namespace: &NamespaceName) {
+        let mut guard = self.schemas_by_namespace.write().unwrap();
+        guard.remove(namespace);
+    }
+
     pub fn inner(&self) -> HashMap<NamespaceName, HashSet<SchemaId>> {
         let guard = self.schemas_by_namespace.read().unwrap();
         guard.clone()
@@ -532,13 +569,101 @@ impl IndexifyState {
         index: &internal_api::Index,
         id: &String,
     ) -> Result<(), StateMachineError> {
+        let existing_index = txn
+            .get_cf(StateMachineColumns::IndexTable.cf(db), id)
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        if existing_index.is_none() {
+            self.update_namespace_stats(db, txn, &index.namespace, |stats| stats.indexes += 1)?;
+        }
         let serialized_index = JsonEncoder::encode(index)?;
         txn.put_cf(StateMachineColumns::IndexTable.cf(db), id, serialized_index)
             .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
         Ok(())
     }
 
-    fn _get_task(
+    fn set_index_migration(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        migration: &internal_api::IndexMigration,
+    ) -> Result<(), StateMachineError> {
+        let serialized_migration = JsonEncoder::encode(migration)?;
+        txn.put_cf(
+            StateMachineColumns::IndexMigrations.cf(db),
+            migration.id(),
+            serialized_migration,
+        )
+        .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn set_blob_migration_job(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        job: &internal_api::BlobMigrationJob,
+        storage_urls: &HashMap<String, String>,
+    ) -> Result<(), StateMachineError> {
+        let serialized_job = JsonEncoder::encode(job)?;
+        txn.put_cf(
+            StateMachineColumns::BlobMigrationJobs.cf(db),
+            job.id(),
+            serialized_job,
+        )
+        .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        for (content_id, storage_url) in storage_urls {
+            //  Content deleted while its blob was being copied stays deleted
+            let Some(mut content) = self.get_content(db, txn, content_id)? else {
+                continue;
+            };
+            if content.namespace != job.namespace {
+                continue;
+            }
+            content.storage_url = storage_url.clone();
+            let serialized_content = JsonEncoder::encode(&content)?;
+            txn.put_cf(
+                StateMachineColumns::ContentTable.cf(db),
+                content_id,
+                &serialized_content,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn set_rebalance_job(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        job: &internal_api::RebalanceJob,
+        index: &Option<internal_api::Index>,
+        extraction_policy: &Option<internal_api::ExtractionPolicy>,
+    ) -> Result<(), StateMachineError> {
+        let serialized_job = JsonEncoder::encode(job)?;
+        txn.put_cf(
+            StateMachineColumns::RebalanceJobs.cf(db),
+            job.id(),
+            serialized_job,
+        )
+        .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        if let Some(index) = index {
+            self.set_index(db, txn, index, &index.id())?;
+        }
+        if let Some(extraction_policy) = extraction_policy {
+            let serialized_extraction_policy = JsonEncoder::encode(extraction_policy)?;
+            txn.put_cf(
+                StateMachineColumns::ExtractionPolicies.cf(db),
+                extraction_policy.id.clone(),
+                serialized_extraction_policy,
+            )
+            .map_err(|e| {
+                StateMachineError::DatabaseError(format!("Error writing extraction policy: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+
+    fn get_task(
         &self,
         db: &Arc<OptimisticTransactionDB>,
         txn: &rocksdb::Transaction<OptimisticTransactionDB>,
@@ -563,6 +688,7 @@ impl IndexifyState {
         // content_id -> Set(Extraction Policy Ids)
         let _content_extraction_policy_mappings: HashMap<String, HashSet<String>> = HashMap::new();
         for task in tasks {
+            self.update_task_stats(db, txn, task)?;
             let serialized_task = JsonEncoder::encode(task)?;
             txn.put_cf(
                 StateMachineColumns::Tasks.cf(db),
@@ -589,6 +715,7 @@ impl IndexifyState {
         update_time: SystemTime,
     ) -> Result<(), StateMachineError> {
         for task in tasks {
+            self.update_task_stats(db, txn, task)?;
             let serialized_task = JsonEncoder::encode(task)?;
             txn.put_cf(
                 StateMachineColumns::Tasks.cf(db),
@@ -729,6 +856,68 @@ impl IndexifyState {
         Ok(task_ids)
     }
 
+    //  Tasks assigned to an executor are also queued in the order they're
+    // given to it, so its next tasks are read without reading every task
+    // assigned to it
+    fn queue_tasks_for_executor(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        executor_id: &str,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<(), StateMachineError> {
+        for task_id in task_ids {
+            let task = self.get_task(db, txn, task_id)?;
+            txn.put_cf(
+                StateMachineColumns::ExecutorTaskQueue.cf(db),
+                task.queue_key(executor_id),
+                task.id.as_bytes(),
+            )
+            .map_err(|e| StateMachineError::DatabaseError(format!("Error queueing task: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    fn dequeue_task_for_executor(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        executor_id: &str,
+        task: &internal_api::Task,
+    ) -> Result<(), StateMachineError> {
+        txn.delete_cf(
+            StateMachineColumns::ExecutorTaskQueue.cf(db),
+            task.queue_key(executor_id),
+        )
+        .map_err(|e| StateMachineError::DatabaseError(format!("Error dequeueing task: {}", e)))
+    }
+
+    fn delete_task_queue_for_executor(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        executor_id: &str,
+    ) -> Result<(), StateMachineError> {
+        let queue_cf = StateMachineColumns::ExecutorTaskQueue.cf(db);
+        let prefix = format!("{}|", executor_id);
+        let mut keys = Vec::new();
+        for item in txn.iterator_cf(
+            queue_cf,
+            rocksdb::IteratorMode::From(prefix.as_bytes(), rocksdb::Direction::Forward),
+        ) {
+            let (key, _) = item.map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            keys.push(key);
+        }
+        for key in keys {
+            txn.delete_cf(queue_cf, key)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
     fn set_content(
         &self,
         db: &Arc<OptimisticTransactionDB>,
@@ -736,6 +925,21 @@ impl IndexifyState {
         contents_vec: &Vec<internal_api::ContentMetadata>,
     ) -> Result<(), StateMachineError> {
         for content in contents_vec {
+            //  Content written again under the same id replaces the bytes it
+            // had stored
+            let previous = self.get_content(db, txn, &content.id)?;
+            if previous.is_none() {
+                self.update_namespace_stats(db, txn, &content.namespace, |stats| {
+                    stats.content += 1
+                })?;
+            }
+            let previous_size_bytes = previous.map_or(0, |previous| previous.size_bytes);
+            self.add_namespace_stored_bytes(
+                db,
+                txn,
+                &content.namespace,
+                content.size_bytes as i64 - previous_size_bytes as i64,
+            )?;
             let serialized_content = JsonEncoder::encode(content)?;
             txn.put_cf(
                 StateMachineColumns::ContentTable.cf(db),
@@ -745,10 +949,273 @@ impl IndexifyState {
             .map_err(|e| {
                 StateMachineError::DatabaseError(format!("error writing content: {}", e))
             })?;
+            //  The upload of the content finished
+            txn.delete_cf(StateMachineColumns::IngestIntents.cf(db), &content.id)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            self.set_parent_preview(db, txn, content)?;
         }
         Ok(())
     }
 
+    fn get_content(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        content_id: &str,
+    ) -> Result<Option<internal_api::ContentMetadata>, StateMachineError> {
+        txn.get_cf(StateMachineColumns::ContentTable.cf(db), content_id)
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?
+            .map(|serialized| JsonEncoder::decode::<internal_api::ContentMetadata>(&serialized))
+            .transpose()
+    }
+
+    //  Bytes of the content of each namespace, counted once for every content
+    // even when content with the same bytes shares a blob
+    fn add_namespace_stored_bytes(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        namespace: &str,
+        delta: i64,
+    ) -> Result<(), StateMachineError> {
+        if delta == 0 {
+            return Ok(());
+        }
+        let stored_bytes = self
+            .get_namespace_stored_bytes(db, txn, namespace)?
+            .saturating_add_signed(delta);
+        txn.put_cf(
+            StateMachineColumns::NamespaceStorage.cf(db),
+            namespace,
+            JsonEncoder::encode(&stored_bytes)?,
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!("error writing namespace storage: {}", e))
+        })
+    }
+
+    fn get_namespace_stored_bytes(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        namespace: &str,
+    ) -> Result<u64, StateMachineError> {
+        Ok(txn
+            .get_cf(StateMachineColumns::NamespaceStorage.cf(db), namespace)
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?
+            .map(|serialized| JsonEncoder::decode::<u64>(&serialized))
+            .transpose()?
+            .unwrap_or(0))
+    }
+
+    fn get_namespace_quotas(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        namespace: &str,
+    ) -> Result<Option<internal_api::NamespaceQuotas>, StateMachineError> {
+        txn.get_cf(StateMachineColumns::NamespaceQuotas.cf(db), namespace)
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?
+            .map(|serialized| JsonEncoder::decode::<internal_api::NamespaceQuotas>(&serialized))
+            .transpose()
+    }
+
+    //  Content uploaded and extraction policies created in a namespace are
+    // rejected when they would take it over one of its quotas. Content
+    // extracted from content of the namespace is counted but never rejected,
+    // so the extraction of content which was accepted doesn't fail halfway.
+    fn check_quotas(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        payload: &RequestPayload,
+    ) -> Result<(), StateMachineError> {
+        match payload {
+            RequestPayload::CreateContent { content_metadata } => {
+                let mut content_by_namespace: HashMap<&str, Vec<&internal_api::ContentMetadata>> =
+                    HashMap::new();
+                for content in content_metadata {
+                    content_by_namespace
+                        .entry(&content.namespace)
+                        .or_default()
+                        .push(content);
+                }
+                for (namespace, contents) in content_by_namespace {
+                    if contents.iter().all(|content| !content.parent_id.is_empty()) {
+                        continue;
+                    }
+                    let Some(quotas) = self.get_namespace_quotas(db, txn, namespace)? else {
+                        continue;
+                    };
+                    let mut new_content = 0;
+                    let mut added_bytes = 0;
+                    for content in contents {
+                        let previous = self.get_content(db, txn, &content.id)?;
+                        if previous.is_none() {
+                            new_content += 1;
+                        }
+                        added_bytes += content.size_bytes as i64 -
+                            previous.map_or(0, |previous| previous.size_bytes as i64);
+                    }
+                    if let Some(max_content) = quotas.max_content {
+                        let requested =
+                            self.get_namespace_stats(db, txn, namespace)?.content + new_content;
+                        if new_content > 0 && requested > max_content {
+                            return Err(quota_exceeded(
+                                namespace,
+                                "content",
+                                max_content,
+                                requested,
+                            ));
+                        }
+                    }
+                    if let Some(max_stored_bytes) = quotas.max_stored_bytes {
+                        let requested = self
+                            .get_namespace_stored_bytes(db, txn, namespace)?
+                            .saturating_add_signed(added_bytes);
+                        if added_bytes > 0 && requested > max_stored_bytes {
+                            return Err(quota_exceeded(
+                                namespace,
+                                "stored bytes",
+                                max_stored_bytes,
+                                requested,
+                            ));
+                        }
+                    }
+                }
+            }
+            RequestPayload::CreateExtractionPolicy {
+                extraction_policy, ..
+            } => {
+                let namespace = &extraction_policy.namespace;
+                let Some(max_extraction_policies) = self
+                    .get_namespace_quotas(db, txn, namespace)?
+                    .and_then(|quotas| quotas.max_extraction_policies)
+                else {
+                    return Ok(());
+                };
+                let extraction_policies = self.extraction_policies_table.get(namespace);
+                let requested = extraction_policies.len() as u64 + 1;
+                if !extraction_policies.contains(&extraction_policy.id) &&
+                    requested > max_extraction_policies
+                {
+                    return Err(quota_exceeded(
+                        namespace,
+                        "extraction policies",
+                        max_extraction_policies,
+                        requested,
+                    ));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    //  Content which isn't text gets the preview of the first text extracted
+    // from it
+    fn set_parent_preview(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        content: &internal_api::ContentMetadata,
+    ) -> Result<(), StateMachineError> {
+        if content.preview.is_empty() || content.parent_id.is_empty() {
+            return Ok(());
+        }
+        let Some(serialized_parent) = txn
+            .get_cf(StateMachineColumns::ContentTable.cf(db), &content.parent_id)
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?
+        else {
+            return Ok(());
+        };
+        let mut parent = JsonEncoder::decode::<internal_api::ContentMetadata>(&serialized_parent)?;
+        if !parent.preview.is_empty() {
+            return Ok(());
+        }
+        parent.preview = content.preview.clone();
+        txn.put_cf(
+            StateMachineColumns::ContentTable.cf(db),
+            &parent.id,
+            JsonEncoder::encode(&parent)?,
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!("error writing content preview: {}", e))
+        })
+    }
+
+    fn get_chunk_reference(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        key: &str,
+    ) -> Result<Option<internal_api::ChunkReference>, StateMachineError> {
+        txn.get_cf(StateMachineColumns::ChunkReferences.cf(db), key)
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?
+            .map(|serialized| JsonEncoder::decode::<internal_api::ChunkReference>(&serialized))
+            .transpose()
+    }
+
+    fn add_chunk_reference(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        index_table: &str,
+        hash: &str,
+        content_id: &str,
+    ) -> Result<(), StateMachineError> {
+        let key = internal_api::ChunkReference::key(index_table, hash);
+        let mut reference = self.get_chunk_reference(db, txn, &key)?.unwrap_or_else(|| {
+            internal_api::ChunkReference {
+                index_table: index_table.to_string(),
+                hash: hash.to_string(),
+                content_ids: vec![],
+            }
+        });
+        if reference.content_ids.iter().any(|id| id == content_id) {
+            return Ok(());
+        }
+        reference.content_ids.push(content_id.to_string());
+        txn.put_cf(
+            StateMachineColumns::ChunkReferences.cf(db),
+            key,
+            JsonEncoder::encode(&reference)?,
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!("error writing chunk reference: {}", e))
+        })
+    }
+
+    //  The embedding is stored under the first content id, so the next content
+    // takes over once the first one is removed
+    fn remove_chunk_reference(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        index_table: &str,
+        hash: &str,
+        content_id: &str,
+    ) -> Result<(), StateMachineError> {
+        let key = internal_api::ChunkReference::key(index_table, hash);
+        let Some(mut reference) = self.get_chunk_reference(db, txn, &key)? else {
+            return Ok(());
+        };
+        reference.content_ids.retain(|id| id != content_id);
+        if reference.content_ids.is_empty() {
+            return txn
+                .delete_cf(StateMachineColumns::ChunkReferences.cf(db), key)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()));
+        }
+        txn.put_cf(
+            StateMachineColumns::ChunkReferences.cf(db),
+            key,
+            JsonEncoder::encode(&reference)?,
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!("error writing chunk reference: {}", e))
+        })
+    }
+
     fn tombstone_content(
         &self,
         db: &Arc<OptimisticTransactionDB>,
@@ -800,6 +1267,17 @@ impl IndexifyState {
         content_ids: Vec<String>,
     ) -> Result<(), StateMachineError> {
         for content_id in content_ids {
+            if let Some(content) = self.get_content(db, txn, &content_id)? {
+                self.add_namespace_stored_bytes(
+                    db,
+                    txn,
+                    &content.namespace,
+                    -(content.size_bytes as i64),
+                )?;
+                self.update_namespace_stats(db, txn, &content.namespace, |stats| {
+                    stats.content = stats.content.saturating_sub(1)
+                })?;
+            }
             txn.delete_cf(StateMachineColumns::ContentTable.cf(db), content_id)
                 .map_err(|e| {
                     StateMachineError::TransactionError(format!(
@@ -815,20 +1293,12 @@ impl IndexifyState {
         &self,
         db: &Arc<OptimisticTransactionDB>,
         txn: &rocksdb::Transaction<OptimisticTransactionDB>,
-        addr: String,
-        executor_id: &str,
-        extractor: &ExtractorDescription,
-        ts_secs: &u64,
+        executor: &internal_api::ExecutorMetadata,
     ) -> Result<(), StateMachineError> {
-        let serialized_executor = JsonEncoder::encode(&internal_api::ExecutorMetadata {
-            id: executor_id.into(),
-            last_seen: *ts_secs,
-            addr: addr.clone(),
-            extractor: extractor.clone(),
-        })?;
+        let serialized_executor = JsonEncoder::encode(executor)?;
         txn.put_cf(
             StateMachineColumns::Executors.cf(db),
-            executor_id,
+            &executor.id,
             serialized_executor,
         )
         .map_err(|e| StateMachineError::DatabaseError(format!("Error writing executor: {}", e)))?;
@@ -899,6 +1369,105 @@ impl IndexifyState {
         Ok(())
     }
 
+    fn update_extraction_policy(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        extraction_policy: &internal_api::ExtractionPolicy,
+    ) -> Result<(), StateMachineError> {
+        txn.put_cf(
+            &StateMachineColumns::ExtractionPolicies.cf(db),
+            extraction_policy.id.clone(),
+            JsonEncoder::encode(extraction_policy)?,
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!("Error updating extraction policy: {}", e))
+        })
+    }
+
+    //  A failed task is put back to be assigned again once its backoff passed
+    // if the retry policy of its extraction policy retries it. The policy may
+    // have been deleted while the task was running. Returns whether the task
+    // is retried
+    fn retry_failed_task(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task: &mut internal_api::Task,
+        update_time: SystemTime,
+    ) -> Result<bool, StateMachineError> {
+        if task.outcome != internal_api::TaskOutcome::Failed {
+            return Ok(false);
+        }
+        let Some(extraction_policy) = txn
+            .get_cf(
+                StateMachineColumns::ExtractionPolicies.cf(db),
+                &task.extraction_policy_id,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?
+            .map(|serialized| JsonEncoder::decode::<internal_api::ExtractionPolicy>(&serialized))
+            .transpose()?
+        else {
+            return Ok(false);
+        };
+        let attempt = task.attempts + 1;
+        let retry_policy = &extraction_policy.retry_policy;
+        if !retry_policy.retries(attempt) {
+            return Ok(false);
+        }
+        let update_time_secs = update_time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        task.attempts = attempt;
+        task.outcome = internal_api::TaskOutcome::Unknown;
+        task.retry_at = Some(update_time_secs + retry_policy.backoff_secs(attempt));
+        Ok(true)
+    }
+
+    fn delete_extraction_policy(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        extraction_policy: &internal_api::ExtractionPolicy,
+        index_ids: &Vec<String>,
+        tasks: &Vec<internal_api::Task>,
+    ) -> Result<(), StateMachineError> {
+        txn.delete_cf(
+            StateMachineColumns::ExtractionPolicies.cf(db),
+            &extraction_policy.id,
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!("Error deleting extraction policy: {}", e))
+        })?;
+        let mut deleted_indexes = 0;
+        for index_id in index_ids {
+            let existing_index = txn
+                .get_cf(StateMachineColumns::IndexTable.cf(db), index_id)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            if existing_index.is_some() {
+                txn.delete_cf(StateMachineColumns::IndexTable.cf(db), index_id)
+                    .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+                deleted_indexes += 1;
+            }
+        }
+        if deleted_indexes > 0 {
+            self.update_namespace_stats(db, txn, &extraction_policy.namespace, |stats| {
+                stats.indexes = stats.indexes.saturating_sub(deleted_indexes)
+            })?;
+        }
+        for task in tasks {
+            self.update_task_stats(db, txn, task)?;
+            txn.put_cf(
+                StateMachineColumns::Tasks.cf(db),
+                &task.id,
+                JsonEncoder::encode(task)?,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
     fn set_namespace(
         &self,
         db: &Arc<OptimisticTransactionDB>,
@@ -917,6 +1486,53 @@ impl IndexifyState {
         Ok(())
     }
 
+    //  The content is only tombstoned, it's deleted once the garbage collection
+    // of its blobs, embeddings and metadata finishes
+    fn delete_namespace(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        namespace: &NamespaceName,
+    ) -> Result<(), StateMachineError> {
+        let content_ids = self
+            .content_namespace_table
+            .inner()
+            .get(namespace)
+            .cloned()
+            .unwrap_or_default();
+        self.tombstone_content(db, txn, &content_ids)?;
+        for extraction_policy_id in self.extraction_policies_table.get(namespace) {
+            txn.delete_cf(
+                StateMachineColumns::ExtractionPolicies.cf(db),
+                extraction_policy_id,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        }
+        for index_id in self.namespace_index_table.get(namespace) {
+            txn.delete_cf(StateMachineColumns::IndexTable.cf(db), index_id)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        }
+        for schema_id in self.schemas_by_namespace.get(namespace) {
+            txn.delete_cf(StateMachineColumns::StructuredDataSchemas.cf(db), schema_id)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        }
+        //  A namespace created again with the same name starts with no content
+        // and no stored bytes
+        for column in [
+            StateMachineColumns::NamespaceQuotas,
+            StateMachineColumns::NamespaceStats,
+            StateMachineColumns::NamespaceStorage,
+        ] {
+            txn.delete_cf(column.cf(db), namespace)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        }
+        txn.delete_cf(StateMachineColumns::Namespaces.cf(db), namespace)
+            .map_err(|e| {
+                StateMachineError::DatabaseError(format!("error deleting namespace: {}", e))
+            })?;
+        Ok(())
+    }
+
     fn set_schema(
         &self,
         db: &Arc<OptimisticTransactionDB>,
@@ -933,12 +1549,39 @@ impl IndexifyState {
         Ok(())
     }
 
+    fn evolve_schema(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        schema: &internal_api::StructuredDataSchema,
+    ) -> Result<(), StateMachineError> {
+        let existing_schema = txn
+            .get_cf(
+                StateMachineColumns::StructuredDataSchemas.cf(db),
+                &schema.id,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?
+            .map(|serialized| {
+                JsonEncoder::decode::<internal_api::StructuredDataSchema>(&serialized)
+            })
+            .transpose()?;
+        match existing_schema {
+            Some(existing_schema) => {
+                if let Some(evolved_schema) = existing_schema.evolve(schema.columns.clone()) {
+                    self.set_schema(db, txn, &evolved_schema)?;
+                }
+                Ok(())
+            }
+            None => self.set_schema(db, txn, schema),
+        }
+    }
+
     pub fn update_content_extraction_policy_state(
         &self,
         db: &Arc<OptimisticTransactionDB>,
         txn: &rocksdb::Transaction<OptimisticTransactionDB>,
         content_id: &str,
-        _extraction_policy_id: &str,
+        extraction_policy_id: &str,
         policy_completion_time: SystemTime,
     ) -> Result<(), StateMachineError> {
         let value = txn
@@ -955,8 +1598,8 @@ impl IndexifyState {
                     content_id
                 ))
             })?;
-        let content_meta = JsonEncoder::decode::<internal_api::ContentMetadata>(&value)?;
-        let _epoch_time = policy_completion_time
+        let mut content_meta = JsonEncoder::decode::<internal_api::ContentMetadata>(&value)?;
+        let epoch_time = policy_completion_time
             .duration_since(SystemTime::UNIX_EPOCH)
             .map_err(|e| {
                 StateMachineError::DatabaseError(format!(
@@ -965,6 +1608,12 @@ impl IndexifyState {
                 ))
             })?
             .as_secs();
+        //  Policies are recorded when their task is created, so the indexes
+        // they may have written to are known when the content is deleted.
+        // The completion time stays zero until the task finishes.
+        content_meta
+            .extraction_policy_ids
+            .insert(extraction_policy_id.to_string(), epoch_time);
         let data = JsonEncoder::encode(&content_meta)?;
         txn.put_cf(StateMachineColumns::ContentTable.cf(db), content_id, data)
             .map_err(|e| {
@@ -999,6 +1648,25 @@ impl IndexifyState {
         Ok(())
     }
 
+    fn delete_coordinator_addr(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        node_id: NodeId,
+    ) -> Result<(), StateMachineError> {
+        txn.delete_cf(
+            StateMachineColumns::CoordinatorAddress.cf(db),
+            node_id.to_string(),
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!(
+                "Error deleting coordinator address for node {}: {}",
+                node_id, e
+            ))
+        })?;
+        Ok(())
+    }
+
     pub fn mark_state_changes_processed(
         &self,
         state_change: &StateChangeProcessed,
@@ -1013,14 +1681,241 @@ impl IndexifyState {
             .insert(&schema.namespace, &schema.id);
     }
 
-    /// This method will make all state machine forward index writes to RocksDB
-    pub fn apply_state_machine_updates(
+    /// Fails with `DuplicateRequest` if any of the request ids was applied for
+    /// the same write, and with `RequestIdConflict` if it was applied for a
+    /// different write.
+    fn check_request_ids(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        request_ids: &[ClientRequestId],
+    ) -> Result<(), StateMachineError> {
+        for request_id in request_ids {
+            let applied = txn
+                .get_cf(StateMachineColumns::AppliedRequests.cf(db), &request_id.id)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            match applied {
+                Some(fingerprint) if fingerprint == request_id.fingerprint.as_bytes() => {
+                    return Err(StateMachineError::DuplicateRequest(request_id.id.clone()));
+                }
+                Some(_) => {
+                    return Err(RequestIdConflict {
+                        request_id: request_id.id.clone(),
+                    }
+                    .into());
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    //  Returns false if the operation was applied already. Records older than
+    // `OPERATION_RECORD_TTL_SECS` are forgotten as operations are recorded,
+    // timed by the operation being recorded so every coordinator forgets the
+    // same records
+    fn record_operation(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        operation: &internal_api::OperationRecord,
+        resource_id: &str,
+    ) -> Result<bool, StateMachineError> {
+        let existing_operation = self
+            .get_from_cf::<internal_api::OperationRecord, _>(
+                db,
+                StateMachineColumns::OperationRecords,
+                &operation.token,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        if let Some(existing_operation) = existing_operation {
+            if existing_operation.operation == operation.operation &&
+                existing_operation.resource_id == resource_id
+            {
+                return Ok(false);
+            }
+            return Err(RequestIdConflict {
+                request_id: operation.token.clone(),
+            }
+            .into());
+        }
+
+        let cf = StateMachineColumns::OperationRecords.cf(db);
+        for res in txn.iterator_cf(cf, rocksdb::IteratorMode::Start) {
+            let (key, value) = res.map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            let record: internal_api::OperationRecord = JsonEncoder::decode(&value)?;
+            if record.created_at.saturating_add(OPERATION_RECORD_TTL_SECS) < operation.created_at {
+                txn.delete_cf(cf, key)
+                    .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            }
+        }
+        let record = internal_api::OperationRecord {
+            resource_id: resource_id.to_string(),
+            ..operation.clone()
+        };
+        txn.put_cf(cf, &operation.token, JsonEncoder::encode(&record)?)
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        Ok(true)
+    }
+
+    //  Request ids are numbered in the order they're applied, so the oldest
+    // ones can be forgotten once more than `APPLIED_REQUEST_IDS` are
+    // remembered
+    fn set_applied_request_ids(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        request_ids: &[ClientRequestId],
+    ) -> Result<(), StateMachineError> {
+        if request_ids.is_empty() {
+            return Ok(());
+        }
+        let by_sequence_cf = StateMachineColumns::AppliedRequestsBySequence.cf(db);
+        let mut sequence = match txn
+            .iterator_cf(by_sequence_cf, rocksdb::IteratorMode::End)
+            .next()
+        {
+            Some(res) => {
+                let (key, _) = res.map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+                bin_to_id(&key) + 1
+            }
+            None => 0,
+        };
+        for request_id in request_ids {
+            txn.put_cf(
+                StateMachineColumns::AppliedRequests.cf(db),
+                &request_id.id,
+                &request_id.fingerprint,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            txn.put_cf(by_sequence_cf, id_to_bin(sequence), &request_id.id)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            sequence += 1;
+        }
+        let Some(first_kept) = sequence.checked_sub(APPLIED_REQUEST_IDS) else {
+            return Ok(());
+        };
+        let forgotten = txn
+            .iterator_cf(by_sequence_cf, rocksdb::IteratorMode::Start)
+            .map(|res| res.map_err(|e| StateMachineError::DatabaseError(e.to_string())))
+            .take_while(|res| {
+                res.as_ref()
+                    .map_or(true, |(key, _)| bin_to_id(key) < first_kept)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for (key, request_id) in forgotten {
+            txn.delete_cf(StateMachineColumns::AppliedRequests.cf(db), request_id)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            txn.delete_cf(by_sequence_cf, key)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn get_namespace_stats(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        namespace: &str,
+    ) -> Result<internal_api::NamespaceStats, StateMachineError> {
+        Ok(txn
+            .get_cf(StateMachineColumns::NamespaceStats.cf(db), namespace)
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?
+            .map(|serialized| JsonEncoder::decode::<internal_api::NamespaceStats>(&serialized))
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    //  Counters of each namespace, written along with the writes they count
+    // so that reading them doesn't need a scan of the namespace
+    fn update_namespace_stats(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        namespace: &str,
+        update: impl FnOnce(&mut internal_api::NamespaceStats),
+    ) -> Result<(), StateMachineError> {
+        let mut stats = self.get_namespace_stats(db, txn, namespace)?;
+        update(&mut stats);
+        txn.put_cf(
+            StateMachineColumns::NamespaceStats.cf(db),
+            namespace,
+            JsonEncoder::encode(&stats)?,
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!("error writing namespace stats: {}", e))
+        })
+    }
+
+    //  Tasks are pending until they reach a terminal state, the counters are
+    // moved by the difference between the previous state of the task and the
+    // new one so that updates applied again don't count twice
+    fn update_task_stats(
         &self,
-        request: StateMachineUpdateRequest,
         db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task: &internal_api::Task,
     ) -> Result<(), StateMachineError> {
+        let previous = txn
+            .get_cf(StateMachineColumns::Tasks.cf(db), &task.id)
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?
+            .map(|serialized| JsonEncoder::decode::<internal_api::Task>(&serialized))
+            .transpose()?;
+        let counts = |task: &internal_api::Task| {
+            (
+                u64::from(!task.terminal_state()),
+                u64::from(task.outcome == internal_api::TaskOutcome::Failed),
+            )
+        };
+        let (previous_pending, previous_failed) = previous.as_ref().map_or((0, 0), counts);
+        let (pending, failed) = counts(task);
+        if (previous_pending, previous_failed) == (pending, failed) {
+            return Ok(());
+        }
+        self.update_namespace_stats(db, txn, &task.namespace, |stats| {
+            stats.tasks_pending = (stats.tasks_pending + pending).saturating_sub(previous_pending);
+            stats.tasks_failed = (stats.tasks_failed + failed).saturating_sub(previous_failed);
+        })
+    }
+
+    /// This method will make all state machine forward index writes to RocksDB.
+    /// Returns false without writing anything if the request carries an
+    /// operation token which was already applied, and fails with
+    /// `RequestIdConflict` if the token was applied to another operation or
+    /// resource. Failed tasks which are
+    /// retried are updated in the request, so the caller sees the request as
+    /// it was applied.
+    pub fn apply_state_machine_updates(
+        &self,
+        request: &mut StateMachineUpdateRequest,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<bool, StateMachineError> {
         let txn = db.transaction();
 
+        if let Some((operation, resource_id)) = request.payload.operation() {
+            if !self.record_operation(db, &txn, operation, resource_id)? {
+                return Ok(false);
+            }
+        }
+        self.check_request_ids(db, &txn, &request.request_ids)?;
+        self.check_quotas(db, &txn, &request.payload)?;
+        self.set_applied_request_ids(db, &txn, &request.request_ids)?;
+
+        //  Whether a failed task is retried is decided as its outcome is
+        // applied, so every coordinator makes the same decision
+        if let RequestPayload::UpdateTask {
+            task, update_time, ..
+        } = &mut request.payload
+        {
+            if self.retry_failed_task(db, &txn, task, *update_time)? {
+                let task_id = task.id.clone();
+                request.new_state_changes.retain(|change| {
+                    change.change_type != internal_api::ChangeType::TaskCompleted ||
+                        change.object_id != task_id
+                });
+            }
+        }
+
         self.set_new_state_changes(db, &txn, &request.new_state_changes)?;
         self.set_processed_state_changes(db, &txn, &request.state_changes_processed)?;
 
@@ -1032,6 +1927,112 @@ impl IndexifyState {
             } => {
                 self.set_index(db, &txn, index, id)?;
             }
+            RequestPayload::SetIndexMigration { migration } => {
+                self.set_index_migration(db, &txn, migration)?;
+            }
+            RequestPayload::SetRebalanceJob {
+                job,
+                index,
+                extraction_policy,
+            } => {
+                self.set_rebalance_job(db, &txn, job, index, extraction_policy)?;
+            }
+            RequestPayload::SetBlobMigrationJob { job, storage_urls } => {
+                self.set_blob_migration_job(db, &txn, job, storage_urls)?;
+            }
+            RequestPayload::SetQueryTemplate { query_template } => {
+                let serialized_query_template = JsonEncoder::encode(query_template)?;
+                txn.put_cf(
+                    StateMachineColumns::QueryTemplates.cf(db),
+                    query_template.id(),
+                    serialized_query_template,
+                )
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            }
+            RequestPayload::DeleteQueryTemplate { id } => {
+                txn.delete_cf(StateMachineColumns::QueryTemplates.cf(db), id)
+                    .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            }
+            RequestPayload::CreateIngestIntent { intent } => {
+                let serialized_intent = JsonEncoder::encode(intent)?;
+                txn.put_cf(
+                    StateMachineColumns::IngestIntents.cf(db),
+                    &intent.content_id,
+                    serialized_intent,
+                )
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            }
+            RequestPayload::DeleteIngestIntents { content_ids } => {
+                for content_id in content_ids {
+                    txn.delete_cf(StateMachineColumns::IngestIntents.cf(db), content_id)
+                        .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+                }
+            }
+            RequestPayload::AddChunkReference {
+                index_table,
+                hash,
+                content_id,
+            } => {
+                self.add_chunk_reference(db, &txn, index_table, hash, content_id)?;
+            }
+            RequestPayload::RemoveChunkReference {
+                index_table,
+                hash,
+                content_id,
+            } => {
+                self.remove_chunk_reference(db, &txn, index_table, hash, content_id)?;
+            }
+            RequestPayload::SetIngestionPlugin { plugin } => {
+                let serialized_plugin = JsonEncoder::encode(plugin)?;
+                txn.put_cf(
+                    StateMachineColumns::IngestionPlugins.cf(db),
+                    plugin.id(),
+                    serialized_plugin,
+                )
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            }
+            RequestPayload::DeleteIngestionPlugin { id } => {
+                txn.delete_cf(StateMachineColumns::IngestionPlugins.cf(db), id)
+                    .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            }
+            RequestPayload::SetProcessingSla { sla } => {
+                let serialized_sla = JsonEncoder::encode(sla)?;
+                txn.put_cf(
+                    StateMachineColumns::ProcessingSlas.cf(db),
+                    sla.id(),
+                    serialized_sla,
+                )
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            }
+            RequestPayload::DeleteProcessingSla { id } => {
+                txn.delete_cf(StateMachineColumns::ProcessingSlas.cf(db), id)
+                    .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            }
+            RequestPayload::SetNamespaceQuotas { namespace, quotas } => {
+                txn.put_cf(
+                    StateMachineColumns::NamespaceQuotas.cf(db),
+                    namespace,
+                    JsonEncoder::encode(quotas)?,
+                )
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            }
+            RequestPayload::CreateEmbeddingExpirations { expirations } => {
+                for expiration in expirations {
+                    let serialized_expiration = JsonEncoder::encode(expiration)?;
+                    txn.put_cf(
+                        StateMachineColumns::EmbeddingExpirations.cf(db),
+                        expiration.key(),
+                        serialized_expiration,
+                    )
+                    .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+                }
+            }
+            RequestPayload::DeleteEmbeddingExpirations { keys } => {
+                for key in keys {
+                    txn.delete_cf(StateMachineColumns::EmbeddingExpirations.cf(db), key)
+                        .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+                }
+            }
             RequestPayload::CreateTasks { tasks } => {
                 self.set_tasks(db, &txn, tasks)?;
             }
@@ -1061,6 +2062,7 @@ impl IndexifyState {
                 // and make sure it's additive.
 
                 for (executor_id, tasks) in assignments.iter() {
+                    self.queue_tasks_for_executor(db, &txn, executor_id, tasks)?;
                     let mut existing_tasks =
                         self.get_task_assignments_for_executor(db, &txn, executor_id)?;
                     existing_tasks.extend(tasks.clone());
@@ -1077,10 +2079,11 @@ impl IndexifyState {
             } => {
                 self.update_tasks(db, &txn, vec![task], *update_time)?;
 
-                if task.terminal_state() {
-                    //  If the task is meant to be marked finished and has an executor id, remove it
-                    // from the list of tasks assigned to an executor
+                if task.terminal_state() || task.retrying() {
+                    //  If the task is meant to be marked finished or retried and has an executor
+                    // id, remove it from the list of tasks assigned to an executor
                     if let Some(executor_id) = executor_id {
+                        self.dequeue_task_for_executor(db, &txn, executor_id, task)?;
                         let mut existing_tasks =
                             self.get_task_assignments_for_executor(db, &txn, executor_id)?;
                         existing_tasks.remove(&task.id);
@@ -1098,13 +2101,36 @@ impl IndexifyState {
                 executor_id,
                 extractor,
                 ts_secs,
+                labels,
             } => {
                 //  Insert the executor
-                self.set_executor(db, &txn, addr.into(), executor_id, extractor, ts_secs)?;
+                let executor = internal_api::ExecutorMetadata {
+                    id: executor_id.clone(),
+                    last_seen: *ts_secs,
+                    addr: addr.clone(),
+                    extractor: extractor.clone(),
+                    labels: labels.clone(),
+                    draining: false,
+                };
+                self.set_executor(db, &txn, &executor)?;
 
                 //  Insert the associated extractor
                 self.set_extractor(db, &txn, extractor)?;
             }
+            RequestPayload::DrainExecutor { executor_id } => {
+                //  Tasks already assigned to the executor are left to finish
+                let executor = txn
+                    .get_cf(StateMachineColumns::Executors.cf(db), executor_id)
+                    .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?
+                    .map(|serialized| {
+                        JsonEncoder::decode::<internal_api::ExecutorMetadata>(&serialized)
+                    })
+                    .transpose()?;
+                if let Some(mut executor) = executor {
+                    executor.draining = true;
+                    self.set_executor(db, &txn, &executor)?;
+                }
+            }
             RequestPayload::RemoveExecutor { executor_id } => {
                 //  NOTE: Special case of a handler that also remove its own reverse indexes
                 // here and returns from this function  Doing this because
@@ -1115,6 +2141,7 @@ impl IndexifyState {
 
                 // Remove all tasks assigned to this executor and get a handle on the task ids
                 let task_ids = self.delete_task_assignments_for_executor(db, &txn, executor_id)?;
+                self.delete_task_queue_for_executor(db, &txn, executor_id)?;
 
                 txn.commit()
                     .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
@@ -1149,6 +2176,7 @@ impl IndexifyState {
                 extraction_policy,
                 updated_structured_data_schema,
                 new_structured_data_schema,
+                operation: _,
             } => {
                 self.set_extraction_policy(
                     db,
@@ -1158,12 +2186,32 @@ impl IndexifyState {
                     new_structured_data_schema,
                 )?;
             }
+            RequestPayload::UpdateExtractionPolicy { extraction_policy } => {
+                self.update_extraction_policy(db, &txn, extraction_policy)?;
+            }
+            RequestPayload::DeleteExtractionPolicy {
+                extraction_policy,
+                index_ids,
+                tasks,
+            } => {
+                self.delete_extraction_policy(db, &txn, extraction_policy, index_ids, tasks)?;
+            }
             RequestPayload::CreateNamespace {
                 name,
                 structured_data_schema,
+                operation: _,
             } => {
                 self.set_namespace(db, &txn, name, structured_data_schema)?;
             }
+            RequestPayload::DeleteNamespace { name, gc_tasks } => {
+                self.delete_namespace(db, &txn, name)?;
+                self.set_garbage_collection_tasks(db, &txn, gc_tasks)?;
+            }
+            RequestPayload::EvolveStructuredDataSchemas { schemas } => {
+                for schema in schemas {
+                    self.evolve_schema(db, &txn, schema)?;
+                }
+            }
             RequestPayload::MarkStateChangesProcessed { state_changes } => {
                 self.set_processed_state_changes(db, &txn, state_changes)?;
             }
@@ -1174,14 +2222,25 @@ impl IndexifyState {
             } => {
                 self.set_coordinator_addr(db, &txn, *node_id, coordinator_addr)?;
             }
+            RequestPayload::AddLearner {
+                node_id,
+                address: _,
+                coordinator_addr,
+            } => {
+                self.set_coordinator_addr(db, &txn, *node_id, coordinator_addr)?;
+            }
+            RequestPayload::PromoteLearner { node_id: _ } => {}
+            RequestPayload::RemoveNode { node_id } => {
+                self.delete_coordinator_addr(db, &txn, *node_id)?;
+            }
         };
 
-        self.apply(request);
+        self.apply(request.clone());
 
         txn.commit()
             .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
 
-        Ok(())
+        Ok(true)
     }
 
     /// This method handles all reverse index writes. All reverse indexes are
@@ -1195,19 +2254,12 @@ impl IndexifyState {
         }
         match request.payload {
             RequestPayload::RegisterExecutor {
-                addr,
                 executor_id,
                 extractor,
-                ts_secs,
+                ..
             } => {
                 self.extractor_executors_table
                     .insert(&extractor.name, &executor_id);
-                let _executor_info = internal_api::ExecutorMetadata {
-                    id: executor_id.clone(),
-                    last_seen: ts_secs,
-                    addr: addr.clone(),
-                    extractor: extractor.clone(),
-                };
                 // initialize executor load at 0
                 self.executor_running_task_count.insert(&executor_id, 0);
             }
@@ -1248,6 +2300,7 @@ impl IndexifyState {
                 extraction_policy,
                 updated_structured_data_schema,
                 new_structured_data_schema,
+                operation: _,
             } => {
                 self.extraction_policies_table
                     .insert(&extraction_policy.namespace, &extraction_policy.id);
@@ -1259,9 +2312,39 @@ impl IndexifyState {
             RequestPayload::CreateNamespace {
                 name: _,
                 structured_data_schema,
+                operation: _,
             } => {
                 self.update_schema_reverse_idx(structured_data_schema);
             }
+            RequestPayload::DeleteExtractionPolicy {
+                extraction_policy,
+                index_ids,
+                tasks,
+            } => {
+                self.extraction_policies_table
+                    .remove(&extraction_policy.namespace, &extraction_policy.id);
+                for index_id in index_ids {
+                    self.namespace_index_table
+                        .remove(&extraction_policy.namespace, &index_id);
+                }
+                for task in tasks {
+                    if task.terminal_state() {
+                        self.unassigned_tasks.remove(&task.id);
+                        self.unfinished_tasks_by_extractor
+                            .remove(&task.extractor, &task.id);
+                    }
+                }
+            }
+            RequestPayload::DeleteNamespace { name, gc_tasks: _ } => {
+                self.extraction_policies_table.remove_namespace(&name);
+                self.namespace_index_table.remove_namespace(&name);
+                self.schemas_by_namespace.remove_namespace(&name);
+            }
+            RequestPayload::EvolveStructuredDataSchemas { schemas } => {
+                for schema in schemas {
+                    self.update_schema_reverse_idx(schema);
+                }
+            }
             RequestPayload::CreateIndex {
                 index: _,
                 namespace,
@@ -1283,6 +2366,13 @@ impl IndexifyState {
                         self.executor_running_task_count
                             .decrement_running_task_count(&executor_id);
                     }
+                } else if task.retrying() {
+                    //  The task is assigned again once its backoff passed
+                    self.unassigned_tasks.insert(&task.id);
+                    if let Some(executor_id) = executor_id {
+                        self.executor_running_task_count
+                            .decrement_running_task_count(&executor_id);
+                    }
                 }
                 for content in content_metadata {
                     self.content_namespace_table
@@ -1322,8 +2412,9 @@ impl IndexifyState {
     }
 
     /// This method is used to get the tasks assigned to an executor
-    /// It does this by looking up the TaskAssignments CF to get the task id's
-    /// and then using those id's to look up tasks via Tasks CF
+    /// It does this by reading the first task id's of the executor's queue in
+    /// the ExecutorTaskQueue CF, tasks of a higher priority first, and then
+    /// using those id's to look up tasks via Tasks CF
     pub fn get_tasks_for_executor(
         &self,
         executor_id: &str,
@@ -1332,27 +2423,31 @@ impl IndexifyState {
     ) -> Result<Vec<indexify_internal_api::Task>, StateMachineError> {
         //  NOTE: Don't do deserialization within the transaction
         let txn = db.transaction();
-        let task_ids_bytes = txn
-            .get_cf(StateMachineColumns::TaskAssignments.cf(db), executor_id)
-            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
-
-        let task_ids: Vec<String> = task_ids_bytes
-            .map(|task_id_bytes| {
-                JsonEncoder::decode(&task_id_bytes)
-                    .map_err(StateMachineError::from)
-                    .unwrap_or_else(|e| {
-                        error!("Failed to deserialize task id: {}", e);
-                        Vec::new()
-                    })
-            })
-            .unwrap_or_else(Vec::new);
+        let prefix = format!("{}|", executor_id);
+        let limit = limit.map(|limit| limit as usize).unwrap_or(usize::MAX);
+
+        let mut task_ids = Vec::new();
+        for item in txn.iterator_cf(
+            StateMachineColumns::ExecutorTaskQueue.cf(db),
+            rocksdb::IteratorMode::From(prefix.as_bytes(), rocksdb::Direction::Forward),
+        ) {
+            if task_ids.len() >= limit {
+                break;
+            }
+            let (key, value) =
+                item.map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            let task_id = String::from_utf8(value.to_vec()).map_err(|e| {
+                StateMachineError::DatabaseError(format!("unable to get task id from queue {}", e))
+            })?;
+            task_ids.push(task_id);
+        }
 
         // FIXME Use MULTIGET
-        let limit = limit.unwrap_or(task_ids.len() as u64) as usize;
-
-        let tasks: Result<Vec<indexify_internal_api::Task>, StateMachineError> = task_ids
+        task_ids
             .into_iter()
-            .take(limit)
             .map(|task_id| {
                 let task_bytes = txn
                     .get_cf(StateMachineColumns::Tasks.cf(db), task_id.as_bytes())
@@ -1362,8 +2457,7 @@ impl IndexifyState {
                     })?;
                 JsonEncoder::decode(&task_bytes).map_err(StateMachineError::from)
             })
-            .collect();
-        tasks
+            .collect()
     }
 
     /// This method will fetch indexes based on the id's of the indexes provided
@@ -1653,6 +2747,10 @@ impl IndexifyState {
         self.extraction_policies_table.inner()
     }
 
+    pub fn get_namespace_extraction_policy_count(&self, namespace: &NamespaceName) -> usize {
+        self.extraction_policies_table.get(namespace).len()
+    }
+
     pub fn get_extractor_executors_table(&self) -> HashMap<ExtractorName, HashSet<ExecutorId>> {
         self.extractor_executors_table.inner()
     }
@@ -1765,6 +2863,15 @@ impl IndexifyState {
     //  END SNAPSHOT METHODS
 }
 
+fn quota_exceeded(namespace: &str, quota: &str, limit: u64, requested: u64) -> StateMachineError {
+    StateMachineError::QuotaExceeded(NamespaceQuotaExceeded {
+        namespace: namespace.to_string(),
+        quota: quota.to_string(),
+        limit,
+        requested,
+    })
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Default)]
 pub struct IndexifyStateSnapshot {
     unassigned_tasks: HashSet<TaskId>,
@@ -1,5 +1,3 @@
-use std::collections::HashSet;
-
 use anyhow::Result;
 
 use self::planner::plan::TaskAllocationPlan;
@@ -24,7 +22,7 @@ impl TaskAllocator {
         }
     }
 
-    pub async fn allocate_tasks(&self, task_ids: HashSet<TaskId>) -> Result<TaskAllocationPlan> {
+    pub async fn allocate_tasks(&self, task_ids: Vec<TaskId>) -> Result<TaskAllocationPlan> {
         self.planner.plan_allocations(task_ids).await
     }
 
@@ -38,6 +36,8 @@ impl TaskAllocator {
             .shared_state
             .unfinished_tasks_by_extractor(extractor_name)
             .await?;
-        self.planner.plan_allocations(task_ids).await
+        self.planner
+            .plan_allocations(task_ids.into_iter().collect())
+            .await
     }
 }
@@ -1,6 +1,6 @@
 use std::{
     cmp::{Ordering, Reverse},
-    collections::{BinaryHeap, HashMap, HashSet},
+    collections::{BinaryHeap, HashMap},
 };
 
 use indexify_internal_api::ExecutorMetadata;
@@ -92,31 +92,31 @@ impl LoadAwareDistributor {
     /// the result.
     ///
     /// # Parameters
-    /// - `task_ids`: A set of `TaskId` representing the task IDs to be grouped.
+    /// - `task_ids`: The task IDs to be grouped, in the order they're
+    ///   allocated.
     ///
     /// # Returns
     /// A `HashMap` where each key is an `ExtractorName` associated with a
-    /// `HashSet` of `TaskId` that represents the grouped task IDs for that
-    /// extractor.
-    async fn group_tasks_by_extractor<'a>(
+    /// `Vec` of `TaskId` that represents the grouped task IDs for that
+    /// extractor, in the order they were given.
+    async fn group_tasks_by_extractor(
         &self,
-        task_ids: &'a HashSet<TaskId>,
-    ) -> HashMap<ExtractorName, HashSet<TaskId>> {
-        // let sm = self.shared_state.indexify_state.read().await;
+        task_ids: &[TaskId],
+    ) -> HashMap<ExtractorName, Vec<TaskId>> {
+        let unfinished_tasks_by_extractor =
+            self.shared_state.get_unfinished_tasks_by_extractor().await;
 
         // Initialize the result HashMap to collect the filtered task IDs by extractor.
-        let mut result = HashMap::new();
-        for (extractor, extractor_task_ids) in self
-            .shared_state
-            .get_unfinished_tasks_by_extractor()
-            .await
-            .iter()
-        {
-            let filtered_task_ids: HashSet<TaskId> =
-                extractor_task_ids.intersection(task_ids).cloned().collect();
-            if !filtered_task_ids.is_empty() {
-                // Only insert if there are actually task IDs to avoid empty entries.
-                result.insert(extractor.clone(), filtered_task_ids);
+        let mut result: HashMap<ExtractorName, Vec<TaskId>> = HashMap::new();
+        for task_id in task_ids {
+            if let Some((extractor, _)) = unfinished_tasks_by_extractor
+                .iter()
+                .find(|(_, extractor_task_ids)| extractor_task_ids.contains(task_id))
+            {
+                result
+                    .entry(extractor.clone())
+                    .or_default()
+                    .push(task_id.clone());
             }
         }
         result
@@ -166,6 +166,9 @@ impl LoadAwareDistributor {
                 })
                 .unwrap_or(None);
             match executor {
+                //  Draining executors finish the tasks they have but aren't given
+                // new ones
+                Some(executor) if executor.draining => {}
                 Some(executor) => {
                     let extractor_name = executor.extractor.name.clone();
 
@@ -212,17 +215,17 @@ impl AllocationPlanner for LoadAwareDistributor {
     /// running task count for each executor, updated as tasks are allocated.
     ///
     /// # Parameters
-    /// - `task_ids`: A `HashSet` of `TaskId` representing the tasks to be
-    ///   allocated. Each `TaskId` is unique and corresponds to a specific task
-    ///   that requires execution.
+    /// - `task_ids`: A `Vec` of `TaskId` representing the tasks to be
+    ///   allocated, in the order they're allocated. Each `TaskId` is unique and
+    ///   corresponds to a specific task that requires execution.
     ///
     /// # Returns
     /// Returns an `AllocationPlannerResult`, which is a result type that wraps
     /// a `TaskAllocationPlan`. The `TaskAllocationPlan` itself is a
     /// `HashMap` of `TaskId` to `ExecutorId`. If no tasks are provided (i.e.,
-    /// the `HashSet` is empty), the method returns an empty
+    /// the `Vec` is empty), the method returns an empty
     /// `TaskAllocationPlan`.
-    async fn plan_allocations(&self, task_ids: HashSet<TaskId>) -> AllocationPlannerResult {
+    async fn plan_allocations(&self, task_ids: Vec<TaskId>) -> AllocationPlannerResult {
         // Early return if there are no tasks to allocate
         if task_ids.is_empty() {
             return Ok(TaskAllocationPlan(HashMap::new()));
@@ -304,6 +307,9 @@ mod tests {
             input_params: json!(null),
             outcome: internal_api::TaskOutcome::Unknown,
             index_tables: vec![],
+            attempts: 0,
+            retry_at: None,
+            priority: 0,
         }
     }
 
@@ -386,7 +392,7 @@ mod tests {
         .unwrap();
         shared_state.initialize_raft().await.unwrap();
 
-        let task_ids: HashSet<TaskId> = shared_state
+        let task_ids: Vec<TaskId> = shared_state
             .state_machine
             .get_all_rows_from_cf::<internal_api::Task>(StateMachineColumns::Tasks)
             .await?
@@ -422,7 +428,12 @@ mod tests {
 
         // Add extractors and extractor bindings and ensure that we are creating tasks
         let state_change_id = shared_state
-            .register_executor("localhost:8956", "test_executor_id", mock_extractor())
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
             .await?;
 
         let task = create_task("test-task", &mock_extractor().name, "test-binding");
@@ -432,7 +443,7 @@ mod tests {
 
         let distributor = LoadAwareDistributor::new(shared_state.clone());
         let result = distributor
-            .plan_allocations(HashSet::from([task.clone().id.clone()]))
+            .plan_allocations(vec![task.clone().id.clone()])
             .await?;
 
         // Verify that the tasks are allocated
@@ -442,6 +453,86 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_no_tasks_allocated_to_draining_executor() -> Result<(), anyhow::Error> {
+        let config = Arc::new(ServerConfig::default());
+        std::fs::remove_dir_all(config.state_store.clone().path.unwrap()).unwrap();
+        let garbage_collector = crate::garbage_collector::GarbageCollector::new();
+        let shared_state = App::new(
+            config.clone(),
+            None,
+            Arc::clone(&garbage_collector),
+            &config.coordinator_addr,
+        )
+        .await
+        .unwrap();
+        shared_state.initialize_raft().await.unwrap();
+
+        let state_change_id = shared_state
+            .register_executor(
+                "localhost:8956",
+                "draining_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        shared_state
+            .register_executor(
+                "localhost:8957",
+                "test_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        shared_state.drain_executor("draining_executor_id").await?;
+        assert!(
+            shared_state
+                .get_executor_by_id("draining_executor_id")
+                .await?
+                .draining
+        );
+
+        let tasks: Vec<_> = (0..4)
+            .map(|i| {
+                create_task(
+                    &format!("test-task-{}", i),
+                    &mock_extractor().name,
+                    "test-binding",
+                )
+            })
+            .collect();
+        shared_state
+            .create_tasks(tasks.clone(), &state_change_id)
+            .await?;
+        let distributor = LoadAwareDistributor::new(shared_state.clone());
+        let result = distributor
+            .plan_allocations(tasks.iter().map(|task| task.id.clone()).collect())
+            .await?;
+        assert_eq!(result.0.len(), 4);
+        assert!(result
+            .0
+            .values()
+            .all(|executor_id| executor_id == "test_executor_id"));
+
+        //  Registering again, e.g. after an upgrade, ends the drain
+        shared_state
+            .register_executor(
+                "localhost:8956",
+                "draining_executor_id",
+                mock_extractor(),
+                HashMap::new(),
+            )
+            .await?;
+        assert!(
+            !shared_state
+                .get_executor_by_id("draining_executor_id")
+                .await?
+                .draining
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     #[tracing_test::traced_test]
     async fn test_round_robin_distribution() -> Result<(), anyhow::Error> {
@@ -480,6 +571,7 @@ mod tests {
                     format!("localhost:{}", 8955 + i).as_str(),
                     format!("text_executor{}", i).as_str(),
                     text_extractor.clone(),
+                    HashMap::new(),
                 )
                 .await?;
             state_change_ids.push(state_change_id);
@@ -488,6 +580,7 @@ mod tests {
                     format!("localhost:{}", 8965 + i).as_str(),
                     format!("json_executor{}", i).as_str(),
                     json_extractor.clone(),
+                    HashMap::new(),
                 )
                 .await?;
             state_change_ids.push(state_change_id);
@@ -706,6 +799,7 @@ mod tests {
                     text_executors[i - 1].0.as_str(),
                     text_executors[i - 1].1.as_str(),
                     text_extractor.clone(),
+                    HashMap::new(),
                 )
             }))
             .await
@@ -726,6 +820,7 @@ mod tests {
                 json_executors[i - 1].0.as_str(),
                 json_executors[i - 1].1.as_str(),
                 json_extractor.clone(),
+                HashMap::new(),
             )
         }))
         .await;
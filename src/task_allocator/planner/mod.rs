@@ -1,7 +1,5 @@
 pub mod load_aware_distributor;
 pub mod plan;
-use std::collections::HashSet;
-
 use plan::TaskAllocationPlan;
 
 use crate::state::store::TaskId;
@@ -10,5 +8,7 @@ pub type AllocationPlannerResult = Result<TaskAllocationPlan, anyhow::Error>;
 
 #[async_trait::async_trait]
 pub trait AllocationPlanner {
-    async fn plan_allocations(&self, tasks: HashSet<TaskId>) -> AllocationPlannerResult;
+    /// Plans the allocation of the tasks, which are allocated in the order
+    /// they're given
+    async fn plan_allocations(&self, tasks: Vec<TaskId>) -> AllocationPlannerResult;
 }
@@ -16,6 +16,11 @@ pub mod db_utils {
             internal_api::OutputSchema::Embedding(internal_api::EmbeddingSchema {
                 dim: 384,
                 distance: "cosine".to_string(),
+                quantization: None,
+                named_vectors: vec![],
+                ttl_secs: None,
+                sparse: false,
+                index_params: None,
             }),
         );
         internal_api::ExtractorDescription {
@@ -48,6 +48,11 @@ impl RaftTestCluster {
                 raft_port: port + 1,
                 state_store: StateStoreConfig {
                     path: Some(format!("/tmp/indexify-test/raft/{}/{}", append, i)),
+                    backup: None,
+                    rocksdb_backup: None,
+                    follower_reads: None,
+                    log: Default::default(),
+                    max_proposal_batch_size: 256,
                 },
                 seed_node: seed_node.clone(),
                 ..Default::default()
@@ -9,6 +9,12 @@ pub fn timestamp_secs() -> u64 {
     duration.as_secs()
 }
 
+pub fn timestamp_millis() -> u64 {
+    let now = SystemTime::now();
+    let duration = now.duration_since(UNIX_EPOCH).unwrap();
+    duration.as_millis() as u64
+}
+
 #[derive(Debug, Clone)]
 pub struct PostgresIndexName(String);
 
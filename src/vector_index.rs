@@ -12,12 +12,26 @@ use crate::{
     api,
     blob_storage::ContentReader,
     coordinator_client::CoordinatorClient,
+    embedding_write_buffer::EmbeddingWriteBuffer,
     extractor_router::ExtractorRouter,
-    vectordbs::{CreateIndexParams, IndexDistance, VectorChunk, VectorDBTS},
+    keyword_index::{is_keyword_schema, KeywordIndex},
+    remote_extractors::RemoteExtractors,
+    server_config::EmbeddingWriteBufferConfig,
+    vectordbs::{
+        CreateIndexParams,
+        IndexDistance,
+        IndexStats,
+        SearchResult,
+        SparseVectorChunk,
+        VectorChunk,
+        VectorDBTS,
+    },
 };
 
 pub struct VectorIndexManager {
     vector_db: VectorDBTS,
+    keyword_index: Arc<KeywordIndex>,
+    write_buffer: EmbeddingWriteBuffer,
     extractor_router: ExtractorRouter,
     coordinator_client: Arc<CoordinatorClient>,
     content_reader: Arc<ContentReader>,
@@ -35,14 +49,27 @@ pub struct ScoredText {
     pub mime_type: String,
     pub labels: HashMap<String, String>,
     pub confidence_score: f32,
+    pub external_id: String,
+    pub created_at: i64,
+    pub parent_id: String,
+    pub duplicate_content_ids: Vec<String>,
 }
 
 impl VectorIndexManager {
-    pub fn new(coordinator_client: Arc<CoordinatorClient>, vector_db: VectorDBTS) -> Result<Self> {
-        let extractor_router = ExtractorRouter::new(coordinator_client.clone())?;
-        let content_reader = Arc::new(ContentReader::new());
+    pub fn new(
+        coordinator_client: Arc<CoordinatorClient>,
+        vector_db: VectorDBTS,
+        keyword_index: Arc<KeywordIndex>,
+        remote_extractors: Arc<RemoteExtractors>,
+        write_buffer_config: EmbeddingWriteBufferConfig,
+        content_reader: Arc<ContentReader>,
+    ) -> Result<Self> {
+        let extractor_router = ExtractorRouter::new(coordinator_client.clone(), remote_extractors)?;
+        let write_buffer = EmbeddingWriteBuffer::new(vector_db.clone(), write_buffer_config);
         Ok(Self {
             vector_db,
+            keyword_index,
+            write_buffer,
             extractor_router,
             coordinator_client: coordinator_client.clone(),
             content_reader,
@@ -54,21 +81,138 @@ impl VectorIndexManager {
         index_name: &str,
         schema: internal_api::EmbeddingSchema,
     ) -> Result<String> {
+        self.create_sharded_index(index_name, schema, None).await
+    }
+
+    pub async fn create_sharded_index(
+        &self,
+        index_name: &str,
+        schema: internal_api::EmbeddingSchema,
+        shard_count: Option<u32>,
+    ) -> Result<String> {
+        if schema.sparse {
+            return self
+                .create_sparse_index(index_name, schema, shard_count)
+                .await;
+        }
         let create_index_params = CreateIndexParams {
             vectordb_index_name: index_name.to_string(),
             vector_dim: schema.dim as u64,
             distance: IndexDistance::from_str(schema.distance.as_str())?,
             unique_params: None,
+            shard_count,
+            quantization: schema.quantization,
+            index_params: schema.index_params,
         };
         info!("Creating index: {:?}", create_index_params);
         self.vector_db.create_index(create_index_params).await?;
         Ok(index_name.to_string())
     }
 
+    async fn create_sparse_index(
+        &self,
+        index_name: &str,
+        schema: internal_api::EmbeddingSchema,
+        shard_count: Option<u32>,
+    ) -> Result<String> {
+        if !matches!(
+            IndexDistance::from_str(&schema.distance)?,
+            IndexDistance::Dot
+        ) {
+            return Err(anyhow!(
+                "sparse index {} can only use the dot distance",
+                index_name
+            ));
+        }
+        if schema.quantization.is_some() {
+            return Err(anyhow!("sparse index {} can't be quantized", index_name));
+        }
+        if !schema.named_vectors.is_empty() {
+            return Err(anyhow!(
+                "sparse index {} can't have named vectors",
+                index_name
+            ));
+        }
+        info!("Creating sparse index: {}", index_name);
+        self.vector_db
+            .create_sparse_index(index_name, shard_count)
+            .await?;
+        Ok(index_name.to_string())
+    }
+
+    pub async fn create_keyword_index(
+        &self,
+        index_name: &str,
+        schema: internal_api::KeywordIndexSchema,
+    ) -> Result<String> {
+        let keyword_index = self.keyword_index.clone();
+        let table_name = index_name.to_string();
+        tokio::task::spawn_blocking(move || keyword_index.create_index(&table_name, &schema))
+            .await??;
+        Ok(index_name.to_string())
+    }
+
     pub async fn drop_index(&self, index_name: &str) -> Result<()> {
+        if self.keyword_index.has_index(index_name) {
+            return self.keyword_index.drop_index(index_name);
+        }
+        self.write_buffer.close(index_name);
         self.vector_db.drop_index(index_name).await
     }
 
+    pub fn vector_store(&self) -> String {
+        self.vector_db.name()
+    }
+
+    pub async fn index_stats(&self, index_name: &str) -> Result<IndexStats> {
+        if self.keyword_index.has_index(index_name) {
+            let keyword_index = self.keyword_index.clone();
+            let table_name = index_name.to_string();
+            return tokio::task::spawn_blocking(move || keyword_index.stats(&table_name)).await?;
+        }
+        self.vector_db.index_stats(index_name).await
+    }
+
+    pub async fn warm_up(&self, index_name: &str) -> Result<()> {
+        if self.keyword_index.has_index(index_name) {
+            let keyword_index = self.keyword_index.clone();
+            let table_name = index_name.to_string();
+            return tokio::task::spawn_blocking(move || keyword_index.warm_up(&table_name)).await?;
+        }
+        self.vector_db.warm_up(index_name).await
+    }
+
+    /// Makes the embeddings written to the index so far visible to searches,
+    /// writing the buffered ones right away. Keyword indexes are reloaded on
+    /// every write and are always up to date.
+    pub async fn refresh(&self, index_name: &str) -> Result<()> {
+        if self.keyword_index.has_index(index_name) {
+            return Ok(());
+        }
+        self.write_buffer.flush(index_name).await;
+        self.vector_db.refresh(index_name).await
+    }
+
+    /// Reads the text of the content from blob storage and adds it to a
+    /// keyword index
+    pub async fn add_content_text(
+        &self,
+        index_name: &str,
+        content: &indexify_coordinator::ContentMetadata,
+    ) -> Result<()> {
+        let bytes = self
+            .content_reader
+            .verified_bytes(&content.storage_url, &content.hash)
+            .await?;
+        let text = String::from_utf8(bytes.to_vec())?;
+        let keyword_index = self.keyword_index.clone();
+        let table_name = index_name.to_string();
+        let texts = vec![(content.id.clone(), text)];
+        tokio::task::spawn_blocking(move || keyword_index.add_texts(&table_name, texts)).await?
+    }
+
+    /// Adds the embeddings to the write buffer of the index and waits until
+    /// they're written to the vector store.
     pub async fn add_embedding(
         &self,
         vector_index_name: &str,
@@ -83,16 +227,87 @@ impl VectorIndexManager {
             );
             vector_chunks.push(vector_chunk);
         });
+        self.write_buffer
+            .write(vector_index_name, vector_chunks)
+            .await
+    }
+
+    /// Writes sparse embeddings to the index. They aren't buffered, the write
+    /// buffer batches dense embeddings only.
+    pub async fn add_sparse_embedding(
+        &self,
+        index_name: &str,
+        content_id: &str,
+        vector: internal_api::SparseVector,
+        metadata: serde_json::Value,
+    ) -> Result<()> {
         self.vector_db
-            .add_embedding(vector_index_name, vector_chunks)
-            .await?;
+            .add_sparse_embedding(
+                index_name,
+                vec![SparseVectorChunk {
+                    content_id: content_id.to_string(),
+                    vector,
+                    metadata,
+                }],
+            )
+            .await
+    }
+
+    /// Copies the embeddings of the given content from one index to another
+    /// and returns the ids of the content which was copied. Content without
+    /// an embedding in the source index is skipped.
+    pub async fn copy_embeddings(
+        &self,
+        source_index: &str,
+        target_index: &str,
+        content_ids: Vec<String>,
+    ) -> Result<Vec<String>> {
+        let chunks = self.vector_db.get_points(source_index, content_ids).await?;
+        let copied = chunks
+            .iter()
+            .map(|chunk| chunk.content_id.clone())
+            .collect_vec();
+        if !chunks.is_empty() {
+            self.vector_db.add_embedding(target_index, chunks).await?;
+        }
+        Ok(copied)
+    }
+
+    /// Stores the embedding of a content under the id of another content in
+    /// the same index. Nothing is written if the content has no embedding.
+    pub async fn copy_embedding(
+        &self,
+        index: &str,
+        content_id: &str,
+        target_content_id: &str,
+    ) -> Result<()> {
+        let chunks = self
+            .vector_db
+            .get_points(index, vec![content_id.to_string()])
+            .await?
+            .into_iter()
+            .map(|chunk| {
+                VectorChunk::new(
+                    target_content_id.to_string(),
+                    chunk.embedding,
+                    chunk.metadata,
+                )
+            })
+            .collect_vec();
+        if !chunks.is_empty() {
+            self.vector_db.add_embedding(index, chunks).await?;
+        }
         Ok(())
     }
 
-    pub async fn remove_embedding(&self, vector_index_name: &str, content_id: &str) -> Result<()> {
-        self.vector_db
-            .remove_embedding(vector_index_name, content_id)
-            .await?;
+    /// Deletes the embedding, or the text in keyword indexes, stored for the
+    /// content so it stops matching queries. Deleting content without an
+    /// entry in the index succeeds.
+    pub async fn delete_by_content_id(&self, index: &str, content_id: &str) -> Result<()> {
+        if self.keyword_index.has_index(index) {
+            return self.keyword_index.remove_content(index, content_id);
+        }
+        self.vector_db.remove_embedding(index, content_id).await?;
         Ok(())
     }
 
@@ -115,7 +330,124 @@ impl VectorIndexManager {
             .await
     }
 
-    pub async fn search(&self, index: Index, query: &str, k: usize) -> Result<Vec<ScoredText>> {
+    /// Searches the index, or one of its named vectors if a vector is given.
+    pub async fn search(
+        &self,
+        index: Index,
+        query: &str,
+        k: usize,
+        vector: Option<&str>,
+    ) -> Result<Vec<ScoredText>> {
+        let search_result = if is_keyword_schema(&index.schema) {
+            if let Some(vector) = vector {
+                return Err(anyhow!(
+                    "index {} is a keyword index and has no vector {}",
+                    index.name,
+                    vector
+                ));
+            }
+            let keyword_index = self.keyword_index.clone();
+            let query = query.to_string();
+            tokio::task::spawn_blocking(move || keyword_index.search(&index.table_name, &query, k))
+                .await??
+        } else {
+            //  Chunk references are kept for the index table, named vectors
+            // share them
+            let table_name = index.table_name.clone();
+            let search_result = self.search_embeddings(index, query, k, vector).await?;
+            self.expand_chunk_references(&table_name, search_result, k)
+                .await?
+        };
+        self.scored_texts(search_result).await
+    }
+
+    //  Embeddings of identical content are only stored once, under the id of
+    // the first content. Every other content sharing the embedding is added
+    // to the results with the same score.
+    async fn expand_chunk_references(
+        &self,
+        table_name: &str,
+        search_result: Vec<SearchResult>,
+        k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let req = indexify_coordinator::GetContentMetadataRequest {
+            content_list: search_result
+                .iter()
+                .map(|r| r.content_id.clone())
+                .collect_vec(),
+            consistency: indexify_coordinator::ReadConsistency::Bounded as i32,
+        };
+        let hashes = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_content_metadata(req)
+            .await?
+            .into_inner()
+            .content_list
+            .into_iter()
+            .filter(|(_, content)| !content.hash.is_empty())
+            .map(|(id, content)| (id, content.hash))
+            .collect::<HashMap<_, _>>();
+        if hashes.is_empty() {
+            return Ok(search_result);
+        }
+        let req = indexify_coordinator::GetChunkReferencesRequest {
+            index_table: table_name.to_string(),
+            hashes: hashes.values().cloned().collect(),
+        };
+        let references = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_chunk_references(req)
+            .await?
+            .into_inner()
+            .references
+            .into_iter()
+            .map(|reference| (reference.hash.clone(), reference.content_ids))
+            .collect::<HashMap<_, _>>();
+        let mut expanded = Vec::new();
+        for result in search_result {
+            let shared_with = hashes
+                .get(&result.content_id)
+                .and_then(|hash| references.get(hash))
+                .map(|content_ids| {
+                    content_ids
+                        .iter()
+                        .filter(|id| **id != result.content_id)
+                        .cloned()
+                        .collect_vec()
+                })
+                .unwrap_or_default();
+            let confidence_score = result.confidence_score;
+            expanded.push(result);
+            expanded.extend(shared_with.into_iter().map(|content_id| SearchResult {
+                content_id,
+                confidence_score,
+            }));
+        }
+        expanded.truncate(k);
+        Ok(expanded)
+    }
+
+    async fn search_embeddings(
+        &self,
+        index: Index,
+        query: &str,
+        k: usize,
+        vector: Option<&str>,
+    ) -> Result<Vec<SearchResult>> {
+        let schema: internal_api::EmbeddingSchema = serde_json::from_str(&index.schema)?;
+        let table_name = match vector {
+            Some(vector) => {
+                if !schema.named_vectors.iter().any(|name| name == vector) {
+                    return Err(anyhow!("index {} has no vector {}", index.name, vector));
+                }
+                internal_api::EmbeddingSchema::named_vector_table(&index.table_name, vector)
+            }
+            None => index.table_name.clone(),
+        };
         let content = api::Content {
             content_type: mime::TEXT_PLAIN.to_string(),
             bytes: query.as_bytes().into(),
@@ -133,16 +465,64 @@ impl VectorIndexManager {
             .ok_or(anyhow!("No embeddings were extracted"))?;
         let embedding: internal_api::Embedding =
             serde_json::from_value(feature.data.clone()).map_err(|e| anyhow!(e.to_string()))?;
-        let search_result = self
-            .vector_db
-            .search(index.table_name, embedding.values, k as u64)
-            .await?;
+        if schema.sparse {
+            return self
+                .vector_db
+                .sparse_search(table_name, embedding.sparse_vector(), k as u64)
+                .await;
+        }
+        let query_embedding = embedding
+            .vector(vector)
+            .ok_or(anyhow!(
+                "extractor {} didn't embed the query for vector {}",
+                index.extractor,
+                vector.unwrap_or_default()
+            ))?
+            .clone();
+        self.vector_db
+            .hybrid_search(table_name, query, query_embedding, k as u64)
+            .await
+    }
+
+    /// Runs the extractor on the content and returns the embedding of the
+    /// output, which is None if the extractor didn't produce that output.
+    pub async fn extract_embedding(
+        &self,
+        extractor: &str,
+        content: api::Content,
+        input_params: Option<serde_json::Value>,
+        output: &str,
+    ) -> Result<Option<internal_api::Embedding>> {
+        let feature = self
+            .extractor_router
+            .extract_content(extractor, content, input_params)
+            .await
+            .map_err(|e| anyhow!("unable to extract embedding: {}", e.to_string()))?
+            .features
+            .into_iter()
+            .find(|feature| {
+                feature.name == output &&
+                    matches!(feature.feature_type, internal_api::FeatureType::Embedding)
+            });
+        match feature {
+            Some(feature) => {
+                let embedding: internal_api::Embedding =
+                    serde_json::from_value(feature.data).map_err(|e| anyhow!(e.to_string()))?;
+                Ok(Some(embedding))
+            }
+            None => Ok(None),
+        }
+    }
+
+    //  Looks up the metadata and the text of the content in the search results
+    async fn scored_texts(&self, search_result: Vec<SearchResult>) -> Result<Vec<ScoredText>> {
         let content_ids = search_result
             .iter()
             .map(|r| r.content_id.clone())
             .collect_vec();
         let req = indexify_coordinator::GetContentMetadataRequest {
             content_list: content_ids.clone(),
+            consistency: indexify_coordinator::ReadConsistency::Bounded as i32,
         };
         let content_metadata_list = self
             .coordinator_client
@@ -163,7 +543,9 @@ impl VectorIndexManager {
         let mut content_bytes_list = Vec::new();
         let mut content_ids = Vec::new();
         for (id, content_meta) in &content_metadata_list {
-            let content = self.content_reader.bytes(&content_meta.storage_url);
+            let content = self
+                .content_reader
+                .verified_bytes(&content_meta.storage_url, &content_meta.hash);
             content_bytes_list.push(content);
             content_ids.push(id.clone());
         }
@@ -197,6 +579,20 @@ impl VectorIndexManager {
                 .unwrap()
                 .labels
                 .clone();
+            let external_id = content_metadata_list
+                .get(&result.content_id)
+                .unwrap()
+                .external_id
+                .clone();
+            let created_at = content_metadata_list
+                .get(&result.content_id)
+                .unwrap()
+                .created_at;
+            let parent_id = content_metadata_list
+                .get(&result.content_id)
+                .unwrap()
+                .parent_id
+                .clone();
             let text = if mime_type.starts_with("text/") {
                 String::from_utf8(content.to_vec()).unwrap()
             } else {
@@ -208,6 +604,10 @@ impl VectorIndexManager {
                 mime_type,
                 labels,
                 confidence_score: result.confidence_score,
+                external_id,
+                created_at,
+                parent_id,
+                duplicate_content_ids: Vec::new(),
             };
             index_search_results.push(search_result);
         }
@@ -0,0 +1,725 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rand::Rng;
+use rocksdb::{ColumnFamily, ColumnFamilyDescriptor, IteratorMode, Options, WriteBatch, DB};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{CreateIndexParams, IndexStats, VectorDb};
+use crate::{
+    server_config::EmbeddedHnswConfig,
+    vectordbs::{IndexDistance, SearchResult, VectorChunk},
+};
+
+//  Column family of the parameters of every index, by index name
+const INDEXES_CF: &str = "indexes";
+
+//  Column family of the embeddings and metadata, by index name and content id
+const VECTORS_CF: &str = "vectors";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexParams {
+    dim: usize,
+    distance: IndexDistance,
+    //  Tuning of the graph of the index, the configured values are used when
+    // not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    m: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ef_construction: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ef_search: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredChunk {
+    embedding: Vec<f32>,
+    metadata: Value,
+}
+
+fn vector_key(index: &str, content_id: &str) -> Vec<u8> {
+    let mut key = index_prefix(index);
+    key.extend_from_slice(content_id.as_bytes());
+    key
+}
+
+fn index_prefix(index: &str) -> Vec<u8> {
+    let mut prefix = index.as_bytes().to_vec();
+    prefix.push(0);
+    prefix
+}
+
+//  Distance between two embeddings, smaller is closer
+fn distance(metric: &IndexDistance, a: &[f32], b: &[f32]) -> f32 {
+    match metric {
+        IndexDistance::Cosine => {
+            let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+            for (x, y) in a.iter().zip(b) {
+                dot += x * y;
+                norm_a += x * x;
+                norm_b += y * y;
+            }
+            if norm_a == 0.0 || norm_b == 0.0 {
+                return 1.0;
+            }
+            1.0 - dot / (norm_a.sqrt() * norm_b.sqrt())
+        }
+        IndexDistance::Dot => -a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>(),
+        IndexDistance::Euclidean => a
+            .iter()
+            .zip(b)
+            .map(|(x, y)| (x - y) * (x - y))
+            .sum::<f32>()
+            .sqrt(),
+    }
+}
+
+//  Score of a result in the same terms as the other vector stores: the
+// similarity for cosine and dot product, the distance for euclidean
+fn score(metric: &IndexDistance, distance: f32) -> f32 {
+    match metric {
+        IndexDistance::Cosine => 1.0 - distance,
+        IndexDistance::Dot => -distance,
+        IndexDistance::Euclidean => distance,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    distance: f32,
+    node: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.node.cmp(&other.node))
+    }
+}
+
+struct Node {
+    content_id: String,
+    embedding: Vec<f32>,
+    //  Neighbors of the node on every layer up to its level
+    neighbors: Vec<Vec<usize>>,
+    deleted: bool,
+}
+
+/// Hierarchical navigable small world graph of the embeddings of an index.
+/// Removed embeddings stay in the graph to keep it connected and are skipped
+/// in the results, the graph is rebuilt once most of it is removed.
+struct HnswGraph {
+    params: IndexParams,
+    m: usize,
+    ef_construction: usize,
+    nodes: Vec<Node>,
+    ids: HashMap<String, usize>,
+    entry_point: Option<usize>,
+}
+
+impl HnswGraph {
+    fn new(params: IndexParams, m: usize, ef_construction: usize) -> Self {
+        Self {
+            params,
+            m: m.max(2),
+            ef_construction,
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+            entry_point: None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    //  Approximate size of the embeddings and links of the graph in memory,
+    // including removed embeddings which haven't been dropped yet
+    fn memory_bytes(&self) -> usize {
+        self.nodes
+            .iter()
+            .map(|node| {
+                std::mem::size_of::<Node>() +
+                    node.content_id.len() +
+                    node.embedding.len() * std::mem::size_of::<f32>() +
+                    node.neighbors
+                        .iter()
+                        .map(|layer| {
+                            std::mem::size_of::<Vec<usize>>() +
+                                layer.len() * std::mem::size_of::<usize>()
+                        })
+                        .sum::<usize>()
+            })
+            .sum()
+    }
+
+    fn distance(&self, query: &[f32], node: usize) -> f32 {
+        distance(&self.params.distance, query, &self.nodes[node].embedding)
+    }
+
+    fn max_neighbors(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.m * 2
+        } else {
+            self.m
+        }
+    }
+
+    fn random_level(&self) -> usize {
+        let ml = 1.0 / (self.m as f64).ln();
+        let r: f64 = rand::thread_rng().gen_range(f64::EPSILON..1.0);
+        (-r.ln() * ml).floor() as usize
+    }
+
+    //  Nearest `ef` nodes to the query on the layer, closest first
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[usize],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<usize> = entry_points.iter().copied().collect();
+        let mut candidates = BinaryHeap::new();
+        let mut nearest = BinaryHeap::new();
+        for &node in entry_points {
+            let candidate = Candidate {
+                distance: self.distance(query, node),
+                node,
+            };
+            candidates.push(Reverse(candidate));
+            nearest.push(candidate);
+        }
+        while let Some(Reverse(candidate)) = candidates.pop() {
+            let furthest = nearest.peek().map(|c: &Candidate| c.distance);
+            if nearest.len() >= ef && furthest.map_or(false, |d| candidate.distance > d) {
+                break;
+            }
+            for &neighbor in &self.nodes[candidate.node].neighbors[layer] {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let neighbor = Candidate {
+                    distance: self.distance(query, neighbor),
+                    node: neighbor,
+                };
+                let furthest = nearest.peek().map(|c: &Candidate| c.distance);
+                if nearest.len() < ef || furthest.map_or(true, |d| neighbor.distance < d) {
+                    candidates.push(Reverse(neighbor));
+                    nearest.push(neighbor);
+                    if nearest.len() > ef {
+                        nearest.pop();
+                    }
+                }
+            }
+        }
+        nearest.into_sorted_vec()
+    }
+
+    //  Greedy search from the entry point down to the layer above `layer`
+    fn descend(&self, query: &[f32], layer: usize) -> Option<usize> {
+        let mut entry_point = self.entry_point?;
+        let top = self.nodes[entry_point].neighbors.len() - 1;
+        for l in (layer + 1..=top).rev() {
+            entry_point = self.search_layer(query, &[entry_point], 1, l)[0].node;
+        }
+        Some(entry_point)
+    }
+
+    fn insert(&mut self, content_id: String, embedding: Vec<f32>) {
+        self.remove(&content_id);
+        let level = self.random_level();
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            content_id: content_id.clone(),
+            embedding,
+            neighbors: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.ids.insert(content_id, node);
+
+        let query = self.nodes[node].embedding.clone();
+        let Some(mut entry_point) = self.descend(&query, level) else {
+            self.entry_point = Some(node);
+            return;
+        };
+        let top = self.nodes[entry_point].neighbors.len() - 1;
+        for layer in (0..=level.min(top)).rev() {
+            let nearest = self.search_layer(&query, &[entry_point], self.ef_construction, layer);
+            entry_point = nearest[0].node;
+            let neighbors: Vec<usize> = nearest.iter().take(self.m).map(|c| c.node).collect();
+            for &neighbor in &neighbors {
+                self.nodes[neighbor].neighbors[layer].push(node);
+                self.prune(neighbor, layer);
+            }
+            self.nodes[node].neighbors[layer] = neighbors;
+        }
+        if level > top {
+            self.entry_point = Some(node);
+        }
+    }
+
+    //  Keeps the closest neighbors of the node on the layer
+    fn prune(&mut self, node: usize, layer: usize) {
+        let max_neighbors = self.max_neighbors(layer);
+        if self.nodes[node].neighbors[layer].len() <= max_neighbors {
+            return;
+        }
+        let embedding = &self.nodes[node].embedding;
+        let mut neighbors: Vec<Candidate> = self.nodes[node].neighbors[layer]
+            .iter()
+            .map(|&neighbor| Candidate {
+                distance: distance(
+                    &self.params.distance,
+                    embedding,
+                    &self.nodes[neighbor].embedding,
+                ),
+                node: neighbor,
+            })
+            .collect();
+        neighbors.sort();
+        self.nodes[node].neighbors[layer] = neighbors
+            .into_iter()
+            .take(max_neighbors)
+            .map(|c| c.node)
+            .collect();
+    }
+
+    fn remove(&mut self, content_id: &str) {
+        if let Some(node) = self.ids.remove(content_id) {
+            self.nodes[node].deleted = true;
+        }
+        //  Rebuild the graph once most of it is removed embeddings
+        if self.nodes.len() > 16 && self.ids.len() * 2 < self.nodes.len() {
+            let nodes = std::mem::take(&mut self.nodes);
+            self.ids.clear();
+            self.entry_point = None;
+            for node in nodes.into_iter().filter(|node| !node.deleted) {
+                self.insert(node.content_id, node.embedding);
+            }
+        }
+    }
+
+    fn search(&self, query: &[f32], k: usize, ef_search: usize) -> Vec<SearchResult> {
+        let Some(entry_point) = self.descend(query, 0) else {
+            return vec![];
+        };
+        //  Look further when removed embeddings take up room in the results
+        let ef = ef_search.max(k) + (self.nodes.len() - self.ids.len()).min(ef_search);
+        self.search_layer(query, &[entry_point], ef, 0)
+            .into_iter()
+            .filter(|c| !self.nodes[c.node].deleted)
+            .take(k)
+            .map(|c| SearchResult {
+                content_id: self.nodes[c.node].content_id.clone(),
+                confidence_score: score(&self.params.distance, c.distance),
+            })
+            .collect()
+    }
+}
+
+/// Vector index kept in process, for single node deployments without an
+/// external vector database. Embeddings and metadata are stored in RocksDB
+/// and the HNSW graph of an index is built in memory from them the first
+/// time the index is used after a restart.
+pub struct EmbeddedHnsw {
+    config: EmbeddedHnswConfig,
+    db: DB,
+    graphs: RwLock<HashMap<String, Arc<RwLock<HnswGraph>>>>,
+}
+
+impl std::fmt::Debug for EmbeddedHnsw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmbeddedHnsw")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl EmbeddedHnsw {
+    pub fn new(config: EmbeddedHnswConfig) -> Result<EmbeddedHnsw> {
+        let mut db_opts = Options::default();
+        db_opts.create_missing_column_families(true);
+        db_opts.create_if_missing(true);
+        let column_families = [INDEXES_CF, VECTORS_CF]
+            .into_iter()
+            .map(|name| ColumnFamilyDescriptor::new(name, Options::default()));
+        let db = DB::open_cf_descriptors(&db_opts, &config.path, column_families)
+            .map_err(|e| anyhow!("unable to open hnsw store at {}: {}", config.path, e))?;
+        Ok(Self {
+            config,
+            db,
+            graphs: RwLock::new(HashMap::new()),
+        })
+    }
+
+    fn cf(&self, name: &str) -> Result<&ColumnFamily> {
+        self.db
+            .cf_handle(name)
+            .ok_or(anyhow!("column family {} not found", name))
+    }
+
+    fn index_params(&self, index: &str) -> Result<Option<IndexParams>> {
+        self.db
+            .get_cf(self.cf(INDEXES_CF)?, index)?
+            .map(|params| serde_json::from_slice(&params))
+            .transpose()
+            .map_err(|e| anyhow!("unable to parse hnsw index params: {}", e))
+    }
+
+    fn get_chunk(&self, index: &str, content_id: &str) -> Result<Option<StoredChunk>> {
+        self.db
+            .get_cf(self.cf(VECTORS_CF)?, vector_key(index, content_id))?
+            .map(|chunk| serde_json::from_slice(&chunk))
+            .transpose()
+            .map_err(|e| anyhow!("unable to parse hnsw embedding: {}", e))
+    }
+
+    //  Returns the graph of the index, building it from the stored embeddings
+    // if it isn't loaded yet
+    fn graph(&self, index: &str) -> Result<Arc<RwLock<HnswGraph>>> {
+        if let Some(graph) = self.graphs.read().unwrap().get(index) {
+            return Ok(graph.clone());
+        }
+        let mut graphs = self.graphs.write().unwrap();
+        if let Some(graph) = graphs.get(index) {
+            return Ok(graph.clone());
+        }
+        let params = self
+            .index_params(index)?
+            .ok_or(anyhow!("hnsw index {} not found", index))?;
+        let mut graph = self.new_graph(params);
+        let prefix = index_prefix(index);
+        let iter = self.db.iterator_cf(
+            self.cf(VECTORS_CF)?,
+            IteratorMode::From(&prefix, rocksdb::Direction::Forward),
+        );
+        for item in iter {
+            let (key, value) = item?;
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            let content_id = String::from_utf8(key[prefix.len()..].to_vec())?;
+            let chunk: StoredChunk = serde_json::from_slice(&value)
+                .map_err(|e| anyhow!("unable to parse hnsw embedding: {}", e))?;
+            graph.insert(content_id, chunk.embedding);
+        }
+        let graph = Arc::new(RwLock::new(graph));
+        graphs.insert(index.to_string(), graph.clone());
+        Ok(graph)
+    }
+
+    fn new_graph(&self, params: IndexParams) -> HnswGraph {
+        let m = params.m.unwrap_or(self.config.m);
+        let ef_construction = params
+            .ef_construction
+            .unwrap_or(self.config.ef_construction);
+        HnswGraph::new(params, m, ef_construction)
+    }
+}
+
+#[async_trait]
+impl VectorDb for EmbeddedHnsw {
+    fn name(&self) -> String {
+        "embedded hnsw".into()
+    }
+
+    async fn create_index(&self, index: CreateIndexParams) -> Result<()> {
+        if index.quantization.is_some() {
+            return Err(anyhow!(
+                "the embedded hnsw index doesn't support quantized indexes"
+            ));
+        }
+        if self.index_params(&index.vectordb_index_name)?.is_some() {
+            return Ok(());
+        }
+        let params = IndexParams {
+            dim: index.vector_dim as usize,
+            m: index.index_param("m")?,
+            ef_construction: index.index_param("ef_construction")?,
+            ef_search: index.index_param("ef_search")?,
+            distance: index.distance,
+        };
+        self.db.put_cf(
+            self.cf(INDEXES_CF)?,
+            &index.vectordb_index_name,
+            serde_json::to_vec(&params)?,
+        )?;
+        self.graphs.write().unwrap().insert(
+            index.vectordb_index_name,
+            Arc::new(RwLock::new(self.new_graph(params))),
+        );
+        Ok(())
+    }
+
+    async fn add_embedding(&self, index: &str, chunks: Vec<VectorChunk>) -> Result<()> {
+        let graph = self.graph(index)?;
+        let mut graph = graph.write().unwrap();
+        let mut batch = WriteBatch::default();
+        for chunk in &chunks {
+            if chunk.embedding.len() != graph.params.dim {
+                return Err(anyhow!(
+                    "embedding of content {} has {} dimensions, index {} has {}",
+                    chunk.content_id,
+                    chunk.embedding.len(),
+                    index,
+                    graph.params.dim
+                ));
+            }
+            let stored = StoredChunk {
+                embedding: chunk.embedding.clone(),
+                metadata: chunk.metadata.clone(),
+            };
+            batch.put_cf(
+                self.cf(VECTORS_CF)?,
+                vector_key(index, &chunk.content_id),
+                serde_json::to_vec(&stored)?,
+            );
+        }
+        self.db.write(batch)?;
+        for chunk in chunks {
+            graph.insert(chunk.content_id, chunk.embedding);
+        }
+        Ok(())
+    }
+
+    async fn remove_embedding(&self, index: &str, content_id: &str) -> Result<()> {
+        let graph = self.graph(index)?;
+        let mut graph = graph.write().unwrap();
+        self.db
+            .delete_cf(self.cf(VECTORS_CF)?, vector_key(index, content_id))?;
+        graph.remove(content_id);
+        Ok(())
+    }
+
+    async fn get_points(&self, index: &str, content_ids: Vec<String>) -> Result<Vec<VectorChunk>> {
+        let mut chunks = Vec::new();
+        for content_id in content_ids {
+            if let Some(chunk) = self.get_chunk(index, &content_id)? {
+                chunks.push(VectorChunk::new(
+                    content_id,
+                    chunk.embedding,
+                    chunk.metadata,
+                ));
+            }
+        }
+        Ok(chunks)
+    }
+
+    async fn update_metadata(
+        &self,
+        index: &str,
+        content_id: String,
+        metadata: serde_json::Value,
+    ) -> Result<()> {
+        let Some(mut chunk) = self.get_chunk(index, &content_id)? else {
+            return Ok(());
+        };
+        chunk.metadata = metadata;
+        self.db.put_cf(
+            self.cf(VECTORS_CF)?,
+            vector_key(index, &content_id),
+            serde_json::to_vec(&chunk)?,
+        )?;
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        index: String,
+        query_embedding: Vec<f32>,
+        k: u64,
+    ) -> Result<Vec<SearchResult>> {
+        let graph = self.graph(&index)?;
+        let graph = graph.read().unwrap();
+        let ef_search = graph.params.ef_search.unwrap_or(self.config.ef_search);
+        Ok(graph.search(&query_embedding, k as usize, ef_search))
+    }
+
+    async fn drop_index(&self, index: &str) -> Result<()> {
+        let mut end = index.as_bytes().to_vec();
+        end.push(1);
+        self.db
+            .delete_range_cf(self.cf(VECTORS_CF)?, index_prefix(index), end)?;
+        self.db.delete_cf(self.cf(INDEXES_CF)?, index)?;
+        self.graphs.write().unwrap().remove(index);
+        Ok(())
+    }
+
+    async fn num_vectors(&self, index: &str) -> Result<u64> {
+        let graph = self.graph(index)?;
+        let len = graph.read().unwrap().len();
+        Ok(len as u64)
+    }
+
+    async fn index_stats(&self, index: &str) -> Result<IndexStats> {
+        let graph = self.graph(index)?;
+        let graph = graph.read().unwrap();
+        Ok(IndexStats {
+            num_vectors: graph.len() as u64,
+            shard_count: Some(1),
+            segment_count: None,
+            memory_bytes: Some(graph.memory_bytes() as u64),
+        })
+    }
+
+    async fn warm_up(&self, index: &str) -> Result<()> {
+        self.graph(index)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn config(path: &str) -> EmbeddedHnswConfig {
+        EmbeddedHnswConfig {
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn index_params(distance: IndexDistance) -> CreateIndexParams {
+        CreateIndexParams {
+            vectordb_index_name: "test_namespace.test_index".into(),
+            vector_dim: 2,
+            distance,
+            unique_params: None,
+            shard_count: None,
+            quantization: None,
+            index_params: None,
+        }
+    }
+
+    #[test]
+    fn test_graph_search() {
+        let params = IndexParams {
+            dim: 2,
+            distance: IndexDistance::Euclidean,
+            m: None,
+            ef_construction: None,
+            ef_search: None,
+        };
+        let mut graph = HnswGraph::new(params, 8, 64);
+        for x in 0..50 {
+            for y in 0..10 {
+                graph.insert(format!("{}_{}", x, y), vec![x as f32, y as f32]);
+            }
+        }
+        let results = graph.search(&[20.2, 3.1], 3, 32);
+        assert_eq!(results[0].content_id, "20_3");
+        assert_eq!(results.len(), 3);
+
+        graph.remove("20_3");
+        let results = graph.search(&[20.2, 3.1], 1, 32);
+        assert_ne!(results[0].content_id, "20_3");
+        assert_eq!(graph.len(), 499);
+
+        //  Removing most of the graph rebuilds it from the remaining nodes
+        for x in 0..40 {
+            for y in 0..10 {
+                graph.remove(&format!("{}_{}", x, y));
+            }
+        }
+        assert_eq!(graph.len(), 100);
+        assert!(graph.nodes.len() < 200);
+        let results = graph.search(&[0., 0.], 1, 32);
+        assert_eq!(results[0].content_id, "40_0");
+    }
+
+    #[tokio::test]
+    async fn test_persists_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let index = "test_namespace.test_index";
+        {
+            let hnsw = EmbeddedHnsw::new(config(path)).unwrap();
+            hnsw.create_index(index_params(IndexDistance::Cosine))
+                .await
+                .unwrap();
+            let chunks = vec![
+                VectorChunk::new("1".into(), vec![0., 2.], json!({"topic": "a"})),
+                VectorChunk::new("2".into(), vec![1., 2.], json!({"topic": "b"})),
+                VectorChunk::new("3".into(), vec![2., 0.], json!({"topic": "c"})),
+            ];
+            hnsw.add_embedding(index, chunks).await.unwrap();
+            hnsw.remove_embedding(index, "3").await.unwrap();
+            hnsw.update_metadata(index, "1".into(), json!({"topic": "d"}))
+                .await
+                .unwrap();
+            assert!(hnsw
+                .add_embedding(
+                    index,
+                    vec![VectorChunk::new("4".into(), vec![1.], json!({}))]
+                )
+                .await
+                .is_err());
+        }
+
+        let hnsw = EmbeddedHnsw::new(config(path)).unwrap();
+        assert!(hnsw.graphs.read().unwrap().is_empty());
+        hnsw.warm_up(index).await.unwrap();
+        assert!(hnsw.graphs.read().unwrap().contains_key(index));
+        assert_eq!(hnsw.num_vectors(index).await.unwrap(), 2);
+        let stats = hnsw.index_stats(index).await.unwrap();
+        assert_eq!(stats.num_vectors, 2);
+        assert!(stats.memory_bytes.unwrap() >= 2 * 2 * std::mem::size_of::<f32>() as u64);
+        let results = hnsw.search(index.into(), vec![10., 1.], 2).await.unwrap();
+        assert_eq!(results[0].content_id, "2");
+        assert_eq!(results[1].content_id, "1");
+        let points = hnsw.get_points(index, vec!["1".into()]).await.unwrap();
+        assert_eq!(points[0].metadata, json!({"topic": "d"}));
+
+        hnsw.drop_index(index).await.unwrap();
+        assert!(hnsw.num_vectors(index).await.is_err());
+        assert!(hnsw
+            .get_points(index, vec!["1".into()])
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_index_params() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let index = "test_namespace.test_index";
+        {
+            let hnsw = EmbeddedHnsw::new(config(path)).unwrap();
+            let mut params = index_params(IndexDistance::Cosine);
+            params.index_params = Some(json!({"m": 4, "ef_search": 16}));
+            hnsw.create_index(params).await.unwrap();
+        }
+
+        //  The tuning is kept with the index when it's loaded again
+        let hnsw = EmbeddedHnsw::new(config(path)).unwrap();
+        let graph = hnsw.graph(index).unwrap();
+        let graph = graph.read().unwrap();
+        assert_eq!(graph.m, 4);
+        assert_eq!(graph.ef_construction, hnsw.config.ef_construction);
+        assert_eq!(graph.params.ef_search, Some(16));
+
+        let mut params = index_params(IndexDistance::Cosine);
+        params.vectordb_index_name = "test_namespace.invalid".into();
+        params.index_params = Some(json!({"m": "4"}));
+        assert!(hnsw.create_index(params).await.is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use std::{
+    collections::HashMap,
     fmt::{self, Debug},
     iter::zip,
+    str::FromStr,
     sync::Arc,
 };
 
@@ -23,11 +25,12 @@ use lancedb::{
     query::{ExecutableQuery, QueryBase},
     table::NewColumnTransform,
     Connection,
+    DistanceType,
     Table,
 };
 use tracing;
 
-use super::{CreateIndexParams, SearchResult, VectorChunk, VectorDb};
+use super::{CreateIndexParams, IndexDistance, SearchResult, VectorChunk, VectorDb};
 use crate::server_config::LancedbConfig;
 
 pub struct LanceDb {
@@ -68,6 +71,40 @@ fn add_nulls_to_record_batch(
     Ok(ret)
 }
 
+//  Key of the distance metric of a table in the metadata of its schema
+const DISTANCE_METADATA_KEY: &str = "indexify_distance";
+
+//  Tables created before the metric was stored have none and are searched
+// with the default metric of lance, L2
+async fn table_distance(tbl: &Table) -> Result<Option<IndexDistance>> {
+    let schema = tbl.schema().await?;
+    schema
+        .metadata()
+        .get(DISTANCE_METADATA_KEY)
+        .map(|distance| {
+            IndexDistance::from_str(distance)
+                .map_err(|e| anyhow!("invalid distance {} of table: {}", distance, e))
+        })
+        .transpose()
+}
+
+fn distance_type(distance: &IndexDistance) -> DistanceType {
+    match distance {
+        IndexDistance::Cosine => DistanceType::Cosine,
+        IndexDistance::Dot => DistanceType::Dot,
+        IndexDistance::Euclidean => DistanceType::L2,
+    }
+}
+
+//  Lance returns distances, cosine and dot product results are scored with
+// the similarity like in the other vector stores
+fn score(distance: Option<&IndexDistance>, value: f32) -> f32 {
+    match distance {
+        Some(IndexDistance::Cosine) | Some(IndexDistance::Dot) => 1.0 - value,
+        _ => value,
+    }
+}
+
 // Update the schema of the table with the missing fields from the metadata keys
 async fn update_schema_with_missing_fields(
     tbl: &Table,
@@ -114,17 +151,27 @@ impl VectorDb for LanceDb {
 
     #[tracing::instrument]
     async fn create_index(&self, index: CreateIndexParams) -> Result<()> {
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new(
-                "vector",
-                DataType::FixedSizeList(
-                    Arc::new(Field::new("item", DataType::Float32, true)),
-                    index.vector_dim as i32,
+        if index.quantization.is_some() {
+            return Err(anyhow!("lancedb doesn't support quantized indexes"));
+        }
+        let metadata = HashMap::from([(
+            DISTANCE_METADATA_KEY.to_string(),
+            index.distance.to_string(),
+        )]);
+        let schema = Arc::new(Schema::new_with_metadata(
+            vec![
+                Field::new("id", DataType::Utf8, false),
+                Field::new(
+                    "vector",
+                    DataType::FixedSizeList(
+                        Arc::new(Field::new("item", DataType::Float32, true)),
+                        index.vector_dim as i32,
+                    ),
+                    true,
                 ),
-                true,
-            ),
-        ]));
+            ],
+            metadata,
+        ));
         let batches = RecordBatchIterator::new(vec![], schema.clone());
         let _ = self
             .conn
@@ -301,10 +348,12 @@ impl VectorDb for LanceDb {
         k: u64,
     ) -> Result<Vec<SearchResult>> {
         let tbl = self.conn.open_table(&index).execute().await?;
-        let res = tbl
-            .query()
-            .nearest_to(query_embedding)?
-            .column("vector")
+        let distance = table_distance(&tbl).await?;
+        let mut query = tbl.query().nearest_to(query_embedding)?.column("vector");
+        if let Some(distance) = &distance {
+            query = query.distance_type(distance_type(distance));
+        }
+        let res = query
             .limit(k as usize)
             .execute()
             .await
@@ -321,10 +370,10 @@ impl VectorDb for LanceDb {
                 .as_any()
                 .downcast_ref::<PrimitiveArray<Float32Type>>()
                 .unwrap();
-            for (id, distance) in zip(id_values, distance_values) {
+            for (id, value) in zip(id_values, distance_values) {
                 results.push(SearchResult {
                     content_id: id.unwrap().to_string(),
-                    confidence_score: distance.unwrap(),
+                    confidence_score: score(distance.as_ref(), value.unwrap()),
                 });
             }
         }
@@ -382,6 +431,9 @@ mod tests {
                 vector_dim: 2,
                 distance: crate::vectordbs::IndexDistance::Cosine,
                 unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -414,6 +466,58 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_distance_metric() {
+        let _ = std::fs::remove_dir_all("/tmp/lance-distance.db/");
+        let lance: VectorDBTS = Arc::new(
+            LanceDb::new(&LancedbConfig {
+                path: "/tmp/lance-distance.db".to_string(),
+            })
+            .await
+            .unwrap(),
+        );
+        for (index, distance) in [
+            ("cosine-index", IndexDistance::Cosine),
+            ("euclidean-index", IndexDistance::Euclidean),
+        ] {
+            lance
+                .create_index(CreateIndexParams {
+                    vectordb_index_name: index.into(),
+                    vector_dim: 2,
+                    distance,
+                    unique_params: None,
+                    shard_count: None,
+                    quantization: None,
+                    index_params: None,
+                })
+                .await
+                .unwrap();
+            lance
+                .add_embedding(
+                    index,
+                    vec![
+                        VectorChunk::new("a".into(), vec![1., 0.], json!({})),
+                        VectorChunk::new("b".into(), vec![10., 1.], json!({})),
+                    ],
+                )
+                .await
+                .unwrap();
+        }
+
+        //  b points in the direction of the query, a is closer to it
+        let results = lance
+            .search("cosine-index".into(), vec![1., 0.1], 1)
+            .await
+            .unwrap();
+        assert_eq!(results[0].content_id, "b");
+        assert!(results[0].confidence_score > 0.99);
+        let results = lance
+            .search("euclidean-index".into(), vec![1., 0.1], 1)
+            .await
+            .unwrap();
+        assert_eq!(results[0].content_id, "a");
+    }
+
     fn make_id() -> String {
         DataManager::make_id("namespace", &nanoid::nanoid!(), &None)
     }
@@ -434,6 +538,9 @@ mod tests {
                 vector_dim: 2,
                 distance: crate::vectordbs::IndexDistance::Cosine,
                 unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -509,6 +616,9 @@ mod tests {
                 vector_dim: 2,
                 distance: IndexDistance::Cosine,
                 unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -546,6 +656,9 @@ mod tests {
                 vector_dim: 2,
                 distance: IndexDistance::Cosine,
                 unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
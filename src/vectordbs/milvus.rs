@@ -0,0 +1,625 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use indexify_internal_api::IndexQuantization;
+use itertools::Itertools;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{namespace_partition, product_quantization_subvectors, CreateIndexParams, VectorDb};
+use crate::{
+    server_config::MilvusConfig,
+    vectordbs::{IndexDistance, SearchResult, VectorChunk},
+};
+
+//  Number of embeddings sent to Milvus in a single upsert request
+const INSERT_BATCH_SIZE: usize = 100;
+
+//  Number of clusters of the IVF index of quantized collections
+const IVF_NLIST: u32 = 1024;
+
+#[derive(Deserialize)]
+struct MilvusResponse {
+    code: i64,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    data: Value,
+}
+
+#[derive(Deserialize)]
+struct MilvusEntity {
+    content_id: String,
+    #[serde(default)]
+    embedding: Vec<f32>,
+    #[serde(default)]
+    metadata: Value,
+}
+
+#[derive(Deserialize)]
+struct MilvusHit {
+    content_id: String,
+    distance: f32,
+}
+
+//  Collection and partition holding the embeddings of an index table
+struct Location {
+    collection: String,
+    partition: Option<String>,
+}
+
+impl Location {
+    //  Restricts a request to the partition of the index table, `key` is the
+    // parameter name the endpoint expects
+    fn scope(&self, mut body: Value, key: &str) -> Value {
+        if let Some(partition) = &self.partition {
+            body[key] = match key {
+                "partitionNames" => json!([partition]),
+                _ => json!(partition),
+            };
+        }
+        body
+    }
+}
+
+/// Vector index backed by Milvus, accessed through its RESTful API. Every
+/// index is a collection with the content id as the primary key, the
+/// embedding and the metadata of the content as a JSON field. With
+/// `partition_by_namespace` the tables of an index in every namespace share
+/// a collection, with a partition per namespace.
+#[derive(Debug)]
+pub struct MilvusDb {
+    config: MilvusConfig,
+    client: reqwest::Client,
+}
+
+/// Parameters of the index of the embeddings, the index params of the policy
+/// are applied over the index type derived from the quantization.
+fn index_params(index: &CreateIndexParams) -> Result<Value> {
+    //  Quantized embeddings are stored in an IVF index, the only index type
+    // all Milvus versions support quantization for
+    let mut index_params = match &index.quantization {
+        None => json!({ "index_type": "AUTOINDEX" }),
+        Some(IndexQuantization::Scalar { .. }) => {
+            json!({ "index_type": "IVF_SQ8", "nlist": IVF_NLIST })
+        }
+        Some(IndexQuantization::Product { compression, .. }) => json!({
+            "index_type": "IVF_PQ",
+            "nlist": IVF_NLIST,
+            "m": product_quantization_subvectors(index.vector_dim, *compression)?,
+            "nbits": 8
+        }),
+    };
+    if let Some(Value::Object(params)) = &index.index_params {
+        for (key, value) in params {
+            index_params[key] = value.clone();
+        }
+    }
+    Ok(index_params)
+}
+
+impl MilvusDb {
+    pub fn new(config: MilvusConfig) -> MilvusDb {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn request(&self, path: &str, body: Value) -> Result<Value> {
+        let url = format!(
+            "{}/v2/vectordb/{}",
+            self.config.addr.trim_end_matches('/'),
+            path
+        );
+        let mut request = self.client.post(&url).json(&body);
+        if let Some(token) = &self.config.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("unable to send milvus request {}: {}", path, e))?
+            .error_for_status()
+            .map_err(|e| anyhow!("milvus request {} failed: {}", path, e))?
+            .json::<MilvusResponse>()
+            .await
+            .map_err(|e| anyhow!("unable to parse milvus response {}: {}", path, e))?;
+        if response.code != 0 {
+            return Err(anyhow!(
+                "milvus request {} failed with code {}: {}",
+                path,
+                response.code,
+                response.message
+            ));
+        }
+        Ok(response.data)
+    }
+
+    fn location(&self, index: &str) -> Result<Location> {
+        if !self.config.partition_by_namespace {
+            return Ok(Location {
+                collection: collection_name(index),
+                partition: None,
+            });
+        }
+        let (namespace, table) = namespace_partition(index)?;
+        Ok(Location {
+            collection: collection_name(table),
+            partition: Some(collection_name(namespace)),
+        })
+    }
+
+    async fn has_partition(&self, collection: &str, partition: &str) -> Result<bool> {
+        let data = self
+            .request(
+                "partitions/has",
+                json!({ "collectionName": collection, "partitionName": partition }),
+            )
+            .await?;
+        Ok(data["has"].as_bool().unwrap_or(false))
+    }
+
+    async fn has_collection(&self, collection: &str) -> Result<bool> {
+        let data = self
+            .request("collections/has", json!({ "collectionName": collection }))
+            .await?;
+        Ok(data["has"].as_bool().unwrap_or(false))
+    }
+
+    async fn create_collection(&self, collection: &str, index: CreateIndexParams) -> Result<()> {
+        let metric_type = match index.distance {
+            IndexDistance::Cosine => "COSINE",
+            IndexDistance::Dot => "IP",
+            IndexDistance::Euclidean => "L2",
+        };
+        let index_params = index_params(&index)?;
+        let mut body = json!({
+            "collectionName": collection,
+            "schema": {
+                "autoId": false,
+                "enableDynamicField": false,
+                "fields": [
+                    {
+                        "fieldName": "content_id",
+                        "dataType": "VarChar",
+                        "isPrimary": true,
+                        "elementTypeParams": { "max_length": 1024 }
+                    },
+                    {
+                        "fieldName": "embedding",
+                        "dataType": "FloatVector",
+                        "elementTypeParams": { "dim": index.vector_dim }
+                    },
+                    {
+                        "fieldName": "metadata",
+                        "dataType": "JSON"
+                    }
+                ]
+            },
+            "indexParams": [
+                {
+                    "fieldName": "embedding",
+                    "indexName": "embedding",
+                    "metricType": metric_type,
+                    "params": index_params
+                }
+            ],
+        });
+        if let Some(shard_count) = index.shard_count {
+            body["params"] = json!({ "shardsNum": shard_count });
+        }
+        self.request("collections/create", body)
+            .await
+            .map_err(|e| anyhow!("unable to create milvus collection: {}", e))?;
+        Ok(())
+    }
+
+    /// Searches the index for the nearest neighbors of the query embedding
+    /// among the embeddings whose metadata matches all of the filters.
+    pub async fn search_with_filters(
+        &self,
+        index: &str,
+        query_embedding: Vec<f32>,
+        k: u64,
+        filters: &HashMap<String, Value>,
+    ) -> Result<Vec<SearchResult>> {
+        let location = self.location(index)?;
+        let data = self
+            .request(
+                "entities/search",
+                location.scope(
+                    json!({
+                        "collectionName": location.collection,
+                        "data": [query_embedding],
+                        "annsField": "embedding",
+                        "filter": metadata_filter(filters),
+                        "limit": k,
+                        "outputFields": ["content_id"],
+                    }),
+                    "partitionNames",
+                ),
+            )
+            .await?;
+        let hits: Vec<MilvusHit> = serde_json::from_value(data)
+            .map_err(|e| anyhow!("unable to parse milvus search response: {}", e))?;
+        Ok(hits
+            .into_iter()
+            .map(|hit| SearchResult {
+                content_id: hit.content_id,
+                confidence_score: hit.distance,
+            })
+            .collect())
+    }
+}
+
+//  Collection names can only contain letters, digits and underscores
+fn collection_name(index: &str) -> String {
+    index
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+//  Builds a boolean expression matching metadata equal to all the filters
+fn metadata_filter(filters: &HashMap<String, Value>) -> String {
+    filters
+        .iter()
+        .sorted_by(|a, b| a.0.cmp(b.0))
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(value) => quote(value),
+                value => value.to_string(),
+            };
+            format!("metadata[{}] == {}", quote(key), value)
+        })
+        .join(" && ")
+}
+
+fn content_id_filter<'a>(content_ids: impl IntoIterator<Item = &'a str>) -> String {
+    format!(
+        "content_id in [{}]",
+        content_ids.into_iter().map(quote).join(", ")
+    )
+}
+
+#[async_trait]
+impl VectorDb for MilvusDb {
+    fn name(&self) -> String {
+        "milvus".into()
+    }
+
+    async fn create_index(&self, index: CreateIndexParams) -> Result<()> {
+        let location = self.location(&index.vectordb_index_name)?;
+        if !self.has_collection(&location.collection).await? {
+            self.create_collection(&location.collection, index).await?;
+        }
+        if let Some(partition) = &location.partition {
+            if !self.has_partition(&location.collection, partition).await? {
+                self.request(
+                    "partitions/create",
+                    json!({
+                        "collectionName": location.collection,
+                        "partitionName": partition,
+                    }),
+                )
+                .await
+                .map_err(|e| anyhow!("unable to create milvus partition: {}", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn add_embedding(&self, index: &str, chunks: Vec<VectorChunk>) -> Result<()> {
+        let location = self.location(index)?;
+        for batch in chunks.chunks(INSERT_BATCH_SIZE) {
+            let data = batch
+                .iter()
+                .map(|chunk| {
+                    json!({
+                        "content_id": chunk.content_id,
+                        "embedding": chunk.embedding,
+                        "metadata": chunk.metadata,
+                    })
+                })
+                .collect_vec();
+            self.request(
+                "entities/upsert",
+                location.scope(
+                    json!({ "collectionName": location.collection, "data": data }),
+                    "partitionName",
+                ),
+            )
+            .await
+            .map_err(|e| anyhow!("unable to add milvus embeddings: {}", e))?;
+        }
+        Ok(())
+    }
+
+    async fn remove_embedding(&self, index: &str, content_id: &str) -> Result<()> {
+        let location = self.location(index)?;
+        self.request(
+            "entities/delete",
+            location.scope(
+                json!({
+                    "collectionName": location.collection,
+                    "filter": content_id_filter([content_id]),
+                }),
+                "partitionName",
+            ),
+        )
+        .await
+        .map_err(|e| anyhow!("unable to remove milvus embedding: {}", e))?;
+        Ok(())
+    }
+
+    async fn get_points(&self, index: &str, content_ids: Vec<String>) -> Result<Vec<VectorChunk>> {
+        if content_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let location = self.location(index)?;
+        let data = self
+            .request(
+                "entities/query",
+                location.scope(
+                    json!({
+                        "collectionName": location.collection,
+                        "filter": content_id_filter(content_ids.iter().map(|id| id.as_str())),
+                        "outputFields": ["content_id", "embedding", "metadata"],
+                        "limit": content_ids.len(),
+                    }),
+                    "partitionNames",
+                ),
+            )
+            .await?;
+        let entities: Vec<MilvusEntity> = serde_json::from_value(data)
+            .map_err(|e| anyhow!("unable to parse milvus query response: {}", e))?;
+        Ok(entities
+            .into_iter()
+            .map(|entity| VectorChunk::new(entity.content_id, entity.embedding, entity.metadata))
+            .collect())
+    }
+
+    async fn update_metadata(
+        &self,
+        index: &str,
+        content_id: String,
+        metadata: serde_json::Value,
+    ) -> Result<()> {
+        //  Fields of an entity can't be updated in place, the embedding is
+        // written again with the new metadata
+        let chunks = self
+            .get_points(index, vec![content_id])
+            .await?
+            .into_iter()
+            .map(|chunk| VectorChunk::new(chunk.content_id, chunk.embedding, metadata.clone()))
+            .collect_vec();
+        if chunks.is_empty() {
+            return Ok(());
+        }
+        self.add_embedding(index, chunks).await
+    }
+
+    async fn search(
+        &self,
+        index: String,
+        query_embedding: Vec<f32>,
+        k: u64,
+    ) -> Result<Vec<SearchResult>> {
+        self.search_with_filters(&index, query_embedding, k, &HashMap::new())
+            .await
+    }
+
+    async fn drop_index(&self, index: &str) -> Result<()> {
+        let location = self.location(index)?;
+        if !self.has_collection(&location.collection).await? {
+            return Ok(());
+        }
+        //  Only the partition of the namespace is dropped, the collection is
+        // shared with the other namespaces
+        if let Some(partition) = &location.partition {
+            if !self.has_partition(&location.collection, partition).await? {
+                return Ok(());
+            }
+            let body = json!({
+                "collectionName": location.collection,
+                "partitionName": partition,
+            });
+            self.request("partitions/release", body.clone())
+                .await
+                .map_err(|e| anyhow!("unable to release milvus partition: {}", e))?;
+            self.request("partitions/drop", body)
+                .await
+                .map_err(|e| anyhow!("unable to drop milvus partition: {}", e))?;
+            return Ok(());
+        }
+        self.request(
+            "collections/drop",
+            json!({ "collectionName": location.collection }),
+        )
+        .await
+        .map_err(|e| anyhow!("unable to drop milvus collection: {}", e))?;
+        Ok(())
+    }
+
+    async fn num_vectors(&self, index: &str) -> Result<u64> {
+        let location = self.location(index)?;
+        let data = match &location.partition {
+            Some(partition) => {
+                self.request(
+                    "partitions/get_stats",
+                    json!({
+                        "collectionName": location.collection,
+                        "partitionName": partition,
+                    }),
+                )
+                .await?
+            }
+            None => {
+                self.request(
+                    "collections/get_stats",
+                    json!({ "collectionName": location.collection }),
+                )
+                .await?
+            }
+        };
+        data["rowCount"]
+            .as_u64()
+            .ok_or(anyhow!("unable to parse milvus collection stats"))
+    }
+
+    //  Milvus only searches collections loaded into memory, a collection or
+    // partition released since is loaded again
+    async fn warm_up(&self, index: &str) -> Result<()> {
+        let location = self.location(index)?;
+        let body = json!({ "collectionName": location.collection });
+        let path = match location.partition {
+            Some(_) => "partitions/load",
+            None => "collections/load",
+        };
+        self.request(path, location.scope(body, "partitionNames"))
+            .await
+            .map_err(|e| anyhow!("unable to load milvus index {}: {}", index, e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use indexify_internal_api::IndexQuantization;
+    use serde_json::json;
+
+    use super::{
+        collection_name,
+        index_params,
+        metadata_filter,
+        CreateIndexParams,
+        MilvusConfig,
+        MilvusDb,
+    };
+    use crate::vectordbs::{IndexDistance, VectorChunk, VectorDBTS};
+
+    #[test]
+    fn test_filters() {
+        assert_eq!(
+            collection_name("default.minilm.embedding"),
+            "default_minilm_embedding"
+        );
+        let filters = HashMap::from([
+            ("topic".to_string(), json!("say \"hi\"")),
+            ("page".to_string(), json!(3)),
+        ]);
+        assert_eq!(
+            metadata_filter(&filters),
+            r#"metadata["page"] == 3 && metadata["topic"] == "say \"hi\"""#
+        );
+        assert_eq!(metadata_filter(&HashMap::new()), "");
+    }
+
+    #[test]
+    fn test_index_params() {
+        let mut index = CreateIndexParams {
+            vectordb_index_name: "default.minilm.embedding".into(),
+            vector_dim: 384,
+            distance: IndexDistance::Cosine,
+            unique_params: None,
+            shard_count: None,
+            quantization: None,
+            index_params: None,
+        };
+        assert_eq!(
+            index_params(&index).unwrap(),
+            json!({"index_type": "AUTOINDEX"})
+        );
+
+        index.index_params = Some(json!({"index_type": "HNSW", "M": 32, "efConstruction": 200}));
+        assert_eq!(
+            index_params(&index).unwrap(),
+            json!({"index_type": "HNSW", "M": 32, "efConstruction": 200})
+        );
+
+        //  The params override the defaults of the quantized index
+        index.quantization = Some(IndexQuantization::Scalar {
+            quantile: None,
+            always_ram: false,
+        });
+        index.index_params = Some(json!({"nlist": 4096}));
+        assert_eq!(
+            index_params(&index).unwrap(),
+            json!({"index_type": "IVF_SQ8", "nlist": 4096})
+        );
+    }
+
+    #[test]
+    fn test_namespace_partitions() {
+        let milvus = MilvusDb::new(MilvusConfig {
+            partition_by_namespace: true,
+            ..Default::default()
+        });
+        let location = milvus.location("my-namespace.minilm.embedding").unwrap();
+        assert_eq!(location.collection, "minilm_embedding");
+        assert_eq!(location.partition.as_deref(), Some("my_namespace"));
+        assert_eq!(
+            location.scope(json!({}), "partitionNames"),
+            json!({"partitionNames": ["my_namespace"]})
+        );
+
+        let location = MilvusDb::new(MilvusConfig::default())
+            .location("default.minilm.embedding")
+            .unwrap();
+        assert_eq!(location.collection, "default_minilm_embedding");
+        assert_eq!(location.scope(json!({}), "partitionName"), json!({}));
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    #[ignore]
+    async fn test_search_basic() {
+        let milvus = MilvusDb::new(MilvusConfig::default());
+        let index = "test_namespace.test_index";
+        milvus.drop_index(index).await.unwrap();
+        milvus
+            .create_index(CreateIndexParams {
+                vectordb_index_name: index.into(),
+                vector_dim: 2,
+                distance: IndexDistance::Cosine,
+                unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
+            })
+            .await
+            .unwrap();
+        let chunks = vec![
+            VectorChunk::new("1".into(), vec![0., 2.], json!({"topic": "a"})),
+            VectorChunk::new("2".into(), vec![1., 2.], json!({"topic": "b"})),
+        ];
+        milvus.add_embedding(index, chunks).await.unwrap();
+
+        let results = milvus
+            .search_with_filters(
+                index,
+                vec![10., 8.],
+                2,
+                &HashMap::from([("topic".to_string(), json!("a"))]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content_id, "1");
+
+        let milvus: VectorDBTS = Arc::new(milvus);
+        let points = milvus.get_points(index, vec!["2".into()]).await.unwrap();
+        assert_eq!(points[0].metadata, json!({"topic": "b"}));
+        milvus.remove_embedding(index, "2").await.unwrap();
+        let results = milvus.search(index.into(), vec![10., 8.], 2).await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
+}
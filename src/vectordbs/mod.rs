@@ -1,20 +1,30 @@
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use serde::{Deserialize, Serialize};
+use indexify_internal_api::{IndexQuantization, SparseVector};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use strum::{Display, EnumString};
 
 use crate::server_config::{IndexStoreKind, VectorIndexConfig};
 
+pub mod hnsw;
 pub mod lancedb;
+pub mod milvus;
 pub mod open_search;
 pub mod pg_vector;
 pub mod qdrant;
+pub mod weaviate;
 
 use qdrant::QdrantDb;
 
-use self::{open_search::OpenSearchKnn, pg_vector::PgVector};
+use self::{
+    hnsw::EmbeddedHnsw,
+    milvus::MilvusDb,
+    open_search::OpenSearchKnn,
+    pg_vector::PgVector,
+    weaviate::WeaviateDb,
+};
 
 #[derive(Display, Debug, Clone, EnumString, Serialize, Deserialize)]
 pub enum IndexDistance {
@@ -39,6 +49,32 @@ pub struct CreateIndexParams {
     pub distance: IndexDistance,
     // TODO: Probably better if this is a HashMap<String, String> (?), or a generic (?)
     pub unique_params: Option<Vec<String>>,
+    // Number of shards or partitions to split the index into. Ignored by
+    // vector stores which don't support it.
+    pub shard_count: Option<u32>,
+    // Compression of the stored embeddings, vector stores which don't
+    // support the requested quantization refuse to create the index.
+    pub quantization: Option<IndexQuantization>,
+    // Backend specific tuning of the index, e.g. HNSW M or IVF nlist. Each
+    // vector store applies the keys it knows and ignores the others.
+    pub index_params: Option<serde_json::Value>,
+}
+
+impl CreateIndexParams {
+    /// Reads a tuning parameter of the index, erroring if it has the wrong
+    /// type.
+    pub fn index_param<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        match self
+            .index_params
+            .as_ref()
+            .and_then(|params| params.get(key))
+        {
+            Some(value) => serde_json::from_value(value.clone())
+                .map(Some)
+                .map_err(|e| anyhow!("invalid index parameter {}: {}", key, e)),
+            None => Ok(None),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -65,6 +101,25 @@ impl VectorChunk {
     }
 }
 
+/// A sparse embedding of a content, only stored in vector databases which
+/// support sparse search.
+#[derive(Debug, Clone)]
+pub struct SparseVectorChunk {
+    pub content_id: String,
+    pub vector: SparseVector,
+    pub metadata: serde_json::Value,
+}
+
+/// Statistics of an index as reported by the store holding it. Stores which
+/// don't report a statistic leave it empty.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexStats {
+    pub num_vectors: u64,
+    pub shard_count: Option<u32>,
+    pub segment_count: Option<u64>,
+    pub memory_bytes: Option<u64>,
+}
+
 /// A trait that defines the interface for interacting with a vector database.
 /// The vector database is responsible for storing and querying vector
 /// embeddings.
@@ -101,17 +156,120 @@ pub trait VectorDb {
         k: u64,
     ) -> Result<Vec<SearchResult>>;
 
+    /// Searches the index with both the query text and its embedding, for
+    /// vector databases which rank results with their own hybrid search.
+    /// Defaults to searching with the embedding only.
+    async fn hybrid_search(
+        &self,
+        index: String,
+        _query: &str,
+        query_embedding: Vec<f32>,
+        k: u64,
+    ) -> Result<Vec<SearchResult>> {
+        self.search(index, query_embedding, k).await
+    }
+
     /// Deletes the specified vector index from the vector database.
     async fn drop_index(&self, index: &str) -> Result<()>;
 
     /// Returns the number of vectors in the specified index.
     async fn num_vectors(&self, index: &str) -> Result<u64>;
 
+    /// Returns the statistics of the specified index, only the number of
+    /// vectors unless the vector database reports more.
+    async fn index_stats(&self, index: &str) -> Result<IndexStats> {
+        Ok(IndexStats {
+            num_vectors: self.num_vectors(index).await?,
+            ..Default::default()
+        })
+    }
+
+    /// Creates an index of sparse embeddings, searched by dot product. Vector
+    /// databases which don't support sparse vectors refuse to create it.
+    async fn create_sparse_index(&self, index: &str, _shard_count: Option<u32>) -> Result<()> {
+        Err(anyhow!(
+            "{} doesn't support sparse vectors, unable to create index {}",
+            self.name(),
+            index
+        ))
+    }
+
+    /// Adds sparse embeddings to an index created with
+    /// `create_sparse_index`.
+    async fn add_sparse_embedding(
+        &self,
+        index: &str,
+        _chunks: Vec<SparseVectorChunk>,
+    ) -> Result<()> {
+        Err(anyhow!(
+            "{} doesn't support sparse vectors, unable to write to index {}",
+            self.name(),
+            index
+        ))
+    }
+
+    /// Searches a sparse index for the embeddings with the highest dot
+    /// product with the query.
+    async fn sparse_search(
+        &self,
+        index: String,
+        _query: SparseVector,
+        _k: u64,
+    ) -> Result<Vec<SearchResult>> {
+        Err(anyhow!(
+            "{} doesn't support sparse vectors, unable to search index {}",
+            self.name(),
+            index
+        ))
+    }
+
+    /// Loads the specified index into memory so the first searches after a
+    /// restart or rebuild don't pay for it. Does nothing by default, for vector
+    /// databases which keep their indexes loaded.
+    async fn warm_up(&self, _index: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Makes the embeddings written to the specified index visible to
+    /// searches. Does nothing by default, for vector databases which make
+    /// writes searchable once they're acknowledged.
+    async fn refresh(&self, _index: &str) -> Result<()> {
+        Ok(())
+    }
+
     fn name(&self) -> String;
 
     //  TODO: Add delete content using namespace and content id
 }
 
+/// Splits the name of an index table into its namespace and the name of the
+/// table within the namespace, for vector stores keeping the tables of an
+/// index in every namespace as partitions of a single collection.
+pub fn namespace_partition(index: &str) -> Result<(&str, &str)> {
+    index
+        .split_once('.')
+        .filter(|(namespace, table)| !namespace.is_empty() && !table.is_empty())
+        .ok_or(anyhow!(
+            "index table {} isn't prefixed with its namespace",
+            index
+        ))
+}
+
+/// Number of sub vectors product quantization with one byte per sub vector
+/// splits embeddings of dimension `dim` into to reach the compression.
+pub fn product_quantization_subvectors(dim: u64, compression: u32) -> Result<u64> {
+    let size = dim * std::mem::size_of::<f32>() as u64;
+    let subvectors = size / compression as u64;
+    if subvectors == 0 || size % compression as u64 != 0 || dim % subvectors != 0 {
+        return Err(anyhow!(
+            "embeddings of dimension {} can't be compressed {}x with product quantization",
+            dim,
+            compression
+        ));
+    }
+    Ok(subvectors)
+}
+
 /// Creates a new vector database based on the specified configuration.
 pub async fn create_vectordb(config: VectorIndexConfig) -> Result<VectorDBTS> {
     match config.index_store {
@@ -125,5 +283,61 @@ pub async fn create_vectordb(config: VectorIndexConfig) -> Result<VectorDBTS> {
         IndexStoreKind::Lancedb => Ok(Arc::new(
             lancedb::LanceDb::new(&config.lancedb_config.unwrap()).await?,
         )),
+        IndexStoreKind::Milvus => Ok(Arc::new(MilvusDb::new(config.milvus_config.unwrap()))),
+        IndexStoreKind::Weaviate => Ok(Arc::new(WeaviateDb::new(config.weaviate_config.unwrap()))),
+        IndexStoreKind::EmbeddedHnsw => Ok(Arc::new(EmbeddedHnsw::new(
+            config.embedded_hnsw_config.unwrap(),
+        )?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        namespace_partition,
+        product_quantization_subvectors,
+        CreateIndexParams,
+        IndexDistance,
+    };
+
+    #[test]
+    fn test_index_param() {
+        let params = CreateIndexParams {
+            vectordb_index_name: "default.minilm.embedding".to_string(),
+            vector_dim: 384,
+            distance: IndexDistance::Cosine,
+            unique_params: None,
+            shard_count: None,
+            quantization: None,
+            index_params: Some(json!({"m": 32, "on_disk": true})),
+        };
+        assert_eq!(params.index_param::<usize>("m").unwrap(), Some(32));
+        assert_eq!(params.index_param::<bool>("on_disk").unwrap(), Some(true));
+        assert_eq!(
+            params.index_param::<usize>("ef_construction").unwrap(),
+            None
+        );
+        assert!(params.index_param::<bool>("m").is_err());
+    }
+
+    #[test]
+    fn test_namespace_partition() {
+        assert_eq!(
+            namespace_partition("default.minilm.embedding").unwrap(),
+            ("default", "minilm.embedding")
+        );
+        assert!(namespace_partition("embedding").is_err());
+        assert!(namespace_partition(".embedding").is_err());
+    }
+
+    #[test]
+    fn test_product_quantization_subvectors() {
+        assert_eq!(product_quantization_subvectors(384, 4).unwrap(), 384);
+        assert_eq!(product_quantization_subvectors(384, 16).unwrap(), 96);
+        assert_eq!(product_quantization_subvectors(768, 64).unwrap(), 48);
+        assert!(product_quantization_subvectors(3, 16).is_err());
+        assert!(product_quantization_subvectors(100, 64).is_err());
     }
 }
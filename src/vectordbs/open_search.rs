@@ -1,12 +1,15 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use indexify_internal_api::IndexQuantization;
 use opensearch::{
     auth::Credentials,
     cert::CertificateValidation,
     http::transport::{SingleNodeConnectionPool, TransportBuilder},
     indices::IndicesCreateParts,
     BulkOperation,
+    MgetParts,
     OpenSearch,
+    UpdateParts,
 };
 use serde::Deserialize;
 use serde_json::{json, Value};
@@ -14,10 +17,13 @@ use url::Url;
 
 use super::{CreateIndexParams, VectorDb};
 use crate::{
-    server_config::OpenSearchBasicConfig,
+    server_config::{OpenSearchBasicConfig, SearchEngineKind},
     vectordbs::{IndexDistance, SearchResult, VectorChunk},
 };
 
+/// Vector index backed by the kNN search of OpenSearch or the dense vectors
+/// of Elasticsearch. Every index stores the embeddings and the metadata of
+/// the content, keyed by the content id.
 pub struct OpenSearchKnn {
     config: OpenSearchBasicConfig,
 }
@@ -41,10 +47,186 @@ impl OpenSearchKnn {
     }
 }
 
+//  Elasticsearch considers this many candidates per shard for every requested
+// neighbor
+const ELASTICSEARCH_NUM_CANDIDATES_FACTOR: u64 = 10;
+
+fn engine_name(engine: &SearchEngineKind) -> &'static str {
+    match engine {
+        SearchEngineKind::OpenSearch => "open search",
+        SearchEngineKind::Elasticsearch => "elasticsearch",
+    }
+}
+
+fn index_body(engine: &SearchEngineKind, params: &CreateIndexParams) -> Result<Value> {
+    //  Metadata is only returned with the embeddings, not searched
+    let metadata = json!({ "type": "object", "enabled": false });
+    if let Some(IndexQuantization::Product { .. }) = params.quantization {
+        return Err(anyhow!(
+            "{} only supports scalar quantization",
+            engine_name(engine)
+        ));
+    }
+    match engine {
+        SearchEngineKind::OpenSearch => {
+            let mut settings = json!({ "index": { "knn": true } });
+            if let Some(shard_count) = params.shard_count {
+                settings["index"]["number_of_shards"] = json!(shard_count);
+            }
+            let mut method = json!({
+                "name": "hnsw",
+                "space_type": match params.distance {
+                    IndexDistance::Cosine => "cosinesimil",
+                    IndexDistance::Dot => "innerproduct",
+                    IndexDistance::Euclidean => "l2",
+                },
+                "engine": "nmslib"
+            });
+            //  Scalar quantization is only available in the faiss engine, which
+            // stores the embeddings as 16 bit floats
+            if params.quantization.is_some() {
+                method["engine"] = json!("faiss");
+                method["parameters"] = json!({
+                    "encoder": { "name": "sq", "parameters": { "type": "fp16" } }
+                });
+            }
+            for key in ["m", "ef_construction"] {
+                if let Some(value) = params.index_param::<u64>(key)? {
+                    method["parameters"][key] = json!(value);
+                }
+            }
+            if let Some(ef_search) = params.index_param::<u64>("ef_search")? {
+                settings["index"]["knn.algo_param.ef_search"] = json!(ef_search);
+            }
+            if let Some(replicas) = params.index_param::<u32>("number_of_replicas")? {
+                settings["index"]["number_of_replicas"] = json!(replicas);
+            }
+            Ok(json!({
+                "settings": settings,
+                "mappings": {
+                    "properties": {
+                        "embeddings": {
+                            "type": "knn_vector",
+                            "dimension": params.vector_dim as i32,
+                            "method": method
+                        },
+                        "metadata": metadata
+                    }
+                }
+            }))
+        }
+        SearchEngineKind::Elasticsearch => {
+            let mut body = json!({
+                "mappings": {
+                    "properties": {
+                        "embeddings": {
+                            "type": "dense_vector",
+                            "dims": params.vector_dim as i32,
+                            "index": true,
+                            "similarity": match params.distance {
+                                IndexDistance::Cosine => "cosine",
+                                IndexDistance::Dot => "dot_product",
+                                IndexDistance::Euclidean => "l2_norm",
+                            }
+                        },
+                        "metadata": metadata
+                    }
+                }
+            });
+            if let Some(IndexQuantization::Scalar { quantile, .. }) = params.quantization {
+                let mut index_options = json!({ "type": "int8_hnsw" });
+                if let Some(quantile) = quantile {
+                    index_options["confidence_interval"] = json!(quantile);
+                }
+                body["mappings"]["properties"]["embeddings"]["index_options"] = index_options;
+            }
+            for key in ["m", "ef_construction"] {
+                if let Some(value) = params.index_param::<u64>(key)? {
+                    let index_options =
+                        &mut body["mappings"]["properties"]["embeddings"]["index_options"];
+                    if index_options.is_null() {
+                        *index_options = json!({ "type": "hnsw" });
+                    }
+                    index_options[key] = json!(value);
+                }
+            }
+            if let Some(shard_count) = params.shard_count {
+                body["settings"]["index"]["number_of_shards"] = json!(shard_count);
+            }
+            if let Some(replicas) = params.index_param::<u32>("number_of_replicas")? {
+                body["settings"]["index"]["number_of_replicas"] = json!(replicas);
+            }
+            Ok(body)
+        }
+    }
+}
+
+fn search_body(engine: &SearchEngineKind, query_embedding: Vec<f32>, k: u64) -> Value {
+    match engine {
+        SearchEngineKind::OpenSearch => json!({
+            "size": k,
+            "_source": false,
+            "query": {
+                "knn": {
+                    "embeddings": {
+                        "vector": query_embedding,
+                        "k": k
+                    }
+                }
+            }
+        }),
+        SearchEngineKind::Elasticsearch => json!({
+            "size": k,
+            "_source": false,
+            "knn": {
+                "field": "embeddings",
+                "query_vector": query_embedding,
+                "k": k,
+                "num_candidates": k * ELASTICSEARCH_NUM_CANDIDATES_FACTOR
+            }
+        }),
+    }
+}
+
+//  Parses the found documents of a multi get response
+fn parse_documents(response_body: &Value) -> Result<Vec<VectorChunk>> {
+    #[derive(Deserialize)]
+    struct Source {
+        embeddings: Vec<f32>,
+        #[serde(default)]
+        metadata: Value,
+    }
+
+    #[derive(Deserialize)]
+    struct Document {
+        _id: String,
+        #[serde(default)]
+        found: bool,
+        _source: Option<Source>,
+    }
+
+    let docs = response_body["docs"]
+        .as_array()
+        .ok_or(anyhow!("unable to parse opensearch get response"))?;
+    let mut chunks = Vec::new();
+    for doc in docs {
+        let doc = serde_json::from_value::<Document>(doc.clone())
+            .map_err(|e| anyhow!("unable to parse opensearch get response: {}", e))?;
+        if let (true, Some(source)) = (doc.found, doc._source) {
+            chunks.push(VectorChunk::new(
+                doc._id,
+                source.embeddings,
+                source.metadata,
+            ));
+        }
+    }
+    Ok(chunks)
+}
+
 #[async_trait]
 impl VectorDb for OpenSearchKnn {
     fn name(&self) -> String {
-        "open search".into()
+        engine_name(&self.config.engine).into()
     }
 
     async fn create_index(&self, index_params: CreateIndexParams) -> Result<()> {
@@ -52,30 +234,7 @@ impl VectorDb for OpenSearchKnn {
             .create_client()?
             .indices()
             .create(IndicesCreateParts::Index(&index_params.vectordb_index_name))
-            .body(json!(
-                {
-                    "settings" : {
-                        "index": { "knn": true, }
-                    },
-                    "mappings" : {
-                        "properties" : {
-                            "embeddings" : {
-                                "type" : "knn_vector",
-                                "dimension" : index_params.vector_dim as i32,
-                                "method": {
-                                    "name": "hnsw",
-                                    "space_type": match index_params.distance {
-                                        IndexDistance::Cosine => "cosinesimil",
-                                        IndexDistance::Dot => "innerproduct",
-                                        IndexDistance::Euclidean => "l2",
-                                    },
-                                    "engine": "nmslib"
-                                }
-                            }
-                        }
-                    }
-                }
-            ))
+            .body(index_body(&self.config.engine, &index_params)?)
             .send()
             .await
             .map_err(|e| anyhow!("unable to create opensearch index: {}", e))?;
@@ -93,8 +252,14 @@ impl VectorDb for OpenSearchKnn {
         for vector_chunk in vector_chunks {
             let body = json!({
                 "embeddings": vector_chunk.embedding,
+                "metadata": vector_chunk.metadata,
             });
-            bulk_ops.push(BulkOperation::create(vector_chunk.content_id, body).into());
+            //  Indexing replaces the document if the content is added again
+            bulk_ops.push(
+                BulkOperation::index(body)
+                    .id(vector_chunk.content_id)
+                    .into(),
+            );
         }
 
         let response = self
@@ -135,19 +300,51 @@ impl VectorDb for OpenSearchKnn {
         }
     }
 
-    async fn get_points(&self, _index: &str, _ids: Vec<String>) -> Result<Vec<VectorChunk>> {
-        // TODO: return empty vector for now
-        Ok(vec![])
+    async fn get_points(&self, index: &str, content_ids: Vec<String>) -> Result<Vec<VectorChunk>> {
+        if content_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let response = self
+            .create_client()?
+            .mget(MgetParts::Index(index))
+            .body(json!({ "ids": content_ids }))
+            .send()
+            .await
+            .map_err(|e| anyhow!("unable to get opensearch embeddings: {}", e))?
+            .error_for_status_code()
+            .map_err(|e| anyhow!("unable to get opensearch embeddings: '{}'", e))?;
+        let response_body = response
+            .json::<Value>()
+            .await
+            .map_err(|e| anyhow!("unable to parse opensearch get response: {}", e))?;
+        parse_documents(&response_body)
     }
 
-    // TODO: implementation of update_metadata
     async fn update_metadata(
         &self,
-        _index: &str,
-        _content_id: String,
-        _metadata: serde_json::Value,
+        index: &str,
+        content_id: String,
+        metadata: serde_json::Value,
     ) -> Result<()> {
-        Ok(())
+        let response = self
+            .create_client()?
+            .update(UpdateParts::IndexId(index, &content_id))
+            .body(json!({ "doc": { "metadata": metadata } }))
+            .send()
+            .await
+            .map_err(|e| anyhow!("unable to update opensearch metadata: {}", e))?;
+        match response.error_for_status_code() {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                //  The content has no embedding in this index
+                if let Some(status) = e.status_code() {
+                    if status.as_u16() == 404 {
+                        return Ok(());
+                    }
+                }
+                return Err(anyhow!("unable to update opensearch metadata: '{}'", e));
+            }
+        }
     }
 
     async fn search(
@@ -159,16 +356,7 @@ impl VectorDb for OpenSearchKnn {
         let response = self
             .create_client()?
             .search(opensearch::SearchParts::Index(&[&index_name]))
-            .body(json!({
-                "query": {
-                    "knn": {
-                        "embeddings": {
-                            "vector": query_embedding,
-                            "k": k
-                        }
-                    }
-                }
-            }))
+            .body(search_body(&self.config.engine, query_embedding, k))
             .send()
             .await
             .map_err(|e| anyhow!("unable to search opensearch embeddings: {}", e))?;
@@ -237,6 +425,18 @@ impl VectorDb for OpenSearchKnn {
         }
     }
 
+    async fn refresh(&self, index: &str) -> Result<()> {
+        self.create_client()?
+            .indices()
+            .refresh(opensearch::indices::IndicesRefreshParts::Index(&[index]))
+            .send()
+            .await
+            .map_err(|e| anyhow!("unable to refresh opensearch index: {}", e))?
+            .error_for_status_code()
+            .map_err(|e| anyhow!("unable to refresh opensearch index: '{}'", e))?;
+        Ok(())
+    }
+
     async fn num_vectors(&self, index: &str) -> Result<u64> {
         let response = self
             .create_client()?
@@ -266,22 +466,114 @@ impl VectorDb for OpenSearchKnn {
 mod tests {
     use std::sync::Arc;
 
+    use indexify_internal_api::IndexQuantization;
+    use serde_json::json;
+
     use crate::{
-        server_config::OpenSearchBasicConfig,
+        server_config::{OpenSearchBasicConfig, SearchEngineKind},
         vectordbs::{IndexDistance, VectorChunk, VectorDBTS},
     };
     const TEST_INDEX_NAME: &str = "test_index_name";
 
-    use super::{CreateIndexParams, OpenSearchKnn};
+    use super::{index_body, parse_documents, search_body, CreateIndexParams, OpenSearchKnn};
 
     fn initialize_opensearch() -> OpenSearchKnn {
         OpenSearchKnn::new(OpenSearchBasicConfig {
             addr: "https://localhost:9200".into(),
             username: "admin".into(),
             password: "admin".into(),
+            engine: SearchEngineKind::OpenSearch,
         })
     }
 
+    #[test]
+    fn test_elasticsearch_requests() {
+        let params = CreateIndexParams {
+            vectordb_index_name: TEST_INDEX_NAME.into(),
+            vector_dim: 2,
+            distance: IndexDistance::Dot,
+            unique_params: None,
+            shard_count: Some(3),
+            quantization: None,
+            index_params: None,
+        };
+        let body = index_body(&SearchEngineKind::Elasticsearch, &params).unwrap();
+        assert_eq!(
+            body["mappings"]["properties"]["embeddings"],
+            json!({"type": "dense_vector", "dims": 2, "index": true, "similarity": "dot_product"})
+        );
+        assert_eq!(body["settings"]["index"]["number_of_shards"], json!(3));
+
+        let body = search_body(&SearchEngineKind::Elasticsearch, vec![1., 0.], 5);
+        assert_eq!(body["knn"]["k"], json!(5));
+        assert_eq!(body["knn"]["num_candidates"], json!(50));
+        assert!(body.get("query").is_none());
+    }
+
+    #[test]
+    fn test_quantized_index_body() {
+        let mut params = CreateIndexParams {
+            vectordb_index_name: TEST_INDEX_NAME.into(),
+            vector_dim: 2,
+            distance: IndexDistance::Cosine,
+            unique_params: None,
+            shard_count: None,
+            quantization: Some(IndexQuantization::Scalar {
+                quantile: Some(0.99),
+                always_ram: false,
+            }),
+            index_params: None,
+        };
+        let body = index_body(&SearchEngineKind::Elasticsearch, &params).unwrap();
+        assert_eq!(
+            body["mappings"]["properties"]["embeddings"]["index_options"],
+            json!({"type": "int8_hnsw", "confidence_interval": 0.99f32})
+        );
+        let body = index_body(&SearchEngineKind::OpenSearch, &params).unwrap();
+        let method = &body["mappings"]["properties"]["embeddings"]["method"];
+        assert_eq!(method["engine"], json!("faiss"));
+        assert_eq!(method["parameters"]["encoder"]["name"], json!("sq"));
+
+        //  Index params tune the graph alongside the quantization
+        params.index_params = Some(json!({"m": 32, "ef_search": 100, "number_of_replicas": 2}));
+        let body = index_body(&SearchEngineKind::Elasticsearch, &params).unwrap();
+        assert_eq!(
+            body["mappings"]["properties"]["embeddings"]["index_options"],
+            json!({"type": "int8_hnsw", "confidence_interval": 0.99f32, "m": 32})
+        );
+        assert_eq!(body["settings"]["index"]["number_of_replicas"], json!(2));
+        let body = index_body(&SearchEngineKind::OpenSearch, &params).unwrap();
+        let method = &body["mappings"]["properties"]["embeddings"]["method"];
+        assert_eq!(method["parameters"]["m"], json!(32));
+        assert_eq!(method["parameters"]["encoder"]["name"], json!("sq"));
+        assert_eq!(
+            body["settings"]["index"]["knn.algo_param.ef_search"],
+            json!(100)
+        );
+
+        params.quantization = Some(IndexQuantization::Product {
+            compression: 16,
+            always_ram: false,
+        });
+        assert!(index_body(&SearchEngineKind::OpenSearch, &params).is_err());
+        assert!(index_body(&SearchEngineKind::Elasticsearch, &params).is_err());
+    }
+
+    #[test]
+    fn test_parse_documents() {
+        let response = json!({
+            "docs": [
+                {"_id": "1", "found": true, "_source": {"embeddings": [0.5, 1.0], "metadata": {"topic": "a"}}},
+                {"_id": "2", "found": false}
+            ]
+        });
+        let chunks = parse_documents(&response).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].content_id, "1");
+        assert_eq!(chunks[0].embedding, vec![0.5, 1.0]);
+        assert_eq!(chunks[0].metadata, json!({"topic": "a"}));
+    }
+
     #[tokio::test]
     #[tracing_test::traced_test]
     #[ignore]
@@ -294,6 +586,9 @@ mod tests {
                 vector_dim: 2,
                 distance: IndexDistance::Cosine,
                 unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -29,6 +29,9 @@ impl VectorDb for PgVector {
     /// we create a new table for each index.
     #[tracing::instrument]
     async fn create_index(&self, index: CreateIndexParams) -> Result<()> {
+        if index.quantization.is_some() {
+            return Err(anyhow!("pg_vector doesn't support quantized indexes"));
+        }
         if let Err(err) = sqlx::query("CREATE EXTENSION IF NOT EXISTS vector")
             .execute(&self.pool)
             .await
@@ -44,14 +47,33 @@ impl VectorDb for PgVector {
             crate::vectordbs::IndexDistance::Dot => "vector_ip_ops",
         };
 
-        let query = format!("CREATE TABLE IF NOT EXISTS \"{index_name}\"(content_id VARCHAR(1024) PRIMARY KEY, embedding vector({vector_dim}), metadata JSONB);", index_name = index_name, vector_dim = vector_dim);
+        //  Sharded indexes are hash partitioned on the content id
+        let partition_clause = match index.shard_count {
+            Some(shard_count) if shard_count > 1 => " PARTITION BY HASH (content_id)",
+            _ => "",
+        };
+        let query = format!("CREATE TABLE IF NOT EXISTS \"{index_name}\"(content_id VARCHAR(1024) PRIMARY KEY, embedding vector({vector_dim}), metadata JSONB){partition_clause};", index_name = index_name, vector_dim = vector_dim);
         if let Err(err) = sqlx::query(&query).execute(&self.pool).await {
             tracing::error!("Failed to create table: {}, query: {}", err, query);
             return Err(anyhow!("Failed to create table {}", err));
         }
-        let query = format!("CREATE INDEX IF NOT EXISTS \"{index_name}_hnsw\" ON \"{index_name}\" USING hnsw(embedding {distance_extension}) WITH (m = {}, ef_construction = {});",
-            self.config.m, self.config.efconstruction
-        );
+        if !partition_clause.is_empty() {
+            let shard_count = index.shard_count.unwrap_or_default();
+            for remainder in 0..shard_count {
+                let query = format!("CREATE TABLE IF NOT EXISTS \"{index_name}_p{remainder}\" PARTITION OF \"{index_name}\" FOR VALUES WITH (MODULUS {shard_count}, REMAINDER {remainder});");
+                if let Err(err) = sqlx::query(&query).execute(&self.pool).await {
+                    tracing::error!("Failed to create partition: {}, query: {}", err, query);
+                    return Err(anyhow!("Failed to create partition {}", err));
+                }
+            }
+        }
+        //  The index params of the policy take precedence over the configured
+        // graph parameters
+        let m = index.index_param::<i32>("m")?.unwrap_or(self.config.m);
+        let ef_construction = index
+            .index_param::<i32>("ef_construction")?
+            .unwrap_or(self.config.efconstruction);
+        let query = format!("CREATE INDEX IF NOT EXISTS \"{index_name}_hnsw\" ON \"{index_name}\" USING hnsw(embedding {distance_extension}) WITH (m = {m}, ef_construction = {ef_construction});");
         if let Err(err) = sqlx::query(&query).execute(&self.pool).await {
             tracing::error!("Failed to create index: {}, query: {}", err, query);
             return Err(anyhow!("Failed to create index {}", err));
@@ -178,6 +200,24 @@ impl VectorDb for PgVector {
         Ok(count as u64)
     }
 
+    //  Reads the table, the partitions of a sharded index and their hnsw
+    // indexes into the buffer cache
+    #[tracing::instrument]
+    async fn warm_up(&self, index: &str) -> Result<()> {
+        sqlx::query("CREATE EXTENSION IF NOT EXISTS pg_prewarm")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to create pg_prewarm extension {}", e))?;
+        let index = PostgresIndexName::new(index);
+        let query = "SELECT pg_prewarm(c.oid) FROM pg_class c WHERE c.relkind IN ('r', 'i') AND (c.oid = $1::regclass OR c.oid IN (SELECT inhrelid FROM pg_inherits WHERE inhparent = $1::regclass) OR c.oid IN (SELECT indexrelid FROM pg_index WHERE indrelid = $1::regclass OR indrelid IN (SELECT inhrelid FROM pg_inherits WHERE inhparent = $1::regclass)));";
+        sqlx::query(query)
+            .bind(format!("\"{index}\""))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow!("Failed to prewarm index {}: {}", index, e))?;
+        Ok(())
+    }
+
     fn name(&self) -> String {
         "pg_vector".into()
     }
@@ -221,6 +261,9 @@ mod tests {
                 vector_dim: 2,
                 distance: IndexDistance::Cosine,
                 unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -268,6 +311,9 @@ mod tests {
                 vector_dim: 2,
                 distance: IndexDistance::Cosine,
                 unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -347,6 +393,9 @@ mod tests {
                 vector_dim: 2,
                 distance: IndexDistance::Cosine,
                 unique_params: Some(hash_on.clone()),
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -394,6 +443,9 @@ mod tests {
                 vector_dim: 2,
                 distance: IndexDistance::Cosine,
                 unique_params: Some(hash_on.clone()),
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -1,27 +1,48 @@
+use std::collections::HashMap;
+
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use indexify_internal_api::{IndexQuantization, SparseVector};
 use qdrant_client::{
     client::{QdrantClient, QdrantClientConfig},
     qdrant::{
         point_id::PointIdOptions::Num,
         points_selector::PointsSelectorOneOf,
+        quantization_config::Quantization,
+        shard_key,
         vectors::VectorsOptions,
         vectors_config::Config,
         with_payload_selector::SelectorOptions,
+        CompressionRatio,
+        CountPoints,
         CreateCollection,
         Distance,
+        HnswConfigDiff,
+        NamedVectors,
         PointId,
         PointStruct,
         PointsIdsList,
         PointsSelector,
+        ProductQuantization,
+        QuantizationConfig,
+        QuantizationType,
+        ScalarQuantization,
         SearchPoints,
+        ShardKey,
+        ShardKeySelector,
+        ShardingMethod,
+        SparseIndices,
+        SparseVectorConfig,
+        SparseVectorParams,
+        Vector,
         VectorParams,
+        Vectors,
         VectorsConfig,
         WithPayloadSelector,
     },
 };
 
-use super::{CreateIndexParams, VectorDb};
+use super::{namespace_partition, CreateIndexParams, IndexStats, SparseVectorChunk, VectorDb};
 use crate::{
     server_config::QdrantConfig,
     vectordbs::{IndexDistance, SearchResult, VectorChunk},
@@ -31,11 +52,40 @@ fn hex_to_u64(hex: &str) -> Result<u64, std::num::ParseIntError> {
     u64::from_str_radix(hex, 16)
 }
 
+//  Sparse embeddings are stored as a named sparse vector, collections
+// can't have an unnamed one
+const SPARSE_VECTOR_NAME: &str = "sparse";
+
 #[allow(dead_code)]
 fn u64_to_hex(number: u64) -> String {
     format!("{:x}", number)
 }
 
+//  Collection and shard key holding the embeddings of an index table
+struct Location {
+    collection: String,
+    shard_key: Option<String>,
+}
+
+impl Location {
+    fn shard_keys(&self) -> Option<Vec<shard_key::Key>> {
+        self.shard_key
+            .as_ref()
+            .map(|key| vec![shard_key::Key::Keyword(key.clone())])
+    }
+
+    fn shard_key_selector(&self) -> Option<ShardKeySelector> {
+        self.shard_key.as_ref().map(|key| ShardKeySelector {
+            shard_keys: vec![ShardKey {
+                key: Some(shard_key::Key::Keyword(key.clone())),
+            }],
+        })
+    }
+}
+
+/// Vector index backed by Qdrant, every index table is a collection. With
+/// `partition_by_namespace` the tables of an index in every namespace share
+/// a collection with custom sharding, with a shard key per namespace.
 #[derive(Debug)]
 pub struct QdrantDb {
     qdrant_config: QdrantConfig,
@@ -55,6 +105,66 @@ impl QdrantDb {
         Ok(client)
     }
 
+    fn location(&self, index: &str) -> Result<Location> {
+        if !self.qdrant_config.partition_by_namespace {
+            return Ok(Location {
+                collection: index.to_string(),
+                shard_key: None,
+            });
+        }
+        let (namespace, table) = namespace_partition(index)?;
+        Ok(Location {
+            collection: table.to_string(),
+            shard_key: Some(namespace.to_string()),
+        })
+    }
+
+    //  Collection info counts the points of every shard key
+    async fn count_shard_key(&self, location: &Location) -> Result<u64> {
+        let result = self
+            .create_client()?
+            .count(&CountPoints {
+                collection_name: location.collection.clone(),
+                exact: Some(true),
+                shard_key_selector: location.shard_key_selector(),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| anyhow!(e.to_string()))?;
+        Ok(result.result.map(|result| result.count).unwrap_or_default())
+    }
+
+    //  With custom sharding the shard count is the number of shards of every
+    // shard key
+    async fn create_shard_key(
+        client: &QdrantClient,
+        location: &Location,
+        shard_count: Option<u32>,
+    ) -> Result<()> {
+        let Some(key) = &location.shard_key else {
+            return Ok(());
+        };
+        let result = client
+            .create_shard_key(
+                &location.collection,
+                &shard_key::Key::Keyword(key.clone()),
+                shard_count,
+                None,
+                &[],
+            )
+            .await;
+        if let Err(err) = result {
+            if !err.to_string().contains("already exists") {
+                return Err(anyhow!(
+                    "unable to create shard key {} of index: {}",
+                    key,
+                    err.to_string()
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn convert_to_qdrant_distance(distance: IndexDistance) -> Distance {
         match distance {
             IndexDistance::Cosine => Distance::Cosine,
@@ -62,6 +172,48 @@ impl QdrantDb {
             IndexDistance::Euclidean => Distance::Euclid,
         }
     }
+
+    //  HNSW tuning of the collection from the index params, the server
+    // defaults are used for the parameters which aren't set
+    fn hnsw_config(index: &CreateIndexParams) -> Result<Option<HnswConfigDiff>> {
+        let config = HnswConfigDiff {
+            m: index.index_param("m")?,
+            ef_construct: index.index_param("ef_construct")?,
+            full_scan_threshold: index.index_param("full_scan_threshold")?,
+            ..Default::default()
+        };
+        Ok((config != HnswConfigDiff::default()).then_some(config))
+    }
+
+    fn convert_to_qdrant_quantization(quantization: IndexQuantization) -> QuantizationConfig {
+        let quantization = match quantization {
+            IndexQuantization::Scalar {
+                quantile,
+                always_ram,
+            } => Quantization::Scalar(ScalarQuantization {
+                r#type: QuantizationType::Int8.into(),
+                quantile,
+                always_ram: Some(always_ram),
+            }),
+            IndexQuantization::Product {
+                compression,
+                always_ram,
+            } => Quantization::Product(ProductQuantization {
+                compression: match compression {
+                    4 => CompressionRatio::X4,
+                    8 => CompressionRatio::X8,
+                    16 => CompressionRatio::X16,
+                    32 => CompressionRatio::X32,
+                    _ => CompressionRatio::X64,
+                }
+                .into(),
+                always_ram: Some(always_ram),
+            }),
+        };
+        QuantizationConfig {
+            quantization: Some(quantization),
+        }
+    }
 }
 
 fn content_id_from_point_id(point_id: Option<PointId>) -> Result<String> {
@@ -83,30 +235,143 @@ impl VectorDb for QdrantDb {
 
     #[tracing::instrument]
     async fn create_index(&self, index: CreateIndexParams) -> Result<()> {
-        let result = self
-            .create_client()?
+        let location = self.location(&index.vectordb_index_name)?;
+        let client = self.create_client()?;
+        let sharding_method = location
+            .shard_key
+            .as_ref()
+            .map(|_| ShardingMethod::Custom.into());
+        let result = client
             .create_collection(&CreateCollection {
-                collection_name: index.vectordb_index_name,
+                collection_name: location.collection.clone(),
                 vectors_config: Some(VectorsConfig {
                     config: Some(Config::Params(VectorParams {
-                        on_disk: None,
+                        on_disk: index.index_param("on_disk")?,
                         size: index.vector_dim,
-                        distance: Self::convert_to_qdrant_distance(index.distance).into(),
-                        hnsw_config: None,
+                        distance: Self::convert_to_qdrant_distance(index.distance.clone()).into(),
+                        hnsw_config: Self::hnsw_config(&index)?,
                         quantization_config: None,
                     })),
                 }),
+                shard_number: index.shard_count,
+                sharding_method,
+                replication_factor: index.index_param("replication_factor")?,
+                write_consistency_factor: index.index_param("write_consistency_factor")?,
+                quantization_config: index.quantization.map(Self::convert_to_qdrant_quantization),
                 ..Default::default()
             })
             .await;
-        if let Err(err) = &result {
-            if err.to_string().contains("already exists") {
-                return Ok(());
+        if let Err(err) = result {
+            if !err.to_string().contains("already exists") {
+                return Err(anyhow!("unable to create index: {}", err.to_string()));
+            }
+        }
+        Self::create_shard_key(&client, &location, index.shard_count).await
+    }
+
+    #[tracing::instrument]
+    async fn create_sparse_index(&self, index: &str, shard_count: Option<u32>) -> Result<()> {
+        let location = self.location(index)?;
+        let client = self.create_client()?;
+        let sharding_method = location
+            .shard_key
+            .as_ref()
+            .map(|_| ShardingMethod::Custom.into());
+        let result = client
+            .create_collection(&CreateCollection {
+                collection_name: location.collection.clone(),
+                sparse_vectors_config: Some(SparseVectorConfig {
+                    map: HashMap::from([(
+                        SPARSE_VECTOR_NAME.to_string(),
+                        SparseVectorParams::default(),
+                    )]),
+                }),
+                shard_number: shard_count,
+                sharding_method,
+                ..Default::default()
+            })
+            .await;
+        if let Err(err) = result {
+            if !err.to_string().contains("already exists") {
+                return Err(anyhow!(
+                    "unable to create sparse index: {}",
+                    err.to_string()
+                ));
             }
         }
+        Self::create_shard_key(&client, &location, shard_count).await
+    }
+
+    #[tracing::instrument]
+    async fn add_sparse_embedding(
+        &self,
+        index: &str,
+        chunks: Vec<SparseVectorChunk>,
+    ) -> Result<()> {
+        let mut points = Vec::<PointStruct>::new();
+        for chunk in chunks {
+            let metadata = serde_json::from_value(chunk.metadata)
+                .map_err(|e| anyhow!("unable to read metadata: {}", e.to_string()))?;
+            let vectors = Vectors {
+                vectors_options: Some(VectorsOptions::Vectors(NamedVectors {
+                    vectors: HashMap::from([(
+                        SPARSE_VECTOR_NAME.to_string(),
+                        Vector {
+                            data: chunk.vector.values,
+                            indices: Some(SparseIndices {
+                                data: chunk.vector.indices,
+                            }),
+                        },
+                    )]),
+                })),
+            };
+            points.push(PointStruct::new(
+                hex_to_u64(&chunk.content_id)?,
+                vectors,
+                metadata,
+            ));
+        }
+        let location = self.location(index)?;
+        self.create_client()?
+            .upsert_points(&location.collection, location.shard_keys(), points, None)
+            .await
+            .map_err(|e| anyhow!("unable to add sparse embedding: {}", e.to_string()))?;
+        Ok(())
+    }
+
+    #[tracing::instrument]
+    async fn sparse_search(
+        &self,
+        index: String,
+        query: SparseVector,
+        k: u64,
+    ) -> Result<Vec<SearchResult>> {
+        let location = self.location(&index)?;
+        let result = self
+            .create_client()?
+            .search_points(&SearchPoints {
+                collection_name: location.collection.clone(),
+                vector: query.values,
+                sparse_indices: Some(SparseIndices {
+                    data: query.indices,
+                }),
+                vector_name: Some(SPARSE_VECTOR_NAME.to_string()),
+                limit: k,
+                shard_key_selector: location.shard_key_selector(),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| anyhow!("unable to search sparse index: {}", e.to_string()))?;
         result
-            .map(|_| ())
-            .map_err(|e| anyhow!("unable to create index: {}", e.to_string()))
+            .result
+            .into_iter()
+            .map(|point| {
+                Ok(SearchResult {
+                    confidence_score: point.score,
+                    content_id: content_id_from_point_id(point.id)?,
+                })
+            })
+            .collect()
     }
 
     #[tracing::instrument]
@@ -122,9 +387,10 @@ impl VectorDb for QdrantDb {
                 metadata,
             ));
         }
+        let location = self.location(index)?;
         let _result = self
             .create_client()?
-            .upsert_points(&index, None, points, None)
+            .upsert_points(&location.collection, location.shard_keys(), points, None)
             .await
             .map_err(|e| anyhow!("unable to add embedding: {}", e.to_string()))?;
         Ok(())
@@ -139,9 +405,17 @@ impl VectorDb for QdrantDb {
             });
         }
         let client = self.create_client()?;
+        let location = self.location(index)?;
 
         let result = client
-            .get_points(&index, None, &points, Some(true), Some(true), None)
+            .get_points(
+                &location.collection,
+                location.shard_keys(),
+                &points,
+                Some(true),
+                Some(true),
+                None,
+            )
             .await
             .map_err(|e| anyhow!("unable to read index: {}", e.to_string()))?;
         let mut documents: Vec<VectorChunk> = Vec::new();
@@ -172,9 +446,17 @@ impl VectorDb for QdrantDb {
             .map_err(|e| anyhow!("unable to read metadata: {}", e.to_string()))?;
         let point_id = hex_to_u64(&content_id).unwrap();
         let points: Vec<PointId> = vec![point_id.into()];
+        let location = self.location(index)?;
         let _result = self
             .create_client()?
-            .overwrite_payload(&index, None, &points.into(), metadata, None, None)
+            .overwrite_payload(
+                &location.collection,
+                location.shard_keys(),
+                &points.into(),
+                metadata,
+                None,
+                None,
+            )
             .await
             .map_err(|e| anyhow!("unable to update metadata: {}", e.to_string()))?;
         Ok(())
@@ -187,8 +469,14 @@ impl VectorDb for QdrantDb {
                 ids: vec![hex_to_u64(content_id).unwrap().into()],
             })),
         };
+        let location = self.location(index)?;
         self.create_client()?
-            .delete_points_blocking(index, None, &points_selector, None)
+            .delete_points_blocking(
+                &location.collection,
+                location.shard_keys(),
+                &points_selector,
+                None,
+            )
             .await
             .map_err(|e| {
                 anyhow!(
@@ -202,6 +490,29 @@ impl VectorDb for QdrantDb {
         Ok(())
     }
 
+    //  Upserts return before they're applied. Updates of a collection are
+    // applied in order, so a blocking delete of no points returns once the
+    // upserts before it are searchable.
+    #[tracing::instrument]
+    async fn refresh(&self, index: &str) -> Result<()> {
+        let points_selector = PointsSelector {
+            points_selector_one_of: Some(PointsSelectorOneOf::Points(PointsIdsList {
+                ids: vec![],
+            })),
+        };
+        let location = self.location(index)?;
+        self.create_client()?
+            .delete_points_blocking(
+                &location.collection,
+                location.shard_keys(),
+                &points_selector,
+                None,
+            )
+            .await
+            .map_err(|e| anyhow!("unable to refresh index {}: {}", index, e.to_string()))?;
+        Ok(())
+    }
+
     #[tracing::instrument]
     async fn search(
         &self,
@@ -209,12 +520,14 @@ impl VectorDb for QdrantDb {
         query_embedding: Vec<f32>,
         k: u64,
     ) -> Result<Vec<SearchResult>> {
+        let location = self.location(&index)?;
         let result = self
             .create_client()?
             .search_points(&SearchPoints {
-                collection_name: index,
+                collection_name: location.collection.clone(),
                 vector: query_embedding,
                 limit: k,
+                shard_key_selector: location.shard_key_selector(),
                 with_payload: Some(WithPayloadSelector {
                     selector_options: Some(SelectorOptions::Enable(true)),
                 }),
@@ -235,7 +548,21 @@ impl VectorDb for QdrantDb {
 
     #[tracing::instrument]
     async fn drop_index(&self, index: &str) -> Result<()> {
-        let result = self.create_client()?.delete_collection(index).await;
+        let location = self.location(index)?;
+        //  Only the shard key of the namespace is deleted, the collection is
+        // shared with the other namespaces
+        let result = match &location.shard_key {
+            Some(key) => self
+                .create_client()?
+                .delete_shard_key(&location.collection, &shard_key::Key::Keyword(key.clone()))
+                .await
+                .map(|_| ()),
+            None => self
+                .create_client()?
+                .delete_collection(&location.collection)
+                .await
+                .map(|_| ()),
+        };
         if let Err(err) = result {
             if err.to_string().contains("doesn't exist") {
                 return Ok(());
@@ -251,6 +578,10 @@ impl VectorDb for QdrantDb {
 
     #[tracing::instrument]
     async fn num_vectors(&self, index: &str) -> Result<u64> {
+        let location = self.location(index)?;
+        if location.shard_key.is_some() {
+            return self.count_shard_key(&location).await;
+        }
         let result = self
             .create_client()?
             .collection_info(index)
@@ -259,12 +590,40 @@ impl VectorDb for QdrantDb {
         let collection_info = result.result.ok_or(anyhow!("index not found: {}", index))?;
         Ok(collection_info.points_count.unwrap_or_default())
     }
+
+    async fn index_stats(&self, index: &str) -> Result<IndexStats> {
+        let location = self.location(index)?;
+        if location.shard_key.is_some() {
+            return Ok(IndexStats {
+                num_vectors: self.count_shard_key(&location).await?,
+                ..Default::default()
+            });
+        }
+        let result = self
+            .create_client()?
+            .collection_info(index)
+            .await
+            .map_err(|e| anyhow!(e.to_string()))?;
+        let collection_info = result.result.ok_or(anyhow!("index not found: {}", index))?;
+        let shard_count = collection_info
+            .config
+            .as_ref()
+            .and_then(|config| config.params.as_ref())
+            .map(|params| params.shard_number);
+        Ok(IndexStats {
+            num_vectors: collection_info.points_count.unwrap_or_default(),
+            shard_count,
+            segment_count: Some(collection_info.segments_count),
+            memory_bytes: None,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
+    use qdrant_client::qdrant::HnswConfigDiff;
     use serde_json::json;
 
     use super::{CreateIndexParams, QdrantDb};
@@ -274,11 +633,61 @@ mod tests {
         vectordbs::{IndexDistance, VectorChunk, VectorDBTS},
     };
 
+    #[test]
+    fn test_hnsw_config() {
+        let mut index = CreateIndexParams {
+            vectordb_index_name: "default.hello".into(),
+            vector_dim: 2,
+            distance: IndexDistance::Cosine,
+            unique_params: None,
+            shard_count: None,
+            quantization: None,
+            index_params: None,
+        };
+        assert_eq!(QdrantDb::hnsw_config(&index).unwrap(), None);
+
+        //  Parameters of other vector stores are ignored
+        index.index_params = Some(json!({"nlist": 1024, "replication_factor": 2}));
+        assert_eq!(QdrantDb::hnsw_config(&index).unwrap(), None);
+
+        index.index_params = Some(json!({"m": 32, "ef_construct": 200}));
+        assert_eq!(
+            QdrantDb::hnsw_config(&index).unwrap(),
+            Some(HnswConfigDiff {
+                m: Some(32),
+                ef_construct: Some(200),
+                ..Default::default()
+            })
+        );
+
+        index.index_params = Some(json!({"m": "32"}));
+        assert!(QdrantDb::hnsw_config(&index).is_err());
+    }
+
+    #[test]
+    fn test_namespace_partitions() {
+        let qdrant = QdrantDb::new(QdrantConfig {
+            partition_by_namespace: true,
+            ..Default::default()
+        });
+        let location = qdrant.location("default.minilm.embedding").unwrap();
+        assert_eq!(location.collection, "minilm.embedding");
+        assert_eq!(location.shard_key.as_deref(), Some("default"));
+        assert_eq!(location.shard_keys().unwrap().len(), 1);
+
+        let location = QdrantDb::new(QdrantConfig::default())
+            .location("default.minilm.embedding")
+            .unwrap();
+        assert_eq!(location.collection, "default.minilm.embedding");
+        assert!(location.shard_key_selector().is_none());
+    }
+
     #[tokio::test]
     #[tracing_test::traced_test]
     async fn test_search_basic() {
         let qdrant: VectorDBTS = Arc::new(QdrantDb::new(QdrantConfig {
             addr: "http://localhost:6334".into(),
+            partition_by_namespace: false,
         }));
         qdrant.drop_index("hello-index").await.unwrap();
         qdrant
@@ -287,6 +696,9 @@ mod tests {
                 vector_dim: 2,
                 distance: IndexDistance::Cosine,
                 unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -316,6 +728,7 @@ mod tests {
     async fn test_store_metadata() {
         let qdrant: VectorDBTS = Arc::new(QdrantDb::new(QdrantConfig {
             addr: "http://localhost:6334".into(),
+            partition_by_namespace: false,
         }));
         qdrant.drop_index("metadata-index").await.unwrap();
         qdrant
@@ -324,6 +737,9 @@ mod tests {
                 vector_dim: 2,
                 distance: IndexDistance::Cosine,
                 unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -388,6 +804,7 @@ mod tests {
         let hash_on = vec!["user_id".to_string(), "url".to_string()];
         let qdrant: VectorDBTS = Arc::new(QdrantDb::new(QdrantConfig {
             addr: "http://localhost:6334".into(),
+            partition_by_namespace: false,
         }));
         qdrant.drop_index(index_name).await.unwrap();
         qdrant
@@ -396,6 +813,9 @@ mod tests {
                 vector_dim: 2,
                 distance: IndexDistance::Cosine,
                 unique_params: Some(hash_on.clone()),
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -423,6 +843,7 @@ mod tests {
         let hash_on = vec!["user_id".to_string(), "url".to_string()];
         let qdrant: VectorDBTS = Arc::new(QdrantDb::new(QdrantConfig {
             addr: "http://localhost:6334".into(),
+            partition_by_namespace: false,
         }));
         qdrant.drop_index(index_name).await.unwrap();
         qdrant
@@ -431,6 +852,9 @@ mod tests {
                 vector_dim: 2,
                 distance: IndexDistance::Cosine,
                 unique_params: Some(hash_on.clone()),
+                shard_count: None,
+                quantization: None,
+                index_params: None,
             })
             .await
             .unwrap();
@@ -0,0 +1,443 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use indexify_internal_api::IndexQuantization;
+use itertools::Itertools;
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use uuid::{Builder, Uuid};
+
+use super::{product_quantization_subvectors, CreateIndexParams, VectorDb};
+use crate::{
+    server_config::WeaviateConfig,
+    vectordbs::{IndexDistance, SearchResult, VectorChunk},
+};
+
+//  Number of objects sent to Weaviate in a single batch request
+const BATCH_SIZE: usize = 100;
+
+/// Vector index backed by Weaviate, accessed through its REST and GraphQL
+/// APIs. Every index is a class without a vectorizer whose objects hold the
+/// content id and the metadata of the content serialized as JSON.
+#[derive(Debug)]
+pub struct WeaviateDb {
+    config: WeaviateConfig,
+    client: reqwest::Client,
+}
+
+//  Class names have to start with an upper case letter and can only contain
+// letters, digits and underscores
+fn class_name(index: &str) -> String {
+    let name: String = index
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    match name.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => {
+            c.to_ascii_uppercase().to_string() + &name[c.len_utf8()..]
+        }
+        _ => format!("Index_{}", name),
+    }
+}
+
+//  Object ids have to be UUIDs, so they're derived from the content id
+fn object_id(content_id: &str) -> Uuid {
+    let digest = Sha256::digest(content_id.as_bytes());
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    Builder::from_custom_bytes(bytes).into_uuid()
+}
+
+fn parse_metadata(properties: &Value) -> Value {
+    properties["metadata"]
+        .as_str()
+        .and_then(|metadata| serde_json::from_str(metadata).ok())
+        .unwrap_or(Value::Null)
+}
+
+impl WeaviateDb {
+    pub fn new(config: WeaviateConfig) -> WeaviateDb {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<(StatusCode, Value)> {
+        let url = format!("{}/v1/{}", self.config.addr.trim_end_matches('/'), path);
+        let mut request = self.client.request(method, &url);
+        if let Some(api_key) = &self.config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("unable to send weaviate request {}: {}", path, e))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| anyhow!("unable to read weaviate response {}: {}", path, e))?;
+        let body = serde_json::from_str(&text).unwrap_or(Value::Null);
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            return Err(anyhow!(
+                "weaviate request {} failed with status {}: {}",
+                path,
+                status,
+                text
+            ));
+        }
+        Ok((status, body))
+    }
+
+    async fn graphql(&self, query: String) -> Result<Value> {
+        let (_, response) = self
+            .request(Method::POST, "graphql", Some(json!({ "query": query })))
+            .await?;
+        if let Some(errors) = response["errors"].as_array() {
+            if !errors.is_empty() {
+                return Err(anyhow!("weaviate graphql query failed: {:?}", errors));
+            }
+        }
+        Ok(response["data"].clone())
+    }
+
+    //  Hybrid searches return a fused score, vector searches a distance
+    async fn search_objects(
+        &self,
+        index: &str,
+        arguments: String,
+        hybrid: bool,
+    ) -> Result<Vec<SearchResult>> {
+        let class = class_name(index);
+        let additional = if hybrid { "score" } else { "distance" };
+        let data = self
+            .graphql(format!(
+                "{{ Get {{ {class}({arguments}) {{ content_id _additional {{ {additional} }} }} }} }}"
+            ))
+            .await?;
+        let hits = data["Get"][&class]
+            .as_array()
+            .ok_or(anyhow!("unable to parse weaviate search response"))?;
+        hits.iter()
+            .map(|hit| -> Result<SearchResult> {
+                let content_id = hit["content_id"]
+                    .as_str()
+                    .ok_or(anyhow!("weaviate search result without content id"))?;
+                let additional = &hit["_additional"];
+                let confidence_score = if hybrid {
+                    additional["score"].as_str().unwrap_or("0").parse::<f32>()?
+                } else {
+                    1.0 - additional["distance"].as_f64().unwrap_or(1.0) as f32
+                };
+                Ok(SearchResult {
+                    content_id: content_id.to_string(),
+                    confidence_score,
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl VectorDb for WeaviateDb {
+    fn name(&self) -> String {
+        "weaviate".into()
+    }
+
+    async fn create_index(&self, index: CreateIndexParams) -> Result<()> {
+        let class = class_name(&index.vectordb_index_name);
+        let (status, _) = self
+            .request(Method::GET, &format!("schema/{}", class), None)
+            .await?;
+        if status.is_success() {
+            return Ok(());
+        }
+        let distance = match index.distance {
+            IndexDistance::Cosine => "cosine",
+            IndexDistance::Dot => "dot",
+            IndexDistance::Euclidean => "l2-squared",
+        };
+        let mut body = json!({
+            "class": class,
+            "vectorizer": "none",
+            "vectorIndexConfig": { "distance": distance },
+            "properties": [
+                { "name": "content_id", "dataType": ["text"], "tokenization": "field" },
+                { "name": "metadata", "dataType": ["text"] }
+            ],
+        });
+        match index.quantization {
+            Some(IndexQuantization::Scalar { .. }) => {
+                body["vectorIndexConfig"]["sq"] = json!({ "enabled": true });
+            }
+            Some(IndexQuantization::Product { compression, .. }) => {
+                body["vectorIndexConfig"]["pq"] = json!({
+                    "enabled": true,
+                    "segments": product_quantization_subvectors(index.vector_dim, compression)?,
+                });
+            }
+            None => {}
+        }
+        //  Index params are HNSW settings of the class, e.g. maxConnections or
+        // efConstruction, except for the replication factor of the class
+        if let Some(Value::Object(params)) = &index.index_params {
+            for (key, value) in params {
+                match key.as_str() {
+                    "replicationFactor" => {
+                        body["replicationConfig"] = json!({ "factor": value });
+                    }
+                    _ => body["vectorIndexConfig"][key] = value.clone(),
+                }
+            }
+        }
+        if let Some(shard_count) = index.shard_count {
+            body["shardingConfig"] = json!({ "desiredCount": shard_count });
+        }
+        self.request(Method::POST, "schema", Some(body))
+            .await
+            .map_err(|e| anyhow!("unable to create weaviate class: {}", e))?;
+        Ok(())
+    }
+
+    async fn add_embedding(&self, index: &str, chunks: Vec<VectorChunk>) -> Result<()> {
+        let class = class_name(index);
+        for batch in chunks.chunks(BATCH_SIZE) {
+            let objects = batch
+                .iter()
+                .map(|chunk| {
+                    json!({
+                        "class": class,
+                        "id": object_id(&chunk.content_id).to_string(),
+                        "vector": chunk.embedding,
+                        "properties": {
+                            "content_id": chunk.content_id,
+                            "metadata": chunk.metadata.to_string(),
+                        },
+                    })
+                })
+                .collect_vec();
+            let (_, response) = self
+                .request(
+                    Method::POST,
+                    "batch/objects",
+                    Some(json!({ "objects": objects })),
+                )
+                .await
+                .map_err(|e| anyhow!("unable to add weaviate objects: {}", e))?;
+            //  The batch succeeds even if some of its objects fail
+            let errors = response
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|object| object["result"]["errors"].as_object())
+                .collect_vec();
+            if !errors.is_empty() {
+                return Err(anyhow!("unable to add weaviate objects: {:?}", errors));
+            }
+        }
+        Ok(())
+    }
+
+    async fn remove_embedding(&self, index: &str, content_id: &str) -> Result<()> {
+        self.request(
+            Method::DELETE,
+            &format!("objects/{}/{}", class_name(index), object_id(content_id)),
+            None,
+        )
+        .await
+        .map_err(|e| anyhow!("unable to remove weaviate object: {}", e))?;
+        Ok(())
+    }
+
+    async fn get_points(&self, index: &str, content_ids: Vec<String>) -> Result<Vec<VectorChunk>> {
+        let class = class_name(index);
+        let mut chunks = Vec::new();
+        for content_id in content_ids {
+            let (status, object) = self
+                .request(
+                    Method::GET,
+                    &format!(
+                        "objects/{}/{}?include=vector",
+                        class,
+                        object_id(&content_id)
+                    ),
+                    None,
+                )
+                .await?;
+            if status == StatusCode::NOT_FOUND {
+                continue;
+            }
+            let embedding = serde_json::from_value(object["vector"].clone())
+                .map_err(|e| anyhow!("unable to parse weaviate object vector: {}", e))?;
+            chunks.push(VectorChunk::new(
+                content_id,
+                embedding,
+                parse_metadata(&object["properties"]),
+            ));
+        }
+        Ok(chunks)
+    }
+
+    async fn update_metadata(
+        &self,
+        index: &str,
+        content_id: String,
+        metadata: serde_json::Value,
+    ) -> Result<()> {
+        let class = class_name(index);
+        self.request(
+            Method::PATCH,
+            &format!("objects/{}/{}", class, object_id(&content_id)),
+            Some(json!({
+                "class": class,
+                "properties": { "metadata": metadata.to_string() },
+            })),
+        )
+        .await
+        .map_err(|e| anyhow!("unable to update weaviate object metadata: {}", e))?;
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        index: String,
+        query_embedding: Vec<f32>,
+        k: u64,
+    ) -> Result<Vec<SearchResult>> {
+        let arguments = format!(
+            "nearVector: {{ vector: {} }}, limit: {}",
+            json!(query_embedding),
+            k
+        );
+        self.search_objects(&index, arguments, false).await
+    }
+
+    /// Passes the query on to Weaviate's hybrid search, which fuses a vector
+    /// search with a keyword search over the metadata of the objects.
+    /// Without a configured `hybrid_alpha` only the vector search is run.
+    async fn hybrid_search(
+        &self,
+        index: String,
+        query: &str,
+        query_embedding: Vec<f32>,
+        k: u64,
+    ) -> Result<Vec<SearchResult>> {
+        let Some(alpha) = self.config.hybrid_alpha else {
+            return self.search(index, query_embedding, k).await;
+        };
+        let arguments = format!(
+            "hybrid: {{ query: {}, vector: {}, alpha: {} }}, limit: {}",
+            json!(query),
+            json!(query_embedding),
+            alpha,
+            k
+        );
+        self.search_objects(&index, arguments, true).await
+    }
+
+    async fn drop_index(&self, index: &str) -> Result<()> {
+        self.request(
+            Method::DELETE,
+            &format!("schema/{}", class_name(index)),
+            None,
+        )
+        .await
+        .map_err(|e| anyhow!("unable to delete weaviate class: {}", e))?;
+        Ok(())
+    }
+
+    async fn num_vectors(&self, index: &str) -> Result<u64> {
+        let class = class_name(index);
+        let data = self
+            .graphql(format!(
+                "{{ Aggregate {{ {class} {{ meta {{ count }} }} }} }}"
+            ))
+            .await?;
+        data["Aggregate"][&class][0]["meta"]["count"]
+            .as_u64()
+            .ok_or(anyhow!("unable to parse weaviate aggregate response"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::{class_name, object_id, CreateIndexParams, WeaviateDb};
+    use crate::{
+        server_config::WeaviateConfig,
+        vectordbs::{IndexDistance, VectorChunk, VectorDBTS},
+    };
+
+    #[test]
+    fn test_class_and_object_names() {
+        assert_eq!(
+            class_name("default.minilm.embedding"),
+            "Default_minilm_embedding"
+        );
+        assert_eq!(class_name("1st.index"), "Index_1st_index");
+        assert_eq!(object_id("content_1"), object_id("content_1"));
+        assert_ne!(object_id("content_1"), object_id("content_2"));
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    #[ignore]
+    async fn test_search_basic() {
+        let weaviate: VectorDBTS = Arc::new(WeaviateDb::new(WeaviateConfig {
+            hybrid_alpha: Some(0.5),
+            ..Default::default()
+        }));
+        let index = "test_namespace.test_index";
+        weaviate.drop_index(index).await.unwrap();
+        weaviate
+            .create_index(CreateIndexParams {
+                vectordb_index_name: index.into(),
+                vector_dim: 2,
+                distance: IndexDistance::Cosine,
+                unique_params: None,
+                shard_count: None,
+                quantization: None,
+                index_params: None,
+            })
+            .await
+            .unwrap();
+        let chunks = vec![
+            VectorChunk::new("1".into(), vec![0., 2.], json!({"topic": "a"})),
+            VectorChunk::new("2".into(), vec![1., 2.], json!({"topic": "b"})),
+        ];
+        weaviate.add_embedding(index, chunks).await.unwrap();
+        assert_eq!(weaviate.num_vectors(index).await.unwrap(), 2);
+
+        let results = weaviate
+            .search(index.into(), vec![10., 8.], 1)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        let results = weaviate
+            .hybrid_search(index.into(), "topic", vec![10., 8.], 2)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        weaviate
+            .update_metadata(index, "2".into(), json!({"topic": "c"}))
+            .await
+            .unwrap();
+        let points = weaviate.get_points(index, vec!["2".into()]).await.unwrap();
+        assert_eq!(points[0].metadata, json!({"topic": "c"}));
+        weaviate.remove_embedding(index, "2").await.unwrap();
+        assert_eq!(weaviate.num_vectors(index).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,396 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{anyhow, Result};
+use indexify_internal_api as internal_api;
+use serde::{Deserialize, Serialize};
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Value};
+
+//  Number of bytes from the beginning of the content passed to the plugins
+pub const INGESTION_PLUGIN_INSPECT_BYTES: usize = 64 * 1024;
+
+pub const DEFAULT_PLUGIN_MAX_FUEL: u64 = 10_000_000;
+
+pub const DEFAULT_PLUGIN_MAX_MEMORY_BYTES: u64 = 16 * 1024 * 1024;
+
+//  Largest output a plugin can return
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// Metadata of the ingested content passed to the plugins as JSON.
+#[derive(Debug, Serialize)]
+pub struct PluginInput<'a> {
+    pub namespace: &'a str,
+    pub file_name: &'a str,
+    pub mime_type: &'a str,
+    pub labels: &'a HashMap<String, String>,
+}
+
+/// Decision returned by a plugin as JSON, e.g.
+/// `{"decision": "reject", "reason": "file names must start with a date"}`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum PluginDecision {
+    Accept,
+    Reject {
+        #[serde(default)]
+        reason: String,
+    },
+    //  Accepts the content and adds the labels to it
+    Annotate {
+        labels: HashMap<String, String>,
+    },
+}
+
+/// Search result passed to scoring plugins as JSON.
+#[derive(Debug, Serialize)]
+pub struct ScoringCandidate<'a> {
+    pub content_id: &'a str,
+    pub score: f32,
+    pub labels: &'a HashMap<String, String>,
+    pub mime_type: &'a str,
+    pub created_at: i64,
+    pub parent_id: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScoringInput<'a> {
+    pub query: &'a str,
+    pub candidates: Vec<ScoringCandidate<'a>>,
+}
+
+/// Candidate kept by a scoring plugin with its new score. Candidates missing
+/// from the output of the plugin are dropped.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ScoredCandidate {
+    pub content_id: String,
+    pub score: f32,
+}
+
+/// Limits of a single run of a plugin.
+#[derive(Debug, Clone, Copy)]
+pub struct PluginLimits {
+    //  Maximum number of instructions the run can execute
+    pub max_fuel: u64,
+    //  Maximum size of the linear memory of the module
+    pub max_memory_bytes: u64,
+}
+
+impl From<&internal_api::IngestionPlugin> for PluginLimits {
+    fn from(plugin: &internal_api::IngestionPlugin) -> Self {
+        Self {
+            max_fuel: plugin.max_fuel,
+            max_memory_bytes: plugin.max_memory_bytes,
+        }
+    }
+}
+
+impl From<&internal_api::ScoringPlugin> for PluginLimits {
+    fn from(plugin: &internal_api::ScoringPlugin) -> Self {
+        Self {
+            max_fuel: plugin.max_fuel,
+            max_memory_bytes: plugin.max_memory_bytes,
+        }
+    }
+}
+
+struct PluginState {
+    limits: StoreLimits,
+}
+
+/// Compiles and runs plugins. A plugin is a WASM module without imports
+/// which exports its `memory`, an `alloc(len: i32) -> i32` function returning
+/// a pointer to `len` bytes an input is written to, and the function called
+/// with the inputs:
+///
+/// - ingestion plugins export `validate(metadata_ptr: i32, metadata_len: i32,
+///   bytes_ptr: i32, bytes_len: i32) -> i64`, returning a [`PluginDecision`]
+/// - scoring plugins export `score(input_ptr: i32, input_len: i32) -> i64`,
+///   returning a list of [`ScoredCandidate`]
+///
+/// The functions return the pointer to their JSON output in the upper 32 bits
+/// and its length in the lower 32 bits. Compiled modules are cached by the
+/// url of the module.
+pub struct WasmPluginRuntime {
+    engine: Engine,
+    modules: Mutex<HashMap<String, Arc<Module>>>,
+}
+
+impl std::fmt::Debug for WasmPluginRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmPluginRuntime").finish()
+    }
+}
+
+impl Default for WasmPluginRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WasmPluginRuntime {
+    pub fn new() -> Self {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        Self {
+            engine: Engine::new(&config),
+            modules: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn compile(&self, wasm: &[u8]) -> Result<Module> {
+        Module::new(&self.engine, wasm).map_err(|e| anyhow!("invalid wasm module: {}", e))
+    }
+
+    pub fn cached_module(&self, wasm_url: &str) -> Option<Arc<Module>> {
+        self.modules.lock().unwrap().get(wasm_url).cloned()
+    }
+
+    pub fn cache_module(&self, wasm_url: &str, wasm: &[u8]) -> Result<Arc<Module>> {
+        let module = Arc::new(self.compile(wasm)?);
+        self.modules
+            .lock()
+            .unwrap()
+            .insert(wasm_url.to_string(), module.clone());
+        Ok(module)
+    }
+
+    /// Runs an ingestion plugin on the content.
+    pub fn validate(
+        &self,
+        module: &Module,
+        limits: PluginLimits,
+        input: &PluginInput,
+        bytes: &[u8],
+    ) -> Result<PluginDecision> {
+        let output = self.call(
+            module,
+            limits,
+            "validate",
+            &[&serde_json::to_vec(input)?, bytes],
+        )?;
+        serde_json::from_slice(&output).map_err(|e| anyhow!("invalid plugin decision: {}", e))
+    }
+
+    /// Runs a scoring plugin on the candidates of a search.
+    pub fn score(
+        &self,
+        module: &Module,
+        limits: PluginLimits,
+        input: &ScoringInput,
+    ) -> Result<Vec<ScoredCandidate>> {
+        let output = self.call(module, limits, "score", &[&serde_json::to_vec(input)?])?;
+        serde_json::from_slice(&output).map_err(|e| anyhow!("invalid plugin scores: {}", e))
+    }
+
+    //  Writes the inputs to the memory of a new instance of the module, calls
+    // the function with the pointer and length of every input and returns
+    // its output. Running out of fuel or memory fails the call.
+    fn call(
+        &self,
+        module: &Module,
+        limits: PluginLimits,
+        function: &str,
+        inputs: &[&[u8]],
+    ) -> Result<Vec<u8>> {
+        let store_limits = StoreLimitsBuilder::new()
+            .memory_size(limits.max_memory_bytes as usize)
+            .build();
+        let mut store = Store::new(
+            &self.engine,
+            PluginState {
+                limits: store_limits,
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store
+            .add_fuel(limits.max_fuel)
+            .map_err(|e| anyhow!("unable to add fuel: {}", e))?;
+        let instance = Linker::<PluginState>::new(&self.engine)
+            .instantiate(&mut store, module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(|e| anyhow!("unable to instantiate plugin: {}", e))?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| anyhow!("plugin doesn't export its memory"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|e| anyhow!("plugin doesn't export alloc: {}", e))?;
+        let func = instance
+            .get_func(&store, function)
+            .ok_or_else(|| anyhow!("plugin doesn't export {}", function))?;
+
+        let mut args = Vec::new();
+        for input in inputs {
+            let ptr = alloc
+                .call(&mut store, input.len() as i32)
+                .map_err(|e| anyhow!("plugin alloc failed: {}", e))?;
+            memory
+                .write(&mut store, ptr as u32 as usize, input)
+                .map_err(|e| anyhow!("unable to write plugin input: {}", e))?;
+            args.push(Value::I32(ptr));
+            args.push(Value::I32(input.len() as i32));
+        }
+        let mut result = [Value::I64(0)];
+        func.call(&mut store, &args, &mut result)
+            .map_err(|e| anyhow!("plugin {} failed: {}", function, e))?;
+        let Value::I64(result) = result[0] else {
+            return Err(anyhow!("plugin {} doesn't return an i64", function));
+        };
+
+        let ptr = (result >> 32) as u32 as usize;
+        let len = (result & 0xffff_ffff) as usize;
+        if len > MAX_OUTPUT_BYTES {
+            return Err(anyhow!("plugin output of {} bytes is too large", len));
+        }
+        let mut output = vec![0; len];
+        memory
+            .read(&store, ptr, &mut output)
+            .map_err(|e| anyhow!("unable to read plugin output: {}", e))?;
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: PluginLimits = PluginLimits {
+        max_fuel: DEFAULT_PLUGIN_MAX_FUEL,
+        max_memory_bytes: DEFAULT_PLUGIN_MAX_MEMORY_BYTES,
+    };
+
+    //  Plugin whose function returns the output in its data segment
+    fn plugin_wat(function: &str, params: usize, output: &str) -> String {
+        format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (global $next (mut i32) (i32.const 1024))
+                (data (i32.const 0) "{output}")
+                (func (export "alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $next))
+                    (global.set $next (i32.add (global.get $next) (local.get $len)))
+                    (local.get $ptr))
+                (func (export "{function}") (param{params}) (result i64)
+                    (i64.const {len})))"#,
+            output = output.replace('"', "\\\""),
+            params = " i32".repeat(params),
+            len = output.len(),
+        )
+    }
+
+    fn validate(wat: &str, limits: PluginLimits) -> Result<PluginDecision> {
+        let runtime = WasmPluginRuntime::new();
+        let module = runtime.compile(&wat::parse_str(wat).unwrap())?;
+        let labels = HashMap::new();
+        let input = PluginInput {
+            namespace: "test",
+            file_name: "report.pdf",
+            mime_type: "application/pdf",
+            labels: &labels,
+        };
+        runtime.validate(&module, limits, &input, b"%PDF-1.4")
+    }
+
+    #[test]
+    fn test_plugin_decisions() {
+        let decision = validate(
+            &plugin_wat(
+                "validate",
+                4,
+                r#"{"decision": "reject", "reason": "no pdfs"}"#,
+            ),
+            LIMITS,
+        )
+        .unwrap();
+        assert_eq!(
+            decision,
+            PluginDecision::Reject {
+                reason: "no pdfs".to_string()
+            }
+        );
+
+        let decision = validate(
+            &plugin_wat(
+                "validate",
+                4,
+                r#"{"decision": "annotate", "labels": {"type": "pdf"}}"#,
+            ),
+            LIMITS,
+        )
+        .unwrap();
+        assert_eq!(
+            decision,
+            PluginDecision::Annotate {
+                labels: HashMap::from([("type".to_string(), "pdf".to_string())])
+            }
+        );
+
+        assert!(validate(
+            &plugin_wat("validate", 4, r#"{"decision": "maybe"}"#),
+            LIMITS
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_plugin_scores() {
+        let runtime = WasmPluginRuntime::new();
+        let wat = plugin_wat("score", 2, r#"[{"content_id": "2", "score": 0.9}]"#);
+        let module = runtime.compile(&wat::parse_str(wat).unwrap()).unwrap();
+        let labels = HashMap::new();
+        let candidates = ["1", "2"]
+            .into_iter()
+            .map(|content_id| ScoringCandidate {
+                content_id,
+                score: 0.5,
+                labels: &labels,
+                mime_type: "text/plain",
+                created_at: 0,
+                parent_id: "",
+            })
+            .collect();
+        let input = ScoringInput {
+            query: "hello",
+            candidates,
+        };
+        let scores = runtime.score(&module, LIMITS, &input).unwrap();
+        assert_eq!(
+            scores,
+            vec![ScoredCandidate {
+                content_id: "2".to_string(),
+                score: 0.9
+            }]
+        );
+
+        //  The function has to take the pointer and length of every input
+        let wat = plugin_wat("validate", 2, r#"{"decision": "accept"}"#);
+        assert!(validate(&wat, LIMITS).is_err());
+    }
+
+    #[test]
+    fn test_plugin_limits() {
+        let looping = r#"(module
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32) (i32.const 0))
+            (func (export "validate") (param i32 i32 i32 i32) (result i64)
+                (loop $forever (br $forever))
+                (i64.const 0)))"#;
+        let limits = PluginLimits {
+            max_fuel: 100_000,
+            ..LIMITS
+        };
+        assert!(validate(looping, limits).is_err());
+
+        //  A module whose initial memory is larger than the limit can't be
+        // instantiated
+        let too_large = r#"(module
+            (memory (export "memory") 1024)
+            (func (export "alloc") (param i32) (result i32) (i32.const 0))
+            (func (export "validate") (param i32 i32 i32 i32) (result i64)
+                (i64.const 0)))"#;
+        assert!(validate(too_large, LIMITS).is_err());
+    }
+}